@@ -0,0 +1,240 @@
+//! Backtest and Replay: Signal Generation over a Book Journal
+//!
+//! A journal is a JSON-lines file of order books, one `OrderBook` per line,
+//! in the order they were observed. `backtest` feeds it through the signal
+//! pipeline (proposer, regime gate, verifier) from the configured initial
+//! portfolio and writes a report of what was proposed and proved. `replay`
+//! does the same and compares every decision against the ones recorded in an
+//! audit log, so a change in behavior shows up as a diff. Proposals are
+//! stamped with their book's time, so the same book yields the same signal
+//! hash in both.
+//!
+//! A capture (see `axiom_data::replay`) records trades and raw book
+//! snapshots instead; `Recording` reads either kind.
+
+use crate::settings::Config;
+use anyhow::Context;
+use axiom_core::{
+    read_audit_log, Amount, AuditEntry, AuditRecord, FunnelRecorder, FunnelStage, MemoryJournal, OrderBook, Portfolio,
+    Symbol, Venue,
+};
+use axiom_data::{ReplaySource, SymbolMapper};
+use axiom_engine::SignalGenerator;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Read every book in a journal
+pub fn read_journal(path: &Path) -> anyhow::Result<Vec<OrderBook>> {
+    let file = File::open(path).with_context(|| format!("Failed to open journal {}", path.display()))?;
+    let mut books = Vec::new();
+    for (idx, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let book = serde_json::from_str(&line)
+            .with_context(|| format!("{}:{}: not an order book", path.display(), idx + 1))?;
+        books.push(book);
+    }
+    Ok(books)
+}
+
+/// Recorded market data fed in instead of a venue
+#[derive(Debug, Clone)]
+pub enum Recording {
+    /// A JSON-lines file of order books
+    Journal(PathBuf),
+    /// A JSON-lines file of trades and book snapshots, in the paper venue's
+    /// name unless a line says otherwise
+    Capture(PathBuf),
+}
+
+impl Recording {
+    pub fn path(&self) -> &Path {
+        match self {
+            Recording::Journal(path) | Recording::Capture(path) => path,
+        }
+    }
+
+    /// Read the whole file, so a bad line fails before anything starts;
+    /// capture symbols are mapped with `symbols`
+    pub fn open(&self, symbols: &SymbolMapper) -> anyhow::Result<ReplaySource> {
+        match self {
+            Recording::Journal(path) => Ok(ReplaySource::from_books(read_journal(path)?)),
+            Recording::Capture(path) => ReplaySource::open(path, &Venue::Paper, symbols)
+                .with_context(|| format!("Failed to read capture {}", path.display())),
+        }
+    }
+}
+
+/// Funnel counts per symbol, collected in memory
+#[derive(Default)]
+struct FunnelCounts(Mutex<BTreeMap<Symbol, BTreeMap<FunnelStage, u64>>>);
+
+impl FunnelRecorder for FunnelCounts {
+    fn record(&self, stage: FunnelStage, symbol: &Symbol) {
+        if let Ok(mut counts) = self.0.lock() {
+            *counts.entry(symbol.clone()).or_default().entry(stage).or_insert(0) += 1;
+        }
+    }
+}
+
+/// Backtest outcome, as written to the report file
+#[derive(Debug, Clone, Serialize)]
+pub struct BacktestReport {
+    pub journal: PathBuf,
+    pub generated_at: DateTime<Utc>,
+    pub books: usize,
+    pub first_book_at: Option<DateTime<Utc>>,
+    pub last_book_at: Option<DateTime<Utc>>,
+    pub initial_equity: Decimal,
+    /// Stage counts per symbol (book_processed, proposed, verified)
+    pub funnel: BTreeMap<Symbol, BTreeMap<FunnelStage, u64>>,
+    /// Share of proposals the verifier rejected
+    pub hallucination_rate: Decimal,
+}
+
+/// Drive the signal pipeline over `journal` and write the report to `report`
+///
+/// Orders are verified but not executed (there is no fill simulation), so the
+/// portfolio stays at the configured initial equity throughout.
+pub fn backtest(config: &Config, journal: &Path, report_path: &Path) -> anyhow::Result<BacktestReport> {
+    let books = read_journal(journal)?;
+    let funnel = Arc::new(FunnelCounts::default());
+    let mut generator = generator(config).with_funnel_recorder(funnel.clone());
+    let portfolio = Portfolio::new(Amount::new(config.system.initial_equity));
+
+    for book in &books {
+        generator.generate_signal(&book.symbol, &book.venue, book, &portfolio);
+    }
+
+    let report = BacktestReport {
+        journal: journal.to_path_buf(),
+        generated_at: Utc::now(),
+        books: books.len(),
+        first_book_at: books.first().map(|book| book.timestamp),
+        last_book_at: books.last().map(|book| book.timestamp),
+        initial_equity: config.system.initial_equity,
+        funnel: funnel.0.lock().map(|counts| counts.clone()).unwrap_or_default(),
+        hallucination_rate: generator.hallucination_rate(),
+    };
+    if let Some(parent) = report_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(report_path, serde_json::to_string_pretty(&report)?)
+        .with_context(|| format!("Failed to write report {}", report_path.display()))?;
+    Ok(report)
+}
+
+/// What happened to one signal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Decision {
+    /// Proposed, but no verification outcome recorded
+    Proposed,
+    Verified { proof_signature: String },
+    Rejected { codes: Vec<String> },
+}
+
+impl std::fmt::Display for Decision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Decision::Proposed => f.write_str("proposed (no outcome)"),
+            Decision::Verified { proof_signature } => write!(f, "verified ({})", proof_signature),
+            Decision::Rejected { codes } => write!(f, "rejected [{}]", codes.join(", ")),
+        }
+    }
+}
+
+/// A signal whose replayed decision differs from the recorded one
+#[derive(Debug, Clone)]
+pub struct Divergence {
+    pub signal_hash: String,
+    pub recorded: Option<Decision>,
+    pub replayed: Option<Decision>,
+}
+
+/// Re-run signal generation over `journal` and diff against the decisions
+/// recorded in `audit` for the same books
+///
+/// Recorded signals outside the journal's symbols or time span are ignored.
+pub fn replay(config: &Config, journal: &Path, audit: &Path) -> anyhow::Result<(usize, Vec<Divergence>)> {
+    let books = read_journal(journal)?;
+    let entries = read_audit_log(audit).with_context(|| format!("Failed to read audit log {}", audit.display()))?;
+    let recorded = read_decisions(entries, |signal_timestamp, symbol| {
+        let in_span = books.first().zip(books.last())
+            .is_some_and(|(first, last)| signal_timestamp >= first.timestamp && signal_timestamp <= last.timestamp);
+        in_span && books.iter().any(|book| &book.symbol == symbol)
+    });
+
+    // The replay keeps its own audit trail in memory, read back the same way
+    let journal = Arc::new(MemoryJournal::new());
+    let mut generator = generator(config).with_journal(journal.clone());
+    let portfolio = Portfolio::new(Amount::new(config.system.initial_equity));
+    for book in &books {
+        generator.generate_signal(&book.symbol, &book.venue, book, &portfolio);
+    }
+    let replayed = read_decisions(journal.entries(), |_, _| true);
+
+    let mut divergences = Vec::new();
+    for (signal_hash, decision) in &replayed {
+        let recorded_decision = recorded.iter().find(|(hash, _)| hash == signal_hash).map(|(_, d)| d.clone());
+        if recorded_decision.as_ref() != Some(decision) {
+            divergences.push(Divergence {
+                signal_hash: signal_hash.clone(),
+                recorded: recorded_decision,
+                replayed: Some(decision.clone()),
+            });
+        }
+    }
+    for (signal_hash, decision) in &recorded {
+        if !replayed.iter().any(|(hash, _)| hash == signal_hash) {
+            divergences.push(Divergence {
+                signal_hash: signal_hash.clone(),
+                recorded: Some(decision.clone()),
+                replayed: None,
+            });
+        }
+    }
+    Ok((replayed.len(), divergences))
+}
+
+/// Final decision per signal hash, in order of first proposal, for signals
+/// accepted by `keep(signal timestamp, symbol)`
+fn read_decisions(
+    entries: Vec<AuditEntry>,
+    keep: impl Fn(DateTime<Utc>, &Symbol) -> bool,
+) -> Vec<(String, Decision)> {
+    let mut decisions: Vec<(String, Decision)> = Vec::new();
+    for entry in entries {
+        let decision = match entry.record {
+            AuditRecord::SignalProposed { signal, .. } => {
+                if keep(signal.timestamp, &signal.symbol) {
+                    decisions.push((entry.signal_hash, Decision::Proposed));
+                }
+                continue;
+            }
+            AuditRecord::OrderVerified { order } => Decision::Verified { proof_signature: order.proof_signature },
+            AuditRecord::SignalRejected { codes, .. } => Decision::Rejected { codes },
+            _ => continue,
+        };
+        if let Some((_, slot)) = decisions.iter_mut().rev().find(|(hash, _)| *hash == entry.signal_hash) {
+            *slot = decision;
+        }
+    }
+    decisions
+}
+
+/// Signal generator configured like the live pipeline, minus signing and logs
+pub(crate) fn generator(config: &Config) -> SignalGenerator {
+    SignalGenerator::new()
+        .with_proposer_config(config.proposer.clone())
+        .with_limits(Arc::new(config.limits()))
+        .with_verifier_config(config.verifier)
+        .with_regime_detector(axiom_engine::RegimeDetector::new(config.regime.thresholds.clone()))
+}
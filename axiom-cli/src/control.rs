@@ -0,0 +1,430 @@
+//! Control: Local Status and Control Endpoint
+//!
+//! A running `run` or `paper` process listens on a Unix domain socket
+//! (owner-only permissions) or a loopback TCP port for newline-delimited
+//! JSON-RPC 2.0 requests: `status`, `pause`, `resume`, `kill_switch`,
+//! `acknowledge` and `snapshot`. When `[control] token` is set every request
+//! must carry it in `params.token`. Every action other than `status`, and
+//! every refused request, is written to the audit log with its source.
+//! `axiom-cli ctl` is the client.
+
+use crate::reload::ConfigReloader;
+use crate::runtime::portfolio_snapshot;
+use crate::settings::{ControlSection, ControlTransport};
+use anyhow::Context;
+use axiom_core::{order_hash, AccountId, AuditLog, AuditRecord, CircuitBreakerState, OrderType, Quantity, Side, Symbol, Venue};
+use axiom_data::DataIngestionManager;
+use axiom_execution::OrderExecutor;
+use axiom_oracle::{constant_time_eq, IncidentTracker, SnapshotExporter, TelemetryCollector};
+use axiom_risk::{AccountBreakers, PortfolioManager, RiskGate};
+use clap::Subcommand;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+use tokio::sync::watch;
+use tracing::{error, info, warn};
+
+/// JSON-RPC error codes
+mod code {
+    pub const PARSE_ERROR: i64 = -32700;
+    pub const METHOD_NOT_FOUND: i64 = -32601;
+    pub const INVALID_PARAMS: i64 = -32602;
+    /// Missing or wrong token
+    pub const UNAUTHORIZED: i64 = -32001;
+    /// The action was understood but could not be carried out
+    pub const ACTION_FAILED: i64 = -32002;
+}
+
+/// Environment variable the client reads the token from
+pub const TOKEN_ENV: &str = "AXIOM_CONTROL_TOKEN";
+
+#[derive(Debug, Deserialize)]
+struct Request {
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Response {
+    jsonrpc: String,
+    id: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<RpcError>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl Response {
+    fn result(id: Value, result: Value) -> Self {
+        Self { jsonrpc: "2.0".to_string(), id, result: Some(result), error: None }
+    }
+
+    fn error(id: Value, code: i64, message: impl Into<String>) -> Self {
+        Self { jsonrpc: "2.0".to_string(), id, result: None, error: Some(RpcError { code, message: message.into() }) }
+    }
+}
+
+/// A resting order as shown by `status`
+#[derive(Debug, Serialize)]
+struct OpenOrder {
+    order_hash: String,
+    account: AccountId,
+    symbol: Symbol,
+    venue: Venue,
+    side: Side,
+    order_type: OrderType,
+    quantity: Quantity,
+}
+
+/// Everything the control endpoint reads or acts on
+pub struct ControlServer {
+    pub settings: ControlSection,
+    pub reloader: Arc<ConfigReloader>,
+    pub telemetry: Arc<TelemetryCollector>,
+    pub portfolio: Arc<RwLock<PortfolioManager>>,
+    pub executor: Arc<OrderExecutor>,
+    pub circuit_breaker: Arc<Mutex<AccountBreakers>>,
+    pub risk_gate: RiskGate,
+    pub incidents: Arc<IncidentTracker>,
+    pub snapshot: Arc<SnapshotExporter>,
+    pub audit_log: Arc<AuditLog>,
+    pub data_manager: Arc<DataIngestionManager>,
+}
+
+impl ControlServer {
+    /// Bind the endpoint and serve until `shutdown` turns true
+    pub async fn spawn(self, mut shutdown: watch::Receiver<bool>) -> anyhow::Result<()> {
+        let server = Arc::new(self);
+        match server.settings.transport {
+            ControlTransport::Tcp => {
+                let listener = TcpListener::bind(&server.settings.bind).await
+                    .with_context(|| format!("Failed to bind control endpoint {}", server.settings.bind))?;
+                info!("Control endpoint listening on tcp {}", server.settings.bind);
+                tokio::spawn(async move {
+                    loop {
+                        tokio::select! {
+                            accepted = listener.accept() => match accepted {
+                                Ok((stream, peer)) => {
+                                    tokio::spawn(server.clone().serve(stream, format!("tcp:{}", peer)));
+                                }
+                                Err(e) => warn!("Control accept failed: {}", e),
+                            },
+                            _ = shutdown.wait_for(|stop| *stop) => break,
+                        }
+                    }
+                });
+            }
+            #[cfg(unix)]
+            ControlTransport::Unix => {
+                let path = server.settings.socket_path.clone();
+                let listener = bind_unix(&path).await?;
+                info!("Control endpoint listening on {}", path.display());
+                tokio::spawn(async move {
+                    loop {
+                        tokio::select! {
+                            accepted = listener.accept() => match accepted {
+                                Ok((stream, _)) => {
+                                    let source = match stream.peer_cred() {
+                                        Ok(cred) => format!("unix:uid={}", cred.uid()),
+                                        Err(_) => "unix".to_string(),
+                                    };
+                                    tokio::spawn(server.clone().serve(stream, source));
+                                }
+                                Err(e) => warn!("Control accept failed: {}", e),
+                            },
+                            _ = shutdown.wait_for(|stop| *stop) => break,
+                        }
+                    }
+                    let _ = std::fs::remove_file(&path);
+                });
+            }
+            #[cfg(not(unix))]
+            ControlTransport::Unix => anyhow::bail!("the unix control transport needs a Unix platform; use tcp"),
+        }
+        Ok(())
+    }
+
+    /// Answer requests on one connection, one per line
+    async fn serve<S: AsyncRead + AsyncWrite + Unpin>(self: Arc<Self>, stream: S, source: String) {
+        let (reader, mut writer) = tokio::io::split(stream);
+        let mut lines = BufReader::new(reader).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let response = match serde_json::from_str::<Request>(&line) {
+                Ok(request) => self.handle(request, &source).await,
+                Err(e) => Response::error(Value::Null, code::PARSE_ERROR, e.to_string()),
+            };
+            let Ok(mut out) = serde_json::to_vec(&response) else { break };
+            out.push(b'\n');
+            if writer.write_all(&out).await.is_err() {
+                break;
+            }
+        }
+    }
+
+    async fn handle(&self, request: Request, source: &str) -> Response {
+        let Request { id, method, params } = request;
+        if !self.settings.token.is_empty() {
+            let presented = params.get("token").and_then(Value::as_str).unwrap_or("");
+            if !constant_time_eq(presented, &self.settings.token) {
+                self.audit(&method, source, "unauthorized");
+                return Response::error(id, code::UNAUTHORIZED, "missing or invalid token");
+            }
+        }
+
+        let outcome = match method.as_str() {
+            "status" => return Response::result(id, self.status().await),
+            "pause" => self.pause(source),
+            "resume" => self.resume(source),
+            "resume_venue" => match params.get("venue").and_then(Value::as_str) {
+                Some(venue) => self.resume_venue(venue, source),
+                None => return Response::error(id, code::INVALID_PARAMS, "resume_venue needs params.venue"),
+            },
+            "kill_switch" => self.kill_switch(source).await,
+            "acknowledge" => match params.get("incident_id").and_then(Value::as_u64) {
+                Some(incident_id) => self.acknowledge(incident_id, source),
+                None => return Response::error(id, code::INVALID_PARAMS, "acknowledge needs params.incident_id"),
+            },
+            "snapshot" => self.snapshot().await,
+            _ => return Response::error(id, code::METHOD_NOT_FOUND, format!("unknown method {}", method)),
+        };
+
+        match outcome {
+            Ok(result) => {
+                self.audit(&method, source, "ok");
+                Response::result(id, result)
+            }
+            Err(message) => {
+                self.audit(&method, source, &format!("failed: {}", message));
+                Response::error(id, code::ACTION_FAILED, message)
+            }
+        }
+    }
+
+    async fn status(&self) -> Value {
+        let (breaker, account_breakers): (CircuitBreakerState, BTreeMap<AccountId, CircuitBreakerState>) =
+            match self.circuit_breaker.lock() {
+                Ok(breakers) => (breakers.state(), breakers.account_states().keys()
+                    .map(|account| (account.clone(), breakers.account_state(account)))
+                    .collect()),
+                Err(_) => (CircuitBreakerState::Tripped, BTreeMap::new()),
+            };
+        let open_orders: Vec<OpenOrder> = self.executor.open_orders().iter()
+            .map(|order| OpenOrder {
+                order_hash: order_hash(order),
+                account: order.signal.account.clone(),
+                symbol: order.signal.symbol.clone(),
+                venue: order.signal.venue.clone(),
+                side: order.signal.side,
+                order_type: order.signal.order_type,
+                quantity: order.signal.quantity,
+            })
+            .collect();
+        let accounts: Vec<Value> = {
+            let manager = self.portfolio.read().unwrap_or_else(PoisonError::into_inner);
+            manager.accounts().map(|account| json!({
+                "account": account,
+                "circuit_breaker": account_breakers.get(account).copied().unwrap_or(breaker),
+                "portfolio": manager.account_portfolio(account),
+                "open_orders": self.executor.open_orders_for(account).len(),
+            })).collect()
+        };
+        json!({
+            "mode": self.reloader.current().system.mode,
+            "gate": self.risk_gate.state(),
+            "accepting_orders": self.executor.is_accepting(),
+            "paused_venues": self.executor.paused_venues(),
+            "circuit_breaker": breaker,
+            "health": self.telemetry.get_health().await,
+            "portfolio": portfolio_snapshot(&self.portfolio),
+            "accounts": accounts,
+            "open_orders": open_orders,
+            "in_flight": self.executor.in_flight(),
+            "incidents": self.incidents.open_incidents(),
+            "recorder": self.data_manager.recorder_stats(),
+        })
+    }
+
+    fn pause(&self, source: &str) -> Result<Value, String> {
+        self.risk_gate.close(format!("paused by {}", source));
+        Ok(json!({ "gate": self.risk_gate.state() }))
+    }
+
+    fn resume(&self, source: &str) -> Result<Value, String> {
+        if !self.executor.is_accepting() {
+            return Err("the kill switch was triggered; restart to trade again".to_string());
+        }
+        info!("Trading resumed by {}", source);
+        self.risk_gate.open();
+        Ok(json!({ "gate": self.risk_gate.state() }))
+    }
+
+    /// Accept orders for a venue paused after an auth failure
+    fn resume_venue(&self, venue: &str, source: &str) -> Result<Value, String> {
+        let venue = Venue::try_from(venue.to_string()).map_err(|e| e.to_string())?;
+        if !self.executor.resume_venue(&venue) {
+            return Err(format!("{} is not paused", venue));
+        }
+        info!("{} resumed by {}", venue, source);
+        Ok(json!({ "paused_venues": self.executor.paused_venues() }))
+    }
+
+    /// Close the gate, refuse orders for the rest of the run, and cancel
+    /// every resting order
+    async fn kill_switch(&self, source: &str) -> Result<Value, String> {
+        self.risk_gate.close(format!("kill switch triggered by {}", source));
+        self.executor.stop_intake();
+        let config = self.reloader.current();
+        let mut failures = Vec::new();
+        let mut cancelled = 0;
+        for symbol in config.symbols() {
+            for (venue, _) in config.venues() {
+                match self.executor.cancel_all(&symbol, &venue).await {
+                    Ok(()) => cancelled += 1,
+                    Err(e) => failures.push(format!("{} on {}: {}", symbol.0, venue, e)),
+                }
+            }
+        }
+        if failures.is_empty() {
+            Ok(json!({ "gate": self.risk_gate.state(), "markets_cancelled": cancelled }))
+        } else {
+            Err(format!("cancel failed: {}", failures.join("; ")))
+        }
+    }
+
+    fn acknowledge(&self, incident_id: u64, source: &str) -> Result<Value, String> {
+        self.incidents.acknowledge(incident_id, source, chrono::Utc::now())
+            .map(|incident| json!(incident))
+            .map_err(|e| e.to_string())
+    }
+
+    async fn snapshot(&self) -> Result<Value, String> {
+        self.snapshot.export("control").await
+            .map(|path| json!({ "path": path }))
+            .map_err(|e| e.to_string())
+    }
+
+    fn audit(&self, action: &str, source: &str, outcome: &str) {
+        let record = AuditRecord::ControlAction {
+            action: action.to_string(),
+            source: source.to_string(),
+            outcome: outcome.to_string(),
+        };
+        if let Err(e) = self.audit_log.append("", None, record) {
+            error!("Failed to audit control action {}: {}", action, e);
+        }
+    }
+}
+
+/// Bind the socket owner-only, replacing a stale one left by a crash
+#[cfg(unix)]
+async fn bind_unix(path: &std::path::Path) -> anyhow::Result<tokio::net::UnixListener> {
+    use std::os::unix::fs::PermissionsExt;
+
+    if path.exists() {
+        if tokio::net::UnixStream::connect(path).await.is_ok() {
+            anyhow::bail!("{} is in use; is another instance running?", path.display());
+        }
+        std::fs::remove_file(path)?;
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let listener = tokio::net::UnixListener::bind(path)
+        .with_context(|| format!("Failed to bind control socket {}", path.display()))?;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+    Ok(listener)
+}
+
+/// `ctl` subcommands
+#[derive(Debug, Subcommand)]
+pub enum CtlCommand {
+    /// Health, positions, open orders, gate and breaker state, open incidents
+    Status,
+    /// Close the risk gate (no new orders) until `resume`
+    Pause,
+    /// Reopen the risk gate
+    Resume,
+    /// Accept orders again for a venue paused after an auth failure
+    ResumeVenue {
+        venue: String,
+    },
+    /// Close the gate, refuse orders until restart, and cancel every resting order
+    KillSwitch,
+    /// Acknowledge an incident
+    Ack {
+        incident_id: u64,
+    },
+    /// Export a state snapshot
+    Snapshot,
+}
+
+impl CtlCommand {
+    fn request(&self, token: Option<&str>) -> Value {
+        let (method, mut params) = match self {
+            CtlCommand::Status => ("status", json!({})),
+            CtlCommand::Pause => ("pause", json!({})),
+            CtlCommand::Resume => ("resume", json!({})),
+            CtlCommand::ResumeVenue { venue } => ("resume_venue", json!({ "venue": venue })),
+            CtlCommand::KillSwitch => ("kill_switch", json!({})),
+            CtlCommand::Ack { incident_id } => ("acknowledge", json!({ "incident_id": incident_id })),
+            CtlCommand::Snapshot => ("snapshot", json!({})),
+        };
+        if let Some(token) = token {
+            params["token"] = json!(token);
+        }
+        json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params })
+    }
+}
+
+/// Send one command to the running instance; Err(message) when it refused
+pub async fn send(settings: &ControlSection, command: &CtlCommand, token: Option<&str>) -> anyhow::Result<Result<Value, String>> {
+    let mut line = serde_json::to_vec(&command.request(token))?;
+    line.push(b'\n');
+    let reply = match settings.transport {
+        ControlTransport::Tcp => {
+            let stream = tokio::net::TcpStream::connect(&settings.bind).await
+                .with_context(|| format!("No instance listening on tcp {}", settings.bind))?;
+            exchange(stream, &line).await?
+        }
+        #[cfg(unix)]
+        ControlTransport::Unix => {
+            let stream = tokio::net::UnixStream::connect(&settings.socket_path).await
+                .with_context(|| format!("No instance listening on {}", settings.socket_path.display()))?;
+            exchange(stream, &line).await?
+        }
+        #[cfg(not(unix))]
+        ControlTransport::Unix => anyhow::bail!("the unix control transport needs a Unix platform; use tcp"),
+    };
+    let response: Response = serde_json::from_str(&reply).context("Malformed control response")?;
+    Ok(match (response.result, response.error) {
+        (_, Some(error)) => Err(format!("{} ({})", error.message, error.code)),
+        (Some(result), None) => Ok(result),
+        (None, None) => Ok(Value::Null),
+    })
+}
+
+async fn exchange<S: AsyncRead + AsyncWrite + Unpin>(stream: S, request: &[u8]) -> anyhow::Result<String> {
+    let (reader, mut writer) = tokio::io::split(stream);
+    writer.write_all(request).await?;
+    let mut reply = String::new();
+    BufReader::new(reader).read_line(&mut reply).await?;
+    if reply.is_empty() {
+        anyhow::bail!("The instance closed the connection without answering");
+    }
+    Ok(reply)
+}
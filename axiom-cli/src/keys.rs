@@ -0,0 +1,251 @@
+//! Key Commands: C=0 Signing Key Setup and Rotation
+//!
+//! `keys generate` writes a new encrypted signing key, `keys show-public`
+//! prints a key file's verifying key, and `keys rotate` schedules (then, with
+//! `--finish`, completes) a hand-over to a successor key. At startup the
+//! signing key, and any scheduled successor, is loaded from the same files;
+//! live mode refuses to start without a key, paper mode falls back to an
+//! ephemeral one.
+
+use anyhow::{bail, Context};
+use axiom_core::{
+    key_id, parse_public_key, read_public_key, CZeroKey, RotationSchedule, SigningKeys, TrustedKey,
+};
+use chrono::{Duration, Utc};
+use clap::{Args, Subcommand};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::{info, warn};
+use zeroize::Zeroizing;
+
+/// Where the signing key lives and which keys the executor trusts
+#[derive(Debug, Clone)]
+pub struct KeySettings {
+    /// Encrypted signing key file
+    pub path: PathBuf,
+    /// Environment variable holding the passphrase (prompted for when unset)
+    pub passphrase_env: String,
+    /// Hex verifying keys accepted besides our own
+    pub trusted_public_keys: Vec<String>,
+    /// Lifetime of each C=0 signature
+    pub signature_validity: chrono::Duration,
+    /// Grace period the executor allows past a signature's expiry
+    pub clock_skew: chrono::Duration,
+}
+
+impl Default for KeySettings {
+    fn default() -> Self {
+        Self {
+            path: PathBuf::from("keys/czero.key"),
+            passphrase_env: "AXIOM_KEY_PASSPHRASE".to_string(),
+            trusted_public_keys: Vec::new(),
+            signature_validity: chrono::Duration::milliseconds(axiom_core::DEFAULT_SIGNATURE_VALIDITY_MS),
+            clock_skew: chrono::Duration::milliseconds(axiom_execution::DEFAULT_CLOCK_SKEW_MS),
+        }
+    }
+}
+
+/// `keys` subcommands
+#[derive(Debug, Subcommand)]
+pub enum KeysCommand {
+    /// Write a new encrypted signing key and print its public key
+    Generate(KeyPath),
+    /// Print a key file's verifying key and key id
+    ShowPublic(KeyPath),
+    /// Schedule a hand-over to a successor key (or complete it with --finish)
+    Rotate(RotateArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct KeyPath {
+    /// Key file (default: keys.signing_key_path from the config)
+    #[arg(long = "key", value_name = "PATH")]
+    path: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct RotateArgs {
+    #[command(flatten)]
+    key: KeyPath,
+    /// Minutes until the successor starts signing
+    #[arg(long, value_name = "N", default_value_t = 60)]
+    activate_in_min: u32,
+    /// Minutes the current key stays accepted after the hand-over
+    #[arg(long, value_name = "N", default_value_t = 60)]
+    grace_min: u32,
+    /// Promote the successor once the grace period is over
+    #[arg(long, conflicts_with_all = ["activate_in_min", "grace_min"])]
+    finish: bool,
+}
+
+impl KeyPath {
+    fn resolve(&self, settings: &KeySettings) -> PathBuf {
+        self.path.clone().unwrap_or_else(|| settings.path.clone())
+    }
+}
+
+/// Run a `keys` subcommand
+pub fn run(command: &KeysCommand, settings: &KeySettings) -> anyhow::Result<()> {
+    match command {
+        KeysCommand::Generate(key) => {
+            let path = key.resolve(settings);
+            let key = create_key(&path, settings)?;
+            println!("Wrote {}", path.display());
+            println!("{}", key.public_key_hex());
+            Ok(())
+        }
+        KeysCommand::ShowPublic(key) => {
+            let path = key.resolve(settings);
+            let public_key = read_public_key(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            println!("{} (key id {})", hex::encode(public_key.as_bytes()), key_id(&public_key));
+            Ok(())
+        }
+        KeysCommand::Rotate(rotate) if rotate.finish => finish_rotation(&rotate.key.resolve(settings)),
+        KeysCommand::Rotate(rotate) => schedule_rotation(
+            &rotate.key.resolve(settings),
+            Duration::minutes(rotate.activate_in_min.into()),
+            Duration::minutes(rotate.grace_min.into()),
+            settings,
+        ),
+    }
+}
+
+/// Write a successor key and the schedule handing signing over to it
+fn schedule_rotation(path: &Path, activate_in: Duration, grace: Duration, settings: &KeySettings) -> anyhow::Result<()> {
+    let schedule_path = sibling(path, ".rotation");
+    if let Some(schedule) = RotationSchedule::load(&schedule_path)? {
+        bail!(
+            "Rotation to key {} is already scheduled for {}; run `keys rotate --finish` after {}",
+            schedule.next_key_id, schedule.activate_at, schedule.retire_at()
+        );
+    }
+    let current = read_public_key(path)
+        .with_context(|| format!("Failed to read current key {}", path.display()))?;
+
+    let next = create_key(&sibling(path, ".next"), settings)?;
+    let schedule = RotationSchedule {
+        next_key_id: next.key_id(),
+        activate_at: Utc::now() + activate_in,
+        grace_secs: grace.num_seconds(),
+    };
+    schedule.save(&schedule_path)?;
+
+    println!("Key {} signs from {}", schedule.next_key_id, schedule.activate_at);
+    println!("Key {} is accepted until {}", key_id(&current), schedule.retire_at());
+    println!("Next public key (add to trusted_public_keys on other executors):");
+    println!("{}", next.public_key_hex());
+    println!("Restart running instances to pick up the schedule; run `keys rotate --finish` after the grace period.");
+    Ok(())
+}
+
+/// Promote the successor key once the old key's grace period is over
+fn finish_rotation(path: &Path) -> anyhow::Result<()> {
+    let schedule_path = sibling(path, ".rotation");
+    let Some(schedule) = RotationSchedule::load(&schedule_path)? else {
+        bail!("No rotation in progress for {}", path.display());
+    };
+    if Utc::now() < schedule.retire_at() {
+        bail!("The current key is still accepted until {}; finish after that", schedule.retire_at());
+    }
+
+    let retired_id = key_id(&read_public_key(path)?);
+    let retired = sibling(path, &format!(".retired-{}", retired_id));
+    rename_pair(path, &retired)?;
+    rename_pair(&sibling(path, ".next"), path)?;
+    std::fs::remove_file(&schedule_path)?;
+
+    println!("Key {} retired to {}", retired_id, retired.display());
+    println!("Key {} is now the current key", schedule.next_key_id);
+    Ok(())
+}
+
+/// Move a key file and its nonce counter together
+fn rename_pair(from: &Path, to: &Path) -> anyhow::Result<()> {
+    std::fs::rename(from, to).with_context(|| format!("Failed to move {} to {}", from.display(), to.display()))?;
+    let (from_nonce, to_nonce) = (sibling(from, ".nonce"), sibling(to, ".nonce"));
+    if from_nonce.exists() {
+        std::fs::rename(&from_nonce, &to_nonce)?;
+    }
+    Ok(())
+}
+
+fn create_key(path: &Path, settings: &KeySettings) -> anyhow::Result<CZeroKey> {
+    if path.exists() {
+        bail!("{} already exists; refusing to overwrite a signing key", path.display());
+    }
+    let passphrase = match std::env::var(&settings.passphrase_env) {
+        Ok(passphrase) => Zeroizing::new(passphrase),
+        Err(_) => {
+            let first = Zeroizing::new(rpassword::prompt_password("New key passphrase: ")?);
+            let confirm = Zeroizing::new(rpassword::prompt_password("Confirm passphrase: ")?);
+            if *first != *confirm {
+                bail!("Passphrases do not match");
+            }
+            first
+        }
+    };
+    let key = CZeroKey::generate();
+    key.save(path, &passphrase)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(key)
+}
+
+/// Load the signing keys for this run
+///
+/// Live mode aborts if the key is missing or cannot be decrypted. Paper mode
+/// substitutes an ephemeral key whose signatures nothing else will trust.
+pub fn load_signing_keys(settings: &KeySettings, live: bool) -> anyhow::Result<SigningKeys> {
+    match load(&settings.path, &settings.passphrase_env) {
+        Ok(keys) => Ok(keys),
+        Err(e) if live => Err(e.context(format!(
+            "Live mode requires the C=0 signing key at {} (create one with `axiom-cli keys generate`)",
+            settings.path.display()
+        ))),
+        Err(e) => {
+            let key = CZeroKey::generate();
+            warn!("{:#}; paper mode is using ephemeral signing key {}", e, key.public_key_hex());
+            Ok(SigningKeys::new(Arc::new(key)))
+        }
+    }
+}
+
+fn load(path: &Path, passphrase_env: &str) -> anyhow::Result<SigningKeys> {
+    if !path.exists() {
+        bail!("No signing key at {}", path.display());
+    }
+    let passphrase = match std::env::var(passphrase_env) {
+        Ok(passphrase) => Zeroizing::new(passphrase),
+        Err(_) => Zeroizing::new(rpassword::prompt_password(format!("Passphrase for {}: ", path.display()))?),
+    };
+    let current = CZeroKey::load(path, &passphrase).with_context(|| format!("Failed to load {}", path.display()))?;
+    info!("Loaded C=0 signing key {}", current.key_id());
+    let keys = SigningKeys::new(Arc::new(current));
+
+    let Some(schedule) = RotationSchedule::load(sibling(path, ".rotation"))? else {
+        return Ok(keys);
+    };
+    let next_path = sibling(path, ".next");
+    let next = CZeroKey::load(&next_path, &passphrase)
+        .with_context(|| format!("Rotation is scheduled but {} failed to load", next_path.display()))?;
+    info!("Key {} scheduled to take over signing at {}", next.key_id(), schedule.activate_at);
+    Ok(keys.with_next(Arc::new(next), schedule)?)
+}
+
+/// Keys the executor accepts: our own (with rotation windows) plus configured extras
+pub fn trusted_keys(settings: &KeySettings, own: &SigningKeys) -> anyhow::Result<Vec<TrustedKey>> {
+    let mut keys = own.trusted();
+    for hex_key in &settings.trusted_public_keys {
+        let key = parse_public_key(hex_key).with_context(|| format!("Invalid trusted key {}", hex_key))?;
+        keys.push(TrustedKey::new(key));
+    }
+    Ok(keys)
+}
+
+/// `path` with `suffix` appended to its file name
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(suffix);
+    PathBuf::from(name)
+}
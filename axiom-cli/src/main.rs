@@ -1,60 +1,339 @@
 //! Axiom Hive CLI: The Command Interface
 //!
 //! Main entry point for the Axiom Hive trading system.
+//!
+//! - `run`: live trading with the configured venues (`--dry-run`: send nothing)
+//! - `paper`: the same pipeline against the paper venue, optionally fed from a journal or capture
+//! - `backtest --journal`: signal generation over recorded books, with a report
+//! - `replay --journal`: re-run a journal and diff against recorded decisions
+//! - `reproduce --journal | --capture`: check that paper runs over a recording are bit-for-bit identical
+//! - `verify-order <hash>`: re-check one order's proof and signature
+//! - `keys`: manage and rotate the C=0 signing key
+//! - `ctl`: query and control a running instance (see `control`)
+//!
+//! `run` and `paper` reload the config file on SIGHUP (see `reload`).
+//!
+//! Exit codes are stable for scripting (see `exit`).
+
+mod backtest;
+mod control;
+mod keys;
+mod pipeline;
+mod reload;
+mod reproduce;
+mod runtime;
+mod settings;
+mod shutdown;
+mod verify;
+
+use anyhow::Context;
+use backtest::Recording;
+use clap::{Args, Parser, Subcommand};
+use reload::ConfigReloader;
+use settings::{Config, ConfigError, Mode, DEFAULT_CONFIG_PATH};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use tracing::{info, warn};
+
+/// Process exit codes
+mod exit {
+    pub const OK: u8 = 0;
+    /// Runtime failure (I/O, keys, startup)
+    pub const FAILURE: u8 = 1;
+    /// Invalid command line (clap's own code)
+    pub const USAGE: u8 = 2;
+    /// Invalid or unreadable configuration
+    pub const CONFIG: u8 = 3;
+    /// Replay diverged, runs were not reproducible, or an order failed
+    /// re-verification
+    pub const MISMATCH: u8 = 4;
+    /// The requested order is not in the audit log
+    pub const NOT_FOUND: u8 = 5;
+    /// A pipeline task failed, or a shutdown step (drain, cancel, flatten,
+    /// persist, flush) did not complete
+    pub const INCOMPLETE_SHUTDOWN: u8 = 6;
+    /// The running instance refused or failed a control request
+    pub const CONTROL_REFUSED: u8 = 7;
+}
 
-use axiom_core::{Symbol, Venue, Portfolio};
-use axiom_data::DataIngestionManager;
-use axiom_engine::SignalGenerator;
-use axiom_execution::OrderExecutor;
-use axiom_risk::{PortfolioManager, CircuitBreaker};
-use axiom_oracle::{SystemMonitor, TelemetryCollector, AlertManager};
-use tokio::sync::mpsc;
-use tracing::{info, error};
-use std::time::Instant;
+#[derive(Debug, Parser)]
+#[command(name = "axiom-cli", version, about = "Axiom Hive trading system")]
+struct Cli {
+    /// Configuration file [default: config/default.toml, or built-in defaults if absent]
+    #[arg(long, global = true, value_name = "PATH")]
+    config: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Trade live on the configured venues
+    Run {
+        #[command(flatten)]
+        run: RunArgs,
+        /// Run everything up to the exchange call, record the orders, send nothing
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Run the full pipeline against the paper venue
+    Paper {
+        #[command(flatten)]
+        run: RunArgs,
+        /// Feed the pipeline from a JSON-lines file of order books instead of a venue
+        #[arg(long, value_name = "PATH")]
+        journal: Option<PathBuf>,
+        /// Feed the pipeline from a JSON-lines capture of trades and book snapshots (a file or a recorder directory)
+        #[arg(long, value_name = "PATH", conflicts_with = "journal")]
+        capture: Option<PathBuf>,
+        /// Pace the recording by its timestamps at this multiple of real time
+        /// [default: as fast as possible]
+        #[arg(long, value_name = "MULTIPLIER")]
+        speed: Option<f64>,
+    },
+    /// Drive signal generation over a book journal and write a report
+    Backtest {
+        /// JSON-lines file of order books
+        #[arg(long, value_name = "PATH")]
+        journal: PathBuf,
+        /// Report file
+        #[arg(long, value_name = "PATH", default_value = "reports/backtest.json")]
+        report: PathBuf,
+    },
+    /// Re-run signal generation over a journal and diff against recorded decisions
+    Replay {
+        /// JSON-lines file of order books
+        #[arg(long, value_name = "PATH")]
+        journal: PathBuf,
+        /// Audit log holding the recorded decisions [default: audit.log_path]
+        #[arg(long, value_name = "PATH")]
+        audit: Option<PathBuf>,
+    },
+    /// Run a recording through the paper pipeline twice and check both runs decide identically
+    Reproduce {
+        /// JSON-lines file of order books
+        #[arg(long, value_name = "PATH", required_unless_present = "capture")]
+        journal: Option<PathBuf>,
+        /// JSON-lines capture of trades and book snapshots (a file or a recorder directory)
+        #[arg(long, value_name = "PATH", conflicts_with = "journal")]
+        capture: Option<PathBuf>,
+        /// Trace of an earlier run to compare against
+        #[arg(long, value_name = "PATH")]
+        baseline: Option<PathBuf>,
+        /// Write this run's trace to --baseline instead of comparing
+        #[arg(long, requires = "baseline")]
+        update_baseline: bool,
+    },
+    /// Re-verify an order's proof and C=0 signature from the audit log
+    VerifyOrder {
+        /// Order hash (or signal hash)
+        hash: String,
+        /// Audit log [default: audit.log_path]
+        #[arg(long, value_name = "PATH")]
+        audit: Option<PathBuf>,
+    },
+    /// Generate, show, or rotate the C=0 signing key
+    #[command(subcommand)]
+    Keys(keys::KeysCommand),
+    /// Query or control the running instance over its control endpoint
+    Ctl {
+        /// Control token [default: $AXIOM_CONTROL_TOKEN, then control.token]
+        #[arg(long)]
+        token: Option<String>,
+        #[command(subcommand)]
+        command: control::CtlCommand,
+    },
+}
+
+#[derive(Debug, Args)]
+struct RunArgs {
+    /// Close every open position during shutdown
+    #[arg(long)]
+    flatten_on_exit: bool,
+}
 
 #[tokio::main]
-async fn main() -> anyhow::Result<()> {
+async fn main() -> ExitCode {
+    let cli = match Cli::try_parse() {
+        Ok(cli) => cli,
+        Err(e) => {
+            let _ = e.print();
+            return ExitCode::from(if e.use_stderr() { exit::USAGE } else { exit::OK });
+        }
+    };
+
     // Initialize logging
     tracing_subscriber::fmt()
         .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
         .init();
 
-    info!("Axiom Hive System Initializing...");
-    info!("Seed: 42 (LOCKED)");
-    info!("Signature: C=0");
-    info!("Protocol: DAVP Verified");
-
-    // Initialize components
-    let (tick_tx, _tick_rx) = mpsc::unbounded_channel();
-    let (book_tx, _book_rx) = mpsc::unbounded_channel();
-    
-    let data_manager = DataIngestionManager::new(tick_tx, book_tx);
-    let signal_generator = SignalGenerator::new();
-    let order_executor = OrderExecutor::new();
-    let portfolio_manager = PortfolioManager::new(rust_decimal::Decimal::from(10000)); // $10k initial
-    let circuit_breaker = CircuitBreaker::new(axiom_core::constants::MAX_DAILY_DRAWDOWN);
-    let system_monitor = SystemMonitor::new(1000);
-    let telemetry = TelemetryCollector::new();
-    let alert_manager = AlertManager;
-
-    info!("All components initialized");
-
-    // Main trading loop (simplified)
-    info!("Entering main trading loop...");
-    
-    // Placeholder: In production, this would:
-    // 1. Start data ingestion streams
-    // 2. Process order book updates
-    // 3. Generate signals
-    // 4. Execute verified orders
-    // 5. Monitor system health
-    
-    info!("System Status: OPTIMAL");
-    
-    // Keep running (in production, would have proper shutdown handling)
-    tokio::signal::ctrl_c().await?;
-    info!("Shutdown signal received");
-
-    Ok(())
+    match dispatch(cli).await {
+        Ok(code) => ExitCode::from(code),
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            ExitCode::from(if e.is::<ConfigError>() { exit::CONFIG } else { exit::FAILURE })
+        }
+    }
+}
+
+async fn dispatch(cli: Cli) -> anyhow::Result<u8> {
+    let path = cli.config.as_deref();
+    match cli.command {
+        Command::Run { run: RunArgs { flatten_on_exit }, dry_run } => {
+            run(path, None, move |config| {
+                config.system.mode = Mode::Live;
+                config.shutdown.flatten_on_exit |= flatten_on_exit;
+                config.dry_run.enabled |= dry_run;
+            })
+            .await
+        }
+        Command::Paper { run: RunArgs { flatten_on_exit }, journal, capture, speed } => {
+            let replay = recording(journal, capture).map(|recording| (recording, speed));
+            run(path, replay, move |config| {
+                config.system.mode = Mode::Paper;
+                config.venues.primary = axiom_core::Venue::Paper.to_string();
+                config.venues.secondary.clear();
+                config.shutdown.flatten_on_exit |= flatten_on_exit;
+            })
+            .await
+        }
+        Command::Backtest { journal, report } => {
+            let config = load_config(path, paper)?;
+            let outcome = backtest::backtest(&config, &journal, &report)?;
+            println!("{} books from {}; report written to {}", outcome.books, journal.display(), report.display());
+            for (symbol, stages) in &outcome.funnel {
+                let counts: Vec<String> = stages.iter().map(|(stage, count)| format!("{} {}", stage, count)).collect();
+                println!("  {}: {}", symbol.0, counts.join(", "));
+            }
+            Ok(exit::OK)
+        }
+        Command::Replay { journal, audit } => {
+            let config = load_config(path, paper)?;
+            let audit = audit.unwrap_or_else(|| config.audit.log_path.clone());
+            let (signals, divergences) = backtest::replay(&config, &journal, &audit)?;
+            for divergence in &divergences {
+                let show = |decision: &Option<backtest::Decision>| decision.as_ref()
+                    .map_or_else(|| "absent".to_string(), ToString::to_string);
+                println!("{}: recorded {}, replayed {}", divergence.signal_hash,
+                    show(&divergence.recorded), show(&divergence.replayed));
+            }
+            println!("{} signals replayed, {} divergent", signals, divergences.len());
+            Ok(if divergences.is_empty() { exit::OK } else { exit::MISMATCH })
+        }
+        Command::Reproduce { journal, capture, baseline, update_baseline } => {
+            let config = load_config(path, paper)?;
+            let recording = recording(journal, capture).context("--journal or --capture is required")?;
+            let outcome = reproduce::reproduce(&config, &recording, baseline.as_deref(), update_baseline)?;
+            println!("{} events from {}; trace hash {}", outcome.trace.events.len(), recording.path().display(), outcome.trace.hash);
+            if let Some(divergence) = &outcome.between_runs {
+                println!("Runs diverge at {}", divergence);
+            }
+            if let Some(divergence) = &outcome.from_baseline {
+                println!("Baseline diverges at {}", divergence);
+            }
+            if let (true, Some(baseline)) = (update_baseline, &baseline) {
+                println!("Baseline written to {}", baseline.display());
+            }
+            Ok(if outcome.between_runs.is_none() && outcome.from_baseline.is_none() { exit::OK } else { exit::MISMATCH })
+        }
+        Command::VerifyOrder { hash, audit } => {
+            let config = load_config(path, |_| {})?;
+            let audit = audit.unwrap_or_else(|| config.audit.log_path.clone());
+            Ok(match verify::verify_order(&audit, &hash, &config.key_settings())? {
+                verify::Verification::Valid => exit::OK,
+                verify::Verification::Invalid => exit::MISMATCH,
+                verify::Verification::NotFound => exit::NOT_FOUND,
+            })
+        }
+        Command::Keys(command) => {
+            let config = load_config(path, |_| {})?;
+            keys::run(&command, &config.key_settings())?;
+            Ok(exit::OK)
+        }
+        Command::Ctl { token, command } => {
+            let config = load_config(path, |_| {})?;
+            let token = token
+                .or_else(|| std::env::var(control::TOKEN_ENV).ok())
+                .or_else(|| (!config.control.token.is_empty()).then(|| config.control.token.clone()));
+            match control::send(&config.control, &command, token.as_deref()).await? {
+                Ok(result) => {
+                    println!("{}", serde_json::to_string_pretty(&result)?);
+                    Ok(exit::OK)
+                }
+                Err(message) => {
+                    eprintln!("Refused: {}", message);
+                    Ok(exit::CONTROL_REFUSED)
+                }
+            }
+        }
+    }
+}
+
+/// Run the pipeline, fed from `replay` (a recording and its speed) when
+/// given; `adjust` is reapplied on every config reload, and the exit code
+/// says whether the run and its shutdown completed
+async fn run(
+    path: Option<&Path>,
+    replay: Option<(Recording, Option<f64>)>,
+    adjust: impl Fn(&mut Config) + Send + Sync + 'static,
+) -> anyhow::Result<u8> {
+    let config = load_config(path, &adjust)?;
+    // Read before anything starts, so a bad recording fails fast
+    let replay = match replay {
+        Some((recording, speed)) => {
+            let source = recording.open(&config.symbol_mapper())?;
+            Some(match speed {
+                Some(speed) => source.with_speed(speed),
+                None => source,
+            })
+        }
+        None => None,
+    };
+    let reloader = ConfigReloader::new(config, config_file(path).map(Path::to_path_buf), adjust);
+    let report = pipeline::run(reloader, replay).await?;
+    for step in report.steps.iter().filter(|step| !step.completed) {
+        eprintln!("Shutdown step {} incomplete: {}", step.step, step.detail);
+    }
+    Ok(if report.completed() { exit::OK } else { exit::INCOMPLETE_SHUTDOWN })
+}
+
+/// The recording named by `--journal` or `--capture` (clap allows one)
+fn recording(journal: Option<PathBuf>, capture: Option<PathBuf>) -> Option<Recording> {
+    journal.map(Recording::Journal).or(capture.map(Recording::Capture))
+}
+
+/// Offline commands never trade, whatever mode the file says
+fn paper(config: &mut Config) {
+    config.system.mode = Mode::Paper;
 }
 
+/// Load the given config, or the default file; built-in defaults are used
+/// only when no path was given and the default file does not exist
+fn load_config(path: Option<&Path>, adjust: impl FnOnce(&mut Config)) -> Result<Config, ConfigError> {
+    let path = match config_file(path) {
+        Some(path) => path,
+        None => {
+            warn!("{} not found; using built-in defaults", DEFAULT_CONFIG_PATH);
+            let mut config = Config::default();
+            adjust(&mut config);
+            let problems = config.validate("");
+            if !problems.is_empty() {
+                return Err(ConfigError::Invalid { path: "<defaults>".to_string(), problems });
+            }
+            return Ok(config);
+        }
+    };
+    let config = Config::load(path, adjust)?;
+    info!("Configuration loaded from {}", path.display());
+    Ok(config)
+}
+
+/// The file `load_config` reads, if any
+fn config_file(path: Option<&Path>) -> Option<&Path> {
+    match path {
+        Some(path) => Some(path),
+        None if Path::new(DEFAULT_CONFIG_PATH).exists() => Some(Path::new(DEFAULT_CONFIG_PATH)),
+        None => None,
+    }
+}
@@ -0,0 +1,446 @@
+//! Pipeline: The Trading Loop Behind `run` and `paper`
+//!
+//! Builds every component from the validated configuration, starts
+//! ingestion and the supervised pipeline tasks (see `runtime`), and runs
+//! until Ctrl-C or a task failure, then hands the components to the shutdown
+//! sequence. `run` and `paper` share this; they differ only in the mode (and
+//! venue) the configuration was adjusted to before validation, and paper mode
+//! can be fed from a recording (a book journal or a capture). While running, limits, proposer
+//! tuning, alert thresholds and the symbol list follow config reloads (see
+//! `reload`).
+
+use crate::control::ControlServer;
+use crate::keys;
+use crate::reload::ConfigReloader;
+use crate::runtime::{self, EngineStats, ExecutionTask, FundingRates, GeneratorFactory, HealthTask, LiquidationIntensities, SignalDriver, Supervisor, VenueRemediation, ORDER_QUEUE};
+use crate::settings::Mode;
+use crate::shutdown::{ShutdownCoordinator, ShutdownReport, StepOutcome};
+use axiom_core::{AccountId, Amount, AuditLog, EventLog, InstrumentRegistry, Symbol, Venue};
+use axiom_data::{feed_channel, DataIngestionManager, DerivativesPoller, InstrumentFetcher, Recorder, ReplaySource};
+use axiom_engine::{OrderSigner, RegimeDetector, SignalGenerator, SignalSettings};
+use axiom_execution::{DryRunClient, ExchangeClient, OrderExecutor, SafetyChecker};
+use axiom_risk::{AccountBreakers, PortfolioManager, CircuitBreaker, Reconciler, RiskGate};
+use axiom_oracle::{
+    AlertManager, AlertSink, BacklogAlertListener, IncidentTracker, LiveStreamHub, LiveStreamServer, LogAlertSink, MonitorFunnelRecorder,
+    MonitorLatencyRecorder, RegimeAlertListener, SmtpAlertSink, SnapshotExporter, SystemMonitor, TelemetryCollector,
+};
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+use tokio::sync::{mpsc, watch};
+use tracing::{info, error, warn};
+
+/// Run the trading pipeline until Ctrl-C (or a task failure), then shut it
+/// down in order; `replay` feeds recorded ticks and books in instead of a venue
+pub async fn run(reloader: ConfigReloader, replay: Option<ReplaySource>) -> anyhow::Result<ShutdownReport> {
+    let config = reloader.current();
+    let live = config.system.mode == Mode::Live;
+
+    info!("Axiom Hive System Initializing...");
+    info!("Seed: {} (LOCKED)", config.system.seed);
+    info!("Signature: C=0");
+    info!("Protocol: DAVP Verified");
+    info!("Mode: {}", if live { "LIVE" } else { "paper" });
+    for (venue, environment) in config.venues() {
+        info!("Venue: {} ({:?})", venue, environment);
+    }
+    let symbols: Vec<String> = config.symbols().into_iter().map(|symbol| symbol.0).collect();
+    info!("Symbols: {}", symbols.join(", "));
+    for (name, account) in &config.accounts {
+        info!("Account: {} on {} ({})", name, account.venue, account.symbols.join(", "));
+        // Orders are sent with these, so a live run without them fails fast
+        if live && !config.dry_run.enabled {
+            for var in [&account.api_key_env, &account.api_secret_env] {
+                if std::env::var(var).map_or(true, |value| value.is_empty()) {
+                    anyhow::bail!("account {}: environment variable {} is not set", name, var);
+                }
+            }
+        }
+    }
+    // The C=0 key signs every verified order; the executor only accepts trusted signatures
+    let key_settings = config.key_settings();
+    let signing_keys = Arc::new(keys::load_signing_keys(&key_settings, live)?);
+    let trusted_keys = keys::trusted_keys(&key_settings, &signing_keys)?;
+
+    // Per-symbol limits shared by the verifier and the executor (validated with the config)
+    let limits = Arc::new(config.limits());
+
+    let alert_sink: Arc<dyn AlertSink> = match config.smtp_config() {
+        Some(smtp) => {
+            let (sink, _worker) = SmtpAlertSink::spawn(smtp)?;
+            Arc::new(sink)
+        }
+        None => Arc::new(LogAlertSink),
+    };
+    let reloader = Arc::new(reloader.with_alert_sink(alert_sink.clone()));
+    let alert_manager = Arc::new(AlertManager::new(config.alert_thresholds.clone(), alert_sink.clone())?);
+
+    // Structured events, mirrored to the live stream when it is enabled;
+    // regime changes and verification backlogs are alerted
+    let telemetry = Arc::new(TelemetryCollector::new());
+    let live_stream = config.live_stream_config()
+        .map(|stream| (LiveStreamHub::new(stream.client_buffer), stream));
+    if let Some(parent) = config.events.log_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut event_log = EventLog::open(&config.events.log_path)?
+        .with_listener(RegimeAlertListener::new(alert_manager.clone()))
+        .with_listener(BacklogAlertListener::new(alert_manager.clone()));
+    if let Some((hub, _)) = &live_stream {
+        event_log = event_log.with_listener(hub.event_listener());
+    }
+    let event_log = Arc::new(event_log);
+    let mut audit_log = open_audit_log(&config.audit.log_path)?;
+    if let Some(max_bytes) = config.audit.rotate_bytes {
+        audit_log = audit_log.with_max_bytes(max_bytes);
+    }
+    let audit_log = Arc::new(audit_log);
+
+    // Latency and funnel counts from every stage feed the health snapshots
+    let system_monitor = Arc::new(Mutex::new(SystemMonitor::new(config.monitoring.history)));
+    let latency = Arc::new(MonitorLatencyRecorder::new(system_monitor.clone()));
+    let funnel = Arc::new(MonitorFunnelRecorder::new(system_monitor.clone()));
+
+    // Initialize components
+    // Bounded, so a stalled consumer drops or delays data instead of growing memory
+    let (tick_tx, tick_rx) = feed_channel(config.data.ticks);
+    let (book_tx, book_rx) = feed_channel(config.data.books);
+    let (derivatives_tx, derivatives_rx) = feed_channel(config.data.derivatives);
+    let (funding_tx, funding_rx) = watch::channel(FundingRates::new());
+    let (liquidation_tx, liquidation_rx) = feed_channel(config.data.liquidations);
+    let (intensity_tx, intensity_rx) = watch::channel(LiquidationIntensities::new());
+    let (order_tx, order_rx) = mpsc::channel(ORDER_QUEUE);
+    let (stats_tx, stats_rx) = watch::channel(EngineStats::default());
+
+    // Live data only: a replay is already on disk
+    let recorder = match config.recorder_settings().filter(|_| replay.is_none()) {
+        Some(settings) => {
+            info!("Recording ticks and books to {}", settings.dir.display());
+            Some(Arc::new(Recorder::new(settings)?))
+        }
+        None => None,
+    };
+    let symbol_mapper = Arc::new(config.symbol_mapper());
+    let mut data_manager = DataIngestionManager::new(tick_tx.clone(), book_tx.clone())
+        .with_latency_recorder(latency.clone())
+        .with_schemas(Arc::new(config.schemas()))
+        .with_symbols(symbol_mapper.clone())
+        .with_derivatives_sender(derivatives_tx)
+        .with_liquidation_sender(liquidation_tx);
+    if let Some(recorder) = &recorder {
+        data_manager = data_manager.with_recorder(recorder.clone());
+    }
+    let data_manager = Arc::new(data_manager);
+    // Venue tick and lot rules, fixed for the run; a replay has no venue to ask
+    let mut instruments = InstrumentRegistry::new();
+    if config.instruments.fetch && replay.is_none() {
+        let venues: Vec<Venue> = config.venues().into_iter().map(|(venue, _)| venue).collect();
+        let fetcher = InstrumentFetcher::new().with_symbols(symbol_mapper.clone());
+        if let Err(e) = fetcher.fetch_into(&mut instruments, &venues).await {
+            warn!("Instrument rules not loaded, orders go unrounded: {}", e);
+        }
+    }
+    let instruments = Arc::new(instruments);
+    // One regime detector (and state file) for every generator
+    let regime_detector = Arc::new(Mutex::new(RegimeDetector::new(config.regime.thresholds.clone())
+        .with_state_path(&config.regime.state_path)
+        .with_event_log(event_log.clone())));
+    // A generator per signal worker, plus one the shutdown sequence proposes
+    // exits with. Only the exit generator signs: the workers' orders are
+    // signed by the signal driver, in the order it queues them
+    let build_generator: GeneratorFactory = {
+        let (reloader, regime_detector, instruments) = (reloader.clone(), regime_detector.clone(), instruments.clone());
+        let (event_log, audit_log, latency, funnel) = (event_log.clone(), audit_log.clone(), latency.clone(), funnel.clone());
+        // Workers start when their symbol's first book arrives, so they take
+        // the settings current then and follow reloads from there
+        Arc::new(move || {
+            let config = reloader.current();
+            SignalGenerator::new()
+                .with_proposer_config(config.proposer.clone())
+                .with_limits(Arc::new(config.limits()))
+                .with_verifier_config(config.verifier)
+                .with_instruments(instruments.clone())
+                .with_shadow_settings(config.shadow.clone())
+                .with_event_log(event_log.clone())
+                .with_audit_log(audit_log.clone())
+                .with_shared_regime_detector(regime_detector.clone())
+                .with_latency_recorder(latency.clone())
+                .with_funnel_recorder(funnel.clone())
+                .with_settings_updates(reloader.slice(|config| SignalSettings {
+                    proposer: config.proposer.clone(),
+                    limits: Arc::new(config.limits()),
+                    shadow: config.shadow.clone(),
+                }))
+        })
+    };
+    let mut exit_generator = build_generator()
+        .with_signing_keys(signing_keys.clone())
+        .with_signature_validity(key_settings.signature_validity);
+    // Closed by `ctl pause` and the kill switch; no order passes while closed
+    let risk_gate = RiskGate::new();
+    // Closed when an account's own breaker trips; halts only that account
+    let account_gates: HashMap<AccountId, RiskGate> = config.accounts.keys()
+        .map(|name| (AccountId(name.clone()), RiskGate::new()))
+        .collect();
+    // Fixed for the whole run: a dry run cannot be turned live by a reload
+    let dry_run = (live && config.dry_run.enabled).then(|| {
+        warn!("DRY RUN: orders are signed, checked and routed, then recorded instead of sent");
+        Arc::new(DryRunClient::new().with_audit_log(audit_log.clone()))
+    });
+    let portfolio_manager = Arc::new(RwLock::new(
+        PortfolioManager::for_accounts(config.accounts()).with_correlation(config.risk.correlation),
+    ));
+    // One breaker over every account together, plus one per configured account
+    let mut aggregate = CircuitBreaker::new(config.risk.max_daily_drawdown)
+        .with_event_log(event_log.clone());
+    if let Some(max) = config.liquidations.breaker_max_notional {
+        aggregate = aggregate.with_max_liquidation_notional(Amount::new(max));
+    }
+    let mut breakers = AccountBreakers::new(aggregate);
+    for (name, account) in &config.accounts {
+        let id = AccountId(name.clone());
+        let breaker = CircuitBreaker::new(account.limits.max_daily_drawdown.unwrap_or(config.risk.max_daily_drawdown))
+            .with_max_leverage(limits.account_max_leverage(&id))
+            .with_event_log(event_log.clone());
+        breakers = breakers.with_account(id, breaker);
+    }
+    let circuit_breaker = Arc::new(Mutex::new(breakers));
+    // Symbols a venue reported halted, until restart
+    let (halted_tx, halted_rx) = watch::channel(BTreeSet::new());
+    let mut order_executor = OrderExecutor::new()
+        .with_risk_gate(risk_gate.clone())
+        .with_remediation(Arc::new(VenueRemediation {
+            alert_sink: alert_sink.clone(),
+            data_manager: data_manager.clone(),
+            portfolio: portfolio_manager.clone(),
+            halted: halted_tx,
+            client: dry_run.clone().map(|client| client as Arc<dyn ExchangeClient>),
+            reconciler: Reconciler::new(config.reconciliation),
+            breakers: circuit_breaker.clone(),
+        }))
+        .with_event_log(event_log.clone())
+        .with_audit_log(audit_log.clone())
+        .with_latency_recorder(latency.clone())
+        .with_funnel_recorder(funnel)
+        .with_safety_checker(
+            SafetyChecker::new()
+                .with_trusted_keys(trusted_keys)
+                .with_clock_skew(key_settings.clock_skew)
+                .with_limits(limits.clone())
+                .with_limit_updates(reloader.slice(|config| Arc::new(config.limits())))
+                .with_instruments(instruments.clone()),
+        );
+    if let Some(client) = &dry_run {
+        order_executor = order_executor.with_exchange_client(client.clone());
+    }
+    for (account, gate) in &account_gates {
+        order_executor = order_executor.with_account_gate(account.clone(), gate.clone());
+    }
+    let order_executor = Arc::new(order_executor);
+    let incidents = Arc::new(IncidentTracker::new(alert_sink.clone(), config.escalation_policy()));
+
+    // Background loops stop when the shutdown sequence flips this
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+
+    tokio::spawn(reloader.clone().listen(shutdown_rx.clone()));
+    follow_alert_thresholds(&reloader, alert_manager.clone());
+    follow_symbols(&reloader, data_manager.clone());
+    if let Some(recorder) = recorder {
+        let shutdown = shutdown_rx.clone();
+        tokio::spawn(async move { recorder.run(shutdown).await });
+    }
+
+    let snapshot_exporter = Arc::new(SnapshotExporter::new(&config.snapshot.path, telemetry.clone()));
+    snapshot_exporter.clone().spawn(std::time::Duration::from_secs(config.snapshot.interval_sec), shutdown_rx.clone());
+
+    if let Some((hub, stream)) = live_stream {
+        LiveStreamServer::new(stream, hub, telemetry.clone()).spawn(shutdown_rx.clone()).await?;
+    }
+
+    if config.control.enabled {
+        ControlServer {
+            settings: config.control.clone(),
+            reloader: reloader.clone(),
+            telemetry: telemetry.clone(),
+            portfolio: portfolio_manager.clone(),
+            executor: order_executor.clone(),
+            circuit_breaker: circuit_breaker.clone(),
+            risk_gate: risk_gate.clone(),
+            incidents,
+            snapshot: snapshot_exporter.clone(),
+            audit_log: audit_log.clone(),
+            data_manager: data_manager.clone(),
+        }
+        .spawn(shutdown_rx.clone())
+        .await?;
+    }
+
+    info!("All components initialized");
+
+    // books -> signals -> order queue -> executor -> portfolio; ticks -> stops;
+    // breaker and health on a cadence
+    let mut supervisor = Supervisor::new(shutdown_rx.clone());
+    supervisor.spawn("signals", SignalDriver {
+        build: build_generator,
+        shards: config.signals.shards,
+        // A replayed recording must produce the same order sequence every time
+        ordered: replay.is_some(),
+        portfolio: portfolio_manager.clone(),
+        symbols: reloader.slice(|config| config.symbols()),
+        routes: config.account_routes(),
+        allocations: reloader.slice(|config| config.allocations.clone()),
+        edge_opt_out: reloader.slice(|config| config.edge.opt_out.clone()),
+        halted: halted_rx,
+        funding: funding_rx,
+        liquidations: intensity_rx,
+        signer: OrderSigner::new(signing_keys.clone())
+            .with_validity(key_settings.signature_validity)
+            .with_audit_log(audit_log.clone())
+            .with_latency_recorder(latency.clone()),
+        orders: order_tx,
+        stats: stats_tx,
+        telemetry: telemetry.clone(),
+    }.run(book_rx, shutdown_rx.clone()));
+    supervisor.spawn("execution", ExecutionTask {
+        executor: order_executor.clone(),
+        portfolio: portfolio_manager.clone(),
+        telemetry: telemetry.clone(),
+        fees: config.fee_model(),
+        paper_fills: !live,
+    }.run(order_rx, shutdown_rx.clone()));
+    supervisor.spawn("ticks", runtime::follow_ticks(order_executor.clone(), tick_rx, shutdown_rx.clone()));
+    supervisor.spawn("derivatives", runtime::follow_derivatives(
+        derivatives_rx, funding_tx, portfolio_manager.clone(), telemetry.clone(), shutdown_rx.clone(),
+    ));
+    supervisor.spawn("liquidations", runtime::follow_liquidations(
+        liquidation_rx, intensity_tx, circuit_breaker.clone(), config.liquidation_window(), shutdown_rx.clone(),
+    ));
+    supervisor.spawn("health", HealthTask {
+        portfolio: portfolio_manager.clone(),
+        circuit_breaker,
+        risk_gate: risk_gate.clone(),
+        account_gates,
+        monitor: system_monitor,
+        stats: stats_rx,
+        telemetry: telemetry.clone(),
+        alert_manager,
+        data_manager: data_manager.clone(),
+    }.run(Duration::from_secs(config.monitoring.health_check_interval_sec), shutdown_rx.clone()));
+
+    // Market data last, once everything downstream is listening
+    match replay {
+        Some(source) => {
+            info!("Replaying {} recorded ticks and books", source.len());
+            tokio::spawn(runtime::feed_replay(source, tick_tx, book_tx));
+        }
+        None => {
+            for (venue, _) in config.venues() {
+                data_manager.start_ingestion(venue.clone(), config.symbols()).await
+                    .map_err(|e| anyhow::anyhow!("Failed to start ingestion on {}: {}", venue, e))?;
+            }
+            if let Some(interval) = config.derivatives_poll_interval() {
+                let poller = DerivativesPoller::new(interval).with_symbols(symbol_mapper.clone());
+                let venues: Vec<Venue> = config.venues().into_iter().map(|(venue, _)| venue).collect();
+                let (data_manager, shutdown) = (data_manager.clone(), shutdown_rx.clone());
+                tokio::spawn(async move { poller.run(&data_manager, venues, shutdown).await });
+            }
+        }
+    }
+
+    info!("System Status: OPTIMAL");
+
+    // Keep running until Ctrl-C or a task failure, then wind down in order
+    let failure = tokio::select! {
+        signal = tokio::signal::ctrl_c() => {
+            if let Err(e) = signal {
+                error!("Fatal: {}", e);
+                if let Err(export_err) = snapshot_exporter.export(&format!("fatal: {}", e)).await {
+                    error!("Failed to export fatal snapshot: {}", export_err);
+                }
+                return Err(e.into());
+            }
+            info!("Shutdown signal received");
+            None
+        }
+        reason = supervisor.failure() => {
+            runtime::on_failure(&reason, &risk_gate, alert_sink.as_ref());
+            Some(reason)
+        }
+    };
+
+    // Cancel on the symbols enabled now, which a reload may have changed
+    let config = reloader.current();
+    let markets = config.symbols().into_iter()
+        .flat_map(|symbol| config.venues().into_iter().map(move |(venue, _)| (symbol.clone(), venue)))
+        .collect();
+    let mut report = ShutdownCoordinator {
+        trigger: shutdown_tx,
+        signal_generator: &mut exit_generator,
+        executor: &order_executor,
+        portfolio: &portfolio_manager,
+        markets,
+        snapshot: &snapshot_exporter,
+        event_log: &event_log,
+        audit_log: &audit_log,
+        settings: config.shutdown.clone(),
+        dry_run: dry_run.as_deref().map(|client| (client, &config.dry_run)),
+    }
+    .run()
+    .await;
+    supervisor.join(Duration::from_millis(config.shutdown.drain_timeout_ms)).await;
+
+    // A failed task makes the whole run incomplete, whatever shutdown managed
+    if let Some(reason) = failure {
+        report.steps.insert(0, StepOutcome { step: "supervise", completed: false, detail: reason });
+    }
+    Ok(report)
+}
+
+/// Apply reloaded alert thresholds
+fn follow_alert_thresholds(reloader: &ConfigReloader, alert_manager: Arc<AlertManager>) {
+    let mut thresholds = reloader.slice(|config| config.alert_thresholds.clone());
+    tokio::spawn(async move {
+        while thresholds.changed().await.is_ok() {
+            let next = thresholds.borrow_and_update().clone();
+            if let Err(e) = alert_manager.update_thresholds(next) {
+                error!("Reloaded alert thresholds rejected: {}", e);
+            }
+        }
+    });
+}
+
+/// Subscribe to symbols a reload enabled and unsubscribe from ones it disabled
+fn follow_symbols(reloader: &ConfigReloader, data_manager: Arc<DataIngestionManager>) {
+    let venues: Vec<_> = reloader.current().venues().into_iter().map(|(venue, _)| venue).collect();
+    let mut symbols = reloader.slice(|config| config.symbols());
+    let mut enabled = symbols.borrow().clone();
+    tokio::spawn(async move {
+        while symbols.changed().await.is_ok() {
+            let next = symbols.borrow_and_update().clone();
+            let added: Vec<Symbol> = next.iter().filter(|symbol| !enabled.contains(symbol)).cloned().collect();
+            let removed: Vec<Symbol> = enabled.iter().filter(|symbol| !next.contains(symbol)).cloned().collect();
+            for venue in &venues {
+                if !added.is_empty() {
+                    if let Err(e) = data_manager.start_ingestion(venue.clone(), added.clone()).await {
+                        error!("Failed to subscribe on {}: {}", venue, e);
+                    }
+                }
+                if !removed.is_empty() {
+                    if let Err(e) = data_manager.stop_ingestion(venue.clone(), removed.clone()).await {
+                        error!("Failed to unsubscribe on {}: {}", venue, e);
+                    }
+                }
+            }
+            enabled = next;
+        }
+    });
+}
+
+/// Open the audit log, creating its directory
+pub fn open_audit_log(path: &Path) -> anyhow::Result<AuditLog> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    Ok(AuditLog::open(path)?)
+}
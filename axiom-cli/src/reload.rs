@@ -0,0 +1,267 @@
+//! Reload: Configuration Changes Without a Restart
+//!
+//! On SIGHUP (and, when `[reload] poll_interval_sec` is set, whenever the
+//! file's modification time changes) the config file is re-read with the
+//! same command line overrides, validated, and diffed against the running
+//! configuration. Changes under the hot-reloadable keys are applied; every
+//! other change (venues, keys, storage paths, ...) is rejected with an alert
+//! and waits for a restart. Components follow the running configuration
+//! through a watch channel, each taking its own slice, so a reload never
+//! leaves a component with half of a change.
+
+use crate::settings::{Config, ConfigError};
+use axiom_oracle::{Alert, AlertSeverity, AlertSink};
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::sync::watch;
+use tracing::{error, info, warn};
+
+/// Keys (and everything below them) applied while running
+const HOT_KEYS: &[&str] = &[
+    "risk.max_leverage",
+    "risk.min_risk_budget",
+    "risk.max_risk_budget",
+    "limits",
+    "allocations",
+    "edge",
+    "proposer",
+    "shadow",
+    "symbols",
+    "alert_thresholds",
+];
+
+/// Keys whose values are never logged
+const SECRET_KEYS: &[&str] = &["live_stream.token", "control.token"];
+
+/// One changed key, dotted from the file's top level
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    pub key: String,
+    pub old: String,
+    pub new: String,
+}
+
+impl Change {
+    /// Whether this change can be applied while running
+    pub fn is_hot(&self) -> bool {
+        HOT_KEYS.iter().any(|hot| self.key == *hot || self.key.starts_with(&format!("{}.", hot)))
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if SECRET_KEYS.contains(&self.key.as_str()) {
+            write!(f, "{}: <redacted>", self.key)
+        } else {
+            write!(f, "{}: {} -> {}", self.key, self.old, self.new)
+        }
+    }
+}
+
+/// What one reload did
+#[derive(Debug, Clone, Default)]
+pub struct ReloadOutcome {
+    pub applied: Vec<Change>,
+    /// Changes that need a restart; the running values were kept
+    pub rejected: Vec<Change>,
+}
+
+/// Owner of the running configuration
+pub struct ConfigReloader {
+    /// None when running on built-in defaults (nothing to re-read)
+    path: Option<PathBuf>,
+    /// Command line overrides, reapplied to every reload
+    adjust: Box<dyn Fn(&mut Config) + Send + Sync>,
+    current: watch::Sender<Arc<Config>>,
+    alert_sink: Option<Arc<dyn AlertSink>>,
+}
+
+impl ConfigReloader {
+    pub fn new(config: Config, path: Option<PathBuf>, adjust: impl Fn(&mut Config) + Send + Sync + 'static) -> Self {
+        let (current, _) = watch::channel(Arc::new(config));
+        Self { path, adjust: Box::new(adjust), current, alert_sink: None }
+    }
+
+    /// Alert on failed reloads and on changes that need a restart
+    pub fn with_alert_sink(mut self, alert_sink: Arc<dyn AlertSink>) -> Self {
+        self.alert_sink = Some(alert_sink);
+        self
+    }
+
+    /// The running configuration
+    pub fn current(&self) -> Arc<Config> {
+        self.current.borrow().clone()
+    }
+
+    /// Follow one slice of the configuration; the receiver only sees a
+    /// change when the slice itself changed
+    pub fn slice<T>(&self, select: impl Fn(&Config) -> T + Send + 'static) -> watch::Receiver<T>
+    where
+        T: PartialEq + Send + Sync + 'static,
+    {
+        let mut config = self.current.subscribe();
+        let initial = config.borrow_and_update().clone();
+        let (tx, rx) = watch::channel(select(&initial));
+        tokio::spawn(async move {
+            while config.changed().await.is_ok() && !tx.is_closed() {
+                let next = config.borrow_and_update().clone();
+                let value = select(&next);
+                if *tx.borrow() != value {
+                    tx.send_replace(value);
+                }
+            }
+        });
+        rx
+    }
+
+    /// Re-read the file and apply the hot-reloadable changes
+    ///
+    /// An unreadable or invalid file changes nothing.
+    pub fn reload(&self) -> Result<ReloadOutcome, ConfigError> {
+        let Some(path) = &self.path else {
+            warn!("Running on built-in defaults; there is no config file to reload");
+            return Ok(ReloadOutcome::default());
+        };
+        let loaded = match Config::load(path, |config| (self.adjust)(config)) {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                error!("Config reload failed, keeping the running configuration: {}", e);
+                self.alert(format!("Config reload failed, keeping the running configuration: {}", e));
+                return Err(e);
+            }
+        };
+
+        let running = self.current();
+        let (applied, rejected): (Vec<Change>, Vec<Change>) = diff(&running, &loaded).into_iter().partition(Change::is_hot);
+
+        for change in &rejected {
+            warn!("Config reload: {} (needs a restart, not applied)", change);
+        }
+        if !rejected.is_empty() {
+            let keys: Vec<&str> = rejected.iter().map(|change| change.key.as_str()).collect();
+            self.alert(format!("{} config changes need a restart and were not applied: {}", rejected.len(), keys.join(", ")));
+        }
+
+        if applied.is_empty() {
+            info!("Config reload: nothing to apply");
+        } else {
+            let mut next = (*running).clone();
+            next.risk.max_leverage = loaded.risk.max_leverage;
+            next.risk.min_risk_budget = loaded.risk.min_risk_budget;
+            next.risk.max_risk_budget = loaded.risk.max_risk_budget;
+            next.limits = loaded.limits;
+            next.proposer = loaded.proposer;
+            next.shadow = loaded.shadow;
+            next.symbols = loaded.symbols;
+            next.alert_thresholds = loaded.alert_thresholds;
+            for change in &applied {
+                info!("Config reload: {}", change);
+            }
+            self.current.send_replace(Arc::new(next));
+        }
+        Ok(ReloadOutcome { applied, rejected })
+    }
+
+    /// Reload on SIGHUP and, if configured, on file change, until shutdown
+    pub async fn listen(self: Arc<Self>, mut shutdown: watch::Receiver<bool>) {
+        let poll = self.current().reload.poll_interval_sec;
+        let mut ticker = tokio::time::interval(Duration::from_secs(poll.max(1)));
+        let mut hangup = Hangup::new();
+        let mut modified = self.modified();
+        loop {
+            tokio::select! {
+                _ = hangup.recv() => info!("SIGHUP received, reloading configuration"),
+                _ = ticker.tick(), if poll > 0 => {
+                    if self.modified() == modified {
+                        continue;
+                    }
+                    info!("Config file changed, reloading configuration");
+                }
+                _ = async { drop(shutdown.wait_for(|stop| *stop).await) } => break,
+            }
+            modified = self.modified();
+            // Failures are logged and alerted inside
+            if let Ok(outcome) = self.reload() {
+                info!("Config reload done: {} changes applied, {} need a restart",
+                    outcome.applied.len(), outcome.rejected.len());
+            }
+        }
+    }
+
+    fn modified(&self) -> Option<SystemTime> {
+        self.path.as_ref().and_then(|path| std::fs::metadata(path).ok()?.modified().ok())
+    }
+
+    fn alert(&self, message: String) {
+        if let Some(sink) = &self.alert_sink {
+            sink.send(&Alert::new(AlertSeverity::Warning, "config_reload", message));
+        }
+    }
+}
+
+/// SIGHUP, where the platform has it
+struct Hangup(#[cfg(unix)] Option<tokio::signal::unix::Signal>);
+
+impl Hangup {
+    fn new() -> Self {
+        #[cfg(unix)]
+        {
+            match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup()) {
+                Ok(signal) => Self(Some(signal)),
+                Err(e) => {
+                    warn!("Cannot listen for SIGHUP: {}", e);
+                    Self(None)
+                }
+            }
+        }
+        #[cfg(not(unix))]
+        Self()
+    }
+
+    /// Wait for the next SIGHUP (forever if there is none)
+    async fn recv(&mut self) {
+        #[cfg(unix)]
+        if let Some(signal) = &mut self.0 {
+            if signal.recv().await.is_some() {
+                return;
+            }
+        }
+        std::future::pending::<()>().await
+    }
+}
+
+/// Every leaf key whose value differs, in key order
+pub fn diff(old: &Config, new: &Config) -> Vec<Change> {
+    let (Ok(old), Ok(new)) = (serde_json::to_value(old), serde_json::to_value(new)) else {
+        return Vec::new();
+    };
+    let mut changes = Vec::new();
+    collect("", &old, &new, &mut changes);
+    changes
+}
+
+fn collect(key: &str, old: &Value, new: &Value, changes: &mut Vec<Change>) {
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            let keys: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+            for name in keys {
+                let child = if key.is_empty() { name.clone() } else { format!("{}.{}", key, name) };
+                collect(&child, old.get(name).unwrap_or(&Value::Null), new.get(name).unwrap_or(&Value::Null), changes);
+            }
+        }
+        _ if old == new => {}
+        _ => changes.push(Change { key: key.to_string(), old: show(old), new: show(new) }),
+    }
+}
+
+fn show(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Null => "(unset)".to_string(),
+        other => other.to_string(),
+    }
+}
@@ -0,0 +1,320 @@
+//! Reproducibility: Bitwise Determinism over a Recording
+//!
+//! Runs the paper pipeline's decisions over a recording's books (features,
+//! proposals, verification outcomes, C=0 signatures, simulated fills) twice
+//! from the same configuration and seed, hashing the ordered trace of each
+//! run, and reports the first event and field where the two part ways. A
+//! stored baseline extends the comparison across builds, so a refactor that
+//! lets HashMap order or the wall clock into a decision shows up as a
+//! divergence.
+//!
+//! Wall-clock time cannot enter the trace: events carry book times and
+//! indices, signal generator events are taken without their envelope
+//! (emission time) or order hash, and orders are signed at their book's
+//! time with a key derived from `system.seed`.
+
+use crate::backtest::{self, Recording};
+use crate::settings::Config;
+use anyhow::Context;
+use axiom_core::{
+    AccountId, Amount, CZeroSignature, EventListener, EventLog, FeatureVector, Price, Quantity, Side, Symbol,
+};
+use axiom_risk::PortfolioManager;
+use ed25519_dalek::SigningKey;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha3::{Digest, Sha3_256};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// One step of a run, in the order it happened
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum TraceEvent {
+    /// The book's features before the generator saw it
+    Features { book: usize, symbol: Symbol, features: Option<FeatureVector> },
+    /// A signal generator event (proposal, verification, rejection)
+    Decision { book: usize, event_type: String, signal_id: Option<String>, payload: Value },
+    Signed { book: usize, order_hash: String, signature: String },
+    /// A simulated fill and the equity it left the portfolio with
+    Filled {
+        book: usize,
+        account: AccountId,
+        symbol: Symbol,
+        side: Side,
+        quantity: Quantity,
+        price: Price,
+        equity: Amount,
+    },
+}
+
+/// The hashed trace of one run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Trace {
+    /// Chained SHA3-256 over every event, hex
+    pub hash: String,
+    pub events: Vec<Value>,
+}
+
+impl Trace {
+    fn new() -> Self {
+        Self { hash: hex::encode([0u8; 32]), events: Vec::new() }
+    }
+
+    fn push(&mut self, event: &TraceEvent) -> anyhow::Result<()> {
+        let event = serde_json::to_value(event)?;
+        let mut hasher = Sha3_256::new();
+        hasher.update(self.hash.as_bytes());
+        hasher.update(serde_json::to_vec(&event)?);
+        self.hash = hex::encode(hasher.finalize());
+        self.events.push(event);
+        Ok(())
+    }
+}
+
+/// Where two traces first differ
+#[derive(Debug, Clone)]
+pub struct Divergence {
+    /// Index of the first differing event
+    pub index: usize,
+    /// That event's type and book, from the expected trace
+    pub event: String,
+    /// Path of the first differing field ("" for the whole event)
+    pub field: String,
+    pub expected: String,
+    pub actual: String,
+}
+
+impl std::fmt::Display for Divergence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "event {} ({})", self.index, self.event)?;
+        if !self.field.is_empty() {
+            write!(f, " field {}", self.field)?;
+        }
+        write!(f, ": expected {}, got {}", self.expected, self.actual)
+    }
+}
+
+/// Outcome of `reproduce`
+#[derive(Debug, Clone)]
+pub struct Reproduction {
+    pub trace: Trace,
+    /// First divergence between the two runs
+    pub between_runs: Option<Divergence>,
+    /// First divergence from the stored baseline, when one was compared
+    pub from_baseline: Option<Divergence>,
+}
+
+/// Run `recording` twice and compare the traces with each other and, when
+/// given, with the baseline at `baseline` (written instead when `update`)
+pub fn reproduce(config: &Config, recording: &Recording, baseline: Option<&Path>, update: bool) -> anyhow::Result<Reproduction> {
+    let first = run(config, recording)?;
+    let second = run(config, recording)?;
+    let between_runs = divergence(&first, &second);
+
+    let mut from_baseline = None;
+    if let Some(path) = baseline {
+        if update {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(path, serde_json::to_string_pretty(&first)?)
+                .with_context(|| format!("Failed to write baseline {}", path.display()))?;
+        } else {
+            let stored = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read baseline {}", path.display()))?;
+            let stored: Trace = serde_json::from_str(&stored)
+                .with_context(|| format!("{}: not a reproducibility baseline", path.display()))?;
+            from_baseline = divergence(&stored, &first);
+        }
+    }
+    Ok(Reproduction { trace: first, between_runs, from_baseline })
+}
+
+/// One paper run over `recording`'s books, as the pipeline would decide it
+///
+/// Books are routed and marked like the signal driver does, and every
+/// verified order fills at once at its limit price (or the mid), as paper
+/// fills do.
+///
+/// The recording is read afresh, so a capture's books are rebuilt from its
+/// snapshots each time.
+fn run(config: &Config, recording: &Recording) -> anyhow::Result<Trace> {
+    let source = recording.open(&config.symbol_mapper())?;
+    let books: Vec<_> = source.books().collect();
+    let decisions = Decisions::default();
+    let event_log = EventLog::from_writer(Box::new(std::io::sink())).with_listener(decisions.clone());
+    let mut generator = backtest::generator(config).with_event_log(Arc::new(event_log));
+    let signing_key = signing_key(config.system.seed);
+    let validity = config.key_settings().signature_validity;
+    let mut manager = PortfolioManager::for_accounts(config.accounts());
+    let fees = config.fee_model();
+    let symbols = config.symbols();
+    let routes = config.account_routes();
+
+    let mut trace = Trace::new();
+    let mut nonce = 0;
+    for (idx, book) in books.into_iter().enumerate() {
+        if !symbols.contains(&book.symbol) {
+            continue;
+        }
+        let account = match routes.get(&book.symbol) {
+            Some(account) => account.clone(),
+            None if routes.is_empty() => AccountId::default(),
+            None => continue,
+        };
+        let Some(mid) = axiom_data::normalization::calculate_mid_price(book) else { continue };
+        if manager.has_position(&book.symbol) {
+            manager.update_prices(&HashMap::from([(book.symbol.clone(), mid)]))?;
+        }
+
+        trace.push(&TraceEvent::Features {
+            book: idx,
+            symbol: book.symbol.clone(),
+            features: generator.feature_vector(book),
+        })?;
+        let aggregate = manager.portfolio().clone();
+        let order = match manager.is_multi_account().then(|| manager.account_portfolio(&account)).flatten() {
+            Some(portfolio) => generator.generate_account_signal(
+                &account, &book.symbol, &book.venue, book, &portfolio, Some(&aggregate),
+            ),
+            None => generator.generate_account_signal(&account, &book.symbol, &book.venue, book, &aggregate, None),
+        };
+        for (event_type, signal_id, payload) in decisions.take() {
+            trace.push(&TraceEvent::Decision { book: idx, event_type, signal_id, payload })?;
+        }
+        let Some(mut order) = order else { continue };
+
+        // Verification and signing time pinned to the book's
+        order.verified_at = book.timestamp;
+        nonce += 1;
+        let signature = CZeroSignature::sign(&order, &signing_key, nonce, book.timestamp + validity);
+        trace.push(&TraceEvent::Signed {
+            book: idx,
+            order_hash: signature.order_hash.clone(),
+            signature: hex::encode(&signature.signature),
+        })?;
+
+        let signal = &order.signal;
+        let price = signal.limit_price.unwrap_or(mid);
+        let fee = fees.fill_fee(&signal.venue, &signal.order_type, signal.quantity, price)?;
+        manager.update_position(&signal.account, signal.symbol.clone(), signal.venue.clone(), signal.side, signal.quantity, price, fee)?;
+        manager.attribute_fill(&signal.strategy, &signal.symbol, signal.side, signal.quantity)?;
+        trace.push(&TraceEvent::Filled {
+            book: idx,
+            account: signal.account.clone(),
+            symbol: signal.symbol.clone(),
+            side: signal.side,
+            quantity: signal.quantity,
+            price,
+            equity: manager.portfolio().equity,
+        })?;
+    }
+    Ok(trace)
+}
+
+/// Signing key for reproducibility runs, the same for the same seed
+fn signing_key(seed: u64) -> SigningKey {
+    let secret: [u8; 32] = Sha3_256::digest(format!("axiom-reproduce:{}", seed)).into();
+    SigningKey::from_bytes(&secret)
+}
+
+/// A signal generator event: type, signal id, payload
+type GeneratorEvent = (String, Option<String>, Value);
+
+/// Signal generator events since the last `take`
+#[derive(Clone, Default)]
+struct Decisions(Arc<Mutex<Vec<GeneratorEvent>>>);
+
+impl Decisions {
+    fn take(&self) -> Vec<GeneratorEvent> {
+        std::mem::take(&mut *self.0.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+}
+
+impl EventListener for Decisions {
+    fn on_event(&self, event_type: &str, line: &str) {
+        let Ok(mut envelope) = serde_json::from_str::<Value>(line) else { return };
+        let signal_id = envelope.get("signal_id").and_then(Value::as_str).map(String::from);
+        let payload = envelope.get_mut("payload").map(Value::take).unwrap_or_default();
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+            .push((event_type.to_string(), signal_id, payload));
+    }
+}
+
+/// First difference between `expected` and `actual`, if their hashes differ
+fn divergence(expected: &Trace, actual: &Trace) -> Option<Divergence> {
+    if expected.hash == actual.hash {
+        return None;
+    }
+    let describe = |event: &Value| format!(
+        "{} at book {}",
+        event.get("event").and_then(Value::as_str).unwrap_or("?"),
+        event.get("book").map_or_else(|| "?".to_string(), Value::to_string),
+    );
+    let index = expected.events.iter().zip(&actual.events)
+        .position(|(a, b)| a != b)
+        .unwrap_or(expected.events.len().min(actual.events.len()));
+    Some(match (expected.events.get(index), actual.events.get(index)) {
+        (Some(a), Some(b)) => {
+            let (field, a_value, b_value) = differing_field(a, b, String::new())
+                .unwrap_or_else(|| (String::new(), a.to_string(), b.to_string()));
+            Divergence { index, event: describe(a), field, expected: a_value, actual: b_value }
+        }
+        (Some(a), None) => Divergence {
+            index, event: describe(a), field: String::new(), expected: a.to_string(), actual: "end of trace".to_string(),
+        },
+        (None, Some(b)) => Divergence {
+            index, event: describe(b), field: String::new(), expected: "end of trace".to_string(), actual: b.to_string(),
+        },
+        // Same events under different hashes: the baseline was edited
+        (None, None) => Divergence {
+            index, event: "trace hash".to_string(), field: String::new(),
+            expected: expected.hash.clone(), actual: actual.hash.clone(),
+        },
+    })
+}
+
+/// Path and values of the first field where `a` and `b` differ
+fn differing_field(a: &Value, b: &Value, path: String) -> Option<(String, String, String)> {
+    if a == b {
+        return None;
+    }
+    let join = |key: &str| if path.is_empty() { key.to_string() } else { format!("{}.{}", path, key) };
+    match (a, b) {
+        (Value::Object(a_fields), Value::Object(b_fields)) => {
+            let keys = a_fields.keys().chain(b_fields.keys().filter(|key| !a_fields.contains_key(*key)));
+            for key in keys {
+                match (a_fields.get(key), b_fields.get(key)) {
+                    (Some(a), Some(b)) => {
+                        if let Some(found) = differing_field(a, b, join(key)) {
+                            return Some(found);
+                        }
+                    }
+                    (a, b) => return Some((join(key), show(a), show(b))),
+                }
+            }
+            None
+        }
+        (Value::Array(a_items), Value::Array(b_items)) => {
+            for idx in 0..a_items.len().max(b_items.len()) {
+                let path = format!("{}[{}]", path, idx);
+                match (a_items.get(idx), b_items.get(idx)) {
+                    (Some(a), Some(b)) => {
+                        if let Some(found) = differing_field(a, b, path) {
+                            return Some(found);
+                        }
+                    }
+                    (a, b) => return Some((path, show(a), show(b))),
+                }
+            }
+            None
+        }
+        _ => Some((path, a.to_string(), b.to_string())),
+    }
+}
+
+fn show(value: Option<&Value>) -> String {
+    value.map_or_else(|| "absent".to_string(), Value::to_string)
+}
@@ -0,0 +1,1160 @@
+//! Runtime: The Long-Running Tasks of the Trading Pipeline
+//!
+//! `pipeline` builds the components; these tasks move data between them:
+//!
+//! - books -> `SignalDriver` (marks positions) -> a signal worker per symbol
+//!   or shard (generates verified orders) -> `SignalDriver` (signs each order
+//!   as it is released) -> bounded order queue -> `ExecutionTask` (risk gate, safety checks,
+//!   depth of the order's book net of our resting orders, submission; in
+//!   paper mode the fill is routed straight into the portfolio)
+//! - ticks -> `follow_ticks` (emulated stops)
+//! - funding and open interest -> `follow_derivatives` (latest funding per
+//!   symbol and venue, handed to the signal workers with each book; paid
+//!   intervals settled on the portfolio)
+//! - liquidations -> `follow_liquidations` (notional liquidated per symbol
+//!   over a rolling window, handed to the signal workers and the breaker)
+//! - venue errors -> `VenueRemediation` (alerts, halted symbols, reconciliation)
+//! - `HealthTask` every `[monitoring] health_check_interval_sec`: circuit
+//!   breaker, health snapshot, telemetry and alerts
+//!
+//! Every task runs under the `Supervisor` until shutdown. A task that ends
+//! early, by returning or by panicking, closes the risk gate and starts the
+//! shutdown sequence; the process then exits as an incomplete shutdown.
+
+use axiom_core::{
+    AccountId, CircuitBreakerState, DerivativesUpdate, ErrorCode, FundingRate, MarketRegime, OrderBook, OrderStatus, Portfolio,
+    Price, StrategyId, Symbol, Tick, Venue, VerifiedOrder, LiquidationEvent, LiquidationIntensity,
+};
+use rust_decimal::Decimal;
+use axiom_data::{DataIngestionManager, FeedReceiver, FeedSender, ReplaySource};
+use axiom_engine::{DepthWindow, FeatureCalculator, OrderSigner, SignalGenerator, MINUTES_PER_YEAR};
+use axiom_core::ShadowObservation;
+use axiom_execution::{ExchangeClient, ExchangeError, OrderExecutor, RemediationHooks};
+use axiom_oracle::{Alert, AlertManager, AlertSeverity, AlertSink, StrategyAllocation, SystemMonitor, TelemetryCollector};
+use axiom_risk::{AccountBreakers, FeeModel, PortfolioManager, ReconciliationAction, Reconciler, RiskGate};
+use chrono::{DateTime, TimeDelta, Utc};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::future::Future;
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+use std::time::Duration;
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinSet;
+use tracing::{debug, error, info, warn};
+
+/// Verified orders waiting for the executor; when full, new orders are
+/// dropped rather than delaying the book path
+pub const ORDER_QUEUE: usize = 256;
+
+/// A verified order, the mid it was verified against and the book that
+/// mid came from
+#[derive(Debug, Clone)]
+pub struct Submission {
+    pub order: VerifiedOrder,
+    pub reference: Price,
+    /// Handed to the executor for its liquidity check
+    pub book: Arc<OrderBook>,
+}
+
+/// What the signal driver last saw, for the health snapshot
+#[derive(Debug, Clone, Copy)]
+pub struct EngineStats {
+    /// Contradiction score of the last verified order
+    pub consistency_error: Decimal,
+    /// Entropy count of the last verified order
+    pub entropy_count: Decimal,
+    pub regime: MarketRegime,
+    pub hallucination_rate: Decimal,
+}
+
+impl Default for EngineStats {
+    fn default() -> Self {
+        Self {
+            consistency_error: Decimal::ZERO,
+            entropy_count: Decimal::ZERO,
+            regime: MarketRegime::Normal,
+            hallucination_rate: Decimal::ZERO,
+        }
+    }
+}
+
+/// A copy of the current portfolio (a poisoned lock still holds usable state)
+pub fn portfolio_snapshot(portfolio: &RwLock<PortfolioManager>) -> Portfolio {
+    portfolio.read().unwrap_or_else(PoisonError::into_inner).portfolio().clone()
+}
+
+/// Runs the pipeline tasks and notices when one stops early
+pub struct Supervisor {
+    tasks: JoinSet<(&'static str, Option<String>)>,
+    shutdown: watch::Receiver<bool>,
+}
+
+impl Supervisor {
+    pub fn new(shutdown: watch::Receiver<bool>) -> Self {
+        Self { tasks: JoinSet::new(), shutdown }
+    }
+
+    /// Run `task` as `name`; it is expected to run until shutdown
+    pub fn spawn(&mut self, name: &'static str, task: impl Future<Output = ()> + Send + 'static) {
+        let inner = tokio::spawn(task);
+        self.tasks.spawn(async move {
+            match inner.await {
+                Ok(()) => (name, None),
+                Err(e) => (name, Some(e.to_string())),
+            }
+        });
+    }
+
+    /// Wait for the first task to stop before shutdown, and say why
+    ///
+    /// Pending forever while every task is healthy.
+    pub async fn failure(&mut self) -> String {
+        while let Some(joined) = self.tasks.join_next().await {
+            if *self.shutdown.borrow() {
+                continue;
+            }
+            return match joined {
+                Ok((name, None)) => format!("task {} stopped unexpectedly", name),
+                Ok((name, Some(panic))) => format!("task {} failed: {}", name, panic),
+                Err(e) => format!("supervised task failed: {}", e),
+            };
+        }
+        std::future::pending().await
+    }
+
+    /// Wait (up to `deadline`) for every task to wind down after shutdown
+    pub async fn join(mut self, deadline: Duration) {
+        let finished = tokio::time::timeout(deadline, async {
+            while self.tasks.join_next().await.is_some() {}
+        })
+        .await;
+        if finished.is_err() {
+            warn!("{} pipeline tasks still running after {:?}; aborting them", self.tasks.len(), deadline);
+            self.tasks.abort_all();
+        }
+    }
+}
+
+/// Close the gate and raise a critical alert for a supervision failure
+pub fn on_failure(reason: &str, risk_gate: &RiskGate, alert_sink: &dyn AlertSink) {
+    error!("Pipeline failure: {}", reason);
+    risk_gate.close(reason.to_string());
+    alert_sink.send(&Alert::new(AlertSeverity::Critical, "supervisor", format!("{}; shutting down", reason)));
+}
+
+/// Follows up the executor's venue-error remediations
+pub struct VenueRemediation {
+    pub alert_sink: Arc<dyn AlertSink>,
+    pub data_manager: Arc<DataIngestionManager>,
+    pub portfolio: Arc<RwLock<PortfolioManager>>,
+    /// Symbols a venue reported halted; the signal driver skips their books
+    pub halted: watch::Sender<BTreeSet<(Symbol, Venue)>>,
+    /// Client asked for the venue's positions when reconciling
+    pub client: Option<Arc<dyn ExchangeClient>>,
+    pub reconciler: Reconciler,
+    /// Tripped for an account whose books fail reconciliation under the
+    /// halt policy
+    pub breakers: Arc<Mutex<AccountBreakers>>,
+}
+
+impl VenueRemediation {
+    /// Recompute the tracked books, so at least our own drift shows up
+    /// next to the rejection
+    fn check_consistency(&self) -> String {
+        let drift = self.portfolio.write().unwrap_or_else(PoisonError::into_inner).check_consistency();
+        match drift {
+            Ok(drift) if drift.is_empty() => "tracked portfolio consistent".to_string(),
+            Ok(drift) => drift.iter()
+                .map(|d| format!("{} tracked {} recomputed {}", d.metric, d.tracked, d.recomputed))
+                .collect::<Vec<_>>()
+                .join("; "),
+            Err(e) => format!("recompute failed: {}", e),
+        }
+    }
+}
+
+impl RemediationHooks for VenueRemediation {
+    fn venue_paused(&self, venue: &Venue, error: &ExchangeError) {
+        self.alert_sink.send(&Alert::for_error(AlertSeverity::Critical, "venue", error)
+            .with_context("venue", venue)
+            .with_context("action", "venue paused; ctl resume-venue once the credentials are fixed"));
+    }
+
+    fn symbol_halted(&self, symbol: &Symbol, venue: &Venue, error: &ExchangeError) {
+        let added = self.halted.send_if_modified(|halted| halted.insert((symbol.clone(), venue.clone())));
+        if !added {
+            return;
+        }
+        self.alert_sink.send(&Alert::for_error(AlertSeverity::Warning, "venue", error)
+            .with_context("venue", venue)
+            .with_context("symbol", &symbol.0)
+            .with_context("action", "symbol unsubscribed; no new signals until restart"));
+        let data_manager = self.data_manager.clone();
+        let (symbol, venue) = (symbol.clone(), venue.clone());
+        tokio::spawn(async move {
+            if let Err(e) = data_manager.stop_ingestion(venue.clone(), vec![symbol.clone()]).await {
+                warn!("Failed to unsubscribe halted {} on {}: {}", symbol.0, venue, e);
+            }
+        });
+    }
+
+    fn reconcile(&self, account: &AccountId, venue: &Venue, error: &ExchangeError) {
+        let alert = Alert::for_error(AlertSeverity::Warning, "venue", error)
+            .with_context("venue", venue)
+            .with_context("account", account);
+        let Some(client) = self.client.clone() else {
+            self.alert_sink.send(&alert.with_context("reconciliation", self.check_consistency()));
+            return;
+        };
+
+        // The venue's report is fetched off the submitting task
+        let (portfolio, breakers, alert_sink) = (self.portfolio.clone(), self.breakers.clone(), self.alert_sink.clone());
+        let reconciler = self.reconciler;
+        let (account, venue) = (account.clone(), venue.clone());
+        let consistency = self.check_consistency();
+        tokio::spawn(async move {
+            let report = match client.positions(&account, &venue).await {
+                Ok(report) => report,
+                Err(e) => {
+                    debug!("No position report for {} on {} [{}]: {}", account, venue, e.code(), e);
+                    alert_sink.send(&alert.with_context("reconciliation", consistency));
+                    return;
+                }
+            };
+            let reconciled = {
+                let mut breakers = breakers.lock().unwrap_or_else(PoisonError::into_inner);
+                let mut manager = portfolio.write().unwrap_or_else(PoisonError::into_inner);
+                reconciler.reconcile(&mut manager, &mut breakers, &report)
+            };
+            let (severity, outcome, action) = match reconciled {
+                Ok(result) => {
+                    let (severity, action) = match result.action {
+                        ReconciliationAction::None => (AlertSeverity::Warning, "none"),
+                        ReconciliationAction::Logged => (AlertSeverity::Warning, "logged only"),
+                        ReconciliationAction::Adopted => (AlertSeverity::Warning, "venue positions and balance adopted"),
+                        ReconciliationAction::Halted => (
+                            AlertSeverity::Critical, "account halted; reset the breaker once the books are fixed",
+                        ),
+                    };
+                    (severity, result.summary(), action)
+                }
+                Err(e) => (AlertSeverity::Warning, format!("diff failed: {}", e), "none"),
+            };
+            let mut alert = alert.with_context("reconciliation", outcome).with_context("action", action);
+            alert.severity = severity;
+            alert_sink.send(&alert);
+        });
+    }
+}
+
+/// Builds a signal generator for one worker
+pub type GeneratorFactory = Arc<dyn Fn() -> SignalGenerator + Send + Sync>;
+
+/// One book for a signal worker
+struct Job {
+    /// Position in the driver's arrival order; the merge order of the output
+    seq: u64,
+    book: Arc<OrderBook>,
+    mid: Price,
+    /// Account the book's signals are placed for
+    account: AccountId,
+    /// Portfolio (every account together) as of dispatch
+    portfolio: Arc<Portfolio>,
+    /// The account's own portfolio, when there are several accounts
+    account_portfolio: Option<Arc<Portfolio>>,
+    /// The symbol's latest funding on the book's venue
+    funding: Option<FundingRate>,
+    /// The symbol's liquidations over the window, across venues
+    liquidations: LiquidationIntensity,
+}
+
+/// A worker's result for one book (every book yields exactly one)
+struct Outcome {
+    seq: u64,
+    worker: usize,
+    submission: Option<Submission>,
+    shadow: Vec<ShadowObservation>,
+    /// Verifier rejections and proposals of the worker's generator so far
+    counts: (u64, u64),
+    regime: MarketRegime,
+}
+
+/// Everything the signal driver reads and writes
+///
+/// The driver marks positions and hands each book to the worker for its
+/// symbol (or shard of symbols). Each worker owns a generator from `build`
+/// (proposer and feature state, verifier) and runs it off the async
+/// threads, or awaits its proofs on the verification pool, so a slow proof
+/// for one symbol does not hold up the others.
+/// Workers share only the regime detector, the logs and recorders (all
+/// synchronized) and read the portfolio from a watch channel published
+/// per book. Verified orders come back unsigned and are signed here, in the
+/// order they go to the single order queue, so their nonces rise in the
+/// order the executor sees them.
+pub struct SignalDriver {
+    pub build: GeneratorFactory,
+    /// 0: one worker per symbol; otherwise symbols are hashed onto this many
+    pub shards: usize,
+    /// Release orders in book arrival order (journal replay), holding back
+    /// a fast worker's order until earlier books are decided; otherwise each
+    /// order is queued as soon as it is verified
+    pub ordered: bool,
+    pub portfolio: Arc<RwLock<PortfolioManager>>,
+    /// Enabled symbols; books for any other symbol are ignored
+    pub symbols: watch::Receiver<Vec<Symbol>>,
+    /// Account each symbol trades for; empty sends everything to the default
+    /// account, otherwise books for an unassigned symbol are ignored
+    pub routes: HashMap<Symbol, AccountId>,
+    /// Share of equity per strategy ([allocations]), for telemetry
+    pub allocations: watch::Receiver<BTreeMap<String, Decimal>>,
+    /// Strategies exempt from the expected-edge check ([edge] opt_out), for telemetry
+    pub edge_opt_out: watch::Receiver<BTreeSet<String>>,
+    /// Symbols halted on a venue (see `VenueRemediation`); their books are ignored
+    pub halted: watch::Receiver<BTreeSet<(Symbol, Venue)>>,
+    /// Latest funding by symbol and venue (see `follow_derivatives`)
+    pub funding: watch::Receiver<FundingRates>,
+    /// Liquidation intensity by symbol (see `follow_liquidations`)
+    pub liquidations: watch::Receiver<LiquidationIntensities>,
+    /// Signs each order as it is queued; the workers' generators have no keys
+    pub signer: OrderSigner,
+    pub orders: mpsc::Sender<Submission>,
+    pub stats: watch::Sender<EngineStats>,
+    pub telemetry: Arc<TelemetryCollector>,
+}
+
+impl SignalDriver {
+    /// Mark positions and dispatch books to the signal workers until
+    /// shutdown; stops early if a worker fails
+    pub async fn run(self, mut books: FeedReceiver<Arc<OrderBook>>, mut shutdown: watch::Receiver<bool>) {
+        let (view, _) = watch::channel(Arc::new(portfolio_snapshot(&self.portfolio)));
+        let (outcome_tx, mut outcomes) = mpsc::unbounded_channel();
+        let mut workers: Vec<mpsc::UnboundedSender<Job>> = Vec::new();
+        let mut assigned: HashMap<Symbol, usize> = HashMap::new();
+        let mut running = JoinSet::new();
+        let mut counts: BTreeMap<usize, (u64, u64)> = BTreeMap::new();
+        let mut pending: BTreeMap<u64, Option<Submission>> = BTreeMap::new();
+        let mut next_seq = 0;
+        let mut released = 0;
+
+        loop {
+            tokio::select! {
+                book = books.recv() => {
+                    let Some(book) = book else { break };
+                    if !self.symbols.borrow().contains(&book.symbol) {
+                        continue;
+                    }
+                    if self.halted.borrow().contains(&(book.symbol.clone(), book.venue.clone())) {
+                        continue;
+                    }
+                    let account = match self.routes.get(&book.symbol) {
+                        Some(account) => account.clone(),
+                        None if self.routes.is_empty() => AccountId::default(),
+                        None => {
+                            debug!("{} is not assigned to an account; skipped", book.symbol.0);
+                            continue;
+                        }
+                    };
+                    let Some(mid) = axiom_data::normalization::calculate_mid_price(&book) else {
+                        debug!("{} book on {} has no mid; skipped", book.symbol.0, book.venue);
+                        continue;
+                    };
+                    self.mark(&book.symbol, mid).await;
+                    view.send_replace(Arc::new(portfolio_snapshot(&self.portfolio)));
+
+                    let worker = *assigned.entry(book.symbol.clone()).or_insert_with(|| match self.shards {
+                        0 => workers.len(),
+                        shards => shard(&book.symbol, shards),
+                    });
+                    while workers.len() <= worker {
+                        let (job_tx, job_rx) = mpsc::unbounded_channel();
+                        running.spawn(signal_worker(workers.len(), (self.build)(), job_rx, outcome_tx.clone()));
+                        workers.push(job_tx);
+                    }
+                    let account_portfolio = self.account_portfolio(&account);
+                    let funding = self.funding.borrow().get(&(book.symbol.clone(), book.venue.clone())).cloned();
+                    let liquidations = self.liquidations.borrow().get(&book.symbol).copied().unwrap_or_default();
+                    let job = Job {
+                        seq: next_seq,
+                        book,
+                        mid,
+                        account,
+                        portfolio: view.borrow().clone(),
+                        account_portfolio,
+                        funding,
+                        liquidations,
+                    };
+                    next_seq += 1;
+                    if workers[worker].send(job).is_err() {
+                        break;
+                    }
+                }
+                outcome = outcomes.recv() => {
+                    let Some(outcome) = outcome else { break };
+                    self.telemetry.record_shadow(&outcome.shadow).await;
+                    counts.insert(outcome.worker, outcome.counts);
+                    self.publish_stats(&outcome, &counts);
+                    if self.ordered {
+                        // Queue everything decided up to the first book still in a worker
+                        pending.insert(outcome.seq, outcome.submission);
+                        while let Some(decided) = pending.remove(&released) {
+                            released += 1;
+                            if let Some(submission) = decided {
+                                if !self.submit(submission).await {
+                                    return;
+                                }
+                            }
+                        }
+                    } else if let Some(submission) = outcome.submission {
+                        if !self.submit(submission).await {
+                            return;
+                        }
+                    }
+                }
+                stopped = running.join_next(), if !running.is_empty() => {
+                    match stopped {
+                        Some(Err(e)) => error!("Signal worker failed: {}", e),
+                        _ => error!("Signal worker stopped unexpectedly"),
+                    }
+                    return;
+                }
+                _ = async { drop(shutdown.wait_for(|stop| *stop).await) } => break,
+            }
+        }
+    }
+
+    /// Sign a verified order and queue it for the executor; false once the
+    /// queue is closed
+    async fn submit(&self, mut submission: Submission) -> bool {
+        self.telemetry.record_verification().await;
+        self.signer.sign(&mut submission.order);
+        match self.orders.try_send(submission) {
+            Ok(()) => true,
+            Err(mpsc::error::TrySendError::Full(submission)) => {
+                warn!("Order queue full; dropped verified order for {}", submission.order.signal.symbol.0);
+                true
+            }
+            Err(mpsc::error::TrySendError::Closed(_)) => false,
+        }
+    }
+
+    /// Fold a worker's result into the engine stats
+    fn publish_stats(&self, outcome: &Outcome, counts: &BTreeMap<usize, (u64, u64)>) {
+        let (rejected, proposed) = counts.values()
+            .fold((0, 0), |(rejected, proposed), (r, p)| (rejected + r, proposed + p));
+        let signal = outcome.submission.as_ref().map(|submission| &submission.order.signal);
+        self.stats.send_modify(|stats| {
+            if let Some(signal) = signal {
+                stats.consistency_error = signal.contradiction_score;
+                stats.entropy_count = signal.entropy_count;
+            }
+            stats.regime = outcome.regime;
+            stats.hallucination_rate = if proposed == 0 {
+                Decimal::ZERO
+            } else {
+                Decimal::from(rejected) / Decimal::from(proposed)
+            };
+        });
+    }
+
+    /// `account`'s own portfolio when there are several accounts
+    fn account_portfolio(&self, account: &AccountId) -> Option<Arc<Portfolio>> {
+        let manager = self.portfolio.read().unwrap_or_else(PoisonError::into_inner);
+        if !manager.is_multi_account() {
+            return None;
+        }
+        manager.account_portfolio(account).map(Arc::new)
+    }
+
+    /// Mark open positions to the latest mid
+    async fn mark(&self, symbol: &Symbol, mid: Price) {
+        let marked = {
+            let mut manager = self.portfolio.write().unwrap_or_else(PoisonError::into_inner);
+            if !manager.has_position(symbol) {
+                // Still a return for the correlation matrix
+                if let Err(e) = manager.observe_mark(symbol, mid) {
+                    warn!("{} mark not added to the correlation returns: {}", symbol.0, e);
+                }
+                return;
+            }
+            manager.update_prices(&HashMap::from([(symbol.clone(), mid)])).map(|()| {
+                // With a single account its equity is the total
+                let accounts: BTreeMap<String, Decimal> = match manager.is_multi_account() {
+                    true => manager.accounts()
+                        .filter_map(|account| Some((account.0.clone(), manager.account_equity(account)?.get())))
+                        .collect(),
+                    false => BTreeMap::new(),
+                };
+                (manager.portfolio().clone(), accounts)
+            })
+        };
+        match marked {
+            Ok((portfolio, accounts)) => {
+                let mut unrealized: BTreeMap<String, Decimal> = BTreeMap::new();
+                for position in &portfolio.positions {
+                    *unrealized.entry(position.symbol.0.clone()).or_default() += position.unrealized_pnl.get();
+                }
+                self.telemetry.record_mark(portfolio.equity.get(), unrealized, accounts).await;
+                let allocations = self.allocations.borrow().clone();
+                let edge_opt_out = self.edge_opt_out.borrow().clone();
+                self.telemetry.record_allocations(strategy_allocations(&portfolio, &allocations, &edge_opt_out)).await;
+            }
+            Err(e) => error!("Failed to mark {} at {}: {}", symbol.0, mid, e),
+        }
+    }
+}
+
+/// Each strategy that holds exposure, has an allocation or skips the edge
+/// check, with its budget at the portfolio's current equity
+fn strategy_allocations(
+    portfolio: &Portfolio,
+    allocations: &BTreeMap<String, Decimal>,
+    edge_opt_out: &BTreeSet<String>,
+) -> BTreeMap<String, StrategyAllocation> {
+    let names: BTreeSet<&String> = portfolio.strategies.keys().map(|strategy| &strategy.0)
+        .chain(allocations.keys())
+        .chain(edge_opt_out)
+        .collect();
+    names.into_iter()
+        .map(|name| {
+            let exposure = portfolio.strategies.get(&StrategyId(name.clone()))
+                .map_or(Decimal::ZERO, |held| held.exposure.get());
+            let allocation = allocations.get(name).copied();
+            let budget = allocation.map(|fraction| fraction * portfolio.equity.get().max(Decimal::ZERO));
+            let edge_opt_out = edge_opt_out.contains(name);
+            (name.clone(), StrategyAllocation { exposure, allocation, budget, edge_opt_out })
+        })
+        .collect()
+}
+
+/// Generate signals for the books of one symbol (or shard) in order
+///
+/// With a verification pool, proofs run on the pool's threads while the
+/// worker awaits them; otherwise the whole generation runs off the async
+/// threads, proving included.
+async fn signal_worker(
+    worker: usize,
+    mut generator: SignalGenerator,
+    mut jobs: mpsc::UnboundedReceiver<Job>,
+    outcomes: mpsc::UnboundedSender<Outcome>,
+) {
+    let pooled = generator.verifies_on_pool();
+    while let Some(job) = jobs.recv().await {
+        let outcome;
+        if pooled {
+            prepare(&mut generator, &job);
+            let portfolio = job.account_portfolio.as_deref().unwrap_or(&*job.portfolio);
+            let aggregate = job.account_portfolio.is_some().then_some(&*job.portfolio);
+            let order = generator
+                .generate_account_signal_async(&job.account, &job.book.symbol, &job.book.venue, &job.book, portfolio, aggregate)
+                .await
+                // A backlog drops the book's signal; the generator reported it
+                .unwrap_or_else(|e| {
+                    warn!("{} signal dropped [{}]: {}", job.book.symbol.0, e.code(), e);
+                    None
+                });
+            outcome = conclude(&mut generator, job, worker, order);
+        } else {
+            // Proving is CPU-bound; run it off the async threads
+            let decided = tokio::task::spawn_blocking(move || {
+                prepare(&mut generator, &job);
+                let order = match &job.account_portfolio {
+                    Some(account_portfolio) => generator.generate_account_signal(
+                        &job.account, &job.book.symbol, &job.book.venue, &job.book, account_portfolio, Some(&job.portfolio),
+                    ),
+                    None => generator.generate_account_signal(
+                        &job.account, &job.book.symbol, &job.book.venue, &job.book, &job.portfolio, None,
+                    ),
+                };
+                let outcome = conclude(&mut generator, job, worker, order);
+                (generator, outcome)
+            })
+            .await;
+            (generator, outcome) = match decided {
+                Ok(decided) => decided,
+                // A panic in the generator stops the worker; the driver notices
+                Err(e) => std::panic::resume_unwind(e.into_panic()),
+            };
+        }
+        if outcomes.send(outcome).is_err() {
+            return;
+        }
+    }
+}
+
+/// Hand `job`'s funding and liquidations to the generator before its book
+fn prepare(generator: &mut SignalGenerator, job: &Job) {
+    if let Some(funding) = &job.funding {
+        generator.record_funding(funding);
+    }
+    generator.record_liquidation_intensity(&job.book.symbol, job.liquidations);
+}
+
+/// The worker's result for `job`, which produced `order`
+fn conclude(generator: &mut SignalGenerator, job: Job, worker: usize, order: Option<VerifiedOrder>) -> Outcome {
+    Outcome {
+        seq: job.seq,
+        worker,
+        submission: order.map(|order| Submission { order, reference: job.mid, book: job.book }),
+        shadow: generator.take_shadow_observations(),
+        counts: generator.proposal_counts(),
+        regime: generator.overall_regime(),
+    }
+}
+
+/// Stable shard for `symbol` (FNV-1a over its name), so a replay assigns
+/// symbols to the same workers
+fn shard(symbol: &Symbol, shards: usize) -> usize {
+    let hash = symbol.0.bytes().fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    });
+    (hash % shards as u64) as usize
+}
+
+/// Everything the execution task touches
+pub struct ExecutionTask {
+    pub executor: Arc<OrderExecutor>,
+    pub portfolio: Arc<RwLock<PortfolioManager>>,
+    pub telemetry: Arc<TelemetryCollector>,
+    /// Rates each fill is charged at
+    pub fees: FeeModel,
+    /// Paper mode: a submitted order fills immediately at its limit price
+    /// (or the reference mid for market orders)
+    pub paper_fills: bool,
+}
+
+impl ExecutionTask {
+    /// Submit queued orders through the risk gate and safety checks until
+    /// shutdown
+    pub async fn run(self, mut orders: mpsc::Receiver<Submission>, mut shutdown: watch::Receiver<bool>) {
+        loop {
+            let submission = tokio::select! {
+                submission = orders.recv() => match submission {
+                    Some(submission) => submission,
+                    None => break,
+                },
+                _ = async { drop(shutdown.wait_for(|stop| *stop).await) } => break,
+            };
+            let order = &submission.order;
+            self.executor.on_book(submission.book.clone());
+            match self.executor.execute_order(order).await {
+                Ok(OrderStatus::Submitted) => {
+                    self.telemetry.record_execution().await;
+                    if self.paper_fills {
+                        let price = order.signal.limit_price.unwrap_or(submission.reference);
+                        self.apply_fill(order, price).await;
+                    }
+                }
+                Ok(status) => {
+                    self.telemetry.record_execution().await;
+                    debug!("Order for {} is {:?}", order.signal.symbol.0, status);
+                }
+                Err(e) => warn!("Order for {} not executed [{}]: {}", order.signal.symbol.0, e.code(), e),
+            }
+        }
+    }
+
+    /// Route an execution report into the executor's records and the portfolio
+    async fn apply_fill(&self, order: &VerifiedOrder, price: Price) {
+        let signal = &order.signal;
+        self.executor.record_fill(order, signal.quantity, price);
+        let updated = self.fees.fill_fee(&signal.venue, &signal.order_type, signal.quantity, price).and_then(|fee| {
+            let mut portfolio = self.portfolio.write().unwrap_or_else(PoisonError::into_inner);
+            let realized = portfolio.update_position(
+                &signal.account, signal.symbol.clone(), signal.venue.clone(), signal.side, signal.quantity, price, fee,
+            )?;
+            portfolio.attribute_fill(&signal.strategy, &signal.symbol, signal.side, signal.quantity)?;
+            Ok((realized, fee))
+        });
+        let (realized, fee) = match updated {
+            Ok(booked) => booked,
+            Err(e) => {
+                error!("Fill for {} not applied to the portfolio: {}", signal.symbol.0, e);
+                return;
+            }
+        };
+        self.telemetry.record_fill(&signal.symbol, realized.get(), fee.get(), Decimal::ZERO).await;
+    }
+}
+
+/// Feed trades to the stop emulator until shutdown
+pub async fn follow_ticks(
+    executor: Arc<OrderExecutor>,
+    mut ticks: FeedReceiver<Tick>,
+    mut shutdown: watch::Receiver<bool>,
+) {
+    loop {
+        let tick = tokio::select! {
+            tick = ticks.recv() => match tick {
+                Some(tick) => tick,
+                None => break,
+            },
+            _ = async { drop(shutdown.wait_for(|stop| *stop).await) } => break,
+        };
+        for result in executor.on_trade(&tick).await {
+            if let Err(e) = result {
+                warn!("Triggered stop for {} not submitted: {}", tick.symbol.0, e);
+            }
+        }
+    }
+}
+
+/// Latest funding by symbol and venue
+pub type FundingRates = HashMap<(Symbol, Venue), FundingRate>;
+
+/// Keep the latest funding per symbol and venue in `funding` until
+/// shutdown; open interest is only logged for now
+///
+/// A rate whose payment time has moved past the previous one means the
+/// previous interval was paid: it is settled on the portfolio's positions
+/// at the new mark price.
+pub async fn follow_derivatives(
+    mut updates: FeedReceiver<DerivativesUpdate>,
+    funding: watch::Sender<FundingRates>,
+    portfolio: Arc<RwLock<PortfolioManager>>,
+    telemetry: Arc<TelemetryCollector>,
+    mut shutdown: watch::Receiver<bool>,
+) {
+    loop {
+        let update = tokio::select! {
+            update = updates.recv() => match update {
+                Some(update) => update,
+                None => break,
+            },
+            _ = async { drop(shutdown.wait_for(|stop| *stop).await) } => break,
+        };
+        match update {
+            DerivativesUpdate::Funding(rate) => {
+                let key = (rate.symbol.clone(), rate.venue.clone());
+                let paid = funding.borrow().get(&key)
+                    .filter(|previous| previous.next_funding < rate.next_funding)
+                    .map(|previous| previous.rate);
+                if let Some(paid) = paid {
+                    let settled = portfolio.write().unwrap_or_else(PoisonError::into_inner)
+                        .apply_funding(&rate.symbol, &rate.venue, paid, rate.mark_price);
+                    match settled {
+                        Ok(received) if !received.is_zero() => {
+                            info!("{} funding on {} settled at {}: {} received", rate.symbol.0, rate.venue, paid, received);
+                            telemetry.record_funding(received.get()).await;
+                        }
+                        Ok(_) => {}
+                        Err(e) => error!("{} funding on {} not settled: {}", rate.symbol.0, rate.venue, e),
+                    }
+                }
+                funding.send_modify(|funding| {
+                    funding.insert(key, rate);
+                });
+            }
+            DerivativesUpdate::OpenInterest(interest) => {
+                debug!("{} open interest on {}: {}", interest.symbol.0, interest.venue, interest.quantity);
+            }
+        }
+    }
+}
+
+/// Liquidation intensity by symbol, across venues
+pub type LiquidationIntensities = HashMap<Symbol, LiquidationIntensity>;
+
+/// Liquidations kept per symbol while they are in the window
+const LIQUIDATION_HISTORY: usize = 10_000;
+
+/// Sum each symbol's liquidations over `window` into `intensities` and the
+/// portfolio breaker until shutdown, as they arrive and every second, so a
+/// cascade that has stopped falls out of the window
+pub async fn follow_liquidations(
+    mut events: FeedReceiver<LiquidationEvent>,
+    intensities: watch::Sender<LiquidationIntensities>,
+    breakers: Arc<Mutex<AccountBreakers>>,
+    window: Duration,
+    mut shutdown: watch::Receiver<bool>,
+) {
+    let window = TimeDelta::from_std(window).unwrap_or(TimeDelta::zero());
+    let mut features = FeatureCalculator::new(LIQUIDATION_HISTORY, DepthWindow::default(), MINUTES_PER_YEAR);
+    // A venue clock ahead of ours would otherwise leave its latest event out
+    let mut latest = DateTime::<Utc>::MIN_UTC;
+    let mut ticker = tokio::time::interval(Duration::from_secs(1));
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        tokio::select! {
+            event = events.recv() => match event {
+                Some(event) => {
+                    latest = latest.max(event.timestamp);
+                    features.record_liquidation(event);
+                }
+                None => break,
+            },
+            _ = ticker.tick() => {}
+            _ = async { drop(shutdown.wait_for(|stop| *stop).await) } => break,
+        }
+        let now = Utc::now().max(latest);
+        features.expire_liquidations(window, now);
+        // Symbols that fell out of the window go back to zero
+        let mut current: LiquidationIntensities = intensities.borrow().keys()
+            .map(|symbol| (symbol.clone(), LiquidationIntensity::default()))
+            .collect();
+        for symbol in features.liquidated_symbols() {
+            match features.calculate_liquidation_intensity(symbol, window, now) {
+                Ok(intensity) => {
+                    current.insert(symbol.clone(), intensity);
+                }
+                Err(e) => warn!("{} liquidation intensity not computable: {}", symbol.0, e),
+            }
+        }
+        {
+            let mut breakers = breakers.lock().unwrap_or_else(PoisonError::into_inner);
+            for (symbol, intensity) in &current {
+                breakers.record_liquidation_intensity(symbol.clone(), *intensity);
+            }
+        }
+        intensities.send_if_modified(|published| {
+            let changed = *published != current;
+            *published = current;
+            changed
+        });
+    }
+}
+
+/// Everything the health cadence reads and writes
+pub struct HealthTask {
+    pub portfolio: Arc<RwLock<PortfolioManager>>,
+    pub circuit_breaker: Arc<Mutex<AccountBreakers>>,
+    pub risk_gate: RiskGate,
+    /// Closed when the account's own breaker trips; the shared gate halts all
+    pub account_gates: HashMap<AccountId, RiskGate>,
+    pub monitor: Arc<Mutex<SystemMonitor>>,
+    pub stats: watch::Receiver<EngineStats>,
+    pub telemetry: Arc<TelemetryCollector>,
+    pub alert_manager: Arc<AlertManager>,
+    /// Read for the feed channels' drop counts
+    pub data_manager: Arc<DataIngestionManager>,
+}
+
+impl HealthTask {
+    /// Check the circuit breaker and publish a health snapshot every
+    /// `interval` until shutdown
+    pub async fn run(self, interval: Duration, mut shutdown: watch::Receiver<bool>) {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let mut last_state = CircuitBreakerState::Normal;
+        let mut last_account_states: BTreeMap<AccountId, CircuitBreakerState> = BTreeMap::new();
+        let mut last_dropped = (0, 0);
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {}
+                _ = async { drop(shutdown.wait_for(|stop| *stop).await) } => break,
+            }
+            let drift = self.portfolio.write().unwrap_or_else(PoisonError::into_inner).take_drift();
+            for d in drift {
+                self.alert_manager.check_portfolio_drift(d.metric, d.tracked, d.recomputed);
+            }
+
+            let dropped = self.data_manager.dropped_messages();
+            self.alert_manager.check_dropped_messages("tick", dropped.0 - last_dropped.0);
+            self.alert_manager.check_dropped_messages("book", dropped.1 - last_dropped.1);
+            last_dropped = dropped;
+
+            // An unusable breaker counts as tripped
+            let (state, account_states, drawdown) = match self.circuit_breaker.lock() {
+                Ok(mut breakers) => {
+                    let manager = self.portfolio.read().unwrap_or_else(PoisonError::into_inner);
+                    let state = breakers.check(&manager);
+                    (state, breakers.account_states(), breakers.equity_curve().current_drawdown_from_peak())
+                }
+                Err(_) => (CircuitBreakerState::Tripped, BTreeMap::new(), Decimal::ZERO),
+            };
+            for (account, account_state) in account_states {
+                let last = last_account_states.insert(account.clone(), account_state).unwrap_or(CircuitBreakerState::Normal);
+                if account_state != last {
+                    self.telemetry.record_breaker(Some(&account), account_state, &format!("{:?} -> {:?}", last, account_state)).await;
+                }
+                if let Some(gate) = self.account_gates.get(&account) {
+                    if matches!(account_state, CircuitBreakerState::Tripped | CircuitBreakerState::Halted) && gate.is_open() {
+                        gate.close(format!("{} circuit breaker {:?}", account, account_state));
+                    }
+                }
+            }
+            if state != last_state {
+                self.telemetry.record_breaker(None, state, &format!("{:?} -> {:?}", last_state, state)).await;
+                last_state = state;
+            }
+            if matches!(state, CircuitBreakerState::Tripped | CircuitBreakerState::Halted) && self.risk_gate.is_open() {
+                self.risk_gate.close(format!("circuit breaker {:?}", state));
+            }
+
+            let stats = *self.stats.borrow();
+            let health = match self.monitor.lock() {
+                Ok(mut monitor) => monitor.generate_health_snapshot(
+                    stats.consistency_error,
+                    stats.entropy_count,
+                    stats.regime,
+                    state,
+                    drawdown,
+                    stats.hallucination_rate,
+                ),
+                Err(_) => {
+                    error!("System monitor lock poisoned; no health snapshot");
+                    continue;
+                }
+            };
+            self.alert_manager.check_anomalies(&health);
+            self.telemetry.update_health(health).await;
+        }
+    }
+}
+
+/// Publish every tick and book of a recording (paper mode's feed)
+pub async fn feed_replay(source: ReplaySource, ticks: FeedSender<Tick>, books: FeedSender<Arc<OrderBook>>) {
+    if let Err(e) = source.run(&ticks, &books).await {
+        warn!("Replay stopped: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axiom_core::{
+        Amount, Band, BookLevel, CZeroKey, LimitsRegistry, OrderType, Quantity, RegimeThresholds, Side, SigningKeys,
+        SymbolLimits, TradeSignal, TrustedKey,
+    };
+    use axiom_data::{feed_channel, ChannelPolicy};
+    use axiom_engine::{
+        CooldownConfig, ProposalContext, ProposerConfig, ProposerStrategy, RegimeDetector, VerificationPoolConfig,
+        VerifierBackend, VerifierConfig,
+    };
+    use axiom_execution::SafetyChecker;
+    use std::time::Instant;
+
+    /// Buys one unit of every book's symbol at its mid, first sleeping for
+    /// `delay` on the books of `slow` from sequence `from` on (a proof that
+    /// takes that long)
+    #[derive(Clone, Default)]
+    struct Quoter {
+        slow: Option<Symbol>,
+        delay: Duration,
+        from: u64,
+    }
+
+    impl Quoter {
+        fn slow(symbol: &Symbol, delay: Duration) -> Self {
+            Self { slow: Some(symbol.clone()), delay, from: 0 }
+        }
+
+        fn from(self, sequence: u64) -> Self {
+            Self { from: sequence, ..self }
+        }
+    }
+
+    impl ProposerStrategy for Quoter {
+        fn name(&self) -> &str {
+            "quoter"
+        }
+
+        fn propose(&mut self, context: &ProposalContext<'_>) -> Option<TradeSignal> {
+            if self.slow.as_ref() == Some(context.symbol) && context.book.sequence >= self.from {
+                std::thread::sleep(self.delay);
+            }
+            TradeSignal::builder()
+                .with_symbol(context.symbol.clone())
+                .with_venue(context.venue.clone())
+                .with_side(Side::Buy)
+                .with_order_type(OrderType::Limit)
+                .with_quantity(Quantity::new(Decimal::ONE).unwrap())
+                .with_limit_price(context.features.mid)
+                .with_scores(Decimal::new(8, 1), Decimal::ZERO)
+                .with_expected_edge_bps(Decimal::ONE_HUNDRED)
+                .with_timestamp(context.book.timestamp)
+                .build(context.limits)
+                .ok()
+        }
+    }
+
+    fn symbols(count: usize) -> Vec<Symbol> {
+        (0..count).map(|index| Symbol(format!("MOCK{}/USD", index))).collect()
+    }
+
+    fn limits(symbols: &[Symbol]) -> LimitsRegistry {
+        let unit = |value| Quantity::new(Decimal::from(value)).unwrap();
+        symbols.iter().fold(LimitsRegistry::new(), |limits, symbol| {
+            limits.with_symbol(symbol.clone(), SymbolLimits::new(unit(1_000), unit(10)))
+        })
+    }
+
+    /// `symbol`'s book around 100, stamped `seq` milliseconds into the run
+    fn book(symbol: &Symbol, seq: u64) -> Arc<OrderBook> {
+        let level = |price: i64, quantity: i64| BookLevel {
+            price: Price::new(Decimal::new(price, 2)).unwrap(),
+            quantity: Quantity::new(Decimal::from(quantity)).unwrap(),
+        };
+        Arc::new(OrderBook {
+            symbol: symbol.clone(),
+            venue: Venue::Binance,
+            bids: Arc::new(vec![level(9_999, 5), level(9_998, 5)]),
+            asks: Arc::new(vec![level(10_001, 5), level(10_002, 5)]),
+            timestamp: DateTime::<Utc>::UNIX_EPOCH + TimeDelta::milliseconds(seq as i64),
+            sequence: seq,
+        })
+    }
+
+    /// A driver over `symbols` proposing with `quoter`, proving on blocking
+    /// tasks with the rules backend and no cooldown, whose orders are signed
+    /// with `key`
+    fn driver(symbols: &[Symbol], quoter: Quoter, ordered: bool, key: Arc<CZeroKey>) -> (SignalDriver, mpsc::Receiver<Submission>) {
+        let limits = Arc::new(limits(symbols));
+        // The default entropy band stands down on any spread at all
+        let thresholds = RegimeThresholds { unprovable_entropy: Band::new(Decimal::ONE, Decimal::ONE), ..RegimeThresholds::default() };
+        let regime = Arc::new(Mutex::new(RegimeDetector::new(thresholds)));
+        let build: GeneratorFactory = Arc::new(move || {
+            SignalGenerator::new()
+                .with_proposer_config(ProposerConfig {
+                    cooldown: CooldownConfig { duration_ms: 0, ..CooldownConfig::default() },
+                    ..ProposerConfig::default()
+                })
+                .with_limits(limits.clone())
+                .with_shared_regime_detector(regime.clone())
+                .with_verifier_config(VerifierConfig {
+                    backend: VerifierBackend::Rules,
+                    pool: VerificationPoolConfig { workers: 0, ..VerificationPoolConfig::default() },
+                    ..VerifierConfig::default()
+                })
+                .with_strategies(vec![Box::new(quoter.clone())])
+        });
+        let (orders, queued) = mpsc::channel(ORDER_QUEUE);
+        let driver = SignalDriver {
+            build,
+            shards: 0,
+            ordered,
+            portfolio: Arc::new(RwLock::new(PortfolioManager::new(Amount::new(Decimal::from(12_500))))),
+            symbols: watch::channel(symbols.to_vec()).1,
+            routes: HashMap::new(),
+            allocations: watch::channel(BTreeMap::new()).1,
+            edge_opt_out: watch::channel(BTreeSet::new()).1,
+            halted: watch::channel(BTreeSet::new()).1,
+            funding: watch::channel(FundingRates::new()).1,
+            liquidations: watch::channel(LiquidationIntensities::new()).1,
+            signer: OrderSigner::new(Arc::new(SigningKeys::new(key))),
+            orders,
+            stats: watch::channel(EngineStats::default()).0,
+            telemetry: Arc::new(TelemetryCollector::new()),
+        };
+        (driver, queued)
+    }
+
+    /// Run `driver` over `books`, fed in order, until `expected` orders are
+    /// queued; each order with how long after its book was fed it came
+    async fn run(
+        driver: SignalDriver,
+        mut queued: mpsc::Receiver<Submission>,
+        books: Vec<Arc<OrderBook>>,
+        pace: Duration,
+        expected: usize,
+    ) -> Vec<(VerifiedOrder, Duration)> {
+        let (feed, book_rx) = feed_channel(ChannelPolicy { capacity: books.len(), ..ChannelPolicy::default() });
+        let (stop, shutdown) = watch::channel(false);
+        let running = tokio::spawn(driver.run(book_rx, shutdown));
+
+        let mut fed = HashMap::new();
+        let mut orders = Vec::with_capacity(expected);
+        let mut books = books.into_iter();
+        let mut ticker = tokio::time::interval(pace);
+        let deadline = tokio::time::sleep(Duration::from_secs(30));
+        tokio::pin!(deadline);
+        while orders.len() < expected {
+            tokio::select! {
+                _ = &mut deadline => panic!("{} of {} orders queued", orders.len(), expected),
+                _ = ticker.tick() => if let Some(book) = books.next() {
+                    fed.insert((book.symbol.clone(), book.timestamp), Instant::now());
+                    feed.send(book).await.unwrap();
+                },
+                submission = queued.recv() => {
+                    let order = submission.unwrap().order;
+                    let latency = fed[&(order.signal.symbol.clone(), order.signal.timestamp)].elapsed();
+                    orders.push((order, latency));
+                }
+            }
+        }
+        stop.send_replace(true);
+        running.await.unwrap();
+        orders
+    }
+
+    fn checker(key: &CZeroKey) -> SafetyChecker {
+        SafetyChecker::new().with_trusted_keys([TrustedKey::new(key.verifying_key())])
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn orders_finished_out_of_order_are_signed_in_queue_order() {
+        let symbols = symbols(2);
+        for ordered in [true, false] {
+            let key = Arc::new(CZeroKey::generate());
+            // The first book's worker finishes after the second's
+            let (driver, queued) = driver(&symbols, Quoter::slow(&symbols[0], Duration::from_millis(200)), ordered, key.clone());
+            let books = vec![book(&symbols[0], 0), book(&symbols[1], 1)];
+            let orders = run(driver, queued, books, Duration::from_millis(1), 2).await;
+
+            let released: Vec<&Symbol> = orders.iter().map(|(order, _)| &order.signal.symbol).collect();
+            match ordered {
+                true => assert_eq!(released, [&symbols[0], &symbols[1]]),
+                false => assert_eq!(released, [&symbols[1], &symbols[0]]),
+            }
+            let limits = Arc::new(limits(&symbols));
+            let checker = checker(&key).with_limits(limits);
+            for (order, _) in &orders {
+                assert!(checker.check_order(order).is_ok(), "ordered {}: {:?}", ordered, checker.check_order(order));
+            }
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn an_ordered_replay_releases_the_same_orders_whichever_worker_is_slow() {
+        let symbols = symbols(3);
+        let books: Vec<Arc<OrderBook>> = (0..12).map(|seq| book(&symbols[seq as usize % 3], seq)).collect();
+
+        let mut replays = Vec::new();
+        for slow in &symbols {
+            let key = Arc::new(CZeroKey::generate());
+            let (driver, queued) = driver(&symbols, Quoter::slow(slow, Duration::from_millis(30)), true, key.clone());
+            let orders = run(driver, queued, books.clone(), Duration::from_millis(1), books.len()).await;
+            let checker = checker(&key).with_limits(Arc::new(limits(&symbols)));
+            assert!(orders.iter().all(|(order, _)| checker.check_order(order).is_ok()));
+            // Released in the order the books arrived, not the order decided
+            let released: Vec<(&Symbol, DateTime<Utc>)> = orders.iter()
+                .map(|(order, _)| (&order.signal.symbol, order.signal.timestamp))
+                .collect();
+            let arrived: Vec<(&Symbol, DateTime<Utc>)> = books.iter().map(|book| (&book.symbol, book.timestamp)).collect();
+            assert_eq!(released, arrived);
+            replays.push(orders.iter().map(|(order, _)| axiom_core::signal_hash(&order.signal)).collect::<Vec<_>>());
+        }
+        assert_eq!(replays[0], replays[1]);
+        assert_eq!(replays[1], replays[2]);
+    }
+
+    /// Book-to-queue latency of 20 symbols, one of which takes 2s a book
+    /// once every worker has started: every later order for the other 19 is
+    /// queued before its next
+    ///
+    /// cargo test -p axiom-cli --release twenty_symbols -- --nocapture
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn twenty_symbols_are_not_held_up_by_a_slow_one() {
+        const ROUNDS: u64 = 4;
+        let symbols = symbols(20);
+        let slow = &symbols[0];
+        let books: Vec<Arc<OrderBook>> = (0..ROUNDS * 20)
+            .map(|seq| book(&symbols[(seq % 20) as usize], seq))
+            .collect();
+        // The first round starts the workers, building a generator each
+        let quoter = Quoter::slow(slow, Duration::from_secs(2)).from(20);
+        let (driver, queued) = driver(&symbols, quoter, false, Arc::new(CZeroKey::generate()));
+        let fast = ((ROUNDS - 1) * 19) as usize;
+        let orders = run(driver, queued, books, Duration::from_millis(20), 20 + fast + 1).await;
+        let measured = &orders[20..];
+
+        let mut latencies: BTreeMap<&Symbol, Vec<Duration>> = BTreeMap::new();
+        for (order, latency) in measured {
+            latencies.entry(&order.signal.symbol).or_default().push(*latency);
+        }
+        for (symbol, latencies) in &latencies {
+            let mean = latencies.iter().sum::<Duration>() / latencies.len() as u32;
+            println!("{}: mean {:?}, worst {:?}", symbol.0, mean, latencies.iter().max().unwrap());
+        }
+        assert!(measured[..fast].iter().all(|(order, _)| &order.signal.symbol != slow));
+        assert_eq!(&measured[fast].0.signal.symbol, slow);
+    }
+}
@@ -0,0 +1,1115 @@
+//! Settings: TOML Configuration for the CLI
+//!
+//! Loads the file given by `--config` (default `config/default.toml`) and
+//! validates it as a whole before anything starts: unknown venues, symbols
+//! without limits, contradictory modes, and out-of-range values are reported
+//! together, each with the line it came from where one can be found. Every
+//! component `main` builds takes its parameters from here.
+
+use crate::keys::KeySettings;
+use axiom_core::constants::*;
+use axiom_core::{
+    AccountId, AccountLimits, Amount, EdgePolicy, LimitsError, LimitsRegistry, RegimeThresholds, StrategyId, Symbol,
+    SymbolLimits, Venue,
+};
+use axiom_data::{ChannelPolicy, OverflowPolicy, RecorderSettings, SchemaRegistry, SymbolMapper, VenueSchema};
+use axiom_engine::{DepthWindow, ProposerConfig, ShadowSettings, VerifierBackend, VerifierConfig, SMT_AVAILABLE};
+use axiom_oracle::{AlertSeverity, AlertThresholds, EscalationPolicy, LiveStreamConfig, SmtpConfig, SmtpTls};
+use axiom_risk::{CorrelationConfig, FeeModel, ReconciliationConfig, VenueFees};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Config file used when `--config` is not given
+pub const DEFAULT_CONFIG_PATH: &str = "config/default.toml";
+
+/// Whether orders reach real exchanges
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Mode {
+    #[default]
+    Paper,
+    Live,
+}
+
+/// Which of a venue's deployments to connect to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VenueEnvironment {
+    Paper,
+    #[default]
+    Testnet,
+    Mainnet,
+}
+
+/// The whole configuration file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub system: SystemSection,
+    pub risk: RiskSection,
+    /// Per-symbol limits overriding or extending the built-in ones
+    pub limits: BTreeMap<String, SymbolLimits>,
+    pub venues: VenuesSection,
+    pub symbols: SymbolsSection,
+    pub data: DataSection,
+    pub derivatives: DerivativesSection,
+    pub liquidations: LiquidationsSection,
+    pub recorder: RecorderSection,
+    pub instruments: InstrumentsSection,
+    pub proposer: ProposerConfig,
+    /// Candidate proposer tunings run beside `proposer` but never traded
+    pub shadow: ShadowSettings,
+    pub signals: SignalsSection,
+    /// Solver timeout and memory cap for every proof (needs a restart)
+    pub verifier: VerifierConfig,
+    /// Trading accounts by name; none trades everything for one default
+    /// account holding [system] initial_equity
+    pub accounts: BTreeMap<String, AccountSection>,
+    /// Share of equity each strategy may hold as gross exposure, by strategy
+    /// name; strategies not listed are unbudgeted
+    pub allocations: BTreeMap<String, Decimal>,
+    /// Expected edge signals must keep after fees and funding
+    pub edge: EdgePolicy,
+    /// Maker and taker rates charged on fills, by venue; venues not listed
+    /// are charged their built-in defaults
+    pub fees: BTreeMap<String, VenueFees>,
+    /// Tolerances and policy for diffing venue position reports against
+    /// the tracked portfolio
+    pub reconciliation: ReconciliationConfig,
+    pub regime: RegimeSection,
+    pub keys: KeysSection,
+    pub events: EventsSection,
+    pub audit: AuditSection,
+    pub monitoring: MonitoringSection,
+    pub snapshot: SnapshotSection,
+    pub shutdown: ShutdownSection,
+    pub dry_run: DryRunSection,
+    pub reload: ReloadSection,
+    pub control: ControlSection,
+    pub incidents: IncidentsSection,
+    pub email: EmailSection,
+    pub live_stream: LiveStreamSection,
+    pub alert_thresholds: AlertThresholds,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SystemSection {
+    pub seed: u64,
+    pub mode: Mode,
+    /// Starting equity (quote currency)
+    pub initial_equity: Decimal,
+}
+
+impl Default for SystemSection {
+    fn default() -> Self {
+        Self { seed: 42, mode: Mode::Paper, initial_equity: Decimal::from(10_000) }
+    }
+}
+
+/// Portfolio-wide risk settings; may only tighten the L0 constants
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RiskSection {
+    pub max_leverage: Decimal,
+    pub max_daily_drawdown: Decimal,
+    /// Risk budget for symbols without their own override
+    pub min_risk_budget: Decimal,
+    pub max_risk_budget: Decimal,
+    /// Mark-return correlation behind the Hamiltonian's correlation penalty
+    pub correlation: CorrelationConfig,
+}
+
+impl Default for RiskSection {
+    fn default() -> Self {
+        Self {
+            max_leverage: MAX_LEVERAGE,
+            max_daily_drawdown: MAX_DAILY_DRAWDOWN,
+            min_risk_budget: MIN_RISK_BUDGET,
+            max_risk_budget: MAX_RISK_BUDGET,
+            correlation: CorrelationConfig::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VenuesSection {
+    pub primary: String,
+    pub secondary: Vec<String>,
+    /// Deployment per venue (default testnet; the paper venue is always paper)
+    pub environments: BTreeMap<String, VenueEnvironment>,
+    /// Feed payload mappings per venue, replacing the built-in ones
+    pub schemas: BTreeMap<String, VenueSchema>,
+}
+
+impl Default for VenuesSection {
+    fn default() -> Self {
+        Self {
+            primary: "paper".to_string(),
+            secondary: Vec::new(),
+            environments: BTreeMap::new(),
+            schemas: BTreeMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SymbolsSection {
+    pub enabled: Vec<String>,
+}
+
+impl Default for SymbolsSection {
+    fn default() -> Self {
+        Self { enabled: SUPPORTED_PAIRS.iter().map(|pair| pair.to_string()).collect() }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SignalsSection {
+    /// Signal workers: 0 runs one per symbol, N hashes symbols onto N
+    pub shards: usize,
+}
+
+/// One trading account: its venue credentials, starting equity, the symbols
+/// the live strategy trades for it, and limits tighter than [risk]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AccountSection {
+    pub venue: String,
+    /// Environment variables holding the venue API key and secret (live mode)
+    pub api_key_env: String,
+    pub api_secret_env: String,
+    /// Starting equity (quote currency)
+    pub initial_equity: Decimal,
+    /// Symbols whose signals are placed for this account
+    pub symbols: Vec<String>,
+    #[serde(flatten)]
+    pub limits: AccountLimits,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RegimeSection {
+    pub state_path: PathBuf,
+    #[serde(flatten)]
+    pub thresholds: RegimeThresholds,
+}
+
+impl Default for RegimeSection {
+    fn default() -> Self {
+        Self { state_path: PathBuf::from("data/regime.json"), thresholds: RegimeThresholds::default() }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeysSection {
+    pub signing_key_path: PathBuf,
+    pub passphrase_env: String,
+    pub trusted_public_keys: Vec<String>,
+    pub signature_validity_ms: i64,
+    pub clock_skew_ms: i64,
+}
+
+impl Default for KeysSection {
+    fn default() -> Self {
+        let defaults = KeySettings::default();
+        Self {
+            signing_key_path: defaults.path,
+            passphrase_env: defaults.passphrase_env,
+            trusted_public_keys: defaults.trusted_public_keys,
+            signature_validity_ms: defaults.signature_validity.num_milliseconds(),
+            clock_skew_ms: defaults.clock_skew.num_milliseconds(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EventsSection {
+    pub log_path: PathBuf,
+}
+
+impl Default for EventsSection {
+    fn default() -> Self {
+        Self { log_path: PathBuf::from("logs/events.jsonl") }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuditSection {
+    /// Hash-chained order provenance log (also read by `verify-order`)
+    pub log_path: PathBuf,
+    /// Rotate the log into a segment once it reaches this size; None never
+    /// rotates
+    pub rotate_bytes: Option<u64>,
+}
+
+impl Default for AuditSection {
+    fn default() -> Self {
+        Self { log_path: PathBuf::from("logs/audit.jsonl"), rotate_bytes: None }
+    }
+}
+
+/// Bounds on the channels carrying market data to the engine
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DataSection {
+    pub ticks: ChannelPolicy,
+    pub books: ChannelPolicy,
+    pub derivatives: ChannelPolicy,
+    pub liquidations: ChannelPolicy,
+}
+
+impl Default for DataSection {
+    fn default() -> Self {
+        Self {
+            // Stops trigger on trades, so a tick is worth a short wait
+            ticks: ChannelPolicy { overflow: OverflowPolicy::Block, ..ChannelPolicy::default() },
+            // A newer book supersedes a queued one
+            books: ChannelPolicy { overflow: OverflowPolicy::DropOldest, ..ChannelPolicy::default() },
+            // Each poll restates the last; a queued one is stale
+            derivatives: ChannelPolicy { overflow: OverflowPolicy::DropOldest, ..ChannelPolicy::default() },
+            // Every forced order counts toward the window's sum
+            liquidations: ChannelPolicy { overflow: OverflowPolicy::Block, ..ChannelPolicy::default() },
+        }
+    }
+}
+
+/// Perpetual funding and open interest polled from the venues
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DerivativesSection {
+    pub enabled: bool,
+    pub poll_interval_sec: u64,
+}
+
+impl Default for DerivativesSection {
+    fn default() -> Self {
+        Self { enabled: false, poll_interval_sec: 60 }
+    }
+}
+
+/// Liquidations from the venues' forced-order streams
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LiquidationsSection {
+    /// Rolling window liquidation intensity is summed over
+    pub window_sec: u64,
+    /// Put the portfolio breaker in Warning while more than this notional
+    /// is liquidated in one symbol over the window
+    pub breaker_max_notional: Option<Decimal>,
+}
+
+impl Default for LiquidationsSection {
+    fn default() -> Self {
+        Self { window_sec: 60, breaker_max_notional: None }
+    }
+}
+
+/// Capture of ingested ticks and books to disk, for replay and post-mortems
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecorderSection {
+    pub enabled: bool,
+    #[serde(flatten)]
+    pub settings: RecorderSettings,
+}
+
+/// Venue tick and lot rules orders are rounded to and checked against
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InstrumentsSection {
+    /// Read the rules from the venues' listings at startup (not in replay);
+    /// without them orders go unrounded and unchecked
+    pub fetch: bool,
+}
+
+impl Default for InstrumentsSection {
+    fn default() -> Self {
+        Self { fetch: true }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MonitoringSection {
+    pub health_check_interval_sec: u64,
+    /// Health snapshots and latency samples kept in memory
+    pub history: usize,
+}
+
+impl Default for MonitoringSection {
+    fn default() -> Self {
+        Self { health_check_interval_sec: 1, history: 1000 }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SnapshotSection {
+    pub path: PathBuf,
+    pub interval_sec: u64,
+}
+
+impl Default for SnapshotSection {
+    fn default() -> Self {
+        Self { path: PathBuf::from("logs/snapshot.json"), interval_sec: 60 }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShutdownSection {
+    /// How long in-flight orders may take to reach the venue
+    pub drain_timeout_ms: u64,
+    /// Close every open position before exiting (also `--flatten-on-exit`)
+    pub flatten_on_exit: bool,
+    /// Where the final portfolio is written
+    pub portfolio_path: PathBuf,
+}
+
+impl Default for ShutdownSection {
+    fn default() -> Self {
+        Self { drain_timeout_ms: 5000, flatten_on_exit: false, portfolio_path: PathBuf::from("data/portfolio.json") }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DryRunSection {
+    /// Live mode only: everything up to the exchange call runs, nothing is
+    /// sent (also `run --dry-run`; needs a restart to change)
+    pub enabled: bool,
+    /// Where the orders a dry run would have placed are written at shutdown
+    pub report_path: PathBuf,
+}
+
+impl Default for DryRunSection {
+    fn default() -> Self {
+        Self { enabled: false, report_path: PathBuf::from("reports/dry-run.json") }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReloadSection {
+    /// Also reload when the file's modification time changes; 0 reloads on SIGHUP only
+    pub poll_interval_sec: u64,
+}
+
+/// How the control endpoint listens
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ControlTransport {
+    /// Unix domain socket, owner-only permissions
+    #[default]
+    Unix,
+    /// TCP on a loopback address
+    Tcp,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ControlSection {
+    pub enabled: bool,
+    pub transport: ControlTransport,
+    pub socket_path: PathBuf,
+    pub bind: String,
+    /// Required from clients when non-empty
+    pub token: String,
+}
+
+impl Default for ControlSection {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            transport: ControlTransport::Unix,
+            socket_path: PathBuf::from("run/axiom.sock"),
+            bind: "127.0.0.1:9102".to_string(),
+            token: String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IncidentsSection {
+    pub escalation_initial_min: i64,
+    pub escalation_factor: i32,
+    pub escalation_max_min: i64,
+}
+
+impl Default for IncidentsSection {
+    fn default() -> Self {
+        Self { escalation_initial_min: 5, escalation_factor: 2, escalation_max_min: 60 }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EmailSection {
+    pub enabled: bool,
+    pub host: String,
+    pub port: u16,
+    pub tls: SmtpTls,
+    pub from: String,
+    pub username: Option<String>,
+    pub password_env: String,
+    pub digest_interval_min: u64,
+    pub max_attempts: u32,
+    pub retry_backoff_sec: u64,
+    pub incident_url_base: String,
+    pub recipients: BTreeMap<AlertSeverity, Vec<String>>,
+}
+
+impl Default for EmailSection {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            host: String::new(),
+            port: 587,
+            tls: SmtpTls::StartTls,
+            from: String::new(),
+            username: None,
+            password_env: "AXIOM_SMTP_PASSWORD".to_string(),
+            digest_interval_min: 15,
+            max_attempts: 5,
+            retry_backoff_sec: 2,
+            incident_url_base: String::new(),
+            recipients: BTreeMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LiveStreamSection {
+    pub enabled: bool,
+    pub bind: String,
+    pub token: String,
+    pub health_interval_ms: u64,
+    pub portfolio_interval_ms: u64,
+    pub client_buffer: usize,
+}
+
+impl Default for LiveStreamSection {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind: "127.0.0.1:9101".to_string(),
+            token: String::new(),
+            health_interval_ms: 1000,
+            portfolio_interval_ms: 5000,
+            client_buffer: 256,
+        }
+    }
+}
+
+/// One problem found validating a config file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigProblem {
+    /// 1-based line the offending setting is on, when it can be found
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+/// Configuration loading error
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error("Failed to read config {path}")]
+    Read { path: String, source: std::io::Error },
+
+    #[error("Invalid config {path}: {message}")]
+    Parse { path: String, message: String },
+
+    #[error("Invalid config {path}:\n{}", format_problems(.path, .problems))]
+    Invalid { path: String, problems: Vec<ConfigProblem> },
+}
+
+fn format_problems(path: &str, problems: &[ConfigProblem]) -> String {
+    problems.iter()
+        .map(|problem| match problem.line {
+            Some(line) => format!("  {}:{}: {}", path, line, problem.message),
+            None => format!("  {}: {}", path, problem.message),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+impl Config {
+    /// Read, parse, and validate a config file; `adjust` applies command
+    /// line overrides before validation
+    pub fn load(path: &Path, adjust: impl FnOnce(&mut Self)) -> Result<Self, ConfigError> {
+        let display = path.display().to_string();
+        let source = std::fs::read_to_string(path)
+            .map_err(|source| ConfigError::Read { path: display.clone(), source })?;
+        let mut config = Self::parse(&source)
+            .map_err(|e| ConfigError::Parse { path: display.clone(), message: e.to_string() })?;
+        adjust(&mut config);
+
+        let problems = config.validate(&source);
+        if problems.is_empty() {
+            Ok(config)
+        } else {
+            Err(ConfigError::Invalid { path: display, problems })
+        }
+    }
+
+    /// Parse without validating
+    pub fn parse(source: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(source)
+    }
+
+    /// Every problem with this configuration; `source` (the file text) is
+    /// only used to find line numbers
+    pub fn validate(&self, source: &str) -> Vec<ConfigProblem> {
+        let mut problems = Vec::new();
+        let mut problem = |section: &str, key: Option<&str>, message: String| {
+            problems.push(ConfigProblem { line: locate(source, section, key), message });
+        };
+
+        if self.system.initial_equity <= Decimal::ZERO {
+            problem("system", Some("initial_equity"), format!("initial_equity must be positive (got {})", self.system.initial_equity));
+        }
+
+        // Venues and their environments
+        let venue_names = std::iter::once(("primary", &self.venues.primary))
+            .chain(self.venues.secondary.iter().map(|name| ("secondary", name)))
+            .chain(self.venues.environments.keys().map(|name| ("environments", name)))
+            .chain(self.venues.schemas.keys().map(|name| ("schemas", name)));
+        for (key, name) in venue_names {
+            if let Err(e) = name.parse::<Venue>() {
+                match key {
+                    "environments" | "schemas" => problem(&format!("venues.{}", key), Some(name.as_str()), e.to_string()),
+                    _ => problem("venues", Some(key), e.to_string()),
+                }
+            }
+        }
+        for (venue, environment) in self.venues() {
+            if self.system.mode == Mode::Paper && environment == VenueEnvironment::Mainnet {
+                problem("venues.environments", Some(venue.name()), format!(
+                    "paper mode cannot connect to mainnet {} (it needs live keys); use testnet or run with --live", venue
+                ));
+            }
+        }
+        if self.system.mode == Mode::Live && self.venues.primary.parse::<Venue>() == Ok(Venue::Paper) {
+            problem("venues", Some("primary"), "live mode needs a real primary venue, not paper".to_string());
+        }
+
+        // Fee rates
+        for (name, fees) in &self.fees {
+            let section = format!("fees.{}", name);
+            if let Err(e) = name.parse::<Venue>() {
+                problem(&section, None, e.to_string());
+            }
+            if fees.taker_bps < Decimal::ZERO {
+                problem(&section, Some("taker_bps"), format!("taker_bps must not be negative (got {})", fees.taker_bps));
+            }
+            for (key, bps) in [("maker_bps", fees.maker_bps), ("taker_bps", fees.taker_bps)] {
+                if bps.abs() >= BPS_PER_UNIT {
+                    problem(&section, Some(key), format!("{} is in basis points and must be below {} (got {})", key, BPS_PER_UNIT, bps));
+                }
+            }
+        }
+
+        let reconciliation = &self.reconciliation;
+        for (key, tolerance) in [
+            ("quantity_tolerance", reconciliation.quantity_tolerance),
+            ("price_tolerance_bps", reconciliation.price_tolerance_bps),
+            ("balance_tolerance", reconciliation.balance_tolerance),
+        ] {
+            if tolerance < Decimal::ZERO {
+                problem("reconciliation", Some(key), format!("{} cannot be negative (got {})", key, tolerance));
+            }
+        }
+
+        // Market data channels
+        let channels = [
+            ("ticks", &self.data.ticks),
+            ("books", &self.data.books),
+            ("derivatives", &self.data.derivatives),
+            ("liquidations", &self.data.liquidations),
+        ];
+        for (key, policy) in channels {
+            if policy.capacity == 0 {
+                problem("data", Some(key), format!("{}: capacity must be positive", key));
+            }
+            if policy.overflow == OverflowPolicy::Block && policy.block_timeout_ms == 0 {
+                problem("data", Some(key), format!("{}: the block policy needs a positive block_timeout_ms", key));
+            }
+        }
+
+        if self.derivatives.enabled && self.derivatives.poll_interval_sec == 0 {
+            problem("derivatives", Some("poll_interval_sec"), "poll_interval_sec must be positive".to_string());
+        }
+        if !(1..=86_400).contains(&self.liquidations.window_sec) {
+            problem("liquidations", Some("window_sec"), "window_sec must be between 1 and 86400 (a day)".to_string());
+        }
+        if self.liquidations.breaker_max_notional.is_some_and(|max| max <= Decimal::ZERO) {
+            problem("liquidations", Some("breaker_max_notional"), "breaker_max_notional must be positive".to_string());
+        }
+        if self.recorder.enabled {
+            if self.recorder.settings.flush_interval_ms == 0 {
+                problem("recorder", Some("flush_interval_ms"), "flush_interval_ms must be positive".to_string());
+            }
+            if self.recorder.settings.max_segment_bytes == 0 {
+                problem("recorder", Some("max_segment_bytes"), "max_segment_bytes must be positive".to_string());
+            }
+        }
+
+        // Symbols and limits
+        let limits = self.limits();
+        for symbol in &self.symbols.enabled {
+            if !limits.contains(&Symbol(symbol.clone())) {
+                problem("symbols", Some("enabled"), format!("symbol {} has no [limits.\"{}\"] entry", symbol, symbol));
+            }
+        }
+        let mapper = self.symbol_mapper();
+        for (venue, _) in self.venues() {
+            for symbol in self.symbols() {
+                if let Err(e) = mapper.exchange(&venue, &symbol) {
+                    problem("symbols", Some("enabled"), format!("{}; map it in [venues.schemas.\"{}\"] symbols", e, venue));
+                }
+            }
+        }
+        if let Err(errors) = limits.validate() {
+            for e in errors {
+                let section = match &e {
+                    LimitsError::Invalid { symbol, .. } | LimitsError::UnknownSymbol(symbol) => format!("limits.\"{}\"", symbol),
+                    LimitsError::InvalidAccount { account, .. } => format!("accounts.{}", account),
+                    LimitsError::InvalidAllocation { .. } | LimitsError::AllocationsOvercommitted(_) => "allocations".to_string(),
+                    LimitsError::InvalidEdgePolicy(_) => "edge".to_string(),
+                };
+                problem(&section, None, e.to_string());
+            }
+        }
+
+        // Accounts: each enabled symbol traded for exactly one
+        let venues: Vec<Venue> = self.venues().into_iter().map(|(venue, _)| venue).collect();
+        let mut assigned: BTreeMap<&str, &str> = BTreeMap::new();
+        for (name, account) in &self.accounts {
+            let section = format!("accounts.{}", name);
+            match account.venue.parse::<Venue>() {
+                Ok(venue) if !venues.contains(&venue) => {
+                    problem(&section, Some("venue"), format!("account {}: venue {} is not configured under [venues]", name, venue));
+                }
+                Ok(_) => {}
+                Err(e) => problem(&section, Some("venue"), format!("account {}: {}", name, e)),
+            }
+            if account.initial_equity <= Decimal::ZERO {
+                problem(&section, Some("initial_equity"), format!("account {}: initial_equity must be positive", name));
+            }
+            if self.system.mode == Mode::Live && (account.api_key_env.is_empty() || account.api_secret_env.is_empty()) {
+                problem(&section, None, format!("account {}: live mode needs api_key_env and api_secret_env", name));
+            }
+            if account.limits.max_leverage.is_some_and(|max| max > self.risk.max_leverage) {
+                problem(&section, Some("max_leverage"), format!("account {}: max_leverage may only tighten [risk]", name));
+            }
+            if account.limits.max_daily_drawdown.is_some_and(|max| max > self.risk.max_daily_drawdown) {
+                problem(&section, Some("max_daily_drawdown"), format!("account {}: max_daily_drawdown may only tighten [risk]", name));
+            }
+            for symbol in &account.symbols {
+                if !self.symbols.enabled.contains(symbol) {
+                    problem(&section, Some("symbols"), format!("account {}: symbol {} is not enabled", name, symbol));
+                }
+                if let Some(other) = assigned.insert(symbol, name) {
+                    problem(&section, Some("symbols"), format!("symbol {} is assigned to both {} and {}", symbol, other, name));
+                }
+            }
+        }
+        if !self.accounts.is_empty() {
+            for symbol in self.symbols.enabled.iter().filter(|symbol| !assigned.contains_key(symbol.as_str())) {
+                problem("symbols", Some("enabled"), format!("symbol {} is not assigned to any account", symbol));
+            }
+        }
+
+        // Risk may tighten but never loosen the L0 constants
+        let risk = &self.risk;
+        if risk.max_leverage <= Decimal::ZERO || risk.max_leverage > MAX_LEVERAGE {
+            problem("risk", Some("max_leverage"), format!("max_leverage must be in (0, {}] (got {})", MAX_LEVERAGE, risk.max_leverage));
+        }
+        if risk.max_daily_drawdown <= Decimal::ZERO || risk.max_daily_drawdown > MAX_DAILY_DRAWDOWN {
+            problem("risk", Some("max_daily_drawdown"), format!(
+                "max_daily_drawdown must be in (0, {}] (got {})", MAX_DAILY_DRAWDOWN, risk.max_daily_drawdown
+            ));
+        }
+        if risk.min_risk_budget <= Decimal::ZERO || risk.min_risk_budget > risk.max_risk_budget || risk.max_risk_budget > Decimal::ONE {
+            problem("risk", Some("min_risk_budget"), format!(
+                "risk budget must satisfy 0 < min ({}) <= max ({}) <= 1", risk.min_risk_budget, risk.max_risk_budget
+            ));
+        }
+        let correlation = &risk.correlation;
+        if correlation.min_samples < 2 || correlation.min_samples > correlation.window {
+            problem("risk.correlation", Some("min_samples"), format!(
+                "min_samples must be in [2, window] (got {}, window {})", correlation.min_samples, correlation.window
+            ));
+        }
+
+        // Proposer and regime
+        if self.proposer.base_quantity.is_zero() {
+            problem("proposer", Some("base_quantity"), "base_quantity must be positive".to_string());
+        }
+        if self.proposer.contradiction_threshold < Decimal::ZERO || self.proposer.spread_threshold < Decimal::ZERO {
+            problem("proposer", None, "proposer thresholds cannot be negative".to_string());
+        }
+        if self.proposer.max_liquidation_notional.is_some_and(|max| !max.is_positive()) {
+            problem("proposer", Some("max_liquidation_notional"), "max_liquidation_notional must be positive".to_string());
+        }
+        if self.proposer.cross_venue.max_book_age_ms < 0 {
+            problem("proposer.cross_venue", Some("max_book_age_ms"), format!(
+                "max_book_age_ms cannot be negative (got {})", self.proposer.cross_venue.max_book_age_ms
+            ));
+        }
+        if self.proposer.cross_venue.min_edge_bps.is_some_and(|edge| edge < Decimal::ZERO) {
+            problem("proposer.cross_venue", Some("min_edge_bps"), "min_edge_bps cannot be negative".to_string());
+        }
+        if self.proposer.cooldown.duration_ms < 0 {
+            problem("proposer.cooldown", Some("duration_ms"), format!(
+                "duration_ms cannot be negative (got {})", self.proposer.cooldown.duration_ms
+            ));
+        }
+        if self.proposer.cooldown.release_move_bps.is_some_and(|bps| bps <= Decimal::ZERO) {
+            problem("proposer.cooldown", Some("release_move_bps"), "release_move_bps must be positive".to_string());
+        }
+        if self.verifier.backend == VerifierBackend::Smt && !SMT_AVAILABLE {
+            problem("verifier", Some("backend"), "this build has no smt feature; use \"rules\"".to_string());
+        }
+        if self.verifier.timeout_ms == 0 {
+            problem("verifier", Some("timeout_ms"), "timeout_ms must be positive".to_string());
+        }
+        if self.verifier.max_memory_mb == Some(0) {
+            problem("verifier", Some("max_memory_mb"), "max_memory_mb must be positive".to_string());
+        }
+        let cache = &self.verifier.cache;
+        for (key, value) in [
+            ("quantity_bucket", cache.quantity_bucket),
+            ("price_bucket", cache.price_bucket),
+            ("leverage_bucket", cache.leverage_bucket),
+            ("entropy_bucket", cache.entropy_bucket),
+            ("max_equity_change", cache.max_equity_change),
+        ] {
+            if value < Decimal::ZERO {
+                problem("verifier.cache", Some(key), format!("{} cannot be negative (got {})", key, value));
+            }
+        }
+        if self.audit.rotate_bytes == Some(0) {
+            problem("audit", Some("rotate_bytes"), "rotate_bytes must be positive".to_string());
+        }
+        if self.verifier.pool.queue_depth == 0 {
+            problem("verifier.pool", Some("queue_depth"), "queue_depth must be positive".to_string());
+        }
+        if self.shadow.max_share < Decimal::ZERO || self.shadow.max_share > Decimal::ONE {
+            problem("shadow", Some("max_share"), format!("max_share must be in [0, 1] (got {})", self.shadow.max_share));
+        }
+        let mut confidence = vec![("proposer.confidence".to_string(), &self.proposer.confidence)];
+        for (name, strategy) in &self.shadow.strategies {
+            confidence.push((format!("shadow.strategies.{}.confidence", name), &strategy.confidence));
+        }
+        for (section, config) in confidence {
+            let decays = [("cex".to_string(), config.cex), ("onchain".to_string(), config.onchain)].into_iter()
+                .chain(config.sources.iter().map(|(source, decay)| (format!("sources.{}", source), *decay)));
+            for (key, decay) in decays {
+                if decay.fresh_ms >= decay.stale_ms {
+                    problem(&section, Some(&key), format!(
+                        "{}: fresh_ms ({}) must be below stale_ms ({})", key, decay.fresh_ms, decay.stale_ms
+                    ));
+                }
+            }
+        }
+        let mut depth_windows = vec![("proposer".to_string(), self.proposer.depth_window)];
+        for (name, strategy) in &self.shadow.strategies {
+            depth_windows.push((format!("shadow.strategies.{}", name), strategy.depth_window));
+        }
+        for (section, window) in depth_windows {
+            let empty = match window {
+                DepthWindow::Levels(levels) => levels == 0,
+                DepthWindow::WithinBps(bps) => bps <= Decimal::ZERO,
+            };
+            if empty {
+                problem(&section, Some("depth_window"), format!(
+                    "depth_window must cover at least one level (got {:?})", window
+                ));
+            }
+        }
+        for (name, strategy) in &self.shadow.strategies {
+            let section = format!("shadow.strategies.{}", name);
+            if strategy.base_quantity.is_zero() {
+                problem(&section, Some("base_quantity"), format!("shadow strategy {}: base_quantity must be positive", name));
+            }
+            if strategy.contradiction_threshold < Decimal::ZERO || strategy.spread_threshold < Decimal::ZERO {
+                problem(&section, None, format!("shadow strategy {}: thresholds cannot be negative", name));
+            }
+        }
+        let thresholds = &self.regime.thresholds;
+        let rising = [
+            ("unprovable_entropy", thresholds.unprovable_entropy),
+            ("disorderly_volatility", thresholds.disorderly_volatility),
+            ("volatile_volatility", thresholds.volatile_volatility),
+            ("trending_trend", thresholds.trending_trend),
+        ];
+        for (key, band) in rising {
+            if band.exit > band.enter {
+                problem("regime", Some(key), format!("{} exit ({}) must not be above enter ({})", key, band.exit, band.enter));
+            }
+        }
+        if thresholds.quiet_volatility.exit < thresholds.quiet_volatility.enter {
+            problem("regime", Some("quiet_volatility"), "quiet_volatility exit must not be below enter".to_string());
+        }
+        if thresholds.min_dwell_secs < 0 {
+            problem("regime", Some("min_dwell_secs"), "min_dwell_secs cannot be negative".to_string());
+        }
+
+        // Keys
+        if self.keys.signing_key_path.as_os_str().is_empty() {
+            problem("keys", Some("signing_key_path"), "signing_key_path must be set".to_string());
+        }
+        if self.keys.signature_validity_ms <= 0 {
+            problem("keys", Some("signature_validity_ms"), "signature_validity_ms must be positive".to_string());
+        }
+        if self.keys.clock_skew_ms < 0 {
+            problem("keys", Some("clock_skew_ms"), "clock_skew_ms cannot be negative".to_string());
+        }
+        for hex_key in &self.keys.trusted_public_keys {
+            if let Err(e) = axiom_core::parse_public_key(hex_key) {
+                problem("keys", Some("trusted_public_keys"), format!("trusted key {}: {}", hex_key, e));
+            }
+        }
+
+        // Alert sinks
+        if let Err(e) = self.alert_thresholds.validate() {
+            problem("alert_thresholds", None, e.to_string());
+        }
+        if self.email.enabled {
+            if self.email.host.is_empty() {
+                problem("email", Some("host"), "email is enabled but host is empty".to_string());
+            }
+            if self.email.recipients.get(&AlertSeverity::Critical).is_none_or(Vec::is_empty) {
+                problem("email.recipients", Some("critical"), "email is enabled but has no critical recipients".to_string());
+            }
+        }
+        if self.control.enabled {
+            match self.control.transport {
+                ControlTransport::Unix if self.control.socket_path.as_os_str().is_empty() => {
+                    problem("control", Some("socket_path"), "socket_path must be set for the unix transport".to_string());
+                }
+                ControlTransport::Tcp => match self.control.bind.parse::<SocketAddr>() {
+                    Ok(addr) if !addr.ip().is_loopback() => {
+                        problem("control", Some("bind"), format!("bind {} is not a loopback address", addr));
+                    }
+                    Ok(_) => {}
+                    Err(e) => problem("control", Some("bind"), format!("bind {}: {}", self.control.bind, e)),
+                },
+                ControlTransport::Unix => {}
+            }
+        }
+        let incidents = &self.incidents;
+        if incidents.escalation_initial_min <= 0 || incidents.escalation_factor < 1
+            || incidents.escalation_max_min < incidents.escalation_initial_min
+        {
+            problem("incidents", None, "escalation needs initial > 0, factor >= 1, and max >= initial".to_string());
+        }
+        if self.live_stream.enabled {
+            if self.live_stream.token.is_empty() {
+                problem("live_stream", Some("token"), "live_stream is enabled but token is empty".to_string());
+            }
+            if let Err(e) = self.live_stream.bind.parse::<SocketAddr>() {
+                problem("live_stream", Some("bind"), format!("bind {}: {}", self.live_stream.bind, e));
+            }
+        }
+
+        problems
+    }
+
+    /// Built-in limits overlaid with the configured ones; symbols without a
+    /// risk budget override take the [risk] budget, [risk] max_leverage
+    /// caps portfolio leverage, [allocations] budget the strategies, and
+    /// [edge] sets the expected-edge floor
+    pub fn limits(&self) -> LimitsRegistry {
+        let mut registry = LimitsRegistry::builtin()
+            .with_max_leverage(self.risk.max_leverage)
+            .with_edge_policy(self.edge.clone());
+        for (symbol, limits) in &self.limits {
+            registry = registry.with_symbol(Symbol(symbol.clone()), limits.clone());
+        }
+        for (name, account) in &self.accounts {
+            registry = registry.with_account(AccountId(name.clone()), account.limits.clone());
+        }
+        for (strategy, fraction) in &self.allocations {
+            registry = registry.with_allocation(StrategyId(strategy.clone()), *fraction);
+        }
+        let symbols: Vec<Symbol> = registry.symbols().cloned().collect();
+        for symbol in symbols {
+            if let Ok(limits) = registry.get(&symbol) {
+                let mut limits = limits.clone();
+                limits.min_risk_budget.get_or_insert(self.risk.min_risk_budget);
+                limits.max_risk_budget.get_or_insert(self.risk.max_risk_budget);
+                registry = registry.with_symbol(symbol, limits);
+            }
+        }
+        registry
+    }
+
+    /// Built-in feed mappings with the configured ones applied; names that
+    /// do not parse are skipped (validation reports them)
+    pub fn schemas(&self) -> SchemaRegistry {
+        self.venues.schemas.iter()
+            .filter_map(|(name, schema)| Some((name.parse::<Venue>().ok()?, schema.clone())))
+            .fold(SchemaRegistry::builtin(), |registry, (venue, schema)| registry.with_schema(venue, schema))
+    }
+
+    /// The built-in symbol table with each venue schema's `symbols`, plus
+    /// every enabled symbol under its own name on the paper venue
+    pub fn symbol_mapper(&self) -> SymbolMapper {
+        let mut symbols = self.schemas().symbol_mapper();
+        symbols.extend(&Venue::Paper, self.symbols().into_iter().map(|symbol| (symbol.0.clone(), symbol)));
+        symbols
+    }
+
+    /// Configured venues (primary first) with their environments; names
+    /// that do not parse are skipped (validation reports them)
+    pub fn venues(&self) -> Vec<(Venue, VenueEnvironment)> {
+        std::iter::once(&self.venues.primary)
+            .chain(&self.venues.secondary)
+            .filter_map(|name| name.parse::<Venue>().ok())
+            .map(|venue| {
+                let environment = match venue {
+                    Venue::Paper => VenueEnvironment::Paper,
+                    _ => self.venues.environments.get(venue.name()).copied().unwrap_or_default(),
+                };
+                (venue, environment)
+            })
+            .collect()
+    }
+
+    /// Fee rates per venue: the configured ones over the built-in defaults
+    pub fn fee_model(&self) -> FeeModel {
+        self.fees.iter()
+            .filter_map(|(name, fees)| Some((name.parse::<Venue>().ok()?, *fees)))
+            .fold(FeeModel::new(), |model, (venue, fees)| model.with_venue(venue, fees))
+    }
+
+    pub fn symbols(&self) -> Vec<Symbol> {
+        self.symbols.enabled.iter().cloned().map(Symbol).collect()
+    }
+
+    /// Accounts with their starting equity: the configured ones, or the
+    /// default account holding [system] initial_equity
+    pub fn accounts(&self) -> Vec<(AccountId, Amount)> {
+        if self.accounts.is_empty() {
+            return vec![(AccountId::default(), Amount::new(self.system.initial_equity))];
+        }
+        self.accounts.iter()
+            .map(|(name, account)| (AccountId(name.clone()), Amount::new(account.initial_equity)))
+            .collect()
+    }
+
+    /// Account each symbol is traded for (empty without configured accounts)
+    pub fn account_routes(&self) -> HashMap<Symbol, AccountId> {
+        self.accounts.iter()
+            .flat_map(|(name, account)| account.symbols.iter().map(move |symbol| (Symbol(symbol.clone()), AccountId(name.clone()))))
+            .collect()
+    }
+
+    pub fn key_settings(&self) -> KeySettings {
+        KeySettings {
+            path: self.keys.signing_key_path.clone(),
+            passphrase_env: self.keys.passphrase_env.clone(),
+            trusted_public_keys: self.keys.trusted_public_keys.clone(),
+            signature_validity: chrono::Duration::milliseconds(self.keys.signature_validity_ms),
+            clock_skew: chrono::Duration::milliseconds(self.keys.clock_skew_ms),
+        }
+    }
+
+    /// SMTP sink settings, when email is enabled (password read from the environment)
+    pub fn smtp_config(&self) -> Option<SmtpConfig> {
+        let email = &self.email;
+        email.enabled.then(|| SmtpConfig {
+            host: email.host.clone(),
+            port: email.port,
+            tls: email.tls,
+            from: email.from.clone(),
+            username: email.username.clone(),
+            password: std::env::var(&email.password_env).ok(),
+            recipients: email.recipients.clone(),
+            digest_interval: Duration::from_secs(email.digest_interval_min * 60),
+            max_attempts: email.max_attempts,
+            retry_backoff: Duration::from_secs(email.retry_backoff_sec),
+            incident_url_base: (!email.incident_url_base.is_empty()).then(|| email.incident_url_base.clone()),
+        })
+    }
+
+    /// Re-notification schedule for unacknowledged Critical incidents
+    pub fn escalation_policy(&self) -> EscalationPolicy {
+        EscalationPolicy {
+            initial: chrono::Duration::minutes(self.incidents.escalation_initial_min),
+            factor: self.incidents.escalation_factor,
+            max: chrono::Duration::minutes(self.incidents.escalation_max_min),
+        }
+    }
+
+    /// How often to poll funding and open interest, when enabled
+    pub fn derivatives_poll_interval(&self) -> Option<Duration> {
+        self.derivatives.enabled.then(|| Duration::from_secs(self.derivatives.poll_interval_sec))
+    }
+
+    /// Window liquidation intensity is summed over
+    pub fn liquidation_window(&self) -> Duration {
+        Duration::from_secs(self.liquidations.window_sec)
+    }
+
+    /// Capture settings, when recording is enabled
+    pub fn recorder_settings(&self) -> Option<RecorderSettings> {
+        self.recorder.enabled.then(|| self.recorder.settings.clone())
+    }
+
+    /// Live stream settings, when enabled
+    pub fn live_stream_config(&self) -> Option<LiveStreamConfig> {
+        let stream = &self.live_stream;
+        let bind = stream.bind.parse().ok()?;
+        stream.enabled.then(|| LiveStreamConfig {
+            bind,
+            token: stream.token.clone(),
+            health_interval: Duration::from_millis(stream.health_interval_ms),
+            portfolio_interval: Duration::from_millis(stream.portfolio_interval_ms),
+            client_buffer: stream.client_buffer,
+        })
+    }
+}
+
+/// 1-based line of `key` inside `[section]`, or of the section header when
+/// `key` is None
+fn locate(source: &str, section: &str, key: Option<&str>) -> Option<usize> {
+    let mut current = String::new();
+    for (idx, line) in source.lines().enumerate() {
+        let line = line.trim();
+        if let Some(header) = line.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) {
+            current = header.trim().to_string();
+            if current == section && key.is_none() {
+                return Some(idx + 1);
+            }
+            continue;
+        }
+        if current != section {
+            continue;
+        }
+        if let Some(key) = key {
+            let bare = line.trim_start_matches('"');
+            if let Some(rest) = bare.strip_prefix(key) {
+                if rest.trim_start_matches('"').trim_start().starts_with('=') {
+                    return Some(idx + 1);
+                }
+            }
+        }
+    }
+    // Fall back to the section header
+    key.and_then(|_| locate(source, section, None))
+}
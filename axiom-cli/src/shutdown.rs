@@ -0,0 +1,184 @@
+//! Shutdown: Ordered Teardown of the Trading Pipeline
+//!
+//! On Ctrl-C the pipeline is wound down in a fixed order: stop taking new
+//! proposals and orders, let in-flight orders reach the venue (up to a
+//! deadline), cancel every resting order, optionally flatten open positions,
+//! persist the portfolio (and, in a dry run, the orders it would have
+//! placed), and finally flush the snapshot, event log, and audit log. Every
+//! step runs even if an earlier one failed; the report says which did not
+//! complete, and the process exit code reflects it.
+
+use crate::runtime::portfolio_snapshot;
+use crate::settings::{DryRunSection, ShutdownSection};
+use axiom_core::{AuditLog, ErrorCode, EventLog, Symbol, Venue};
+use axiom_engine::SignalGenerator;
+use axiom_execution::{DryRunClient, OrderExecutor};
+use axiom_oracle::SnapshotExporter;
+use axiom_risk::PortfolioManager;
+use std::path::Path;
+use std::sync::{PoisonError, RwLock};
+use std::time::Duration;
+use tokio::sync::watch;
+use tracing::{error, info, warn};
+
+/// Outcome of one shutdown step
+#[derive(Debug, Clone)]
+pub struct StepOutcome {
+    pub step: &'static str,
+    pub completed: bool,
+    pub detail: String,
+}
+
+/// Outcome of the whole sequence, in step order
+#[derive(Debug, Clone, Default)]
+pub struct ShutdownReport {
+    pub steps: Vec<StepOutcome>,
+}
+
+impl ShutdownReport {
+    /// Whether every step completed
+    pub fn completed(&self) -> bool {
+        self.steps.iter().all(|step| step.completed)
+    }
+
+    fn record(&mut self, step: &'static str, completed: bool, detail: String) {
+        if completed {
+            info!("Shutdown {}: {}", step, detail);
+        } else {
+            error!("Shutdown {} incomplete: {}", step, detail);
+        }
+        self.steps.push(StepOutcome { step, completed, detail });
+    }
+}
+
+/// Everything the shutdown sequence touches
+pub struct ShutdownCoordinator<'a> {
+    /// Turns true to stop the background loops (snapshot, live stream, dead man's switch)
+    pub trigger: watch::Sender<bool>,
+    pub signal_generator: &'a mut SignalGenerator,
+    pub executor: &'a OrderExecutor,
+    pub portfolio: &'a RwLock<PortfolioManager>,
+    /// Markets whose resting orders are cancelled
+    pub markets: Vec<(Symbol, Venue)>,
+    pub snapshot: &'a SnapshotExporter,
+    pub event_log: &'a EventLog,
+    pub audit_log: &'a AuditLog,
+    pub settings: ShutdownSection,
+    /// Set in a dry run; its orders are summarized after the portfolio
+    pub dry_run: Option<(&'a DryRunClient, &'a DryRunSection)>,
+}
+
+impl ShutdownCoordinator<'_> {
+    /// Run every step in order
+    pub async fn run(self) -> ShutdownReport {
+        let mut report = ShutdownReport::default();
+
+        // 1. Stop intake: no new proposals, no new orders, background loops wind down
+        let _ = self.trigger.send(true);
+        self.signal_generator.stop_intake();
+        self.executor.stop_intake();
+        report.record("stop_intake", true, "proposals and new orders refused".to_string());
+
+        // 2. Drain orders already on their way to the venue
+        let deadline = Duration::from_millis(self.settings.drain_timeout_ms);
+        let drained = self.executor.drain(deadline).await;
+        report.record("drain", drained, if drained {
+            "no orders in flight".to_string()
+        } else {
+            format!("{} orders still in flight after {:?}", self.executor.in_flight(), deadline)
+        });
+
+        // 3. Cancel resting orders everywhere we trade
+        let mut failures = Vec::new();
+        for (symbol, venue) in &self.markets {
+            if let Err(e) = self.executor.cancel_all(symbol, venue).await {
+                failures.push(format!("{} on {} [{}]: {}", symbol.0, venue, e.code(), e));
+            }
+        }
+        report.record("cancel", failures.is_empty(), if failures.is_empty() {
+            format!("cancelled on {} markets", self.markets.len())
+        } else {
+            failures.join("; ")
+        });
+
+        // 4. Optionally close every open position (verified and signed like any order)
+        if self.settings.flatten_on_exit {
+            let portfolio = portfolio_snapshot(self.portfolio);
+            let mut failures = Vec::new();
+            let mut closed = 0;
+            for position in portfolio.positions.iter().filter(|position| !position.quantity.is_zero()) {
+                // Verified against the position's own account when it has one
+                let account_portfolio = self.portfolio.read().unwrap_or_else(PoisonError::into_inner)
+                    .account_portfolio(&position.account);
+                let Some(order) = self.signal_generator.propose_exit(position, account_portfolio.as_ref().unwrap_or(&portfolio)) else {
+                    failures.push(format!("{}: exit not verified", position.symbol.0));
+                    continue;
+                };
+                match self.executor.execute_exit(&order).await {
+                    Ok(_) => closed += 1,
+                    Err(e) => failures.push(format!("{} [{}]: {}", position.symbol.0, e.code(), e)),
+                }
+            }
+            report.record("flatten", failures.is_empty(), if failures.is_empty() {
+                format!("{} positions closed", closed)
+            } else {
+                failures.join("; ")
+            });
+        } else {
+            report.record("flatten", true, "disabled".to_string());
+        }
+
+        // 5. Persist the portfolio
+        let path = &self.settings.portfolio_path;
+        match self.portfolio.read().unwrap_or_else(PoisonError::into_inner).save(path) {
+            Ok(()) => report.record("persist", true, format!("portfolio written to {}", path.display())),
+            Err(e) => report.record("persist", false, format!("{}: {}", path.display(), e)),
+        }
+
+        // 5b. Summarize what a dry run would have placed
+        if let Some((client, settings)) = self.dry_run {
+            let orders = client.orders();
+            for order in &orders {
+                let signal = &order.order.signal;
+                info!("Dry run would have placed {} {:?} {} {} {:?} @ {:?} on {}",
+                    order.order_hash, signal.side, signal.quantity, signal.symbol.0,
+                    order.order_type, order.limit_price, order.venue);
+            }
+            let path = &settings.report_path;
+            match write_json(path, &orders) {
+                Ok(()) => report.record("dry_run_summary", true, format!("{} orders written to {}", orders.len(), path.display())),
+                Err(e) => report.record("dry_run_summary", false, format!("{}: {}", path.display(), e)),
+            }
+        }
+
+        // 6. Flush telemetry and logs last, so they include everything above
+        let mut failures = Vec::new();
+        if let Err(e) = self.snapshot.export("shutdown").await {
+            failures.push(format!("snapshot: {}", e));
+        }
+        if let Err(e) = self.event_log.flush() {
+            failures.push(format!("event log: {}", e));
+        }
+        if let Err(e) = self.audit_log.sync() {
+            failures.push(format!("audit log: {}", e));
+        }
+        report.record("flush", failures.is_empty(), if failures.is_empty() {
+            "snapshot, event log and audit log flushed".to_string()
+        } else {
+            failures.join("; ")
+        });
+
+        if !report.completed() {
+            warn!("Shutdown finished with incomplete steps");
+        }
+        report
+    }
+}
+
+/// Write `value` to `path` as pretty JSON, creating its directory
+fn write_json(path: &Path, value: &impl serde::Serialize) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(value).map_err(std::io::Error::other)?)
+}
@@ -0,0 +1,160 @@
+//! Verify Order: Re-Check One Order's Provenance from the Audit Log
+//!
+//! Finds every audit entry for an order hash (or its signal hash), then
+//! re-checks what can be checked after the fact: the audit chain is intact,
+//! the proof signature matches the proof, the recorded signal hash matches
+//! the signal, and the C=0 signature is valid and from a trusted key. The
+//! decision context (signal, scores, axioms, submission, fills) is printed
+//! alongside.
+
+use crate::keys::KeySettings;
+use anyhow::Context;
+use axiom_core::{
+    key_id, parse_public_key, proof_signature_matches, read_audit_log, read_public_key, signal_hash, verify_audit_chain, AuditEntry,
+    AuditRecord, CZeroSignature, VerifiedOrder,
+};
+use std::collections::BTreeSet;
+use std::path::Path;
+
+/// Result of `verify-order`
+pub enum Verification {
+    /// Every check passed
+    Valid,
+    /// The order was found but at least one check failed
+    Invalid,
+    /// No audit entry mentions the hash
+    NotFound,
+}
+
+/// Re-verify the order with `hash` (order hash or signal hash) from `audit`
+pub fn verify_order(audit: &Path, hash: &str, settings: &KeySettings) -> anyhow::Result<Verification> {
+    let entries: Vec<AuditEntry> = read_audit_log(audit)
+        .with_context(|| format!("Failed to read audit log {}", audit.display()))?
+        .into_iter()
+        .filter(|entry| entry.order_hash.as_deref() == Some(hash) || entry.signal_hash == hash)
+        .collect();
+    if entries.is_empty() {
+        println!("No audit entries for {}", hash);
+        return Ok(Verification::NotFound);
+    }
+
+    let mut failures = Vec::new();
+    let chain = verify_audit_chain(audit)?;
+    match &chain.first_broken {
+        None => println!("Audit chain: intact ({} entries)", chain.entries_verified),
+        Some(broken) => failures.push(format!("audit chain broken at {} line {}: {}", broken.file.display(), broken.line, broken.reason)),
+    }
+
+    let trusted = trusted_key_ids(settings);
+    for entry in &entries {
+        println!("#{} {} {}", entry.sequence, entry.recorded_at, stage(&entry.record));
+        match &entry.record {
+            AuditRecord::SignalProposed { signal, .. } => {
+                println!("  {} {} {:?} {} ({:?}) @ {:?}", signal.venue, signal.symbol.0, signal.side,
+                    signal.quantity, signal.order_type, signal.limit_price);
+                println!("  contradiction {} entropy {} at {}", signal.contradiction_score, signal.entropy_count, signal.timestamp);
+                if signal_hash(signal) != entry.signal_hash {
+                    failures.push(format!("entry #{}: signal hash does not match the signal", entry.sequence));
+                }
+            }
+            AuditRecord::SignalRejected { codes, reason } => println!("  [{}] {}", codes.join(", "), reason),
+            AuditRecord::OrderVerified { order } => {
+                check_proof(entry, order, &mut failures);
+            }
+            AuditRecord::OrderSigned { order, signature } => {
+                check_proof(entry, order, &mut failures);
+                check_signature(entry, order, signature, &trusted, &mut failures);
+            }
+            AuditRecord::OrderSubmitted { status } => println!("  {:?}", status),
+            AuditRecord::OrderFilled { quantity, price } => println!("  filled {} @ {}", quantity, price),
+            AuditRecord::KeyRotation { step, key_id, at } => println!("  {:?} key {} at {}", step, key_id, at),
+            AuditRecord::ControlAction { action, source, outcome } => println!("  {} by {}: {}", action, source, outcome),
+            AuditRecord::DryRunSubmission { venue, order_type, limit_price } => {
+                println!("  dry run: would have sent {:?} @ {:?} to {}", order_type, limit_price, venue)
+            }
+            AuditRecord::ShadowSignal { strategy, order, codes, .. } => match order {
+                Some(order) => {
+                    println!("  shadow {}: verified, never executed", strategy);
+                    check_proof(entry, order, &mut failures);
+                    if let Some(signature) = &order.signature {
+                        check_signature(entry, order, signature, &trusted, &mut failures);
+                    }
+                }
+                None => println!("  shadow {}: rejected [{}]", strategy, codes.join(", ")),
+            },
+        }
+    }
+
+    if failures.is_empty() {
+        println!("VALID");
+        Ok(Verification::Valid)
+    } else {
+        for failure in &failures {
+            println!("FAILED: {}", failure);
+        }
+        Ok(Verification::Invalid)
+    }
+}
+
+fn check_proof(entry: &AuditEntry, order: &VerifiedOrder, failures: &mut Vec<String>) {
+    println!("  proof {} (satisfiable: {}) verified at {}", order.proof_signature, order.proof.satisfiable, order.verified_at);
+    println!("  axioms: {}", order.proof.axioms_satisfied.join(", "));
+    if !proof_signature_matches(order) {
+        failures.push(format!("entry #{}: proof signature does not match the signal and proof", entry.sequence));
+    }
+    if !order.proof.satisfiable {
+        failures.push(format!("entry #{}: proof is not satisfiable", entry.sequence));
+    }
+    if signal_hash(&order.signal) != entry.signal_hash {
+        failures.push(format!("entry #{}: signal hash does not match the order's signal", entry.sequence));
+    }
+}
+
+fn check_signature(
+    entry: &AuditEntry,
+    order: &VerifiedOrder,
+    signature: &CZeroSignature,
+    trusted: &BTreeSet<String>,
+    failures: &mut Vec<String>,
+) {
+    println!("  signed by {} (nonce {}, expires {})", signature.key_id, signature.nonce, signature.expires_at);
+    if let Err(e) = signature.verify_integrity(order) {
+        failures.push(format!("entry #{}: C=0 signature invalid: {}", entry.sequence, e));
+    }
+    if !trusted.contains(&signature.key_id) {
+        failures.push(format!("entry #{}: key {} is not trusted", entry.sequence, signature.key_id));
+    }
+}
+
+/// Our own key (current and scheduled successor) plus the configured ones
+fn trusted_key_ids(settings: &KeySettings) -> BTreeSet<String> {
+    let mut ids = BTreeSet::new();
+    let mut next = settings.path.clone().into_os_string();
+    next.push(".next");
+    for path in [settings.path.as_path(), Path::new(&next)] {
+        if let Ok(key) = read_public_key(path) {
+            ids.insert(key_id(&key));
+        }
+    }
+    for hex_key in &settings.trusted_public_keys {
+        if let Ok(key) = parse_public_key(hex_key) {
+            ids.insert(key_id(&key));
+        }
+    }
+    ids
+}
+
+fn stage(record: &AuditRecord) -> &'static str {
+    match record {
+        AuditRecord::SignalProposed { .. } => "signal_proposed",
+        AuditRecord::SignalRejected { .. } => "signal_rejected",
+        AuditRecord::OrderVerified { .. } => "order_verified",
+        AuditRecord::OrderSigned { .. } => "order_signed",
+        AuditRecord::OrderSubmitted { .. } => "order_submitted",
+        AuditRecord::OrderFilled { .. } => "order_filled",
+        AuditRecord::KeyRotation { .. } => "key_rotation",
+        AuditRecord::ControlAction { .. } => "control_action",
+        AuditRecord::DryRunSubmission { .. } => "dry_run_submission",
+        AuditRecord::ShadowSignal { .. } => "shadow_signal",
+    }
+}
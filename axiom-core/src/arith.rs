@@ -0,0 +1,71 @@
+//! Checked Arithmetic: Panic-Free Decimal Math
+//!
+//! Decimal's operators panic on overflow and division by zero, which would
+//! take the process down mid-trade. Math over unbounded inputs (book depth,
+//! leverage, returns) goes through these helpers and propagates an
+//! `ArithmeticError` instead. Unit-typed values have their own checked
+//! operations in `units`.
+
+use crate::errors::ErrorCode;
+use crate::units::UnitError;
+use rust_decimal::Decimal;
+use serde::Serialize;
+
+/// Raw Decimal arithmetic error
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, thiserror::Error)]
+#[serde(tag = "code", content = "details")]
+pub enum ArithmeticError {
+    #[error("Decimal overflow in {0}")]
+    #[serde(rename = "ARITH_OVERFLOW")]
+    Overflow(&'static str),
+
+    #[error("Division by zero in {0}")]
+    #[serde(rename = "ARITH_DIVISION_BY_ZERO")]
+    DivisionByZero(&'static str),
+}
+
+impl ErrorCode for ArithmeticError {
+    fn code(&self) -> &'static str {
+        match self {
+            ArithmeticError::Overflow(_) => "ARITH_OVERFLOW",
+            ArithmeticError::DivisionByZero(_) => "ARITH_DIVISION_BY_ZERO",
+        }
+    }
+}
+
+impl From<ArithmeticError> for UnitError {
+    fn from(error: ArithmeticError) -> Self {
+        match error {
+            ArithmeticError::Overflow(op) => UnitError::Overflow(op),
+            ArithmeticError::DivisionByZero(op) => UnitError::DivisionByZero(op),
+        }
+    }
+}
+
+/// `a + b`; `op` names the calculation in the error
+pub fn checked_add(op: &'static str, a: Decimal, b: Decimal) -> Result<Decimal, ArithmeticError> {
+    a.checked_add(b).ok_or(ArithmeticError::Overflow(op))
+}
+
+/// `a - b`
+pub fn checked_sub(op: &'static str, a: Decimal, b: Decimal) -> Result<Decimal, ArithmeticError> {
+    a.checked_sub(b).ok_or(ArithmeticError::Overflow(op))
+}
+
+/// `a × b`
+pub fn checked_mul(op: &'static str, a: Decimal, b: Decimal) -> Result<Decimal, ArithmeticError> {
+    a.checked_mul(b).ok_or(ArithmeticError::Overflow(op))
+}
+
+/// `a ÷ b`; a zero `b` is an error, never a panic
+pub fn checked_div(op: &'static str, a: Decimal, b: Decimal) -> Result<Decimal, ArithmeticError> {
+    if b.is_zero() {
+        return Err(ArithmeticError::DivisionByZero(op));
+    }
+    a.checked_div(b).ok_or(ArithmeticError::Overflow(op))
+}
+
+/// Sum of `values`
+pub fn checked_sum(op: &'static str, values: impl IntoIterator<Item = Decimal>) -> Result<Decimal, ArithmeticError> {
+    values.into_iter().try_fold(Decimal::ZERO, |total, value| checked_add(op, total, value))
+}
@@ -0,0 +1,656 @@
+//! Audit Log: Tamper-Evident Order Provenance
+//!
+//! Every stage of an order's life (signal → proof → signature → submission → fill)
+//! is appended to a hash-chained, append-only log. Each entry commits to the
+//! digest of its predecessor, so modifying or removing any entry breaks the chain.
+//!
+//! The log can rotate once its file passes a size: the full file is renamed
+//! after the sequence of its first entry (`audit.jsonl.1024`) and the chain
+//! carries on in a fresh file, so the segments, oldest first, followed by
+//! the live file form one chain. `read_audit_log` reads them back in order
+//! and `signal_trails` regroups the entries into each signal's history.
+//! Components write through `SignalJournal`, which `MemoryJournal` also
+//! implements for runs that keep nothing on disk.
+
+use crate::types::*;
+use crate::signature::*;
+use crate::keys::RotationStep;
+use crate::shadow::FeatureVector;
+use serde::{Deserialize, Serialize};
+use crate::canonical::canonical_digest;
+use chrono::{DateTime, Utc};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing::warn;
+
+/// Digest preceding the first entry of a chain
+pub const GENESIS_DIGEST: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// A single stage in the order lifecycle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "stage", rename_all = "snake_case")]
+pub enum AuditRecord {
+    /// Proposer emitted a signal, with the features it was made from (none
+    /// for exits, or in entries written before features were journaled)
+    SignalProposed {
+        signal: TradeSignal,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        features: Option<FeatureVector>,
+    },
+    /// Verifier rejected the signal; `codes` lists every violated invariant
+    /// (empty in entries written before error codes)
+    SignalRejected {
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        codes: Vec<String>,
+        reason: String,
+    },
+    /// Verifier proved the signal satisfies the L0 contract
+    OrderVerified { order: VerifiedOrder },
+    /// C=0 signature attached to the verified order
+    OrderSigned { order: VerifiedOrder, signature: CZeroSignature },
+    /// Order handed to the exchange
+    OrderSubmitted { status: OrderStatus },
+    /// Execution report from the exchange
+    OrderFilled { quantity: Quantity, price: Price },
+    /// Signing key rotation step (system record; empty signal hash)
+    KeyRotation { step: RotationStep, key_id: String, at: DateTime<Utc> },
+    /// Operator action on the control endpoint (system record; empty signal hash)
+    ControlAction { action: String, source: String, outcome: String },
+    /// Order a dry run would have handed to the exchange (never sent)
+    DryRunSubmission { venue: Venue, order_type: OrderType, limit_price: Option<Price> },
+    /// A shadow strategy's decision (never executed); `order` is the
+    /// verified and signed order, None with `codes` when rejected
+    ShadowSignal {
+        strategy: String,
+        signal: TradeSignal,
+        order: Option<VerifiedOrder>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        codes: Vec<String>,
+    },
+}
+
+/// A chained audit entry as written to disk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub sequence: u64,
+    /// Hash of the originating TradeSignal (stable across every stage)
+    pub signal_hash: String,
+    /// Hash of the VerifiedOrder, once one exists
+    pub order_hash: Option<String>,
+    pub record: AuditRecord,
+    pub recorded_at: DateTime<Utc>,
+    /// Digest of the previous entry (GENESIS_DIGEST for the first)
+    pub prev_digest: String,
+    /// Digest of this entry, covering every field above
+    pub digest: String,
+}
+
+impl AuditEntry {
+    /// Compute the chained digest for this entry's contents
+    ///
+    /// Covers the canonical encoding of every field but the digest itself,
+    /// including `prev_digest`, so each entry commits to its predecessor.
+    pub fn compute_digest(&self) -> Result<String, AuditError> {
+        Ok(canonical_digest("audit", self))
+    }
+}
+
+/// Destination for the audit records of signals and orders
+pub trait SignalJournal: Send + Sync {
+    /// Chain `record` after the last entry and keep it
+    fn append(&self, signal_hash: &str, order_hash: Option<&str>, record: AuditRecord) -> Result<AuditEntry, AuditError>;
+}
+
+/// The entry following one with `sequence - 1` and digest `prev_digest`
+fn chain_entry(
+    sequence: u64,
+    prev_digest: &str,
+    signal_hash: &str,
+    order_hash: Option<&str>,
+    record: AuditRecord,
+) -> Result<AuditEntry, AuditError> {
+    let mut entry = AuditEntry {
+        sequence,
+        signal_hash: signal_hash.to_string(),
+        order_hash: order_hash.map(str::to_string),
+        record,
+        recorded_at: Utc::now(),
+        prev_digest: prev_digest.to_string(),
+        digest: String::new(),
+    };
+    entry.digest = entry.compute_digest()?;
+    Ok(entry)
+}
+
+/// Append-only, hash-chained audit log
+///
+/// Each append is flushed before returning, so a crash loses at most the
+/// entry being written at that moment.
+pub struct AuditLog {
+    path: PathBuf,
+    writer: Mutex<ChainWriter>,
+    /// Rotate once the live file reaches this size; None never rotates
+    max_bytes: Option<u64>,
+}
+
+struct ChainWriter {
+    file: File,
+    next_sequence: u64,
+    last_digest: String,
+    /// Sequence of the live file's first entry, naming it once rotated
+    first_sequence: u64,
+    /// Size of the live file
+    bytes: u64,
+}
+
+impl AuditLog {
+    /// Open (or create) an audit log, resuming the chain from its last
+    /// complete entry (in the newest rotated segment when the live file has
+    /// none)
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, AuditError> {
+        let path = path.as_ref().to_path_buf();
+
+        let live = read_chain_file(&path, true)?;
+        let (next_sequence, last_digest) = match live.last() {
+            Some(entry) => (entry.sequence + 1, entry.digest.clone()),
+            None => match audit_segments(&path)?.last() {
+                Some(segment) => match read_chain_file(segment, false)?.last() {
+                    Some(entry) => (entry.sequence + 1, entry.digest.clone()),
+                    None => (0, GENESIS_DIGEST.to_string()),
+                },
+                None => (0, GENESIS_DIGEST.to_string()),
+            },
+        };
+        let first_sequence = live.first().map_or(next_sequence, |entry| entry.sequence);
+
+        let mut file = OpenOptions::new().create(true).read(true).append(true).open(&path)?;
+        let bytes = truncate_torn_tail(&mut file, &path)?;
+
+        Ok(Self {
+            path,
+            writer: Mutex::new(ChainWriter {
+                file,
+                next_sequence,
+                last_digest,
+                first_sequence,
+                bytes,
+            }),
+            max_bytes: None,
+        })
+    }
+
+    /// Rotate the live file into a segment once it reaches `max_bytes`
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Append a record to the chain
+    pub fn append(
+        &self,
+        signal_hash: &str,
+        order_hash: Option<&str>,
+        record: AuditRecord,
+    ) -> Result<AuditEntry, AuditError> {
+        let mut writer = self.writer.lock().map_err(|_| AuditError::Poisoned)?;
+
+        let entry = chain_entry(writer.next_sequence, &writer.last_digest, signal_hash, order_hash, record)?;
+
+        let mut line = serde_json::to_string(&entry)
+            .map_err(|e| AuditError::Serialization(e.to_string()))?;
+        line.push('\n');
+
+        writer.file.write_all(line.as_bytes())?;
+        writer.file.flush()?;
+
+        writer.next_sequence += 1;
+        writer.last_digest = entry.digest.clone();
+        writer.bytes += line.len() as u64;
+
+        if self.max_bytes.is_some_and(|max_bytes| writer.bytes >= max_bytes) {
+            // The entry is written either way; a failed rotation is retried
+            // on the next append
+            if let Err(e) = self.rotate(&mut writer) {
+                warn!("Audit log {} not rotated: {}", self.path.display(), e);
+            }
+        }
+
+        Ok(entry)
+    }
+
+    /// Move the live file aside as a segment and continue the chain in a
+    /// fresh one
+    fn rotate(&self, writer: &mut ChainWriter) -> Result<(), AuditError> {
+        writer.file.sync_all()?;
+        std::fs::rename(&self.path, segment_path(&self.path, writer.first_sequence))?;
+        writer.file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        writer.first_sequence = writer.next_sequence;
+        writer.bytes = 0;
+        Ok(())
+    }
+
+    /// Force every appended entry to stable storage
+    pub fn sync(&self) -> Result<(), AuditError> {
+        let writer = self.writer.lock().map_err(|_| AuditError::Poisoned)?;
+        writer.file.sync_all()?;
+        Ok(())
+    }
+
+    /// Path of the underlying file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl SignalJournal for AuditLog {
+    fn append(&self, signal_hash: &str, order_hash: Option<&str>, record: AuditRecord) -> Result<AuditEntry, AuditError> {
+        AuditLog::append(self, signal_hash, order_hash, record)
+    }
+}
+
+/// A chain kept in memory only, for runs and tools that write nothing to disk
+#[derive(Debug, Default)]
+pub struct MemoryJournal {
+    entries: Mutex<Vec<AuditEntry>>,
+}
+
+impl MemoryJournal {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every entry so far, in order
+    pub fn entries(&self) -> Vec<AuditEntry> {
+        self.entries.lock().unwrap_or_else(std::sync::PoisonError::into_inner).clone()
+    }
+}
+
+impl SignalJournal for MemoryJournal {
+    fn append(&self, signal_hash: &str, order_hash: Option<&str>, record: AuditRecord) -> Result<AuditEntry, AuditError> {
+        let mut entries = self.entries.lock().map_err(|_| AuditError::Poisoned)?;
+        let (sequence, prev_digest) = match entries.last() {
+            Some(last) => (last.sequence + 1, last.digest.as_str()),
+            None => (0, GENESIS_DIGEST),
+        };
+        let entry = chain_entry(sequence, prev_digest, signal_hash, order_hash, record)?;
+        entries.push(entry.clone());
+        Ok(entry)
+    }
+}
+
+/// Where the live file starting at `first_sequence` goes when rotated
+fn segment_path(path: &Path, first_sequence: u64) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}", first_sequence));
+    path.with_file_name(name)
+}
+
+/// The rotated segments of the log at `path`, oldest first
+pub fn audit_segments(path: impl AsRef<Path>) -> Result<Vec<PathBuf>, AuditError> {
+    let path = path.as_ref();
+    let Some(name) = path.file_name().and_then(|name| name.to_str()) else { return Ok(Vec::new()) };
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let listing = match std::fs::read_dir(dir) {
+        Ok(listing) => listing,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    let mut segments: Vec<(u64, PathBuf)> = Vec::new();
+    for item in listing {
+        let item = item?;
+        let file_name = item.file_name();
+        let Some(first_sequence) = file_name.to_str()
+            .and_then(|file_name| file_name.strip_prefix(name)?.strip_prefix('.')?.parse::<u64>().ok())
+        else {
+            continue;
+        };
+        segments.push((first_sequence, item.path()));
+    }
+    segments.sort();
+    Ok(segments.into_iter().map(|(_, segment)| segment).collect())
+}
+
+/// The segments then the live file: the whole chain, in order
+fn chain_files(path: &Path) -> Result<Vec<PathBuf>, AuditError> {
+    let mut files = audit_segments(path)?;
+    files.push(path.to_path_buf());
+    Ok(files)
+}
+
+/// Cut a torn trailing line (a crash mid-append) off the live file, so the
+/// next entry starts on a line of its own; returns the length kept
+fn truncate_torn_tail(file: &mut File, path: &Path) -> Result<u64, AuditError> {
+    let len = file.metadata()?.len();
+    let mut complete = 0;
+    let mut end = len;
+    let mut chunk = [0u8; 4096];
+    while end > 0 {
+        let start = end.saturating_sub(chunk.len() as u64);
+        let buf = &mut chunk[..(end - start) as usize];
+        file.seek(SeekFrom::Start(start))?;
+        file.read_exact(buf)?;
+        if let Some(newline) = buf.iter().rposition(|byte| *byte == b'\n') {
+            complete = start + newline as u64 + 1;
+            break;
+        }
+        end = start;
+    }
+    if complete < len {
+        warn!("Truncating {} torn bytes off the end of {}", len - complete, path.display());
+        file.set_len(complete)?;
+    }
+    Ok(complete)
+}
+
+/// The complete entries of one file; a missing file has none when
+/// `missing_ok`
+fn read_chain_file(path: &Path, missing_ok: bool) -> Result<Vec<AuditEntry>, AuditError> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if missing_ok && e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    let mut entries = Vec::new();
+    for line in BufReader::new(file).lines() {
+        // A torn trailing line from a crash is not part of the chain
+        if let Ok(entry) = serde_json::from_str::<AuditEntry>(&line?) {
+            entries.push(entry);
+        }
+    }
+    Ok(entries)
+}
+
+/// Every complete entry of the log at `path`, rotated segments included,
+/// in chain order (links are not checked; see `verify_audit_chain`)
+pub fn read_audit_log(path: impl AsRef<Path>) -> Result<Vec<AuditEntry>, AuditError> {
+    let path = path.as_ref();
+    let mut entries = Vec::new();
+    for file in audit_segments(path)? {
+        entries.extend(read_chain_file(&file, false)?);
+    }
+    entries.extend(read_chain_file(path, false)?);
+    Ok(entries)
+}
+
+/// One signal's entries, from proposal to its last fill
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignalTrail {
+    pub signal_hash: String,
+    /// In chain order
+    pub entries: Vec<AuditEntry>,
+}
+
+impl SignalTrail {
+    /// The signal as proposed
+    pub fn signal(&self) -> Option<&TradeSignal> {
+        self.entries.iter().find_map(|entry| match &entry.record {
+            AuditRecord::SignalProposed { signal, .. } => Some(signal),
+            _ => None,
+        })
+    }
+
+    /// The features it was proposed on
+    pub fn features(&self) -> Option<&FeatureVector> {
+        self.entries.iter().find_map(|entry| match &entry.record {
+            AuditRecord::SignalProposed { features, .. } => features.as_ref(),
+            _ => None,
+        })
+    }
+
+    /// The verified order, with its proof
+    pub fn verified(&self) -> Option<&VerifiedOrder> {
+        self.entries.iter().find_map(|entry| match &entry.record {
+            AuditRecord::OrderVerified { order } => Some(order),
+            _ => None,
+        })
+    }
+
+    /// The signed order and its C=0 signature
+    pub fn signed(&self) -> Option<(&VerifiedOrder, &CZeroSignature)> {
+        self.entries.iter().find_map(|entry| match &entry.record {
+            AuditRecord::OrderSigned { order, signature } => Some((order, signature)),
+            _ => None,
+        })
+    }
+
+    /// The verifier's violation codes and reason, when rejected
+    pub fn rejection(&self) -> Option<(&[String], &str)> {
+        self.entries.iter().find_map(|entry| match &entry.record {
+            AuditRecord::SignalRejected { codes, reason } => Some((codes.as_slice(), reason.as_str())),
+            _ => None,
+        })
+    }
+}
+
+/// `entries` regrouped by signal, in order of each signal's first entry;
+/// system records (no signal hash) are left out
+pub fn signal_trails(entries: impl IntoIterator<Item = AuditEntry>) -> Vec<SignalTrail> {
+    let mut trails: Vec<SignalTrail> = Vec::new();
+    let mut index: std::collections::HashMap<String, usize> = std::collections::HashMap::new();
+    for entry in entries {
+        if entry.signal_hash.is_empty() {
+            continue;
+        }
+        match index.get(&entry.signal_hash) {
+            Some(&at) => trails[at].entries.push(entry),
+            None => {
+                index.insert(entry.signal_hash.clone(), trails.len());
+                trails.push(SignalTrail { signal_hash: entry.signal_hash.clone(), entries: vec![entry] });
+            }
+        }
+    }
+    trails
+}
+
+/// Outcome of replaying an audit chain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChainVerification {
+    /// Entries whose link and signatures checked out
+    pub entries_verified: u64,
+    /// The first broken link, if any
+    pub first_broken: Option<BrokenLink>,
+}
+
+impl ChainVerification {
+    pub fn is_intact(&self) -> bool {
+        self.first_broken.is_none()
+    }
+}
+
+/// Location and cause of a chain break
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BrokenLink {
+    /// File of the chain holding the entry (a rotated segment or the live
+    /// file)
+    #[serde(default)]
+    pub file: PathBuf,
+    /// 1-based line number in that file
+    pub line: u64,
+    pub reason: String,
+}
+
+/// A signed order awaiting batch verification, and where it is
+struct PendingSignature {
+    /// Entries before it in the chain
+    position: u64,
+    file: PathBuf,
+    line: u64,
+    order: VerifiedOrder,
+    signature: CZeroSignature,
+}
+
+/// Replay an audit log, checking every link and re-verifying every C=0 signature
+///
+/// Rotated segments are replayed first, oldest first, then the live file.
+/// Stops at the first broken link. A torn final line (crash mid-write) is
+/// reported as a break so it is never silently accepted. Signatures are
+/// verified in batches (see `verify_batch`); a bad signature is still
+/// reported at its own line, ahead of any later break.
+pub fn verify_audit_chain(path: impl AsRef<Path>) -> Result<ChainVerification, AuditError> {
+    let mut expected_prev = GENESIS_DIGEST.to_string();
+    let mut expected_sequence = 0u64;
+    let mut verified = 0u64;
+    let mut pending: Vec<PendingSignature> = Vec::new();
+
+    for file in chain_files(path.as_ref())? {
+        let reader = BufReader::new(File::open(&file)?);
+        for (idx, line) in reader.lines().enumerate() {
+            let line_no = idx as u64 + 1;
+            let line = line?;
+
+            // A break here only counts if every earlier signature holds
+            let mut broken = |reason: String| verify_pending(&mut pending).unwrap_or(ChainVerification {
+                entries_verified: verified,
+                first_broken: Some(BrokenLink { file: file.clone(), line: line_no, reason }),
+            });
+
+            let entry: AuditEntry = match serde_json::from_str(&line) {
+                Ok(entry) => entry,
+                Err(e) => return Ok(broken(format!("Unparseable entry: {}", e))),
+            };
+
+            if entry.sequence != expected_sequence {
+                return Ok(broken(format!(
+                    "Sequence {} out of order (expected {})",
+                    entry.sequence, expected_sequence
+                )));
+            }
+
+            if entry.prev_digest != expected_prev {
+                return Ok(broken("Previous digest does not match chain".to_string()));
+            }
+
+            if entry.compute_digest()? != entry.digest {
+                return Ok(broken("Entry digest mismatch (contents altered)".to_string()));
+            }
+
+            if let AuditRecord::OrderSigned { order, signature } = entry.record {
+                if !signature.covers(&order) {
+                    return Ok(broken("Signature order hash does not match order".to_string()));
+                }
+                pending.push(PendingSignature { position: verified, file: file.clone(), line: line_no, order, signature });
+            }
+
+            expected_prev = entry.digest;
+            expected_sequence += 1;
+            verified += 1;
+
+            if pending.len() >= SIGNATURE_BATCH_SIZE {
+                if let Some(earlier) = verify_pending(&mut pending) {
+                    return Ok(earlier);
+                }
+            }
+        }
+    }
+
+    Ok(verify_pending(&mut pending).unwrap_or(ChainVerification {
+        entries_verified: verified,
+        first_broken: None,
+    }))
+}
+
+/// Batch-verify and clear the pending signatures; the first failure, if any,
+/// as a chain break at its line
+fn verify_pending(pending: &mut Vec<PendingSignature>) -> Option<ChainVerification> {
+    let items: Vec<(&VerifiedOrder, &CZeroSignature)> = pending.iter()
+        .map(|pending| (&pending.order, &pending.signature))
+        .collect();
+    let failure = verify_batch(&items)
+        .into_iter()
+        .zip(pending.iter())
+        .find_map(|(result, pending)| result.err().map(|e| (pending, e)))
+        .map(|(pending, e)| ChainVerification {
+            // Entries are consecutive, so everything before the bad one verified
+            entries_verified: pending.position,
+            first_broken: Some(BrokenLink {
+                file: pending.file.clone(),
+                line: pending.line,
+                reason: format!("C=0 signature invalid: {}", e),
+            }),
+        });
+    pending.clear();
+    failure
+}
+
+/// Audit log error
+#[derive(Debug, thiserror::Error)]
+pub enum AuditError {
+    #[error("Audit log I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Audit serialization error: {0}")]
+    Serialization(String),
+
+    #[error("Audit log writer poisoned")]
+    Poisoned,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keys::CZeroKey;
+    use crate::testing;
+    use chrono::Duration;
+
+    #[test]
+    fn replay_names_the_entry_with_a_bad_signature() {
+        let key = CZeroKey::generate();
+        let journal = MemoryJournal::new();
+        for index in 0..5 {
+            let order = testing::order();
+            let mut signature = key.sign(&order, Duration::minutes(1)).unwrap();
+            if index == 3 {
+                // Chained over as written, so only the signature check can catch it
+                signature.signature[0] ^= 0x01;
+            }
+            journal.append("signal", None, AuditRecord::OrderSigned { order, signature }).unwrap();
+        }
+        let path = std::env::temp_dir().join(format!("axiom-audit-bad-signature-{}.jsonl", std::process::id()));
+        let lines: Vec<String> = journal.entries().iter().map(|entry| serde_json::to_string(entry).unwrap()).collect();
+        std::fs::write(&path, lines.join("\n") + "\n").unwrap();
+
+        let verification = verify_audit_chain(&path);
+        std::fs::remove_file(&path).unwrap();
+        let verification = verification.unwrap();
+
+        // Entry 3 is on line 4; the three before it verified
+        let broken = verification.first_broken.expect("chain reported intact");
+        assert_eq!((broken.file, broken.line), (path, 4));
+        assert!(broken.reason.starts_with("C=0 signature invalid"), "{}", broken.reason);
+        assert_eq!(verification.entries_verified, 3);
+    }
+
+    #[test]
+    fn reopening_after_a_torn_append_truncates_it() {
+        let path = std::env::temp_dir().join(format!("axiom-audit-torn-tail-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let proposed = || AuditRecord::SignalProposed { signal: testing::signal(), features: None };
+        let log = AuditLog::open(&path).unwrap();
+        for _ in 0..3 {
+            log.append("signal", None, proposed()).unwrap();
+        }
+        drop(log);
+        // A crash partway through writing the fourth entry
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(br#"{"sequence":3,"timestamp":"2026-01-02T03:"#).unwrap();
+        drop(file);
+
+        let log = AuditLog::open(&path).unwrap();
+        log.append("signal", None, proposed()).unwrap();
+        drop(log);
+        let verification = verify_audit_chain(&path);
+        let entries = read_audit_log(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        let verification = verification.unwrap();
+        assert!(verification.first_broken.is_none(), "{:?}", verification.first_broken);
+        assert_eq!(verification.entries_verified, 4);
+        let sequences: Vec<u64> = entries.unwrap().iter().map(|entry| entry.sequence).collect();
+        assert_eq!(sequences, vec![0, 1, 2, 3]);
+    }
+}
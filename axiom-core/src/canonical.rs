@@ -0,0 +1,770 @@
+//! Canonical Encoding: Deterministic Bytes for Hashing and Signing
+//!
+//! Anything that is hashed or signed (signals, proofs, verified orders, C=0
+//! signatures, audit entries) is encoded with this layout rather than serde, so
+//! the same logical value produces the same bytes in every process and every
+//! release. The layout is a strict subset of JSON:
+//!
+//! - objects with keys in ascending byte order and no whitespace
+//! - Decimals as strings, normalized (no trailing zeros, no negative zero)
+//! - timestamps as RFC 3339 UTC strings with nanosecond precision
+//! - byte strings as lowercase hex
+//! - enum variants as their name, or `{"Name":payload}` when they carry data
+//! - map entries sorted by key; sequences keep their order
+//!
+//! Every digest and signed message is prefixed with the layout version and a
+//! domain tag, so an order hash can never collide with a signal hash and a
+//! layout change can never verify against bytes written by an older one.
+//!
+//! ```text
+//! {"contradiction_score":"0.5","entropy_count":"2","limit_price":"50000.1",...}
+//! ```
+//!
+//! The same values also have a compact binary layout (version 2), used on the
+//! signing hot path (C=0 signatures and order hashes). Every value starts with
+//! a one-byte type tag; integers are big-endian:
+//!
+//! - null `00`; bool `01 b`; u64 `02` + 8 bytes
+//! - string `03` + u32 length + UTF-8; byte string `06` + u32 length + bytes
+//! - Decimal `04` + i128 mantissa + u8 scale (normalized)
+//! - timestamp `05` + i64 seconds + u32 nanoseconds since the Unix epoch
+//! - sequence `07` + u32 count + items
+//! - object `08` + (u32 key length + key + value)* + `09`, keys ascending
+
+use crate::audit::{AuditEntry, AuditRecord};
+use crate::keys::RotationStep;
+use crate::shadow::FeatureVector;
+use crate::signature::CZeroSignature;
+use crate::types::*;
+use chrono::{DateTime, SecondsFormat, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Sha3_256};
+use std::collections::HashMap;
+use std::io::Write as _;
+
+/// Version of the canonical JSON layout; bump on any change to an encoding below
+pub const CANONICAL_VERSION: u32 = 1;
+
+/// Version of the canonical binary layout
+pub const BINARY_CANONICAL_VERSION: u32 = 2;
+
+/// Which canonical layout a message uses
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Encoding {
+    /// The JSON subset (layout version 1)
+    #[default]
+    Json,
+    /// The binary layout (layout version 2)
+    Binary,
+}
+
+impl Encoding {
+    /// Layout version embedded in the message prefix
+    pub fn version(self) -> u32 {
+        match self {
+            Encoding::Json => CANONICAL_VERSION,
+            Encoding::Binary => BINARY_CANONICAL_VERSION,
+        }
+    }
+}
+
+/// A value with a fixed canonical encoding
+pub trait Canonical {
+    fn encode(&self, out: &mut CanonicalWriter);
+}
+
+/// Binary type tags
+mod tag {
+    pub const NULL: u8 = 0x00;
+    pub const BOOL: u8 = 0x01;
+    pub const U64: u8 = 0x02;
+    pub const STR: u8 = 0x03;
+    pub const DECIMAL: u8 = 0x04;
+    pub const TIMESTAMP: u8 = 0x05;
+    pub const BYTES: u8 = 0x06;
+    pub const SEQ: u8 = 0x07;
+    pub const OBJECT: u8 = 0x08;
+    pub const OBJECT_END: u8 = 0x09;
+}
+
+/// Accumulates a canonical encoding
+pub struct CanonicalWriter {
+    buf: Vec<u8>,
+    encoding: Encoding,
+}
+
+impl CanonicalWriter {
+    fn new(encoding: Encoding) -> Self {
+        Self { buf: Vec::new(), encoding }
+    }
+
+    fn binary(&self) -> bool {
+        self.encoding == Encoding::Binary
+    }
+
+    /// u32 length prefix (lengths here never approach 4 GiB)
+    fn len_prefix(&mut self, len: usize) {
+        self.buf.extend_from_slice(&(len as u32).to_be_bytes());
+    }
+
+    pub fn null(&mut self) {
+        if self.binary() {
+            self.buf.push(tag::NULL);
+        } else {
+            self.buf.extend_from_slice(b"null");
+        }
+    }
+
+    pub fn bool(&mut self, value: bool) {
+        if self.binary() {
+            self.buf.extend_from_slice(&[tag::BOOL, value as u8]);
+        } else {
+            self.buf.extend_from_slice(if value { b"true" } else { b"false" });
+        }
+    }
+
+    pub fn u64(&mut self, value: u64) {
+        if self.binary() {
+            self.buf.push(tag::U64);
+            self.buf.extend_from_slice(&value.to_be_bytes());
+        } else {
+            let _ = write!(self.buf, "{}", value);
+        }
+    }
+
+    /// In JSON, a fixed escaping rule: `"`, `\` and control characters are
+    /// escaped, everything else is written as UTF-8
+    pub fn str(&mut self, value: &str) {
+        if self.binary() {
+            self.buf.push(tag::STR);
+            self.len_prefix(value.len());
+            self.buf.extend_from_slice(value.as_bytes());
+            return;
+        }
+        self.buf.push(b'"');
+        for c in value.chars() {
+            match c {
+                '"' => self.buf.extend_from_slice(b"\\\""),
+                '\\' => self.buf.extend_from_slice(b"\\\\"),
+                c if (c as u32) < 0x20 => {
+                    let _ = write!(self.buf, "\\u{:04x}", c as u32);
+                }
+                c => self.buf.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes()),
+            }
+        }
+        self.buf.push(b'"');
+    }
+
+    /// Normalized Decimal, so 1.0 and 1.00 encode identically
+    pub fn decimal(&mut self, value: Decimal) {
+        let value = value.normalize();
+        if self.binary() {
+            self.buf.push(tag::DECIMAL);
+            self.buf.extend_from_slice(&value.mantissa().to_be_bytes());
+            // Decimal scales are at most 28
+            self.buf.push(value.scale() as u8);
+        } else {
+            self.str(&value.to_string());
+        }
+    }
+
+    pub fn timestamp(&mut self, value: DateTime<Utc>) {
+        if self.binary() {
+            self.buf.push(tag::TIMESTAMP);
+            self.buf.extend_from_slice(&value.timestamp().to_be_bytes());
+            self.buf.extend_from_slice(&value.timestamp_subsec_nanos().to_be_bytes());
+        } else {
+            self.str(&value.to_rfc3339_opts(SecondsFormat::Nanos, true));
+        }
+    }
+
+    /// Byte string (lowercase hex in JSON)
+    pub fn bytes(&mut self, value: &[u8]) {
+        if self.binary() {
+            self.buf.push(tag::BYTES);
+            self.len_prefix(value.len());
+            self.buf.extend_from_slice(value);
+            return;
+        }
+        self.buf.push(b'"');
+        for byte in value {
+            let _ = write!(self.buf, "{:02x}", byte);
+        }
+        self.buf.push(b'"');
+    }
+
+    pub fn seq<'a, T: Canonical + 'a>(&mut self, items: impl IntoIterator<Item = &'a T>) {
+        if self.binary() {
+            let items: Vec<&T> = items.into_iter().collect();
+            self.buf.push(tag::SEQ);
+            self.len_prefix(items.len());
+            for item in items {
+                item.encode(self);
+            }
+            return;
+        }
+        self.buf.push(b'[');
+        for (idx, item) in items.into_iter().enumerate() {
+            if idx > 0 {
+                self.buf.push(b',');
+            }
+            item.encode(self);
+        }
+        self.buf.push(b']');
+    }
+
+    /// Enum variant carrying data: `{"Name":payload}`
+    pub fn variant(&mut self, name: &'static str, payload: &(impl Canonical + ?Sized)) {
+        self.object().field(name, payload).finish();
+    }
+
+    /// Start an object; fields must be added in ascending key order (a
+    /// field out of order panics)
+    pub fn object(&mut self) -> ObjectWriter<'_> {
+        self.buf.push(if self.binary() { tag::OBJECT } else { b'{' });
+        ObjectWriter { out: self, last: None }
+    }
+}
+
+/// Writes one object's fields in ascending key order
+#[must_use = "call finish() to close the object"]
+pub struct ObjectWriter<'a> {
+    out: &'a mut CanonicalWriter,
+    last: Option<&'a str>,
+}
+
+impl<'a> ObjectWriter<'a> {
+    pub fn field(mut self, name: &'a str, value: &(impl Canonical + ?Sized)) -> Self {
+        // A misordered field would change the bytes every hash and
+        // signature is taken over, so it is a bug in release builds too
+        assert!(
+            self.last.is_none_or(|last| last < name),
+            "canonical field {:?} out of order after {:?}",
+            name,
+            self.last,
+        );
+        if self.out.binary() {
+            self.out.len_prefix(name.len());
+            self.out.buf.extend_from_slice(name.as_bytes());
+        } else {
+            if self.last.is_some() {
+                self.out.buf.push(b',');
+            }
+            self.out.str(name);
+            self.out.buf.push(b':');
+        }
+        value.encode(self.out);
+        self.last = Some(name);
+        self
+    }
+
+    pub fn finish(self) {
+        self.out.buf.push(if self.out.binary() { tag::OBJECT_END } else { b'}' });
+    }
+}
+
+/// Canonical JSON encoding of `value` (without version or domain prefix)
+pub fn canonical_bytes(value: &(impl Canonical + ?Sized)) -> Vec<u8> {
+    canonical_bytes_with(Encoding::Json, value)
+}
+
+/// Canonical encoding of `value` in the given layout
+pub fn canonical_bytes_with(encoding: Encoding, value: &(impl Canonical + ?Sized)) -> Vec<u8> {
+    let mut out = CanonicalWriter::new(encoding);
+    value.encode(&mut out);
+    out.buf
+}
+
+/// Versioned, domain-separated message for hashing or signing (JSON layout)
+pub fn canonical_message(domain: &str, value: &(impl Canonical + ?Sized)) -> Vec<u8> {
+    canonical_message_with(Encoding::Json, domain, value)
+}
+
+/// Versioned, domain-separated message in the given layout
+pub fn canonical_message_with(encoding: Encoding, domain: &str, value: &(impl Canonical + ?Sized)) -> Vec<u8> {
+    let mut out = CanonicalWriter::new(encoding);
+    let _ = write!(out.buf, "axiom-canonical-v{}:{}:", encoding.version(), domain);
+    value.encode(&mut out);
+    out.buf
+}
+
+/// SHA3-256 hex digest of the canonical message for `value` (JSON layout)
+pub fn canonical_digest(domain: &str, value: &(impl Canonical + ?Sized)) -> String {
+    canonical_digest_with(Encoding::Json, domain, value)
+}
+
+/// SHA3-256 hex digest of the canonical message in the given layout
+pub fn canonical_digest_with(encoding: Encoding, domain: &str, value: &(impl Canonical + ?Sized)) -> String {
+    let mut hasher = Sha3_256::new();
+    hasher.update(canonical_message_with(encoding, domain, value));
+    format!("{:x}", hasher.finalize())
+}
+
+// --- Primitives ---
+
+impl Canonical for str {
+    fn encode(&self, out: &mut CanonicalWriter) {
+        out.str(self);
+    }
+}
+
+impl Canonical for String {
+    fn encode(&self, out: &mut CanonicalWriter) {
+        out.str(self);
+    }
+}
+
+impl Canonical for bool {
+    fn encode(&self, out: &mut CanonicalWriter) {
+        out.bool(*self);
+    }
+}
+
+impl Canonical for u64 {
+    fn encode(&self, out: &mut CanonicalWriter) {
+        out.u64(*self);
+    }
+}
+
+impl Canonical for Decimal {
+    fn encode(&self, out: &mut CanonicalWriter) {
+        out.decimal(*self);
+    }
+}
+
+impl Canonical for DateTime<Utc> {
+    fn encode(&self, out: &mut CanonicalWriter) {
+        out.timestamp(*self);
+    }
+}
+
+impl<T: Canonical> Canonical for Option<T> {
+    fn encode(&self, out: &mut CanonicalWriter) {
+        match self {
+            Some(value) => value.encode(out),
+            None => out.null(),
+        }
+    }
+}
+
+impl<T: Canonical> Canonical for Vec<T> {
+    fn encode(&self, out: &mut CanonicalWriter) {
+        out.seq(self);
+    }
+}
+
+impl<V: Canonical> Canonical for HashMap<String, V> {
+    fn encode(&self, out: &mut CanonicalWriter) {
+        let mut entries: Vec<(&String, &V)> = self.iter().collect();
+        entries.sort_by(|a, b| a.0.cmp(b.0));
+        let mut object = out.object();
+        for (key, value) in entries {
+            object = object.field(key, value);
+        }
+        object.finish();
+    }
+}
+
+// --- Domain types ---
+
+impl Canonical for Price {
+    fn encode(&self, out: &mut CanonicalWriter) {
+        out.decimal(self.get());
+    }
+}
+
+impl Canonical for Quantity {
+    fn encode(&self, out: &mut CanonicalWriter) {
+        out.decimal(self.get());
+    }
+}
+
+impl Canonical for Amount {
+    fn encode(&self, out: &mut CanonicalWriter) {
+        out.decimal(self.get());
+    }
+}
+
+impl Canonical for Symbol {
+    fn encode(&self, out: &mut CanonicalWriter) {
+        out.str(&self.0);
+    }
+}
+
+impl Canonical for AccountId {
+    fn encode(&self, out: &mut CanonicalWriter) {
+        out.str(&self.0);
+    }
+}
+
+impl Canonical for StrategyId {
+    fn encode(&self, out: &mut CanonicalWriter) {
+        out.str(&self.0);
+    }
+}
+
+impl Canonical for Venue {
+    fn encode(&self, out: &mut CanonicalWriter) {
+        out.str(&self.to_string());
+    }
+}
+
+impl Canonical for Side {
+    fn encode(&self, out: &mut CanonicalWriter) {
+        out.str(match self {
+            Side::Buy => "Buy",
+            Side::Sell => "Sell",
+        });
+    }
+}
+
+impl Canonical for TrailOffset {
+    fn encode(&self, out: &mut CanonicalWriter) {
+        match self {
+            TrailOffset::Absolute(distance) => out.variant("Absolute", distance),
+            TrailOffset::Percent(percent) => out.variant("Percent", percent),
+        }
+    }
+}
+
+/// Payload of a data-carrying OrderType variant
+struct StopParams<'a> {
+    trigger: Option<&'a Price>,
+    limit: Option<&'a Price>,
+    offset: Option<&'a TrailOffset>,
+}
+
+impl Canonical for StopParams<'_> {
+    fn encode(&self, out: &mut CanonicalWriter) {
+        let mut object = out.object();
+        if let Some(limit) = self.limit {
+            object = object.field("limit", limit);
+        }
+        if let Some(offset) = self.offset {
+            object = object.field("offset", offset);
+        }
+        if let Some(trigger) = self.trigger {
+            object = object.field("trigger", trigger);
+        }
+        object.finish();
+    }
+}
+
+impl Canonical for OrderType {
+    fn encode(&self, out: &mut CanonicalWriter) {
+        let none = StopParams { trigger: None, limit: None, offset: None };
+        match self {
+            OrderType::Limit => out.str("Limit"),
+            OrderType::Market => out.str("Market"),
+            OrderType::StopLoss => out.str("StopLoss"),
+            OrderType::TakeProfit => out.str("TakeProfit"),
+            OrderType::StopLimit { trigger, limit } => out.variant(
+                "StopLimit",
+                &StopParams { trigger: Some(trigger), limit: Some(limit), ..none },
+            ),
+            OrderType::StopMarket { trigger } => out.variant(
+                "StopMarket",
+                &StopParams { trigger: Some(trigger), ..none },
+            ),
+            OrderType::TrailingStop { offset } => out.variant(
+                "TrailingStop",
+                &StopParams { offset: Some(offset), ..none },
+            ),
+        }
+    }
+}
+
+impl Canonical for OrderStatus {
+    fn encode(&self, out: &mut CanonicalWriter) {
+        out.str(match self {
+            OrderStatus::Pending => "Pending",
+            OrderStatus::Submitted => "Submitted",
+            OrderStatus::PartiallyFilled => "PartiallyFilled",
+            OrderStatus::Filled => "Filled",
+            OrderStatus::Cancelled => "Cancelled",
+            OrderStatus::Rejected => "Rejected",
+        });
+    }
+}
+
+impl Canonical for TradeSignal {
+    fn encode(&self, out: &mut CanonicalWriter) {
+        let object = out.object();
+        // Omitted for the default account, so single-account hashes are unchanged
+        let object = if self.account.is_default() {
+            object
+        } else {
+            object.field("account", &self.account)
+        };
+        let object = object
+            .field("contradiction_score", &self.contradiction_score)
+            .field("entropy_count", &self.entropy_count);
+        // Omitted without an estimate, so signals from before the edge check hash as before
+        let object = match &self.expected_edge_bps {
+            Some(edge_bps) => object.field("expected_edge_bps", edge_bps),
+            None => object,
+        };
+        let object = object
+            .field("limit_price", &self.limit_price)
+            .field("order_type", &self.order_type)
+            .field("quantity", &self.quantity)
+            .field("side", &self.side)
+            .field("stop_price", &self.stop_price);
+        // Likewise for the default strategy
+        let object = if self.strategy.is_default() {
+            object
+        } else {
+            object.field("strategy", &self.strategy)
+        };
+        object
+            .field("symbol", &self.symbol)
+            .field("timestamp", &self.timestamp)
+            .field("venue", &self.venue)
+            .finish();
+    }
+}
+
+/// Excludes `solve_us` and `cached`, which vary from run to run
+impl Canonical for Proof {
+    fn encode(&self, out: &mut CanonicalWriter) {
+        out.object()
+            .field("axioms_satisfied", &self.axioms_satisfied)
+            .field("model", &self.model)
+            .field("satisfiable", &self.satisfiable)
+            .finish();
+    }
+}
+
+/// Excludes `signature`, which is computed over this encoding
+impl Canonical for VerifiedOrder {
+    fn encode(&self, out: &mut CanonicalWriter) {
+        out.object()
+            .field("proof", &self.proof)
+            .field("proof_signature", &self.proof_signature)
+            .field("signal", &self.signal)
+            .field("verified_at", &self.verified_at)
+            .finish();
+    }
+}
+
+/// Byte string encoded as lowercase hex
+struct Hex<'a>(&'a [u8]);
+
+impl Canonical for Hex<'_> {
+    fn encode(&self, out: &mut CanonicalWriter) {
+        out.bytes(self.0);
+    }
+}
+
+impl Canonical for CZeroSignature {
+    fn encode(&self, out: &mut CanonicalWriter) {
+        let object = out.object();
+        // Omitted for JSON-layout signatures, so their audit digests are unchanged
+        let object = match self.encoding {
+            Encoding::Json => object,
+            Encoding::Binary => object.field("encoding", &u64::from(self.encoding.version())),
+        };
+        object
+            .field("expires_at", &self.expires_at)
+            .field("key_id", &self.key_id)
+            .field("nonce", &self.nonce)
+            .field("order_hash", &self.order_hash)
+            .field("signature", &Hex(&self.signature))
+            .field("timestamp", &self.timestamp)
+            .field("verifying_key", &Hex(&self.verifying_key))
+            .finish();
+    }
+}
+
+impl Canonical for RotationStep {
+    fn encode(&self, out: &mut CanonicalWriter) {
+        out.str(match self {
+            RotationStep::Scheduled => "scheduled",
+            RotationStep::Activated => "activated",
+            RotationStep::Retired => "retired",
+        });
+    }
+}
+
+impl Canonical for LiquidationIntensity {
+    fn encode(&self, out: &mut CanonicalWriter) {
+        out.object()
+            .field("buy", &self.buy)
+            .field("sell", &self.sell)
+            .finish();
+    }
+}
+
+impl Canonical for BookSlope {
+    fn encode(&self, out: &mut CanonicalWriter) {
+        out.object()
+            .field("ask", &self.ask)
+            .field("bid", &self.bid)
+            .finish();
+    }
+}
+
+impl Canonical for FeatureVector {
+    fn encode(&self, out: &mut CanonicalWriter) {
+        // Ages are integers of either sign, written as Decimals
+        out.object()
+            .field("adjusted_contradiction_score", &self.adjusted_contradiction_score)
+            .field("book_slope", &self.book_slope)
+            .field("cex_age_ms", &Decimal::from(self.cex_age_ms))
+            .field("contradiction_score", &self.contradiction_score)
+            .field("depth_imbalance", &self.depth_imbalance)
+            .field("entropy", &self.entropy)
+            .field("funding_basis", &self.funding_basis)
+            .field("liquidation_intensity", &self.liquidation_intensity)
+            .field("microprice", &self.microprice)
+            .field("mid", &self.mid)
+            .field("onchain_age_ms", &Decimal::from(self.onchain_age_ms))
+            .field("rsi", &self.rsi)
+            .field("spread_pct", &self.spread_pct)
+            .field("volatility", &self.volatility)
+            .finish();
+    }
+}
+
+impl Canonical for AuditRecord {
+    fn encode(&self, out: &mut CanonicalWriter) {
+        let object = out.object();
+        let object = match self {
+            AuditRecord::SignalProposed { signal, features } => {
+                // Omitted without features, so entries from before them keep their digests
+                let object = match features {
+                    Some(features) => object.field("features", features),
+                    None => object,
+                };
+                object
+                    .field("signal", signal)
+                    .field("stage", "signal_proposed")
+            }
+            AuditRecord::SignalRejected { codes, reason } => {
+                // Omitted when empty, so entries from before error codes keep their digests
+                let object = if codes.is_empty() { object } else { object.field("codes", codes) };
+                object
+                    .field("reason", reason)
+                    .field("stage", "signal_rejected")
+            }
+            AuditRecord::OrderVerified { order } => object
+                .field("order", order)
+                .field("stage", "order_verified"),
+            AuditRecord::OrderSigned { order, signature } => object
+                .field("order", order)
+                .field("signature", signature)
+                .field("stage", "order_signed"),
+            AuditRecord::OrderSubmitted { status } => object
+                .field("stage", "order_submitted")
+                .field("status", status),
+            AuditRecord::OrderFilled { quantity, price } => object
+                .field("price", price)
+                .field("quantity", quantity)
+                .field("stage", "order_filled"),
+            AuditRecord::KeyRotation { step, key_id, at } => object
+                .field("at", at)
+                .field("key_id", key_id)
+                .field("stage", "key_rotation")
+                .field("step", step),
+            AuditRecord::ControlAction { action, source, outcome } => object
+                .field("action", action)
+                .field("outcome", outcome)
+                .field("source", source)
+                .field("stage", "control_action"),
+            AuditRecord::DryRunSubmission { venue, order_type, limit_price } => object
+                .field("limit_price", limit_price)
+                .field("order_type", order_type)
+                .field("stage", "dry_run_submission")
+                .field("venue", venue),
+            AuditRecord::ShadowSignal { strategy, signal, order, codes } => object
+                .field("codes", codes)
+                .field("order", order)
+                .field("signal", signal)
+                .field("stage", "shadow_signal")
+                .field("strategy", strategy),
+        };
+        object.finish();
+    }
+}
+
+/// Everything an audit entry's digest commits to (all fields but the digest itself)
+impl Canonical for AuditEntry {
+    fn encode(&self, out: &mut CanonicalWriter) {
+        out.object()
+            .field("order_hash", &self.order_hash)
+            .field("prev_digest", &self.prev_digest)
+            .field("record", &self.record)
+            .field("recorded_at", &self.recorded_at)
+            .field("sequence", &self.sequence)
+            .field("signal_hash", &self.signal_hash)
+            .finish();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    const SIGNAL_JSON: &str = concat!(
+        r#"{"contradiction_score":"0.8","entropy_count":"3","limit_price":"65000.5","order_type":"Limit","#,
+        r#""quantity":"0.25","side":"Buy","stop_price":null,"symbol":"BTC/USDT","#,
+        r#""timestamp":"2026-01-02T03:04:05.000000000Z","venue":"binance"}"#,
+    );
+
+    /// `SIGNAL_JSON`'s values in the binary layout, a line per key and value
+    const SIGNAL_BINARY: &str = concat!(
+        "08", // object
+        "00000013636f6e74726164696374696f6e5f73636f7265", // contradiction_score
+        "040000000000000000000000000000000801", // 0.8
+        "0000000d656e74726f70795f636f756e74", // entropy_count
+        "040000000000000000000000000000000300", // 3
+        "0000000b6c696d69745f7072696365", // limit_price
+        "040000000000000000000000000009eb1501", // 65000.5
+        "0000000a6f726465725f74797065", // order_type
+        "03000000054c696d6974", // Limit
+        "000000087175616e74697479", // quantity
+        "040000000000000000000000000000001902", // 0.25
+        "0000000473696465", // side
+        "0300000003427579", // Buy
+        "0000000a73746f705f7072696365", // stop_price
+        "00", // null
+        "0000000673796d626f6c", // symbol
+        "03000000084254432f55534454", // BTC/USDT
+        "0000000974696d657374616d70", // timestamp
+        "0500000000695735a500000000", // 2026-01-02T03:04:05Z
+        "0000000576656e7565", // venue
+        "030000000762696e616e6365", // binance
+        "09", // end
+    );
+
+    #[test]
+    fn signal_matches_golden_json() {
+        let signal = testing::signal();
+        assert_eq!(String::from_utf8(canonical_bytes(&signal)).unwrap(), SIGNAL_JSON);
+        // SHA3-256 of "axiom-canonical-v1:signal:" and the JSON above
+        assert_eq!(
+            canonical_digest("signal", &signal),
+            "5318a65c234b11354437900838198c23d4f7257665e3b6e7f8bcd3b118379488",
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "out of order")]
+    fn field_out_of_order_panics() {
+        let mut out = CanonicalWriter::new(Encoding::Json);
+        out.object().field("b", &true).field("a", &true).finish();
+    }
+
+    #[test]
+    fn signal_matches_golden_binary() {
+        let signal = testing::signal();
+        assert_eq!(hex::encode(canonical_bytes_with(Encoding::Binary, &signal)), SIGNAL_BINARY);
+        // SHA3-256 of "axiom-canonical-v2:signal:" and the bytes above
+        assert_eq!(
+            canonical_digest_with(Encoding::Binary, "signal", &signal),
+            "3bc8663062b73c1c6b3f27bb6924eea2745e578230e1304d7b0c3c11a54e240f",
+        );
+    }
+}
@@ -5,7 +5,7 @@
 
 use rust_decimal::Decimal;
 
-/// The deterministic seed for all random number generation
+/// The deterministic seed for all random number generation (see `AxiomRng`)
 pub const DETERMINISTIC_SEED: u64 = 42;
 
 /// Maximum acceptable consistency error (C=0 mandate)
@@ -39,9 +39,15 @@ pub const DELTA_U_MAX_SQ: Decimal = dec!(0.000000000001); // 1e-12
 /// Maximum acceptable slippage (as fraction of mid price)
 pub const MAX_SLIPPAGE_TOLERANCE: Decimal = dec!(0.001); // 0.1%
 
+/// Basis points per unit (fees, expected edge and funding are quoted in bps)
+pub const BPS_PER_UNIT: Decimal = dec!(10000);
+
 /// Minimum liquidity requirement (in quote currency)
 pub const MIN_LIQUIDITY_USD: Decimal = dec!(10000.0);
 
+/// Default minimum order notional (in quote currency); smaller orders are dust
+pub const MIN_ORDER_NOTIONAL_USD: Decimal = dec!(10.0);
+
 /// Hallucination rate threshold (triggers model hot-swap)
 pub const MAX_HALLUCINATION_RATE: Decimal = dec!(0.0001); // 0.01%
 
@@ -54,5 +60,5 @@ pub const TARGET_LATENCY_ONTOLOGY: u64 = 200;
 pub const SUPPORTED_PAIRS: &[&str] = &["BTC/USD", "ETH/USD", "SOL/USD"];
 
 /// Supported venues
-pub const SUPPORTED_VENUES: &[&str] = &["binance", "bybit", "hyperliquid"];
+pub const SUPPORTED_VENUES: &[&str] = &["binance", "bybit", "hyperliquid", "paper"];
 
@@ -4,6 +4,15 @@
 
 use thiserror::Error;
 
+/// Stable, machine-readable error code
+///
+/// Codes are part of the external contract (event log, telemetry, alerts,
+/// audit log): never change or reuse an existing code; add a new one instead.
+/// Error enums serialize as `{"code": <code>, "details": <fields>}`.
+pub trait ErrorCode {
+    fn code(&self) -> &'static str;
+}
+
 /// Top-level error type
 #[derive(Debug, Error)]
 pub enum AxiomError {
@@ -13,6 +22,12 @@ pub enum AxiomError {
     #[error("Signature error: {0}")]
     SignatureError(#[from] crate::signature::SignatureError),
 
+    #[error("Audit log error: {0}")]
+    AuditError(#[from] crate::audit::AuditError),
+
+    #[error("Arithmetic error: {0}")]
+    ArithmeticError(#[from] crate::arith::ArithmeticError),
+
     #[error("Data ingestion error: {0}")]
     DataError(String),
 
@@ -0,0 +1,490 @@
+//! Structured Events: Machine-Readable System Log
+//!
+//! Significant system events are emitted as one JSON object per line to a
+//! dedicated sink, separate from the human-readable tracing output. Each event
+//! type has a stable schema and an explicit version that must be bumped
+//! whenever its fields change.
+
+use crate::types::*;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+
+/// A structured event with a stable, versioned schema
+pub trait SystemEvent: Serialize {
+    /// Stable event type name
+    const EVENT_TYPE: &'static str;
+    /// Schema version (bump on any field change)
+    const SCHEMA_VERSION: u32;
+}
+
+/// Correlation IDs linking an event to a trade's lifecycle
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Correlation {
+    pub signal_id: Option<String>,
+    pub order_hash: Option<String>,
+    pub venue: Option<String>,
+    /// Account the trade (or breaker) belongs to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account: Option<String>,
+}
+
+impl Correlation {
+    pub fn for_signal(signal_id: &str, venue: &Venue) -> Self {
+        Self {
+            signal_id: Some(signal_id.to_string()),
+            order_hash: None,
+            venue: Some(venue.to_string()),
+            account: None,
+        }
+    }
+
+    pub fn with_account(mut self, account: &AccountId) -> Self {
+        self.account = Some(account.0.clone());
+        self
+    }
+
+    pub fn with_order_hash(mut self, order_hash: &str) -> Self {
+        self.order_hash = Some(order_hash.to_string());
+        self
+    }
+}
+
+/// Envelope written for every event
+#[derive(Debug, Serialize)]
+pub struct EventEnvelope<'a, E: SystemEvent> {
+    pub event_type: &'static str,
+    pub schema_version: u32,
+    pub emitted_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub correlation: &'a Correlation,
+    pub payload: &'a E,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignalProposed {
+    pub symbol: Symbol,
+    pub side: Side,
+    pub quantity: Quantity,
+    pub limit_price: Option<Price>,
+    pub contradiction_score: Decimal,
+    pub entropy_count: Decimal,
+}
+
+impl SystemEvent for SignalProposed {
+    const EVENT_TYPE: &'static str = "signal_proposed";
+    const SCHEMA_VERSION: u32 = 1;
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignalRejected {
+    pub symbol: Symbol,
+    /// Stable error code of every violated invariant (see `ErrorCode`), in order
+    pub codes: Vec<String>,
+    /// Structured violations: `[{"code": ..., "details": ...}, ...]`
+    pub violations: serde_json::Value,
+    /// Human-readable message
+    pub message: String,
+}
+
+impl SystemEvent for SignalRejected {
+    const EVENT_TYPE: &'static str = "signal_rejected";
+    const SCHEMA_VERSION: u32 = 3;
+}
+
+/// A signal dropped unverified because the verification queue was full
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerificationBacklog {
+    pub symbol: Symbol,
+    pub venue: Venue,
+    pub queue_depth: usize,
+}
+
+impl SystemEvent for VerificationBacklog {
+    const EVENT_TYPE: &'static str = "verification_backlog";
+    const SCHEMA_VERSION: u32 = 1;
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderVerified {
+    pub symbol: Symbol,
+    pub side: Side,
+    pub quantity: Quantity,
+    pub limit_price: Option<Price>,
+    pub proof_signature: String,
+    pub axioms_satisfied: Vec<String>,
+}
+
+impl SystemEvent for OrderVerified {
+    const EVENT_TYPE: &'static str = "order_verified";
+    const SCHEMA_VERSION: u32 = 1;
+}
+
+/// A shadow strategy's signal: verified and signed, never executed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShadowSignal {
+    pub strategy: String,
+    pub symbol: Symbol,
+    pub side: Side,
+    pub quantity: Quantity,
+    pub limit_price: Option<Price>,
+    /// Empty when the verifier accepted it
+    pub codes: Vec<String>,
+    /// Always true; lets consumers filter shadow traffic without knowing the type
+    pub shadow: bool,
+}
+
+impl SystemEvent for ShadowSignal {
+    const EVENT_TYPE: &'static str = "shadow_signal";
+    const SCHEMA_VERSION: u32 = 1;
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderSubmitted {
+    pub symbol: Symbol,
+    pub side: Side,
+    pub quantity: Quantity,
+    pub limit_price: Option<Price>,
+    pub status: OrderStatus,
+}
+
+impl SystemEvent for OrderSubmitted {
+    const EVENT_TYPE: &'static str = "order_submitted";
+    const SCHEMA_VERSION: u32 = 1;
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Fill {
+    pub symbol: Symbol,
+    pub side: Side,
+    pub quantity: Quantity,
+    pub price: Price,
+}
+
+impl SystemEvent for Fill {
+    const EVENT_TYPE: &'static str = "fill";
+    const SCHEMA_VERSION: u32 = 1;
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BreakerTripped {
+    pub state: CircuitBreakerState,
+    pub reason: String,
+}
+
+impl SystemEvent for BreakerTripped {
+    const EVENT_TYPE: &'static str = "breaker_tripped";
+    const SCHEMA_VERSION: u32 = 1;
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegimeChanged {
+    pub symbol: Symbol,
+    pub from: MarketRegime,
+    pub to: MarketRegime,
+    /// Seconds spent in `from`
+    pub dwell_secs: i64,
+    pub volatility: Decimal,
+    pub trend: Decimal,
+    pub entropy: Decimal,
+}
+
+impl SystemEvent for RegimeChanged {
+    const EVENT_TYPE: &'static str = "regime_changed";
+    const SCHEMA_VERSION: u32 = 1;
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VenueDown {
+    pub venue: Venue,
+    pub reason: String,
+}
+
+impl SystemEvent for VenueDown {
+    const EVENT_TYPE: &'static str = "venue_down";
+    const SCHEMA_VERSION: u32 = 1;
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigReloaded {
+    pub changed_keys: Vec<String>,
+}
+
+impl SystemEvent for ConfigReloaded {
+    const EVENT_TYPE: &'static str = "config_reloaded";
+    const SCHEMA_VERSION: u32 = 1;
+}
+
+/// Receiver of every emitted event, alongside the JSON-lines sink
+///
+/// Called on the emitting thread; implementations must not block.
+pub trait EventListener: Send + Sync {
+    fn on_event(&self, event_type: &str, line: &str);
+}
+
+/// JSON-lines event sink
+pub struct EventLog {
+    sink: Mutex<Box<dyn Write + Send>>,
+    listeners: Vec<Box<dyn EventListener>>,
+}
+
+impl EventLog {
+    /// Append events to a file
+    pub fn open(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let file: File = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self::from_writer(Box::new(file)))
+    }
+
+    /// Write events to an arbitrary sink (stdout, a pipe, a buffer)
+    pub fn from_writer(writer: Box<dyn Write + Send>) -> Self {
+        Self {
+            sink: Mutex::new(writer),
+            listeners: Vec::new(),
+        }
+    }
+
+    /// Also deliver every event to `listener` (e.g. a live dashboard stream)
+    pub fn with_listener(mut self, listener: impl EventListener + 'static) -> Self {
+        self.listeners.push(Box::new(listener));
+        self
+    }
+
+    /// Flush the sink (each emit already flushes; used at shutdown)
+    pub fn flush(&self) -> std::io::Result<()> {
+        match self.sink.lock() {
+            Ok(mut sink) => sink.flush(),
+            Err(_) => Err(std::io::Error::other("event sink poisoned")),
+        }
+    }
+
+    /// Emit one event as a single JSON line
+    pub fn emit<E: SystemEvent>(&self, correlation: &Correlation, event: &E) {
+        let envelope = EventEnvelope {
+            event_type: E::EVENT_TYPE,
+            schema_version: E::SCHEMA_VERSION,
+            emitted_at: Utc::now(),
+            correlation,
+            payload: event,
+        };
+
+        let line = match serde_json::to_string(&envelope) {
+            Ok(line) => line,
+            Err(e) => {
+                tracing::error!("Failed to serialize {} event: {}", E::EVENT_TYPE, e);
+                return;
+            }
+        };
+
+        for listener in &self.listeners {
+            listener.on_event(E::EVENT_TYPE, &line);
+        }
+
+        let Ok(mut sink) = self.sink.lock() else {
+            tracing::error!("Event sink poisoned; dropping {} event", E::EVENT_TYPE);
+            return;
+        };
+
+        if let Err(e) = writeln!(sink, "{}", line).and_then(|_| sink.flush()) {
+            tracing::error!("Failed to write {} event: {}", E::EVENT_TYPE, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    /// Keeps every line emitted
+    #[derive(Clone, Default)]
+    struct Lines(Arc<Mutex<Vec<String>>>);
+
+    impl EventListener for Lines {
+        fn on_event(&self, _event_type: &str, line: &str) {
+            self.0.lock().unwrap().push(line.to_string());
+        }
+    }
+
+    /// The line `event` is emitted as, its timestamp replaced by `<now>`
+    ///
+    /// The golden lines below fix each event's type, version and fields;
+    /// one that has to change means the event's SCHEMA_VERSION is bumped.
+    fn line<E: SystemEvent>(event: &E) -> String {
+        let lines = Lines::default();
+        let log = EventLog::from_writer(Box::new(std::io::sink())).with_listener(lines.clone());
+        let correlation = Correlation::for_signal("sig-1", &Venue::Binance).with_order_hash("ab12");
+        log.emit(&correlation, event);
+        let line = lines.0.lock().unwrap().pop().expect("one line");
+        let parsed: serde_json::Value = serde_json::from_str(&line).unwrap();
+        let emitted_at = parsed["emitted_at"].as_str().expect("emitted_at");
+        assert!(emitted_at.parse::<DateTime<Utc>>().is_ok(), "{}", emitted_at);
+        line.replace(emitted_at, "<now>")
+    }
+
+    fn btc() -> Symbol {
+        Symbol("BTC/USD".to_string())
+    }
+
+    fn quantity() -> Quantity {
+        Quantity::new(Decimal::new(25, 2)).unwrap()
+    }
+
+    fn price() -> Price {
+        Price::new(Decimal::from(65_000)).unwrap()
+    }
+
+    #[test]
+    fn signal_proposed_line() {
+        let event = SignalProposed {
+            symbol: btc(),
+            side: Side::Buy,
+            quantity: quantity(),
+            limit_price: Some(price()),
+            contradiction_score: Decimal::new(8, 1),
+            entropy_count: Decimal::ZERO,
+        };
+        let golden = concat!(
+            r#"{"event_type":"signal_proposed","schema_version":1,"emitted_at":"<now>","signal_id":"sig-1","order_hash":"ab12","venue":"binance","#,
+            r#""payload":{"symbol":"BTC/USD","side":"Buy","quantity":"0.25","limit_price":"65000","contradiction_score":"0.8","entropy_count":"0"}}"#,
+        );
+        assert_eq!(line(&event), golden);
+    }
+
+    #[test]
+    fn signal_rejected_line() {
+        let event = SignalRejected {
+            symbol: btc(),
+            codes: vec!["L0_LEVERAGE".to_string()],
+            violations: serde_json::json!([{"code": "L0_LEVERAGE", "details": {"current": "6", "max": "5"}}]),
+            message: "Leverage 6 exceeds 5".to_string(),
+        };
+        let golden = concat!(
+            r#"{"event_type":"signal_rejected","schema_version":3,"emitted_at":"<now>","signal_id":"sig-1","order_hash":"ab12","venue":"binance","#,
+            r#""payload":{"symbol":"BTC/USD","codes":["L0_LEVERAGE"],"violations":[{"code":"L0_LEVERAGE","details":{"current":"6","max":"5"}}],"message":"Leverage 6 exceeds 5"}}"#,
+        );
+        assert_eq!(line(&event), golden);
+    }
+
+    #[test]
+    fn verification_backlog_line() {
+        let event = VerificationBacklog { symbol: btc(), venue: Venue::Binance, queue_depth: 64 };
+        let golden = concat!(
+            r#"{"event_type":"verification_backlog","schema_version":1,"emitted_at":"<now>","signal_id":"sig-1","order_hash":"ab12","venue":"binance","#,
+            r#""payload":{"symbol":"BTC/USD","venue":"binance","queue_depth":64}}"#,
+        );
+        assert_eq!(line(&event), golden);
+    }
+
+    #[test]
+    fn order_verified_line() {
+        let event = OrderVerified {
+            symbol: btc(),
+            side: Side::Sell,
+            quantity: quantity(),
+            limit_price: None,
+            proof_signature: "cd34".to_string(),
+            axioms_satisfied: vec!["PositionSizeLimit".to_string()],
+        };
+        let golden = concat!(
+            r#"{"event_type":"order_verified","schema_version":1,"emitted_at":"<now>","signal_id":"sig-1","order_hash":"ab12","venue":"binance","#,
+            r#""payload":{"symbol":"BTC/USD","side":"Sell","quantity":"0.25","limit_price":null,"proof_signature":"cd34","axioms_satisfied":["PositionSizeLimit"]}}"#,
+        );
+        assert_eq!(line(&event), golden);
+    }
+
+    #[test]
+    fn shadow_signal_line() {
+        let event = ShadowSignal {
+            strategy: "momentum".to_string(),
+            symbol: btc(),
+            side: Side::Buy,
+            quantity: quantity(),
+            limit_price: Some(price()),
+            codes: vec![],
+            shadow: true,
+        };
+        let golden = concat!(
+            r#"{"event_type":"shadow_signal","schema_version":1,"emitted_at":"<now>","signal_id":"sig-1","order_hash":"ab12","venue":"binance","#,
+            r#""payload":{"strategy":"momentum","symbol":"BTC/USD","side":"Buy","quantity":"0.25","limit_price":"65000","codes":[],"shadow":true}}"#,
+        );
+        assert_eq!(line(&event), golden);
+    }
+
+    #[test]
+    fn order_submitted_line() {
+        let event = OrderSubmitted {
+            symbol: btc(),
+            side: Side::Buy,
+            quantity: quantity(),
+            limit_price: Some(price()),
+            status: OrderStatus::Submitted,
+        };
+        let golden = concat!(
+            r#"{"event_type":"order_submitted","schema_version":1,"emitted_at":"<now>","signal_id":"sig-1","order_hash":"ab12","venue":"binance","#,
+            r#""payload":{"symbol":"BTC/USD","side":"Buy","quantity":"0.25","limit_price":"65000","status":"Submitted"}}"#,
+        );
+        assert_eq!(line(&event), golden);
+    }
+
+    #[test]
+    fn fill_line() {
+        let event = Fill { symbol: btc(), side: Side::Buy, quantity: quantity(), price: price() };
+        let golden = concat!(
+            r#"{"event_type":"fill","schema_version":1,"emitted_at":"<now>","signal_id":"sig-1","order_hash":"ab12","venue":"binance","#,
+            r#""payload":{"symbol":"BTC/USD","side":"Buy","quantity":"0.25","price":"65000"}}"#,
+        );
+        assert_eq!(line(&event), golden);
+    }
+
+    #[test]
+    fn breaker_tripped_line() {
+        let event = BreakerTripped { state: CircuitBreakerState::Tripped, reason: "drawdown 5%".to_string() };
+        let golden = concat!(
+            r#"{"event_type":"breaker_tripped","schema_version":1,"emitted_at":"<now>","signal_id":"sig-1","order_hash":"ab12","venue":"binance","#,
+            r#""payload":{"state":"Tripped","reason":"drawdown 5%"}}"#,
+        );
+        assert_eq!(line(&event), golden);
+    }
+
+    #[test]
+    fn regime_changed_line() {
+        let event = RegimeChanged {
+            symbol: btc(),
+            from: MarketRegime::Normal,
+            to: MarketRegime::Volatile,
+            dwell_secs: 120,
+            volatility: Decimal::new(35, 3),
+            trend: Decimal::new(-1, 2),
+            entropy: Decimal::ZERO,
+        };
+        let golden = concat!(
+            r#"{"event_type":"regime_changed","schema_version":1,"emitted_at":"<now>","signal_id":"sig-1","order_hash":"ab12","venue":"binance","#,
+            r#""payload":{"symbol":"BTC/USD","from":"Normal","to":"Volatile","dwell_secs":120,"volatility":"0.035","trend":"-0.01","entropy":"0"}}"#,
+        );
+        assert_eq!(line(&event), golden);
+    }
+
+    #[test]
+    fn venue_down_line() {
+        let event = VenueDown { venue: Venue::Bybit, reason: "connection reset".to_string() };
+        let golden = concat!(
+            r#"{"event_type":"venue_down","schema_version":1,"emitted_at":"<now>","signal_id":"sig-1","order_hash":"ab12","venue":"binance","#,
+            r#""payload":{"venue":"bybit","reason":"connection reset"}}"#,
+        );
+        assert_eq!(line(&event), golden);
+    }
+
+    #[test]
+    fn config_reloaded_line() {
+        let event = ConfigReloaded { changed_keys: vec!["risk.max_leverage".to_string()] };
+        let golden = concat!(
+            r#"{"event_type":"config_reloaded","schema_version":1,"emitted_at":"<now>","signal_id":"sig-1","order_hash":"ab12","venue":"binance","#,
+            r#""payload":{"changed_keys":["risk.max_leverage"]}}"#,
+        );
+        assert_eq!(line(&event), golden);
+    }
+}
@@ -0,0 +1,53 @@
+//! Opportunity Funnel: Stage Counters per Symbol
+//!
+//! Each stage an opportunity passes through (book → proposal → proof →
+//! signature → submission → fill) is counted per symbol, so conversion ratios
+//! show where opportunities die.
+
+use crate::types::Symbol;
+use serde::{Deserialize, Serialize};
+
+/// Funnel stage, in pipeline order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FunnelStage {
+    BookProcessed,
+    Proposed,
+    Verified,
+    Signed,
+    Submitted,
+    Filled,
+}
+
+impl FunnelStage {
+    pub const ALL: [FunnelStage; 6] = [
+        FunnelStage::BookProcessed,
+        FunnelStage::Proposed,
+        FunnelStage::Verified,
+        FunnelStage::Signed,
+        FunnelStage::Submitted,
+        FunnelStage::Filled,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FunnelStage::BookProcessed => "book_processed",
+            FunnelStage::Proposed => "proposed",
+            FunnelStage::Verified => "verified",
+            FunnelStage::Signed => "signed",
+            FunnelStage::Submitted => "submitted",
+            FunnelStage::Filled => "filled",
+        }
+    }
+}
+
+impl std::fmt::Display for FunnelStage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Receiver of funnel stage events
+pub trait FunnelRecorder: Send + Sync {
+    fn record(&self, stage: FunnelStage, symbol: &Symbol);
+}
@@ -0,0 +1,197 @@
+//! Instruments: Venue Order Rules
+//!
+//! An exchange accepts a limit price only on the instrument's tick grid and
+//! a quantity only in whole lot steps, between a minimum and maximum size
+//! and above a minimum notional; anything else is rejected. The registry
+//! holds those rules per (venue, symbol), filled from the venues' instrument
+//! listings, and the helpers here put a price or size on the grid. Buy
+//! prices round down and sell prices up, so rounding never makes a limit
+//! worse than the one asked for; sizes always round down.
+
+use crate::errors::ErrorCode;
+use crate::types::{Side, Symbol, Venue};
+use crate::units::{Amount, Price, Quantity, UnitError};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// One instrument's order rules on one venue
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Instrument {
+    /// Smallest price increment
+    pub price_tick: Price,
+    /// Smallest quantity increment (the lot step)
+    pub quantity_step: Quantity,
+    /// Smallest order notional (quote currency)
+    #[serde(default)]
+    pub min_notional: Amount,
+    #[serde(default)]
+    pub min_quantity: Quantity,
+    #[serde(default)]
+    pub max_quantity: Option<Quantity>,
+}
+
+impl Instrument {
+    pub fn new(price_tick: Price, quantity_step: Quantity) -> Self {
+        Self {
+            price_tick,
+            quantity_step,
+            min_notional: Amount::ZERO,
+            min_quantity: Quantity::ZERO,
+            max_quantity: None,
+        }
+    }
+
+    pub fn with_min_notional(mut self, min_notional: Amount) -> Self {
+        self.min_notional = min_notional;
+        self
+    }
+
+    pub fn with_quantity_range(mut self, min: Quantity, max: Option<Quantity>) -> Self {
+        self.min_quantity = min;
+        self.max_quantity = max;
+        self
+    }
+
+    /// `price` on the tick grid, rounded the way that favors `side`
+    pub fn round_price(&self, price: Price, side: Side) -> Result<Price, UnitError> {
+        round_price_to_tick(price, self.price_tick, side)
+    }
+
+    /// `quantity` rounded down to whole lot steps
+    pub fn round_quantity(&self, quantity: Quantity) -> Result<Quantity, UnitError> {
+        round_qty_to_step(quantity, self.quantity_step)
+    }
+
+    /// Whether a limit price for `side` is on the tick grid
+    pub fn check_price(&self, price: Price, side: Side) -> Result<(), InstrumentError> {
+        if self.round_price(price, side)? != price {
+            return Err(InstrumentError::OffTick { price, tick: self.price_tick });
+        }
+        Ok(())
+    }
+
+    /// Whether `quantity` is in whole steps within the size range and, at
+    /// `price` when known, above the minimum notional
+    pub fn check_quantity(&self, quantity: Quantity, price: Option<Price>) -> Result<(), InstrumentError> {
+        if self.round_quantity(quantity)? != quantity {
+            return Err(InstrumentError::OffStep { quantity, step: self.quantity_step });
+        }
+        if quantity < self.min_quantity {
+            return Err(InstrumentError::BelowMinQuantity { quantity, min: self.min_quantity });
+        }
+        if let Some(max) = self.max_quantity.filter(|max| quantity > *max) {
+            return Err(InstrumentError::AboveMaxQuantity { quantity, max });
+        }
+        if let Some(price) = price {
+            let notional = price.notional(quantity)?;
+            if notional < self.min_notional {
+                return Err(InstrumentError::BelowMinNotional { notional, min: self.min_notional });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Round `price` to a whole number of `tick`s: down for a buy, up for a
+/// sell; a zero tick leaves it unchanged
+pub fn round_price_to_tick(price: Price, tick: Price, side: Side) -> Result<Price, UnitError> {
+    if tick.is_zero() {
+        return Ok(price);
+    }
+    match side {
+        Side::Buy => price.floor_to(tick),
+        Side::Sell => price.ceil_to(tick),
+    }
+}
+
+/// Round `quantity` down to a whole number of `step`s; a zero step leaves
+/// it unchanged
+pub fn round_qty_to_step(quantity: Quantity, step: Quantity) -> Result<Quantity, UnitError> {
+    if step.is_zero() {
+        return Ok(quantity);
+    }
+    quantity.floor_to(step)
+}
+
+/// Order rules by venue and symbol
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InstrumentRegistry {
+    instruments: BTreeMap<Venue, BTreeMap<Symbol, Instrument>>,
+}
+
+impl InstrumentRegistry {
+    /// No instruments; orders go unchecked against venue rules
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_instrument(mut self, venue: Venue, symbol: Symbol, instrument: Instrument) -> Self {
+        self.insert(venue, symbol, instrument);
+        self
+    }
+
+    /// Add or replace `symbol`'s rules on `venue`
+    pub fn insert(&mut self, venue: Venue, symbol: Symbol, instrument: Instrument) {
+        self.instruments.entry(venue).or_default().insert(symbol, instrument);
+    }
+
+    /// Add or replace every `(symbol, rules)` for `venue` (a fetched listing)
+    pub fn extend(&mut self, venue: &Venue, instruments: impl IntoIterator<Item = (Symbol, Instrument)>) {
+        self.instruments.entry(venue.clone()).or_default().extend(instruments);
+    }
+
+    pub fn get(&self, venue: &Venue, symbol: &Symbol) -> Option<&Instrument> {
+        self.instruments.get(venue)?.get(symbol)
+    }
+
+    /// Instruments across every venue
+    pub fn len(&self) -> usize {
+        self.instruments.values().map(BTreeMap::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// An order the venue's rules would reject
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, thiserror::Error)]
+#[serde(tag = "code", content = "details")]
+pub enum InstrumentError {
+    #[error("Price {price} is not a multiple of the tick {tick}")]
+    #[serde(rename = "INSTRUMENT_OFF_TICK")]
+    OffTick { price: Price, tick: Price },
+
+    #[error("Quantity {quantity} is not a multiple of the step {step}")]
+    #[serde(rename = "INSTRUMENT_OFF_STEP")]
+    OffStep { quantity: Quantity, step: Quantity },
+
+    #[error("Quantity {quantity} is below the minimum {min}")]
+    #[serde(rename = "INSTRUMENT_BELOW_MIN_QUANTITY")]
+    BelowMinQuantity { quantity: Quantity, min: Quantity },
+
+    #[error("Quantity {quantity} is above the maximum {max}")]
+    #[serde(rename = "INSTRUMENT_ABOVE_MAX_QUANTITY")]
+    AboveMaxQuantity { quantity: Quantity, max: Quantity },
+
+    #[error("Notional {notional} is below the minimum {min}")]
+    #[serde(rename = "INSTRUMENT_BELOW_MIN_NOTIONAL")]
+    BelowMinNotional { notional: Amount, min: Amount },
+
+    #[error("Arithmetic error: {0}")]
+    #[serde(rename = "INSTRUMENT_ARITHMETIC")]
+    Arithmetic(#[from] UnitError),
+}
+
+impl ErrorCode for InstrumentError {
+    fn code(&self) -> &'static str {
+        match self {
+            InstrumentError::OffTick { .. } => "INSTRUMENT_OFF_TICK",
+            InstrumentError::OffStep { .. } => "INSTRUMENT_OFF_STEP",
+            InstrumentError::BelowMinQuantity { .. } => "INSTRUMENT_BELOW_MIN_QUANTITY",
+            InstrumentError::AboveMaxQuantity { .. } => "INSTRUMENT_ABOVE_MAX_QUANTITY",
+            InstrumentError::BelowMinNotional { .. } => "INSTRUMENT_BELOW_MIN_NOTIONAL",
+            InstrumentError::Arithmetic(_) => "INSTRUMENT_ARITHMETIC",
+        }
+    }
+}
@@ -3,9 +3,14 @@
 //! This module defines the formal invariants that must be satisfied
 //! for any trade to execute. These are the "laws of physics" for the system.
 
+use crate::arith;
 use crate::types::*;
 use crate::constants::*;
+use crate::errors::ErrorCode;
+use crate::limits::{LimitsError, LimitsRegistry, SymbolLimits};
+use crate::units::UnitError;
 use rust_decimal::Decimal;
+use serde::Serialize;
 use thiserror::Error;
 
 /// The L0 Invariant Contract
@@ -17,52 +22,311 @@ pub struct L0InvariantContract;
 impl L0InvariantContract {
     /// Verify that a trade signal satisfies all L0 invariants
     ///
-    /// Returns Ok(()) if all invariants are satisfied, Err(InvariantViolation) otherwise.
-    pub fn verify_signal(signal: &TradeSignal, portfolio: &Portfolio) -> Result<(), InvariantViolation> {
+    /// `market` is the reference price (the mid of the book the signal was
+    /// proposed from) that stop placement and slippage are measured against.
+    /// Every invariant is evaluated; returns Ok(()) if all are satisfied,
+    /// otherwise every violation in invariant order. Callers that only need
+    /// pass/fail can use `.is_ok()`.
+    pub fn verify_signal(
+        signal: &TradeSignal,
+        portfolio: &Portfolio,
+        limits: &LimitsRegistry,
+        market: Price,
+    ) -> Result<(), ViolationSet> {
+        let mut violations = ViolationSet::new();
+
         // Invariant 1: Consistency Error must be zero
         if signal.contradiction_score < Decimal::ZERO {
-            return Err(InvariantViolation::NegativeContradiction);
+            violations.push(InvariantViolation::NegativeContradiction);
         }
 
-        // Invariant 2: Position size must not exceed maximum
-        Self::check_position_size(&signal.symbol, signal.quantity)?;
+        // Invariant 2: The position once the signal fills must not exceed the
+        // symbol's maximum (limit-dependent invariants are skipped for an
+        // unknown symbol)
+        let symbol_limits = match limits.get(&signal.symbol) {
+            Ok(symbol_limits) => Some(symbol_limits),
+            Err(e) => {
+                violations.push(e.into());
+                None
+            }
+        };
+        if let Some(symbol_limits) = symbol_limits {
+            violations.record(
+                Self::projected_position(signal, portfolio)
+                    .and_then(|projected| Self::check_position_size(symbol_limits, projected)),
+            );
+        }
 
-        // Invariant 3: Portfolio leverage must not exceed maximum
-        Self::check_leverage(portfolio)?;
+        // Invariant 3: Portfolio leverage must not exceed the account's maximum
+        violations.record(Self::check_leverage(portfolio, limits.account_max_leverage(&signal.account)));
 
         // Invariant 4: Risk budget must be respected
-        Self::check_risk_budget(signal, portfolio)?;
+        if let Some(symbol_limits) = symbol_limits {
+            violations.record(Self::check_risk_budget(signal, portfolio, symbol_limits, market));
+        }
 
         // Invariant 5: Entropy must be below threshold (not in Unprovable regime)
         if signal.entropy_count > DELTA_U_MAX_SQ {
-            return Err(InvariantViolation::ExcessiveEntropy);
+            violations.push(InvariantViolation::ExcessiveEntropy);
         }
 
-        // Invariant 6: Slippage tolerance must be satisfiable
-        // (This is checked during execution, but we verify the signal is within bounds)
-        if let Some(limit_price) = signal.limit_price {
-            // Basic sanity check
-            if limit_price <= Decimal::ZERO {
-                return Err(InvariantViolation::InvalidPrice);
+        // Invariant 6: Conditional order parameters must be coherent and the
+        // trigger on the correct side of the market
+        violations.record(Self::check_order_type(signal));
+        violations.record(Self::verify_trigger_placement(signal, market));
+
+        // Invariant 7: Execution price must be within slippage tolerance of the reference
+        violations.record(Self::check_slippage(signal, market));
+
+        // Invariant 8: No dust (below minimum notional or the lot step)
+        if let Some(symbol_limits) = symbol_limits {
+            violations.record(Self::check_min_size(signal, symbol_limits, market));
+        }
+
+        violations.into_result()
+    }
+
+    /// Verify the invariants that hold across every account together
+    ///
+    /// `verify_signal` checks the signal's own account; with several
+    /// accounts, `aggregate` (all of them combined) must also stay within the
+    /// portfolio-wide leverage cap and energy threshold.
+    pub fn verify_aggregate(aggregate: &Portfolio, limits: &LimitsRegistry) -> Result<(), ViolationSet> {
+        let mut violations = ViolationSet::new();
+        if aggregate.leverage > limits.max_leverage() {
+            violations.push(InvariantViolation::AggregateLeverageExceeded {
+                current: aggregate.leverage,
+                max: limits.max_leverage(),
+            });
+        }
+        violations.record(Self::verify_hamiltonian_energy(aggregate));
+        violations.into_result()
+    }
+
+    /// Check the signal keeps its strategy within its capital allocation
+    ///
+    /// `portfolio` carries the per-strategy exposures (the aggregate when
+    /// there are several accounts). A strategy without an allocation is
+    /// unbudgeted. An order that shrinks the strategy's own position in the
+    /// symbol always passes, so a strategy over a lowered allocation winds
+    /// down as it trades instead of being liquidated; so does one that only
+    /// reduces its account's position (exits).
+    pub fn verify_allocation(
+        signal: &TradeSignal,
+        portfolio: &Portfolio,
+        limits: &LimitsRegistry,
+        market: Price,
+    ) -> Result<(), InvariantViolation> {
+        let Some(fraction) = limits.allocation(&signal.strategy) else { return Ok(()) };
+        let reduces_account = Self::reduces_account(signal, portfolio);
+        let held = portfolio.strategies.get(&signal.strategy).cloned().unwrap_or_default();
+        let net = held.net_quantity(&signal.symbol);
+        let after = net + match signal.side {
+            Side::Buy => signal.quantity.get(),
+            Side::Sell => -signal.quantity.get(),
+        };
+        if reduces_account || after.abs() <= net.abs() {
+            return Ok(());
+        }
+
+        let price = Self::order_price(signal, market);
+        let added = price.notional(Quantity::new(after.abs() - net.abs())?)?;
+        let exposure = held.exposure.checked_add(added)?;
+        let budget = if portfolio.equity.is_positive() { portfolio.equity.scale(fraction)? } else { Amount::ZERO };
+        if exposure > budget {
+            return Err(InvariantViolation::AllocationExceeded {
+                strategy: signal.strategy.0.clone(),
+                exposure: exposure.get(),
+                budget: budget.get(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Check the signal's expected edge covers its costs
+    ///
+    /// Round-trip fees are charged at the venue's taker rate on both legs, as
+    /// the worst case. Funding is charged when the next payment falls within
+    /// the expected holding period and the signal's side pays it; funding it
+    /// would receive is not credited, since the rate can turn before payment.
+    /// Exits (orders that only reduce their account's position) and
+    /// strategies opted out in the edge policy are not checked.
+    pub fn verify_edge(
+        signal: &TradeSignal,
+        portfolio: &Portfolio,
+        limits: &LimitsRegistry,
+        funding: Option<&FundingEstimate>,
+    ) -> Result<EdgeCheck, InvariantViolation> {
+        let policy = limits.edge_policy();
+        if Self::reduces_account(signal, portfolio) {
+            return Ok(EdgeCheck::Exit);
+        }
+        if policy.opts_out(&signal.strategy) {
+            return Ok(EdgeCheck::OptedOut);
+        }
+        let expected_bps = signal.expected_edge_bps.ok_or_else(|| InvariantViolation::MissingExpectedEdge {
+            strategy: signal.strategy.0.clone(),
+        })?;
+
+        let fees_bps = signal.venue.capabilities().taker_fee_bps * Decimal::TWO;
+        // None: the holding period runs past any representable time
+        let horizon = signal.timestamp.checked_add_signed(policy.holding_period());
+        let funding_bps = funding
+            .filter(|funding| {
+                funding.next_funding > signal.timestamp && horizon.is_none_or(|horizon| funding.next_funding <= horizon)
+            })
+            .map_or(Decimal::ZERO, |funding| {
+                let paid = match signal.side {
+                    Side::Buy => funding.rate,
+                    Side::Sell => -funding.rate,
+                };
+                (paid * BPS_PER_UNIT).max(Decimal::ZERO)
+            });
+        let breakdown = EdgeBreakdown {
+            expected_bps,
+            fees_bps,
+            funding_bps,
+            net_bps: expected_bps - fees_bps - funding_bps,
+            min_net_bps: policy.min_net_edge_bps,
+        };
+        if breakdown.net_bps < breakdown.min_net_bps {
+            return Err(InvariantViolation::InsufficientEdge {
+                expected_bps: breakdown.expected_bps,
+                fees_bps: breakdown.fees_bps,
+                funding_bps: breakdown.funding_bps,
+                net_bps: breakdown.net_bps,
+                min_net_bps: breakdown.min_net_bps,
+            });
+        }
+        Ok(EdgeCheck::Cleared(breakdown))
+    }
+
+    /// Whether the signal only shrinks an opposite position its account
+    /// holds on the signal's venue
+    fn reduces_account(signal: &TradeSignal, portfolio: &Portfolio) -> bool {
+        portfolio.positions.iter().any(|position| {
+            position.account == signal.account
+                && position.symbol == signal.symbol
+                && position.venue == signal.venue
+                && position.side != signal.side
+                && position.quantity >= signal.quantity
+        })
+    }
+
+    /// Price an order is valued at: its limit (a stop-limit's own limit
+    /// when `limit_price` is unset), else its trigger, else the market
+    /// reference (trailing stops)
+    pub fn order_price(signal: &TradeSignal, market: Price) -> Price {
+        let own_limit = match signal.order_type {
+            OrderType::StopLimit { limit, .. } => Some(limit),
+            _ => None,
+        };
+        signal.limit_price.or(own_limit).or(signal.order_type.trigger()).unwrap_or(market)
+    }
+
+    /// Check the order is large enough for the venue to accept and worth filling
+    ///
+    /// Notional is quantity × the order's own price (see `order_price`).
+    fn check_min_size(signal: &TradeSignal, limits: &SymbolLimits, market: Price) -> Result<(), InvariantViolation> {
+        if let Some(step) = limits.step_size {
+            if signal.quantity < step {
+                return Err(InvariantViolation::BelowStepSize {
+                    quantity: signal.quantity.get(),
+                    step: step.get(),
+                });
             }
         }
 
+        let notional = Self::order_price(signal, market).notional(signal.quantity)?;
+        if notional < limits.min_notional {
+            return Err(InvariantViolation::BelowMinNotional {
+                notional: notional.get(),
+                min: limits.min_notional.get(),
+            });
+        }
+
         Ok(())
     }
 
-    /// Check position size limits
-    fn check_position_size(symbol: &Symbol, quantity: Quantity) -> Result<(), InvariantViolation> {
-        let max_size = match symbol.0.as_str() {
-            "BTC/USD" => MAX_POSITION_SIZE_BTC,
-            "ETH/USD" => MAX_POSITION_SIZE_ETH,
-            "SOL/USD" => MAX_POSITION_SIZE_SOL,
-            _ => return Err(InvariantViolation::UnsupportedSymbol),
+    /// Price an order commits to, and the worst price the slippage tolerance allows
+    ///
+    /// Limit and market orders are measured against `market`; market orders
+    /// must carry their expected execution price in `limit_price` and are
+    /// rejected without one. A stop-limit's limit is measured against its
+    /// trigger, the market it expects when it fires. Other conditional orders
+    /// execute at market after triggering and have no bound here.
+    pub fn slippage_bound(signal: &TradeSignal, market: Price) -> Result<Option<SlippageBound>, InvariantViolation> {
+        let (price, reference) = match signal.order_type {
+            OrderType::Limit => (signal.limit_price.ok_or(InvariantViolation::InvalidPrice)?, market),
+            OrderType::Market => (signal.limit_price.ok_or(InvariantViolation::MissingExpectedPrice)?, market),
+            OrderType::StopLimit { trigger, limit } => (limit, trigger),
+            _ => return Ok(None),
+        };
+        if price.is_zero() || reference.is_zero() {
+            return Err(InvariantViolation::InvalidPrice);
+        }
+
+        Ok(Some(SlippageBound {
+            price,
+            reference,
+            worst: reference.adverse(signal.side, MAX_SLIPPAGE_TOLERANCE)?,
+        }))
+    }
+
+    /// Reject prices that deviate from the reference by more than the tolerance
+    /// in the adverse direction (above for buys, below for sells)
+    fn check_slippage(signal: &TradeSignal, market: Price) -> Result<(), InvariantViolation> {
+        let Some(bound) = Self::slippage_bound(signal, market)? else {
+            return Ok(());
+        };
+
+        let within = match signal.side {
+            Side::Buy => bound.price <= bound.worst,
+            Side::Sell => bound.price >= bound.worst,
         };
+        if !within {
+            let adverse = match signal.side {
+                Side::Buy => bound.price.delta(bound.reference),
+                Side::Sell => bound.reference.delta(bound.price),
+            };
+            let deviation = arith::checked_div("slippage deviation", adverse, bound.reference.get())
+                .map_err(UnitError::from)?;
+            return Err(InvariantViolation::SlippageToleranceExceeded {
+                side: signal.side,
+                price: bound.price.get(),
+                reference: bound.reference.get(),
+                deviation,
+                max: MAX_SLIPPAGE_TOLERANCE,
+            });
+        }
+
+        Ok(())
+    }
 
-        if quantity > max_size {
+    /// Size held in the signal's symbol once the signal fills: the
+    /// portfolio's positions in it netted long against short, plus the signal
+    pub fn projected_position(signal: &TradeSignal, portfolio: &Portfolio) -> Result<Quantity, InvariantViolation> {
+        let signed = |side: Side, quantity: Quantity| match side {
+            Side::Buy => quantity.get(),
+            Side::Sell => -quantity.get(),
+        };
+        let held = portfolio.positions.iter()
+            .filter(|position| position.symbol == signal.symbol)
+            .try_fold(Decimal::ZERO, |net, position| {
+                arith::checked_add("projected position", net, signed(position.side, position.quantity))
+            })
+            .map_err(UnitError::from)?;
+        let projected = arith::checked_add("projected position", held, signed(signal.side, signal.quantity))
+            .map_err(UnitError::from)?;
+        Ok(Quantity::new(projected.abs())?)
+    }
+
+    /// Check position size limits
+    fn check_position_size(limits: &SymbolLimits, quantity: Quantity) -> Result<(), InvariantViolation> {
+        if quantity > limits.max_position {
             return Err(InvariantViolation::PositionSizeExceeded {
-                quantity,
-                max: max_size,
+                quantity: quantity.get(),
+                max: limits.max_position.get(),
             });
         }
 
@@ -70,11 +334,11 @@ impl L0InvariantContract {
     }
 
     /// Check portfolio leverage
-    fn check_leverage(portfolio: &Portfolio) -> Result<(), InvariantViolation> {
-        if portfolio.leverage > MAX_LEVERAGE {
+    fn check_leverage(portfolio: &Portfolio, max_leverage: Decimal) -> Result<(), InvariantViolation> {
+        if portfolio.leverage > max_leverage {
             return Err(InvariantViolation::LeverageExceeded {
                 current: portfolio.leverage,
-                max: MAX_LEVERAGE,
+                max: max_leverage,
             });
         }
 
@@ -82,25 +346,95 @@ impl L0InvariantContract {
     }
 
     /// Check risk budget per trade
-    fn check_risk_budget(signal: &TradeSignal, portfolio: &Portfolio) -> Result<(), InvariantViolation> {
+    fn check_risk_budget(
+        signal: &TradeSignal,
+        portfolio: &Portfolio,
+        limits: &SymbolLimits,
+        market: Price,
+    ) -> Result<(), InvariantViolation> {
         // Calculate position value
-        let position_value = signal.quantity
-            * signal.limit_price.unwrap_or(Decimal::ZERO);
+        let position_value = Self::order_price(signal, market).notional(signal.quantity)?;
 
         // Calculate risk as fraction of equity
-        let risk_fraction = position_value / portfolio.equity;
+        if !portfolio.equity.is_positive() {
+            return Err(InvariantViolation::NonPositiveEquity);
+        }
+        let risk_fraction = position_value.ratio(portfolio.equity)?;
 
-        if risk_fraction < MIN_RISK_BUDGET {
+        if risk_fraction < limits.min_risk_budget() {
             return Err(InvariantViolation::RiskBudgetTooSmall {
                 fraction: risk_fraction,
-                min: MIN_RISK_BUDGET,
+                min: limits.min_risk_budget(),
             });
         }
 
-        if risk_fraction > MAX_RISK_BUDGET {
+        if risk_fraction > limits.max_risk_budget() {
             return Err(InvariantViolation::RiskBudgetExceeded {
                 fraction: risk_fraction,
-                max: MAX_RISK_BUDGET,
+                max: limits.max_risk_budget(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Check stop and trailing-stop parameters (independent of the market)
+    fn check_order_type(signal: &TradeSignal) -> Result<(), InvariantViolation> {
+        match signal.order_type {
+            OrderType::StopLimit { trigger, limit } => {
+                if trigger.is_zero() || limit.is_zero() {
+                    return Err(InvariantViolation::InvalidPrice);
+                }
+                // The limit must leave room to fill once triggered
+                let coherent = match signal.side {
+                    Side::Buy => limit >= trigger,
+                    Side::Sell => limit <= trigger,
+                };
+                if !coherent {
+                    return Err(InvariantViolation::InvalidStopLimit {
+                        side: signal.side,
+                        trigger: trigger.get(),
+                        limit: limit.get(),
+                    });
+                }
+            }
+            OrderType::StopMarket { trigger } if trigger.is_zero() => {
+                return Err(InvariantViolation::InvalidPrice);
+            }
+            OrderType::TrailingStop { offset } => {
+                let valid = match offset {
+                    TrailOffset::Absolute(distance) => !distance.is_zero(),
+                    TrailOffset::Percent(percent) => percent > Decimal::ZERO && percent < Decimal::ONE_HUNDRED,
+                };
+                if !valid {
+                    return Err(InvariantViolation::InvalidTrailOffset);
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Verify a stop's trigger sits on the correct side of the market
+    ///
+    /// Buy stops trigger on a rise, so the trigger must be above the market;
+    /// sell stops trigger on a fall, so it must be below. A trigger already
+    /// through the market would fire immediately.
+    pub fn verify_trigger_placement(signal: &TradeSignal, market: Price) -> Result<(), InvariantViolation> {
+        let Some(trigger) = signal.order_type.trigger() else {
+            return Ok(());
+        };
+
+        let correct_side = match signal.side {
+            Side::Buy => trigger > market,
+            Side::Sell => trigger < market,
+        };
+        if !correct_side {
+            return Err(InvariantViolation::TriggerWrongSide {
+                side: signal.side,
+                trigger: trigger.get(),
+                market: market.get(),
             });
         }
 
@@ -120,34 +454,304 @@ impl L0InvariantContract {
     }
 }
 
+/// Every invariant a signal violates, in invariant order
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(transparent)]
+pub struct ViolationSet(Vec<InvariantViolation>);
+
+impl ViolationSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, violation: InvariantViolation) {
+        self.0.push(violation);
+    }
+
+    /// Keep the violation, if `result` is one
+    pub fn record(&mut self, result: Result<(), InvariantViolation>) {
+        if let Err(violation) = result {
+            self.push(violation);
+        }
+    }
+
+    pub fn extend(&mut self, other: ViolationSet) {
+        self.0.extend(other.0);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, InvariantViolation> {
+        self.0.iter()
+    }
+
+    /// Error codes of every violation, in order
+    pub fn codes(&self) -> Vec<&'static str> {
+        self.0.iter().map(ErrorCode::code).collect()
+    }
+
+    /// Ok(()) when nothing was violated
+    pub fn into_result(self) -> Result<(), ViolationSet> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl From<InvariantViolation> for ViolationSet {
+    fn from(violation: InvariantViolation) -> Self {
+        Self(vec![violation])
+    }
+}
+
+impl IntoIterator for ViolationSet {
+    type Item = InvariantViolation;
+    type IntoIter = std::vec::IntoIter<InvariantViolation>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl std::fmt::Display for ViolationSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (idx, violation) in self.0.iter().enumerate() {
+            if idx > 0 {
+                f.write_str("; ")?;
+            }
+            write!(f, "{}", violation)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ViolationSet {}
+
+/// Slippage bound for one signal (see `L0InvariantContract::slippage_bound`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlippageBound {
+    /// Price the order commits to
+    pub price: Price,
+    /// Price slippage is measured from
+    pub reference: Price,
+    /// Worst acceptable price: `reference` moved adversely by the tolerance
+    pub worst: Price,
+}
+
+/// Outcome of the expected-edge check (see `L0InvariantContract::verify_edge`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum EdgeCheck {
+    /// The edge covers its costs by at least the floor
+    Cleared(EdgeBreakdown),
+    /// The signal's strategy is exempt
+    OptedOut,
+    /// The signal only reduces a position, so it is not checked
+    Exit,
+}
+
+/// How a signal's expected edge compares with its costs, in basis points
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct EdgeBreakdown {
+    pub expected_bps: Decimal,
+    /// Taker fee on entry and exit
+    pub fees_bps: Decimal,
+    /// Funding due within the holding period on the signal's side
+    pub funding_bps: Decimal,
+    pub net_bps: Decimal,
+    pub min_net_bps: Decimal,
+}
+
 /// Invariant violation error
-#[derive(Debug, Error, Clone)]
+#[derive(Debug, Error, Clone, Serialize)]
+#[serde(tag = "code", content = "details")]
 pub enum InvariantViolation {
     #[error("Negative contradiction score (must be >= 0)")]
+    #[serde(rename = "L0_NEGATIVE_CONTRADICTION")]
     NegativeContradiction,
 
     #[error("Position size exceeded: {quantity} > {max}")]
+    #[serde(rename = "L0_POSITION_SIZE")]
     PositionSizeExceeded { quantity: Decimal, max: Decimal },
 
     #[error("Leverage exceeded: {current} > {max}")]
+    #[serde(rename = "L0_LEVERAGE")]
     LeverageExceeded { current: Decimal, max: Decimal },
 
+    #[error("Leverage across all accounts exceeded: {current} > {max}")]
+    #[serde(rename = "L0_AGGREGATE_LEVERAGE")]
+    AggregateLeverageExceeded { current: Decimal, max: Decimal },
+
     #[error("Risk budget exceeded: {fraction} > {max}")]
+    #[serde(rename = "L0_RISK_BUDGET_EXCEEDED")]
     RiskBudgetExceeded { fraction: Decimal, max: Decimal },
 
     #[error("Risk budget too small: {fraction} < {min}")]
+    #[serde(rename = "L0_RISK_BUDGET_TOO_SMALL")]
     RiskBudgetTooSmall { fraction: Decimal, min: Decimal },
 
     #[error("Excessive entropy: energy exceeds threshold")]
+    #[serde(rename = "L0_EXCESSIVE_ENTROPY")]
     ExcessiveEntropy,
 
     #[error("Invalid price (must be > 0)")]
+    #[serde(rename = "L0_INVALID_PRICE")]
     InvalidPrice,
 
-    #[error("Unsupported symbol")]
-    UnsupportedSymbol,
+    #[error("Unsupported symbol: {0}")]
+    #[serde(rename = "L0_UNSUPPORTED_SYMBOL")]
+    UnsupportedSymbol(#[from] LimitsError),
 
     #[error("Hamiltonian energy divergence: {energy} > {threshold}")]
+    #[serde(rename = "L0_ENERGY_DIVERGENCE")]
     EnergyDivergence { energy: Decimal, threshold: Decimal },
+
+    #[error("{side:?} stop trigger {trigger} is on the wrong side of the market ({market})")]
+    #[serde(rename = "L0_TRIGGER_WRONG_SIDE")]
+    TriggerWrongSide { side: Side, trigger: Decimal, market: Decimal },
+
+    #[error("{side:?} stop-limit has limit {limit} that can never fill after trigger {trigger}")]
+    #[serde(rename = "L0_INVALID_STOP_LIMIT")]
+    InvalidStopLimit { side: Side, trigger: Decimal, limit: Decimal },
+
+    #[error("Trailing offset must be positive (and under 100%)")]
+    #[serde(rename = "L0_INVALID_TRAIL_OFFSET")]
+    InvalidTrailOffset,
+
+    #[error("Non-positive equity; risk budget is undefined")]
+    #[serde(rename = "L0_NON_POSITIVE_EQUITY")]
+    NonPositiveEquity,
+
+    #[error("Arithmetic error: {0}")]
+    #[serde(rename = "L0_ARITHMETIC")]
+    Arithmetic(#[from] UnitError),
+
+    #[error("{side:?} price {price} is {deviation} from reference {reference}, beyond slippage tolerance {max}")]
+    #[serde(rename = "L0_SLIPPAGE_TOLERANCE")]
+    SlippageToleranceExceeded { side: Side, price: Decimal, reference: Decimal, deviation: Decimal, max: Decimal },
+
+    #[error("Market order carries no expected execution price")]
+    #[serde(rename = "L0_MISSING_EXPECTED_PRICE")]
+    MissingExpectedPrice,
+
+    #[error("No market reference price (empty book)")]
+    #[serde(rename = "L0_NO_MARKET_REFERENCE")]
+    NoMarketReference,
+
+    #[error("Order notional {notional} below minimum {min}")]
+    #[serde(rename = "L0_MIN_NOTIONAL")]
+    BelowMinNotional { notional: Decimal, min: Decimal },
+
+    #[error("Order quantity {quantity} below lot step {step}")]
+    #[serde(rename = "L0_BELOW_STEP_SIZE")]
+    BelowStepSize { quantity: Decimal, step: Decimal },
+
+    #[error("Strategy {strategy} exposure {exposure} would exceed its allocation {budget}")]
+    #[serde(rename = "L0_ALLOCATION_EXCEEDED")]
+    AllocationExceeded { strategy: String, exposure: Decimal, budget: Decimal },
+
+    #[error("Strategy {strategy} gives no expected edge and is not opted out of the edge check")]
+    #[serde(rename = "L0_MISSING_EXPECTED_EDGE")]
+    MissingExpectedEdge { strategy: String },
+
+    #[error("Expected edge {expected_bps}bps - fees {fees_bps}bps - funding {funding_bps}bps = {net_bps}bps, below floor {min_net_bps}bps")]
+    #[serde(rename = "L0_INSUFFICIENT_EDGE")]
+    InsufficientEdge { expected_bps: Decimal, fees_bps: Decimal, funding_bps: Decimal, net_bps: Decimal, min_net_bps: Decimal },
+
+    #[error("SMT proof failed without naming an axiom: {reason}")]
+    #[serde(rename = "L0_UNPROVEN")]
+    Unproven { reason: String },
+
+    #[error("SMT proof did not finish within {timeout_ms}ms")]
+    #[serde(rename = "L0_VERIFICATION_TIMEOUT")]
+    VerificationTimeout { timeout_ms: u32 },
 }
 
+impl InvariantViolation {
+    /// Rejected on the trade's economics rather than its risk
+    pub fn is_economic(&self) -> bool {
+        matches!(self, InvariantViolation::MissingExpectedEdge { .. } | InvariantViolation::InsufficientEdge { .. })
+    }
+}
+
+impl ErrorCode for InvariantViolation {
+    fn code(&self) -> &'static str {
+        match self {
+            InvariantViolation::NegativeContradiction => "L0_NEGATIVE_CONTRADICTION",
+            InvariantViolation::PositionSizeExceeded { .. } => "L0_POSITION_SIZE",
+            InvariantViolation::LeverageExceeded { .. } => "L0_LEVERAGE",
+            InvariantViolation::AggregateLeverageExceeded { .. } => "L0_AGGREGATE_LEVERAGE",
+            InvariantViolation::RiskBudgetExceeded { .. } => "L0_RISK_BUDGET_EXCEEDED",
+            InvariantViolation::RiskBudgetTooSmall { .. } => "L0_RISK_BUDGET_TOO_SMALL",
+            InvariantViolation::ExcessiveEntropy => "L0_EXCESSIVE_ENTROPY",
+            InvariantViolation::InvalidPrice => "L0_INVALID_PRICE",
+            InvariantViolation::UnsupportedSymbol(_) => "L0_UNSUPPORTED_SYMBOL",
+            InvariantViolation::EnergyDivergence { .. } => "L0_ENERGY_DIVERGENCE",
+            InvariantViolation::TriggerWrongSide { .. } => "L0_TRIGGER_WRONG_SIDE",
+            InvariantViolation::InvalidStopLimit { .. } => "L0_INVALID_STOP_LIMIT",
+            InvariantViolation::InvalidTrailOffset => "L0_INVALID_TRAIL_OFFSET",
+            InvariantViolation::NonPositiveEquity => "L0_NON_POSITIVE_EQUITY",
+            InvariantViolation::Arithmetic(_) => "L0_ARITHMETIC",
+            InvariantViolation::SlippageToleranceExceeded { .. } => "L0_SLIPPAGE_TOLERANCE",
+            InvariantViolation::MissingExpectedPrice => "L0_MISSING_EXPECTED_PRICE",
+            InvariantViolation::NoMarketReference => "L0_NO_MARKET_REFERENCE",
+            InvariantViolation::BelowMinNotional { .. } => "L0_MIN_NOTIONAL",
+            InvariantViolation::BelowStepSize { .. } => "L0_BELOW_STEP_SIZE",
+            InvariantViolation::AllocationExceeded { .. } => "L0_ALLOCATION_EXCEEDED",
+            InvariantViolation::MissingExpectedEdge { .. } => "L0_MISSING_EXPECTED_EDGE",
+            InvariantViolation::InsufficientEdge { .. } => "L0_INSUFFICIENT_EDGE",
+            InvariantViolation::Unproven { .. } => "L0_UNPROVEN",
+            InvariantViolation::VerificationTimeout { .. } => "L0_VERIFICATION_TIMEOUT",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn price(value: i64) -> Price {
+        Price::new(Decimal::from(value)).unwrap()
+    }
+
+    #[test]
+    fn conditional_orders_are_valued_at_their_own_price() {
+        // 0.25 BTC near 65000 is about 16250, 0.8% of equity: inside the
+        // 0.25%-1% budget at any of these prices, nothing at a zero price
+        let portfolio = Portfolio::new(Amount::new(Decimal::from(2_000_000)));
+        let market = price(65_000);
+        let order_types = [
+            OrderType::StopLimit { trigger: price(65_050), limit: price(65_100) },
+            OrderType::StopMarket { trigger: price(65_050) },
+            OrderType::TrailingStop { offset: TrailOffset::Absolute(price(50)) },
+        ];
+        for order_type in order_types {
+            let signal = TradeSignal {
+                symbol: Symbol("BTC/USD".to_string()),
+                order_type,
+                limit_price: None,
+                entropy_count: Decimal::ZERO,
+                ..testing::signal()
+            };
+            let verdict = L0InvariantContract::verify_signal(&signal, &portfolio, &LimitsRegistry::builtin(), market);
+            assert!(verdict.is_ok(), "{:?}: {:?}", order_type, verdict);
+        }
+
+        // A stop-limit is valued at its limit, a stop-market at its trigger
+        let stop_limit = TradeSignal { order_type: order_types[0], limit_price: None, ..testing::signal() };
+        assert_eq!(L0InvariantContract::order_price(&stop_limit, market), price(65_100));
+        let stop_market = TradeSignal { order_type: order_types[1], limit_price: None, ..testing::signal() };
+        assert_eq!(L0InvariantContract::order_price(&stop_market, market), price(65_050));
+        let trailing = TradeSignal { order_type: order_types[2], limit_price: None, ..testing::signal() };
+        assert_eq!(L0InvariantContract::order_price(&trailing, market), market);
+    }
+}
@@ -0,0 +1,499 @@
+//! Key Management: The C=0 Signing Key
+//!
+//! The signing key is stored on disk encrypted under a passphrase (Argon2id
+//! key derivation, XChaCha20-Poly1305 encryption, bound to the public key).
+//! Once loaded, the secret lives only inside `CZeroKey`, which zeroizes it on
+//! drop and never exposes it through Debug or serde. Everything else works
+//! with the `VerifyingKey`.
+//!
+//! Signature nonces are reserved in blocks in a counter file beside the key
+//! (`<key>.nonce`), so nonces keep increasing across restarts without a disk
+//! write per signature.
+//!
+//! Keys rotate without a restart: `SigningKeys` holds the current key and a
+//! scheduled successor, switches signing at the activation time, and keeps
+//! the old key trusted for a grace period. Every step is recorded in the
+//! audit chain.
+
+use crate::audit::{AuditLog, AuditRecord};
+use crate::signature::{key_id, CZeroSignature};
+use crate::types::VerifiedOrder;
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use ed25519_dalek::{SigningKey, VerifyingKey};
+use rand_core::{OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Duration, Utc};
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tracing::{error, info};
+use zeroize::Zeroizing;
+
+/// Layout version of the encrypted key file
+const KEY_FILE_VERSION: u32 = 1;
+
+/// Shortest passphrase accepted when writing a key file
+pub const MIN_PASSPHRASE_LEN: usize = 12;
+
+/// Argon2id cost: 64 MiB, 3 passes, 1 lane
+const KDF_MEMORY_KIB: u32 = 64 * 1024;
+const KDF_PASSES: u32 = 3;
+const KDF_LANES: u32 = 1;
+
+/// Nonces reserved per counter-file write
+const NONCE_BLOCK: u64 = 1024;
+
+/// Encrypted key file as written to disk
+#[derive(Debug, Serialize, Deserialize)]
+struct KeyFile {
+    version: u32,
+    /// Hex-encoded verifying key (readable without the passphrase)
+    public_key: String,
+    kdf: KdfParams,
+    nonce: String,
+    ciphertext: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct KdfParams {
+    algorithm: String,
+    salt: String,
+    memory_kib: u32,
+    passes: u32,
+    lanes: u32,
+}
+
+/// The C=0 signing key
+///
+/// Deliberately neither Clone nor Serialize; share it behind an `Arc`.
+pub struct CZeroKey {
+    signing: SigningKey,
+    nonces: Mutex<NonceCounter>,
+}
+
+impl CZeroKey {
+    /// Generate a fresh random key
+    ///
+    /// Its nonces are not persisted, which is safe only because no other
+    /// process can hold the same key.
+    pub fn generate() -> Self {
+        let mut secret = Zeroizing::new([0u8; 32]);
+        OsRng.fill_bytes(secret.as_mut());
+        Self {
+            signing: SigningKey::from_bytes(&secret),
+            nonces: Mutex::new(NonceCounter::ephemeral()),
+        }
+    }
+
+    pub fn verifying_key(&self) -> VerifyingKey {
+        self.signing.verifying_key()
+    }
+
+    /// Short fingerprint carried in every signature this key makes
+    pub fn key_id(&self) -> String {
+        key_id(&self.verifying_key())
+    }
+
+    /// Hex-encoded verifying key, as listed in trusted-key configuration
+    pub fn public_key_hex(&self) -> String {
+        hex::encode(self.verifying_key().as_bytes())
+    }
+
+    /// C=0 signature over a verified order, valid for `validity`
+    pub fn sign(&self, order: &VerifiedOrder, validity: Duration) -> Result<CZeroSignature, KeyError> {
+        let nonce = self.nonces.lock().unwrap_or_else(|e| e.into_inner()).take()?;
+        Ok(CZeroSignature::sign(order, &self.signing, nonce, Utc::now() + validity))
+    }
+
+    /// Encrypt the key to `path` under `passphrase`
+    ///
+    /// Refuses to overwrite an existing file. On Unix the file is created
+    /// readable by its owner only.
+    pub fn save(&self, path: impl AsRef<Path>, passphrase: &str) -> Result<(), KeyError> {
+        if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+            return Err(KeyError::WeakPassphrase);
+        }
+
+        let mut salt = [0u8; 16];
+        OsRng.fill_bytes(&mut salt);
+        let mut nonce = [0u8; 24];
+        OsRng.fill_bytes(&mut nonce);
+
+        let kdf = KdfParams {
+            algorithm: "argon2id".to_string(),
+            salt: hex::encode(salt),
+            memory_kib: KDF_MEMORY_KIB,
+            passes: KDF_PASSES,
+            lanes: KDF_LANES,
+        };
+        let cipher = cipher(passphrase, &kdf)?;
+        let public_key = self.verifying_key();
+        let secret = Zeroizing::new(self.signing.to_bytes());
+        let ciphertext = cipher
+            .encrypt(XNonce::from_slice(&nonce), Payload { msg: secret.as_ref(), aad: public_key.as_bytes() })
+            .map_err(|_| KeyError::Format("encryption failed".to_string()))?;
+
+        let file = KeyFile {
+            version: KEY_FILE_VERSION,
+            public_key: hex::encode(public_key.as_bytes()),
+            kdf,
+            nonce: hex::encode(nonce),
+            ciphertext: hex::encode(ciphertext),
+        };
+        let contents = serde_json::to_string_pretty(&file)
+            .map_err(|e| KeyError::Format(e.to_string()))?;
+
+        let path = path.as_ref();
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut out = options.open(path)?;
+        out.write_all(contents.as_bytes())?;
+        out.sync_all()?;
+        Ok(())
+    }
+
+    /// Decrypt the key at `path` and open its nonce counter
+    pub fn load(path: impl AsRef<Path>, passphrase: &str) -> Result<Self, KeyError> {
+        let file = read_key_file(path.as_ref())?;
+        let public_key = parse_public_key(&file.public_key)?;
+
+        let nonce = decode_hex("nonce", &file.nonce)?;
+        if nonce.len() != 24 {
+            return Err(KeyError::Format("nonce must be 24 bytes".to_string()));
+        }
+        let ciphertext = decode_hex("ciphertext", &file.ciphertext)?;
+
+        let secret = Zeroizing::new(
+            cipher(passphrase, &file.kdf)?
+                .decrypt(XNonce::from_slice(&nonce), Payload { msg: &ciphertext, aad: public_key.as_bytes() })
+                .map_err(|_| KeyError::Decrypt)?,
+        );
+        let secret: Zeroizing<[u8; 32]> = Zeroizing::new(
+            secret.as_slice().try_into()
+                .map_err(|_| KeyError::Format("secret key must be 32 bytes".to_string()))?,
+        );
+
+        let key = Self {
+            signing: SigningKey::from_bytes(&secret),
+            nonces: Mutex::new(NonceCounter::open(nonce_path(path.as_ref()))?),
+        };
+        if key.verifying_key() != public_key {
+            return Err(KeyError::Format("public key does not match the encrypted secret".to_string()));
+        }
+        Ok(key)
+    }
+}
+
+impl fmt::Debug for CZeroKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CZeroKey")
+            .field("public_key", &self.public_key_hex())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Step in a signing key rotation, as recorded in the audit chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RotationStep {
+    /// A successor key was loaded with an activation time
+    Scheduled,
+    /// The key started signing
+    Activated,
+    /// The key's grace period ended; its signatures are no longer accepted
+    Retired,
+}
+
+/// Planned hand-over from the current key to the next
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RotationSchedule {
+    pub next_key_id: String,
+    pub activate_at: DateTime<Utc>,
+    /// How long the old key's signatures are accepted after `activate_at`
+    pub grace_secs: i64,
+}
+
+impl RotationSchedule {
+    /// When the old key stops being trusted
+    pub fn retire_at(&self) -> DateTime<Utc> {
+        self.activate_at + Duration::seconds(self.grace_secs)
+    }
+
+    /// Read a schedule file; `None` if there is no rotation in progress
+    pub fn load(path: impl AsRef<Path>) -> Result<Option<Self>, KeyError> {
+        match fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents)
+                .map(Some)
+                .map_err(|e| KeyError::Format(e.to_string())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), KeyError> {
+        let contents = serde_json::to_string_pretty(self).map_err(|e| KeyError::Format(e.to_string()))?;
+        fs::write(path, contents)?;
+        Ok(())
+    }
+}
+
+/// Where a trusted key stands at a given instant
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyStatus {
+    /// Scheduled but not yet signing
+    Pending,
+    Active,
+    /// Past its grace period
+    Retired,
+}
+
+/// A verifying key the executor accepts, with its validity window
+#[derive(Debug, Clone)]
+pub struct TrustedKey {
+    pub key: VerifyingKey,
+    pub key_id: String,
+    pub active_from: Option<DateTime<Utc>>,
+    pub retired_at: Option<DateTime<Utc>>,
+}
+
+impl TrustedKey {
+    /// A key trusted from now on, with no retirement scheduled
+    pub fn new(key: VerifyingKey) -> Self {
+        Self {
+            key_id: key_id(&key),
+            key,
+            active_from: None,
+            retired_at: None,
+        }
+    }
+
+    pub fn with_active_from(mut self, at: DateTime<Utc>) -> Self {
+        self.active_from = Some(at);
+        self
+    }
+
+    pub fn with_retired_at(mut self, at: DateTime<Utc>) -> Self {
+        self.retired_at = Some(at);
+        self
+    }
+
+    pub fn status(&self, at: DateTime<Utc>) -> KeyStatus {
+        if self.retired_at.is_some_and(|retired_at| at >= retired_at) {
+            KeyStatus::Retired
+        } else if self.active_from.is_some_and(|active_from| at < active_from) {
+            KeyStatus::Pending
+        } else {
+            KeyStatus::Active
+        }
+    }
+}
+
+/// The current signing key and an optional scheduled successor
+pub struct SigningKeys {
+    current: Arc<CZeroKey>,
+    next: Option<(Arc<CZeroKey>, RotationSchedule)>,
+    audit_log: Option<Arc<AuditLog>>,
+    /// Rotation steps already recorded
+    recorded: Mutex<Vec<RotationStep>>,
+}
+
+impl SigningKeys {
+    pub fn new(current: Arc<CZeroKey>) -> Self {
+        Self {
+            current,
+            next: None,
+            audit_log: None,
+            recorded: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Hand signing over to `next` at `schedule.activate_at`
+    pub fn with_next(mut self, next: Arc<CZeroKey>, schedule: RotationSchedule) -> Result<Self, KeyError> {
+        if next.key_id() != schedule.next_key_id {
+            return Err(KeyError::Format(format!(
+                "rotation schedule names key {} but the next key is {}",
+                schedule.next_key_id,
+                next.key_id()
+            )));
+        }
+        self.next = Some((next, schedule));
+        Ok(self)
+    }
+
+    /// Record rotation steps in the audit chain
+    pub fn with_audit_log(mut self, audit_log: Arc<AuditLog>) -> Self {
+        self.audit_log = Some(audit_log);
+        self
+    }
+
+    /// The key that signs at `now`
+    pub fn signer(&self, now: DateTime<Utc>) -> Arc<CZeroKey> {
+        self.poll(now);
+        match &self.next {
+            Some((next, schedule)) if now >= schedule.activate_at => next.clone(),
+            _ => self.current.clone(),
+        }
+    }
+
+    /// Record every rotation step reached by `now` (each only once)
+    pub fn poll(&self, now: DateTime<Utc>) {
+        let Some((next, schedule)) = &self.next else { return };
+        self.record(RotationStep::Scheduled, &next.key_id(), now.min(schedule.activate_at));
+        if now >= schedule.activate_at {
+            self.record(RotationStep::Activated, &next.key_id(), schedule.activate_at);
+        }
+        if now >= schedule.retire_at() {
+            self.record(RotationStep::Retired, &self.current.key_id(), schedule.retire_at());
+        }
+    }
+
+    /// Trust windows for the executor's safety checker
+    pub fn trusted(&self) -> Vec<TrustedKey> {
+        let current = TrustedKey::new(self.current.verifying_key());
+        match &self.next {
+            Some((next, schedule)) => vec![
+                current.with_retired_at(schedule.retire_at()),
+                TrustedKey::new(next.verifying_key()).with_active_from(schedule.activate_at),
+            ],
+            None => vec![current],
+        }
+    }
+
+    fn record(&self, step: RotationStep, key_id: &str, at: DateTime<Utc>) {
+        {
+            let mut recorded = self.recorded.lock().unwrap_or_else(|e| e.into_inner());
+            if recorded.contains(&step) {
+                return;
+            }
+            recorded.push(step);
+        }
+
+        info!("Signing key rotation: key {} {:?} at {}", key_id, step, at);
+        if let Some(log) = &self.audit_log {
+            let record = AuditRecord::KeyRotation { step, key_id: key_id.to_string(), at };
+            if let Err(e) = log.append("", None, record) {
+                error!("Failed to audit key rotation: {}", e);
+            }
+        }
+    }
+}
+
+/// Strictly increasing signature nonces
+struct NonceCounter {
+    next: u64,
+    /// Highest nonce recorded in the counter file
+    reserved: u64,
+    path: Option<PathBuf>,
+}
+
+impl NonceCounter {
+    fn ephemeral() -> Self {
+        Self { next: 1, reserved: u64::MAX, path: None }
+    }
+
+    fn open(path: PathBuf) -> Result<Self, KeyError> {
+        let reserved: u64 = match fs::read_to_string(&path) {
+            Ok(contents) => contents.trim().parse()
+                .map_err(|_| KeyError::Format(format!("corrupt nonce counter {}", path.display())))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => 0,
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self { next: reserved.saturating_add(1), reserved, path: Some(path) })
+    }
+
+    fn take(&mut self) -> Result<u64, KeyError> {
+        if self.next > self.reserved {
+            let reserved = self.next.checked_add(NONCE_BLOCK - 1)
+                .ok_or_else(|| KeyError::Format("nonce counter exhausted".to_string()))?;
+            if let Some(path) = &self.path {
+                // Write-then-rename, so a crash leaves either the old or the new reservation
+                let tmp = path.with_extension("nonce.tmp");
+                let mut out = fs::File::create(&tmp)?;
+                out.write_all(reserved.to_string().as_bytes())?;
+                out.sync_all()?;
+                fs::rename(&tmp, path)?;
+            }
+            self.reserved = reserved;
+        }
+        let nonce = self.next;
+        self.next += 1;
+        Ok(nonce)
+    }
+}
+
+fn nonce_path(key_path: &Path) -> PathBuf {
+    let mut path = key_path.as_os_str().to_owned();
+    path.push(".nonce");
+    PathBuf::from(path)
+}
+
+/// Read the public key from a key file without decrypting it
+pub fn read_public_key(path: impl AsRef<Path>) -> Result<VerifyingKey, KeyError> {
+    parse_public_key(&read_key_file(path.as_ref())?.public_key)
+}
+
+/// Parse a hex-encoded ed25519 verifying key
+pub fn parse_public_key(hex_key: &str) -> Result<VerifyingKey, KeyError> {
+    let bytes: [u8; 32] = decode_hex("public key", hex_key)?
+        .try_into()
+        .map_err(|_| KeyError::Format("public key must be 32 bytes".to_string()))?;
+    VerifyingKey::from_bytes(&bytes).map_err(|_| KeyError::Format("invalid ed25519 public key".to_string()))
+}
+
+fn read_key_file(path: &Path) -> Result<KeyFile, KeyError> {
+    let file: KeyFile = serde_json::from_str(&fs::read_to_string(path)?)
+        .map_err(|e| KeyError::Format(e.to_string()))?;
+    if file.version != KEY_FILE_VERSION {
+        return Err(KeyError::Format(format!("unsupported key file version {}", file.version)));
+    }
+    Ok(file)
+}
+
+/// Derive the file-encryption key from the passphrase
+fn cipher(passphrase: &str, kdf: &KdfParams) -> Result<XChaCha20Poly1305, KeyError> {
+    if kdf.algorithm != "argon2id" {
+        return Err(KeyError::Format(format!("unsupported KDF {}", kdf.algorithm)));
+    }
+    let salt = decode_hex("salt", &kdf.salt)?;
+    let params = Params::new(kdf.memory_kib, kdf.passes, kdf.lanes, Some(32))
+        .map_err(|e| KeyError::Format(e.to_string()))?;
+
+    let mut derived = Zeroizing::new([0u8; 32]);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), &salt, derived.as_mut())
+        .map_err(|e| KeyError::Format(e.to_string()))?;
+
+    XChaCha20Poly1305::new_from_slice(derived.as_ref()).map_err(|e| KeyError::Format(e.to_string()))
+}
+
+fn decode_hex(field: &str, value: &str) -> Result<Vec<u8>, KeyError> {
+    hex::decode(value).map_err(|e| KeyError::Format(format!("{}: {}", field, e)))
+}
+
+/// Key management error
+#[derive(Debug, thiserror::Error)]
+pub enum KeyError {
+    #[error("Key file I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Malformed key file: {0}")]
+    Format(String),
+
+    #[error("Wrong passphrase or corrupted key file")]
+    Decrypt,
+
+    #[error("Passphrase must be at least {MIN_PASSPHRASE_LEN} characters")]
+    WeakPassphrase,
+}
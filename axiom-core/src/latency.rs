@@ -0,0 +1,106 @@
+//! Latency Instrumentation: Per-Stage, Per-Venue Timing
+//!
+//! Pipeline stages report elapsed time through a `LatencyRecorder`, tagged
+//! with the stage and venue, so slowness can be attributed to a specific
+//! component rather than one undifferentiated pool.
+
+use crate::types::Venue;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Instrumented pipeline stage
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LatencyStage {
+    /// Exchange trade time → message received (network plus clock offset)
+    FeedDelay,
+    /// Raw exchange message → normalized tick
+    IngestParse,
+    /// Incremental update applied to the local book
+    BookApply,
+    /// Feature computation in the proposer
+    FeatureCalc,
+    /// L0 contract checks and SMT proof
+    Verify,
+    /// The SMT solver's check alone, within Verify
+    Solve,
+    /// C=0 signature generation
+    Sign,
+    /// Exchange submission round trip
+    SubmitRtt,
+}
+
+impl LatencyStage {
+    pub const ALL: [LatencyStage; 8] = [
+        LatencyStage::FeedDelay,
+        LatencyStage::IngestParse,
+        LatencyStage::BookApply,
+        LatencyStage::FeatureCalc,
+        LatencyStage::Verify,
+        LatencyStage::Solve,
+        LatencyStage::Sign,
+        LatencyStage::SubmitRtt,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LatencyStage::FeedDelay => "feed_delay",
+            LatencyStage::IngestParse => "ingest_parse",
+            LatencyStage::BookApply => "book_apply",
+            LatencyStage::FeatureCalc => "feature_calc",
+            LatencyStage::Verify => "verify",
+            LatencyStage::Solve => "solve",
+            LatencyStage::Sign => "sign",
+            LatencyStage::SubmitRtt => "submit_rtt",
+        }
+    }
+
+    /// Default p99 budget for the stage (microseconds)
+    pub fn default_p99_budget_us(&self) -> u64 {
+        match self {
+            LatencyStage::FeedDelay => 250_000,
+            LatencyStage::IngestParse => 1_000,
+            LatencyStage::BookApply => 500,
+            LatencyStage::FeatureCalc => 2_000,
+            LatencyStage::Verify => 50_000,
+            LatencyStage::Solve => 20_000,
+            LatencyStage::Sign => 1_000,
+            LatencyStage::SubmitRtt => 100_000,
+        }
+    }
+}
+
+impl std::fmt::Display for LatencyStage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Receiver of stage latency samples
+pub trait LatencyRecorder: Send + Sync {
+    fn record(&self, stage: LatencyStage, venue: &Venue, elapsed: Duration);
+}
+
+/// Percentiles for one (stage, venue) pair
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StageLatency {
+    pub stage: LatencyStage,
+    pub venue: Venue,
+    pub count: usize,
+    pub p50_us: u64,
+    pub p99_us: u64,
+    pub p999_us: u64,
+}
+
+/// Per-stage latency percentiles, ordered by (stage, venue)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LatencyBreakdown {
+    pub stages: Vec<StageLatency>,
+}
+
+impl LatencyBreakdown {
+    /// Entries for a single stage across all venues
+    pub fn stage(&self, stage: LatencyStage) -> impl Iterator<Item = &StageLatency> {
+        self.stages.iter().filter(move |entry| entry.stage == stage)
+    }
+}
@@ -8,12 +8,43 @@
 pub mod constants;
 pub mod invariants;
 pub mod types;
+pub mod units;
+pub mod arith;
+pub mod limits;
+pub mod instruments;
 pub mod signature;
+pub mod canonical;
+pub mod keys;
 pub mod errors;
+pub mod audit;
+pub mod events;
+pub mod latency;
+pub mod funnel;
+pub mod signal_builder;
+pub mod regime;
+pub mod shadow;
+pub mod rng;
+
+#[cfg(test)]
+mod testing;
 
 pub use constants::*;
 pub use invariants::*;
 pub use types::*;
+pub use units::*;
+pub use arith::ArithmeticError;
+pub use limits::*;
+pub use instruments::*;
 pub use signature::*;
+pub use canonical::*;
+pub use keys::*;
 pub use errors::*;
+pub use audit::*;
+pub use events::{EventLog, EventListener, Correlation, SystemEvent};
+pub use latency::*;
+pub use funnel::*;
+pub use signal_builder::*;
+pub use regime::*;
+pub use shadow::*;
+pub use rng::AxiomRng;
 
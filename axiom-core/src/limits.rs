@@ -0,0 +1,360 @@
+//! Limits Registry: Per-Symbol Trading Limits
+//!
+//! One place that knows, for every tradable symbol, the maximum position and
+//! order size, the minimum notional, and any risk-budget override, plus the
+//! account-wide leverage cap. Built-in defaults cover the launch symbols;
+//! configuration adds or overrides entries, so supporting a new symbol is a
+//! config change. Accounts may tighten the leverage cap for their own
+//! positions, and strategies may be held to a share of equity and to an
+//! expected edge that covers their costs. Share it across components behind
+//! an `Arc`.
+
+use crate::constants::*;
+use crate::errors::ErrorCode;
+use crate::types::{AccountId, Amount, Quantity, StrategyId, Symbol};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Limits for one symbol
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SymbolLimits {
+    /// Largest position (base units)
+    pub max_position: Quantity,
+    /// Largest single order (base units)
+    pub max_order: Quantity,
+    /// Smallest order notional (quote currency); smaller orders are dust
+    #[serde(default)]
+    pub min_notional: Amount,
+    /// Order quantity increment (the instrument's lot step), when known
+    #[serde(default)]
+    pub step_size: Option<Quantity>,
+    /// Per-trade risk budget floor, as a fraction of equity (default MIN_RISK_BUDGET)
+    #[serde(default)]
+    pub min_risk_budget: Option<Decimal>,
+    /// Per-trade risk budget ceiling, as a fraction of equity (default MAX_RISK_BUDGET)
+    #[serde(default)]
+    pub max_risk_budget: Option<Decimal>,
+}
+
+impl SymbolLimits {
+    pub fn new(max_position: Quantity, max_order: Quantity) -> Self {
+        Self {
+            max_position,
+            max_order,
+            min_notional: Amount::ZERO,
+            step_size: None,
+            min_risk_budget: None,
+            max_risk_budget: None,
+        }
+    }
+
+    pub fn with_min_notional(mut self, min_notional: Amount) -> Self {
+        self.min_notional = min_notional;
+        self
+    }
+
+    pub fn with_step_size(mut self, step_size: Quantity) -> Self {
+        self.step_size = Some(step_size);
+        self
+    }
+
+    pub fn with_risk_budget(mut self, min: Decimal, max: Decimal) -> Self {
+        self.min_risk_budget = Some(min);
+        self.max_risk_budget = Some(max);
+        self
+    }
+
+    /// Effective per-trade risk budget floor
+    pub fn min_risk_budget(&self) -> Decimal {
+        self.min_risk_budget.unwrap_or(MIN_RISK_BUDGET)
+    }
+
+    /// Effective per-trade risk budget ceiling
+    pub fn max_risk_budget(&self) -> Decimal {
+        self.max_risk_budget.unwrap_or(MAX_RISK_BUDGET)
+    }
+
+    fn validate(&self, symbol: &Symbol, errors: &mut Vec<LimitsError>) {
+        let mut invalid = |reason: String| errors.push(LimitsError::Invalid { symbol: symbol.0.clone(), reason });
+
+        if self.max_position.is_zero() {
+            invalid("max_position must be positive".to_string());
+        }
+        if self.max_order.is_zero() {
+            invalid("max_order must be positive".to_string());
+        }
+        if self.max_order > self.max_position {
+            invalid(format!("max_order {} exceeds max_position {}", self.max_order, self.max_position));
+        }
+        if self.min_notional.get() < Decimal::ZERO {
+            invalid(format!("min_notional {} is negative", self.min_notional));
+        }
+        if self.step_size.is_some_and(Quantity::is_zero) {
+            invalid("step_size must be positive".to_string());
+        }
+        let (min, max) = (self.min_risk_budget(), self.max_risk_budget());
+        if min <= Decimal::ZERO || max > Decimal::ONE || min > max {
+            invalid(format!("risk budget must satisfy 0 < min ({}) <= max ({}) <= 1", min, max));
+        }
+    }
+}
+
+/// Limits for one account's own positions, on top of the registry's
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountLimits {
+    /// Leverage cap for the account alone (the registry cap still applies)
+    #[serde(default)]
+    pub max_leverage: Option<Decimal>,
+    /// Daily drawdown that trips the account's circuit breaker (default: the
+    /// portfolio-wide limit)
+    #[serde(default)]
+    pub max_daily_drawdown: Option<Decimal>,
+}
+
+impl AccountLimits {
+    fn validate(&self, account: &AccountId, errors: &mut Vec<LimitsError>) {
+        let mut invalid = |reason: String| errors.push(LimitsError::InvalidAccount { account: account.0.clone(), reason });
+
+        if self.max_leverage.is_some_and(|max| max <= Decimal::ZERO) {
+            invalid("max_leverage must be positive".to_string());
+        }
+        if self.max_daily_drawdown.is_some_and(|max| max <= Decimal::ZERO || max >= Decimal::ONE) {
+            invalid("max_daily_drawdown must be between 0 and 1".to_string());
+        }
+    }
+}
+
+/// Economics a signal must clear after its costs (an `[edge]` table)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EdgePolicy {
+    /// Least expected edge, in basis points, left after round-trip fees and
+    /// imminent funding
+    pub min_net_edge_bps: Decimal,
+    /// How long positions are expected to be held; a funding payment due
+    /// within it is charged against the edge
+    pub holding_period_sec: u64,
+    /// Strategies that cannot estimate their edge; their signals skip the check
+    pub opt_out: BTreeSet<String>,
+}
+
+impl Default for EdgePolicy {
+    fn default() -> Self {
+        Self { min_net_edge_bps: Decimal::ZERO, holding_period_sec: 3600, opt_out: BTreeSet::new() }
+    }
+}
+
+impl EdgePolicy {
+    /// Whether `strategy` signals are exempt from the edge check
+    pub fn opts_out(&self, strategy: &StrategyId) -> bool {
+        self.opt_out.contains(&strategy.0)
+    }
+
+    pub fn holding_period(&self) -> chrono::Duration {
+        i64::try_from(self.holding_period_sec).ok()
+            .and_then(chrono::Duration::try_seconds)
+            .unwrap_or(chrono::Duration::MAX)
+    }
+}
+
+/// Symbol → limits
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LimitsRegistry {
+    limits: BTreeMap<Symbol, SymbolLimits>,
+    /// Portfolio leverage cap (never above MAX_LEVERAGE)
+    max_leverage: Decimal,
+    accounts: BTreeMap<AccountId, AccountLimits>,
+    /// Share of portfolio equity each strategy may hold as gross exposure
+    allocations: BTreeMap<StrategyId, Decimal>,
+    /// Expected-edge floor and the strategies exempt from it
+    edge: EdgePolicy,
+}
+
+impl Default for LimitsRegistry {
+    fn default() -> Self {
+        Self {
+            limits: BTreeMap::new(),
+            max_leverage: MAX_LEVERAGE,
+            accounts: BTreeMap::new(),
+            allocations: BTreeMap::new(),
+            edge: EdgePolicy::default(),
+        }
+    }
+}
+
+impl LimitsRegistry {
+    /// An empty registry (every symbol unknown)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Built-in limits for the launch symbols
+    pub fn builtin() -> Self {
+        let limits = [
+            ("BTC/USD", MAX_POSITION_SIZE_BTC, MAX_ORDER_SIZE_BTC),
+            ("ETH/USD", MAX_POSITION_SIZE_ETH, MAX_ORDER_SIZE_ETH),
+            ("SOL/USD", MAX_POSITION_SIZE_SOL, MAX_ORDER_SIZE_SOL),
+        ];
+        limits.into_iter().fold(Self::new(), |registry, (symbol, max_position, max_order)| {
+            // The constants are non-negative literals
+            let limits = SymbolLimits::new(
+                Quantity::new(max_position).unwrap_or(Quantity::ZERO),
+                Quantity::new(max_order).unwrap_or(Quantity::ZERO),
+            )
+            .with_min_notional(Amount::new(MIN_ORDER_NOTIONAL_USD));
+            registry.with_symbol(Symbol(symbol.to_string()), limits)
+        })
+    }
+
+    /// Add a symbol, or replace its limits
+    pub fn with_symbol(mut self, symbol: Symbol, limits: SymbolLimits) -> Self {
+        self.limits.insert(symbol, limits);
+        self
+    }
+
+    /// Cap portfolio leverage at `max_leverage` (clamped to MAX_LEVERAGE)
+    pub fn with_max_leverage(mut self, max_leverage: Decimal) -> Self {
+        self.max_leverage = max_leverage.min(MAX_LEVERAGE);
+        self
+    }
+
+    /// Portfolio leverage cap
+    pub fn max_leverage(&self) -> Decimal {
+        self.max_leverage
+    }
+
+    /// Add an account's limits, or replace them
+    pub fn with_account(mut self, account: AccountId, limits: AccountLimits) -> Self {
+        self.accounts.insert(account, limits);
+        self
+    }
+
+    /// Limits for `account` (none beyond the registry's when unlisted)
+    pub fn account(&self, account: &AccountId) -> AccountLimits {
+        self.accounts.get(account).cloned().unwrap_or_default()
+    }
+
+    /// Leverage cap for one account's positions: its own, never above the
+    /// portfolio cap
+    pub fn account_max_leverage(&self, account: &AccountId) -> Decimal {
+        self.accounts.get(account)
+            .and_then(|limits| limits.max_leverage)
+            .map_or(self.max_leverage, |max| max.min(self.max_leverage))
+    }
+
+    /// Give `strategy` a fraction of portfolio equity as its exposure budget
+    pub fn with_allocation(mut self, strategy: StrategyId, fraction: Decimal) -> Self {
+        self.allocations.insert(strategy, fraction);
+        self
+    }
+
+    /// `strategy`'s budget as a fraction of equity (unbudgeted when unlisted)
+    pub fn allocation(&self, strategy: &StrategyId) -> Option<Decimal> {
+        self.allocations.get(strategy).copied()
+    }
+
+    /// Every strategy's budget fraction
+    pub fn allocations(&self) -> &BTreeMap<StrategyId, Decimal> {
+        &self.allocations
+    }
+
+    /// Hold signals to `policy`'s expected-edge floor
+    pub fn with_edge_policy(mut self, policy: EdgePolicy) -> Self {
+        self.edge = policy;
+        self
+    }
+
+    pub fn edge_policy(&self) -> &EdgePolicy {
+        &self.edge
+    }
+
+    /// Limits for `symbol`
+    pub fn get(&self, symbol: &Symbol) -> Result<&SymbolLimits, LimitsError> {
+        self.limits.get(symbol).ok_or_else(|| LimitsError::UnknownSymbol(symbol.0.clone()))
+    }
+
+    pub fn contains(&self, symbol: &Symbol) -> bool {
+        self.limits.contains_key(symbol)
+    }
+
+    pub fn symbols(&self) -> impl Iterator<Item = &Symbol> {
+        self.limits.keys()
+    }
+
+    /// Check every entry, reporting all problems at once
+    pub fn validate(&self) -> Result<(), Vec<LimitsError>> {
+        let mut errors = Vec::new();
+        for (symbol, limits) in &self.limits {
+            limits.validate(symbol, &mut errors);
+        }
+        for (account, limits) in &self.accounts {
+            limits.validate(account, &mut errors);
+        }
+        for (strategy, fraction) in &self.allocations {
+            if *fraction <= Decimal::ZERO || *fraction > Decimal::ONE {
+                errors.push(LimitsError::InvalidAllocation {
+                    strategy: strategy.0.clone(),
+                    reason: format!("allocation {} must be in (0, 1]", fraction),
+                });
+            }
+        }
+        let total: Decimal = self.allocations.values().sum();
+        if total > Decimal::ONE {
+            errors.push(LimitsError::AllocationsOvercommitted(total));
+        }
+        if self.edge.holding_period_sec == 0 {
+            errors.push(LimitsError::InvalidEdgePolicy("holding_period_sec must be positive".to_string()));
+        }
+        if self.edge.opt_out.iter().any(|strategy| strategy.trim().is_empty()) {
+            errors.push(LimitsError::InvalidEdgePolicy("opt_out lists an empty strategy name".to_string()));
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Limits lookup or validation error
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, thiserror::Error)]
+#[serde(tag = "code", content = "details")]
+pub enum LimitsError {
+    #[error("No limits configured for symbol {0}")]
+    #[serde(rename = "LIMITS_UNKNOWN_SYMBOL")]
+    UnknownSymbol(String),
+
+    #[error("Invalid limits for {symbol}: {reason}")]
+    #[serde(rename = "LIMITS_INVALID")]
+    Invalid { symbol: String, reason: String },
+
+    #[error("Invalid limits for account {account}: {reason}")]
+    #[serde(rename = "LIMITS_INVALID_ACCOUNT")]
+    InvalidAccount { account: String, reason: String },
+
+    #[error("Invalid allocation for strategy {strategy}: {reason}")]
+    #[serde(rename = "LIMITS_INVALID_ALLOCATION")]
+    InvalidAllocation { strategy: String, reason: String },
+
+    #[error("Strategy allocations sum to {0}, more than all of equity")]
+    #[serde(rename = "LIMITS_ALLOCATIONS_OVERCOMMITTED")]
+    AllocationsOvercommitted(Decimal),
+
+    #[error("Invalid edge policy: {0}")]
+    #[serde(rename = "LIMITS_INVALID_EDGE_POLICY")]
+    InvalidEdgePolicy(String),
+}
+
+impl ErrorCode for LimitsError {
+    fn code(&self) -> &'static str {
+        match self {
+            LimitsError::UnknownSymbol(_) => "LIMITS_UNKNOWN_SYMBOL",
+            LimitsError::Invalid { .. } => "LIMITS_INVALID",
+            LimitsError::InvalidAccount { .. } => "LIMITS_INVALID_ACCOUNT",
+            LimitsError::InvalidAllocation { .. } => "LIMITS_INVALID_ALLOCATION",
+            LimitsError::AllocationsOvercommitted(_) => "LIMITS_ALLOCATIONS_OVERCOMMITTED",
+            LimitsError::InvalidEdgePolicy(_) => "LIMITS_INVALID_EDGE_POLICY",
+        }
+    }
+}
@@ -0,0 +1,209 @@
+//! Regime State Machine: Market Regime Transitions with Hysteresis
+//!
+//! Classifies the market from volatility, trend, and entropy metrics. Each
+//! regime is entered past one threshold and only left once the metric falls
+//! back past a second, looser one, and a de-escalation waits out a minimum
+//! dwell time, so a metric hovering at a boundary does not flap the regime.
+//! Escalation to a more restrictive regime is always immediate. Time is an
+//! input, never read from the clock, so a replayed metric sequence
+//! reproduces every transition.
+
+use crate::types::{MarketRegime, Symbol};
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Inputs to one regime evaluation
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegimeMetrics {
+    /// Annualized realized volatility
+    pub volatility: Decimal,
+    /// Relative price drift over the detector's window (signed)
+    pub trend: Decimal,
+    /// Entropy count (market disorder)
+    pub entropy: Decimal,
+}
+
+/// Entry and exit level for one metric; `exit` is looser than `enter`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Band {
+    pub enter: Decimal,
+    pub exit: Decimal,
+}
+
+impl Band {
+    pub fn new(enter: Decimal, exit: Decimal) -> Self {
+        Self { enter, exit }
+    }
+}
+
+/// Transition rules
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RegimeThresholds {
+    /// Entropy above `enter` is Unprovable (stand down)
+    pub unprovable_entropy: Band,
+    /// Volatility at or above `enter` is Disorderly
+    pub disorderly_volatility: Band,
+    /// Volatility at or above `enter` is Volatile
+    pub volatile_volatility: Band,
+    /// Absolute trend at or above `enter` is Trending
+    pub trending_trend: Band,
+    /// Volatility at or below `enter` is Quiet
+    pub quiet_volatility: Band,
+    /// Minimum time in a regime before de-escalating out of it
+    pub min_dwell_secs: i64,
+}
+
+impl Default for RegimeThresholds {
+    fn default() -> Self {
+        Self {
+            unprovable_entropy: Band::new(crate::constants::DELTA_U_MAX_SQ, dec!(0.0000000000005)),
+            disorderly_volatility: Band::new(dec!(1.5), dec!(1.2)),
+            volatile_volatility: Band::new(dec!(0.8), dec!(0.6)),
+            trending_trend: Band::new(dec!(0.01), dec!(0.005)),
+            quiet_volatility: Band::new(dec!(0.2), dec!(0.3)),
+            min_dwell_secs: 300,
+        }
+    }
+}
+
+impl RegimeThresholds {
+    /// The regime these metrics call for, given the current one
+    ///
+    /// Regimes are tested from most to least restrictive. The current regime
+    /// is held while its metric stays past the exit level; any other is
+    /// entered only past its entry level.
+    pub fn classify(&self, current: MarketRegime, metrics: &RegimeMetrics) -> MarketRegime {
+        let past = |regime: MarketRegime, band: &Band, value: Decimal, above: bool| {
+            let level = if current == regime { band.exit } else { band.enter };
+            if above { value >= level } else { value <= level }
+        };
+
+        if metrics.entropy > self.unprovable_entropy.enter
+            || (current == MarketRegime::Unprovable && metrics.entropy > self.unprovable_entropy.exit)
+        {
+            MarketRegime::Unprovable
+        } else if past(MarketRegime::Disorderly, &self.disorderly_volatility, metrics.volatility, true) {
+            MarketRegime::Disorderly
+        } else if past(MarketRegime::Volatile, &self.volatile_volatility, metrics.volatility, true) {
+            MarketRegime::Volatile
+        } else if past(MarketRegime::Trending, &self.trending_trend, metrics.trend.abs(), true) {
+            MarketRegime::Trending
+        } else if past(MarketRegime::Quiet, &self.quiet_volatility, metrics.volatility, false) {
+            MarketRegime::Quiet
+        } else {
+            MarketRegime::Normal
+        }
+    }
+}
+
+/// Current regime and when it was entered (what gets persisted)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegimeState {
+    pub regime: MarketRegime,
+    pub entered_at: DateTime<Utc>,
+}
+
+/// A regime change
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegimeTransition {
+    pub from: MarketRegime,
+    pub to: MarketRegime,
+    pub at: DateTime<Utc>,
+    /// Time spent in `from`
+    pub dwell_secs: i64,
+    pub metrics: RegimeMetrics,
+}
+
+/// Regime for one market, advanced by successive metric observations
+#[derive(Debug, Clone)]
+pub struct RegimeStateMachine {
+    thresholds: RegimeThresholds,
+    state: RegimeState,
+}
+
+impl RegimeStateMachine {
+    /// Start in Normal at `at`
+    pub fn new(thresholds: RegimeThresholds, at: DateTime<Utc>) -> Self {
+        Self::restore(thresholds, RegimeState { regime: MarketRegime::Normal, entered_at: at })
+    }
+
+    /// Resume from a persisted state
+    pub fn restore(thresholds: RegimeThresholds, state: RegimeState) -> Self {
+        Self { thresholds, state }
+    }
+
+    pub fn regime(&self) -> MarketRegime {
+        self.state.regime
+    }
+
+    pub fn state(&self) -> RegimeState {
+        self.state
+    }
+
+    /// Apply one observation taken at `at`; returns the transition, if any
+    ///
+    /// Moving to a more restrictive regime happens at once; moving to a less
+    /// restrictive one waits until the current regime has lasted
+    /// `min_dwell_secs`.
+    pub fn observe(&mut self, metrics: RegimeMetrics, at: DateTime<Utc>) -> Option<RegimeTransition> {
+        let from = self.state.regime;
+        let to = self.thresholds.classify(from, &metrics);
+        if to == from {
+            return None;
+        }
+
+        let dwell = at - self.state.entered_at;
+        if to.severity() < from.severity() && dwell < Duration::seconds(self.thresholds.min_dwell_secs) {
+            return None;
+        }
+
+        self.state = RegimeState { regime: to, entered_at: at };
+        Some(RegimeTransition { from, to, at, dwell_secs: dwell.num_seconds(), metrics })
+    }
+}
+
+/// Persisted regime per symbol
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegimeSnapshot {
+    pub symbols: BTreeMap<Symbol, RegimeState>,
+}
+
+impl RegimeSnapshot {
+    /// Read a snapshot; a missing file is an empty one
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, RegimeError> {
+        match fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents).map_err(|e| RegimeError::Format(e.to_string())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Write the snapshot (temp file + rename)
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), RegimeError> {
+        let path = path.as_ref();
+        let contents = serde_json::to_string_pretty(self).map_err(|e| RegimeError::Format(e.to_string()))?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, contents)?;
+        fs::rename(&tmp, path)?;
+        Ok(())
+    }
+}
+
+/// Regime persistence error
+#[derive(Debug, thiserror::Error)]
+pub enum RegimeError {
+    #[error("Regime state I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid regime state: {0}")]
+    Format(String),
+}
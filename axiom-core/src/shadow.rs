@@ -0,0 +1,169 @@
+//! Shadow Strategies: Live-vs-Candidate Comparison
+//!
+//! A shadow strategy sees the same books as the live one and has its signals
+//! verified and signed, but never executed. Each book produces one
+//! observation per shadow strategy; a day's observations fold into a
+//! `ShadowComparison` for the daily report.
+
+use crate::types::*;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// Divergence cases kept per strategy per day
+pub const MAX_SHADOW_DIVERGENCES: usize = 50;
+
+/// Market features at the moment of a decision: everything the proposer
+/// decides from, in a fixed field order, so a journaled decision can be
+/// reproduced from its record
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeatureVector {
+    pub mid: Price,
+    /// Touches weighted by the opposite side's size (None in vectors
+    /// recorded before it existed)
+    #[serde(default)]
+    pub microprice: Option<Price>,
+    pub spread_pct: Decimal,
+    pub depth_imbalance: Decimal,
+    pub contradiction_score: Decimal,
+    /// `contradiction_score` weighted by confidence in its inputs (zero in
+    /// vectors recorded before it existed)
+    #[serde(default)]
+    pub adjusted_contradiction_score: Decimal,
+    /// Age of the book and of the on-chain reading behind the newer one
+    #[serde(default)]
+    pub cex_age_ms: i64,
+    #[serde(default)]
+    pub onchain_age_ms: i64,
+    pub entropy: Decimal,
+    /// Wilder's RSI over the symbol's recent mids, once there are enough
+    #[serde(default)]
+    pub rsi: Option<Decimal>,
+    /// Annualized realized volatility of the symbol's recent mids
+    #[serde(default)]
+    pub volatility: Decimal,
+    /// Perpetual premium over the book plus funding, when the symbol's
+    /// funding is known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub funding_basis: Option<Decimal>,
+    /// Notional liquidated per side over the liquidation window (zero when
+    /// none, or in vectors recorded before it existed)
+    #[serde(default)]
+    pub liquidation_intensity: LiquidationIntensity,
+    /// Book shape over the depth window (empty in vectors recorded
+    /// before it existed)
+    #[serde(default)]
+    pub book_slope: BookSlope,
+}
+
+/// One book's live and shadow decisions for one shadow strategy
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShadowObservation {
+    pub strategy: String,
+    pub symbol: Symbol,
+    pub at: DateTime<Utc>,
+    /// False when the shadow budget skipped this book
+    pub evaluated: bool,
+    /// Side of the verified live order, if any
+    pub live: Option<Side>,
+    /// Side of the verified shadow order, if any
+    pub shadow: Option<Side>,
+    /// The shadow proposed but the verifier rejected it
+    pub shadow_rejected: bool,
+    pub features: Option<FeatureVector>,
+    /// Hypothetical PnL since start under the paper-fill model
+    pub live_pnl: Decimal,
+    pub shadow_pnl: Decimal,
+}
+
+impl ShadowObservation {
+    /// Live and shadow made the same call (both silent counts)
+    pub fn agrees(&self) -> bool {
+        self.live == self.shadow
+    }
+}
+
+/// A book where live and shadow disagreed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShadowDivergence {
+    pub at: DateTime<Utc>,
+    pub symbol: Symbol,
+    pub live: Option<Side>,
+    pub shadow: Option<Side>,
+    pub features: Option<FeatureVector>,
+}
+
+/// One shadow strategy against live over a period
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ShadowComparison {
+    /// Books both strategies decided on
+    pub books: u64,
+    /// Books skipped by the shadow budget
+    pub skipped: u64,
+    pub agreements: u64,
+    /// Books where at least one side signalled, and how many of those agreed
+    pub signal_books: u64,
+    pub signal_agreements: u64,
+    pub live_signals: u64,
+    pub shadow_signals: u64,
+    pub shadow_rejections: u64,
+    /// Hypothetical PnL over the period (paper-fill model)
+    pub live_pnl: Decimal,
+    pub shadow_pnl: Decimal,
+    /// Cumulative PnL at the first observation of the period
+    #[serde(default)]
+    pub live_pnl_open: Option<Decimal>,
+    #[serde(default)]
+    pub shadow_pnl_open: Option<Decimal>,
+    /// First `MAX_SHADOW_DIVERGENCES` disagreements, with their features
+    pub divergences: Vec<ShadowDivergence>,
+}
+
+impl ShadowComparison {
+    pub fn record(&mut self, observation: &ShadowObservation) {
+        let live_open = *self.live_pnl_open.get_or_insert(observation.live_pnl);
+        let shadow_open = *self.shadow_pnl_open.get_or_insert(observation.shadow_pnl);
+        self.live_pnl = observation.live_pnl - live_open;
+        self.shadow_pnl = observation.shadow_pnl - shadow_open;
+
+        if !observation.evaluated {
+            self.skipped += 1;
+            return;
+        }
+        self.books += 1;
+        self.live_signals += observation.live.is_some() as u64;
+        self.shadow_signals += observation.shadow.is_some() as u64;
+        self.shadow_rejections += observation.shadow_rejected as u64;
+        let signalled = observation.live.is_some() || observation.shadow.is_some();
+        self.signal_books += signalled as u64;
+        if observation.agrees() {
+            self.agreements += 1;
+            self.signal_agreements += signalled as u64;
+        } else if self.divergences.len() < MAX_SHADOW_DIVERGENCES {
+            self.divergences.push(ShadowDivergence {
+                at: observation.at,
+                symbol: observation.symbol.clone(),
+                live: observation.live,
+                shadow: observation.shadow,
+                features: observation.features.clone(),
+            });
+        }
+    }
+
+    /// Fraction of books with the same decision
+    pub fn agreement_rate(&self) -> Decimal {
+        ratio(self.agreements, self.books)
+    }
+
+    /// Fraction of books where someone signalled that had the same decision
+    pub fn signal_agreement_rate(&self) -> Decimal {
+        ratio(self.signal_agreements, self.signal_books)
+    }
+}
+
+fn ratio(numerator: u64, denominator: u64) -> Decimal {
+    if denominator == 0 {
+        return Decimal::ZERO;
+    }
+    Decimal::from(numerator) / Decimal::from(denominator)
+}
@@ -0,0 +1,334 @@
+//! Signal Builder: Validated TradeSignal Construction
+//!
+//! Strategies build signals here rather than filling in a struct, so an
+//! inconsistent signal (a stop-limit whose limit leaves no room to fill, a
+//! market order with a limit, a zero quantity, an unlisted symbol) fails at
+//! construction with every problem listed, instead of much later in the
+//! verifier or at the venue.
+
+use crate::errors::ErrorCode;
+use crate::limits::{LimitsError, LimitsRegistry};
+use crate::types::{AccountId, OrderType, Price, Quantity, Side, StrategyId, Symbol, TradeSignal, TrailOffset, Venue};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+
+/// Builder for `TradeSignal`; see `TradeSignal::builder`
+#[derive(Debug, Clone, Default)]
+pub struct TradeSignalBuilder {
+    account: AccountId,
+    strategy: StrategyId,
+    symbol: Option<Symbol>,
+    venue: Option<Venue>,
+    side: Option<Side>,
+    order_type: Option<OrderType>,
+    quantity: Option<Quantity>,
+    limit_price: Option<Price>,
+    expected_price: Option<Price>,
+    stop_price: Option<Price>,
+    timestamp: Option<DateTime<Utc>>,
+    contradiction_score: Decimal,
+    entropy_count: Decimal,
+    expected_edge_bps: Option<Decimal>,
+}
+
+impl TradeSignal {
+    /// Start building a signal
+    pub fn builder() -> TradeSignalBuilder {
+        TradeSignalBuilder::new()
+    }
+}
+
+impl TradeSignalBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Account the order is for (default: the default account)
+    pub fn with_account(mut self, account: AccountId) -> Self {
+        self.account = account;
+        self
+    }
+
+    /// Strategy the order's exposure is attributed to (default: the default strategy)
+    pub fn with_strategy(mut self, strategy: StrategyId) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    pub fn with_symbol(mut self, symbol: Symbol) -> Self {
+        self.symbol = Some(symbol);
+        self
+    }
+
+    pub fn with_venue(mut self, venue: Venue) -> Self {
+        self.venue = Some(venue);
+        self
+    }
+
+    pub fn with_side(mut self, side: Side) -> Self {
+        self.side = Some(side);
+        self
+    }
+
+    pub fn with_order_type(mut self, order_type: OrderType) -> Self {
+        self.order_type = Some(order_type);
+        self
+    }
+
+    pub fn with_quantity(mut self, quantity: Quantity) -> Self {
+        self.quantity = Some(quantity);
+        self
+    }
+
+    /// Limit price (Limit orders; optional for StopLoss/TakeProfit)
+    pub fn with_limit_price(mut self, price: Price) -> Self {
+        self.limit_price = Some(price);
+        self
+    }
+
+    /// Price a market order expects to execute at (required for Market
+    /// orders; the slippage invariant measures it against the market)
+    pub fn with_expected_price(mut self, price: Price) -> Self {
+        self.expected_price = Some(price);
+        self
+    }
+
+    /// Stop price (StopLoss/TakeProfit orders)
+    pub fn with_stop_price(mut self, price: Price) -> Self {
+        self.stop_price = Some(price);
+        self
+    }
+
+    /// Signal time (default: when `build` is called)
+    pub fn with_timestamp(mut self, timestamp: DateTime<Utc>) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
+    pub fn with_scores(mut self, contradiction_score: Decimal, entropy_count: Decimal) -> Self {
+        self.contradiction_score = contradiction_score;
+        self.entropy_count = entropy_count;
+        self
+    }
+
+    /// Edge the strategy expects before costs, in basis points (the L0
+    /// contract checks it covers fees and imminent funding)
+    pub fn with_expected_edge_bps(mut self, edge_bps: Decimal) -> Self {
+        self.expected_edge_bps = Some(edge_bps);
+        self
+    }
+
+    /// Validate and build, reporting every problem at once
+    pub fn build(self, limits: &LimitsRegistry) -> Result<TradeSignal, InvalidSignal> {
+        let mut problems = Vec::new();
+        let mut missing = |field: &'static str| problems.push(SignalProblem::MissingField(field));
+
+        let symbol = self.symbol.clone().or_else(|| { missing("symbol"); None });
+        let venue = self.venue.clone().or_else(|| { missing("venue"); None });
+        let side = self.side.or_else(|| { missing("side"); None });
+        let order_type = self.order_type.or_else(|| { missing("order_type"); None });
+        let quantity = self.quantity.or_else(|| { missing("quantity"); None });
+
+        if let Some(symbol) = &symbol {
+            if let Err(e) = limits.get(symbol) {
+                problems.push(SignalProblem::UnknownSymbol(e));
+            }
+        }
+        if quantity.is_some_and(Quantity::is_zero) {
+            problems.push(SignalProblem::ZeroQuantity);
+        }
+        for (field, price) in [("limit_price", self.limit_price), ("expected_price", self.expected_price), ("stop_price", self.stop_price)] {
+            if price.is_some_and(Price::is_zero) {
+                problems.push(SignalProblem::ZeroPrice(field));
+            }
+        }
+        if let (Some(side), Some(order_type)) = (side, order_type) {
+            self.check_prices(side, &order_type, &mut problems);
+        }
+
+        match (symbol, venue, side, order_type, quantity) {
+            (Some(symbol), Some(venue), Some(side), Some(order_type), Some(quantity)) if problems.is_empty() => {
+                Ok(TradeSignal {
+                    account: self.account,
+                    strategy: self.strategy,
+                    symbol,
+                    venue,
+                    side,
+                    order_type,
+                    quantity,
+                    // Market orders carry their expected price in the limit slot
+                    limit_price: self.limit_price.or(self.expected_price),
+                    stop_price: self.stop_price,
+                    timestamp: self.timestamp.unwrap_or_else(Utc::now),
+                    contradiction_score: self.contradiction_score,
+                    entropy_count: self.entropy_count,
+                    expected_edge_bps: self.expected_edge_bps,
+                })
+            }
+            _ => Err(InvalidSignal(problems)),
+        }
+    }
+
+    /// Which prices each order type needs, allows, and how they must relate
+    fn check_prices(&self, side: Side, order_type: &OrderType, problems: &mut Vec<SignalProblem>) {
+        let name = order_type_name(order_type);
+        let mut require = |field: &'static str, price: Option<Price>| {
+            if price.is_none() {
+                problems.push(SignalProblem::MissingPrice { field, order_type: name });
+            }
+        };
+        let mut forbid = Vec::new();
+
+        match order_type {
+            OrderType::Limit => {
+                require("limit_price", self.limit_price);
+                forbid.extend([("expected_price", self.expected_price), ("stop_price", self.stop_price)]);
+            }
+            OrderType::Market => {
+                require("expected_price", self.expected_price);
+                forbid.extend([("limit_price", self.limit_price), ("stop_price", self.stop_price)]);
+            }
+            OrderType::StopLoss | OrderType::TakeProfit => {
+                require("stop_price", self.stop_price);
+                forbid.push(("expected_price", self.expected_price));
+                if let (Some(stop), Some(limit)) = (self.stop_price, self.limit_price) {
+                    check_stop_limit(side, stop, limit, problems);
+                }
+            }
+            // Trigger and limit live in the order type itself
+            OrderType::StopLimit { trigger, limit } => {
+                if trigger.is_zero() || limit.is_zero() {
+                    problems.push(SignalProblem::ZeroPrice("trigger"));
+                }
+                check_stop_limit(side, *trigger, *limit, problems);
+                forbid.extend([("limit_price", self.limit_price), ("expected_price", self.expected_price), ("stop_price", self.stop_price)]);
+            }
+            OrderType::StopMarket { trigger } => {
+                if trigger.is_zero() {
+                    problems.push(SignalProblem::ZeroPrice("trigger"));
+                }
+                forbid.extend([("limit_price", self.limit_price), ("expected_price", self.expected_price), ("stop_price", self.stop_price)]);
+            }
+            OrderType::TrailingStop { offset } => {
+                let valid = match offset {
+                    TrailOffset::Absolute(distance) => !distance.is_zero(),
+                    TrailOffset::Percent(percent) => *percent > Decimal::ZERO && *percent < Decimal::ONE_HUNDRED,
+                };
+                if !valid {
+                    problems.push(SignalProblem::InvalidTrailOffset);
+                }
+                forbid.extend([("limit_price", self.limit_price), ("expected_price", self.expected_price), ("stop_price", self.stop_price)]);
+            }
+        }
+
+        for (field, price) in forbid {
+            if price.is_some() {
+                problems.push(SignalProblem::UnexpectedPrice { field, order_type: name });
+            }
+        }
+    }
+}
+
+/// A stop's limit must leave room to fill once triggered: at or above the
+/// stop for buys, at or below it for sells
+fn check_stop_limit(side: Side, stop: Price, limit: Price, problems: &mut Vec<SignalProblem>) {
+    let coherent = match side {
+        Side::Buy => limit >= stop,
+        Side::Sell => limit <= stop,
+    };
+    if !coherent {
+        problems.push(SignalProblem::StopLimitMismatch { side, stop: stop.get(), limit: limit.get() });
+    }
+}
+
+fn order_type_name(order_type: &OrderType) -> &'static str {
+    match order_type {
+        OrderType::Limit => "Limit",
+        OrderType::Market => "Market",
+        OrderType::StopLoss => "StopLoss",
+        OrderType::TakeProfit => "TakeProfit",
+        OrderType::StopLimit { .. } => "StopLimit",
+        OrderType::StopMarket { .. } => "StopMarket",
+        OrderType::TrailingStop { .. } => "TrailingStop",
+    }
+}
+
+/// One reason a signal could not be built
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, thiserror::Error)]
+#[serde(tag = "code", content = "details")]
+pub enum SignalProblem {
+    #[error("Missing required field {0}")]
+    #[serde(rename = "SIGNAL_MISSING_FIELD")]
+    MissingField(&'static str),
+
+    #[error("{order_type} order requires {field}")]
+    #[serde(rename = "SIGNAL_MISSING_PRICE")]
+    MissingPrice { field: &'static str, order_type: &'static str },
+
+    #[error("{order_type} order does not take {field}")]
+    #[serde(rename = "SIGNAL_UNEXPECTED_PRICE")]
+    UnexpectedPrice { field: &'static str, order_type: &'static str },
+
+    #[error("{0} must be positive")]
+    #[serde(rename = "SIGNAL_ZERO_PRICE")]
+    ZeroPrice(&'static str),
+
+    #[error("Quantity must be positive")]
+    #[serde(rename = "SIGNAL_ZERO_QUANTITY")]
+    ZeroQuantity,
+
+    #[error("{side:?} stop at {stop} with limit {limit} leaves no room to fill")]
+    #[serde(rename = "SIGNAL_STOP_LIMIT_MISMATCH")]
+    StopLimitMismatch { side: Side, stop: Decimal, limit: Decimal },
+
+    #[error("Trailing offset must be positive (and under 100%)")]
+    #[serde(rename = "SIGNAL_INVALID_TRAIL_OFFSET")]
+    InvalidTrailOffset,
+
+    #[error("{0}")]
+    #[serde(rename = "SIGNAL_UNKNOWN_SYMBOL")]
+    UnknownSymbol(LimitsError),
+}
+
+impl ErrorCode for SignalProblem {
+    fn code(&self) -> &'static str {
+        match self {
+            SignalProblem::MissingField(_) => "SIGNAL_MISSING_FIELD",
+            SignalProblem::MissingPrice { .. } => "SIGNAL_MISSING_PRICE",
+            SignalProblem::UnexpectedPrice { .. } => "SIGNAL_UNEXPECTED_PRICE",
+            SignalProblem::ZeroPrice(_) => "SIGNAL_ZERO_PRICE",
+            SignalProblem::ZeroQuantity => "SIGNAL_ZERO_QUANTITY",
+            SignalProblem::StopLimitMismatch { .. } => "SIGNAL_STOP_LIMIT_MISMATCH",
+            SignalProblem::InvalidTrailOffset => "SIGNAL_INVALID_TRAIL_OFFSET",
+            SignalProblem::UnknownSymbol(_) => "SIGNAL_UNKNOWN_SYMBOL",
+        }
+    }
+}
+
+/// Every problem found building a signal
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub struct InvalidSignal(pub Vec<SignalProblem>);
+
+impl InvalidSignal {
+    /// Error codes of every problem, in order
+    pub fn codes(&self) -> Vec<&'static str> {
+        self.0.iter().map(ErrorCode::code).collect()
+    }
+}
+
+impl std::fmt::Display for InvalidSignal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Invalid trade signal: ")?;
+        for (idx, problem) in self.0.iter().enumerate() {
+            if idx > 0 {
+                f.write_str("; ")?;
+            }
+            write!(f, "{}", problem)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for InvalidSignal {}
@@ -4,10 +4,15 @@
 //! all L0 invariants. This provides cryptographic provenance.
 
 use crate::types::*;
-use ed25519_dalek::{SigningKey, VerifyingKey, Signature, Signer, Verifier, SignatureError as Ed25519Error};
-use sha3::{Sha3_256, Digest};
+use crate::canonical::{canonical_digest, canonical_digest_with, canonical_message_with, Canonical, CanonicalWriter, Encoding};
+use crate::errors::ErrorCode;
+use ed25519_dalek::{SigningKey, VerifyingKey, Signature, Signer, Verifier};
 use serde::{Deserialize, Serialize};
-use chrono::Utc;
+use sha3::{Sha3_256, Digest};
+use chrono::{DateTime, Duration, Utc};
+
+/// How long a fresh C=0 signature remains valid by default
+pub const DEFAULT_SIGNATURE_VALIDITY_MS: i64 = 30_000;
 
 /// C=0 Signature: Proof that consistency error equals zero
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,77 +21,295 @@ pub struct CZeroSignature {
     pub signature: Vec<u8>,
     /// The verifying key (public key)
     pub verifying_key: Vec<u8>,
+    /// Fingerprint of the verifying key (signed)
+    pub key_id: String,
     /// Hash of the order data
     pub order_hash: String,
     /// Timestamp of signature generation
     pub timestamp: chrono::DateTime<Utc>,
+    /// Per-key counter, strictly increasing across signatures (signed)
+    pub nonce: u64,
+    /// The signature is rejected after this instant (signed)
+    pub expires_at: DateTime<Utc>,
+    /// Canonical layout of the signed message and `order_hash`; signatures
+    /// from before the binary layout carry none and are JSON
+    #[serde(default, skip_serializing_if = "is_json")]
+    pub encoding: Encoding,
+}
+
+fn is_json(encoding: &Encoding) -> bool {
+    *encoding == Encoding::Json
+}
+
+/// Exactly what a C=0 signature signs
+struct SignedPayload<'a> {
+    order: &'a VerifiedOrder,
+    key_id: &'a str,
+    nonce: u64,
+    expires_at: DateTime<Utc>,
+}
+
+impl Canonical for SignedPayload<'_> {
+    fn encode(&self, out: &mut CanonicalWriter) {
+        out.object()
+            .field("expires_at", &self.expires_at)
+            .field("key_id", self.key_id)
+            .field("nonce", &self.nonce)
+            .field("order", self.order)
+            .finish();
+    }
 }
 
 impl CZeroSignature {
     /// Generate a C=0 signature for a verified order
-    pub fn sign(order: &VerifiedOrder, signing_key: &SigningKey) -> Self {
-        // Hash the order data
-        let mut hasher = Sha3_256::new();
-        hasher.update(serde_json::to_string(order).unwrap().as_bytes());
-        let order_hash = format!("{:x}", hasher.finalize());
-
-        // Create message: order_hash + proof + timestamp
-        let message = format!("{}:{}:{}", 
-            order_hash,
-            order.proof_signature,
-            order.verified_at.timestamp()
-        );
-
-        // Sign the message
-        let signature = signing_key.sign(message.as_bytes());
+    ///
+    /// The signed message is the canonical (binary) encoding of the order
+    /// (signal, proof, proof signature, verification time) together with
+    /// `nonce` and `expires_at`, so no two signatures are alike and none lives
+    /// forever.
+    pub fn sign(order: &VerifiedOrder, signing_key: &SigningKey, nonce: u64, expires_at: DateTime<Utc>) -> Self {
+        let encoding = Encoding::Binary;
+        let verifying_key = signing_key.verifying_key();
+        let key_id = key_id(&verifying_key);
+        let payload = SignedPayload { order, key_id: &key_id, nonce, expires_at };
+        let signature = signing_key.sign(&canonical_message_with(encoding, CZERO_DOMAIN, &payload));
 
         Self {
             signature: signature.to_bytes().to_vec(),
-            verifying_key: signing_key.verifying_key().to_bytes().to_vec(),
-            order_hash,
+            verifying_key: verifying_key.to_bytes().to_vec(),
+            key_id,
+            order_hash: order_hash_with(encoding, order),
             timestamp: Utc::now(),
+            nonce,
+            expires_at,
+            encoding,
         }
     }
 
-    /// Verify a C=0 signature
-    pub fn verify(&self, order: &VerifiedOrder) -> Result<(), SignatureError> {
-        // Reconstruct the message
-        let message = format!("{}:{}:{}",
-            self.order_hash,
-            order.proof_signature,
-            order.verified_at.timestamp()
-        );
+    /// Whether `order_hash` is the hash of `order` (in this signature's layout)
+    pub fn covers(&self, order: &VerifiedOrder) -> bool {
+        self.order_hash == order_hash_with(self.encoding, order)
+    }
+
+    /// Verify a C=0 signature for execution at `now`
+    ///
+    /// Rejects signatures that expired more than `skew_tolerance` ago. Nonce
+    /// freshness depends on what has been seen before and is checked by the
+    /// caller (the executor's safety checker).
+    pub fn verify(&self, order: &VerifiedOrder, now: DateTime<Utc>, skew_tolerance: Duration) -> Result<(), SignatureError> {
+        if now > self.expires_at + skew_tolerance {
+            return Err(SignatureError::Expired { expires_at: self.expires_at });
+        }
+        self.verify_integrity(order)
+    }
+
+    /// Verify that the signature covers `order`, ignoring expiry
+    ///
+    /// For replaying historical records (e.g. the audit chain), whose
+    /// signatures have long expired.
+    pub fn verify_integrity(&self, order: &VerifiedOrder) -> Result<(), SignatureError> {
+        let (verifying_key, signature, message) = self.prepare(order)?;
+        verifying_key.verify(&message, &signature)
+            .map_err(|_| SignatureError::VerificationFailed)?;
+
+        Ok(())
+    }
+
+    /// Everything but the ed25519 check: order hash, key, key id, signature
+    /// format, and the signed message
+    fn prepare(&self, order: &VerifiedOrder) -> Result<(VerifyingKey, Signature, Vec<u8>), SignatureError> {
+        if !self.covers(order) {
+            return Err(SignatureError::OrderHashMismatch);
+        }
 
         // Reconstruct verifying key
         let verifying_key = VerifyingKey::from_bytes(
             self.verifying_key.as_slice().try_into()
                 .map_err(|_| SignatureError::InvalidKey)?
         ).map_err(|_| SignatureError::InvalidKey)?;
+        if self.key_id != key_id(&verifying_key) {
+            return Err(SignatureError::KeyIdMismatch);
+        }
 
         // Reconstruct signature
         let signature = Signature::from_bytes(
             self.signature.as_slice().try_into()
                 .map_err(|_| SignatureError::InvalidSignature)?
-        ).map_err(|_| SignatureError::InvalidSignature)?;
+        );
 
-        // Verify
-        verifying_key.verify(message.as_bytes(), &signature)
-            .map_err(|_| SignatureError::VerificationFailed)?;
+        let payload = SignedPayload {
+            order,
+            key_id: &self.key_id,
+            nonce: self.nonce,
+            expires_at: self.expires_at,
+        };
+        Ok((verifying_key, signature, canonical_message_with(self.encoding, CZERO_DOMAIN, &payload)))
+    }
+}
 
-        Ok(())
+/// Signatures checked per ed25519 batch; a failed batch is re-verified one
+/// by one, so this also bounds the cost of pinpointing a bad signature
+pub const SIGNATURE_BATCH_SIZE: usize = 1024;
+
+/// Verify many C=0 signatures, ignoring expiry (see `verify_integrity`)
+///
+/// Returns one result per `(order, signature)` pair, in input order. Each
+/// pair is prepared individually, then the well-formed ones are checked in
+/// ed25519 batches; when a batch fails, its signatures are verified
+/// individually to find which ones are bad.
+pub fn verify_batch(items: &[(&VerifiedOrder, &CZeroSignature)]) -> Vec<Result<(), SignatureError>> {
+    let mut results: Vec<Result<(), SignatureError>> = Vec::with_capacity(items.len());
+    let mut prepared = Vec::with_capacity(items.len());
+    for (idx, (order, signature)) in items.iter().enumerate() {
+        match signature.prepare(order) {
+            Ok(parts) => {
+                results.push(Ok(()));
+                prepared.push((idx, parts));
+            }
+            Err(e) => results.push(Err(e)),
+        }
+    }
+
+    for chunk in prepared.chunks(SIGNATURE_BATCH_SIZE) {
+        let messages: Vec<&[u8]> = chunk.iter().map(|(_, (_, _, message))| message.as_slice()).collect();
+        let signatures: Vec<Signature> = chunk.iter().map(|(_, (_, signature, _))| *signature).collect();
+        let keys: Vec<VerifyingKey> = chunk.iter().map(|(_, (key, _, _))| *key).collect();
+        if ed25519_dalek::verify_batch(&messages, &signatures, &keys).is_ok() {
+            continue;
+        }
+        for (idx, (key, signature, message)) in chunk {
+            if key.verify(message, signature).is_err() {
+                results[*idx] = Err(SignatureError::VerificationFailed);
+            }
+        }
+    }
+
+    results
+}
+
+/// Domain tag of the message a C=0 signature signs
+const CZERO_DOMAIN: &str = "czero";
+
+/// Short fingerprint identifying a signing key: the first 8 bytes of the
+/// SHA3-256 of its verifying key, in hex
+pub fn key_id(verifying_key: &VerifyingKey) -> String {
+    let digest = Sha3_256::digest(verifying_key.as_bytes());
+    hex::encode(&digest[..8])
+}
+
+/// SHA3-256 hash of a verified order's canonical (binary) encoding
+pub fn order_hash(order: &VerifiedOrder) -> String {
+    order_hash_with(Encoding::Binary, order)
+}
+
+/// SHA3-256 hash of a verified order in the given canonical layout
+pub fn order_hash_with(encoding: Encoding, order: &VerifiedOrder) -> String {
+    canonical_digest_with(encoding, "order", order)
+}
+
+/// SHA3-256 hash of a trade signal (identifies an order across its lifecycle)
+pub fn signal_hash(signal: &TradeSignal) -> String {
+    canonical_digest("signal", signal)
+}
+
+/// SHA3-256 hash of an SMT proof
+pub fn proof_hash(proof: &Proof) -> String {
+    canonical_digest("proof", proof)
+}
+
+/// A proof together with the signal it proves
+struct ProvenSignal<'a> {
+    signal: &'a TradeSignal,
+    proof: &'a Proof,
+}
+
+impl Canonical for ProvenSignal<'_> {
+    fn encode(&self, out: &mut CanonicalWriter) {
+        out.object()
+            .field("proof", self.proof)
+            .field("signal", self.signal)
+            .finish();
     }
 }
 
+/// The verifier's `proof_signature`: `C=0:` and the SHA3-256 hash of
+/// `signal` and `proof` together, so the proof cannot be carried over to
+/// another signal
+pub fn proof_signature(signal: &TradeSignal, proof: &Proof) -> String {
+    format!("C=0:{}", canonical_digest("proven_signal", &ProvenSignal { signal, proof }))
+}
+
+/// Whether `order.proof_signature` commits to its signal and proof
+///
+/// The older proof-only form (`C=0:` and `proof_hash`) is not accepted: it
+/// does not bind the proof to the order it is attached to.
+pub fn proof_signature_matches(order: &VerifiedOrder) -> bool {
+    order.proof_signature == proof_signature(&order.signal, &order.proof)
+}
+
 /// Signature verification error
-#[derive(Debug, thiserror::Error)]
+#[derive(Debug, Serialize, thiserror::Error)]
+#[serde(tag = "code", content = "details")]
 pub enum SignatureError {
     #[error("Invalid verifying key")]
+    #[serde(rename = "SIG_INVALID_KEY")]
     InvalidKey,
 
     #[error("Invalid signature format")]
+    #[serde(rename = "SIG_INVALID_SIGNATURE")]
     InvalidSignature,
 
     #[error("Signature verification failed")]
+    #[serde(rename = "SIG_VERIFICATION_FAILED")]
     VerificationFailed,
+
+    #[error("Key ID does not match the verifying key")]
+    #[serde(rename = "SIG_KEY_ID_MISMATCH")]
+    KeyIdMismatch,
+
+    #[error("Signature does not cover this order (order hash mismatch)")]
+    #[serde(rename = "SIG_ORDER_HASH_MISMATCH")]
+    OrderHashMismatch,
+
+    #[error("Signature expired at {expires_at}")]
+    #[serde(rename = "SIG_EXPIRED")]
+    Expired { expires_at: DateTime<Utc> },
 }
 
+impl ErrorCode for SignatureError {
+    fn code(&self) -> &'static str {
+        match self {
+            SignatureError::InvalidKey => "SIG_INVALID_KEY",
+            SignatureError::InvalidSignature => "SIG_INVALID_SIGNATURE",
+            SignatureError::VerificationFailed => "SIG_VERIFICATION_FAILED",
+            SignatureError::KeyIdMismatch => "SIG_KEY_ID_MISMATCH",
+            SignatureError::OrderHashMismatch => "SIG_ORDER_HASH_MISMATCH",
+            SignatureError::Expired { .. } => "SIG_EXPIRED",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use crate::units::Quantity;
+    use rust_decimal::Decimal;
+
+    #[test]
+    fn proof_signature_is_bound_to_the_signal() {
+        let order = testing::order();
+        assert!(proof_signature_matches(&order));
+
+        let mut moved = order.clone();
+        moved.signal.quantity = Quantity::new(Decimal::ONE).unwrap();
+        assert!(!proof_signature_matches(&moved));
+    }
+
+    #[test]
+    fn proof_only_signature_is_rejected() {
+        let mut order = testing::order();
+        order.proof_signature = format!("C=0:{}", proof_hash(&order.proof));
+        assert!(!proof_signature_matches(&order));
+    }
+}
@@ -0,0 +1,53 @@
+//! Fixtures shared by the unit tests
+
+use crate::types::*;
+use crate::units::{Price, Quantity};
+use chrono::{DateTime, TimeZone, Utc};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+/// Fixed instant, so fixtures hash the same on every run
+pub fn at() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2026, 1, 2, 3, 4, 5).unwrap()
+}
+
+pub fn signal() -> TradeSignal {
+    TradeSignal {
+        account: AccountId::default(),
+        strategy: StrategyId::default(),
+        symbol: Symbol("BTC/USDT".to_string()),
+        venue: Venue::Binance,
+        side: Side::Buy,
+        order_type: OrderType::Limit,
+        quantity: Quantity::new(Decimal::new(25, 2)).unwrap(),
+        limit_price: Some(Price::new(Decimal::new(6500050, 2)).unwrap()),
+        stop_price: None,
+        timestamp: at(),
+        contradiction_score: Decimal::new(8, 1),
+        entropy_count: Decimal::new(3, 0),
+        expected_edge_bps: None,
+    }
+}
+
+pub fn proof() -> Proof {
+    Proof {
+        satisfiable: true,
+        model: HashMap::from([("leverage".to_string(), "1/4".to_string())]),
+        axioms_satisfied: vec!["MaxLeverage".to_string(), "PositionLimit".to_string()],
+        solve_us: 0,
+        cached: false,
+    }
+}
+
+/// A verified order whose proof signature matches its signal and proof
+pub fn order() -> VerifiedOrder {
+    let signal = signal();
+    let proof = proof();
+    VerifiedOrder {
+        proof_signature: crate::signature::proof_signature(&signal, &proof),
+        signal,
+        proof,
+        verified_at: at(),
+        signature: None,
+    }
+}
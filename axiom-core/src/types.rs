@@ -6,26 +6,232 @@ use rust_decimal::Decimal;
 use num_rational::Rational64;
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
-use std::collections::HashMap;
-
-/// Fixed-point price representation (ensures determinism)
-pub type Price = Decimal;
-pub type Quantity = Decimal;
-pub type Amount = Decimal;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+pub use crate::units::{Amount, Price, Quantity};
 
 /// Rational number for precise calculations
 pub type Rational = Rational64;
 
 /// Symbol identifier (e.g., "BTC/USD")
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct Symbol(pub String);
 
-/// Venue identifier (e.g., "binance", "bybit")
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub struct Venue(pub String);
+/// Name of the account new signals use when none is configured
+pub const DEFAULT_ACCOUNT: &str = "default";
 
-/// Order side
+/// Trading account (one set of venue credentials, positions and limits)
+///
+/// Records written before accounts existed carry none and load as the
+/// default account.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct AccountId(pub String);
+
+impl AccountId {
+    pub fn is_default(&self) -> bool {
+        self.0 == DEFAULT_ACCOUNT
+    }
+}
+
+impl Default for AccountId {
+    fn default() -> Self {
+        Self(DEFAULT_ACCOUNT.to_string())
+    }
+}
+
+impl std::fmt::Display for AccountId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Name of the strategy signals are attributed to when none is given
+pub const DEFAULT_STRATEGY: &str = "default";
+
+/// Strategy a signal (and the exposure it opens) is attributed to
+///
+/// Records written before attribution existed carry none and load as the
+/// default strategy.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct StrategyId(pub String);
+
+impl StrategyId {
+    pub fn is_default(&self) -> bool {
+        self.0 == DEFAULT_STRATEGY
+    }
+}
+
+impl Default for StrategyId {
+    fn default() -> Self {
+        Self(DEFAULT_STRATEGY.to_string())
+    }
+}
+
+impl std::fmt::Display for StrategyId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Trading venue
+///
+/// Serialized as its lowercase name ("binance", "bybit", ...), so config files
+/// and persisted records written with string venues still load.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub enum Venue {
+    Binance,
+    Bybit,
+    Hyperliquid,
+    /// Simulated fills; never reaches an exchange
+    Paper,
+    /// Venue without built-in support, written "custom:<name>"
+    Custom(String),
+}
+
+/// What a venue's API supports natively
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VenueCapabilities {
+    pub post_only: bool,
+    pub reduce_only: bool,
+    pub oco: bool,
+    /// Exchange-side countdown cancel-all (dead man's switch)
+    pub countdown_cancel: bool,
+    /// Native stop-limit / stop-market orders
+    pub stop_orders: bool,
+    /// Native trailing stops (percentage callback)
+    pub trailing_stop: bool,
+    /// Default maker fee in basis points (negative = rebate)
+    pub maker_fee_bps: Decimal,
+    /// Default taker fee in basis points
+    pub taker_fee_bps: Decimal,
+}
+
+impl VenueCapabilities {
+    /// Default fee for a maker or taker fill, in basis points
+    pub fn fee_bps(&self, maker: bool) -> Decimal {
+        if maker { self.maker_fee_bps } else { self.taker_fee_bps }
+    }
+}
+
+impl Venue {
+    /// Built-in venues
+    pub const KNOWN: [Venue; 4] = [Venue::Binance, Venue::Bybit, Venue::Hyperliquid, Venue::Paper];
+
+    pub fn name(&self) -> &str {
+        match self {
+            Venue::Binance => "binance",
+            Venue::Bybit => "bybit",
+            Venue::Hyperliquid => "hyperliquid",
+            Venue::Paper => "paper",
+            Venue::Custom(name) => name,
+        }
+    }
+
+    /// Native feature support and default fees
+    ///
+    /// Custom venues are assumed to support nothing beyond plain limit and
+    /// market orders, so callers fall back to emulation.
+    pub fn capabilities(&self) -> VenueCapabilities {
+        match self {
+            Venue::Binance => VenueCapabilities {
+                post_only: true,
+                reduce_only: true,
+                oco: true,
+                countdown_cancel: true,
+                stop_orders: true,
+                trailing_stop: true,
+                maker_fee_bps: Decimal::new(2, 0),
+                taker_fee_bps: Decimal::new(5, 0),
+            },
+            Venue::Bybit => VenueCapabilities {
+                post_only: true,
+                reduce_only: true,
+                oco: false,
+                countdown_cancel: true,
+                stop_orders: true,
+                trailing_stop: false,
+                maker_fee_bps: Decimal::new(2, 0),
+                taker_fee_bps: Decimal::new(55, 1),
+            },
+            Venue::Hyperliquid => VenueCapabilities {
+                post_only: true,
+                reduce_only: true,
+                oco: false,
+                countdown_cancel: true,
+                stop_orders: true,
+                trailing_stop: false,
+                maker_fee_bps: Decimal::new(15, 1),
+                taker_fee_bps: Decimal::new(45, 1),
+            },
+            Venue::Paper => VenueCapabilities {
+                post_only: true,
+                reduce_only: true,
+                oco: true,
+                countdown_cancel: false,
+                stop_orders: false,
+                trailing_stop: false,
+                maker_fee_bps: Decimal::new(2, 0),
+                taker_fee_bps: Decimal::new(5, 0),
+            },
+            Venue::Custom(_) => VenueCapabilities {
+                post_only: false,
+                reduce_only: false,
+                oco: false,
+                countdown_cancel: false,
+                stop_orders: false,
+                trailing_stop: false,
+                maker_fee_bps: Decimal::new(10, 0),
+                taker_fee_bps: Decimal::new(10, 0),
+            },
+        }
+    }
+}
+
+/// Venue name that is neither built in nor written as "custom:<name>"
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Unknown venue '{0}' (supported: {list}, or custom:<name>)", list = crate::constants::SUPPORTED_VENUES.join(", "))]
+pub struct UnknownVenue(pub String);
+
+impl std::str::FromStr for Venue {
+    type Err = UnknownVenue;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().to_ascii_lowercase();
+        if let Some(custom) = name.strip_prefix("custom:").filter(|custom| !custom.is_empty()) {
+            return Ok(Venue::Custom(custom.to_string()));
+        }
+        Venue::KNOWN.into_iter()
+            .find(|venue| venue.name() == name)
+            .ok_or_else(|| UnknownVenue(s.to_string()))
+    }
+}
+
+impl std::fmt::Display for Venue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Venue::Custom(name) => write!(f, "custom:{}", name),
+            venue => f.write_str(venue.name()),
+        }
+    }
+}
+
+impl From<Venue> for String {
+    fn from(venue: Venue) -> Self {
+        venue.to_string()
+    }
+}
+
+impl TryFrom<String> for Venue {
+    type Error = UnknownVenue;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+/// Order side
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Side {
     Buy,
     Sell,
@@ -38,6 +244,56 @@ pub enum OrderType {
     Market,
     StopLoss,
     TakeProfit,
+    /// Becomes a limit order at `limit` once the market trades through `trigger`
+    StopLimit { trigger: Price, limit: Price },
+    /// Becomes a market order once the market trades through `trigger`
+    StopMarket { trigger: Price },
+    /// Market order whose trigger trails the best traded price by `offset`
+    TrailingStop { offset: TrailOffset },
+}
+
+impl OrderType {
+    /// Whether the order rests until a trigger condition is met
+    pub fn is_conditional(&self) -> bool {
+        matches!(self, OrderType::StopLimit { .. } | OrderType::StopMarket { .. } | OrderType::TrailingStop { .. })
+    }
+
+    /// Fixed trigger price, if any (trailing stops have none until armed)
+    pub fn trigger(&self) -> Option<Price> {
+        match self {
+            OrderType::StopLimit { trigger, .. } | OrderType::StopMarket { trigger } => Some(*trigger),
+            _ => None,
+        }
+    }
+}
+
+/// Distance a trailing stop keeps from the best traded price
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TrailOffset {
+    Absolute(Price),
+    /// Percent of the best price (1.5 = 1.5%)
+    Percent(Decimal),
+}
+
+impl TrailOffset {
+    /// Stop price for an order on `side`, given the most favorable traded price
+    /// since arming (the high for sells, the low for buys)
+    pub fn stop_from(&self, side: Side, extreme: Price) -> Result<Price, crate::units::UnitError> {
+        let distance = match self {
+            TrailOffset::Absolute(distance) => distance.get(),
+            TrailOffset::Percent(percent) => extreme.get()
+                .checked_mul(*percent / Decimal::ONE_HUNDRED)
+                .ok_or(crate::units::UnitError::Overflow("trail offset"))?,
+        };
+        match side {
+            // A sell stop trails below the high; a buy stop trails above the low
+            Side::Sell => Price::new((extreme.get() - distance).max(Decimal::ZERO)),
+            Side::Buy => extreme.get()
+                .checked_add(distance)
+                .ok_or(crate::units::UnitError::Overflow("trail offset"))
+                .and_then(Price::new),
+        }
+    }
 }
 
 /// Order status
@@ -51,6 +307,13 @@ pub enum OrderStatus {
     Rejected,
 }
 
+impl OrderStatus {
+    /// The order will not change again
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, OrderStatus::Filled | OrderStatus::Cancelled | OrderStatus::Rejected)
+    }
+}
+
 /// Tick data with deterministic timestamp
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Tick {
@@ -58,8 +321,46 @@ pub struct Tick {
     pub venue: Venue,
     pub price: Price,
     pub quantity: Quantity,
+    /// Exchange time of the trade; everything downstream keys on this
     pub timestamp: DateTime<Utc>,
     pub side: Side,
+    /// When our ingestion received it; None for ticks not received live
+    /// (a replay), so replays stay identical
+    #[serde(default)]
+    pub received_at: Option<DateTime<Utc>>,
+}
+
+impl Tick {
+    /// Exchange time to receipt, when received live; negative when the
+    /// venue's clock runs ahead of ours
+    pub fn feed_delay(&self) -> Option<chrono::Duration> {
+        self.received_at.map(|received_at| received_at - self.timestamp)
+    }
+}
+
+/// One symbol's trades on one venue over a bar interval
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Candle {
+    pub symbol: Symbol,
+    pub venue: Venue,
+    pub open: Price,
+    pub high: Price,
+    pub low: Price,
+    pub close: Price,
+    /// Base units traded
+    pub volume: Quantity,
+    /// Zero for a bar carried forward from the previous close
+    pub trade_count: u64,
+    /// Exchange time the bar covers, from `start` up to but excluding `end`
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+impl Candle {
+    /// Whether the bar had no trades and repeats the previous close
+    pub fn is_carried(&self) -> bool {
+        self.trade_count == 0
+    }
 }
 
 /// Order book level
@@ -70,19 +371,32 @@ pub struct BookLevel {
 }
 
 /// Full order book snapshot (L2/L3)
+///
+/// Books travel the hot path as `Arc<OrderBook>`. Each side is shared
+/// between successive books while an update leaves it unchanged.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OrderBook {
     pub symbol: Symbol,
     pub venue: Venue,
-    pub bids: Vec<BookLevel>,
-    pub asks: Vec<BookLevel>,
+    pub bids: Arc<Vec<BookLevel>>,
+    pub asks: Arc<Vec<BookLevel>>,
     pub timestamp: DateTime<Utc>,
     pub sequence: u64,
 }
 
 /// Trade signal generated by the proposer
+///
+/// Outside this crate, construct signals with `TradeSignal::builder()`, which
+/// validates them.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
 pub struct TradeSignal {
+    /// Account the order is placed for
+    #[serde(default)]
+    pub account: AccountId,
+    /// Strategy the signal's exposure counts against
+    #[serde(default)]
+    pub strategy: StrategyId,
     pub symbol: Symbol,
     pub venue: Venue,
     pub side: Side,
@@ -95,6 +409,103 @@ pub struct TradeSignal {
     pub contradiction_score: Decimal,
     /// Entropy count (higher = more disorder)
     pub entropy_count: Decimal,
+    /// Edge the strategy expects to capture, in basis points of notional,
+    /// before fees and funding (None: the strategy gives no estimate)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_edge_bps: Option<Decimal>,
+}
+
+/// Next funding payment of a perpetual, as last published by the venue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FundingEstimate {
+    /// Rate for the interval as a fraction of notional; longs pay shorts
+    /// when positive
+    pub rate: Decimal,
+    /// When the payment is made
+    pub next_funding: DateTime<Utc>,
+}
+
+/// A perpetual's funding rate and mark price, as a venue published them
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FundingRate {
+    pub symbol: Symbol,
+    pub venue: Venue,
+    /// Rate for the current interval as a fraction of notional; longs pay
+    /// shorts when positive
+    pub rate: Decimal,
+    /// When it is paid
+    pub next_funding: DateTime<Utc>,
+    /// The perpetual's mark price
+    pub mark_price: Price,
+    /// Spot index the venue marks against, when published
+    pub index_price: Option<Price>,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl FundingRate {
+    /// The payment, as the edge check charges it
+    pub fn estimate(&self) -> FundingEstimate {
+        FundingEstimate { rate: self.rate, next_funding: self.next_funding }
+    }
+}
+
+/// Open interest in a perpetual
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OpenInterest {
+    pub symbol: Symbol,
+    pub venue: Venue,
+    /// Contracts open, in base units
+    pub quantity: Quantity,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// One update on the derivatives channel
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DerivativesUpdate {
+    Funding(FundingRate),
+    OpenInterest(OpenInterest),
+}
+
+/// A forced close of a leveraged position, as a venue reported it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LiquidationEvent {
+    pub symbol: Symbol,
+    pub venue: Venue,
+    /// Side of the forced order: Sell closes a long, Buy a short
+    pub side: Side,
+    pub price: Price,
+    pub quantity: Quantity,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl LiquidationEvent {
+    /// Quote notional liquidated
+    pub fn notional(&self) -> Result<Amount, crate::units::UnitError> {
+        self.price.notional(self.quantity)
+    }
+}
+
+/// Notional liquidated per forced-order side over a window
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LiquidationIntensity {
+    /// Shorts closed by forced buys
+    pub buy: Amount,
+    /// Longs closed by forced sells
+    pub sell: Amount,
+}
+
+impl LiquidationIntensity {
+    pub fn total(&self) -> Result<Amount, crate::units::UnitError> {
+        self.buy.checked_add(self.sell)
+    }
+}
+
+/// How fast each side's cumulative size grows with distance from the mid,
+/// in quantity per basis point; None for a side without the levels to tell
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BookSlope {
+    pub bid: Option<Decimal>,
+    pub ask: Option<Decimal>,
 }
 
 /// Verified trade order (post-verification)
@@ -106,6 +517,10 @@ pub struct VerifiedOrder {
     /// Mathematical proof (SMT model)
     pub proof: Proof,
     pub verified_at: DateTime<Utc>,
+    /// Ed25519 C=0 signature, attached after verification when a signing key
+    /// is configured (not part of the order's canonical encoding)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<crate::signature::CZeroSignature>,
 }
 
 /// SMT Solver proof
@@ -114,33 +529,204 @@ pub struct Proof {
     pub satisfiable: bool,
     pub model: HashMap<String, String>,
     pub axioms_satisfied: Vec<String>,
+    /// Time the solver took to check the axioms, in microseconds (not part
+    /// of the proof's canonical encoding, so replays hash alike)
+    #[serde(default)]
+    pub solve_us: u64,
+    /// Reused from an earlier signal's proof instead of solved for this one
+    /// (not part of the canonical encoding either)
+    #[serde(default)]
+    pub cached: bool,
 }
 
+/// Portfolio/Position serialization layout (bump on any structural change)
+///
+/// Documents written before versioning carry no `schema_version` and load
+/// as version 0; fields added since then fall back to their defaults.
+pub const PORTFOLIO_SCHEMA_VERSION: u32 = 1;
+
+/// Decimal places kept for position sizes on external surfaces
+pub const REDACTED_QUANTITY_DP: u32 = 2;
+
+/// Decimal places kept for money and leverage on external surfaces
+pub const REDACTED_AMOUNT_DP: u32 = 0;
+
 /// Position state
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Position {
+    #[serde(default)]
+    pub account: AccountId,
     pub symbol: Symbol,
     pub venue: Venue,
     pub side: Side,
     pub quantity: Quantity,
     pub entry_price: Price,
     pub current_price: Price,
+    #[serde(default)]
     pub unrealized_pnl: Amount,
+    #[serde(default)]
     pub realized_pnl: Amount,
 }
 
+impl Position {
+    /// This position for external surfaces: size rounded, venue omitted
+    pub fn redacted(&self) -> RedactedPosition {
+        RedactedPosition {
+            symbol: self.symbol.clone(),
+            side: self.side,
+            quantity: self.quantity.get().round_dp(REDACTED_QUANTITY_DP),
+            entry_price: self.entry_price,
+            current_price: self.current_price,
+            unrealized_pnl: self.unrealized_pnl.get().round_dp(REDACTED_AMOUNT_DP),
+        }
+    }
+}
+
+/// One position as a venue reported it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReportedPosition {
+    pub symbol: Symbol,
+    pub side: Side,
+    pub quantity: Quantity,
+    /// Average entry price, when the venue gives one
+    pub entry_price: Option<Price>,
+}
+
+/// An account's open positions and balance on one venue, as the venue's
+/// API reported them; what the local portfolio is reconciled against
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExchangePositionReport {
+    pub account: AccountId,
+    pub venue: Venue,
+    /// Every open position; a symbol not listed is flat
+    pub positions: Vec<ReportedPosition>,
+    /// Cash balance excluding unrealized PnL, when the venue gives one
+    pub balance: Option<Amount>,
+    pub timestamp: DateTime<Utc>,
+}
+
 /// Portfolio state (Hamiltonian energy model)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Portfolio {
+    /// Layout version this document was written with (see PORTFOLIO_SCHEMA_VERSION)
+    #[serde(default)]
+    pub schema_version: u32,
     pub equity: Amount,
+    #[serde(default)]
     pub positions: Vec<Position>,
+    #[serde(default)]
     pub total_exposure: Amount,
+    #[serde(default)]
     pub net_exposure: Amount,
+    #[serde(default)]
     pub leverage: Decimal,
     /// Hamiltonian energy (risk measure)
+    #[serde(default)]
     pub energy: Decimal,
-    /// Correlation matrix (for diversification check)
+    /// Correlation matrix of mark returns (for diversification check);
+    /// rows and columns follow `correlation_symbols`
+    #[serde(default)]
     pub correlation_matrix: Vec<Vec<Decimal>>,
+    #[serde(default)]
+    pub correlation_symbols: Vec<Symbol>,
+    /// Exposure attributed to each strategy that has traded
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub strategies: BTreeMap<StrategyId, StrategyExposure>,
+    /// Trading fees paid, already out of equity (negative after rebates)
+    #[serde(default)]
+    pub total_fees: Amount,
+    /// Net funding received on perpetuals, already in equity (negative when
+    /// paid)
+    #[serde(default)]
+    pub total_funding: Amount,
+}
+
+/// One strategy's share of the portfolio
+///
+/// Positions are shared: strategies trading the same symbol in the same
+/// account net into one position, and each keeps its own signed quantity.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StrategyExposure {
+    /// Signed quantity per symbol (positive long), from the strategy's fills
+    pub net: BTreeMap<Symbol, Decimal>,
+    /// Gross notional of `net` at current prices
+    pub exposure: Amount,
+}
+
+impl StrategyExposure {
+    /// The strategy's signed quantity in `symbol`
+    pub fn net_quantity(&self, symbol: &Symbol) -> Decimal {
+        self.net.get(symbol).copied().unwrap_or_default()
+    }
+}
+
+impl Portfolio {
+    /// A flat portfolio holding `equity`
+    pub fn new(equity: Amount) -> Self {
+        Self {
+            schema_version: PORTFOLIO_SCHEMA_VERSION,
+            equity,
+            positions: Vec::new(),
+            total_exposure: Amount::ZERO,
+            net_exposure: Amount::ZERO,
+            leverage: Decimal::ZERO,
+            energy: Decimal::ZERO,
+            correlation_matrix: Vec::new(),
+            correlation_symbols: Vec::new(),
+            strategies: BTreeMap::new(),
+            total_fees: Amount::ZERO,
+            total_funding: Amount::ZERO,
+        }
+    }
+
+    /// This portfolio for external surfaces (dashboard, webhooks)
+    pub fn redacted(&self) -> RedactedPortfolio {
+        let round = |amount: Amount| amount.get().round_dp(REDACTED_AMOUNT_DP);
+        RedactedPortfolio {
+            schema_version: PORTFOLIO_SCHEMA_VERSION,
+            equity: round(self.equity),
+            total_exposure: round(self.total_exposure),
+            net_exposure: round(self.net_exposure),
+            leverage: self.leverage.round_dp(REDACTED_QUANTITY_DP),
+            energy: self.energy,
+            unrealized_pnl: self.positions.iter().map(|position| position.unrealized_pnl.get()).sum::<Decimal>()
+                .round_dp(REDACTED_AMOUNT_DP),
+            positions: self.positions.iter().map(Position::redacted).collect(),
+        }
+    }
+}
+
+/// Position as shown outside the process
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedactedPosition {
+    pub symbol: Symbol,
+    pub side: Side,
+    /// Rounded to REDACTED_QUANTITY_DP
+    pub quantity: Decimal,
+    pub entry_price: Price,
+    pub current_price: Price,
+    /// Rounded to REDACTED_AMOUNT_DP
+    pub unrealized_pnl: Decimal,
+}
+
+/// Portfolio as shown outside the process: sizes and money rounded, no
+/// venue account detail
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedactedPortfolio {
+    pub schema_version: u32,
+    pub equity: Decimal,
+    pub total_exposure: Decimal,
+    pub net_exposure: Decimal,
+    pub leverage: Decimal,
+    pub energy: Decimal,
+    pub unrealized_pnl: Decimal,
+    pub positions: Vec<RedactedPosition>,
+}
+
+impl RedactedPortfolio {
+    pub fn open_positions(&self) -> usize {
+        self.positions.len()
+    }
 }
 
 /// Consistency error metric
@@ -160,13 +746,40 @@ pub struct EntropyCount {
     pub timestamp: DateTime<Utc>,
 }
 
-/// Market regime classification
+/// Market regime classification (see `RegimeStateMachine` for transitions)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum MarketRegime {
-    HighVolatility,
-    LowLiquidity,
+    /// Low volatility
+    Quiet,
     Normal,
-    Unprovable, // Stand down mode
+    /// Sustained directional drift
+    Trending,
+    /// Elevated volatility
+    #[serde(alias = "HighVolatility")]
+    Volatile,
+    /// Extreme volatility or a broken book
+    #[serde(alias = "LowLiquidity")]
+    Disorderly,
+    /// Entropy above threshold: stand down
+    Unprovable,
+}
+
+impl MarketRegime {
+    /// Restrictiveness, lowest for Quiet and Normal
+    pub fn severity(self) -> u8 {
+        match self {
+            MarketRegime::Quiet | MarketRegime::Normal => 0,
+            MarketRegime::Trending => 1,
+            MarketRegime::Volatile => 2,
+            MarketRegime::Disorderly => 3,
+            MarketRegime::Unprovable => 4,
+        }
+    }
+
+    /// Whether strategies may open new positions
+    pub fn allows_new_positions(self) -> bool {
+        !matches!(self, MarketRegime::Disorderly | MarketRegime::Unprovable)
+    }
 }
 
 /// Circuit breaker state
@@ -184,10 +797,16 @@ pub struct SystemHealth {
     pub consistency_error: ConsistencyError,
     pub entropy_count: EntropyCount,
     pub circuit_breaker: CircuitBreakerState,
+    /// Equity of every account together below its peak, as a fraction
+    #[serde(default)]
+    pub drawdown: Decimal,
     pub hallucination_rate: Decimal,
     pub latency_p50: u64,
     pub latency_p99: u64,
     pub latency_p999: u64,
+    /// Per-stage, per-venue percentiles
+    #[serde(default)]
+    pub latency_breakdown: crate::latency::LatencyBreakdown,
     pub timestamp: DateTime<Utc>,
 }
 
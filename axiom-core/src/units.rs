@@ -0,0 +1,307 @@
+//! Units: Price, Quantity, and Amount Newtypes
+//!
+//! Prices, sizes, and money are distinct types that only combine in
+//! unit-consistent ways (Price × Quantity → Notional, Notional ÷ Price →
+//! Quantity, ...). Every operation uses checked Decimal arithmetic and returns
+//! a `UnitError` instead of panicking on overflow.
+//!
+//! `get()` is the one escape hatch back to a raw Decimal, for formatting,
+//! comparison against Decimal constants, and solver encoding.
+
+use crate::errors::ErrorCode;
+use crate::types::Side;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Unit arithmetic error
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, thiserror::Error)]
+#[serde(tag = "code", content = "details")]
+pub enum UnitError {
+    #[error("{unit} cannot be negative (got {value})")]
+    #[serde(rename = "UNIT_NEGATIVE")]
+    Negative { unit: &'static str, value: Decimal },
+
+    #[error("Decimal overflow in {0}")]
+    #[serde(rename = "UNIT_OVERFLOW")]
+    Overflow(&'static str),
+
+    #[error("Division by zero in {0}")]
+    #[serde(rename = "UNIT_DIVISION_BY_ZERO")]
+    DivisionByZero(&'static str),
+}
+
+impl ErrorCode for UnitError {
+    fn code(&self) -> &'static str {
+        match self {
+            UnitError::Negative { .. } => "UNIT_NEGATIVE",
+            UnitError::Overflow(_) => "UNIT_OVERFLOW",
+            UnitError::DivisionByZero(_) => "UNIT_DIVISION_BY_ZERO",
+        }
+    }
+}
+
+fn non_negative(unit: &'static str, value: Decimal) -> Result<Decimal, UnitError> {
+    if value.is_sign_negative() && !value.is_zero() {
+        Err(UnitError::Negative { unit, value })
+    } else {
+        Ok(value)
+    }
+}
+
+fn checked(op: &'static str, value: Option<Decimal>) -> Result<Decimal, UnitError> {
+    value.ok_or(UnitError::Overflow(op))
+}
+
+fn divide(op: &'static str, numerator: Decimal, denominator: Decimal) -> Result<Decimal, UnitError> {
+    if denominator.is_zero() {
+        return Err(UnitError::DivisionByZero(op));
+    }
+    checked(op, numerator.checked_div(denominator))
+}
+
+/// Price per unit of base asset (never negative)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "Decimal", into = "Decimal")]
+pub struct Price(Decimal);
+
+/// Size in units of base asset (never negative; direction lives in `Side`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "Decimal", into = "Decimal")]
+pub struct Quantity(Decimal);
+
+/// Quote-currency money: equity, PnL, exposure, fees (signed)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Amount(Decimal);
+
+/// Price × Quantity; an Amount that is non-negative by construction
+pub type Notional = Amount;
+
+impl Price {
+    pub const ZERO: Price = Price(Decimal::ZERO);
+
+    pub fn new(value: Decimal) -> Result<Self, UnitError> {
+        non_negative("Price", value).map(Price)
+    }
+
+    /// The raw Decimal (escape hatch; prefer the typed operations)
+    pub fn get(self) -> Decimal {
+        self.0
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.0.is_zero()
+    }
+
+    /// Price × Quantity
+    pub fn notional(self, quantity: Quantity) -> Result<Notional, UnitError> {
+        checked("notional", self.0.checked_mul(quantity.0)).map(Amount)
+    }
+
+    /// Halfway between two prices
+    pub fn midpoint(self, other: Price) -> Result<Price, UnitError> {
+        let sum = checked("midpoint", self.0.checked_add(other.0))?;
+        Ok(Price(sum / Decimal::TWO))
+    }
+
+    /// `self - other` as a signed price difference
+    pub fn delta(self, other: Price) -> Decimal {
+        // Both operands are non-negative, so this cannot overflow
+        self.0 - other.0
+    }
+
+    /// Round down to a whole number of `tick`s
+    pub fn floor_to(self, tick: Price) -> Result<Price, UnitError> {
+        let ticks = divide("price tick", self.0, tick.0)?.floor();
+        checked("price tick", ticks.checked_mul(tick.0)).map(Price)
+    }
+
+    /// Round up to a whole number of `tick`s
+    pub fn ceil_to(self, tick: Price) -> Result<Price, UnitError> {
+        let ticks = divide("price tick", self.0, tick.0)?.ceil();
+        checked("price tick", ticks.checked_mul(tick.0)).map(Price)
+    }
+
+    /// Move this price by `fraction` in the direction that is adverse for `side`
+    /// (up for buys, down for sells); used for slippage and protective limits
+    pub fn adverse(self, side: Side, fraction: Decimal) -> Result<Price, UnitError> {
+        let offset = checked("adverse price", self.0.checked_mul(fraction))?;
+        let moved = match side {
+            Side::Buy => checked("adverse price", self.0.checked_add(offset))?,
+            Side::Sell => self.0 - offset,
+        };
+        Price::new(moved)
+    }
+}
+
+impl Quantity {
+    pub const ZERO: Quantity = Quantity(Decimal::ZERO);
+
+    pub fn new(value: Decimal) -> Result<Self, UnitError> {
+        non_negative("Quantity", value).map(Quantity)
+    }
+
+    /// The raw Decimal (escape hatch; prefer the typed operations)
+    pub fn get(self) -> Decimal {
+        self.0
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.0.is_zero()
+    }
+
+    pub fn checked_add(self, other: Quantity) -> Result<Quantity, UnitError> {
+        checked("quantity add", self.0.checked_add(other.0)).map(Quantity)
+    }
+
+    /// `self - other`; errors if the result would be negative
+    pub fn checked_sub(self, other: Quantity) -> Result<Quantity, UnitError> {
+        Quantity::new(self.0 - other.0)
+    }
+
+    /// `self - other`, floored at zero
+    pub fn saturating_sub(self, other: Quantity) -> Quantity {
+        Quantity(if other.0 >= self.0 { Decimal::ZERO } else { self.0 - other.0 })
+    }
+
+    /// Scale by a non-negative factor (e.g. a certainty weight)
+    pub fn scale(self, factor: Decimal) -> Result<Quantity, UnitError> {
+        Quantity::new(checked("quantity scale", self.0.checked_mul(factor))?)
+    }
+
+    /// Round down to a whole number of `step`s
+    pub fn floor_to(self, step: Quantity) -> Result<Quantity, UnitError> {
+        let steps = divide("quantity step", self.0, step.0)?.floor();
+        checked("quantity step", steps.checked_mul(step.0)).map(Quantity)
+    }
+
+    /// Round up to a whole number of `step`s
+    pub fn ceil_to(self, step: Quantity) -> Result<Quantity, UnitError> {
+        let steps = divide("quantity step", self.0, step.0)?.ceil();
+        checked("quantity step", steps.checked_mul(step.0)).map(Quantity)
+    }
+}
+
+impl Amount {
+    pub const ZERO: Amount = Amount(Decimal::ZERO);
+
+    /// Amounts are signed, so any Decimal is valid
+    pub fn new(value: Decimal) -> Self {
+        Amount(value)
+    }
+
+    /// The raw Decimal (escape hatch; prefer the typed operations)
+    pub fn get(self) -> Decimal {
+        self.0
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.0.is_zero()
+    }
+
+    pub fn is_positive(self) -> bool {
+        self.0 > Decimal::ZERO
+    }
+
+    pub fn checked_add(self, other: Amount) -> Result<Amount, UnitError> {
+        checked("amount add", self.0.checked_add(other.0)).map(Amount)
+    }
+
+    pub fn checked_sub(self, other: Amount) -> Result<Amount, UnitError> {
+        checked("amount sub", self.0.checked_sub(other.0)).map(Amount)
+    }
+
+    pub fn checked_sum(amounts: impl IntoIterator<Item = Amount>) -> Result<Amount, UnitError> {
+        amounts.into_iter().try_fold(Amount::ZERO, Amount::checked_add)
+    }
+
+    /// Scale by a dimensionless factor (e.g. a risk budget fraction)
+    pub fn scale(self, factor: Decimal) -> Result<Amount, UnitError> {
+        checked("amount scale", self.0.checked_mul(factor)).map(Amount)
+    }
+
+    /// Dimensionless `self / other` (e.g. leverage, risk fraction)
+    pub fn ratio(self, other: Amount) -> Result<Decimal, UnitError> {
+        divide("amount ratio", self.0, other.0)
+    }
+
+    /// Notional ÷ Price → Quantity
+    pub fn quantity_at(self, price: Price) -> Result<Quantity, UnitError> {
+        Quantity::new(divide("quantity at price", self.0, price.0)?)
+    }
+
+    /// Notional ÷ Quantity → average Price
+    pub fn price_per(self, quantity: Quantity) -> Result<Price, UnitError> {
+        Price::new(divide("price per unit", self.0, quantity.0)?)
+    }
+
+    /// Exposure signed by direction: positive for longs, negative for shorts
+    pub fn signed(self, side: Side) -> Amount {
+        match side {
+            Side::Buy => self,
+            Side::Sell => Amount(-self.0),
+        }
+    }
+
+    /// Profit of holding `quantity` on `side` from `entry` to `exit`
+    pub fn pnl(side: Side, entry: Price, exit: Price, quantity: Quantity) -> Result<Amount, UnitError> {
+        let per_unit = match side {
+            Side::Buy => exit.delta(entry),
+            Side::Sell => entry.delta(exit),
+        };
+        checked("pnl", per_unit.checked_mul(quantity.0)).map(Amount)
+    }
+}
+
+impl TryFrom<Decimal> for Price {
+    type Error = UnitError;
+
+    fn try_from(value: Decimal) -> Result<Self, Self::Error> {
+        Price::new(value)
+    }
+}
+
+impl TryFrom<Decimal> for Quantity {
+    type Error = UnitError;
+
+    fn try_from(value: Decimal) -> Result<Self, Self::Error> {
+        Quantity::new(value)
+    }
+}
+
+impl From<Price> for Decimal {
+    fn from(price: Price) -> Self {
+        price.0
+    }
+}
+
+impl From<Quantity> for Decimal {
+    fn from(quantity: Quantity) -> Self {
+        quantity.0
+    }
+}
+
+impl From<Amount> for Decimal {
+    fn from(amount: Amount) -> Self {
+        amount.0
+    }
+}
+
+impl fmt::Display for Price {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl fmt::Display for Quantity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl fmt::Display for Amount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
@@ -0,0 +1,78 @@
+//! Tick parsing throughput over 1M trade messages: each parsed into a
+//! `serde_json::Value` and normalized, against the struct-targeted parsers.
+//!
+//! The messages are Binance and Bybit trades and default-shape ticks in
+//! equal parts, with prices, sizes and timestamps varying as a live stream's
+//! do.
+//!
+//! cargo bench -p axiom-data --features fast-ingest --bench fast_ingest
+
+use axiom_core::{Tick, Venue};
+use axiom_data::{
+    normalize_tick, parse_binance_trade, parse_bybit_trade, parse_tick, IngestionError, SchemaRegistry, SymbolMapper,
+    TickParser,
+};
+use std::time::{Duration, Instant};
+
+const MESSAGES: usize = 1_000_000;
+
+/// One message per venue in turn
+fn messages() -> Vec<(Venue, String)> {
+    (0..MESSAGES)
+        .map(|index| {
+            let (price, size, at) = (65_000 * 100 + index % 5_000, 1 + index % 997, 1_700_000_000_000u64 + index as u64);
+            let (price, size) = (format!("{}.{:02}", price / 100, price % 100), format!("0.{:04}", size));
+            match index % 3 {
+                0 => (Venue::Binance, format!(
+                    r#"{{"e":"trade","E":{at},"s":"BTCUSDT","t":{index},"p":"{price}","q":"{size}","T":{at},"m":{},"M":true}}"#,
+                    index % 2 == 0,
+                )),
+                1 => (Venue::Bybit, format!(
+                    r#"{{"topic":"publicTrade.BTCUSDT","type":"snapshot","ts":{at},"data":[{{"T":{at},"s":"BTCUSDT","S":"{}","v":"{size}","p":"{price}","L":"PlusTick","i":"{index}","BT":false}}]}}"#,
+                    if index % 2 == 0 { "Buy" } else { "Sell" },
+                )),
+                _ => (Venue::Paper, format!(
+                    r#"{{"symbol":"BTC/USD","price":"{price}","quantity":"{size}","timestamp":{at},"side":"{}"}}"#,
+                    if index % 2 == 0 { "buy" } else { "sell" },
+                )),
+            }
+        })
+        .collect()
+}
+
+/// Time to parse every message with `parse`
+fn measure(messages: &[(Venue, String)], mut parse: impl FnMut(&Venue, &[u8]) -> Result<Tick, IngestionError>) -> Duration {
+    let start = Instant::now();
+    for (venue, raw) in messages {
+        parse(venue, raw.as_bytes()).unwrap();
+    }
+    start.elapsed()
+}
+
+fn main() {
+    let messages = messages();
+    let symbols = SymbolMapper::builtin();
+    let schemas = SchemaRegistry::builtin();
+
+    let value = measure(&messages, |venue, raw| {
+        let json: serde_json::Value = serde_json::from_slice(raw).map_err(|e| IngestionError::InvalidFormat(e.to_string()))?;
+        match schemas.get(venue).filter(|schema| schema.tick.is_some()) {
+            Some(schema) => schema.normalize_tick(&json, venue, &symbols),
+            None => normalize_tick(&json, venue, &symbols),
+        }
+    });
+    let typed = measure(&messages, |venue, raw| {
+        let parse: TickParser = match venue {
+            Venue::Binance => parse_binance_trade,
+            Venue::Bybit => parse_bybit_trade,
+            _ => parse_tick,
+        };
+        parse(raw, venue, &symbols)
+    });
+
+    let throughput = |elapsed: Duration| MESSAGES as f64 / elapsed.as_secs_f64();
+    println!("{} messages", MESSAGES);
+    println!("serde_json::Value: {:.0} messages/s", throughput(value));
+    println!("struct-targeted: {:.0} messages/s", throughput(typed));
+    println!("speedup: {:.1}x", value.as_secs_f64() / typed.as_secs_f64());
+}
@@ -0,0 +1,247 @@
+//! Bars: OHLCV Candles from the Tick Stream
+//!
+//! Ticks are bucketed per (symbol, venue) into bars of each configured
+//! interval. Buckets start on whole multiples of the interval since the
+//! Unix epoch, by exchange time, so the same ticks make the same bars live
+//! and in a replay. A bar is emitted when the first tick of a later bucket
+//! arrives; buckets that saw no trades in between are emitted as carried
+//! bars (open, high, low and close all at the previous close, no volume)
+//! so indicators see an unbroken series. A tick older than its symbol's
+//! open bar arrived too late to count and is dropped.
+
+use axiom_core::{Candle, Quantity, Symbol, Tick, Venue};
+use crate::errors::*;
+use crate::feed::{FeedSendError, FeedReceiver, FeedSender};
+use crate::ingestion::send_error;
+use chrono::{DateTime, DurationRound, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tokio::sync::watch;
+use tracing::{debug, warn};
+
+/// Carried bars emitted for one gap at most; a longer silence (a halted
+/// market, a replay spanning days) leaves the rest of the gap out
+pub const MAX_CARRIED_BARS: u64 = 10_000;
+
+/// Bar length, written "1s", "1m", "5m", "1h"
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub struct BarInterval {
+    secs: u32,
+}
+
+impl BarInterval {
+    pub const ONE_SECOND: BarInterval = BarInterval { secs: 1 };
+    pub const ONE_MINUTE: BarInterval = BarInterval { secs: 60 };
+    pub const FIVE_MINUTES: BarInterval = BarInterval { secs: 300 };
+
+    /// A bar `secs` seconds long; None for zero
+    pub fn from_secs(secs: u32) -> Option<Self> {
+        (secs > 0).then_some(Self { secs })
+    }
+
+    pub fn secs(&self) -> u32 {
+        self.secs
+    }
+
+    pub fn length(&self) -> TimeDelta {
+        TimeDelta::seconds(i64::from(self.secs))
+    }
+
+    /// Start of the bucket `at` falls in
+    pub fn bucket(&self, at: DateTime<Utc>) -> Result<DateTime<Utc>, IngestionError> {
+        at.duration_trunc(self.length())
+            .map_err(|e| IngestionError::InvalidFormat(format!("Bar bucket for {}: {}", at, e)))
+    }
+}
+
+impl std::fmt::Display for BarInterval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.secs {
+            secs if secs % 3600 == 0 => write!(f, "{}h", secs / 3600),
+            secs if secs % 60 == 0 => write!(f, "{}m", secs / 60),
+            secs => write!(f, "{}s", secs),
+        }
+    }
+}
+
+impl std::str::FromStr for BarInterval {
+    type Err = IngestionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || IngestionError::InvalidFormat(format!("Bar interval {:?} is not like 1s, 1m, 5m or 1h", s));
+        let text = s.trim();
+        let (split, _) = text.char_indices().last().ok_or_else(invalid)?;
+        let (count, unit) = text.split_at(split);
+        let count: u32 = count.parse().map_err(|_| invalid())?;
+        let scale = match unit {
+            "s" => 1,
+            "m" => 60,
+            "h" => 3600,
+            _ => return Err(invalid()),
+        };
+        count.checked_mul(scale).and_then(Self::from_secs).ok_or_else(invalid)
+    }
+}
+
+impl From<BarInterval> for String {
+    fn from(interval: BarInterval) -> Self {
+        interval.to_string()
+    }
+}
+
+impl TryFrom<String> for BarInterval {
+    type Error = IngestionError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+/// Builds candles of each interval from ticks
+#[derive(Debug, Clone)]
+pub struct BarAggregator {
+    intervals: Vec<BarInterval>,
+    /// The bar being filled, by interval, symbol and venue
+    open: BTreeMap<(BarInterval, Symbol, Venue), Candle>,
+    late_ticks: u64,
+}
+
+impl BarAggregator {
+    /// Bars of every interval in `intervals` (duplicates are ignored)
+    pub fn new(intervals: impl IntoIterator<Item = BarInterval>) -> Self {
+        let mut intervals: Vec<BarInterval> = intervals.into_iter().collect();
+        intervals.sort();
+        intervals.dedup();
+        Self { intervals, open: BTreeMap::new(), late_ticks: 0 }
+    }
+
+    pub fn intervals(&self) -> &[BarInterval] {
+        &self.intervals
+    }
+
+    /// Ticks dropped for arriving after their bucket had closed
+    pub fn late_ticks(&self) -> u64 {
+        self.late_ticks
+    }
+
+    /// `interval`'s bar still being filled for `symbol` on `venue`
+    pub fn open_bar(&self, interval: BarInterval, symbol: &Symbol, venue: &Venue) -> Option<&Candle> {
+        self.open.get(&(interval, symbol.clone(), venue.clone()))
+    }
+
+    /// Add `tick` to its bars; returns the bars it completed, carried bars
+    /// included, shortest interval first and oldest first within one
+    pub fn on_tick(&mut self, tick: &Tick) -> Result<Vec<Candle>, IngestionError> {
+        let mut completed = Vec::new();
+        for &interval in &self.intervals {
+            let start = interval.bucket(tick.timestamp)?;
+            let key = (interval, tick.symbol.clone(), tick.venue.clone());
+            let Some(bar) = self.open.get_mut(&key) else {
+                self.open.insert(key, first_bar(tick, start, interval));
+                continue;
+            };
+            if start < bar.start {
+                self.late_ticks += 1;
+                debug!("Late {} tick at {} for the {} bar from {}", tick.symbol.0, tick.timestamp, interval, bar.start);
+                continue;
+            }
+            if start == bar.start {
+                add_trade(bar, tick)?;
+                continue;
+            }
+
+            // A later bucket: close this bar and carry its close across the gap
+            let next = first_bar(tick, start, interval);
+            let done = std::mem::replace(bar, next);
+            let close = done.close;
+            let mut carried_from = done.end;
+            completed.push(done);
+            let mut carried = 0;
+            while carried_from < start {
+                if carried == MAX_CARRIED_BARS {
+                    warn!(
+                        "{} on {} silent from {} to {}; {} bars not carried",
+                        tick.symbol.0, tick.venue, carried_from, start, interval,
+                    );
+                    break;
+                }
+                let end = carried_from + interval.length();
+                completed.push(Candle {
+                    symbol: tick.symbol.clone(),
+                    venue: tick.venue.clone(),
+                    open: close,
+                    high: close,
+                    low: close,
+                    close,
+                    volume: Quantity::ZERO,
+                    trade_count: 0,
+                    start: carried_from,
+                    end,
+                });
+                carried_from = end;
+                carried += 1;
+            }
+        }
+        Ok(completed)
+    }
+
+    /// Aggregate `ticks` into `candles` until shutdown or the tick feed
+    /// ends; a tick that cannot be bucketed is logged and skipped
+    pub async fn run(
+        mut self,
+        mut ticks: FeedReceiver<Tick>,
+        candles: FeedSender<Candle>,
+        mut shutdown: watch::Receiver<bool>,
+    ) -> Result<(), IngestionError> {
+        loop {
+            let tick = tokio::select! {
+                tick = ticks.recv() => match tick {
+                    Some(tick) => tick,
+                    None => return Ok(()),
+                },
+                _ = async { drop(shutdown.wait_for(|stop| *stop).await) } => return Ok(()),
+            };
+            let completed = match self.on_tick(&tick) {
+                Ok(completed) => completed,
+                Err(e) => {
+                    warn!("{} tick not aggregated: {}", tick.symbol.0, e);
+                    continue;
+                }
+            };
+            for candle in completed {
+                match candles.send(candle).await {
+                    Ok(()) | Err(FeedSendError::TimedOut) => {}
+                    Err(e @ FeedSendError::Closed) => return Err(send_error("candle", e)),
+                }
+            }
+        }
+    }
+}
+
+/// A bar opened by `tick`
+fn first_bar(tick: &Tick, start: DateTime<Utc>, interval: BarInterval) -> Candle {
+    Candle {
+        symbol: tick.symbol.clone(),
+        venue: tick.venue.clone(),
+        open: tick.price,
+        high: tick.price,
+        low: tick.price,
+        close: tick.price,
+        volume: tick.quantity,
+        trade_count: 1,
+        start,
+        end: start + interval.length(),
+    }
+}
+
+/// Fold `tick` into the bar its bucket is already filling
+fn add_trade(bar: &mut Candle, tick: &Tick) -> Result<(), IngestionError> {
+    bar.high = bar.high.max(tick.price);
+    bar.low = bar.low.min(tick.price);
+    bar.close = tick.price;
+    bar.volume = bar.volume.checked_add(tick.quantity)
+        .map_err(|e| IngestionError::InvalidFormat(format!("{} bar volume: {}", tick.symbol.0, e)))?;
+    bar.trade_count += 1;
+    Ok(())
+}
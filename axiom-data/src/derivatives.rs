@@ -0,0 +1,261 @@
+//! Derivatives: Perpetual Funding and Open Interest
+//!
+//! Funding is the carry on a perpetual and open interest its crowding; both
+//! are polled over REST every `poll_interval` and published on the
+//! derivatives channel. Binance USD-M `premiumIndex` lists every perpetual's
+//! `lastFundingRate`, `nextFundingTime`, `markPrice` and `indexPrice`, with
+//! open interest per symbol from `openInterest`; Bybit v5 linear `tickers`
+//! carries `fundingRate`, `nextFundingTime`, `markPrice`, `indexPrice` and
+//! `openInterest` on each entry; Hyperliquid's comes from `HyperliquidInfo`.
+//! Perpetuals share the spot names on Binance and Bybit ("BTCUSDT"), so
+//! symbols go through the same mapper; markets it does not map are skipped.
+
+use axiom_core::{DerivativesUpdate, FundingRate, OpenInterest, Venue};
+use crate::errors::*;
+use crate::ingestion::DataIngestionManager;
+use crate::normalization::*;
+use crate::venues::{HyperliquidInfo, HyperliquidMarketState};
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+use tracing::{debug, warn};
+
+/// Binance USD-M funding and mark prices, every perpetual (mainnet)
+pub const BINANCE_PREMIUM_INDEX_URL: &str = "https://fapi.binance.com/fapi/v1/premiumIndex";
+
+/// Binance USD-M open interest, one symbol per request (mainnet)
+pub const BINANCE_OPEN_INTEREST_URL: &str = "https://fapi.binance.com/fapi/v1/openInterest";
+
+/// Bybit linear perpetual tickers (mainnet)
+pub const BYBIT_LINEAR_TICKERS_URL: &str = "https://api.bybit.com/v5/market/tickers?category=linear";
+
+/// A funding entry from a venue listing: a Binance `premiumIndex` element
+/// or a Bybit `tickers` list entry; `observed_at` stamps entries that carry
+/// no time of their own (Bybit's)
+pub fn normalize_funding(
+    json: &Value,
+    venue: &Venue,
+    symbols: &SymbolMapper,
+    observed_at: DateTime<Utc>,
+) -> Result<FundingRate, IngestionError> {
+    let rate_field = match venue {
+        Venue::Binance => "lastFundingRate",
+        Venue::Bybit => "fundingRate",
+        other => return Err(IngestionError::InvalidFormat(format!("No funding format for {}", other))),
+    };
+    Ok(FundingRate {
+        symbol: symbols.canonical(venue, text(json, "symbol")?)?,
+        venue: venue.clone(),
+        rate: parse_decimal(text(json, rate_field)?)?,
+        next_funding: normalize_timestamp_in(field(json, "nextFundingTime")?, TimestampUnit::Millis)?,
+        mark_price: normalize_price(field(json, "markPrice")?)?,
+        index_price: json.get("indexPrice").map(normalize_price).transpose()?,
+        timestamp: entry_time(json, observed_at)?,
+    })
+}
+
+/// An open interest entry: a Binance `openInterest` response or a Bybit
+/// `tickers` list entry (in base units on both)
+pub fn normalize_open_interest(
+    json: &Value,
+    venue: &Venue,
+    symbols: &SymbolMapper,
+    observed_at: DateTime<Utc>,
+) -> Result<OpenInterest, IngestionError> {
+    Ok(OpenInterest {
+        symbol: symbols.canonical(venue, text(json, "symbol")?)?,
+        venue: venue.clone(),
+        quantity: normalize_quantity(field(json, "openInterest")?)?,
+        timestamp: entry_time(json, observed_at)?,
+    })
+}
+
+/// Polls funding and open interest from the venues' REST listings
+#[derive(Debug, Clone)]
+pub struct DerivativesPoller {
+    client: reqwest::Client,
+    symbols: Arc<SymbolMapper>,
+    hyperliquid: HyperliquidInfo,
+    poll_interval: Duration,
+}
+
+impl DerivativesPoller {
+    pub fn new(poll_interval: Duration) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            symbols: Arc::new(SymbolMapper::builtin()),
+            hyperliquid: HyperliquidInfo::new(),
+            poll_interval,
+        }
+    }
+
+    /// Map listed symbols with `symbols` instead of the built-in table
+    pub fn with_symbols(mut self, symbols: Arc<SymbolMapper>) -> Self {
+        self.hyperliquid = self.hyperliquid.with_symbols(symbols.clone());
+        self.symbols = symbols;
+        self
+    }
+
+    /// Whether `venue` has listings this poller reads
+    pub fn supports(venue: &Venue) -> bool {
+        matches!(venue, Venue::Binance | Venue::Bybit | Venue::Hyperliquid)
+    }
+
+    /// Publish every supported venue's updates through `manager` each
+    /// interval until `shutdown` flips; a failed poll is logged and retried
+    /// at the next interval
+    pub async fn run(&self, manager: &DataIngestionManager, venues: Vec<Venue>, mut shutdown: watch::Receiver<bool>) {
+        let venues: Vec<Venue> = venues.into_iter().filter(Self::supports).collect();
+        if venues.is_empty() {
+            return;
+        }
+        let mut ticker = tokio::time::interval(self.poll_interval.max(Duration::from_secs(1)));
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {}
+                _ = async { drop(shutdown.wait_for(|stop| *stop).await) } => return,
+            }
+            for venue in &venues {
+                let updates = match self.poll(venue).await {
+                    Ok(updates) => updates,
+                    Err(e) => {
+                        warn!("Funding poll on {} failed: {}", venue, e);
+                        continue;
+                    }
+                };
+                debug!("{} derivatives updates from {}", updates.len(), venue);
+                for update in updates {
+                    if let Err(e) = manager.publish_derivatives(update).await {
+                        warn!("Derivatives update from {} not published: {}", venue, e);
+                    }
+                }
+            }
+        }
+    }
+
+    /// `venue`'s current funding and open interest for every mapped market
+    pub async fn poll(&self, venue: &Venue) -> Result<Vec<DerivativesUpdate>, IngestionError> {
+        match venue {
+            Venue::Binance => self.poll_binance().await,
+            Venue::Bybit => {
+                let body = self.get(BYBIT_LINEAR_TICKERS_URL, venue).await?;
+                self.parse_bybit_tickers(&body, Utc::now())
+            }
+            Venue::Hyperliquid => Ok(self.hyperliquid.market_states().await?
+                .into_iter()
+                .flat_map(hyperliquid_updates)
+                .collect()),
+            other => Err(IngestionError::ExchangeApi(format!("No funding listing for {}", other))),
+        }
+    }
+
+    async fn poll_binance(&self) -> Result<Vec<DerivativesUpdate>, IngestionError> {
+        let body = self.get(BINANCE_PREMIUM_INDEX_URL, &Venue::Binance).await?;
+        let funding = self.parse_binance_premium_index(&body, Utc::now())?;
+        let mut updates = Vec::with_capacity(funding.len() * 2);
+        for rate in funding {
+            let exchange = self.symbols.exchange(&Venue::Binance, &rate.symbol)?;
+            let url = format!("{}?symbol={}", BINANCE_OPEN_INTEREST_URL, exchange);
+            let interest = self.get(&url, &Venue::Binance).await?;
+            updates.push(DerivativesUpdate::OpenInterest(
+                normalize_open_interest(&interest, &Venue::Binance, &self.symbols, Utc::now())?,
+            ));
+            updates.push(DerivativesUpdate::Funding(rate));
+        }
+        Ok(updates)
+    }
+
+    /// A `premiumIndex` response (an array of every perpetual) as funding
+    /// for the mapped ones
+    pub fn parse_binance_premium_index(&self, body: &Value, observed_at: DateTime<Utc>) -> Result<Vec<FundingRate>, IngestionError> {
+        let listed = body.as_array()
+            .ok_or_else(|| IngestionError::InvalidFormat("Binance premiumIndex is not an array".to_string()))?;
+        listed.iter()
+            .filter(|entry| self.mapped(&Venue::Binance, entry))
+            .map(|entry| normalize_funding(entry, &Venue::Binance, &self.symbols, observed_at))
+            .collect()
+    }
+
+    /// A `tickers` response, `{"retCode": 0, "result": {"list": [...]}}`, as
+    /// funding and open interest for the mapped perpetuals
+    pub fn parse_bybit_tickers(&self, body: &Value, observed_at: DateTime<Utc>) -> Result<Vec<DerivativesUpdate>, IngestionError> {
+        if let Some(code) = body.get("retCode").and_then(Value::as_i64).filter(|code| *code != 0) {
+            let message = body.get("retMsg").and_then(Value::as_str).unwrap_or("no message");
+            return Err(IngestionError::ExchangeApi(format!("Bybit tickers failed ({}): {}", code, message)));
+        }
+        let listed = body.get("result").and_then(|result| result.get("list")).and_then(Value::as_array)
+            .ok_or_else(|| IngestionError::InvalidFormat("Bybit tickers has no result list".to_string()))?;
+        // Entries carry no time of their own; the response's is when they were taken
+        let observed_at = match body.get("time") {
+            Some(time) => normalize_timestamp_in(time, TimestampUnit::Millis)?,
+            None => observed_at,
+        };
+        let mut updates = Vec::new();
+        for entry in listed.iter().filter(|entry| self.mapped(&Venue::Bybit, entry)) {
+            updates.push(DerivativesUpdate::Funding(normalize_funding(entry, &Venue::Bybit, &self.symbols, observed_at)?));
+            updates.push(DerivativesUpdate::OpenInterest(normalize_open_interest(entry, &Venue::Bybit, &self.symbols, observed_at)?));
+        }
+        Ok(updates)
+    }
+
+    /// Whether an entry's symbol is one we trade (listings hold hundreds)
+    fn mapped(&self, venue: &Venue, entry: &Value) -> bool {
+        entry.get("symbol").and_then(Value::as_str)
+            .is_some_and(|symbol| self.symbols.canonical(venue, symbol).is_ok())
+    }
+
+    async fn get(&self, url: &str, venue: &Venue) -> Result<Value, IngestionError> {
+        let response = self.client.get(url)
+            .send()
+            .await
+            .map_err(|e| IngestionError::Network(format!("{} derivatives: {}", venue, e)))?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(IngestionError::ExchangeApi(format!("{} derivatives returned {}", venue, status)));
+        }
+        response.json().await
+            .map_err(|e| IngestionError::InvalidFormat(format!("{} derivatives: {}", venue, e)))
+    }
+}
+
+/// A Hyperliquid market state as funding (marked against the oracle) and
+/// open interest
+fn hyperliquid_updates(state: HyperliquidMarketState) -> [DerivativesUpdate; 2] {
+    [
+        DerivativesUpdate::Funding(FundingRate {
+            symbol: state.symbol.clone(),
+            venue: Venue::Hyperliquid,
+            rate: state.funding.rate,
+            next_funding: state.funding.next_funding,
+            mark_price: state.mark_price,
+            index_price: Some(state.oracle_price),
+            timestamp: state.observed_at,
+        }),
+        DerivativesUpdate::OpenInterest(OpenInterest {
+            symbol: state.symbol,
+            venue: Venue::Hyperliquid,
+            quantity: state.open_interest,
+            timestamp: state.observed_at,
+        }),
+    ]
+}
+
+/// The entry's own `time` (millis), else `observed_at`
+fn entry_time(json: &Value, observed_at: DateTime<Utc>) -> Result<DateTime<Utc>, IngestionError> {
+    match json.get("time") {
+        Some(time) => Ok(normalize_timestamp_in(time, TimestampUnit::Millis)?),
+        None => Ok(observed_at),
+    }
+}
+
+fn field<'a>(value: &'a Value, key: &str) -> Result<&'a Value, IngestionError> {
+    value.get(key).ok_or_else(|| IngestionError::InvalidFormat(format!("Missing {}", key)))
+}
+
+fn text<'a>(value: &'a Value, key: &str) -> Result<&'a str, IngestionError> {
+    field(value, key)?.as_str()
+        .ok_or_else(|| IngestionError::InvalidFormat(format!("{} is not a string", key)))
+}
@@ -9,6 +9,14 @@ pub enum NormalizationError {
 
     #[error("Invalid type: {0}")]
     InvalidType(String),
+
+    /// An exchange symbol string with no mapping to ours
+    #[error("Unknown symbol {symbol:?} on {venue}")]
+    UnknownSymbol { venue: String, symbol: String },
+
+    /// A timestamp outside 2000-2100, usually read in the wrong unit
+    #[error("Timestamp {value} read as {unit} is {at}, outside 2000-2100")]
+    TimestampOutOfRange { value: String, unit: String, at: String },
 }
 
 #[derive(Debug, Error)]
@@ -19,13 +27,37 @@ pub enum IngestionError {
     #[error("Network error: {0}")]
     Network(String),
 
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
     #[error("Exchange API error: {0}")]
     ExchangeApi(String),
 
+    /// The venue refused a REST call for exceeding its rate limit (HTTP
+    /// 429, or 418 once it has banned the IP)
+    #[error("Rate limited (retry after {retry_after:?})")]
+    RateLimited { retry_after: Option<std::time::Duration> },
+
+    /// A REST call answered with an unsuccessful status
+    #[error("HTTP status {code}")]
+    HttpStatus { code: u16 },
+
     #[error("Timeout: {0}")]
     Timeout(String),
 
+    /// A full feed channel under the block policy found no room in time
+    #[error("Backpressure: {0}")]
+    Backpressure(String),
+
     #[error("Invalid data format: {0}")]
     InvalidFormat(String),
+
+    /// A book update did not follow on from the last one applied
+    #[error("Sequence gap: expected update {expected}, got {got}")]
+    SequenceGap { expected: u64, got: u64 },
+
+    /// A venue schema did not fit the message
+    #[error("Mapping for {venue} at {path}: {reason} (in {fragment})")]
+    Mapping { venue: String, path: String, reason: String, fragment: String },
 }
 
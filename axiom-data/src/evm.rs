@@ -0,0 +1,305 @@
+//! EVM: Uniswap v3 Pool State over eth_call
+//!
+//! A pool is read at a single block (`eth_blockNumber`, then every call
+//! tagged with it) so its price and holdings agree: `slot0()` for the
+//! Q64.96 square root price, `liquidity()` for the in-range liquidity,
+//! `token0()` and `token1()` for its tokens and `balanceOf(pool)` on each
+//! for what it holds. Results are ABI words, 32 bytes big-endian each,
+//! decoded here without a web3 stack. Token decimals are not read from the
+//! chain; they come from the reader's token map. Liquidity is both holdings
+//! valued in the quote token at the pool's price.
+
+use axiom_core::Symbol;
+use crate::errors::*;
+use crate::rpc::{EndpointReport, HealthPolicy, JsonRpc};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Liquidity source name for pool readings, as confidence overrides key it
+pub const UNISWAP_V3_SOURCE: &str = "uniswap_v3";
+
+/// `slot0()`
+const SLOT0: &str = "0x3850c7bd";
+/// `liquidity()`
+const LIQUIDITY: &str = "0x1a686502";
+/// `token0()`
+const TOKEN0: &str = "0x0dfe1681";
+/// `token1()`
+const TOKEN1: &str = "0xd21220a7";
+/// `balanceOf(address)`
+const BALANCE_OF: &str = "0x70a08231";
+
+/// A Uniswap v3 pool read for one symbol
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EvmPool {
+    /// Pool contract address, 0x-prefixed hex
+    pub address: String,
+    /// The pool's token0 is the symbol's quote (USDC/WETH for ETH/USD)
+    #[serde(default)]
+    pub inverted: bool,
+}
+
+impl EvmPool {
+    pub fn uniswap_v3(address: impl Into<String>) -> Self {
+        Self { address: address.into(), inverted: false }
+    }
+
+    /// Read the pool's tokens the other way round
+    pub fn inverted(mut self) -> Self {
+        self.inverted = true;
+        self
+    }
+}
+
+/// A pool's price and holdings at one block
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvmPoolState {
+    pub symbol: Symbol,
+    /// Pool contract address
+    pub pool: String,
+    /// Quote per base at the pool's price
+    pub price: Decimal,
+    /// Both holdings in quote
+    pub liquidity_quote: Decimal,
+    /// The pool's in-range liquidity, as `liquidity()` returns it
+    pub active_liquidity: u128,
+    pub block_number: u64,
+    pub observed_at: DateTime<Utc>,
+}
+
+/// A token balance in raw units and the token's decimals
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenBalance {
+    pub amount: u128,
+    pub decimals: u32,
+}
+
+/// Reads Uniswap v3 pools with eth_call, failing over between endpoints by health
+#[derive(Debug, Clone)]
+pub struct EvmPoolReader {
+    rpc: JsonRpc,
+    pools: HashMap<Symbol, EvmPool>,
+    /// Decimals by lowercase token address
+    decimals: HashMap<String, u32>,
+}
+
+impl EvmPoolReader {
+    /// Read through `rpc_endpoints` (the first preferred until latencies
+    /// are known)
+    pub fn new(rpc_endpoints: Vec<String>) -> Self {
+        Self {
+            rpc: JsonRpc::new("Ethereum", "eth_blockNumber", rpc_endpoints),
+            pools: HashMap::new(),
+            decimals: HashMap::new(),
+        }
+    }
+
+    /// Give each endpoint `timeout` to answer before trying the next
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.rpc = self.rpc.with_timeout(timeout);
+        self
+    }
+
+    pub fn with_health_policy(mut self, policy: HealthPolicy) -> Self {
+        self.rpc = self.rpc.with_health_policy(policy);
+        self
+    }
+
+    /// Read `symbol`'s state from `pool`, replacing any pool it had
+    pub fn with_pool(mut self, symbol: Symbol, pool: EvmPool) -> Self {
+        self.pools.insert(symbol, pool);
+        self
+    }
+
+    /// Scale the token at `address` by `decimals`
+    pub fn with_token(mut self, address: &str, decimals: u32) -> Self {
+        self.decimals.insert(address.to_ascii_lowercase(), decimals);
+        self
+    }
+
+    /// Every endpoint's health, in configured order
+    pub fn endpoint_health(&self) -> Vec<EndpointReport> {
+        self.rpc.endpoint_health()
+    }
+
+    /// Whether `symbol` has a pool to read
+    pub fn supports(&self, symbol: &Symbol) -> bool {
+        self.pools.contains_key(symbol)
+    }
+
+    /// `symbol`'s pool price and liquidity in its quote token, at the
+    /// latest block
+    pub async fn fetch_pool_state(&self, symbol: &Symbol) -> Result<EvmPoolState, IngestionError> {
+        let pool = self.pools.get(symbol)
+            .ok_or_else(|| IngestionError::ExchangeApi(format!("No EVM pool registered for {}", symbol.0)))?;
+        let block_number = parse_quantity(&self.rpc.call("eth_blockNumber", json!([])).await?)?;
+        let block = format!("{:#x}", block_number);
+
+        let (slot0, liquidity, token0, token1) = futures::try_join!(
+            self.eth_call(&pool.address, SLOT0, &block),
+            self.eth_call(&pool.address, LIQUIDITY, &block),
+            self.eth_call(&pool.address, TOKEN0, &block),
+            self.eth_call(&pool.address, TOKEN1, &block),
+        )?;
+        let sqrt_price = decode_sqrt_price_x96(first_word(&slot0)?)?;
+        let active_liquidity = abi_uint(first_word(&liquidity)?)?;
+        let token0 = abi_address(first_word(&token0)?)?;
+        let token1 = abi_address(first_word(&token1)?)?;
+
+        let balance_of = balance_of_call(&pool.address)?;
+        let (balance0, balance1) = futures::try_join!(
+            self.eth_call(&token0, &balance_of, &block),
+            self.eth_call(&token1, &balance_of, &block),
+        )?;
+        let balances = [
+            TokenBalance { amount: abi_uint(first_word(&balance0)?)?, decimals: self.token_decimals(&token0)? },
+            TokenBalance { amount: abi_uint(first_word(&balance1)?)?, decimals: self.token_decimals(&token1)? },
+        ];
+        pool_state(symbol, pool, sqrt_price, balances, active_liquidity, block_number)
+    }
+
+    fn token_decimals(&self, token: &str) -> Result<u32, IngestionError> {
+        self.decimals.get(&token.to_ascii_lowercase()).copied()
+            .ok_or_else(|| IngestionError::InvalidFormat(format!("No decimals configured for token {}", token)))
+    }
+
+    /// `data` called on `to` at `block`, as ABI words
+    async fn eth_call(&self, to: &str, data: &str, block: &str) -> Result<Vec<[u8; 32]>, IngestionError> {
+        let result = self.rpc.call("eth_call", json!([{ "to": to, "data": data }, block])).await?;
+        abi_words(&result)
+    }
+}
+
+/// `symbol`'s state from `pool`'s square root price (`decode_sqrt_price_x96`)
+/// and its token0 and token1 `balances`
+pub fn pool_state(
+    symbol: &Symbol,
+    pool: &EvmPool,
+    sqrt_price: Decimal,
+    balances: [TokenBalance; 2],
+    active_liquidity: u128,
+    block_number: u64,
+) -> Result<EvmPoolState, IngestionError> {
+    let overflow = || IngestionError::InvalidFormat(format!("Pool {} overflows a decimal", pool.address));
+    let [balance0, balance1] = balances;
+    let holding0 = whole_tokens(balance0)?;
+    let holding1 = whole_tokens(balance1)?;
+
+    // Token1 per token0 in whole tokens: raw price, shifted by the decimals
+    let price_1_per_0 = sqrt_price.checked_mul(sqrt_price)
+        .and_then(|raw| raw.checked_div(unit(balance0.decimals)?))
+        .and_then(|price| price.checked_mul(unit(balance1.decimals)?))
+        .ok_or_else(overflow)?;
+
+    let (base, quote, price) = if pool.inverted {
+        let price = Decimal::ONE.checked_div(price_1_per_0)
+            .ok_or_else(|| IngestionError::InvalidFormat(format!("Pool {} has no price", pool.address)))?;
+        (holding1, holding0, price)
+    } else {
+        (holding0, holding1, price_1_per_0)
+    };
+    let liquidity_quote = base.checked_mul(price)
+        .and_then(|base_value| base_value.checked_add(quote))
+        .ok_or_else(overflow)?;
+
+    Ok(EvmPoolState {
+        symbol: symbol.clone(),
+        pool: pool.address.clone(),
+        price,
+        liquidity_quote,
+        active_liquidity,
+        block_number,
+        observed_at: Utc::now(),
+    })
+}
+
+/// An `eth_call` result, `"0x..."`, split into 32-byte words; an empty
+/// result means the address has no such function (or no code)
+pub fn abi_words(result: &Value) -> Result<Vec<[u8; 32]>, IngestionError> {
+    let text = result.as_str()
+        .ok_or_else(|| IngestionError::InvalidFormat(format!("eth_call result {} is not a string", result)))?;
+    let bytes = hex::decode(text.strip_prefix("0x").unwrap_or(text))
+        .map_err(|e| IngestionError::InvalidFormat(format!("eth_call result: {}", e)))?;
+    if bytes.is_empty() || bytes.len() % 32 != 0 {
+        return Err(IngestionError::InvalidFormat(format!("eth_call returned {} bytes, not whole ABI words", bytes.len())));
+    }
+    Ok(bytes.chunks_exact(32).map(|chunk| {
+        let mut word = [0u8; 32];
+        word.copy_from_slice(chunk);
+        word
+    }).collect())
+}
+
+/// A uint word that fits 128 bits (balances, `liquidity()`)
+pub fn abi_uint(word: &[u8; 32]) -> Result<u128, IngestionError> {
+    if word[..16].iter().any(|byte| *byte != 0) {
+        return Err(IngestionError::InvalidFormat(format!("ABI uint 0x{} exceeds 128 bits", hex::encode(word))));
+    }
+    let mut low = [0u8; 16];
+    low.copy_from_slice(&word[16..]);
+    Ok(u128::from_be_bytes(low))
+}
+
+/// An address word as 0x-prefixed lowercase hex
+pub fn abi_address(word: &[u8; 32]) -> Result<String, IngestionError> {
+    if word[..12].iter().any(|byte| *byte != 0) {
+        return Err(IngestionError::InvalidFormat(format!("ABI word 0x{} is not an address", hex::encode(word))));
+    }
+    Ok(format!("0x{}", hex::encode(&word[12..])))
+}
+
+/// slot0's `sqrtPriceX96`, sqrt(raw token1 per raw token0) scaled by 2^96,
+/// as a decimal
+///
+/// A uint160 has 64 integer bits above 96 fractional ones; the fraction's
+/// lowest 32 bits fall below a decimal's precision and are dropped.
+pub fn decode_sqrt_price_x96(word: &[u8; 32]) -> Result<Decimal, IngestionError> {
+    if word[..12].iter().any(|byte| *byte != 0) {
+        return Err(IngestionError::InvalidFormat(format!("sqrtPriceX96 0x{} exceeds 160 bits", hex::encode(word))));
+    }
+    let mut integer = [0u8; 8];
+    integer.copy_from_slice(&word[12..20]);
+    let mut fraction = [0u8; 8];
+    fraction.copy_from_slice(&word[20..28]);
+    let two_pow_64 = Decimal::from(u64::MAX) + Decimal::ONE;
+    Ok(Decimal::from(u64::from_be_bytes(integer)) + Decimal::from(u64::from_be_bytes(fraction)) / two_pow_64)
+}
+
+/// A JSON-RPC quantity, `"0x1b4"`
+pub fn parse_quantity(result: &Value) -> Result<u64, IngestionError> {
+    result.as_str()
+        .and_then(|text| text.strip_prefix("0x"))
+        .and_then(|digits| u64::from_str_radix(digits, 16).ok())
+        .ok_or_else(|| IngestionError::InvalidFormat(format!("{} is not a hex quantity", result)))
+}
+
+/// Calldata for `balanceOf(owner)`
+fn balance_of_call(owner: &str) -> Result<String, IngestionError> {
+    let digits = owner.strip_prefix("0x").unwrap_or(owner);
+    match hex::decode(digits) {
+        Ok(bytes) if bytes.len() == 20 => Ok(format!("{}{:0>64}", BALANCE_OF, digits.to_ascii_lowercase())),
+        _ => Err(IngestionError::InvalidFormat(format!("{} is not an address", owner))),
+    }
+}
+
+fn first_word(words: &[[u8; 32]]) -> Result<&[u8; 32], IngestionError> {
+    words.first().ok_or_else(|| IngestionError::InvalidFormat("eth_call returned no words".to_string()))
+}
+
+/// A raw balance in whole tokens
+fn whole_tokens(balance: TokenBalance) -> Result<Decimal, IngestionError> {
+    i128::try_from(balance.amount).ok()
+        .and_then(|amount| Decimal::try_from_i128_with_scale(amount, balance.decimals).ok())
+        .ok_or_else(|| IngestionError::InvalidFormat(format!(
+            "Balance {} with {} decimals overflows a decimal", balance.amount, balance.decimals,
+        )))
+}
+
+/// One raw unit of a token with `decimals` decimals, in whole tokens
+fn unit(decimals: u32) -> Option<Decimal> {
+    Decimal::try_from_i128_with_scale(1, decimals).ok()
+}
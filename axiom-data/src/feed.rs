@@ -0,0 +1,221 @@
+//! Bounded Feed Channels
+//!
+//! Ticks and books pass from ingestion to the engine through bounded
+//! queues, so a consumer that falls behind during a burst costs dropped or
+//! delayed messages instead of unbounded memory. Each channel's overflow
+//! policy picks which: wait a short while for room, discard the oldest
+//! queued message, or discard the new one. Every discarded message is
+//! counted.
+
+use axiom_core::constants::TARGET_LATENCY_ORDERBOOK;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+use tokio::sync::Notify;
+
+/// Messages a feed channel holds unless configured
+pub const DEFAULT_FEED_CAPACITY: usize = 10_000;
+
+/// What a full channel does with the next message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverflowPolicy {
+    /// Wait up to the block timeout for room, then discard the message
+    Block,
+    /// Make room by discarding the oldest queued message
+    DropOldest,
+    /// Discard the new message
+    DropNewest,
+}
+
+/// One channel's bound and overflow handling
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChannelPolicy {
+    pub capacity: usize,
+    pub overflow: OverflowPolicy,
+    /// How long `Block` waits for room
+    pub block_timeout_ms: u64,
+}
+
+impl Default for ChannelPolicy {
+    fn default() -> Self {
+        Self {
+            capacity: DEFAULT_FEED_CAPACITY,
+            overflow: OverflowPolicy::DropOldest,
+            block_timeout_ms: TARGET_LATENCY_ORDERBOOK,
+        }
+    }
+}
+
+/// Why a message was not queued
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedSendError {
+    /// The receiver is gone
+    Closed,
+    /// `Block` waited its timeout without room
+    TimedOut,
+}
+
+struct Queue<T> {
+    items: VecDeque<T>,
+    senders: usize,
+    receiver: bool,
+}
+
+struct Shared<T> {
+    queue: Mutex<Queue<T>>,
+    /// Wakes the receiver
+    sent: Notify,
+    /// Wakes senders blocked on a full queue
+    received: Notify,
+    policy: ChannelPolicy,
+    dropped: AtomicU64,
+}
+
+impl<T> Shared<T> {
+    fn lock(&self) -> MutexGuard<'_, Queue<T>> {
+        // Nothing panics with the lock held, so a poisoned queue is intact
+        self.queue.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// A bounded channel applying `policy` when full
+pub fn feed_channel<T>(policy: ChannelPolicy) -> (FeedSender<T>, FeedReceiver<T>) {
+    let shared = Arc::new(Shared {
+        queue: Mutex::new(Queue { items: VecDeque::with_capacity(policy.capacity.min(1024)), senders: 1, receiver: true }),
+        sent: Notify::new(),
+        received: Notify::new(),
+        policy: ChannelPolicy { capacity: policy.capacity.max(1), ..policy },
+        dropped: AtomicU64::new(0),
+    });
+    (FeedSender { shared: shared.clone() }, FeedReceiver { shared })
+}
+
+/// Sending half of a feed channel
+pub struct FeedSender<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> FeedSender<T> {
+    /// Queue `item`, applying the overflow policy when the channel is full
+    ///
+    /// Only `Block` waits; the drop policies always return at once.
+    pub async fn send(&self, item: T) -> Result<(), FeedSendError> {
+        let deadline = tokio::time::Instant::now() + Duration::from_millis(self.shared.policy.block_timeout_ms);
+        let mut item = item;
+        loop {
+            // Registered before the check, so a receive in between still wakes us
+            let received = self.shared.received.notified();
+            item = match self.try_queue(item)? {
+                None => return Ok(()),
+                Some(item) => item,
+            };
+            if tokio::time::timeout_at(deadline, received).await.is_err() {
+                self.shared.dropped.fetch_add(1, Ordering::Relaxed);
+                return Err(FeedSendError::TimedOut);
+            }
+        }
+    }
+
+    /// Queue `item` unless the channel is full under `Block`, in which case
+    /// it is handed back
+    fn try_queue(&self, item: T) -> Result<Option<T>, FeedSendError> {
+        let mut queue = self.shared.lock();
+        if !queue.receiver {
+            return Err(FeedSendError::Closed);
+        }
+        if queue.items.len() >= self.shared.policy.capacity {
+            match self.shared.policy.overflow {
+                OverflowPolicy::Block => return Ok(Some(item)),
+                OverflowPolicy::DropNewest => {
+                    self.shared.dropped.fetch_add(1, Ordering::Relaxed);
+                    return Ok(None);
+                }
+                OverflowPolicy::DropOldest => {
+                    queue.items.pop_front();
+                    self.shared.dropped.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        }
+        queue.items.push_back(item);
+        drop(queue);
+        self.shared.sent.notify_one();
+        Ok(None)
+    }
+
+    /// Messages discarded by the overflow policy so far
+    pub fn dropped(&self) -> u64 {
+        self.shared.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Messages queued now
+    pub fn len(&self) -> usize {
+        self.shared.lock().items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn policy(&self) -> ChannelPolicy {
+        self.shared.policy
+    }
+}
+
+impl<T> Clone for FeedSender<T> {
+    fn clone(&self) -> Self {
+        self.shared.lock().senders += 1;
+        Self { shared: self.shared.clone() }
+    }
+}
+
+impl<T> Drop for FeedSender<T> {
+    fn drop(&mut self) {
+        let mut queue = self.shared.lock();
+        queue.senders -= 1;
+        if queue.senders == 0 {
+            drop(queue);
+            self.shared.sent.notify_one();
+        }
+    }
+}
+
+/// Receiving half of a feed channel
+pub struct FeedReceiver<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> FeedReceiver<T> {
+    /// The oldest queued message; None once every sender is gone and the
+    /// queue is drained
+    pub async fn recv(&mut self) -> Option<T> {
+        loop {
+            let sent = self.shared.sent.notified();
+            {
+                let mut queue = self.shared.lock();
+                if let Some(item) = queue.items.pop_front() {
+                    drop(queue);
+                    self.shared.received.notify_one();
+                    return Some(item);
+                }
+                if queue.senders == 0 {
+                    return None;
+                }
+            }
+            sent.await;
+        }
+    }
+}
+
+impl<T> Drop for FeedReceiver<T> {
+    fn drop(&mut self) {
+        let mut queue = self.shared.lock();
+        queue.receiver = false;
+        queue.items.clear();
+        drop(queue);
+        self.shared.received.notify_waiters();
+    }
+}
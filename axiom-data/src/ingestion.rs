@@ -3,37 +3,113 @@
 //! Handles real-time data ingestion from exchanges with deterministic
 //! normalization and latency monitoring.
 
-use axiom_core::{Symbol, Venue, Tick, OrderBook};
+use axiom_core::{Symbol, Venue, Tick, OrderBook, DerivativesUpdate, LiquidationEvent, LatencyRecorder, LatencyStage};
 use crate::normalization::*;
 use crate::errors::*;
-use tokio::sync::mpsc;
-use tracing::{info, error};
-use std::time::Instant;
+use crate::liquidations::parse_liquidations;
+use crate::feed::{FeedSendError, FeedSender};
+use crate::recorder::{Recorder, RecorderStats};
+use crate::schema::SchemaRegistry;
+use crate::venues::{VenueAdapter, VenueEvent};
+use chrono::{DateTime, Utc};
+use tracing::{info, error, warn};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 /// Data ingestion manager
 pub struct DataIngestionManager {
-    tick_sender: mpsc::UnboundedSender<Tick>,
-    book_sender: mpsc::UnboundedSender<OrderBook>,
+    tick_sender: FeedSender<Tick>,
+    book_sender: FeedSender<Arc<OrderBook>>,
+    /// Funding and open interest, when something consumes them
+    derivatives_sender: Option<FeedSender<DerivativesUpdate>>,
+    /// Forced orders, when something consumes them
+    liquidation_sender: Option<FeedSender<LiquidationEvent>>,
+    latency: Option<Arc<dyn LatencyRecorder>>,
+    schemas: Arc<SchemaRegistry>,
+    symbols: Arc<SymbolMapper>,
+    recorder: Option<Arc<Recorder>>,
+    /// Typed trade parsers for venues still on their built-in tick mapping
+    #[cfg(feature = "fast-ingest")]
+    typed_ticks: std::collections::HashMap<Venue, crate::raw::TickParser>,
 }
 
 impl DataIngestionManager {
+    /// Publish to `tick_sender` and `book_sender`, each bounded and with
+    /// the overflow policy its `feed_channel` was built with
     pub fn new(
-        tick_sender: mpsc::UnboundedSender<Tick>,
-        book_sender: mpsc::UnboundedSender<OrderBook>,
+        tick_sender: FeedSender<Tick>,
+        book_sender: FeedSender<Arc<OrderBook>>,
     ) -> Self {
+        let schemas = Arc::new(SchemaRegistry::builtin());
         Self {
             tick_sender,
             book_sender,
+            derivatives_sender: None,
+            liquidation_sender: None,
+            latency: None,
+            #[cfg(feature = "fast-ingest")]
+            typed_ticks: crate::raw::typed_tick_parsers(&schemas),
+            schemas,
+            symbols: Arc::new(SymbolMapper::builtin()),
+            recorder: None,
         }
     }
 
+    /// Publish funding and open interest updates to `sender`
+    pub fn with_derivatives_sender(mut self, sender: FeedSender<DerivativesUpdate>) -> Self {
+        self.derivatives_sender = Some(sender);
+        self
+    }
+
+    /// Publish liquidations to `sender`
+    pub fn with_liquidation_sender(mut self, sender: FeedSender<LiquidationEvent>) -> Self {
+        self.liquidation_sender = Some(sender);
+        self
+    }
+
+    /// Report ingest_parse latency per venue
+    pub fn with_latency_recorder(mut self, latency: Arc<dyn LatencyRecorder>) -> Self {
+        self.latency = Some(latency);
+        self
+    }
+
+    /// Map venue payloads with `schemas` instead of the built-in mappings
+    pub fn with_schemas(mut self, schemas: Arc<SchemaRegistry>) -> Self {
+        #[cfg(feature = "fast-ingest")]
+        {
+            self.typed_ticks = crate::raw::typed_tick_parsers(&schemas);
+        }
+        self.schemas = schemas;
+        self
+    }
+
+    /// Map exchange symbols to ours with `symbols` instead of the built-in table
+    pub fn with_symbols(mut self, symbols: Arc<SymbolMapper>) -> Self {
+        self.symbols = symbols;
+        self
+    }
+
+    /// Also write every tick and book published to capture segments
+    pub fn with_recorder(mut self, recorder: Arc<Recorder>) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    /// What the recorder has written, when recording
+    pub fn recorder_stats(&self) -> Option<RecorderStats> {
+        self.recorder.as_ref().map(|recorder| recorder.stats())
+    }
+
     /// Start ingesting data from a venue
     pub async fn start_ingestion(
         &self,
         venue: Venue,
         symbols: Vec<Symbol>,
     ) -> Result<(), IngestionError> {
-        info!("Starting data ingestion for venue: {}", venue.0);
+        match self.schemas.get(&venue).and_then(|schema| schema.feed_url.as_deref()) {
+            Some(url) => info!("Starting data ingestion for venue: {} ({}): {:?}", venue, url, symbols),
+            None => info!("Starting data ingestion for venue: {}: {:?}", venue, symbols),
+        }
         
         // For now, this is a placeholder. In production, this would:
         // 1. Connect to exchange WebSocket/REST API
@@ -44,67 +120,225 @@ impl DataIngestionManager {
         Ok(())
     }
 
+    /// Stop ingesting `symbols` from a venue (unsubscribe)
+    pub async fn stop_ingestion(
+        &self,
+        venue: Venue,
+        symbols: Vec<Symbol>,
+    ) -> Result<(), IngestionError> {
+        let names: Vec<&str> = symbols.iter().map(|symbol| symbol.0.as_str()).collect();
+        info!("Stopping data ingestion for venue {}: {}", venue, names.join(", "));
+
+        // Placeholder, like start_ingestion: in production this would
+        // unsubscribe the venue streams for these symbols
+
+        Ok(())
+    }
+
+    /// Ticks and books discarded by the channels' overflow policies so far
+    pub fn dropped_messages(&self) -> (u64, u64) {
+        (self.tick_sender.dropped(), self.book_sender.dropped())
+    }
+
     /// Process a raw tick from exchange
-    pub fn process_tick(&self, raw: &[u8], venue: &Venue) -> Result<(), IngestionError> {
+    ///
+    /// A full tick channel under the block policy gives `Backpressure` once
+    /// its timeout passes; the tick is dropped and counted.
+    pub async fn process_tick(&self, raw: &[u8], venue: &Venue) -> Result<(), IngestionError> {
         let start = Instant::now();
-        
-        // Parse JSON
-        let json: serde_json::Value = serde_json::from_slice(raw)
-            .map_err(|e| IngestionError::InvalidFormat(format!("JSON parse: {}", e)))?;
+        let received_at = Utc::now();
 
-        // Normalize to Tick
-        let tick = self.normalize_tick(&json, venue)?;
+        let mut tick = self.parse_tick(raw, venue)?;
+        self.stamp_received(&mut tick, received_at);
 
         // Check latency
-        let latency_ms = start.elapsed().as_millis() as u64;
+        let elapsed = start.elapsed();
+        if let Some(latency) = &self.latency {
+            latency.record(LatencyStage::IngestParse, venue, elapsed);
+        }
+        let latency_ms = elapsed.as_millis() as u64;
         if latency_ms > 10 {
             error!("High ingestion latency: {}ms", latency_ms);
         }
 
         // Send to channel
-        self.tick_sender.send(tick)
-            .map_err(|e| IngestionError::Network(format!("Channel send: {}", e)))?;
+        self.record_tick(&tick);
+        self.tick_sender.send(tick).await
+            .map_err(|e| send_error("tick", e))
+    }
+
+    /// Publish a funding or open interest update; dropped when no
+    /// derivatives channel is attached
+    pub async fn publish_derivatives(&self, update: DerivativesUpdate) -> Result<(), IngestionError> {
+        match &self.derivatives_sender {
+            Some(sender) => sender.send(update).await.map_err(|e| send_error("derivatives", e)),
+            None => Ok(()),
+        }
+    }
 
+    /// Process a raw forceOrder or liquidation message; dropped when no
+    /// liquidation channel is attached
+    pub async fn process_liquidation(&self, raw: &[u8], venue: &Venue) -> Result<(), IngestionError> {
+        if self.liquidation_sender.is_none() {
+            return Ok(());
+        }
+        for event in parse_liquidations(raw, venue, &self.symbols)? {
+            self.publish_liquidation(event).await?;
+        }
         Ok(())
     }
 
-    /// Normalize raw JSON to Tick
-    fn normalize_tick(&self, json: &serde_json::Value, venue: &Venue) -> Result<Tick, IngestionError> {
-        let symbol = Symbol(
-            json.get("symbol")
-                .and_then(|v| v.as_str())
-                .ok_or_else(|| IngestionError::InvalidFormat("Missing symbol".to_string()))?
-                .to_string()
-        );
+    /// Publish one liquidation; dropped when no liquidation channel is
+    /// attached
+    pub async fn publish_liquidation(&self, event: LiquidationEvent) -> Result<(), IngestionError> {
+        match &self.liquidation_sender {
+            Some(sender) => sender.send(event).await.map_err(|e| send_error("liquidation", e)),
+            None => Ok(()),
+        }
+    }
 
-        let price = normalize_price(
-            json.get("price")
-                .ok_or_else(|| IngestionError::InvalidFormat("Missing price".to_string()))?
-        )?;
+    /// Handle one message from a venue connection through its adapter
+    ///
+    /// Trades, books and liquidations go to the channels. Returns the
+    /// requests to send back on the connection (a book resync), usually none.
+    pub async fn process_frame(
+        &self,
+        adapter: &mut dyn VenueAdapter,
+        raw: &[u8],
+    ) -> Result<Vec<serde_json::Value>, IngestionError> {
+        let start = Instant::now();
+        let received_at = Utc::now();
+        let event = adapter.handle(raw)?;
+        if let Some(latency) = &self.latency {
+            latency.record(LatencyStage::IngestParse, adapter.venue(), start.elapsed());
+        }
 
-        let quantity = normalize_quantity(
-            json.get("quantity")
-                .ok_or_else(|| IngestionError::InvalidFormat("Missing quantity".to_string()))?
-        )?;
+        match event {
+            VenueEvent::Ticks(ticks) => {
+                for mut tick in ticks {
+                    self.stamp_received(&mut tick, received_at);
+                    self.record_tick(&tick);
+                    self.tick_sender.send(tick).await
+                        .map_err(|e| send_error("tick", e))?;
+                }
+            }
+            VenueEvent::Book(book) => {
+                self.record_book(&book);
+                self.book_sender.send(book).await
+                    .map_err(|e| send_error("book", e))?;
+            }
+            VenueEvent::Liquidations(events) => {
+                for event in events {
+                    self.publish_liquidation(event).await?;
+                }
+            }
+            VenueEvent::Resync { symbol, requests } => {
+                warn!("Resyncing {} book on {}", symbol.0, adapter.venue());
+                return Ok(requests);
+            }
+            VenueEvent::Control => {}
+        }
+        Ok(Vec::new())
+    }
 
-        let timestamp = normalize_timestamp(
-            json.get("timestamp")
-                .ok_or_else(|| IngestionError::InvalidFormat("Missing timestamp".to_string()))?
-        )?;
+    /// Mark when `tick`'s message arrived and report its delay from the
+    /// exchange (zero when the venue's clock runs ahead of ours)
+    fn stamp_received(&self, tick: &mut Tick, received_at: DateTime<Utc>) {
+        tick.received_at = Some(received_at);
+        if let (Some(latency), Some(delay)) = (&self.latency, tick.feed_delay()) {
+            latency.record(LatencyStage::FeedDelay, &tick.venue, delay.to_std().unwrap_or(Duration::ZERO));
+        }
+    }
 
-        let side_str = json.get("side")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| IngestionError::InvalidFormat("Missing side".to_string()))?;
-        let side = normalize_side(side_str)?;
-
-        Ok(Tick {
-            symbol,
-            venue: venue.clone(),
-            price,
-            quantity,
-            timestamp,
-            side,
-        })
+    /// A failed capture write is logged; it never holds up the feed
+    fn record_tick(&self, tick: &Tick) {
+        if let Some(Err(e)) = self.recorder.as_ref().map(|recorder| recorder.record_tick(tick)) {
+            warn!("Capture of {} tick failed: {}", tick.symbol.0, e);
+        }
+    }
+
+    fn record_book(&self, book: &OrderBook) {
+        if let Some(Err(e)) = self.recorder.as_ref().map(|recorder| recorder.record_book(book)) {
+            warn!("Capture of {} book failed: {}", book.symbol.0, e);
+        }
+    }
+
+    /// Parse and normalize a raw tick message, through the venue's typed
+    /// parser (`fast-ingest`) or tick mapping when it has one
+    fn parse_tick(&self, raw: &[u8], venue: &Venue) -> Result<Tick, IngestionError> {
+        #[cfg(feature = "fast-ingest")]
+        if let Some(parse) = self.typed_ticks.get(venue) {
+            return parse(raw, venue, &self.symbols);
+        }
+        match self.schemas.get(venue).filter(|schema| schema.tick.is_some()) {
+            Some(schema) => schema.normalize_tick(&parse_json(raw)?, venue, &self.symbols),
+            None => self.parse_default_tick(raw, venue),
+        }
+    }
+
+    /// Parse a tick in the default payload shape
+    #[cfg(feature = "fast-ingest")]
+    fn parse_default_tick(&self, raw: &[u8], venue: &Venue) -> Result<Tick, IngestionError> {
+        crate::raw::parse_tick(raw, venue, &self.symbols)
+    }
+
+    /// Parse a tick in the default payload shape
+    #[cfg(not(feature = "fast-ingest"))]
+    fn parse_default_tick(&self, raw: &[u8], venue: &Venue) -> Result<Tick, IngestionError> {
+        normalize_tick(&parse_json(raw)?, venue, &self.symbols)
     }
 }
 
+pub(crate) fn send_error(stream: &str, error: FeedSendError) -> IngestionError {
+    match error {
+        FeedSendError::Closed => IngestionError::Network(format!("Channel send: {} receiver closed", stream)),
+        FeedSendError::TimedOut => IngestionError::Backpressure(format!("{} channel full; message dropped", stream)),
+    }
+}
+
+fn parse_json(raw: &[u8]) -> Result<serde_json::Value, IngestionError> {
+    serde_json::from_slice(raw)
+        .map_err(|e| IngestionError::InvalidFormat(format!("JSON parse: {}", e)))
+}
+
+/// Normalize a parsed tick message in the default payload shape (the
+/// `Value` path), for venues without a tick mapping; the symbol is the
+/// venue's, mapped to ours through `symbols`
+pub fn normalize_tick(json: &serde_json::Value, venue: &Venue, symbols: &SymbolMapper) -> Result<Tick, IngestionError> {
+    let symbol = symbols.canonical(
+        venue,
+        json.get("symbol")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| IngestionError::InvalidFormat("Missing symbol".to_string()))?
+    )?;
+
+    let price = normalize_price(
+        json.get("price")
+            .ok_or_else(|| IngestionError::InvalidFormat("Missing price".to_string()))?
+    )?;
+
+    let quantity = normalize_quantity(
+        json.get("quantity")
+            .ok_or_else(|| IngestionError::InvalidFormat("Missing quantity".to_string()))?
+    )?;
+
+    let timestamp = normalize_timestamp(
+        json.get("timestamp")
+            .ok_or_else(|| IngestionError::InvalidFormat("Missing timestamp".to_string()))?
+    )?;
+
+    let side_str = json.get("side")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| IngestionError::InvalidFormat("Missing side".to_string()))?;
+    let side = normalize_side(side_str)?;
+
+    Ok(Tick {
+        symbol,
+        venue: venue.clone(),
+        price,
+        quantity,
+        timestamp,
+        side,
+        received_at: None,
+    })
+}
@@ -0,0 +1,182 @@
+//! Instrument Listings: Venue Tick and Lot Rules
+//!
+//! Binance spot `exchangeInfo` gives each symbol's filters: `PRICE_FILTER`
+//! (`tickSize`), `LOT_SIZE` (`stepSize`, `minQty`, `maxQty`) and `NOTIONAL`,
+//! or the older `MIN_NOTIONAL` (`minNotional`). Bybit v5 `instruments-info`
+//! gives `priceFilter.tickSize` and a `lotSizeFilter` with the step
+//! (`basePrecision` on spot, `qtyStep` on derivatives), `minOrderQty`,
+//! `maxOrderQty` and the minimum notional (`minOrderAmt` on spot,
+//! `minNotionalValue` on derivatives). Symbols go through the symbol mapper;
+//! markets it does not map are skipped.
+
+use axiom_core::{Amount, Instrument, InstrumentRegistry, Symbol, Venue};
+use crate::errors::*;
+use crate::normalization::*;
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::info;
+
+/// Binance spot listing (mainnet)
+pub const BINANCE_EXCHANGE_INFO_URL: &str = "https://api.binance.com/api/v3/exchangeInfo";
+
+/// Bybit spot listing (mainnet)
+pub const BYBIT_INSTRUMENTS_URL: &str = "https://api.bybit.com/v5/market/instruments-info?category=spot";
+
+/// Reads instrument rules from venue listings
+#[derive(Debug, Clone)]
+pub struct InstrumentFetcher {
+    client: reqwest::Client,
+    symbols: Arc<SymbolMapper>,
+    binance_url: String,
+    bybit_url: String,
+}
+
+impl InstrumentFetcher {
+    pub fn new() -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            symbols: Arc::new(SymbolMapper::builtin()),
+            binance_url: BINANCE_EXCHANGE_INFO_URL.to_string(),
+            bybit_url: BYBIT_INSTRUMENTS_URL.to_string(),
+        }
+    }
+
+    /// Map listed symbols with `symbols` instead of the built-in table
+    pub fn with_symbols(mut self, symbols: Arc<SymbolMapper>) -> Self {
+        self.symbols = symbols;
+        self
+    }
+
+    /// Query `url` for Binance instead of mainnet (testnet, a proxy)
+    pub fn with_binance_url(mut self, url: impl Into<String>) -> Self {
+        self.binance_url = url.into();
+        self
+    }
+
+    /// Query `url` for Bybit instead of mainnet spot
+    pub fn with_bybit_url(mut self, url: impl Into<String>) -> Self {
+        self.bybit_url = url.into();
+        self
+    }
+
+    /// Whether `venue` has a listing this fetcher reads
+    pub fn supports(venue: &Venue) -> bool {
+        matches!(venue, Venue::Binance | Venue::Bybit)
+    }
+
+    /// Add every mapped instrument `venues` list to `registry`; venues
+    /// without a listing are skipped
+    pub async fn fetch_into(&self, registry: &mut InstrumentRegistry, venues: &[Venue]) -> Result<(), IngestionError> {
+        for venue in venues.iter().filter(|venue| Self::supports(venue)) {
+            let instruments = self.fetch(venue).await?;
+            info!("Loaded {} instruments from {}", instruments.len(), venue);
+            registry.extend(venue, instruments);
+        }
+        Ok(())
+    }
+
+    /// `venue`'s mapped instruments
+    pub async fn fetch(&self, venue: &Venue) -> Result<Vec<(Symbol, Instrument)>, IngestionError> {
+        match venue {
+            Venue::Binance => self.parse_binance(&self.get(&self.binance_url, venue).await?),
+            Venue::Bybit => self.parse_bybit(&self.get(&self.bybit_url, venue).await?),
+            other => Err(IngestionError::ExchangeApi(format!("No instrument listing for {}", other))),
+        }
+    }
+
+    async fn get(&self, url: &str, venue: &Venue) -> Result<Value, IngestionError> {
+        let response = self.client.get(url)
+            .send()
+            .await
+            .map_err(|e| IngestionError::Network(format!("{} instruments: {}", venue, e)))?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(IngestionError::ExchangeApi(format!("{} instruments returned {}", venue, status)));
+        }
+        response.json().await
+            .map_err(|e| IngestionError::InvalidFormat(format!("{} instruments: {}", venue, e)))
+    }
+
+    /// An `exchangeInfo` response, `{"symbols": [{"symbol", "filters": [...]}, ...]}`
+    pub fn parse_binance(&self, body: &Value) -> Result<Vec<(Symbol, Instrument)>, IngestionError> {
+        let listed = body.get("symbols").and_then(Value::as_array)
+            .ok_or_else(|| IngestionError::InvalidFormat("Binance exchangeInfo has no symbols".to_string()))?;
+        let mut instruments = Vec::new();
+        for entry in listed {
+            let Ok(symbol) = self.symbols.canonical(&Venue::Binance, text(entry, "symbol")?) else { continue };
+            let filters = entry.get("filters").and_then(Value::as_array)
+                .ok_or_else(|| IngestionError::InvalidFormat(format!("Binance {} has no filters", symbol.0)))?;
+            let filter = |kind: &str| filters.iter()
+                .find(|filter| filter.get("filterType").and_then(Value::as_str) == Some(kind));
+
+            let price = filter("PRICE_FILTER")
+                .ok_or_else(|| IngestionError::InvalidFormat(format!("Binance {} has no PRICE_FILTER", symbol.0)))?;
+            let lot = filter("LOT_SIZE")
+                .ok_or_else(|| IngestionError::InvalidFormat(format!("Binance {} has no LOT_SIZE", symbol.0)))?;
+            let mut instrument = Instrument::new(
+                normalize_price_str(text(price, "tickSize")?)?,
+                normalize_quantity_str(text(lot, "stepSize")?)?,
+            )
+            .with_quantity_range(
+                normalize_quantity_str(text(lot, "minQty")?)?,
+                positive(normalize_quantity_str(text(lot, "maxQty")?)?),
+            );
+            if let Some(notional) = filter("NOTIONAL").or_else(|| filter("MIN_NOTIONAL")) {
+                instrument = instrument.with_min_notional(Amount::new(parse_decimal(text(notional, "minNotional")?)?));
+            }
+            instruments.push((symbol, instrument));
+        }
+        Ok(instruments)
+    }
+
+    /// An `instruments-info` response, `{"retCode": 0, "result": {"list": [...]}}`
+    pub fn parse_bybit(&self, body: &Value) -> Result<Vec<(Symbol, Instrument)>, IngestionError> {
+        if let Some(code) = body.get("retCode").and_then(Value::as_i64).filter(|code| *code != 0) {
+            let message = body.get("retMsg").and_then(Value::as_str).unwrap_or("no message");
+            return Err(IngestionError::ExchangeApi(format!("Bybit instruments-info failed ({}): {}", code, message)));
+        }
+        let listed = body.get("result").and_then(|result| result.get("list")).and_then(Value::as_array)
+            .ok_or_else(|| IngestionError::InvalidFormat("Bybit instruments-info has no result list".to_string()))?;
+        let mut instruments = Vec::new();
+        for entry in listed {
+            let Ok(symbol) = self.symbols.canonical(&Venue::Bybit, text(entry, "symbol")?) else { continue };
+            let price = field(entry, "priceFilter")?;
+            let lot = field(entry, "lotSizeFilter")?;
+            let step = lot.get("qtyStep").or_else(|| lot.get("basePrecision"))
+                .ok_or_else(|| IngestionError::InvalidFormat(format!("Bybit {} has no quantity step", symbol.0)))?;
+            let mut instrument = Instrument::new(
+                normalize_price(field(price, "tickSize")?)?,
+                normalize_quantity(step)?,
+            )
+            .with_quantity_range(
+                normalize_quantity(field(lot, "minOrderQty")?)?,
+                positive(normalize_quantity(field(lot, "maxOrderQty")?)?),
+            );
+            if let Some(notional) = lot.get("minOrderAmt").or_else(|| lot.get("minNotionalValue")) {
+                instrument = instrument.with_min_notional(Amount::new(normalize_price(notional)?.get()));
+            }
+            instruments.push((symbol, instrument));
+        }
+        Ok(instruments)
+    }
+}
+
+impl Default for InstrumentFetcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A maximum of zero means none
+fn positive(quantity: axiom_core::Quantity) -> Option<axiom_core::Quantity> {
+    (!quantity.is_zero()).then_some(quantity)
+}
+
+fn field<'a>(value: &'a Value, key: &str) -> Result<&'a Value, IngestionError> {
+    value.get(key).ok_or_else(|| IngestionError::InvalidFormat(format!("Instrument listing missing {:?}", key)))
+}
+
+fn text<'a>(value: &'a Value, key: &str) -> Result<&'a str, IngestionError> {
+    field(value, key)?.as_str()
+        .ok_or_else(|| IngestionError::InvalidFormat(format!("Instrument field {:?} is not a string", key)))
+}
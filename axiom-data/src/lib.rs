@@ -4,15 +4,47 @@
 //! normalizing all data into fixed-point representations to ensure
 //! bitwise reproducibility.
 
+pub mod bars;
+pub mod derivatives;
+pub mod evm;
+pub mod feed;
 pub mod ingestion;
+pub mod instruments;
+pub mod liquidations;
 pub mod normalization;
 pub mod orderbook;
+pub mod ratelimit;
 pub mod onchain;
+pub mod recorder;
+pub mod rpc;
+pub mod replay;
 pub mod errors;
+pub mod schema;
+pub mod snapshot;
+pub mod solana;
+pub mod venues;
+#[cfg(feature = "fast-ingest")]
+pub mod raw;
 
+pub use bars::*;
+pub use derivatives::*;
+pub use evm::*;
+pub use feed::*;
 pub use ingestion::*;
+pub use instruments::*;
+pub use liquidations::*;
 pub use normalization::*;
 pub use orderbook::*;
+pub use ratelimit::*;
 pub use onchain::*;
+pub use recorder::*;
+pub use rpc::*;
+pub use replay::*;
 pub use errors::*;
+pub use schema::*;
+pub use snapshot::*;
+pub use solana::*;
+pub use venues::*;
+#[cfg(feature = "fast-ingest")]
+pub use raw::*;
 
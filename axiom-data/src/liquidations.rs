@@ -0,0 +1,104 @@
+//! Liquidations: Forced Orders from Venue Feeds
+//!
+//! Binance USD-M streams each forced order on `<symbol>@forceOrder`:
+//! `{"e": "forceOrder", "E", "o": {"s", "S", "q", "p", "ap", "z", "T", ...}}`,
+//! where `S` is the forced order's side, `ap` its average fill price and `z`
+//! the quantity filled so far. Bybit v5 linear streams them on
+//! `allLiquidation.<symbol>`, several to a message (`"data": [{"T", "s",
+//! "S", "v", "p"}, ...]`), and on the older `liquidation.<symbol>` one at a
+//! time (`"data": {"symbol", "side", "size", "price", "updatedTime"}`).
+//! Bybit's side is the liquidated position's, so a `Buy` is a long closed by
+//! a forced sell; events always carry the forced order's side.
+
+use axiom_core::{LiquidationEvent, Side, Venue};
+use crate::errors::*;
+use crate::normalization::*;
+use serde_json::Value;
+
+/// Every liquidation in one feed message; messages that carry none (control
+/// traffic, other topics) give an empty list
+pub fn parse_liquidations(raw: &[u8], venue: &Venue, symbols: &SymbolMapper) -> Result<Vec<LiquidationEvent>, IngestionError> {
+    let message: Value = serde_json::from_slice(raw)
+        .map_err(|e| IngestionError::InvalidFormat(format!("JSON parse: {}", e)))?;
+    match venue {
+        Venue::Binance => {
+            // Combined streams wrap the event as {"stream", "data"}
+            let event = message.get("data").unwrap_or(&message);
+            if event.get("e").and_then(Value::as_str) != Some("forceOrder") {
+                return Ok(Vec::new());
+            }
+            normalize_binance_force_order(field(event, "o")?, symbols).map(|event| vec![event])
+        }
+        Venue::Bybit => match message.get("topic").and_then(Value::as_str) {
+            Some(topic) if topic.starts_with("allLiquidation.") => normalize_bybit_liquidations(&message, symbols),
+            Some(topic) if topic.starts_with("liquidation.") => {
+                let data = field(&message, "data")?;
+                let canonical = serde_json::json!({
+                    "s": field(data, "symbol")?,
+                    "S": field(data, "side")?,
+                    "v": field(data, "size")?,
+                    "p": field(data, "price")?,
+                    "T": field(data, "updatedTime")?,
+                });
+                normalize_bybit_liquidation(&canonical, symbols).map(|event| vec![event])
+            }
+            _ => Ok(Vec::new()),
+        },
+        other => Err(IngestionError::InvalidFormat(format!("No liquidation format for {}", other))),
+    }
+}
+
+/// A Binance forceOrder's order object
+pub fn normalize_binance_force_order(order: &Value, symbols: &SymbolMapper) -> Result<LiquidationEvent, IngestionError> {
+    // The average fill price once anything has filled, else the order's limit
+    let price = match order.get("ap").map(normalize_price).transpose()? {
+        Some(average) if !average.is_zero() => average,
+        _ => normalize_price(field(order, "p")?)?,
+    };
+    let quantity = match order.get("z").map(normalize_quantity).transpose()? {
+        Some(filled) if !filled.is_zero() => filled,
+        _ => normalize_quantity(field(order, "q")?)?,
+    };
+    Ok(LiquidationEvent {
+        symbol: symbols.canonical(&Venue::Binance, text(order, "s")?)?,
+        venue: Venue::Binance,
+        side: normalize_side(text(order, "S")?)?,
+        price,
+        quantity,
+        timestamp: normalize_timestamp_in(field(order, "T")?, TimestampUnit::Millis)?,
+    })
+}
+
+/// Every entry in a Bybit allLiquidation message
+pub fn normalize_bybit_liquidations(message: &Value, symbols: &SymbolMapper) -> Result<Vec<LiquidationEvent>, IngestionError> {
+    field(message, "data")?.as_array()
+        .ok_or_else(|| IngestionError::InvalidFormat("Bybit liquidation data is not an array".to_string()))?
+        .iter()
+        .map(|entry| normalize_bybit_liquidation(entry, symbols))
+        .collect()
+}
+
+/// One Bybit liquidation entry, `{"T", "s", "S", "v", "p"}`
+fn normalize_bybit_liquidation(entry: &Value, symbols: &SymbolMapper) -> Result<LiquidationEvent, IngestionError> {
+    let side = match normalize_side(text(entry, "S")?)? {
+        Side::Buy => Side::Sell,
+        Side::Sell => Side::Buy,
+    };
+    Ok(LiquidationEvent {
+        symbol: symbols.canonical(&Venue::Bybit, text(entry, "s")?)?,
+        venue: Venue::Bybit,
+        side,
+        price: normalize_price(field(entry, "p")?)?,
+        quantity: normalize_quantity(field(entry, "v")?)?,
+        timestamp: normalize_timestamp_in(field(entry, "T")?, TimestampUnit::Millis)?,
+    })
+}
+
+fn field<'a>(value: &'a Value, key: &str) -> Result<&'a Value, IngestionError> {
+    value.get(key).ok_or_else(|| IngestionError::InvalidFormat(format!("Liquidation missing {:?}", key)))
+}
+
+fn text<'a>(value: &'a Value, key: &str) -> Result<&'a str, IngestionError> {
+    field(value, key)?.as_str()
+        .ok_or_else(|| IngestionError::InvalidFormat(format!("Liquidation field {:?} is not a string", key)))
+}
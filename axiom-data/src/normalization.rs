@@ -1,58 +1,267 @@
 //! Data Normalization: Deterministic Type Conversion
 //!
 //! All external data (JSON, floats) is normalized into Decimal types
-//! to ensure bitwise determinism.
+//! to ensure bitwise determinism. Numbers are parsed from their decimal
+//! text, never through f64, so the `serde_json::Value` path and the
+//! struct-targeted path (`raw`, feature `fast-ingest`) produce the same values.
+//! Exchange symbol strings are mapped to ours the same way on every path.
+//! Epoch timestamps may be seconds, millis, micros or nanos; a result
+//! outside 2000-2100 (a unit misread) is rejected rather than passed on.
 
-use axiom_core::{Symbol, Venue, Price, Quantity, Tick, OrderBook, BookLevel, Side};
+use axiom_core::constants::{BPS_PER_UNIT, SUPPORTED_PAIRS};
+use axiom_core::{BookLevel, OrderBook, Price, Quantity, Side, Symbol, Venue};
+use crate::errors::*;
 use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::HashMap;
 
-/// Normalize price from external format to Decimal
-pub fn normalize_price(value: &Value) -> Result<Price, NormalizationError> {
-    match value {
-        Value::String(s) => {
-            Decimal::from_str_exact(s)
-                .or_else(|_| s.parse::<f64>()
-                    .map(|f| Decimal::try_from(f).unwrap_or(Decimal::ZERO))
-                    .map_err(|_| NormalizationError::ParseError("Failed to parse price".to_string())))
-                .map_err(|e| NormalizationError::ParseError(format!("Price: {}", e)))
+/// Exchange symbol strings to ours and back, per venue
+///
+/// Venues name one market differently ("BTCUSDT", "BTC-USD", "BTC/USD:USD");
+/// past ingestion only our form ("BTC/USD") is seen. A string with no
+/// mapping is an error, never passed through, so a market the limits do not
+/// know cannot get in under a name they would miss.
+#[derive(Debug, Clone, Default)]
+pub struct SymbolMapper {
+    ours: HashMap<Venue, HashMap<String, Symbol>>,
+    /// The first exchange string added for each of ours
+    exchange: HashMap<Venue, HashMap<Symbol, String>>,
+}
+
+impl SymbolMapper {
+    /// No mappings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The supported pairs on every built-in venue: Binance and Bybit USDT
+    /// markets ("BTCUSDT", standing in for USD), Hyperliquid coins ("BTC"),
+    /// Kraken ("BTC/USD", as "custom:kraken") and the paper venue, which
+    /// uses our names
+    pub fn builtin() -> Self {
+        let mut mapper = Self::new();
+        for pair in SUPPORTED_PAIRS {
+            let symbol = Symbol(pair.to_string());
+            let base = pair.split('/').next().unwrap_or(pair);
+            mapper.insert(&Venue::Binance, format!("{}USDT", base), symbol.clone());
+            mapper.insert(&Venue::Bybit, format!("{}USDT", base), symbol.clone());
+            mapper.insert(&Venue::Hyperliquid, base, symbol.clone());
+            mapper.insert(&Venue::Custom("kraken".to_string()), *pair, symbol.clone());
+            mapper.insert(&Venue::Paper, *pair, symbol);
         }
-        Value::Number(n) => {
-            n.as_f64()
-                .ok_or_else(|| NormalizationError::InvalidType("Number not convertible to f64".to_string()))
-                .and_then(|f| {
-                    Decimal::try_from(f)
-                        .map_err(|e| NormalizationError::ParseError(format!("Decimal conversion: {}", e)))
-                })
+        mapper
+    }
+
+    /// Map `exchange` on `venue` to `symbol`, replacing any mapping it had;
+    /// the first string added for a symbol stays the one sent to the venue
+    pub fn insert(&mut self, venue: &Venue, exchange: impl Into<String>, symbol: Symbol) {
+        let exchange = exchange.into();
+        self.exchange.entry(venue.clone()).or_default()
+            .entry(symbol.clone())
+            .or_insert_with(|| exchange.clone());
+        self.ours.entry(venue.clone()).or_default().insert(exchange, symbol);
+    }
+
+    /// Add `(exchange string, ours)` mappings for `venue`, from config or at runtime
+    pub fn extend(&mut self, venue: &Venue, entries: impl IntoIterator<Item = (String, Symbol)>) {
+        for (exchange, symbol) in entries {
+            self.insert(venue, exchange, symbol);
         }
+    }
+
+    /// Our symbol for `exchange` on `venue`
+    ///
+    /// A string already in our form, for a symbol the venue maps, is taken
+    /// as is, so recorded captures read back unchanged.
+    pub fn canonical(&self, venue: &Venue, exchange: &str) -> Result<Symbol, NormalizationError> {
+        if let Some(symbol) = self.ours.get(venue).and_then(|ours| ours.get(exchange)) {
+            return Ok(symbol.clone());
+        }
+        let symbol = Symbol(exchange.to_string());
+        if self.exchange.get(venue).is_some_and(|mapped| mapped.contains_key(&symbol)) {
+            return Ok(symbol);
+        }
+        Err(NormalizationError::UnknownSymbol { venue: venue.to_string(), symbol: exchange.to_string() })
+    }
+
+    /// `venue`'s string for `symbol`
+    pub fn exchange(&self, venue: &Venue, symbol: &Symbol) -> Result<&str, NormalizationError> {
+        self.exchange.get(venue)
+            .and_then(|mapped| mapped.get(symbol))
+            .map(String::as_str)
+            .ok_or_else(|| NormalizationError::UnknownSymbol { venue: venue.to_string(), symbol: symbol.0.clone() })
+    }
+}
+
+/// Parse decimal text exactly (plain or scientific notation)
+pub fn parse_decimal(text: &str) -> Result<Decimal, NormalizationError> {
+    Decimal::from_str_exact(text)
+        .or_else(|_| Decimal::from_scientific(text))
+        .map_err(|e| NormalizationError::ParseError(format!("Decimal {:?}: {}", text, e)))
+}
+
+/// Normalize a decimal value from external format (string or number)
+fn normalize_decimal(value: &Value) -> Result<Decimal, NormalizationError> {
+    match value {
+        Value::String(s) => parse_decimal(s),
+        // A JSON number's text is its shortest round-trip form
+        Value::Number(n) => parse_decimal(&n.to_string()),
         _ => Err(NormalizationError::InvalidType("Expected string or number".to_string())),
     }
 }
 
-/// Normalize quantity from external format to Decimal
+/// Normalize price from external format to Price (rejects negatives)
+pub fn normalize_price(value: &Value) -> Result<Price, NormalizationError> {
+    price_from(normalize_decimal(value)?)
+}
+
+/// Normalize quantity from external format to Quantity (rejects negatives)
 pub fn normalize_quantity(value: &Value) -> Result<Quantity, NormalizationError> {
-    normalize_price(value) // Same logic as price
+    quantity_from(normalize_decimal(value)?)
+}
+
+/// Normalize a price given as decimal text
+pub fn normalize_price_str(text: &str) -> Result<Price, NormalizationError> {
+    price_from(parse_decimal(text)?)
+}
+
+/// Normalize a quantity given as decimal text
+pub fn normalize_quantity_str(text: &str) -> Result<Quantity, NormalizationError> {
+    quantity_from(parse_decimal(text)?)
+}
+
+fn price_from(value: Decimal) -> Result<Price, NormalizationError> {
+    Price::new(value).map_err(|e| NormalizationError::ParseError(e.to_string()))
+}
+
+fn quantity_from(value: Decimal) -> Result<Quantity, NormalizationError> {
+    Quantity::new(value).map_err(|e| NormalizationError::ParseError(e.to_string()))
+}
+
+/// 2000-01-01T00:00:00Z, the earliest timestamp accepted (Unix seconds)
+const EARLIEST_TIMESTAMP: i64 = 946_684_800;
+
+/// 2100-01-01T00:00:00Z, past the latest timestamp accepted (Unix seconds)
+const LATEST_TIMESTAMP: i64 = 4_102_444_800;
+
+/// How a venue writes timestamps
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimestampUnit {
+    /// Seconds since the epoch; may be fractional ("1534614057.321597")
+    Seconds,
+    #[default]
+    Millis,
+    Micros,
+    Nanos,
+    /// RFC 3339 text
+    Rfc3339,
+}
+
+impl TimestampUnit {
+    /// The unit an epoch timestamp between 2000 and 2100 is in, by its
+    /// magnitude; each unit's span there is three orders of magnitude from
+    /// the next, so the boundaries sit between them
+    pub fn detect(value: Decimal) -> TimestampUnit {
+        let magnitude = value.abs();
+        if magnitude < Decimal::from(100_000_000_000_i64) {
+            TimestampUnit::Seconds
+        } else if magnitude < Decimal::from(100_000_000_000_000_i64) {
+            TimestampUnit::Millis
+        } else if magnitude < Decimal::from(100_000_000_000_000_000_i64) {
+            TimestampUnit::Micros
+        } else {
+            TimestampUnit::Nanos
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TimestampUnit::Seconds => "seconds",
+            TimestampUnit::Millis => "millis",
+            TimestampUnit::Micros => "micros",
+            TimestampUnit::Nanos => "nanos",
+            TimestampUnit::Rfc3339 => "rfc3339",
+        }
+    }
+
+    fn nanos_per_unit(&self) -> i64 {
+        match self {
+            TimestampUnit::Seconds => 1_000_000_000,
+            TimestampUnit::Millis => 1_000_000,
+            TimestampUnit::Micros => 1_000,
+            TimestampUnit::Nanos | TimestampUnit::Rfc3339 => 1,
+        }
+    }
+}
+
+impl std::fmt::Display for TimestampUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
 
-/// Normalize timestamp from various formats
+/// Normalize a timestamp: RFC 3339 text, or an epoch count (number or
+/// numeric string) whose unit is told by its magnitude
 pub fn normalize_timestamp(value: &Value) -> Result<DateTime<Utc>, NormalizationError> {
     match value {
-        Value::Number(n) => {
-            let ts = n.as_i64()
-                .ok_or_else(|| NormalizationError::InvalidType("Timestamp not i64".to_string()))?;
-            DateTime::from_timestamp(ts / 1000, ((ts % 1000) * 1_000_000) as u32)
-                .ok_or_else(|| NormalizationError::InvalidType("Invalid timestamp".to_string()))
-        }
-        Value::String(s) => {
-            DateTime::parse_from_rfc3339(s)
-                .map(|dt| dt.with_timezone(&Utc))
-                .map_err(|e| NormalizationError::ParseError(format!("RFC3339 parse: {}", e)))
+        Value::String(s) if parse_decimal(s).is_err() => timestamp_from_rfc3339(s),
+        Value::Number(_) | Value::String(_) => {
+            let amount = normalize_decimal(value)?;
+            timestamp_from_epoch(amount, TimestampUnit::detect(amount))
         }
         _ => Err(NormalizationError::InvalidType("Expected number or RFC3339 string".to_string())),
     }
 }
 
+/// Normalize a timestamp written in `unit`
+pub fn normalize_timestamp_in(value: &Value, unit: TimestampUnit) -> Result<DateTime<Utc>, NormalizationError> {
+    if unit == TimestampUnit::Rfc3339 {
+        let text = value.as_str()
+            .ok_or_else(|| NormalizationError::InvalidType("Expected an RFC 3339 string".to_string()))?;
+        return timestamp_from_rfc3339(text);
+    }
+    timestamp_from_epoch(normalize_decimal(value)?, unit)
+}
+
+/// `amount` `unit`s since the epoch, exact to the nanosecond (finer digits
+/// are truncated)
+pub fn timestamp_from_epoch(amount: Decimal, unit: TimestampUnit) -> Result<DateTime<Utc>, NormalizationError> {
+    let out_of_range = || NormalizationError::TimestampOutOfRange {
+        value: amount.to_string(),
+        unit: unit.to_string(),
+        at: "out of range".to_string(),
+    };
+    let nanos = amount.checked_mul(Decimal::from(unit.nanos_per_unit()))
+        .and_then(|nanos| nanos.trunc().to_i64())
+        .ok_or_else(out_of_range)?;
+    within_window(DateTime::from_timestamp_nanos(nanos), &amount.to_string(), unit)
+}
+
+/// Milliseconds since the Unix epoch
+pub fn timestamp_from_millis(ts: i64) -> Result<DateTime<Utc>, NormalizationError> {
+    timestamp_from_epoch(Decimal::from(ts), TimestampUnit::Millis)
+}
+
+/// `at` when it falls between 2000 and 2100; anything else is a misread
+/// unit or a broken clock
+fn within_window(at: DateTime<Utc>, value: &str, unit: TimestampUnit) -> Result<DateTime<Utc>, NormalizationError> {
+    if (EARLIEST_TIMESTAMP..LATEST_TIMESTAMP).contains(&at.timestamp()) {
+        return Ok(at);
+    }
+    Err(NormalizationError::TimestampOutOfRange { value: value.to_string(), unit: unit.to_string(), at: at.to_rfc3339() })
+}
+
+pub fn timestamp_from_rfc3339(text: &str) -> Result<DateTime<Utc>, NormalizationError> {
+    let at = DateTime::parse_from_rfc3339(text)
+        .map(|dt| dt.with_timezone(&Utc))
+        .map_err(|e| NormalizationError::ParseError(format!("RFC3339 parse: {}", e)))?;
+    within_window(at, text, TimestampUnit::Rfc3339)
+}
+
 /// Normalize side from string
 pub fn normalize_side(value: &str) -> Result<Side, NormalizationError> {
     match value.to_uppercase().as_str() {
@@ -64,12 +273,68 @@ pub fn normalize_side(value: &str) -> Result<Side, NormalizationError> {
 
 /// Calculate depth imbalance (deterministic)
 pub fn calculate_depth_imbalance(book: &OrderBook) -> Decimal {
-    let bid_volume: Decimal = book.bids.iter()
-        .map(|level| level.quantity)
+    imbalance(&book.bids, &book.asks)
+}
+
+/// Depth imbalance over the best `levels` levels of each side
+pub fn calculate_depth_imbalance_levels(book: &OrderBook, levels: usize) -> Decimal {
+    imbalance(&book.bids[..levels.min(book.bids.len())], &book.asks[..levels.min(book.asks.len())])
+}
+
+/// Depth imbalance over the levels within `bps` basis points of the mid;
+/// zero for a book without both sides, which has no mid to measure from
+pub fn calculate_depth_imbalance_within_bps(book: &OrderBook, bps: Decimal) -> Decimal {
+    match depth_within_bps(book, bps) {
+        Some((bids, asks)) => imbalance(bids, asks),
+        None => Decimal::ZERO,
+    }
+}
+
+/// Each side's levels within `bps` basis points of the mid, best first;
+/// None without a mid
+pub fn depth_within_bps(book: &OrderBook, bps: Decimal) -> Option<(&[BookLevel], &[BookLevel])> {
+    let mid = calculate_mid_price(book)?.get();
+    let reach = mid.checked_mul(bps)?.checked_div(BPS_PER_UNIT)?;
+    let (floor, ceiling) = (mid.checked_sub(reach)?, mid.checked_add(reach)?);
+    let bids = book.bids.iter().take_while(|level| level.price.get() >= floor).count();
+    let asks = book.asks.iter().take_while(|level| level.price.get() <= ceiling).count();
+    Some((&book.bids[..bids], &book.asks[..asks]))
+}
+
+/// How fast `side`'s cumulative size grows with distance from the mid over
+/// its best `levels` levels: the least-squares slope through the origin of
+/// cumulative quantity against distance in basis points, so size per basis
+/// point. None without a mid, with no level off the mid, or on overflow
+pub fn calculate_book_slope(book: &OrderBook, side: Side, levels: usize) -> Option<Decimal> {
+    let mid = calculate_mid_price(book)?.get();
+    if mid.is_zero() {
+        return None;
+    }
+    let book_side = match side {
+        Side::Buy => &book.bids,
+        Side::Sell => &book.asks,
+    };
+    let (mut cumulative, mut covariance, mut variance) = (Decimal::ZERO, Decimal::ZERO, Decimal::ZERO);
+    for level in book_side.iter().take(levels) {
+        cumulative = cumulative.checked_add(level.quantity.get())?;
+        let distance = level.price.get().checked_sub(mid)?.abs().checked_div(mid)?.checked_mul(BPS_PER_UNIT)?;
+        covariance = covariance.checked_add(distance.checked_mul(cumulative)?)?;
+        variance = variance.checked_add(distance.checked_mul(distance)?)?;
+    }
+    if variance.is_zero() {
+        return None;
+    }
+    covariance.checked_div(variance)
+}
+
+/// (bid volume - ask volume) / total volume; zero for no volume
+fn imbalance(bids: &[BookLevel], asks: &[BookLevel]) -> Decimal {
+    let bid_volume: Decimal = bids.iter()
+        .map(|level| level.quantity.get())
         .sum();
-    
-    let ask_volume: Decimal = book.asks.iter()
-        .map(|level| level.quantity)
+
+    let ask_volume: Decimal = asks.iter()
+        .map(|level| level.quantity.get())
         .sum();
 
     if bid_volume + ask_volume == Decimal::ZERO {
@@ -84,7 +349,43 @@ pub fn calculate_mid_price(book: &OrderBook) -> Option<Price> {
     let best_bid = book.bids.first()?;
     let best_ask = book.asks.first()?;
     
-    Some((best_bid.price + best_ask.price) / Decimal::from(2))
+    best_bid.price.midpoint(best_ask.price).ok()
+}
+
+/// Microprice: the touch prices weighted by the opposite side's size,
+/// (bid_px * ask_qty + ask_px * bid_qty) / (bid_qty + ask_qty), which leans
+/// toward the side more likely to trade through; the mid when a touch has
+/// no size, None without both sides
+pub fn calculate_microprice(book: &OrderBook) -> Option<Price> {
+    calculate_weighted_mid(book, 1)
+}
+
+/// Microprice over the best `levels` levels: each side's size-weighted
+/// price, weighted by the other side's total size; the mid when a side
+/// has no size, None without both sides or for zero levels
+pub fn calculate_weighted_mid(book: &OrderBook, levels: usize) -> Option<Price> {
+    let side = |book_side: &[BookLevel]| -> Option<(Decimal, Decimal)> {
+        let (mut notional, mut size) = (Decimal::ZERO, Decimal::ZERO);
+        for level in book_side.iter().take(levels) {
+            notional = notional.checked_add(level.price.get().checked_mul(level.quantity.get())?)?;
+            size = size.checked_add(level.quantity.get())?;
+        }
+        Some((notional, size))
+    };
+    if levels == 0 {
+        return None;
+    }
+    let mid = calculate_mid_price(book)?;
+    let (bid_notional, bid_size) = side(&book.bids)?;
+    let (ask_notional, ask_size) = side(&book.asks)?;
+    if bid_size.is_zero() || ask_size.is_zero() {
+        return Some(mid);
+    }
+    let total = bid_size.checked_add(ask_size)?;
+    let bid_price = bid_notional.checked_div(bid_size)?;
+    let ask_price = ask_notional.checked_div(ask_size)?;
+    let weighted = bid_price.checked_mul(ask_size)?.checked_add(ask_price.checked_mul(bid_size)?)?.checked_div(total)?;
+    Price::new(weighted).ok()
 }
 
 /// Calculate spread (deterministic)
@@ -92,7 +393,7 @@ pub fn calculate_spread(book: &OrderBook) -> Option<Decimal> {
     let best_bid = book.bids.first()?;
     let best_ask = book.asks.first()?;
     
-    Some(best_ask.price - best_bid.price)
+    Some(best_ask.price.delta(best_bid.price))
 }
 
 /// Calculate spread percentage (deterministic)
@@ -100,10 +401,10 @@ pub fn calculate_spread_pct(book: &OrderBook) -> Option<Decimal> {
     let spread = calculate_spread(book)?;
     let mid = calculate_mid_price(book)?;
     
-    if mid == Decimal::ZERO {
+    if mid.is_zero() {
         return None;
     }
     
-    Some((spread / mid) * Decimal::from(100))
+    Some((spread / mid.get()) * Decimal::from(100))
 }
 
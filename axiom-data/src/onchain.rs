@@ -5,32 +5,93 @@
 
 use axiom_core::{Symbol, Venue};
 use crate::errors::*;
+use crate::rpc::{EndpointReport, HealthPolicy};
+use crate::solana::{PoolLiquidity, PoolRegistry, SolanaRpc};
+use crate::venues::HyperliquidInfo;
+use std::time::Duration;
 use tracing::info;
 
 /// On-chain data fetcher
 pub struct OnChainFetcher {
-    rpc_endpoints: Vec<String>,
+    solana: SolanaRpc,
+    pools: PoolRegistry,
+    hyperliquid: Option<HyperliquidInfo>,
 }
 
 impl OnChainFetcher {
-    pub fn new(rpc_endpoints: Vec<String>) -> Self {
-        Self { rpc_endpoints }
+    /// Read Solana through `rpc_endpoints` (the first preferred until
+    /// latencies are known) for the symbols `pools` has a pool for
+    pub fn new(rpc_endpoints: Vec<String>, pools: PoolRegistry) -> Self {
+        Self { solana: SolanaRpc::new(rpc_endpoints), pools, hyperliquid: None }
+    }
+
+    /// Give each Solana RPC endpoint `timeout` before failing over
+    pub fn with_rpc_timeout(mut self, timeout: Duration) -> Self {
+        self.solana = self.solana.with_timeout(timeout);
+        self
+    }
+
+    /// Demote and re-promote Solana RPC endpoints by `policy`
+    pub fn with_rpc_health_policy(mut self, policy: HealthPolicy) -> Self {
+        self.solana = self.solana.with_health_policy(policy);
+        self
+    }
+
+    /// Each Solana RPC endpoint's latency, failures and standing
+    pub fn endpoint_health(&self) -> Vec<EndpointReport> {
+        self.solana.endpoint_health()
+    }
+
+    /// Read Hyperliquid symbols' open interest and funding through `info`
+    pub fn with_hyperliquid(mut self, info: HyperliquidInfo) -> Self {
+        self.hyperliquid = Some(info);
+        self
+    }
+
+    /// `symbol`'s Solana pool reserves and liquidity in its quote token
+    pub async fn pool_liquidity(&self, symbol: &Symbol) -> Result<PoolLiquidity, IngestionError> {
+        let pool = self.pools.get(symbol)
+            .ok_or_else(|| IngestionError::ExchangeApi(format!("No Solana pool registered for {}", symbol.0)))?;
+        self.solana.pool_liquidity(symbol, pool).await
     }
 
     /// Fetch liquidity data from on-chain sources
+    ///
+    /// For a symbol Hyperliquid lists, liquidity is its open interest at the
+    /// mark price, alongside the open interest and next funding payment. For
+    /// one with a registered Solana pool, it is the pool's reserves valued
+    /// in the quote token. Network means every RPC endpoint failed.
     pub async fn fetch_liquidity(&self, symbol: &Symbol) -> Result<serde_json::Value, IngestionError> {
         info!("Fetching on-chain liquidity for {}", symbol.0);
-        
-        // Placeholder: In production, this would:
-        // 1. Connect to Solana/Hyperliquid RPC
-        // 2. Query liquidity pools
-        // 3. Return normalized data
-        
+
+        if let Some(hyperliquid) = &self.hyperliquid {
+            if let Some(state) = hyperliquid.market_state(symbol).await? {
+                let liquidity = state.open_interest_notional()
+                    .map_err(|e| IngestionError::InvalidFormat(format!("Open interest of {}: {}", symbol.0, e)))?;
+                return Ok(serde_json::json!({
+                    "symbol": symbol.0,
+                    "source": Venue::Hyperliquid.to_string(),
+                    "liquidity": liquidity.to_string(),
+                    "open_interest": state.open_interest.to_string(),
+                    "mark_price": state.mark_price.to_string(),
+                    "funding_rate": state.funding.rate.to_string(),
+                    "next_funding": state.funding.next_funding.to_rfc3339(),
+                    "timestamp": state.observed_at.to_rfc3339()
+                }));
+            }
+        }
+
+        let pool = self.pool_liquidity(symbol).await?;
         Ok(serde_json::json!({
             "symbol": symbol.0,
-            "liquidity": "0",
+            "source": pool.pool.dex.to_string(),
+            "pool": pool.pool.address,
+            "liquidity": pool.liquidity.to_string(),
+            "base_reserve": pool.base_reserve.to_string(),
+            "quote_reserve": pool.quote_reserve.to_string(),
+            "price": pool.price.to_string(),
+            "slot": pool.slot,
             "timestamp": chrono::Utc::now().to_rfc3339()
         }))
     }
 }
-
@@ -1,32 +1,99 @@
 //! Order Book Management: L2/L3 Reconstruction
 //!
 //! Handles full-depth order book reconstruction and maintenance
-//! with deterministic calculations.
+//! with deterministic calculations. Every update yields a new shared book;
+//! a side the update does not touch is shared with the previous book rather
+//! than copied.
+//!
+//! Exchanges number their incremental updates. The builder tracks the id
+//! its book is at and refuses an update that skips ahead of it; after such
+//! a gap the book needs a fresh snapshot, and updates are held until one
+//! arrives. This is Binance's depth-sync procedure: updates covering ids
+//! up to the snapshot's are dropped, and the first one applied must
+//! straddle it.
 
-use axiom_core::{Symbol, Venue, OrderBook, BookLevel};
+use axiom_core::{Symbol, Venue, OrderBook, BookLevel, LatencyRecorder, LatencyStage, Price, Quantity};
 use crate::normalization::*;
 use crate::errors::*;
-use rust_decimal::Decimal;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use std::cmp::Reverse;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Updates held while waiting for a snapshot, oldest dropped first
+const MAX_PENDING_UPDATES: usize = 1024;
+
+/// Exchange update ids an incremental update covers (Binance's `U` to `u`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UpdateIds {
+    pub first: u64,
+    pub last: u64,
+}
+
+impl UpdateIds {
+    pub fn new(first: u64, last: u64) -> Self {
+        Self { first, last }
+    }
+
+    /// An update with one id (Bybit's `u`)
+    pub fn single(id: u64) -> Self {
+        Self { first: id, last: id }
+    }
+}
 
 /// Order book builder and maintainer
 pub struct OrderBookBuilder {
     symbol: Symbol,
     venue: Venue,
     sequence: u64,
+    /// Exchange update id the book is at; None until a sequenced snapshot
+    /// or the first update after an unsequenced one
+    update_id: Option<u64>,
+    /// A gap was seen; updates are held until the next snapshot
+    needs_resync: bool,
+    pending: VecDeque<(UpdateIds, serde_json::Value)>,
+    latency: Option<Arc<dyn LatencyRecorder>>,
 }
 
 impl OrderBookBuilder {
-    pub fn new(symbol: Symbol, venue: Venue) -> Self {
-        Self {
-            symbol,
+    /// A builder for `exchange_symbol`'s book on `venue`; its books carry
+    /// our symbol for it
+    pub fn new(symbols: &SymbolMapper, venue: Venue, exchange_symbol: &str) -> Result<Self, NormalizationError> {
+        Ok(Self {
+            symbol: symbols.canonical(&venue, exchange_symbol)?,
             venue,
             sequence: 0,
-        }
+            update_id: None,
+            needs_resync: false,
+            pending: VecDeque::new(),
+            latency: None,
+        })
     }
 
-    /// Build order book from snapshot
-    pub fn from_snapshot(&mut self, snapshot: &serde_json::Value) -> Result<OrderBook, IngestionError> {
+    /// Report book_apply latency
+    pub fn with_latency_recorder(mut self, latency: Arc<dyn LatencyRecorder>) -> Self {
+        self.latency = Some(latency);
+        self
+    }
+
+    /// Whether a gap left the book out of date; send for a snapshot and
+    /// pass it to `from_sequenced_snapshot`
+    pub fn needs_resync(&self) -> bool {
+        self.needs_resync
+    }
+
+    /// Exchange update id the book is at
+    pub fn update_id(&self) -> Option<u64> {
+        self.update_id
+    }
+
+    /// Build order book from snapshot, stamped `timestamp` (the receive
+    /// time for a live feed, the recorded time for a replay)
+    ///
+    /// The snapshot carries no update id, so the next update is taken as
+    /// the start of the sequence. Held updates are discarded.
+    pub fn from_snapshot(&mut self, snapshot: &serde_json::Value, timestamp: DateTime<Utc>) -> Result<OrderBook, IngestionError> {
         let bids = self.parse_levels(
             snapshot.get("bids")
                 .ok_or_else(|| IngestionError::InvalidFormat("Missing bids".to_string()))?
@@ -39,33 +106,146 @@ impl OrderBookBuilder {
 
         // Sort: bids descending, asks ascending
         let mut bids = bids;
-        bids.sort_by(|a, b| b.price.cmp(&a.price));
+        bids.sort_by_key(|level| Reverse(level.price));
 
         let mut asks = asks;
-        asks.sort_by(|a, b| a.price.cmp(&b.price));
+        asks.sort_by_key(|level| level.price);
 
         self.sequence += 1;
+        self.update_id = None;
+        self.needs_resync = false;
+        self.pending.clear();
 
         Ok(OrderBook {
             symbol: self.symbol.clone(),
             venue: self.venue.clone(),
+            bids: Arc::new(bids),
+            asks: Arc::new(asks),
+            timestamp,
+            sequence: self.sequence,
+        })
+    }
+
+    /// Build order book from a snapshot at exchange update `update_id`,
+    /// then replay the updates held since a gap that are newer than it
+    ///
+    /// Should the held updates themselves have a gap, the book is returned
+    /// as far as they reach and `needs_resync` is set again.
+    pub fn from_sequenced_snapshot(
+        &mut self,
+        snapshot: &serde_json::Value,
+        update_id: u64,
+        timestamp: DateTime<Utc>,
+    ) -> Result<Arc<OrderBook>, IngestionError> {
+        let pending = std::mem::take(&mut self.pending);
+        let mut book = Arc::new(self.from_snapshot(snapshot, timestamp)?);
+        self.update_id = Some(update_id);
+
+        for (ids, update) in pending {
+            if ids.last <= update_id {
+                continue;
+            }
+            match self.apply_update(&book, &update, ids) {
+                Ok(Some(next)) => book = next,
+                // After a gap the rest are held again
+                Ok(None) | Err(IngestionError::SequenceGap { .. }) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(book)
+    }
+
+    /// Apply an incremental update, returning the next book
+    ///
+    /// `update` carries optional `bids` / `asks` arrays of `[price, quantity]`
+    /// levels; a zero quantity removes the level. A side the update leaves
+    /// out is shared with `book`. (Venue formats are normalized to this shape
+    /// before they get here.)
+    ///
+    /// `ids` must reach past the book's update id without skipping any:
+    /// an update wholly at or before it is a duplicate or stale, and one
+    /// starting beyond the next id is a gap. Both are `SequenceGap`; a gap
+    /// also sets `needs_resync`, after which updates are held (`Ok(None)`)
+    /// until `from_sequenced_snapshot`.
+    pub fn apply_update(&mut self, book: &Arc<OrderBook>, update: &serde_json::Value, ids: UpdateIds) -> Result<Option<Arc<OrderBook>>, IngestionError> {
+        if ids.first > ids.last {
+            return Err(IngestionError::InvalidFormat(format!("Update ids {} to {} run backwards", ids.first, ids.last)));
+        }
+        if self.needs_resync {
+            self.hold(ids, update);
+            return Ok(None);
+        }
+        if let Some(at) = self.update_id {
+            let expected = at.saturating_add(1);
+            if ids.last <= at {
+                return Err(IngestionError::SequenceGap { expected, got: ids.last });
+            }
+            if ids.first > expected {
+                self.needs_resync = true;
+                self.hold(ids, update);
+                return Err(IngestionError::SequenceGap { expected, got: ids.first });
+            }
+        }
+
+        let start = Instant::now();
+
+        let bids = match update.get("bids") {
+            Some(levels) => self.merge_levels(&book.bids, levels, |a, b| b.cmp(a))?,
+            None => book.bids.clone(),
+        };
+        let asks = match update.get("asks") {
+            Some(levels) => self.merge_levels(&book.asks, levels, |a, b| a.cmp(b))?,
+            None => book.asks.clone(),
+        };
+
+        self.sequence += 1;
+        self.update_id = Some(ids.last);
+        let next = Arc::new(OrderBook {
+            symbol: book.symbol.clone(),
+            venue: book.venue.clone(),
             bids,
             asks,
             timestamp: Utc::now(),
             sequence: self.sequence,
-        })
+        });
+
+        if let Some(latency) = &self.latency {
+            latency.record(LatencyStage::BookApply, &self.venue, start.elapsed());
+        }
+
+        Ok(Some(next))
     }
 
-    /// Update order book with incremental update
-    pub fn apply_update(&mut self, book: &mut OrderBook, update: &serde_json::Value) -> Result<(), IngestionError> {
-        // Handle incremental updates (add/remove/update levels)
-        // This is exchange-specific, so simplified here
-        
-        self.sequence += 1;
-        book.sequence = self.sequence;
-        book.timestamp = Utc::now();
+    fn hold(&mut self, ids: UpdateIds, update: &serde_json::Value) {
+        if self.pending.len() == MAX_PENDING_UPDATES {
+            self.pending.pop_front();
+        }
+        self.pending.push_back((ids, update.clone()));
+    }
+
+    /// One side with `changes` applied, kept in `order`
+    fn merge_levels(
+        &self,
+        side: &[BookLevel],
+        changes: &serde_json::Value,
+        order: impl Fn(&Price, &Price) -> std::cmp::Ordering,
+    ) -> Result<Arc<Vec<BookLevel>>, IngestionError> {
+        let array = changes.as_array()
+            .ok_or_else(|| IngestionError::InvalidFormat("Levels not an array".to_string()))?;
 
-        Ok(())
+        let mut levels = side.to_vec();
+        for change in array {
+            let (price, quantity) = self.parse_level(change)?;
+            match levels.binary_search_by(|level| order(&level.price, &price)) {
+                Ok(index) if quantity.is_zero() => {
+                    levels.remove(index);
+                }
+                Ok(index) => levels[index].quantity = quantity,
+                Err(_) if quantity.is_zero() => {}
+                Err(index) => levels.insert(index, BookLevel { price, quantity }),
+            }
+        }
+        Ok(Arc::new(levels))
     }
 
     fn parse_levels(&self, levels: &serde_json::Value) -> Result<Vec<BookLevel>, IngestionError> {
@@ -74,22 +254,130 @@ impl OrderBookBuilder {
 
         let mut result = Vec::new();
         for level in array {
-            let price = normalize_price(
-                level.get(0)
-                    .ok_or_else(|| IngestionError::InvalidFormat("Missing price in level".to_string()))?
-            )?;
-
-            let quantity = normalize_quantity(
-                level.get(1)
-                    .ok_or_else(|| IngestionError::InvalidFormat("Missing quantity in level".to_string()))?
-            )?;
-
-            if quantity > Decimal::ZERO {
+            let (price, quantity) = self.parse_level(level)?;
+            if !quantity.is_zero() {
                 result.push(BookLevel { price, quantity });
             }
         }
 
         Ok(result)
     }
+
+    fn parse_level(&self, level: &serde_json::Value) -> Result<(Price, Quantity), IngestionError> {
+        let price = normalize_price(
+            level.get(0)
+                .ok_or_else(|| IngestionError::InvalidFormat("Missing price in level".to_string()))?
+        )?;
+
+        let quantity = normalize_quantity(
+            level.get(1)
+                .ok_or_else(|| IngestionError::InvalidFormat("Missing quantity in level".to_string()))?
+        )?;
+
+        Ok((price, quantity))
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = include_str!("../tests/fixtures/btcusdt_depth.json");
+
+    fn builder() -> OrderBookBuilder {
+        OrderBookBuilder::new(&SymbolMapper::builtin(), Venue::Binance, "BTCUSDT").unwrap()
+    }
+
+    fn levels(side: &[BookLevel]) -> Vec<(Price, Quantity)> {
+        side.iter().map(|level| (level.price, level.quantity)).collect()
+    }
+
+    #[test]
+    fn snapshot_plus_deltas_matches_later_snapshot() {
+        let fixture: serde_json::Value = serde_json::from_str(FIXTURE).unwrap();
+        let mut builder = builder();
+        let snapshot = &fixture["snapshot"];
+        let mut book = builder
+            .from_sequenced_snapshot(snapshot, snapshot["lastUpdateId"].as_u64().unwrap(), Utc::now())
+            .unwrap();
+        for delta in fixture["deltas"].as_array().unwrap() {
+            let ids = UpdateIds::new(delta["U"].as_u64().unwrap(), delta["u"].as_u64().unwrap());
+            book = builder.apply_update(&book, delta, ids).unwrap().unwrap();
+        }
+
+        let expected = self::builder().from_snapshot(&fixture["later_snapshot"], Utc::now()).unwrap();
+        assert_eq!(builder.update_id(), fixture["later_snapshot"]["lastUpdateId"].as_u64());
+        assert_eq!(levels(&book.bids), levels(&expected.bids));
+        assert_eq!(levels(&book.asks), levels(&expected.asks));
+    }
+
+    #[test]
+    fn untouched_side_is_shared() {
+        let fixture: serde_json::Value = serde_json::from_str(FIXTURE).unwrap();
+        let mut builder = builder();
+        let book = builder.from_sequenced_snapshot(&fixture["snapshot"], 1000, Utc::now()).unwrap();
+
+        let bids_only = serde_json::json!({ "bids": [["64990.50", "0.100"]] });
+        let next = builder.apply_update(&book, &bids_only, UpdateIds::single(1001)).unwrap().unwrap();
+
+        assert!(Arc::ptr_eq(&book.asks, &next.asks));
+        assert!(!Arc::ptr_eq(&book.bids, &next.bids));
+    }
+
+    #[test]
+    fn gap_holds_updates_until_the_next_snapshot() {
+        let fixture: serde_json::Value = serde_json::from_str(FIXTURE).unwrap();
+        let mut builder = builder();
+        let book = builder.from_sequenced_snapshot(&fixture["snapshot"], 1000, Utc::now()).unwrap();
+        let bid = serde_json::json!({ "bids": [["64990.50", "0.100"]] });
+
+        // 1001 never arrived
+        match builder.apply_update(&book, &bid, UpdateIds::single(1002)) {
+            Err(IngestionError::SequenceGap { expected: 1001, got: 1002 }) => {}
+            other => panic!("expected a gap, got {:?}", other),
+        }
+        assert!(builder.needs_resync());
+        assert!(builder.apply_update(&book, &bid, UpdateIds::single(1003)).unwrap().is_none());
+
+        // The snapshot covers 1002; the held 1003 is replayed on top of it
+        let book = builder.from_sequenced_snapshot(&fixture["snapshot"], 1002, Utc::now()).unwrap();
+        assert!(!builder.needs_resync());
+        assert_eq!(builder.update_id(), Some(1003));
+        assert_eq!(book.bids[0].price, Price::new("64990.50".parse().unwrap()).unwrap());
+    }
+
+    #[test]
+    fn duplicate_update_is_refused_without_a_resync() {
+        let fixture: serde_json::Value = serde_json::from_str(FIXTURE).unwrap();
+        let mut builder = builder();
+        let book = builder.from_sequenced_snapshot(&fixture["snapshot"], 1000, Utc::now()).unwrap();
+        let bid = serde_json::json!({ "bids": [["64990.50", "0.100"]] });
+        let book = builder.apply_update(&book, &bid, UpdateIds::single(1001)).unwrap().unwrap();
+
+        match builder.apply_update(&book, &bid, UpdateIds::single(1001)) {
+            Err(IngestionError::SequenceGap { expected: 1002, got: 1001 }) => {}
+            other => panic!("expected a duplicate, got {:?}", other),
+        }
+        assert!(!builder.needs_resync());
+        assert_eq!(builder.update_id(), Some(1001));
+    }
+
+    #[test]
+    fn stale_update_is_refused_and_an_overlapping_one_applied() {
+        let fixture: serde_json::Value = serde_json::from_str(FIXTURE).unwrap();
+        let mut builder = builder();
+        let book = builder.from_sequenced_snapshot(&fixture["snapshot"], 1000, Utc::now()).unwrap();
+        let bid = serde_json::json!({ "bids": [["64990.50", "0.100"]] });
+
+        // Wholly at or before the snapshot
+        match builder.apply_update(&book, &bid, UpdateIds::new(990, 1000)) {
+            Err(IngestionError::SequenceGap { expected: 1001, got: 1000 }) => {}
+            other => panic!("expected a stale update, got {:?}", other),
+        }
+        assert!(!builder.needs_resync());
+
+        // Straddling it: the first update after a snapshot
+        assert!(builder.apply_update(&book, &bid, UpdateIds::new(995, 1002)).unwrap().is_some());
+        assert_eq!(builder.update_id(), Some(1002));
+    }
+}
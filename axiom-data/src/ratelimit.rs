@@ -0,0 +1,79 @@
+//! Rate Limiting: Venue Request Weight Budgets
+//!
+//! Venues meter REST calls by weight over a window (Binance spot: 6000 per
+//! minute, a depth snapshot costing 5 to 250 by its limit; Bybit: 600
+//! requests per 5 seconds per IP). A token bucket per venue holds that
+//! budget: it refills continuously and a request waits until its weight is
+//! available, so a burst of resyncs is spread out instead of getting the IP
+//! banned.
+
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+/// A token bucket of `capacity` weight refilling at `per_second`
+pub struct TokenBucket {
+    capacity: f64,
+    per_second: f64,
+    state: Mutex<Bucket>,
+}
+
+impl TokenBucket {
+    /// Starts full
+    pub fn new(capacity: u32, per_second: f64) -> Self {
+        Self {
+            capacity: f64::from(capacity),
+            per_second: per_second.max(f64::MIN_POSITIVE),
+            state: Mutex::new(Bucket { tokens: f64::from(capacity), refilled_at: Instant::now() }),
+        }
+    }
+
+    /// `weight` per `window`, the way venues document their limits
+    pub fn per_window(weight: u32, window: Duration) -> Self {
+        Self::new(weight, f64::from(weight) / window.as_secs_f64().max(f64::MIN_POSITIVE))
+    }
+
+    /// Take `weight` now if it is available; otherwise how long until it is
+    ///
+    /// A weight above the capacity is charged as the whole capacity.
+    pub fn try_acquire(&self, weight: u32) -> Result<(), Duration> {
+        let weight = f64::from(weight).min(self.capacity);
+        let mut bucket = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.per_second).min(self.capacity);
+        bucket.refilled_at = now;
+        if bucket.tokens >= weight {
+            bucket.tokens -= weight;
+            return Ok(());
+        }
+        Err(Duration::try_from_secs_f64((weight - bucket.tokens) / self.per_second).unwrap_or(Duration::MAX))
+    }
+
+    /// Wait until `weight` is available, then take it
+    pub async fn acquire(&self, weight: u32) {
+        while let Err(wait) = self.try_acquire(weight) {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Empty the bucket, as when the venue says the budget is spent
+    pub fn drain(&self) {
+        let mut bucket = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        bucket.tokens = 0.0;
+        bucket.refilled_at = Instant::now();
+    }
+}
+
+impl std::fmt::Debug for TokenBucket {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TokenBucket")
+            .field("capacity", &self.capacity)
+            .field("per_second", &self.per_second)
+            .finish()
+    }
+}
@@ -0,0 +1,278 @@
+//! Raw Messages: Struct-Targeted Tick Parsing
+//!
+//! For venues without a schema mapping, `process_tick` normally parses each
+//! message into a `serde_json::Value`, allocating a full document per
+//! message. With the `fast-ingest` feature it uses `parse_tick` instead: a deserializer targeted at the five fields a
+//! tick needs, which borrows their text from the message and skips
+//! everything else without building it. The borrowed text goes through the
+//! same exact decimal and timestamp normalization as the `Value` path, so
+//! both paths yield identical ticks.
+//!
+//! Binance and Bybit trades get typed parsers of their own
+//! (`parse_binance_trade`, `parse_bybit_trade`), used in place of the schema
+//! `Value` path while the venue's tick mapping is the built-in one they
+//! mirror; a mapping overridden in config goes back to the schema. A
+//! missing or malformed field is `InvalidFormat` naming the field.
+
+use axiom_core::{Side, Tick, Venue};
+use crate::errors::*;
+use crate::normalization::*;
+use crate::schema::SchemaRegistry;
+use serde::de::{self, IgnoredAny, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+use serde_json::value::RawValue;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+use std::marker::PhantomData;
+
+/// A typed trade parser for one venue's messages
+pub type TickParser = fn(&[u8], &Venue, &SymbolMapper) -> Result<Tick, IngestionError>;
+
+/// The fields of a tick message, borrowed from the raw bytes
+#[derive(Deserialize)]
+struct RawTick<'a> {
+    #[serde(borrow)]
+    symbol: Cow<'a, str>,
+    #[serde(borrow)]
+    price: &'a RawValue,
+    #[serde(borrow)]
+    quantity: &'a RawValue,
+    #[serde(borrow)]
+    timestamp: &'a RawValue,
+    #[serde(borrow)]
+    side: Cow<'a, str>,
+}
+
+/// Parse a raw tick message without building a JSON document
+pub fn parse_tick(raw: &[u8], venue: &Venue, symbols: &SymbolMapper) -> Result<Tick, IngestionError> {
+    let message: RawTick = serde_json::from_slice(raw)
+        .map_err(|e| IngestionError::InvalidFormat(format!("Tick: {}", e)))?;
+
+    // As `normalize_timestamp`: numeric text is an epoch count, other strings RFC 3339
+    let (text, quoted) = unquote(message.timestamp)?;
+    let timestamp = match parse_decimal(text) {
+        Ok(amount) => timestamp_from_epoch(amount, TimestampUnit::detect(amount))?,
+        Err(_) if quoted => timestamp_from_rfc3339(text)?,
+        Err(e) => return Err(e.into()),
+    };
+
+    Ok(Tick {
+        symbol: symbols.canonical(venue, &message.symbol)?,
+        venue: venue.clone(),
+        price: normalize_price_str(number_text(message.price)?)?,
+        quantity: normalize_quantity_str(number_text(message.quantity)?)?,
+        timestamp,
+        side: normalize_side(&message.side)?,
+        received_at: None,
+    })
+}
+
+/// A decimal given as a JSON number or a numeric string
+fn number_text(value: &RawValue) -> Result<&str, NormalizationError> {
+    match unquote(value)? {
+        (text, _) if !text.is_empty() => Ok(text),
+        _ => Err(NormalizationError::InvalidType("Expected string or number".to_string())),
+    }
+}
+
+/// A value's text without quotes, and whether it was a string; strings
+/// with escapes are not numbers or timestamps
+fn unquote(value: &RawValue) -> Result<(&str, bool), NormalizationError> {
+    let text = value.get();
+    match text.strip_prefix('"').and_then(|rest| rest.strip_suffix('"')) {
+        Some(inner) if inner.contains('\\') => {
+            Err(NormalizationError::InvalidType(format!("Escaped string {}", text)))
+        }
+        Some(inner) => Ok((inner, true)),
+        None if text.starts_with(|c: char| c == '-' || c.is_ascii_digit()) => Ok((text, false)),
+        None => Err(NormalizationError::InvalidType(format!("Expected string or number, got {}", text))),
+    }
+}
+
+/// A Binance spot trade stream message; `m` is "buyer was the maker"
+#[derive(Deserialize)]
+struct RawBinanceTrade<'a> {
+    #[serde(borrow, rename = "s")]
+    symbol: Cow<'a, str>,
+    #[serde(borrow, rename = "p")]
+    price: &'a RawValue,
+    #[serde(borrow, rename = "q")]
+    quantity: &'a RawValue,
+    #[serde(borrow, rename = "T")]
+    timestamp: &'a RawValue,
+    #[serde(rename = "m")]
+    buyer_maker: bool,
+}
+
+/// A Bybit v5 publicTrade message; only the first trade of a batch is taken
+#[derive(Deserialize)]
+struct RawBybitTrades<'a> {
+    #[serde(borrow)]
+    data: First<RawBybitTrade<'a>>,
+}
+
+#[derive(Deserialize)]
+struct RawBybitTrade<'a> {
+    #[serde(borrow, rename = "s")]
+    symbol: Cow<'a, str>,
+    #[serde(borrow, rename = "p")]
+    price: &'a RawValue,
+    #[serde(borrow, rename = "v")]
+    quantity: &'a RawValue,
+    #[serde(borrow, rename = "T")]
+    timestamp: &'a RawValue,
+    #[serde(borrow, rename = "S")]
+    side: Cow<'a, str>,
+}
+
+/// The first element of an array; the rest are skipped without being built
+struct First<T>(T);
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for First<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FirstVisitor<T>(PhantomData<T>);
+
+        impl<'de, T: Deserialize<'de>> Visitor<'de> for FirstVisitor<T> {
+            type Value = First<T>;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a non-empty array")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<First<T>, A::Error> {
+                let first = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(0, &self))?;
+                while seq.next_element::<IgnoredAny>()?.is_some() {}
+                Ok(First(first))
+            }
+        }
+
+        deserializer.deserialize_seq(FirstVisitor(PhantomData))
+    }
+}
+
+/// Parse a Binance trade message (the built-in Binance tick mapping)
+pub fn parse_binance_trade(raw: &[u8], venue: &Venue, symbols: &SymbolMapper) -> Result<Tick, IngestionError> {
+    let trade: RawBinanceTrade = serde_json::from_slice(raw)
+        .map_err(|e| IngestionError::InvalidFormat(format!("Binance trade: {}", e)))?;
+    Ok(Tick {
+        symbol: symbols.canonical(venue, &trade.symbol)?,
+        venue: venue.clone(),
+        price: normalize_price_str(number_text(trade.price)?)?,
+        quantity: normalize_quantity_str(number_text(trade.quantity)?)?,
+        timestamp: timestamp_from_epoch(parse_decimal(number_text(trade.timestamp)?)?, TimestampUnit::Millis)?,
+        // The taker sold into a resting bid
+        side: if trade.buyer_maker { Side::Sell } else { Side::Buy },
+        received_at: None,
+    })
+}
+
+/// Parse a Bybit publicTrade message (the built-in Bybit tick mapping)
+pub fn parse_bybit_trade(raw: &[u8], venue: &Venue, symbols: &SymbolMapper) -> Result<Tick, IngestionError> {
+    let message: RawBybitTrades = serde_json::from_slice(raw)
+        .map_err(|e| IngestionError::InvalidFormat(format!("Bybit trade: {}", e)))?;
+    let trade = message.data.0;
+    Ok(Tick {
+        symbol: symbols.canonical(venue, &trade.symbol)?,
+        venue: venue.clone(),
+        price: normalize_price_str(number_text(trade.price)?)?,
+        quantity: normalize_quantity_str(number_text(trade.quantity)?)?,
+        timestamp: timestamp_from_epoch(parse_decimal(number_text(trade.timestamp)?)?, TimestampUnit::Millis)?,
+        side: normalize_side(&trade.side)?,
+        received_at: None,
+    })
+}
+
+/// Typed parsers for the venues in `schemas` whose tick mapping is still
+/// the built-in one the parser mirrors
+pub fn typed_tick_parsers(schemas: &SchemaRegistry) -> HashMap<Venue, TickParser> {
+    let builtin = SchemaRegistry::builtin();
+    let parsers: [(Venue, TickParser); 2] = [
+        (Venue::Binance, parse_binance_trade),
+        (Venue::Bybit, parse_bybit_trade),
+    ];
+    parsers.into_iter()
+        .filter(|(venue, _)| {
+            let mapping = schemas.get(venue).and_then(|schema| schema.tick.as_ref());
+            mapping.is_some() && mapping == builtin.get(venue).and_then(|schema| schema.tick.as_ref())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ingestion::normalize_tick;
+
+    /// A tick as text, every field included, or the failure as `Err`
+    fn outcome(result: Result<Tick, IngestionError>) -> Result<String, ()> {
+        result.map(|tick| serde_json::to_string(&tick).unwrap()).map_err(|_| ())
+    }
+
+    /// `raw` through the `Value` path: the schema's tick mapping when the
+    /// venue has one, the default shape otherwise
+    fn through_value(raw: &str, venue: &Venue) -> Result<String, ()> {
+        let symbols = SymbolMapper::builtin();
+        let json: serde_json::Value = serde_json::from_str(raw).unwrap();
+        let schemas = SchemaRegistry::builtin();
+        outcome(match schemas.get(venue).filter(|schema| schema.tick.is_some()) {
+            Some(schema) => schema.normalize_tick(&json, venue, &symbols),
+            None => normalize_tick(&json, venue, &symbols),
+        })
+    }
+
+    /// Both paths give the same tick for each of `accepted` and fail on
+    /// each of `rejected`
+    fn assert_same(parse: TickParser, venue: &Venue, accepted: &[&str], rejected: &[&str]) {
+        let symbols = SymbolMapper::builtin();
+        for raw in accepted.iter().chain(rejected) {
+            let typed = outcome(parse(raw.as_bytes(), venue, &symbols));
+            assert_eq!(typed, through_value(raw, venue), "{}", raw);
+            assert_eq!(typed.is_ok(), accepted.contains(raw), "{}", raw);
+        }
+    }
+
+    #[test]
+    fn default_shape_ticks_match_the_value_path() {
+        let accepted = [
+            r#"{"symbol":"BTC/USD","price":"65000.10","quantity":"0.250","timestamp":1700000000123,"side":"buy"}"#,
+            r#"{"symbol":"BTC/USD","price":65000.1,"quantity":0.25,"timestamp":1700000000,"side":"SELL"}"#,
+            r#"{"symbol":"ETH/USD","price":"3500","quantity":"2","timestamp":"2024-01-02T03:04:05.678Z","side":"b"}"#,
+            r#"{"symbol":"ETH/USD","price":"3500.5","quantity":"1e-3","timestamp":"1700000000123456","side":"2"}"#,
+            r#"{"side":"buy","extra":{"nested":[1,2,{"x":null}]},"timestamp":1700000000123,"quantity":"1","price":"1.5","symbol":"SOL/USD"}"#,
+        ];
+        let rejected = [
+            r#"{"symbol":"BTC/USD","quantity":"1","timestamp":1700000000123,"side":"buy"}"#,
+            r#"{"symbol":"BTC/USD","price":"abc","quantity":"1","timestamp":1700000000123,"side":"buy"}"#,
+            r#"{"symbol":"BTC/USD","price":"65000","quantity":"-1","timestamp":1700000000123,"side":"buy"}"#,
+            r#"{"symbol":"DOGE/USD","price":"1","quantity":"1","timestamp":1700000000123,"side":"buy"}"#,
+            r#"{"symbol":"BTC/USD","price":"65000","quantity":"1","timestamp":1700000000123,"side":"hold"}"#,
+        ];
+        assert_same(parse_tick, &Venue::Paper, &accepted, &rejected);
+    }
+
+    #[test]
+    fn binance_trades_match_the_schema_mapping() {
+        let accepted = [
+            r#"{"e":"trade","E":1700000000125,"s":"BTCUSDT","t":12345,"p":"65000.10","q":"0.25000000","T":1700000000123,"m":true,"M":true}"#,
+            r#"{"e":"trade","E":1700000000125,"s":"ETHUSDT","t":12346,"p":"3500.00","q":"1.5","T":1700000000124,"m":false,"M":true}"#,
+        ];
+        let rejected = [
+            r#"{"e":"trade","s":"BTCUSDT","p":"65000.10","T":1700000000123,"m":true}"#,
+            r#"{"e":"trade","s":"XRPUSDT","p":"0.5","q":"10","T":1700000000123,"m":true}"#,
+        ];
+        assert_same(parse_binance_trade, &Venue::Binance, &accepted, &rejected);
+    }
+
+    #[test]
+    fn bybit_trades_match_the_schema_mapping() {
+        let accepted = [
+            r#"{"topic":"publicTrade.BTCUSDT","type":"snapshot","ts":1700000000130,"data":[{"T":1700000000123,"s":"BTCUSDT","S":"Buy","v":"0.250","p":"65000.10","L":"PlusTick","i":"a1","BT":false},{"T":1700000000124,"s":"BTCUSDT","S":"Sell","v":"1","p":"64999","L":"MinusTick","i":"a2","BT":false}]}"#,
+            r#"{"topic":"publicTrade.SOLUSDT","type":"snapshot","ts":1700000000130,"data":[{"T":1700000000123,"s":"SOLUSDT","S":"Sell","v":"12","p":"150.25","i":"b1"}]}"#,
+        ];
+        let rejected = [
+            r#"{"topic":"publicTrade.BTCUSDT","type":"snapshot","ts":1700000000130,"data":[{"T":1700000000123,"s":"BTCUSDT","S":"Buy","p":"65000.10"}]}"#,
+        ];
+        assert_same(parse_bybit_trade, &Venue::Bybit, &accepted, &rejected);
+    }
+}
@@ -0,0 +1,270 @@
+//! Recorder: Ticks and Books to Disk
+//!
+//! Writes every tick and book ingestion publishes as capture lines (the
+//! format `ReplaySource` reads) into append-only segment files under one
+//! directory. A segment holds at most one UTC hour, by record time, and at
+//! most `max_segment_bytes` of lines. Segments are named
+//! `capture-<YYYYMMDD>T<HH>-<nnnn>.jsonl`, plus `.gz` when compressed, so
+//! name order is time order; an existing segment is never reopened.
+//!
+//! Lines are buffered and flushed every `flush_interval_ms`. A crash loses
+//! at most the unflushed tail and may leave a partial last line, which the
+//! reader skips.
+
+use axiom_core::{BookLevel, OrderBook, Side, Tick};
+use crate::errors::*;
+use chrono::{DateTime, DurationRound, SecondsFormat, TimeDelta, Utc};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+use tracing::{info, warn};
+
+/// Where and how captures are written
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecorderSettings {
+    pub dir: PathBuf,
+    /// Compress segments with gzip
+    pub gzip: bool,
+    pub flush_interval_ms: u64,
+    /// Start a new segment before one grows past this many bytes of lines
+    pub max_segment_bytes: u64,
+}
+
+impl Default for RecorderSettings {
+    fn default() -> Self {
+        Self {
+            dir: PathBuf::from("data/capture"),
+            gzip: false,
+            flush_interval_ms: 1000,
+            max_segment_bytes: 256 * 1024 * 1024,
+        }
+    }
+}
+
+/// What the recorder has written
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecorderStats {
+    pub records: u64,
+    /// Line bytes before compression
+    pub bytes: u64,
+    pub segments: u64,
+    pub last_flush: Option<DateTime<Utc>>,
+    /// Segment being written
+    pub segment: Option<PathBuf>,
+}
+
+enum SegmentWriter {
+    Plain(BufWriter<File>),
+    Gzip(GzEncoder<BufWriter<File>>),
+}
+
+impl SegmentWriter {
+    fn writer(&mut self) -> &mut dyn Write {
+        match self {
+            SegmentWriter::Plain(writer) => writer,
+            SegmentWriter::Gzip(writer) => writer,
+        }
+    }
+
+    /// Flush and, for gzip, write the trailer
+    fn finish(self) -> std::io::Result<()> {
+        match self {
+            SegmentWriter::Plain(mut writer) => writer.flush(),
+            SegmentWriter::Gzip(writer) => writer.finish()?.flush(),
+        }
+    }
+}
+
+struct Segment {
+    path: PathBuf,
+    hour: DateTime<Utc>,
+    bytes: u64,
+    writer: SegmentWriter,
+}
+
+struct RecorderState {
+    segment: Option<Segment>,
+    stats: RecorderStats,
+    flushed_at: Instant,
+}
+
+/// Appends ticks and books to capture segments
+pub struct Recorder {
+    settings: RecorderSettings,
+    state: Mutex<RecorderState>,
+}
+
+impl Recorder {
+    /// Record into `settings.dir`, creating it if needed
+    pub fn new(settings: RecorderSettings) -> Result<Self, IngestionError> {
+        std::fs::create_dir_all(&settings.dir)?;
+        Ok(Self {
+            settings,
+            state: Mutex::new(RecorderState { segment: None, stats: RecorderStats::default(), flushed_at: Instant::now() }),
+        })
+    }
+
+    pub fn record_tick(&self, tick: &Tick) -> Result<(), IngestionError> {
+        self.write(tick.timestamp, &tick_line(tick))
+    }
+
+    pub fn record_book(&self, book: &OrderBook) -> Result<(), IngestionError> {
+        self.write(book.timestamp, &book_line(book))
+    }
+
+    pub fn stats(&self) -> RecorderStats {
+        self.lock().stats.clone()
+    }
+
+    /// Write buffered lines out to the current segment
+    pub fn flush(&self) -> Result<(), IngestionError> {
+        let mut state = self.lock();
+        Self::flush_state(&mut state)
+    }
+
+    /// Flush every `flush_interval_ms` (so a quiet feed is not left in the
+    /// buffer), then close the segment once `shutdown` flips
+    pub async fn run(&self, mut shutdown: watch::Receiver<bool>) {
+        let mut ticker = tokio::time::interval(Duration::from_millis(self.settings.flush_interval_ms.max(1)));
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {}
+                _ = async { drop(shutdown.wait_for(|stop| *stop).await) } => break,
+            }
+            if let Err(e) = self.flush() {
+                warn!("Capture flush failed: {}", e);
+            }
+        }
+        if let Err(e) = self.close() {
+            warn!("Capture segment not closed cleanly: {}", e);
+        }
+    }
+
+    /// Finish the current segment; the next record starts a new one
+    pub fn close(&self) -> Result<(), IngestionError> {
+        let mut state = self.lock();
+        if let Some(segment) = state.segment.take() {
+            segment.writer.finish()?;
+            state.stats.last_flush = Some(Utc::now());
+            state.stats.segment = None;
+        }
+        Ok(())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, RecorderState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn write(&self, at: DateTime<Utc>, line: &Value) -> Result<(), IngestionError> {
+        let mut text = serde_json::to_string(line)
+            .map_err(|e| IngestionError::InvalidFormat(format!("Capture line: {}", e)))?;
+        text.push('\n');
+        let len = text.len() as u64;
+        let hour = at.duration_trunc(TimeDelta::hours(1))
+            .map_err(|e| IngestionError::InvalidFormat(format!("Capture time {}: {}", at, e)))?;
+
+        let mut state = self.lock();
+        // Records older than the segment's hour stay in it; segments only move forward
+        let rotate = state.segment.as_ref()
+            .is_none_or(|segment| hour > segment.hour || segment.bytes + len > self.settings.max_segment_bytes);
+        if rotate {
+            let hour = state.segment.as_ref().map_or(hour, |segment| segment.hour.max(hour));
+            if let Some(segment) = state.segment.take() {
+                segment.writer.finish()?;
+            }
+            let segment = self.open_segment(hour)?;
+            info!("Capturing to {}", segment.path.display());
+            state.stats.segments += 1;
+            state.stats.segment = Some(segment.path.clone());
+            state.segment = Some(segment);
+        }
+
+        let Some(segment) = state.segment.as_mut() else { return Ok(()) };
+        segment.writer.writer().write_all(text.as_bytes())?;
+        segment.bytes += len;
+        state.stats.records += 1;
+        state.stats.bytes += len;
+
+        if state.flushed_at.elapsed() >= Duration::from_millis(self.settings.flush_interval_ms) {
+            Self::flush_state(&mut state)?;
+        }
+        Ok(())
+    }
+
+    fn flush_state(state: &mut RecorderState) -> Result<(), IngestionError> {
+        if let Some(segment) = state.segment.as_mut() {
+            segment.writer.writer().flush()?;
+            state.stats.last_flush = Some(Utc::now());
+        }
+        state.flushed_at = Instant::now();
+        Ok(())
+    }
+
+    /// A new segment for `hour`, after any already on disk
+    fn open_segment(&self, hour: DateTime<Utc>) -> Result<Segment, IngestionError> {
+        let extension = if self.settings.gzip { "jsonl.gz" } else { "jsonl" };
+        let stem = format!("capture-{}", hour.format("%Y%m%dT%H"));
+        let mut index = 0;
+        loop {
+            let path = self.settings.dir.join(format!("{}-{:04}.{}", stem, index, extension));
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(file) => {
+                    let file = BufWriter::new(file);
+                    let writer = if self.settings.gzip {
+                        SegmentWriter::Gzip(GzEncoder::new(file, Compression::default()))
+                    } else {
+                        SegmentWriter::Plain(file)
+                    };
+                    return Ok(Segment { path, hour, bytes: 0, writer });
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => index += 1,
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+}
+
+/// Whether `path` is a capture segment, by name
+pub fn is_capture_segment(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.ends_with(".jsonl") || name.ends_with(".jsonl.gz"))
+}
+
+/// A tick as a capture line
+fn tick_line(tick: &Tick) -> Value {
+    json!({
+        "symbol": tick.symbol.0,
+        "venue": tick.venue.to_string(),
+        "price": tick.price.to_string(),
+        "quantity": tick.quantity.to_string(),
+        "side": match tick.side {
+            Side::Buy => "buy",
+            Side::Sell => "sell",
+        },
+        "timestamp": tick.timestamp.to_rfc3339_opts(SecondsFormat::AutoSi, true),
+    })
+}
+
+/// A book as a capture snapshot line
+fn book_line(book: &OrderBook) -> Value {
+    let levels = |side: &[BookLevel]| -> Vec<Value> {
+        side.iter().map(|level| json!([level.price.to_string(), level.quantity.to_string()])).collect()
+    };
+    json!({
+        "symbol": book.symbol.0,
+        "venue": book.venue.to_string(),
+        "timestamp": book.timestamp.to_rfc3339_opts(SecondsFormat::AutoSi, true),
+        "bids": levels(&book.bids),
+        "asks": levels(&book.asks),
+    })
+}
@@ -0,0 +1,251 @@
+//! Replay: Recorded Market Data Back Through the Pipeline
+//!
+//! A capture file holds one JSON object per line, in the order it was
+//! received: a trade in the shape `normalize_tick` takes
+//! (`{"symbol", "price", "quantity", "timestamp", "side"}`), or a book
+//! snapshot (`{"symbol", "timestamp", "bids": [[price, quantity], ...], "asks": [...]}`).
+//! Either may name its `venue`; otherwise the source's default venue is used.
+//! Symbols may be the venue's or ours; both go through the symbol mapper.
+//! A capture may be one file or a directory of `Recorder` segments, read in
+//! name order; `.gz` files are decompressed. The last line of a file that
+//! does not parse and has no newline was cut off by a crash and is skipped.
+//!
+//! Everything keeps its recorded time, so two replays of the same file hand
+//! the engine identical ticks and books, and book sequence numbers rise per
+//! (symbol, venue) exactly as they did. Replay runs as fast as the channels
+//! take it, or paced by the recorded times at a speed multiplier.
+
+use axiom_core::{OrderBook, Symbol, Tick, Venue};
+use crate::errors::*;
+use crate::feed::{FeedSendError, FeedSender};
+use crate::ingestion::{normalize_tick, send_error};
+use crate::normalization::{normalize_timestamp, SymbolMapper};
+use crate::orderbook::OrderBookBuilder;
+use crate::recorder::is_capture_segment;
+use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use serde_json::Value;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Book builders by (symbol, venue), carried across a capture's files
+type Builders = HashMap<(Symbol, Venue), OrderBookBuilder>;
+
+/// One recorded message
+#[derive(Debug, Clone)]
+pub enum ReplayRecord {
+    Tick(Tick),
+    Book(OrderBook),
+}
+
+impl ReplayRecord {
+    /// Recorded time
+    pub fn timestamp(&self) -> DateTime<Utc> {
+        match self {
+            ReplayRecord::Tick(tick) => tick.timestamp,
+            ReplayRecord::Book(book) => book.timestamp,
+        }
+    }
+}
+
+/// What a replay sent
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReplayStats {
+    pub ticks: usize,
+    pub books: usize,
+}
+
+/// Recorded ticks and books, ready to be sent in order
+#[derive(Debug, Clone, Default)]
+pub struct ReplaySource {
+    records: Vec<ReplayRecord>,
+    /// Multiple of the recorded rate; None sends as fast as possible
+    speed: Option<f64>,
+}
+
+impl ReplaySource {
+    /// Read a capture file, or every segment in a capture directory; a bad
+    /// line (other than a cut-off last one) fails the whole capture
+    pub fn open(path: &Path, venue: &Venue, symbols: &SymbolMapper) -> Result<Self, IngestionError> {
+        let files = if path.is_dir() {
+            let mut files: Vec<PathBuf> = std::fs::read_dir(path)?
+                .map(|entry| entry.map(|entry| entry.path()))
+                .collect::<Result<_, _>>()?;
+            files.retain(|file| is_capture_segment(file));
+            files.sort();
+            files
+        } else {
+            vec![path.to_path_buf()]
+        };
+
+        let mut builders = Builders::new();
+        let mut records = Vec::new();
+        for file in files {
+            let reader: Box<dyn BufRead> = match file.extension().and_then(|extension| extension.to_str()) {
+                Some("gz") => Box::new(BufReader::new(GzDecoder::new(File::open(&file)?))),
+                _ => Box::new(BufReader::new(File::open(&file)?)),
+            };
+            read_records(reader, venue, symbols, &mut builders, &mut records)
+                .map_err(|e| IngestionError::InvalidFormat(format!("{}: {}", file.display(), e)))?;
+        }
+        Ok(Self { records, speed: None })
+    }
+
+    /// Read capture lines; trades and books without a `venue` are `venue`'s
+    pub fn from_reader(reader: impl BufRead, venue: &Venue, symbols: &SymbolMapper) -> Result<Self, IngestionError> {
+        let mut records = Vec::new();
+        read_records(reader, venue, symbols, &mut Builders::new(), &mut records)?;
+        Ok(Self { records, speed: None })
+    }
+
+    /// Replay books already built (a book journal), as recorded
+    pub fn from_books(books: Vec<OrderBook>) -> Self {
+        Self { records: books.into_iter().map(ReplayRecord::Book).collect(), speed: None }
+    }
+
+    /// Pace sends by the recorded times, `speed` times as fast (2.0 is
+    /// double speed); a speed that is not positive sends as fast as possible
+    pub fn with_speed(mut self, speed: f64) -> Self {
+        self.speed = (speed.is_finite() && speed > 0.0).then_some(speed);
+        self
+    }
+
+    pub fn records(&self) -> &[ReplayRecord] {
+        &self.records
+    }
+
+    /// The recorded books, in order
+    pub fn books(&self) -> impl Iterator<Item = &OrderBook> {
+        self.records.iter().filter_map(|record| match record {
+            ReplayRecord::Book(book) => Some(book),
+            ReplayRecord::Tick(_) => None,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Send every record to `ticks` or `books`, stopping early when a
+    /// receiver is gone
+    ///
+    /// A message a full channel drops is counted by the channel, as for a
+    /// live feed, and the replay carries on.
+    pub async fn run(
+        self,
+        ticks: &FeedSender<Tick>,
+        books: &FeedSender<Arc<OrderBook>>,
+    ) -> Result<ReplayStats, IngestionError> {
+        let mut stats = ReplayStats::default();
+        let mut previous: Option<DateTime<Utc>> = None;
+        for record in self.records {
+            let at = record.timestamp();
+            match (self.speed, previous) {
+                (Some(speed), Some(previous)) => {
+                    let gap = (at - previous).to_std().unwrap_or(Duration::ZERO);
+                    tokio::time::sleep(gap.div_f64(speed)).await;
+                }
+                _ => tokio::task::yield_now().await,
+            }
+            previous = Some(at);
+
+            let (stream, sent) = match record {
+                ReplayRecord::Tick(tick) => {
+                    stats.ticks += 1;
+                    ("tick", ticks.send(tick).await)
+                }
+                ReplayRecord::Book(book) => {
+                    stats.books += 1;
+                    ("book", books.send(Arc::new(book)).await)
+                }
+            };
+            match sent {
+                Ok(()) | Err(FeedSendError::TimedOut) => {}
+                Err(e @ FeedSendError::Closed) => return Err(send_error(stream, e)),
+            }
+        }
+        info!("Replay finished: {} ticks and {} books sent", stats.ticks, stats.books);
+        Ok(stats)
+    }
+}
+
+/// Append every record in `reader` to `records`
+fn read_records(
+    mut reader: impl BufRead,
+    venue: &Venue,
+    symbols: &SymbolMapper,
+    builders: &mut Builders,
+    records: &mut Vec<ReplayRecord>,
+) -> Result<(), IngestionError> {
+    let mut line = String::new();
+    let mut number = 0;
+    loop {
+        line.clear();
+        let read = match reader.read_line(&mut line) {
+            Ok(read) => read,
+            // A compressed segment cut off by a crash
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => {
+                warn!("Capture ends mid-stream after line {}", number);
+                return Ok(());
+            }
+            Err(e) => return Err(e.into()),
+        };
+        if read == 0 {
+            return Ok(());
+        }
+        number += 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match parse_record(&line, venue, symbols, builders) {
+            Ok(record) => records.push(record),
+            Err(e) if !line.ends_with('\n') => {
+                warn!("Skipping cut-off last line {} of capture: {}", number, e);
+                return Ok(());
+            }
+            Err(e) => return Err(IngestionError::InvalidFormat(format!("line {}: {}", number, e))),
+        }
+    }
+}
+
+fn parse_record(
+    line: &str,
+    default_venue: &Venue,
+    symbols: &SymbolMapper,
+    builders: &mut Builders,
+) -> Result<ReplayRecord, IngestionError> {
+    let json: Value = serde_json::from_str(line)
+        .map_err(|e| IngestionError::InvalidFormat(format!("JSON parse: {}", e)))?;
+    let venue = match json.get("venue").and_then(Value::as_str) {
+        Some(name) => name.parse::<Venue>()
+            .map_err(|e| IngestionError::InvalidFormat(e.to_string()))?,
+        None => default_venue.clone(),
+    };
+    if json.get("bids").is_none() && json.get("asks").is_none() {
+        return normalize_tick(&json, &venue, symbols).map(ReplayRecord::Tick);
+    }
+
+    let exchange_symbol = json.get("symbol")
+        .and_then(Value::as_str)
+        .ok_or_else(|| IngestionError::InvalidFormat("Missing symbol".to_string()))?;
+    let symbol = symbols.canonical(&venue, exchange_symbol)?;
+    let timestamp = normalize_timestamp(
+        json.get("timestamp")
+            .ok_or_else(|| IngestionError::InvalidFormat("Missing timestamp".to_string()))?
+    )?;
+    let builder = match builders.entry((symbol, venue.clone())) {
+        Entry::Occupied(entry) => entry.into_mut(),
+        Entry::Vacant(entry) => entry.insert(OrderBookBuilder::new(symbols, venue, exchange_symbol)?),
+    };
+    builder.from_snapshot(&json, timestamp).map(ReplayRecord::Book)
+}
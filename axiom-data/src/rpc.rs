@@ -0,0 +1,300 @@
+//! JSON-RPC: Node Endpoints with Failover
+//!
+//! Chain nodes (Solana, EVM) answer JSON-RPC 2.0 over HTTP. A call fails
+//! over to the next endpoint on a timeout, a network failure or an
+//! overloaded node (429, 5xx); other failures, an RPC error among them, are
+//! returned at once.
+//!
+//! Endpoints are tried healthy first, those whose last attempt succeeded
+//! before those whose last attempt failed, then by median latency over
+//! recent answers (unmeasured ones after measured ones, in configured
+//! order). An endpoint that fails `max_consecutive_failures` times in a row
+//! is demoted behind every healthy one and probed in the background every
+//! `probe_interval` with the chain's cheapest method; the first answer
+//! promotes it again.
+
+use crate::errors::*;
+use reqwest::StatusCode;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+/// How long one endpoint gets to answer before the next is tried
+pub const DEFAULT_RPC_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// When an endpoint is demoted and how it earns its way back
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HealthPolicy {
+    /// Latest answers kept per endpoint for its median latency
+    pub latency_window: usize,
+    /// Failures in a row that demote an endpoint
+    pub max_consecutive_failures: u32,
+    /// Wait between probes of a demoted endpoint
+    pub probe_interval: Duration,
+}
+
+impl Default for HealthPolicy {
+    fn default() -> Self {
+        Self { latency_window: 32, max_consecutive_failures: 3, probe_interval: Duration::from_secs(30) }
+    }
+}
+
+#[derive(Debug, Default)]
+struct EndpointState {
+    latencies: VecDeque<Duration>,
+    successes: u64,
+    failures: u64,
+    consecutive_failures: u32,
+    /// While demoted, when the next probe is due
+    next_probe: Option<Instant>,
+    last_error: Option<String>,
+}
+
+impl EndpointState {
+    fn demoted(&self) -> bool {
+        self.next_probe.is_some()
+    }
+
+    fn p50(&self) -> Option<Duration> {
+        let mut sorted: Vec<Duration> = self.latencies.iter().copied().collect();
+        sorted.sort();
+        sorted.get(sorted.len() / 2).copied()
+    }
+}
+
+/// One endpoint's health
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EndpointReport {
+    /// Position in the configured list
+    pub index: usize,
+    /// Scheme and host only; the path and query often carry an API key
+    pub host: String,
+    pub p50_latency_ms: Option<u64>,
+    pub successes: u64,
+    pub failures: u64,
+    pub consecutive_failures: u32,
+    pub demoted: bool,
+    /// The next call goes here first
+    pub preferred: bool,
+    pub last_error: Option<String>,
+}
+
+/// JSON-RPC over several endpoints of one chain, failing over between them
+#[derive(Debug, Clone)]
+pub struct JsonRpc {
+    /// Chain name for errors and logs ("Solana", "Ethereum")
+    chain: &'static str,
+    /// Parameterless method demoted endpoints are probed with
+    probe_method: &'static str,
+    client: reqwest::Client,
+    endpoints: Arc<Vec<String>>,
+    /// By endpoint, in configured order
+    health: Arc<Mutex<Vec<EndpointState>>>,
+    policy: HealthPolicy,
+    timeout: Duration,
+}
+
+impl JsonRpc {
+    pub fn new(chain: &'static str, probe_method: &'static str, endpoints: Vec<String>) -> Self {
+        let health = endpoints.iter().map(|_| EndpointState::default()).collect();
+        Self {
+            chain,
+            probe_method,
+            client: reqwest::Client::new(),
+            endpoints: Arc::new(endpoints),
+            health: Arc::new(Mutex::new(health)),
+            policy: HealthPolicy::default(),
+            timeout: DEFAULT_RPC_TIMEOUT,
+        }
+    }
+
+    pub fn with_health_policy(mut self, policy: HealthPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Give each endpoint `timeout` to answer before trying the next
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn endpoints(&self) -> &[String] {
+        &self.endpoints
+    }
+
+    /// `method`'s result from the first endpoint able to answer; Network
+    /// once every endpoint has timed out or failed to
+    pub async fn call(&self, method: &str, params: Value) -> Result<Value, IngestionError> {
+        if self.endpoints.is_empty() {
+            return Err(IngestionError::Network(format!("{} {}: no RPC endpoints configured", self.chain, method)));
+        }
+        self.probe_due();
+        let mut failures = Vec::new();
+        for index in self.order() {
+            let started = Instant::now();
+            match self.request(&self.endpoints[index], method, &params).await {
+                Err(e) if fails_over(&e) => {
+                    // Endpoint URLs often carry an API key, so log them by position
+                    warn!("{} RPC endpoint {} failed {}: {}", self.chain, index, method, e);
+                    self.record_failure(index, &e);
+                    failures.push(format!("endpoint {}: {}", index, e));
+                }
+                answered => {
+                    self.record_success(index, started.elapsed());
+                    return answered;
+                }
+            }
+        }
+        Err(IngestionError::Network(format!(
+            "{} {} failed on every RPC endpoint ({})", self.chain, method, failures.join("; "),
+        )))
+    }
+
+    /// Every endpoint's health, in configured order
+    pub fn endpoint_health(&self) -> Vec<EndpointReport> {
+        let preferred = self.order().first().copied();
+        self.lock_health().iter().enumerate().map(|(index, state)| EndpointReport {
+            index,
+            host: host_of(&self.endpoints[index]),
+            p50_latency_ms: state.p50().map(|p50| u64::try_from(p50.as_millis()).unwrap_or(u64::MAX)),
+            successes: state.successes,
+            failures: state.failures,
+            consecutive_failures: state.consecutive_failures,
+            demoted: state.demoted(),
+            preferred: preferred == Some(index),
+            last_error: state.last_error.clone(),
+        }).collect()
+    }
+
+    fn lock_health(&self) -> MutexGuard<'_, Vec<EndpointState>> {
+        self.health.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Endpoint indexes in the order a call tries them
+    fn order(&self) -> Vec<usize> {
+        let health = self.lock_health();
+        let mut order: Vec<usize> = (0..health.len()).collect();
+        order.sort_by_key(|&index| {
+            let state = &health[index];
+            (state.demoted(), state.consecutive_failures > 0, state.p50().unwrap_or(Duration::MAX), index)
+        });
+        order
+    }
+
+    fn record_success(&self, index: usize, latency: Duration) {
+        let mut health = self.lock_health();
+        let state = &mut health[index];
+        state.latencies.push_back(latency);
+        while state.latencies.len() > self.policy.latency_window.max(1) {
+            state.latencies.pop_front();
+        }
+        state.successes += 1;
+        state.consecutive_failures = 0;
+        if state.next_probe.take().is_some() {
+            info!("{} RPC endpoint {} recovered; promoted", self.chain, index);
+        }
+    }
+
+    fn record_failure(&self, index: usize, e: &IngestionError) {
+        let mut health = self.lock_health();
+        let state = &mut health[index];
+        state.failures += 1;
+        state.consecutive_failures += 1;
+        state.last_error = Some(e.to_string());
+        if state.consecutive_failures >= self.policy.max_consecutive_failures && !state.demoted() {
+            warn!("{} RPC endpoint {} failed {} times in a row; demoted", self.chain, index, state.consecutive_failures);
+            state.next_probe = Some(Instant::now() + self.policy.probe_interval);
+        }
+    }
+
+    /// Probe, in the background, every demoted endpoint whose probe is due
+    fn probe_due(&self) {
+        let now = Instant::now();
+        let due: Vec<usize> = self.lock_health().iter_mut().enumerate()
+            .filter_map(|(index, state)| match state.next_probe {
+                Some(at) if at <= now => {
+                    state.next_probe = Some(now + self.policy.probe_interval);
+                    Some(index)
+                }
+                _ => None,
+            })
+            .collect();
+        for index in due {
+            let rpc = self.clone();
+            tokio::spawn(async move { rpc.probe(index).await });
+        }
+    }
+
+    async fn probe(&self, index: usize) {
+        let started = Instant::now();
+        match self.request(&self.endpoints[index], self.probe_method, &json!([])).await {
+            Ok(_) => self.record_success(index, started.elapsed()),
+            Err(e) => {
+                debug!("{} RPC endpoint {} still failing: {}", self.chain, index, e);
+                self.record_failure(index, &e);
+            }
+        }
+    }
+
+    async fn request(&self, endpoint: &str, method: &str, params: &Value) -> Result<Value, IngestionError> {
+        let chain = self.chain;
+        let failed = |e: reqwest::Error| {
+            let e = e.without_url();
+            if e.is_timeout() {
+                IngestionError::Timeout(format!("{} {}: {}", chain, method, e))
+            } else {
+                IngestionError::Network(format!("{} {}: {}", chain, method, e))
+            }
+        };
+        let response = self.client.post(endpoint)
+            .timeout(self.timeout)
+            .json(&json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }))
+            .send()
+            .await
+            .map_err(failed)?;
+        let status = response.status();
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            return Err(IngestionError::RateLimited { retry_after: None });
+        }
+        if !status.is_success() {
+            return Err(IngestionError::HttpStatus { code: status.as_u16() });
+        }
+        let body: Value = response.json().await.map_err(|e| if e.is_timeout() {
+            failed(e)
+        } else {
+            IngestionError::InvalidFormat(format!("{} {}: {}", chain, method, e.without_url()))
+        })?;
+        parse_rpc_response(chain, method, body)
+    }
+}
+
+/// Whether `e` says the endpoint, not the request, is at fault
+fn fails_over(e: &IngestionError) -> bool {
+    match e {
+        IngestionError::Timeout(_) | IngestionError::Network(_) | IngestionError::RateLimited { .. } => true,
+        IngestionError::HttpStatus { code } => *code >= 500,
+        _ => false,
+    }
+}
+
+/// A JSON-RPC response's `result`, or its `error` as ExchangeApi
+pub fn parse_rpc_response(chain: &str, method: &str, mut body: Value) -> Result<Value, IngestionError> {
+    if let Some(error) = body.get("error") {
+        let code = error.get("code").and_then(Value::as_i64).unwrap_or_default();
+        let message = error.get("message").and_then(Value::as_str).unwrap_or("no message");
+        return Err(IngestionError::ExchangeApi(format!("{} {} failed ({}): {}", chain, method, code, message)));
+    }
+    body.get_mut("result").map(Value::take)
+        .ok_or_else(|| IngestionError::InvalidFormat(format!("{} {} response has no result", chain, method)))
+}
+
+/// `endpoint`'s scheme and host
+fn host_of(endpoint: &str) -> String {
+    reqwest::Url::parse(endpoint).ok()
+        .and_then(|url| url.host_str().map(|host| format!("{}://{}", url.scheme(), host)))
+        .unwrap_or_else(|| "(unparsable URL)".to_string())
+}
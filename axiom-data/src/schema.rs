@@ -0,0 +1,453 @@
+//! Venue Schemas: Declarative Payload Mapping
+//!
+//! Every exchange names and nests its fields differently. A `VenueSchema`
+//! says where a venue's tick and book fields live in its JSON (paths such
+//! as `data[0].p`), which unit its timestamps use and how it encodes the
+//! trade side, and the generic normalizer here applies it. Built-in
+//! mappings cover Binance, Bybit and Kraken; config may override them or
+//! add venues, so a simple public feed needs a mapping entry and a feed
+//! URL rather than a new normalizer. Values go through the same exact
+//! decimal normalization as every other path.
+
+use axiom_core::{BookLevel, Price, Quantity, Side, Symbol, Tick, Venue};
+use crate::errors::*;
+use crate::normalization::*;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Longest raw fragment quoted in a mapping error
+const MAX_FRAGMENT: usize = 200;
+
+/// One step of a field path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+/// Where a field lives in a payload: keys separated by `.`, array indices
+/// in brackets (`data[0].p`, `[1][0][2]`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct FieldPath(Vec<PathSegment>);
+
+impl FieldPath {
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.0
+    }
+
+    /// The value at this path under `root`; on failure, the value the
+    /// first missing segment was looked up in
+    fn resolve<'a>(&self, root: &'a Value) -> Result<&'a Value, &'a Value> {
+        self.0.iter().try_fold(root, |value, segment| {
+            let next = match segment {
+                PathSegment::Key(key) => value.get(key),
+                PathSegment::Index(index) => value.get(*index),
+            };
+            next.ok_or(value)
+        })
+    }
+
+    /// This path, then element `index`, then `rest` (for error messages)
+    fn element(&self, index: usize, rest: &FieldPath) -> FieldPath {
+        let mut segments = self.0.clone();
+        segments.push(PathSegment::Index(index));
+        segments.extend(rest.0.iter().cloned());
+        FieldPath(segments)
+    }
+}
+
+impl std::str::FromStr for FieldPath {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut segments = Vec::new();
+        let mut rest = text;
+        while !rest.is_empty() {
+            if let Some(after) = rest.strip_prefix('[') {
+                let (index, after) = after.split_once(']')
+                    .ok_or_else(|| format!("unclosed '[' in field path {:?}", text))?;
+                let index = index.parse()
+                    .map_err(|_| format!("bad array index {:?} in field path {:?}", index, text))?;
+                segments.push(PathSegment::Index(index));
+                rest = after.strip_prefix('.').unwrap_or(after);
+            } else {
+                let end = rest.find(['.', '[']).unwrap_or(rest.len());
+                if end == 0 {
+                    return Err(format!("empty key in field path {:?}", text));
+                }
+                segments.push(PathSegment::Key(rest[..end].to_string()));
+                rest = &rest[end..];
+                rest = rest.strip_prefix('.').unwrap_or(rest);
+            }
+        }
+        if segments.is_empty() {
+            return Err("empty field path".to_string());
+        }
+        Ok(FieldPath(segments))
+    }
+}
+
+impl TryFrom<String> for FieldPath {
+    type Error = String;
+
+    fn try_from(text: String) -> Result<Self, Self::Error> {
+        text.parse()
+    }
+}
+
+impl From<FieldPath> for String {
+    fn from(path: FieldPath) -> Self {
+        path.to_string()
+    }
+}
+
+impl fmt::Display for FieldPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (position, segment) in self.0.iter().enumerate() {
+            match segment {
+                PathSegment::Key(key) if position == 0 => write!(f, "{}", key)?,
+                PathSegment::Key(key) => write!(f, ".{}", key)?,
+                PathSegment::Index(index) => write!(f, "[{}]", index)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// How a venue writes the trade side
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SideEncoding {
+    /// "buy"/"sell", "b"/"s" or "1"/"2", any case
+    #[default]
+    Text,
+    /// A boolean "buyer was the maker": true means the taker sold
+    BuyerMaker,
+}
+
+/// Where a trade message's fields are
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TickSchema {
+    pub symbol: FieldPath,
+    pub price: FieldPath,
+    pub quantity: FieldPath,
+    pub timestamp: FieldPath,
+    pub side: FieldPath,
+    #[serde(default)]
+    pub timestamp_unit: TimestampUnit,
+    #[serde(default)]
+    pub side_encoding: SideEncoding,
+}
+
+/// Where a book message's fields are
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BookSchema {
+    pub symbol: FieldPath,
+    /// Arrays of levels
+    pub bids: FieldPath,
+    pub asks: FieldPath,
+    /// Price and quantity within one level
+    #[serde(default = "BookSchema::default_level_price")]
+    pub level_price: FieldPath,
+    #[serde(default = "BookSchema::default_level_quantity")]
+    pub level_quantity: FieldPath,
+    /// Books without one are stamped with the receive time
+    #[serde(default)]
+    pub timestamp: Option<FieldPath>,
+    #[serde(default)]
+    pub timestamp_unit: TimestampUnit,
+    #[serde(default)]
+    pub sequence: Option<FieldPath>,
+}
+
+impl BookSchema {
+    fn default_level_price() -> FieldPath {
+        FieldPath(vec![PathSegment::Index(0)])
+    }
+
+    fn default_level_quantity() -> FieldPath {
+        FieldPath(vec![PathSegment::Index(1)])
+    }
+}
+
+/// One venue's feed and payload mapping (a `[venues.schemas."<venue>"]` table)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VenueSchema {
+    /// Public market data feed the connector subscribes to
+    pub feed_url: Option<String>,
+    /// Venue symbol to ours ("BTCUSDT" = "BTC/USD"), added to the built-in
+    /// `SymbolMapper` table; a symbol mapped nowhere is rejected
+    pub symbols: BTreeMap<String, String>,
+    pub tick: Option<TickSchema>,
+    pub book: Option<BookSchema>,
+}
+
+/// Book levels from one message, in the venue's order; zero quantities
+/// (removals in an update) are kept
+#[derive(Debug, Clone)]
+pub struct BookLevels {
+    pub symbol: Symbol,
+    pub bids: Vec<BookLevel>,
+    pub asks: Vec<BookLevel>,
+    /// None when the schema has no timestamp path
+    pub timestamp: Option<DateTime<Utc>>,
+    pub sequence: Option<u64>,
+}
+
+impl VenueSchema {
+    /// A trade message as a tick
+    pub fn normalize_tick(&self, json: &Value, venue: &Venue, symbols: &SymbolMapper) -> Result<Tick, IngestionError> {
+        let schema = self.tick.as_ref()
+            .ok_or_else(|| IngestionError::InvalidFormat(format!("No tick mapping for venue {}", venue)))?;
+        let mapper = Mapper { venue };
+
+        let symbol = symbols.canonical(venue, &mapper.convert(&schema.symbol, json, text)?)?;
+        let price = mapper.convert(&schema.price, json, price)?;
+        let quantity = mapper.convert(&schema.quantity, json, quantity)?;
+        let timestamp = mapper.convert(&schema.timestamp, json, |value| timestamp(value, schema.timestamp_unit))?;
+        let side = match schema.side_encoding {
+            SideEncoding::Text => mapper.convert(&schema.side, json, |value| {
+                normalize_side(value.as_str().ok_or_else(|| "expected a string".to_string())?).map_err(|e| e.to_string())
+            })?,
+            SideEncoding::BuyerMaker => mapper.convert(&schema.side, json, |value| {
+                match value.as_bool() {
+                    Some(true) => Ok(Side::Sell),
+                    Some(false) => Ok(Side::Buy),
+                    None => Err("expected a boolean".to_string()),
+                }
+            })?,
+        };
+
+        Ok(Tick { symbol, venue: venue.clone(), price, quantity, timestamp, side, received_at: None })
+    }
+
+    /// A book snapshot or update message as levels
+    pub fn normalize_book(&self, json: &Value, venue: &Venue, symbols: &SymbolMapper) -> Result<BookLevels, IngestionError> {
+        let schema = self.book.as_ref()
+            .ok_or_else(|| IngestionError::InvalidFormat(format!("No book mapping for venue {}", venue)))?;
+        let mapper = Mapper { venue };
+
+        let side = |path: &FieldPath| -> Result<Vec<BookLevel>, IngestionError> {
+            let levels = mapper.field(path, json)?;
+            let levels = levels.as_array().ok_or_else(|| mapper.error(path, "expected an array", levels))?;
+            levels.iter().enumerate()
+                .map(|(index, level)| Ok(BookLevel {
+                    price: mapper.convert_at(&path.element(index, &schema.level_price), &schema.level_price, level, price)?,
+                    quantity: mapper.convert_at(&path.element(index, &schema.level_quantity), &schema.level_quantity, level, quantity)?,
+                }))
+                .collect()
+        };
+
+        Ok(BookLevels {
+            symbol: symbols.canonical(venue, &mapper.convert(&schema.symbol, json, text)?)?,
+            bids: side(&schema.bids)?,
+            asks: side(&schema.asks)?,
+            timestamp: schema.timestamp.as_ref()
+                .map(|path| mapper.convert(path, json, |value| timestamp(value, schema.timestamp_unit)))
+                .transpose()?,
+            sequence: schema.sequence.as_ref()
+                .map(|path| mapper.convert(path, json, |value| {
+                    integer_text(value)?.parse().map_err(|_| "expected an unsigned integer".to_string())
+                }))
+                .transpose()?,
+        })
+    }
+}
+
+/// Schemas by venue (`Venue`'s display form: "binance", "custom:kraken")
+#[derive(Debug, Clone, Default)]
+pub struct SchemaRegistry {
+    schemas: BTreeMap<String, VenueSchema>,
+}
+
+impl SchemaRegistry {
+    /// No mappings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mappings for the public feeds of Binance (spot trade and diff depth
+    /// streams), Bybit (v5 spot publicTrade and orderbook) and Kraken
+    /// (v2 trade and book channels, as "custom:kraken")
+    pub fn builtin() -> Self {
+        let path = |text: &str| text.parse::<FieldPath>().expect("built-in field path");
+        let binance = VenueSchema {
+            feed_url: Some("wss://stream.binance.com:9443/ws".to_string()),
+            symbols: BTreeMap::new(),
+            tick: Some(TickSchema {
+                symbol: path("s"),
+                price: path("p"),
+                quantity: path("q"),
+                timestamp: path("T"),
+                side: path("m"),
+                timestamp_unit: TimestampUnit::Millis,
+                side_encoding: SideEncoding::BuyerMaker,
+            }),
+            book: Some(BookSchema {
+                symbol: path("s"),
+                bids: path("b"),
+                asks: path("a"),
+                level_price: BookSchema::default_level_price(),
+                level_quantity: BookSchema::default_level_quantity(),
+                timestamp: Some(path("E")),
+                timestamp_unit: TimestampUnit::Millis,
+                sequence: Some(path("u")),
+            }),
+        };
+        // Trade messages batch several trades; the first is taken
+        let bybit = VenueSchema {
+            feed_url: Some("wss://stream.bybit.com/v5/public/spot".to_string()),
+            symbols: BTreeMap::new(),
+            tick: Some(TickSchema {
+                symbol: path("data[0].s"),
+                price: path("data[0].p"),
+                quantity: path("data[0].v"),
+                timestamp: path("data[0].T"),
+                side: path("data[0].S"),
+                timestamp_unit: TimestampUnit::Millis,
+                side_encoding: SideEncoding::Text,
+            }),
+            book: Some(BookSchema {
+                symbol: path("data.s"),
+                bids: path("data.b"),
+                asks: path("data.a"),
+                level_price: BookSchema::default_level_price(),
+                level_quantity: BookSchema::default_level_quantity(),
+                timestamp: Some(path("ts")),
+                timestamp_unit: TimestampUnit::Millis,
+                sequence: Some(path("data.u")),
+            }),
+        };
+        let kraken = VenueSchema {
+            feed_url: Some("wss://ws.kraken.com/v2".to_string()),
+            symbols: BTreeMap::new(),
+            tick: Some(TickSchema {
+                symbol: path("data[0].symbol"),
+                price: path("data[0].price"),
+                quantity: path("data[0].qty"),
+                timestamp: path("data[0].timestamp"),
+                side: path("data[0].side"),
+                timestamp_unit: TimestampUnit::Rfc3339,
+                side_encoding: SideEncoding::Text,
+            }),
+            book: Some(BookSchema {
+                symbol: path("data[0].symbol"),
+                bids: path("data[0].bids"),
+                asks: path("data[0].asks"),
+                level_price: path("price"),
+                level_quantity: path("qty"),
+                // Snapshots carry no timestamp
+                timestamp: None,
+                timestamp_unit: TimestampUnit::Rfc3339,
+                sequence: None,
+            }),
+        };
+
+        Self::new()
+            .with_schema(Venue::Binance, binance)
+            .with_schema(Venue::Bybit, bybit)
+            .with_schema(Venue::Custom("kraken".to_string()), kraken)
+    }
+
+    /// Add or replace `venue`'s mapping
+    pub fn with_schema(mut self, venue: Venue, schema: VenueSchema) -> Self {
+        self.schemas.insert(venue.to_string(), schema);
+        self
+    }
+
+    pub fn get(&self, venue: &Venue) -> Option<&VenueSchema> {
+        self.schemas.get(&venue.to_string())
+    }
+
+    /// The built-in symbol table plus every schema's `symbols`
+    pub fn symbol_mapper(&self) -> SymbolMapper {
+        let mut mapper = SymbolMapper::builtin();
+        for (venue, schema) in &self.schemas {
+            if let Ok(venue) = venue.parse::<Venue>() {
+                let entries = schema.symbols.iter().map(|(exchange, ours)| (exchange.clone(), Symbol(ours.clone())));
+                mapper.extend(&venue, entries);
+            }
+        }
+        mapper
+    }
+}
+
+/// Applies paths to one message, reporting failures with their context
+struct Mapper<'a> {
+    venue: &'a Venue,
+}
+
+impl Mapper<'_> {
+    /// The value at `path` under `base`
+    fn field<'v>(&self, path: &FieldPath, base: &'v Value) -> Result<&'v Value, IngestionError> {
+        path.resolve(base).map_err(|parent| self.error(path, "missing field", parent))
+    }
+
+    /// The value at `path` under `base`, converted
+    fn convert<T>(
+        &self,
+        path: &FieldPath,
+        base: &Value,
+        convert: impl FnOnce(&Value) -> Result<T, String>,
+    ) -> Result<T, IngestionError> {
+        self.convert_at(path, path, base, convert)
+    }
+
+    /// As `convert`, with `shown` as the path in errors (the full path of a
+    /// field inside an array element)
+    fn convert_at<T>(
+        &self,
+        shown: &FieldPath,
+        path: &FieldPath,
+        base: &Value,
+        convert: impl FnOnce(&Value) -> Result<T, String>,
+    ) -> Result<T, IngestionError> {
+        let value = path.resolve(base).map_err(|parent| self.error(shown, "missing field", parent))?;
+        convert(value).map_err(|reason| self.error(shown, &reason, value))
+    }
+
+    fn error(&self, path: &FieldPath, reason: &str, fragment: &Value) -> IngestionError {
+        let mut fragment = fragment.to_string();
+        if fragment.len() > MAX_FRAGMENT {
+            let end = (0..=MAX_FRAGMENT).rev().find(|&end| fragment.is_char_boundary(end)).unwrap_or(0);
+            fragment.truncate(end);
+            fragment.push('…');
+        }
+        IngestionError::Mapping {
+            venue: self.venue.to_string(),
+            path: path.to_string(),
+            reason: reason.to_string(),
+            fragment,
+        }
+    }
+}
+
+fn text(value: &Value) -> Result<String, String> {
+    value.as_str().map(str::to_string).ok_or_else(|| "expected a string".to_string())
+}
+
+fn price(value: &Value) -> Result<Price, String> {
+    normalize_price(value).map_err(|e| e.to_string())
+}
+
+fn quantity(value: &Value) -> Result<Quantity, String> {
+    normalize_quantity(value).map_err(|e| e.to_string())
+}
+
+/// The digits of an integer given as a JSON number or numeric string
+fn integer_text(value: &Value) -> Result<String, String> {
+    match value {
+        Value::Number(number) => Ok(number.to_string()),
+        Value::String(text) => Ok(text.clone()),
+        _ => Err("expected a number or numeric string".to_string()),
+    }
+}
+
+fn timestamp(value: &Value, unit: TimestampUnit) -> Result<DateTime<Utc>, String> {
+    normalize_timestamp_in(value, unit).map_err(|e| e.to_string())
+}
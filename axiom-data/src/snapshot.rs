@@ -0,0 +1,286 @@
+//! Book Snapshots: REST Depth for Startup and Resync
+//!
+//! Binance spot `GET /api/v3/depth?symbol=&limit=` answers
+//! `{"lastUpdateId", "bids": [[price, quantity], ...], "asks": [...]}`;
+//! Bybit v5 `GET /v5/market/orderbook?category=spot&symbol=&limit=` answers
+//! `{"retCode", "result": {"s", "b", "a", "ts", "u", "seq"}}`. Both come
+//! back in the `{"bids", "asks"}` shape `OrderBookBuilder` takes, with the
+//! update id to align increments against (`lastUpdateId`, `u`).
+//!
+//! Every request first takes its weight from the venue's token bucket. A
+//! 429 or 418, a 5xx or a network failure is retried with jittered
+//! exponential backoff, never sooner than a Retry-After header asks, up to
+//! `max_attempts`; any other failure is returned at once.
+
+use axiom_core::{OrderBook, Symbol, Venue};
+use crate::errors::*;
+use crate::normalization::SymbolMapper;
+use crate::orderbook::OrderBookBuilder;
+use crate::ratelimit::TokenBucket;
+use chrono::{DateTime, Utc};
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::warn;
+
+/// Binance spot depth (mainnet)
+pub const BINANCE_DEPTH_URL: &str = "https://api.binance.com/api/v3/depth";
+
+/// Bybit spot order book (mainnet)
+pub const BYBIT_ORDERBOOK_URL: &str = "https://api.bybit.com/v5/market/orderbook?category=spot";
+
+/// Levels requested per side unless configured
+pub const DEFAULT_SNAPSHOT_DEPTH: u32 = 100;
+
+/// Bybit's retCode for too many requests
+const BYBIT_RATE_LIMITED: i64 = 10006;
+
+/// A depth snapshot ready for `OrderBookBuilder`
+#[derive(Debug, Clone, PartialEq)]
+pub struct BookSnapshot {
+    pub symbol: Symbol,
+    pub venue: Venue,
+    /// `{"bids": [[price, quantity], ...], "asks": [...]}`
+    pub body: Value,
+    /// Exchange update id the snapshot is at
+    pub last_update_id: u64,
+}
+
+impl BookSnapshot {
+    /// Resync `builder` from this snapshot, replaying the updates it held
+    pub fn apply(&self, builder: &mut OrderBookBuilder, timestamp: DateTime<Utc>) -> Result<Arc<OrderBook>, IngestionError> {
+        builder.from_sequenced_snapshot(&self.body, self.last_update_id, timestamp)
+    }
+}
+
+/// How a failed request is retried
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Attempts in all, the first included
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self { max_attempts: 5, base_delay: Duration::from_millis(500), max_delay: Duration::from_secs(30) }
+    }
+}
+
+impl RetryPolicy {
+    /// Wait before retry `attempt` (1 for the first): doubling from
+    /// `base_delay` up to `max_delay`, less a random share of up to half
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        jitter(self.base_delay.saturating_mul(factor).min(self.max_delay))
+    }
+}
+
+/// One venue's depth endpoint and its request budget
+#[derive(Debug)]
+struct Endpoint {
+    url: String,
+    limiter: TokenBucket,
+}
+
+/// Fetches book snapshots over REST within each venue's rate limit
+#[derive(Debug, Clone)]
+pub struct SnapshotFetcher {
+    client: reqwest::Client,
+    symbols: Arc<SymbolMapper>,
+    endpoints: HashMap<Venue, Arc<Endpoint>>,
+    depth: u32,
+    retry: RetryPolicy,
+}
+
+impl SnapshotFetcher {
+    pub fn new() -> Self {
+        let endpoints = HashMap::from([
+            (Venue::Binance, Arc::new(Endpoint {
+                url: BINANCE_DEPTH_URL.to_string(),
+                limiter: TokenBucket::per_window(6000, Duration::from_secs(60)),
+            })),
+            (Venue::Bybit, Arc::new(Endpoint {
+                url: BYBIT_ORDERBOOK_URL.to_string(),
+                limiter: TokenBucket::per_window(600, Duration::from_secs(5)),
+            })),
+        ]);
+        Self {
+            client: reqwest::Client::new(),
+            symbols: Arc::new(SymbolMapper::builtin()),
+            endpoints,
+            depth: DEFAULT_SNAPSHOT_DEPTH,
+            retry: RetryPolicy::default(),
+        }
+    }
+
+    /// Map symbols with `symbols` instead of the built-in table
+    pub fn with_symbols(mut self, symbols: Arc<SymbolMapper>) -> Self {
+        self.symbols = symbols;
+        self
+    }
+
+    /// Ask for `depth` levels per side
+    pub fn with_depth(mut self, depth: u32) -> Self {
+        self.depth = depth.max(1);
+        self
+    }
+
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Query `url` for `venue`'s snapshots instead of mainnet (testnet, a
+    /// proxy, a mock server), keeping its rate limit; a venue without one
+    /// gets 600 requests a minute and is read in Binance's format
+    pub fn with_url(mut self, venue: Venue, url: impl Into<String>) -> Self {
+        let limiter = match self.endpoints.remove(&venue).and_then(Arc::into_inner) {
+            Some(endpoint) => endpoint.limiter,
+            None => TokenBucket::per_window(600, Duration::from_secs(60)),
+        };
+        self.endpoints.insert(venue, Arc::new(Endpoint { url: url.into(), limiter }));
+        self
+    }
+
+    /// Hold `venue`'s requests to `limiter` instead of its documented limit
+    pub fn with_rate_limit(mut self, venue: Venue, limiter: TokenBucket) -> Self {
+        if let Some(endpoint) = self.endpoints.remove(&venue) {
+            self.endpoints.insert(venue, Arc::new(Endpoint { url: endpoint.url.clone(), limiter }));
+        }
+        self
+    }
+
+    /// Whether `venue` has a depth endpoint
+    pub fn supports(&self, venue: &Venue) -> bool {
+        self.endpoints.contains_key(venue)
+    }
+
+    /// `symbol`'s book on `venue`
+    pub async fn fetch(&self, venue: &Venue, symbol: &Symbol) -> Result<BookSnapshot, IngestionError> {
+        let endpoint = self.endpoints.get(venue)
+            .ok_or_else(|| IngestionError::ExchangeApi(format!("No depth endpoint for {}", venue)))?;
+        let native = self.symbols.exchange(venue, symbol)?;
+        let separator = if endpoint.url.contains('?') { '&' } else { '?' };
+        let url = format!("{}{}symbol={}&limit={}", endpoint.url, separator, native, self.depth);
+        let weight = match venue {
+            Venue::Binance => binance_depth_weight(self.depth),
+            _ => 1,
+        };
+        let body = self.get(venue, endpoint, &url, weight).await?;
+        let (body, last_update_id) = match venue {
+            Venue::Bybit => parse_bybit_orderbook(&body)?,
+            _ => parse_binance_depth(&body)?,
+        };
+        Ok(BookSnapshot { symbol: symbol.clone(), venue: venue.clone(), body, last_update_id })
+    }
+
+    /// GET `url` within `endpoint`'s budget, retrying what may pass
+    async fn get(&self, venue: &Venue, endpoint: &Endpoint, url: &str, weight: u32) -> Result<Value, IngestionError> {
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            endpoint.limiter.acquire(weight).await;
+            let result = self.request(url, venue).await;
+            let retry = match &result {
+                Err(IngestionError::RateLimited { retry_after }) => {
+                    // The venue has counted more than we have; stop spending
+                    endpoint.limiter.drain();
+                    Some(*retry_after)
+                }
+                Err(IngestionError::HttpStatus { code }) if *code >= 500 => Some(None),
+                Err(IngestionError::Network(_) | IngestionError::Timeout(_)) => Some(None),
+                _ => None,
+            };
+            let Some(retry_after) = retry.filter(|_| attempt < self.retry.max_attempts) else {
+                return result;
+            };
+            let delay = self.retry.delay(attempt).max(retry_after.unwrap_or_default());
+            if let Err(e) = &result {
+                warn!("{} snapshot attempt {} failed: {}; retrying in {:?}", venue, attempt, e, delay);
+            }
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    async fn request(&self, url: &str, venue: &Venue) -> Result<Value, IngestionError> {
+        let response = self.client.get(url)
+            .send()
+            .await
+            .map_err(|e| if e.is_timeout() {
+                IngestionError::Timeout(format!("{} snapshot: {}", venue, e))
+            } else {
+                IngestionError::Network(format!("{} snapshot: {}", venue, e))
+            })?;
+        let status = response.status();
+        if status == StatusCode::TOO_MANY_REQUESTS || status == StatusCode::IM_A_TEAPOT {
+            let retry_after = response.headers().get(reqwest::header::RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.trim().parse::<u64>().ok())
+                .map(Duration::from_secs);
+            return Err(IngestionError::RateLimited { retry_after });
+        }
+        if !status.is_success() {
+            return Err(IngestionError::HttpStatus { code: status.as_u16() });
+        }
+        let body: Value = response.json().await
+            .map_err(|e| IngestionError::InvalidFormat(format!("{} snapshot: {}", venue, e)))?;
+        // Bybit reports its limit in a successful response
+        if body.get("retCode").and_then(Value::as_i64) == Some(BYBIT_RATE_LIMITED) {
+            return Err(IngestionError::RateLimited { retry_after: None });
+        }
+        Ok(body)
+    }
+}
+
+impl Default for SnapshotFetcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Binance's depth request weight by `limit`
+pub fn binance_depth_weight(limit: u32) -> u32 {
+    match limit {
+        0..=100 => 5,
+        101..=500 => 25,
+        501..=1000 => 50,
+        _ => 250,
+    }
+}
+
+/// A depth response as the builder's snapshot and its `lastUpdateId`
+pub fn parse_binance_depth(body: &Value) -> Result<(Value, u64), IngestionError> {
+    let last_update_id = body.get("lastUpdateId").and_then(Value::as_u64)
+        .ok_or_else(|| IngestionError::InvalidFormat("Binance depth has no lastUpdateId".to_string()))?;
+    let side = |key: &str| body.get(key).cloned()
+        .ok_or_else(|| IngestionError::InvalidFormat(format!("Binance depth has no {}", key)));
+    Ok((json!({ "bids": side("bids")?, "asks": side("asks")? }), last_update_id))
+}
+
+/// An orderbook response as the builder's snapshot and its update id `u`
+pub fn parse_bybit_orderbook(body: &Value) -> Result<(Value, u64), IngestionError> {
+    if let Some(code) = body.get("retCode").and_then(Value::as_i64).filter(|code| *code != 0) {
+        let message = body.get("retMsg").and_then(Value::as_str).unwrap_or("no message");
+        return Err(IngestionError::ExchangeApi(format!("Bybit orderbook failed ({}): {}", code, message)));
+    }
+    let result = body.get("result")
+        .ok_or_else(|| IngestionError::InvalidFormat("Bybit orderbook has no result".to_string()))?;
+    let update_id = result.get("u").and_then(Value::as_u64)
+        .ok_or_else(|| IngestionError::InvalidFormat("Bybit orderbook has no update id".to_string()))?;
+    let side = |key: &str| result.get(key).cloned()
+        .ok_or_else(|| IngestionError::InvalidFormat(format!("Bybit orderbook has no {}", key)));
+    Ok((json!({ "bids": side("b")?, "asks": side("a")? }), update_id))
+}
+
+/// `delay` less a random share of up to half, so clients that failed
+/// together do not retry together
+fn jitter(delay: Duration) -> Duration {
+    // The clock's nanoseconds are random enough for spreading retries
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |now| now.subsec_nanos());
+    let share = f64::from(nanos % 1_000_000) / 1_000_000.0;
+    delay.mul_f64(1.0 - share / 2.0)
+}
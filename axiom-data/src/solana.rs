@@ -0,0 +1,358 @@
+//! Solana: Pool Reserves over JSON-RPC
+//!
+//! A pool account is read with `getAccountInfo` (base64) and decoded by its
+//! program's layout to find the two token vaults, whose balances are then
+//! read together with `getMultipleAccounts` as parsed SPL token accounts.
+//! Raydium AMM v4 pools (752 bytes) keep the base and quote vaults at
+//! offsets 336 and 368 and owe the protocol `baseNeedTakePnl` and
+//! `quoteNeedTakePnl` (192, 200) out of them. Orca Whirlpools (653 bytes,
+//! Anchor) keep the Q64.64 square root price at 65, the protocol fees owed
+//! at 85 and 93, and the token A and B vaults at 133 and 213. Liquidity is
+//! both reserves valued in the quote token at the pool's own price.
+
+use axiom_core::Symbol;
+use crate::errors::*;
+use crate::rpc::{EndpointReport, HealthPolicy, JsonRpc};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use rust_decimal::Decimal;
+use rust_decimal::prelude::FromPrimitive;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Raydium's constant-product AMM (v4) program
+pub const RAYDIUM_AMM_V4_PROGRAM: &str = "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8";
+
+/// Orca's concentrated liquidity (Whirlpool) program
+pub const ORCA_WHIRLPOOL_PROGRAM: &str = "whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc";
+
+const RAYDIUM_AMM_V4_LEN: usize = 752;
+const WHIRLPOOL_LEN: usize = 653;
+
+/// 2^64, the scale of a Q64.64 number
+const Q64: u128 = 1 << 64;
+
+/// Program a pool belongs to, which fixes its account layout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PoolDex {
+    RaydiumAmmV4,
+    OrcaWhirlpool,
+}
+
+impl PoolDex {
+    /// Program that owns this kind of pool account
+    pub fn program_id(&self) -> &'static str {
+        match self {
+            PoolDex::RaydiumAmmV4 => RAYDIUM_AMM_V4_PROGRAM,
+            PoolDex::OrcaWhirlpool => ORCA_WHIRLPOOL_PROGRAM,
+        }
+    }
+}
+
+impl std::fmt::Display for PoolDex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PoolDex::RaydiumAmmV4 => write!(f, "Raydium AMM v4"),
+            PoolDex::OrcaWhirlpool => write!(f, "Orca Whirlpool"),
+        }
+    }
+}
+
+/// The pool read for one symbol
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolanaPool {
+    pub dex: PoolDex,
+    /// Pool account address, base58
+    pub address: String,
+    /// The pool's base (Raydium) or token A (Orca) is the symbol's quote
+    pub inverted: bool,
+}
+
+impl SolanaPool {
+    pub fn raydium(address: impl Into<String>) -> Self {
+        Self { dex: PoolDex::RaydiumAmmV4, address: address.into(), inverted: false }
+    }
+
+    pub fn orca(address: impl Into<String>) -> Self {
+        Self { dex: PoolDex::OrcaWhirlpool, address: address.into(), inverted: false }
+    }
+
+    /// Read the pool's tokens the other way round
+    pub fn inverted(mut self) -> Self {
+        self.inverted = true;
+        self
+    }
+}
+
+/// Pools to read on-chain liquidity from, by symbol
+#[derive(Debug, Clone, Default)]
+pub struct PoolRegistry {
+    pools: HashMap<Symbol, SolanaPool>,
+}
+
+impl PoolRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read `symbol`'s liquidity from `pool`, replacing any pool it had
+    pub fn with_pool(mut self, symbol: Symbol, pool: SolanaPool) -> Self {
+        self.pools.insert(symbol, pool);
+        self
+    }
+
+    pub fn get(&self, symbol: &Symbol) -> Option<&SolanaPool> {
+        self.pools.get(symbol)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pools.is_empty()
+    }
+}
+
+/// An account as `getAccountInfo` returned it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawAccount {
+    /// Program that owns the account, base58
+    pub owner: String,
+    pub data: Vec<u8>,
+    /// Slot the account was read at
+    pub slot: u64,
+}
+
+/// What a pool account's layout says about its vaults
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolAccount {
+    /// Raydium's base vault, Orca's token A vault, base58
+    pub vault_a: String,
+    pub vault_b: String,
+    /// Raw amounts held in each vault that belong to the protocol
+    pub owed_a: u64,
+    pub owed_b: u64,
+    /// Q64.64 square root of raw B per raw A, for concentrated pools
+    pub sqrt_price_x64: Option<u128>,
+}
+
+/// A vault's raw balance and its token's decimals
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenAmount {
+    pub amount: u64,
+    pub decimals: u32,
+}
+
+/// A pool's reserves and their value in the quote token
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolLiquidity {
+    pub symbol: Symbol,
+    pub pool: SolanaPool,
+    pub base_reserve: Decimal,
+    pub quote_reserve: Decimal,
+    /// Quote per base at the pool's price
+    pub price: Decimal,
+    /// Both reserves in quote
+    pub liquidity: Decimal,
+    /// Slot the pool account was read at
+    pub slot: u64,
+}
+
+/// Solana JSON-RPC, failing over between endpoints by health
+#[derive(Debug, Clone)]
+pub struct SolanaRpc {
+    rpc: JsonRpc,
+}
+
+impl SolanaRpc {
+    pub fn new(endpoints: Vec<String>) -> Self {
+        Self { rpc: JsonRpc::new("Solana", "getHealth", endpoints) }
+    }
+
+    /// Give each endpoint `timeout` to answer before trying the next
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.rpc = self.rpc.with_timeout(timeout);
+        self
+    }
+
+    pub fn with_health_policy(mut self, policy: HealthPolicy) -> Self {
+        self.rpc = self.rpc.with_health_policy(policy);
+        self
+    }
+
+    pub fn endpoints(&self) -> &[String] {
+        self.rpc.endpoints()
+    }
+
+    /// Every endpoint's health, in configured order
+    pub fn endpoint_health(&self) -> Vec<EndpointReport> {
+        self.rpc.endpoint_health()
+    }
+
+    /// `method`'s result from the first endpoint able to answer
+    pub async fn call(&self, method: &str, params: Value) -> Result<Value, IngestionError> {
+        self.rpc.call(method, params).await
+    }
+
+    /// An account's owner and data
+    pub async fn account(&self, address: &str) -> Result<RawAccount, IngestionError> {
+        let result = self.call("getAccountInfo", json!([address, { "encoding": "base64", "commitment": "confirmed" }])).await?;
+        decode_account_info(&result, address)
+    }
+
+    /// `pool`'s reserves and liquidity in `symbol`'s quote token
+    pub async fn pool_liquidity(&self, symbol: &Symbol, pool: &SolanaPool) -> Result<PoolLiquidity, IngestionError> {
+        let account = self.account(&pool.address).await?;
+        if account.owner != pool.dex.program_id() {
+            return Err(IngestionError::InvalidFormat(format!(
+                "{} is owned by {}, not the {} program", pool.address, account.owner, pool.dex,
+            )));
+        }
+        let decoded = decode_pool_account(pool.dex, &account.data)?;
+        let vaults = self.call(
+            "getMultipleAccounts",
+            json!([[decoded.vault_a, decoded.vault_b], { "encoding": "jsonParsed", "commitment": "confirmed" }]),
+        ).await?;
+        let (vault_a, vault_b) = parse_vault_balances(&vaults)?;
+        pool_liquidity(symbol, pool, &decoded, vault_a, vault_b, account.slot)
+    }
+}
+
+/// A base64 `getAccountInfo` result,
+/// `{"context": {"slot"}, "value": {"data": [data, "base64"], "owner", ...}}`
+pub fn decode_account_info(result: &Value, address: &str) -> Result<RawAccount, IngestionError> {
+    let invalid = |what: &str| IngestionError::InvalidFormat(format!("Account {} has no {}", address, what));
+    let slot = result.pointer("/context/slot").and_then(Value::as_u64).ok_or_else(|| invalid("slot"))?;
+    let value = result.get("value").filter(|value| !value.is_null())
+        .ok_or_else(|| IngestionError::ExchangeApi(format!("Account {} does not exist", address)))?;
+    let owner = value.get("owner").and_then(Value::as_str).ok_or_else(|| invalid("owner"))?;
+    let encoded = value.pointer("/data/0").and_then(Value::as_str).ok_or_else(|| invalid("base64 data"))?;
+    let data = BASE64.decode(encoded)
+        .map_err(|e| IngestionError::InvalidFormat(format!("Account {} data: {}", address, e)))?;
+    Ok(RawAccount { owner: owner.to_string(), data, slot })
+}
+
+/// Vaults, protocol debts and price from a `dex` pool account's data
+pub fn decode_pool_account(dex: PoolDex, data: &[u8]) -> Result<PoolAccount, IngestionError> {
+    let expected = match dex {
+        PoolDex::RaydiumAmmV4 => RAYDIUM_AMM_V4_LEN,
+        PoolDex::OrcaWhirlpool => WHIRLPOOL_LEN,
+    };
+    if data.len() != expected {
+        return Err(IngestionError::InvalidFormat(format!(
+            "{} pool account is {} bytes, not {}", dex, data.len(), expected,
+        )));
+    }
+    match dex {
+        PoolDex::RaydiumAmmV4 => Ok(PoolAccount {
+            vault_a: pubkey(data, 336)?,
+            vault_b: pubkey(data, 368)?,
+            owed_a: u64::from_le_bytes(read(data, 192)?),
+            owed_b: u64::from_le_bytes(read(data, 200)?),
+            sqrt_price_x64: None,
+        }),
+        PoolDex::OrcaWhirlpool => Ok(PoolAccount {
+            vault_a: pubkey(data, 133)?,
+            vault_b: pubkey(data, 213)?,
+            owed_a: u64::from_le_bytes(read(data, 85)?),
+            owed_b: u64::from_le_bytes(read(data, 93)?),
+            sqrt_price_x64: Some(u128::from_le_bytes(read(data, 65)?)),
+        }),
+    }
+}
+
+/// Both vaults' balances from a jsonParsed `getMultipleAccounts` result,
+/// `{"value": [{"data": {"parsed": {"info": {"tokenAmount": {"amount", "decimals"}}}}}, ...]}`
+pub fn parse_vault_balances(result: &Value) -> Result<(TokenAmount, TokenAmount), IngestionError> {
+    let accounts = result.get("value").and_then(Value::as_array)
+        .ok_or_else(|| IngestionError::InvalidFormat("Vault accounts missing".to_string()))?;
+    match accounts.as_slice() {
+        [a, b] => Ok((parse_token_amount(a)?, parse_token_amount(b)?)),
+        other => Err(IngestionError::InvalidFormat(format!("Expected 2 vault accounts, got {}", other.len()))),
+    }
+}
+
+/// One parsed SPL token account's balance
+pub fn parse_token_amount(account: &Value) -> Result<TokenAmount, IngestionError> {
+    let invalid = |what: &str| IngestionError::InvalidFormat(format!("Vault account has no {}", what));
+    let token_amount = account.pointer("/data/parsed/info/tokenAmount").ok_or_else(|| invalid("token amount"))?;
+    let amount = token_amount.get("amount").and_then(Value::as_str)
+        .and_then(|amount| amount.parse().ok())
+        .ok_or_else(|| invalid("amount"))?;
+    let decimals = token_amount.get("decimals").and_then(Value::as_u64)
+        .and_then(|decimals| u32::try_from(decimals).ok())
+        .ok_or_else(|| invalid("decimals"))?;
+    Ok(TokenAmount { amount, decimals })
+}
+
+/// `pool`'s reserves, less what the protocol is owed, valued in `symbol`'s
+/// quote token at the pool's price: the square root price for a
+/// concentrated pool, the reserve ratio for a constant-product one
+pub fn pool_liquidity(
+    symbol: &Symbol,
+    pool: &SolanaPool,
+    account: &PoolAccount,
+    vault_a: TokenAmount,
+    vault_b: TokenAmount,
+    slot: u64,
+) -> Result<PoolLiquidity, IngestionError> {
+    let overflow = || IngestionError::InvalidFormat(format!("{} pool {} overflows a decimal", pool.dex, pool.address));
+    let reserve_a = reserve(vault_a, account.owed_a)?;
+    let reserve_b = reserve(vault_b, account.owed_b)?;
+
+    // Token B per token A, in whole tokens
+    let price_b_per_a = match account.sqrt_price_x64 {
+        Some(sqrt_price) => {
+            let root = Decimal::from_u128(sqrt_price)
+                .zip(Decimal::from_u128(Q64))
+                .and_then(|(sqrt_price, q64)| sqrt_price.checked_div(q64))
+                .ok_or_else(overflow)?;
+            root.checked_mul(root)
+                .and_then(|raw| raw.checked_mul(unit(vault_b.decimals)?))
+                .and_then(|price| price.checked_div(unit(vault_a.decimals)?))
+                .ok_or_else(overflow)?
+        }
+        None => reserve_b.checked_div(reserve_a)
+            .ok_or_else(|| IngestionError::InvalidFormat(format!("{} pool {} is empty", pool.dex, pool.address)))?,
+    };
+
+    let (base_reserve, quote_reserve, price) = if pool.inverted {
+        let price = Decimal::ONE.checked_div(price_b_per_a)
+            .ok_or_else(|| IngestionError::InvalidFormat(format!("{} pool {} has no price", pool.dex, pool.address)))?;
+        (reserve_b, reserve_a, price)
+    } else {
+        (reserve_a, reserve_b, price_b_per_a)
+    };
+    let liquidity = base_reserve.checked_mul(price)
+        .and_then(|base_value| base_value.checked_add(quote_reserve))
+        .ok_or_else(overflow)?;
+
+    Ok(PoolLiquidity {
+        symbol: symbol.clone(),
+        pool: pool.clone(),
+        base_reserve,
+        quote_reserve,
+        price,
+        liquidity,
+        slot,
+    })
+}
+
+/// A vault's balance less `owed`, in whole tokens
+fn reserve(vault: TokenAmount, owed: u64) -> Result<Decimal, IngestionError> {
+    Decimal::try_from_i128_with_scale(i128::from(vault.amount.saturating_sub(owed)), vault.decimals)
+        .map_err(|e| IngestionError::InvalidFormat(format!("Vault balance with {} decimals: {}", vault.decimals, e)))
+}
+
+/// One raw unit of a token with `decimals` decimals, in whole tokens
+fn unit(decimals: u32) -> Option<Decimal> {
+    Decimal::try_from_i128_with_scale(1, decimals).ok()
+}
+
+fn read<const N: usize>(data: &[u8], offset: usize) -> Result<[u8; N], IngestionError> {
+    data.get(offset..offset + N)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| IngestionError::InvalidFormat(format!("Pool account has no {} bytes at {}", N, offset)))
+}
+
+fn pubkey(data: &[u8], offset: usize) -> Result<String, IngestionError> {
+    Ok(bs58::encode(read::<32>(data, offset)?).into_string())
+}
@@ -0,0 +1,267 @@
+//! Bybit v5 Public Feed
+//!
+//! Spot and derivatives public streams share one layout. Trades arrive
+//! several to a message:
+//! `{"topic": "publicTrade.BTCUSDT", "ts": ..., "data": [{"T", "s", "S", "v", "p"}, ...]}`;
+//! books as a snapshot followed by deltas:
+//! `{"topic": "orderbook.50.BTCUSDT", "type": "snapshot" | "delta", "ts": ...,
+//! "data": {"s", "b": [[price, size], ...], "a": [...], "u", "seq"}}`.
+//! Each delta's update id `u` follows the previous one by exactly one, and
+//! a size of zero removes the level. Bybit sends a fresh snapshot whenever
+//! its side resets (with `u` = 1 after a service restart), so a snapshot
+//! always replaces the local book. A delta that skips an id, or arrives
+//! with no snapshot to apply to, asks for the topic to be resubscribed,
+//! which Bybit answers with a snapshot. The book's builder holds deltas
+//! until then and replays those newer than the snapshot; a delta the book
+//! already covers is skipped.
+//!
+//! On linear (derivatives) connections the adapter can also subscribe to
+//! `allLiquidation.<symbol>`; see `liquidations` for the message.
+
+use axiom_core::{LatencyRecorder, OrderBook, Symbol, Tick, Venue};
+use crate::errors::*;
+use crate::ingestion::normalize_tick;
+use crate::liquidations::normalize_bybit_liquidations;
+use crate::normalization::SymbolMapper;
+use crate::orderbook::{OrderBookBuilder, UpdateIds};
+use crate::venues::{VenueAdapter, VenueEvent};
+use chrono::Utc;
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tracing::warn;
+
+/// Book depth subscribed to unless configured (spot offers 1, 50 and 200)
+pub const BYBIT_DEFAULT_DEPTH: u32 = 50;
+
+/// One symbol's book and the builder tracking its update ids
+struct BookState {
+    builder: OrderBookBuilder,
+    book: Arc<OrderBook>,
+}
+
+/// Protocol state for one Bybit v5 public connection
+pub struct BybitAdapter {
+    venue: Venue,
+    depth: u32,
+    /// Bybit symbols to ours ("BTCUSDT" = "BTC/USD")
+    symbols: Arc<SymbolMapper>,
+    /// By Bybit symbol
+    books: HashMap<String, BookState>,
+    /// Bybit symbols waiting for a snapshot
+    resyncing: HashSet<String>,
+    /// Subscribe to forced orders as well (linear connections only)
+    liquidations: bool,
+    latency: Option<Arc<dyn LatencyRecorder>>,
+}
+
+impl BybitAdapter {
+    pub fn new() -> Self {
+        Self {
+            venue: Venue::Bybit,
+            depth: BYBIT_DEFAULT_DEPTH,
+            symbols: Arc::new(SymbolMapper::builtin()),
+            books: HashMap::new(),
+            resyncing: HashSet::new(),
+            liquidations: false,
+            latency: None,
+        }
+    }
+
+    /// Translate Bybit symbols with `symbols` instead of the built-in table
+    pub fn with_symbols(mut self, symbols: Arc<SymbolMapper>) -> Self {
+        self.symbols = symbols;
+        self
+    }
+
+    /// Subscribe to books `depth` levels deep
+    pub fn with_depth(mut self, depth: u32) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    /// Subscribe to each symbol's liquidations too; spot connections have
+    /// no such topic
+    pub fn with_liquidations(mut self, liquidations: bool) -> Self {
+        self.liquidations = liquidations;
+        self
+    }
+
+    /// Report book_apply latency
+    pub fn with_latency_recorder(mut self, latency: Arc<dyn LatencyRecorder>) -> Self {
+        self.latency = Some(latency);
+        self
+    }
+
+    fn book_topic(&self, native: &str) -> String {
+        format!("orderbook.{}.{}", self.depth, native)
+    }
+
+    /// Topics for `symbols`; one Bybit does not list is left out
+    fn topics(&self, symbols: &[Symbol]) -> Vec<String> {
+        symbols.iter()
+            .filter_map(|symbol| match self.symbols.exchange(&self.venue, symbol) {
+                Ok(native) => Some(native),
+                Err(e) => {
+                    warn!("Not subscribing on Bybit: {}", e);
+                    None
+                }
+            })
+            .flat_map(|native| {
+                let mut topics = vec![format!("publicTrade.{}", native), self.book_topic(native)];
+                if self.liquidations {
+                    topics.push(format!("allLiquidation.{}", native));
+                }
+                topics
+            })
+            .collect()
+    }
+
+    /// A subscription acknowledgement, pong, or rejected request
+    fn control(&self, message: &Value) -> Result<VenueEvent, IngestionError> {
+        if message.get("success").and_then(Value::as_bool) == Some(false) {
+            let reason = message.get("ret_msg").and_then(Value::as_str).unwrap_or("no reason given");
+            let op = message.get("op").and_then(Value::as_str).unwrap_or("request");
+            return Err(IngestionError::ExchangeApi(format!("Bybit {} failed: {}", op, reason)));
+        }
+        Ok(VenueEvent::Control)
+    }
+
+    /// Every trade in a publicTrade message, through the canonical tick shape
+    fn trades(&self, message: &Value) -> Result<Vec<Tick>, IngestionError> {
+        let trades = field(message, "data")?.as_array()
+            .ok_or_else(|| IngestionError::InvalidFormat("Bybit trade data is not an array".to_string()))?;
+        trades.iter()
+            .map(|trade| {
+                let canonical = json!({
+                    "symbol": text(trade, "s")?,
+                    "price": field(trade, "p")?,
+                    "quantity": field(trade, "v")?,
+                    "timestamp": field(trade, "T")?,
+                    "side": field(trade, "S")?,
+                });
+                normalize_tick(&canonical, &self.venue, &self.symbols)
+            })
+            .collect()
+    }
+
+    /// Apply a book snapshot or delta
+    fn book(&mut self, message: &Value) -> Result<VenueEvent, IngestionError> {
+        let data = field(message, "data")?;
+        let native = text(data, "s")?.to_string();
+        let symbol = self.symbols.canonical(&self.venue, &native)?;
+        let update_id = field(data, "u")?.as_u64()
+            .ok_or_else(|| IngestionError::InvalidFormat("Bybit book update id is not an unsigned integer".to_string()))?;
+        let levels = json!({ "bids": field(data, "b")?, "asks": field(data, "a")? });
+
+        match text(message, "type")? {
+            "snapshot" => {
+                // Keep the builder, so book sequence numbers keep rising across
+                // resyncs and deltas held since a gap are replayed
+                let mut builder = match self.books.remove(&native) {
+                    Some(state) => state.builder,
+                    None => self.builder(&native)?,
+                };
+                let book = builder.from_sequenced_snapshot(&levels, update_id, Utc::now())?;
+                let gap = builder.needs_resync();
+                self.resyncing.remove(&native);
+                self.books.insert(native.clone(), BookState { builder, book: book.clone() });
+                if gap {
+                    return Ok(self.resync(native, symbol, "gap in deltas held for the snapshot".to_string()));
+                }
+                Ok(VenueEvent::Book(book))
+            }
+            "delta" => {
+                let Some(state) = self.books.get_mut(&native) else {
+                    if self.resyncing.contains(&native) {
+                        return Ok(VenueEvent::Control);
+                    }
+                    return Ok(self.resync(native, symbol, "delta before any snapshot".to_string()));
+                };
+                let applied = state.builder.apply_update(&state.book, &levels, UpdateIds::single(update_id));
+                let gap = state.builder.needs_resync();
+                match applied {
+                    Ok(Some(book)) => {
+                        state.book = book.clone();
+                        Ok(VenueEvent::Book(book))
+                    }
+                    // Held for the snapshot already asked for
+                    Ok(None) => Ok(VenueEvent::Control),
+                    Err(IngestionError::SequenceGap { expected, got }) if gap => {
+                        Ok(self.resync(native, symbol, format!("expected update {}, got {}", expected, got)))
+                    }
+                    Err(IngestionError::SequenceGap { expected, got }) => {
+                        warn!("Bybit {} delta {} is stale (book expects {}); skipped", native, got, expected);
+                        Ok(VenueEvent::Control)
+                    }
+                    Err(e) => Err(e),
+                }
+            }
+            other => Err(IngestionError::InvalidFormat(format!("Unknown Bybit book message type {:?}", other))),
+        }
+    }
+
+    fn builder(&self, native: &str) -> Result<OrderBookBuilder, IngestionError> {
+        let builder = OrderBookBuilder::new(&self.symbols, self.venue.clone(), native)?;
+        Ok(match &self.latency {
+            Some(latency) => builder.with_latency_recorder(latency.clone()),
+            None => builder,
+        })
+    }
+
+    /// Ask for `native`'s topic again; its builder holds deltas meanwhile
+    fn resync(&mut self, native: String, symbol: Symbol, reason: String) -> VenueEvent {
+        warn!("Bybit {} book out of sync ({}); resubscribing", native, reason);
+        let topic = self.book_topic(&native);
+        let requests = vec![
+            json!({ "op": "unsubscribe", "args": [topic] }),
+            json!({ "op": "subscribe", "args": [topic] }),
+        ];
+        self.resyncing.insert(native);
+        VenueEvent::Resync { symbol, requests }
+    }
+}
+
+impl Default for BybitAdapter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VenueAdapter for BybitAdapter {
+    fn venue(&self) -> &Venue {
+        &self.venue
+    }
+
+    fn subscribe(&self, symbols: &[Symbol]) -> Vec<Value> {
+        vec![json!({ "op": "subscribe", "args": self.topics(symbols) })]
+    }
+
+    fn unsubscribe(&self, symbols: &[Symbol]) -> Vec<Value> {
+        vec![json!({ "op": "unsubscribe", "args": self.topics(symbols) })]
+    }
+
+    fn handle(&mut self, raw: &[u8]) -> Result<VenueEvent, IngestionError> {
+        let message: Value = serde_json::from_slice(raw)
+            .map_err(|e| IngestionError::InvalidFormat(format!("JSON parse: {}", e)))?;
+        match message.get("topic").and_then(Value::as_str) {
+            Some(topic) if topic.starts_with("publicTrade.") => self.trades(&message).map(VenueEvent::Ticks),
+            Some(topic) if topic.starts_with("orderbook.") => self.book(&message),
+            Some(topic) if topic.starts_with("allLiquidation.") => {
+                normalize_bybit_liquidations(&message, &self.symbols).map(VenueEvent::Liquidations)
+            }
+            // Topics not subscribed through this adapter
+            Some(_) => Ok(VenueEvent::Control),
+            None => self.control(&message),
+        }
+    }
+}
+
+fn field<'a>(value: &'a Value, key: &str) -> Result<&'a Value, IngestionError> {
+    value.get(key).ok_or_else(|| IngestionError::InvalidFormat(format!("Bybit message missing {:?}", key)))
+}
+
+fn text<'a>(value: &'a Value, key: &str) -> Result<&'a str, IngestionError> {
+    field(value, key)?.as_str()
+        .ok_or_else(|| IngestionError::InvalidFormat(format!("Bybit field {:?} is not a string", key)))
+}
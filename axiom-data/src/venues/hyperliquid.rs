@@ -0,0 +1,290 @@
+//! Hyperliquid Feed and Market State
+//!
+//! The websocket feed sends whole books, not deltas:
+//! `{"channel": "l2Book", "data": {"coin", "time", "levels": [[bids...], [asks...]]}}`
+//! with each level `{"px", "sz", "n"}`, and trades several to a message:
+//! `{"channel": "trades", "data": [{"coin", "side": "B" | "A", "px", "sz", "time"}, ...]}`.
+//! Every book message replaces the last one, so there is no sequence to
+//! keep. Markets are named by coin ("BTC"), quoted in USD.
+//!
+//! Open interest and funding are not on the feed; `HyperliquidInfo` reads
+//! them from the info endpoint for `OnChainFetcher` and the edge check.
+
+use axiom_core::{Amount, FundingEstimate, LatencyRecorder, Price, Quantity, Symbol, Tick, UnitError, Venue};
+use crate::errors::*;
+use crate::ingestion::normalize_tick;
+use crate::normalization::*;
+use crate::orderbook::OrderBookBuilder;
+use crate::venues::{VenueAdapter, VenueEvent};
+use chrono::{DateTime, DurationRound, TimeDelta, Utc};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::warn;
+
+/// Public info endpoint (mainnet)
+pub const HYPERLIQUID_INFO_URL: &str = "https://api.hyperliquid.xyz/info";
+
+/// Funding is paid every hour, on the hour
+const FUNDING_INTERVAL_SECS: i64 = 3600;
+
+/// One perpetual's open interest, prices and next funding payment
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HyperliquidMarketState {
+    pub symbol: Symbol,
+    /// Open interest in base units
+    pub open_interest: Quantity,
+    pub mark_price: Price,
+    pub oracle_price: Price,
+    pub funding: FundingEstimate,
+    pub observed_at: DateTime<Utc>,
+}
+
+impl HyperliquidMarketState {
+    /// Open interest valued at the mark price
+    pub fn open_interest_notional(&self) -> Result<Amount, UnitError> {
+        self.mark_price.notional(self.open_interest)
+    }
+}
+
+/// Reads market state from Hyperliquid's info endpoint
+#[derive(Debug, Clone)]
+pub struct HyperliquidInfo {
+    url: String,
+    client: reqwest::Client,
+    /// Coins to ours ("BTC" = "BTC/USD"), shared with the feed adapter
+    symbols: Arc<SymbolMapper>,
+}
+
+impl HyperliquidInfo {
+    pub fn new() -> Self {
+        Self {
+            url: HYPERLIQUID_INFO_URL.to_string(),
+            client: reqwest::Client::new(),
+            symbols: Arc::new(SymbolMapper::builtin()),
+        }
+    }
+
+    /// Query `url` instead of mainnet (testnet, a proxy)
+    pub fn with_url(mut self, url: impl Into<String>) -> Self {
+        self.url = url.into();
+        self
+    }
+
+    /// Translate coins with `symbols` instead of the built-in table
+    pub fn with_symbols(mut self, symbols: Arc<SymbolMapper>) -> Self {
+        self.symbols = symbols;
+        self
+    }
+
+    /// Every listed perpetual's state
+    pub async fn market_states(&self) -> Result<Vec<HyperliquidMarketState>, IngestionError> {
+        let response = self.client.post(&self.url)
+            .json(&json!({ "type": "metaAndAssetCtxs" }))
+            .send()
+            .await
+            .map_err(|e| IngestionError::Network(format!("Hyperliquid info: {}", e)))?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(IngestionError::ExchangeApi(format!("Hyperliquid info returned {}", status)));
+        }
+        let body: Value = response.json().await
+            .map_err(|e| IngestionError::InvalidFormat(format!("Hyperliquid info: {}", e)))?;
+        self.parse_market_states(&body, Utc::now())
+    }
+
+    /// `symbol`'s state; None when Hyperliquid does not list it
+    pub async fn market_state(&self, symbol: &Symbol) -> Result<Option<HyperliquidMarketState>, IngestionError> {
+        let states = self.market_states().await?;
+        Ok(states.into_iter().find(|state| state.symbol == *symbol))
+    }
+
+    /// A `metaAndAssetCtxs` response, `[{"universe": [{"name"}, ...]}, [ctx, ...]]`
+    /// with contexts in universe order, as observed at `observed_at`; coins
+    /// with no symbol mapping are left out
+    pub fn parse_market_states(&self, body: &Value, observed_at: DateTime<Utc>) -> Result<Vec<HyperliquidMarketState>, IngestionError> {
+        let universe = body.get(0).and_then(|meta| meta.get("universe")).and_then(Value::as_array)
+            .ok_or_else(|| IngestionError::InvalidFormat("Hyperliquid meta has no universe".to_string()))?;
+        let contexts = body.get(1).and_then(Value::as_array)
+            .ok_or_else(|| IngestionError::InvalidFormat("Hyperliquid response has no asset contexts".to_string()))?;
+        let next_funding = next_funding_after(observed_at)?;
+
+        let mut states = Vec::new();
+        for (asset, context) in universe.iter().zip(contexts) {
+            // Hyperliquid lists far more markets than we trade
+            let Ok(symbol) = self.symbols.canonical(&Venue::Hyperliquid, text(asset, "name")?) else { continue };
+            let funding = parse_decimal(text(context, "funding")?)?;
+            states.push(HyperliquidMarketState {
+                symbol,
+                open_interest: normalize_quantity(field(context, "openInterest")?)?,
+                mark_price: normalize_price(field(context, "markPx")?)?,
+                oracle_price: normalize_price(field(context, "oraclePx")?)?,
+                funding: FundingEstimate { rate: funding, next_funding },
+                observed_at,
+            });
+        }
+        Ok(states)
+    }
+}
+
+impl Default for HyperliquidInfo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The first funding time strictly after `time`
+fn next_funding_after(time: DateTime<Utc>) -> Result<DateTime<Utc>, IngestionError> {
+    let interval = TimeDelta::seconds(FUNDING_INTERVAL_SECS);
+    time.duration_trunc(interval)
+        .ok()
+        .and_then(|hour| hour.checked_add_signed(interval))
+        .ok_or_else(|| IngestionError::InvalidFormat(format!("No funding time after {}", time)))
+}
+
+/// Protocol state for one Hyperliquid websocket connection
+pub struct HyperliquidAdapter {
+    venue: Venue,
+    info: HyperliquidInfo,
+    /// By coin
+    builders: HashMap<String, OrderBookBuilder>,
+    latency: Option<Arc<dyn LatencyRecorder>>,
+}
+
+impl HyperliquidAdapter {
+    pub fn new() -> Self {
+        Self { venue: Venue::Hyperliquid, info: HyperliquidInfo::new(), builders: HashMap::new(), latency: None }
+    }
+
+    /// Read market state through `info` (and translate coins with its symbols)
+    pub fn with_info(mut self, info: HyperliquidInfo) -> Self {
+        self.info = info;
+        self
+    }
+
+    /// Report book_apply latency
+    pub fn with_latency_recorder(mut self, latency: Arc<dyn LatencyRecorder>) -> Self {
+        self.latency = Some(latency);
+        self
+    }
+
+    /// The info endpoint this adapter reads open interest and funding from
+    pub fn info(&self) -> &HyperliquidInfo {
+        &self.info
+    }
+
+    /// `symbol`'s open interest, prices and next funding payment
+    pub async fn market_state(&self, symbol: &Symbol) -> Result<Option<HyperliquidMarketState>, IngestionError> {
+        self.info.market_state(symbol).await
+    }
+
+    /// Book and trade subscriptions for `symbols`; one Hyperliquid does not
+    /// list is left out
+    fn subscriptions(&self, method: &str, symbols: &[Symbol]) -> Vec<Value> {
+        symbols.iter()
+            .filter_map(|symbol| match self.info.symbols.exchange(&self.venue, symbol) {
+                Ok(coin) => Some(coin),
+                Err(e) => {
+                    warn!("Not subscribing on Hyperliquid: {}", e);
+                    None
+                }
+            })
+            .flat_map(|coin| {
+                ["l2Book", "trades"].map(|channel| json!({
+                    "method": method,
+                    "subscription": { "type": channel, "coin": coin },
+                }))
+            })
+            .collect()
+    }
+
+    /// Every trade in a trades message, through the canonical tick shape
+    fn trades(&self, data: &Value) -> Result<Vec<Tick>, IngestionError> {
+        let trades = data.as_array()
+            .ok_or_else(|| IngestionError::InvalidFormat("Hyperliquid trades data is not an array".to_string()))?;
+        trades.iter()
+            .map(|trade| {
+                // The taker's side: "B" bought (lifted the ask), "A" sold
+                let side = match text(trade, "side")? {
+                    "B" => "buy",
+                    "A" => "sell",
+                    other => return Err(IngestionError::InvalidFormat(format!("Unknown Hyperliquid trade side {:?}", other))),
+                };
+                let canonical = json!({
+                    "symbol": text(trade, "coin")?,
+                    "price": field(trade, "px")?,
+                    "quantity": field(trade, "sz")?,
+                    "timestamp": field(trade, "time")?,
+                    "side": side,
+                });
+                normalize_tick(&canonical, &self.venue, &self.info.symbols)
+            })
+            .collect()
+    }
+
+    /// A whole book, replacing the last one
+    fn book(&mut self, data: &Value) -> Result<VenueEvent, IngestionError> {
+        let coin = text(data, "coin")?;
+        let levels = field(data, "levels")?;
+        let side = |index: usize| -> Result<Vec<Value>, IngestionError> {
+            levels.get(index).and_then(Value::as_array)
+                .ok_or_else(|| IngestionError::InvalidFormat("Hyperliquid book levels are not [bids, asks]".to_string()))?
+                .iter()
+                .map(|level| Ok(json!([field(level, "px")?, field(level, "sz")?])))
+                .collect()
+        };
+        let snapshot = json!({ "bids": side(0)?, "asks": side(1)? });
+
+        if !self.builders.contains_key(coin) {
+            let mut builder = OrderBookBuilder::new(&self.info.symbols, self.venue.clone(), coin)?;
+            if let Some(latency) = &self.latency {
+                builder = builder.with_latency_recorder(latency.clone());
+            }
+            self.builders.insert(coin.to_string(), builder);
+        }
+        let builder = self.builders.get_mut(coin).expect("builder inserted above");
+        Ok(VenueEvent::Book(Arc::new(builder.from_snapshot(&snapshot, Utc::now())?)))
+    }
+}
+
+impl Default for HyperliquidAdapter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VenueAdapter for HyperliquidAdapter {
+    fn venue(&self) -> &Venue {
+        &self.venue
+    }
+
+    fn subscribe(&self, symbols: &[Symbol]) -> Vec<Value> {
+        self.subscriptions("subscribe", symbols)
+    }
+
+    fn unsubscribe(&self, symbols: &[Symbol]) -> Vec<Value> {
+        self.subscriptions("unsubscribe", symbols)
+    }
+
+    fn handle(&mut self, raw: &[u8]) -> Result<VenueEvent, IngestionError> {
+        let message: Value = serde_json::from_slice(raw)
+            .map_err(|e| IngestionError::InvalidFormat(format!("JSON parse: {}", e)))?;
+        let data = message.get("data").unwrap_or(&Value::Null);
+        match message.get("channel").and_then(Value::as_str) {
+            Some("l2Book") => self.book(data),
+            Some("trades") => self.trades(data).map(VenueEvent::Ticks),
+            Some("error") => Err(IngestionError::ExchangeApi(format!("Hyperliquid: {}", data))),
+            // subscriptionResponse, pong, and channels not subscribed here
+            _ => Ok(VenueEvent::Control),
+        }
+    }
+}
+
+fn field<'a>(value: &'a Value, key: &str) -> Result<&'a Value, IngestionError> {
+    value.get(key).ok_or_else(|| IngestionError::InvalidFormat(format!("Hyperliquid message missing {:?}", key)))
+}
+
+fn text<'a>(value: &'a Value, key: &str) -> Result<&'a str, IngestionError> {
+    field(value, key)?.as_str()
+        .ok_or_else(|| IngestionError::InvalidFormat(format!("Hyperliquid field {:?} is not a string", key)))
+}
@@ -0,0 +1,49 @@
+//! Venue Adapters: Native Feed Protocols
+//!
+//! A venue schema (see `schema`) maps one self-contained message. Some
+//! feeds need more than that: subscription requests, batched trades, and
+//! books sent as a snapshot followed by deltas that must arrive in order.
+//! An adapter owns that protocol state for one venue connection, rewrites
+//! each native message into the canonical shapes `normalize_tick` and
+//! `OrderBookBuilder` take, and reports when a book has to be re-requested.
+
+pub mod bybit;
+pub mod hyperliquid;
+
+pub use bybit::*;
+pub use hyperliquid::*;
+
+use axiom_core::{LiquidationEvent, OrderBook, Symbol, Tick, Venue};
+use crate::errors::IngestionError;
+use serde_json::Value;
+use std::sync::Arc;
+
+/// One venue connection's feed protocol
+pub trait VenueAdapter: Send {
+    fn venue(&self) -> &Venue;
+
+    /// Requests subscribing the connection to `symbols`' trades and books
+    fn subscribe(&self, symbols: &[Symbol]) -> Vec<Value>;
+
+    /// Requests undoing `subscribe` for `symbols`
+    fn unsubscribe(&self, symbols: &[Symbol]) -> Vec<Value>;
+
+    /// Interpret one message from the connection
+    fn handle(&mut self, raw: &[u8]) -> Result<VenueEvent, IngestionError>;
+}
+
+/// What one feed message produced
+#[derive(Debug, Clone)]
+pub enum VenueEvent {
+    /// Trades, in the venue's order
+    Ticks(Vec<Tick>),
+    /// The symbol's book after a snapshot or delta
+    Book(Arc<OrderBook>),
+    /// Forced orders, in the venue's order
+    Liquidations(Vec<LiquidationEvent>),
+    /// The symbol's book is out of sync (an update was missed); send
+    /// `requests` to the venue to get a fresh snapshot
+    Resync { symbol: Symbol, requests: Vec<Value> },
+    /// Subscription acknowledgements, pongs and other control traffic
+    Control,
+}
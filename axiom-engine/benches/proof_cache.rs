@@ -0,0 +1,50 @@
+//! Verifier throughput over 10k repeats of one signal: every proof solved
+//! by z3, against proofs reused from the cache after the first.
+//!
+//! cargo bench -p axiom-engine --bench proof_cache
+
+use axiom_core::{Amount, LimitsRegistry, OrderType, Portfolio, Price, Quantity, Side, Symbol, TradeSignal, Venue};
+use axiom_engine::{ProofCacheConfig, Verifier, VerifierConfig};
+use rust_decimal::Decimal;
+use std::time::{Duration, Instant};
+
+const SIGNALS: usize = 10_000;
+
+/// Time to verify `signal` `SIGNALS` times over
+fn measure(verifier: &Verifier, signal: &TradeSignal) -> Duration {
+    let portfolio = Portfolio::new(Amount::new(Decimal::from(2_000_000)));
+    let market = Price::new(Decimal::from(65_000)).unwrap();
+    let start = Instant::now();
+    for _ in 0..SIGNALS {
+        verifier.verify_signal(signal, &portfolio, market).unwrap();
+    }
+    start.elapsed()
+}
+
+fn main() {
+    // A limit buy of 0.25 BTC/USD at 65000, within every axiom
+    let signal = TradeSignal::builder()
+        .with_symbol(Symbol("BTC/USD".to_string()))
+        .with_venue(Venue::Binance)
+        .with_side(Side::Buy)
+        .with_order_type(OrderType::Limit)
+        .with_quantity(Quantity::new(Decimal::new(25, 2)).unwrap())
+        .with_limit_price(Price::new(Decimal::from(65_000)).unwrap())
+        .with_scores(Decimal::new(8, 1), Decimal::ZERO)
+        .with_expected_edge_bps(Decimal::ONE_HUNDRED)
+        .build(&LimitsRegistry::builtin())
+        .unwrap();
+    let config = |cache| VerifierConfig { cache, ..VerifierConfig::default() };
+
+    let uncached = Verifier::new().with_config(config(ProofCacheConfig { capacity: 0, ..ProofCacheConfig::default() }));
+    let solved = measure(&uncached, &signal);
+    let cached = Verifier::new().with_config(config(ProofCacheConfig::default()));
+    let reused = measure(&cached, &signal);
+    let (hits, misses) = cached.cache_stats();
+
+    let throughput = |elapsed: Duration| SIGNALS as f64 / elapsed.as_secs_f64();
+    println!("{} signals", SIGNALS);
+    println!("solved every time: {:.0} signals/s", throughput(solved));
+    println!("cached: {:.0} signals/s ({} hits, {} misses)", throughput(reused), hits, misses);
+    println!("speedup: {:.1}x", solved.as_secs_f64() / reused.as_secs_f64());
+}
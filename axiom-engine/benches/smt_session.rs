@@ -0,0 +1,67 @@
+//! Per-verification latency of the z3 backend over a stream of 10k signals:
+//! one session reused with push/pop, against a session rebuilt for every
+//! signal (every limit constant asserted afresh).
+//!
+//! cargo bench -p axiom-engine --bench smt_session
+
+use axiom_core::{Amount, LimitsRegistry, OrderType, Portfolio, Price, Quantity, Side, Symbol, TradeSignal, Venue};
+use axiom_engine::{ProofBackend, ProofObligation, SmtVerifier, VerifierConfig};
+use rust_decimal::Decimal;
+use std::time::{Duration, Instant};
+
+const SIGNALS: usize = 10_000;
+
+/// Buys of 0.20 to 0.29 BTC/USD at 65000, all within every axiom against
+/// 2,000,000 of equity
+fn signals(limits: &LimitsRegistry) -> Vec<TradeSignal> {
+    (0..SIGNALS)
+        .map(|index| {
+            TradeSignal::builder()
+                .with_symbol(Symbol("BTC/USD".to_string()))
+                .with_venue(Venue::Binance)
+                .with_side(Side::Buy)
+                .with_order_type(OrderType::Limit)
+                .with_quantity(Quantity::new(Decimal::new(20 + (index % 10) as i64, 2)).unwrap())
+                .with_limit_price(Price::new(Decimal::from(65_000)).unwrap())
+                .build(limits)
+                .unwrap()
+        })
+        .collect()
+}
+
+/// Mean and worst latency of `prove` over every signal
+fn measure(signals: &[TradeSignal], mut prove: impl FnMut(&ProofObligation<'_>)) -> (Duration, Duration) {
+    let limits = LimitsRegistry::builtin();
+    let portfolio = Portfolio::new(Amount::new(Decimal::from(2_000_000)));
+    let market = Price::new(Decimal::from(65_000)).unwrap();
+    let mut total = Duration::ZERO;
+    let mut worst = Duration::ZERO;
+    for signal in signals {
+        let obligation = ProofObligation::new(signal, &portfolio, &limits, market).unwrap();
+        let start = Instant::now();
+        prove(&obligation);
+        let elapsed = start.elapsed();
+        total += elapsed;
+        worst = worst.max(elapsed);
+    }
+    (total / signals.len() as u32, worst)
+}
+
+fn main() {
+    let limits = LimitsRegistry::builtin();
+    let config = VerifierConfig::default();
+    let signals = signals(&limits);
+
+    let session = SmtVerifier::new(&limits, &config);
+    let (reused, reused_worst) = measure(&signals, |obligation| {
+        session.prove(obligation).unwrap();
+    });
+    let (rebuilt, rebuilt_worst) = measure(&signals, |obligation| {
+        SmtVerifier::new(&limits, &config).prove(obligation).unwrap();
+    });
+
+    println!("{} signals", SIGNALS);
+    println!("session reused (push/pop): mean {:?}, worst {:?}", reused, reused_worst);
+    println!("session rebuilt per signal: mean {:?}, worst {:?}", rebuilt, rebuilt_worst);
+    println!("speedup: {:.1}x", rebuilt.as_secs_f64() / reused.as_secs_f64());
+}
@@ -0,0 +1,103 @@
+//! Proposal Cooldown: One Order per Opportunity
+//!
+//! While a condition holds, a strategy proposes the same trade on every
+//! book. Once an order is verified for an account, symbol and side, further
+//! proposals for it are suppressed until the cooldown runs out, the mid
+//! moves far enough from where the order was made, or the order is reported
+//! filled, cancelled or rejected. Time is measured between book timestamps,
+//! so a replay suppresses the same proposals.
+
+use axiom_core::{AccountId, OrderStatus, Price, Side, Symbol, BPS_PER_UNIT};
+use chrono::{DateTime, TimeDelta, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Proposal cooldown tuning
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CooldownConfig {
+    /// How long an account, symbol and side stay suppressed after an order;
+    /// 0 never suppresses
+    pub duration_ms: i64,
+    /// Release early once the mid has moved this far from the order's;
+    /// None waits out the duration
+    pub release_move_bps: Option<Decimal>,
+}
+
+impl Default for CooldownConfig {
+    fn default() -> Self {
+        Self { duration_ms: 5_000, release_move_bps: Some(Decimal::TEN) }
+    }
+}
+
+type CooldownKey = (AccountId, Symbol, Side);
+
+#[derive(Debug, Clone)]
+struct Cooldown {
+    started: DateTime<Utc>,
+    /// Mid of the book the order came from
+    mid: Price,
+    /// Hash of the order, for `on_order_update`
+    order_id: String,
+}
+
+/// Cooldowns in force, by account, symbol and side
+#[derive(Debug, Clone, Default)]
+pub struct CooldownTracker {
+    config: CooldownConfig,
+    active: HashMap<CooldownKey, Cooldown>,
+}
+
+impl CooldownTracker {
+    pub fn new(config: CooldownConfig) -> Self {
+        Self { config, active: HashMap::new() }
+    }
+
+    /// Retune, keeping the cooldowns in force
+    pub fn set_config(&mut self, config: CooldownConfig) {
+        self.config = config;
+    }
+
+    /// Whether a proposal to trade `side` of `symbol` for `account`, with
+    /// the mid at `mid` as of `now`, is held back; a cooldown that has
+    /// ended is dropped
+    pub fn suppresses(&mut self, account: &AccountId, symbol: &Symbol, side: Side, mid: Price, now: DateTime<Utc>) -> bool {
+        let key = (account.clone(), symbol.clone(), side);
+        let Some(cooldown) = self.active.get(&key) else { return false };
+        let expired = now - cooldown.started >= TimeDelta::milliseconds(self.config.duration_ms);
+        let moved = self.config.release_move_bps.is_some_and(|release| {
+            !cooldown.mid.is_zero() && (mid.delta(cooldown.mid) / cooldown.mid.get() * BPS_PER_UNIT).abs() >= release
+        });
+        if expired || moved {
+            self.active.remove(&key);
+            return false;
+        }
+        true
+    }
+
+    /// Hold back `side` of `symbol` for `account` now that order `order_id`
+    /// was made with the mid at `mid` as of `now`
+    pub fn start(&mut self, account: &AccountId, symbol: &Symbol, side: Side, mid: Price, now: DateTime<Utc>, order_id: String) {
+        if self.config.duration_ms <= 0 {
+            return;
+        }
+        self.active.insert((account.clone(), symbol.clone(), side), Cooldown { started: now, mid, order_id });
+    }
+
+    /// End the cooldown order `order_id` started once it is filled,
+    /// cancelled or rejected; true when one ended
+    pub fn on_order_update(&mut self, order_id: &str, status: OrderStatus) -> bool {
+        if !status.is_terminal() {
+            return false;
+        }
+        let before = self.active.len();
+        self.active.retain(|_, cooldown| cooldown.order_id != order_id);
+        self.active.len() < before
+    }
+
+    /// Cooldowns in force
+    pub fn active(&self) -> usize {
+        self.active.len()
+    }
+}
@@ -0,0 +1,176 @@
+//! Cross-Venue Divergence: One Symbol's Books Side by Side
+//!
+//! The tracker keeps the latest book per symbol and venue. For a symbol it
+//! tries every ordered pair of venues, buying at one's ask and selling at
+//! the other's bid, and reports the pair with the widest edge: whether the
+//! touches cross, the edge and the mid spread in basis points, and the size
+//! executable against both books' depth before the cross closes.
+//!
+//! A book more than `max_age` behind the symbol's newest one is left out.
+//! Ages are measured between books, never against the wall clock, so a
+//! replay reports the same divergence.
+
+use axiom_core::{ArithmeticError, BookLevel, OrderBook, Price, Quantity, Symbol, Venue, BPS_PER_UNIT};
+use axiom_core::arith::{checked_add, checked_div, checked_mul, checked_sub};
+use axiom_data::normalization::calculate_mid_price;
+use chrono::{DateTime, TimeDelta, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Cross-venue arbitrage tuning
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CrossVenueConfig {
+    /// Leave out a venue's book this far behind the symbol's newest
+    pub max_book_age_ms: i64,
+    /// Propose a paired buy and sell once the touches cross by this much;
+    /// None never proposes arbitrage
+    pub min_edge_bps: Option<Decimal>,
+}
+
+impl Default for CrossVenueConfig {
+    fn default() -> Self {
+        Self { max_book_age_ms: 1_000, min_edge_bps: None }
+    }
+}
+
+/// Buying on one venue and selling on another, as the books stood
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DivergenceReport {
+    pub symbol: Symbol,
+    /// Venue bought on, at its best ask
+    pub buy_venue: Venue,
+    /// Venue sold on, at its best bid
+    pub sell_venue: Venue,
+    pub buy_price: Price,
+    pub sell_price: Price,
+    /// The bid is above the ask, so trading both touches locks in a profit
+    pub crossed: bool,
+    /// (sell - buy) / buy in basis points; negative when not crossed
+    pub edge_bps: Decimal,
+    /// Sell venue's mid over the buy venue's, in basis points
+    pub mid_spread_bps: Decimal,
+    /// Size that can be bought on one and sold on the other while the ask
+    /// stays below the bid, walking both books
+    pub executable_quantity: Quantity,
+    /// Quote earned trading `executable_quantity` level by level, before fees
+    pub executable_profit: Decimal,
+    /// Timestamp of the newer of the two books
+    pub as_of: DateTime<Utc>,
+}
+
+/// Latest book per symbol and venue
+#[derive(Debug, Clone)]
+pub struct CrossVenueTracker {
+    books: BTreeMap<Symbol, BTreeMap<Venue, OrderBook>>,
+    max_age: TimeDelta,
+}
+
+impl CrossVenueTracker {
+    /// Compare books at most `max_age` behind their symbol's newest
+    pub fn new(max_age: TimeDelta) -> Self {
+        Self { books: BTreeMap::new(), max_age }
+    }
+
+    pub fn from_config(config: &CrossVenueConfig) -> Self {
+        Self::new(TimeDelta::milliseconds(config.max_book_age_ms))
+    }
+
+    /// Keep `book` as its venue's latest for its symbol, unless a newer
+    /// one is already kept
+    pub fn record(&mut self, book: &OrderBook) {
+        let venues = self.books.entry(book.symbol.clone()).or_default();
+        if venues.get(&book.venue).is_some_and(|kept| kept.timestamp > book.timestamp) {
+            return;
+        }
+        venues.insert(book.venue.clone(), book.clone());
+    }
+
+    /// Venues with a book kept for `symbol`
+    pub fn venues(&self, symbol: &Symbol) -> impl Iterator<Item = &Venue> {
+        self.books.get(symbol).into_iter().flat_map(BTreeMap::keys)
+    }
+
+    /// `venue`'s latest book for `symbol`
+    pub fn book(&self, symbol: &Symbol, venue: &Venue) -> Option<&OrderBook> {
+        self.books.get(symbol)?.get(venue)
+    }
+
+    /// The venue pair with the widest edge for `symbol`, crossed or not;
+    /// None without two fresh two-sided books, or when a pair's figures
+    /// overflow
+    pub fn calculate_cross_venue_divergence(&self, symbol: &Symbol) -> Option<DivergenceReport> {
+        let venues = self.books.get(symbol)?;
+        let newest = venues.values().map(|book| book.timestamp).max()?;
+        let fresh: Vec<&OrderBook> = venues.values()
+            .filter(|book| newest - book.timestamp <= self.max_age)
+            .collect();
+
+        let mut widest: Option<DivergenceReport> = None;
+        for buy in &fresh {
+            for sell in fresh.iter().filter(|sell| sell.venue != buy.venue) {
+                let Ok(Some(report)) = divergence(symbol, buy, sell) else { continue };
+                // Venues are visited in order, so ties go to the first pair
+                if widest.as_ref().is_none_or(|widest| report.edge_bps > widest.edge_bps) {
+                    widest = Some(report);
+                }
+            }
+        }
+        widest
+    }
+}
+
+/// Buying `buy`'s best ask and selling `sell`'s best bid; None when either
+/// book is one-sided
+pub fn divergence(symbol: &Symbol, buy: &OrderBook, sell: &OrderBook) -> Result<Option<DivergenceReport>, ArithmeticError> {
+    let (Some(ask), Some(bid)) = (buy.asks.first(), sell.bids.first()) else {
+        return Ok(None);
+    };
+    let (Some(buy_mid), Some(sell_mid)) = (calculate_mid_price(buy), calculate_mid_price(sell)) else {
+        return Ok(None);
+    };
+    if ask.price.is_zero() || buy_mid.is_zero() {
+        return Ok(None);
+    }
+    let edge_bps = checked_mul("cross-venue edge", checked_div("cross-venue edge", bid.price.delta(ask.price), ask.price.get())?, BPS_PER_UNIT)?;
+    let mid_spread_bps = checked_mul("cross-venue mid spread", checked_div("cross-venue mid spread", sell_mid.delta(buy_mid), buy_mid.get())?, BPS_PER_UNIT)?;
+    let (quantity, profit) = executable_size(&buy.asks, &sell.bids)?;
+
+    Ok(Some(DivergenceReport {
+        symbol: symbol.clone(),
+        buy_venue: buy.venue.clone(),
+        sell_venue: sell.venue.clone(),
+        buy_price: ask.price,
+        sell_price: bid.price,
+        crossed: bid.price.get() > ask.price.get(),
+        edge_bps,
+        mid_spread_bps,
+        executable_quantity: Quantity::new(quantity).map_err(|_| ArithmeticError::Overflow("arbitrage size"))?,
+        executable_profit: profit,
+        as_of: buy.timestamp.max(sell.timestamp),
+    }))
+}
+
+/// Size and profit of buying up `asks` (best first) and selling into
+/// `bids` (best first) for as long as the ask is below the bid
+pub fn executable_size(asks: &[BookLevel], bids: &[BookLevel]) -> Result<(Decimal, Decimal), ArithmeticError> {
+    let level = |level: &BookLevel| (level.price.get(), level.quantity.get());
+    let (mut asks, mut bids) = (asks.iter(), bids.iter());
+    let (mut ask, mut bid) = (asks.next().map(level), bids.next().map(level));
+    let (mut quantity, mut profit) = (Decimal::ZERO, Decimal::ZERO);
+
+    while let (Some((ask_price, ask_left)), Some((bid_price, bid_left))) = (ask, bid) {
+        if ask_price >= bid_price {
+            break;
+        }
+        let size = ask_left.min(bid_left);
+        quantity = checked_add("arbitrage size", quantity, size)?;
+        let margin = checked_sub("arbitrage profit", bid_price, ask_price)?;
+        profit = checked_add("arbitrage profit", profit, checked_mul("arbitrage profit", size, margin)?)?;
+        // The level that ran out gives way to the next one
+        ask = if size < ask_left { Some((ask_price, ask_left - size)) } else { asks.next().map(level) };
+        bid = if size < bid_left { Some((bid_price, bid_left - size)) } else { bids.next().map(level) };
+    }
+    Ok((quantity, profit))
+}
@@ -1,127 +1,1083 @@
 //! Feature Engineering: Deterministic State Construction
 //!
 //! All features are calculated using fixed-point arithmetic to ensure
-//! bitwise determinism across all execution environments.
+//! bitwise determinism across all execution environments. Book depth sums
+//! take the scaled-integer fast path in `fixed` and fall back to Decimal
+//! when a result leaves its range; both give the same Decimal.
+//!
+//! The on-chain/CEX contradiction is also reported weighted by the
+//! confidence in each input: a source loses weight as it falls behind the
+//! other one and with its data quality, so a lagging feed pulls the
+//! adjusted score toward zero instead of spiking it.
+//!
+//! Liquidations are kept per symbol and summed by forced-order side over a
+//! rolling window; a burst on one side is a cascade in progress.
+//!
+//! Volatility is over log returns, kept with Welford's running mean and
+//! sum of squared deviations, updated as a return enters and leaves the
+//! window: O(1) per price, and without the cancellation a sum of squares
+//! less a squared mean suffers when returns are small. The incremental
+//! figure agrees with one computed from scratch over the same window to
+//! within rounding.
+//!
+//! Entropy and book shape look only at the depth window (the best levels,
+//! or the levels near the mid): far-from-touch size carries little signal
+//! and would drown out the top of book.
+//!
+//! RSI is Wilder's: simple means of gains and losses over the first
+//! `period` changes, then each change smoothed in at 1/`period`, O(1) per
+//! price in `RsiState`. The batch `calculate_rsi` replays a slice through
+//! the same state.
+//!
+//! EMA, MACD and Bollinger Bands follow the same pattern: a state type
+//! updated O(1) per price, and a batch helper replaying a slice through it
+//! for backtests. Everything stays in Decimal, so the same prices give the
+//! same digits on every machine.
+//!
+//! `compute_all` gathers every feature of a book into one `FeatureVector`
+//! from its arguments alone; the proposer decides from that vector and
+//! journals it with each signal.
 
-use axiom_core::{OrderBook, Tick, Price, Decimal};
+use crate::fixed::FixedPoint;
+use axiom_core::{
+    BookLevel, BookSlope, FeatureVector, FundingRate, LiquidationEvent, LiquidationIntensity, OrderBook, Price, Side, Symbol,
+    ArithmeticError, UnitError,
+};
+use axiom_core::arith::{checked_add, checked_div, checked_mul, checked_sub, checked_sum};
+use axiom_data::evm::{EvmPoolState, UNISWAP_V3_SOURCE};
 use axiom_data::normalization::*;
-use std::collections::VecDeque;
+use chrono::{DateTime, TimeDelta, Utc};
+use rust_decimal::{Decimal, MathematicalOps};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+
+/// A liquidity reading from one source
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LiquidityInput {
+    /// Source name (a venue, or an on-chain pool such as "raydium")
+    pub source: String,
+    /// Liquidity in quote currency
+    pub liquidity: Decimal,
+    pub observed_at: DateTime<Utc>,
+    /// Data quality in [0, 1], as scored by the source (1 when unscored)
+    pub quality: Decimal,
+}
+
+impl LiquidityInput {
+    /// Stand-in while no on-chain feed is wired: no liquidity, as fresh as
+    /// the book and fully trusted, which leaves the adjusted score equal to
+    /// the raw one
+    pub fn absent(book: &OrderBook) -> Self {
+        Self {
+            source: "none".to_string(),
+            liquidity: Decimal::ZERO,
+            observed_at: book.timestamp,
+            quality: Decimal::ONE,
+        }
+    }
+
+    /// A Uniswap v3 pool's holdings, as of when they were read
+    pub fn from_evm_pool(state: &EvmPoolState) -> Self {
+        Self {
+            source: UNISWAP_V3_SOURCE.to_string(),
+            liquidity: state.liquidity_quote,
+            observed_at: state.observed_at,
+            quality: Decimal::ONE,
+        }
+    }
+}
+
+/// How confidence in a source decays with its age: full up to `fresh_ms`,
+/// none from `stale_ms`, linear in between
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceDecay {
+    pub fresh_ms: u64,
+    pub stale_ms: u64,
+}
+
+impl SourceDecay {
+    /// Confidence in a reading `age_ms` old with data quality `quality`
+    pub fn weight(&self, age_ms: i64, quality: Decimal) -> Decimal {
+        let quality = quality.clamp(Decimal::ZERO, Decimal::ONE);
+        let age = age_ms.max(0) as u64;
+        if age <= self.fresh_ms {
+            return quality;
+        }
+        if age >= self.stale_ms {
+            return Decimal::ZERO;
+        }
+        let remaining = Decimal::from(self.stale_ms - age) / Decimal::from(self.stale_ms - self.fresh_ms);
+        quality * remaining
+    }
+}
+
+/// Confidence decay per source kind, with per-source overrides
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConfidenceConfig {
+    /// Exchange order books
+    pub cex: SourceDecay,
+    /// On-chain liquidity snapshots
+    pub onchain: SourceDecay,
+    /// Overrides by source name (venue or on-chain source)
+    pub sources: BTreeMap<String, SourceDecay>,
+}
+
+impl Default for ConfidenceConfig {
+    fn default() -> Self {
+        Self {
+            cex: SourceDecay { fresh_ms: 250, stale_ms: 5_000 },
+            onchain: SourceDecay { fresh_ms: 2_000, stale_ms: 30_000 },
+            sources: BTreeMap::new(),
+        }
+    }
+}
+
+impl ConfidenceConfig {
+    fn decay(&self, source: &str, default: SourceDecay) -> SourceDecay {
+        self.sources.get(source).copied().unwrap_or(default)
+    }
+}
+
+/// The part of the book imbalance, entropy and slope are measured over
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DepthWindow {
+    /// The best N levels of each side
+    Levels(usize),
+    /// Levels within this many basis points of the mid
+    WithinBps(Decimal),
+}
+
+impl Default for DepthWindow {
+    fn default() -> Self {
+        DepthWindow::Levels(10)
+    }
+}
+
+impl DepthWindow {
+    /// Depth imbalance over this window
+    pub fn imbalance(&self, book: &OrderBook) -> Decimal {
+        match *self {
+            DepthWindow::Levels(levels) => calculate_depth_imbalance_levels(book, levels),
+            DepthWindow::WithinBps(bps) => calculate_depth_imbalance_within_bps(book, bps),
+        }
+    }
+
+    /// Each side's slope over this window
+    pub fn slope(&self, book: &OrderBook) -> BookSlope {
+        let (bid_levels, ask_levels) = match *self {
+            DepthWindow::Levels(levels) => (levels, levels),
+            DepthWindow::WithinBps(bps) => depth_within_bps(book, bps)
+                .map_or((0, 0), |(bids, asks)| (bids.len(), asks.len())),
+        };
+        BookSlope {
+            bid: calculate_book_slope(book, Side::Buy, bid_levels),
+            ask: calculate_book_slope(book, Side::Sell, ask_levels),
+        }
+    }
+}
+
+/// On-chain/CEX contradiction, raw and confidence-adjusted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Contradiction {
+    pub raw: Decimal,
+    /// `raw` scaled by the confidence in both inputs
+    pub adjusted: Decimal,
+    /// Age of each input behind the newer of the two
+    pub cex_age_ms: i64,
+    pub onchain_age_ms: i64,
+}
+
+/// One-minute samples in a 365-day year
+pub const MINUTES_PER_YEAR: Decimal = Decimal::from_parts(525_600, 0, 0, false, 0);
+
+/// Samples in a 365-day year when one is taken every `interval`
+pub fn periods_per_year(interval: std::time::Duration) -> Decimal {
+    let year_nanos = Decimal::from(365 * 24 * 3600) * Decimal::from(1_000_000_000);
+    let nanos = Decimal::from(u64::try_from(interval.as_nanos()).unwrap_or(u64::MAX));
+    if nanos.is_zero() {
+        return Decimal::ZERO;
+    }
+    year_nanos / nanos
+}
 
 /// Feature calculator with deterministic arithmetic
 pub struct FeatureCalculator {
-    price_history: VecDeque<Price>,
+    /// Log returns between successive prices, oldest first
+    returns: VecDeque<Decimal>,
+    /// Mean and squared deviations of `returns`
+    return_stats: Welford,
+    last_price: Option<Price>,
+    /// Latest liquidations per symbol, oldest first
+    liquidations: BTreeMap<Symbol, VecDeque<LiquidationEvent>>,
     max_history: usize,
+    depth_window: DepthWindow,
+    periods_per_year: Decimal,
+    rsi: RsiState,
+    ema: EmaState,
+    macd: MacdState,
+    bollinger: BollingerState,
+}
+
+/// Changes the running RSI is taken over unless set
+pub const DEFAULT_RSI_PERIOD: usize = 14;
+
+/// Prices the running EMA is taken over unless set
+pub const DEFAULT_EMA_PERIOD: usize = 20;
+
+/// Prices the running Bollinger Bands are taken over unless set
+pub const DEFAULT_BOLLINGER_PERIOD: usize = 20;
+
+/// Standard deviations from the middle band to either outer band unless set
+pub const DEFAULT_BOLLINGER_WIDTH: Decimal = Decimal::TWO;
+
+/// The running indicators as of the latest price; each None until it has
+/// seen enough prices
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Indicators {
+    pub rsi: Option<Decimal>,
+    pub ema: Option<Decimal>,
+    pub macd: Option<Macd>,
+    pub bollinger: Option<BollingerBands>,
 }
 
 impl FeatureCalculator {
-    pub fn new(max_history: usize) -> Self {
+    /// Keep `max_history` prices (and liquidations per symbol); measure
+    /// entropy and book shape over `depth_window`; annualize volatility
+    /// for prices sampled `periods_per_year` times a year
+    pub fn new(max_history: usize, depth_window: DepthWindow, periods_per_year: Decimal) -> Self {
         Self {
-            price_history: VecDeque::with_capacity(max_history),
+            returns: VecDeque::with_capacity(max_history),
+            return_stats: Welford::default(),
+            last_price: None,
+            liquidations: BTreeMap::new(),
             max_history,
+            depth_window,
+            periods_per_year,
+            rsi: RsiState::new(DEFAULT_RSI_PERIOD),
+            ema: EmaState::new(DEFAULT_EMA_PERIOD),
+            macd: MacdState::new(MacdParams::default()),
+            bollinger: BollingerState::new(DEFAULT_BOLLINGER_PERIOD, DEFAULT_BOLLINGER_WIDTH),
+        }
+    }
+
+    /// Take the running RSI over `period` changes, starting afresh
+    pub fn with_rsi_period(mut self, period: usize) -> Self {
+        self.rsi = RsiState::new(period);
+        self
+    }
+
+    /// Take the running EMA over `period` prices, starting afresh
+    pub fn with_ema_period(mut self, period: usize) -> Self {
+        self.ema = EmaState::new(period);
+        self
+    }
+
+    /// Take the running MACD with `params`, starting afresh
+    pub fn with_macd(mut self, params: MacdParams) -> Self {
+        self.macd = MacdState::new(params);
+        self
+    }
+
+    /// Take the running Bollinger Bands over `period` prices, `width`
+    /// standard deviations wide, starting afresh
+    pub fn with_bollinger(mut self, period: usize, width: Decimal) -> Self {
+        self.bollinger = BollingerState::new(period, width);
+        self
+    }
+
+    pub fn depth_window(&self) -> DepthWindow {
+        self.depth_window
+    }
+
+    /// Keep `event` for `calculate_liquidation_intensity`; each symbol keeps
+    /// its latest `max_history` events
+    pub fn record_liquidation(&mut self, event: LiquidationEvent) {
+        let events = self.liquidations.entry(event.symbol.clone()).or_default();
+        events.push_back(event);
+        if events.len() > self.max_history {
+            events.pop_front();
+        }
+    }
+
+    /// Drop liquidations older than `window` before `now`
+    pub fn expire_liquidations(&mut self, window: TimeDelta, now: DateTime<Utc>) {
+        let cutoff = now - window;
+        self.liquidations.retain(|_, events| {
+            while events.front().is_some_and(|event| event.timestamp < cutoff) {
+                events.pop_front();
+            }
+            !events.is_empty()
+        });
+    }
+
+    /// Symbols with liquidations kept
+    pub fn liquidated_symbols(&self) -> impl Iterator<Item = &Symbol> {
+        self.liquidations.keys()
+    }
+
+    /// Notional liquidated in `symbol` per forced-order side over the
+    /// `window` ending at `now` (events after `now` are not counted)
+    pub fn calculate_liquidation_intensity(
+        &self,
+        symbol: &Symbol,
+        window: TimeDelta,
+        now: DateTime<Utc>,
+    ) -> Result<LiquidationIntensity, UnitError> {
+        let cutoff = now - window;
+        let mut intensity = LiquidationIntensity::default();
+        let events = self.liquidations.get(symbol).into_iter().flatten()
+            .filter(|event| event.timestamp >= cutoff && event.timestamp <= now);
+        for event in events {
+            let side = match event.side {
+                Side::Buy => &mut intensity.buy,
+                Side::Sell => &mut intensity.sell,
+            };
+            *side = side.checked_add(event.notional()?)?;
         }
+        Ok(intensity)
     }
 
     /// Calculate contradiction score
     ///
     /// Measures divergence between implied and realized volatility,
-    /// or between on-chain and CEX liquidity.
+    /// or between on-chain and CEX liquidity. An empty book has no CEX
+    /// liquidity to compare against and scores zero.
     pub fn calculate_contradiction_score(
         &self,
         book: &OrderBook,
         onchain_liquidity: Decimal,
-    ) -> Decimal {
-        let cex_liquidity = self.calculate_cex_liquidity(book);
+    ) -> Result<Decimal, ArithmeticError> {
+        let cex_liquidity = self.calculate_cex_liquidity(book)?;
         
         if cex_liquidity == Decimal::ZERO {
-            return Decimal::ZERO;
+            return Ok(Decimal::ZERO);
         }
 
         // Contradiction = |onchain - cex| / cex
-        let diff = (onchain_liquidity - cex_liquidity).abs();
-        diff / cex_liquidity
+        let diff = checked_sub("contradiction score", onchain_liquidity, cex_liquidity)?.abs();
+        checked_div("contradiction score", diff, cex_liquidity)
+    }
+
+    /// Contradiction score with each input weighted by its confidence
+    ///
+    /// Ages are measured against the newer of the book and the on-chain
+    /// reading (never the wall clock), so a replay scores the same.
+    pub fn calculate_weighted_contradiction(
+        &self,
+        book: &OrderBook,
+        onchain: &LiquidityInput,
+        confidence: &ConfidenceConfig,
+    ) -> Result<Contradiction, ArithmeticError> {
+        let raw = self.calculate_contradiction_score(book, onchain.liquidity)?;
+        let newest = book.timestamp.max(onchain.observed_at);
+        let cex_age_ms = (newest - book.timestamp).num_milliseconds();
+        let onchain_age_ms = (newest - onchain.observed_at).num_milliseconds();
+        let cex_weight = confidence.decay(&book.venue.to_string(), confidence.cex).weight(cex_age_ms, Decimal::ONE);
+        let onchain_weight = confidence.decay(&onchain.source, confidence.onchain).weight(onchain_age_ms, onchain.quality);
+        let adjusted = checked_mul("adjusted contradiction", raw, checked_mul("adjusted contradiction", cex_weight, onchain_weight)?)?;
+        Ok(Contradiction { raw, adjusted, cex_age_ms, onchain_age_ms })
     }
 
-    /// Calculate entropy count (market disorder)
-    pub fn calculate_entropy(&self, book: &OrderBook) -> Decimal {
+    /// Calculate entropy count (market disorder), from the imbalance over
+    /// the depth window
+    pub fn calculate_entropy(&self, book: &OrderBook) -> Result<Decimal, ArithmeticError> {
         let spread_pct = calculate_spread_pct(book)
             .unwrap_or(Decimal::ZERO);
         
-        let imbalance = calculate_depth_imbalance(book).abs();
+        let imbalance = self.depth_window.imbalance(book).abs();
 
         // Entropy = spread_pct * (1 + imbalance)
-        spread_pct * (Decimal::ONE + imbalance)
+        checked_mul("entropy", spread_pct, checked_add("entropy", Decimal::ONE, imbalance)?)
+    }
+
+    /// Carry of a long-spot, short-perpetual position over one funding
+    /// interval: the perpetual's premium, (perp - spot) / spot, plus the
+    /// funding rate the short side receives. Positive when perpetuals trade
+    /// rich or longs pay; zero without a spot price
+    pub fn calculate_funding_basis(
+        &self,
+        spot_mid: Price,
+        perp_mid: Price,
+        funding_rate: Decimal,
+    ) -> Result<Decimal, ArithmeticError> {
+        if spot_mid.is_zero() {
+            return Ok(Decimal::ZERO);
+        }
+        let premium = checked_div("funding basis", perp_mid.delta(spot_mid), spot_mid.get())?;
+        checked_add("funding basis", premium, funding_rate)
+    }
+
+    /// Every feature of `book` in one vector, the proposer's single input:
+    /// RSI and volatility over the symbol's `history` of mids (oldest
+    /// first, this book's excluded) followed by this book's, the funding
+    /// basis against the perpetual's mark when `funding` is known and the
+    /// symbol's recent `liquidations`; None for a one-sided book
+    ///
+    /// Depends on nothing but its arguments, so the same inputs give the
+    /// same vector on replay.
+    pub fn compute_all(
+        &self,
+        book: &OrderBook,
+        history: &[Price],
+        onchain: &LiquidityInput,
+        confidence: &ConfidenceConfig,
+        funding: Option<&FundingRate>,
+        liquidations: LiquidationIntensity,
+    ) -> Result<Option<FeatureVector>, ArithmeticError> {
+        let (Some(mid), Some(spread_pct)) = (calculate_mid_price(book), calculate_spread_pct(book)) else {
+            return Ok(None);
+        };
+        let contradiction = self.calculate_weighted_contradiction(book, onchain, confidence)?;
+        let funding_basis = match funding {
+            Some(funding) => Some(self.calculate_funding_basis(mid, funding.mark_price, funding.rate)?),
+            None => None,
+        };
+        let prices = history.iter().copied().chain(std::iter::once(mid));
+        let mut rsi = RsiState::new(self.rsi.period());
+        let mut latest_rsi = None;
+        for price in prices.clone() {
+            latest_rsi = rsi.update(price)?;
+        }
+        Ok(Some(FeatureVector {
+            mid,
+            microprice: calculate_microprice(book),
+            spread_pct,
+            depth_imbalance: self.depth_window.imbalance(book),
+            contradiction_score: contradiction.raw,
+            adjusted_contradiction_score: contradiction.adjusted,
+            cex_age_ms: contradiction.cex_age_ms,
+            onchain_age_ms: contradiction.onchain_age_ms,
+            entropy: self.calculate_entropy(book)?,
+            rsi: latest_rsi,
+            volatility: self.realized_volatility(prices)?,
+            funding_basis,
+            liquidation_intensity: liquidations,
+            book_slope: self.depth_window.slope(book),
+        }))
+    }
+
+    /// Depth imbalance, (bid volume - ask volume) / total volume
+    ///
+    /// Same result as `normalization::calculate_depth_imbalance`.
+    pub fn calculate_depth_imbalance(&self, book: &OrderBook) -> Decimal {
+        let volume = |levels: &[BookLevel]| {
+            levels.iter().try_fold(FixedPoint::ZERO, |total, level| {
+                total.checked_add(FixedPoint::from_decimal(level.quantity.get()))
+            })
+        };
+        let fixed = || {
+            let (bid_volume, ask_volume) = (volume(&book.bids)?, volume(&book.asks)?);
+            Some((bid_volume.checked_sub(ask_volume)?.to_decimal(), bid_volume.checked_add(ask_volume)?.to_decimal()))
+        };
+        let Some((difference, total)) = fixed() else {
+            return calculate_depth_imbalance(book);
+        };
+
+        if total == Decimal::ZERO {
+            return Decimal::ZERO;
+        }
+        difference / total
     }
 
     /// Calculate CEX liquidity from order book
-    fn calculate_cex_liquidity(&self, book: &OrderBook) -> Decimal {
-        let bid_volume: Decimal = book.bids.iter()
-            .take(10) // Top 10 levels
-            .map(|level| level.price * level.quantity)
-            .sum();
+    fn calculate_cex_liquidity(&self, book: &OrderBook) -> Result<Decimal, ArithmeticError> {
+        let fixed_depth = |levels: &[BookLevel]| {
+            levels.iter()
+                .take(10) // Top 10 levels
+                .try_fold(FixedPoint::ZERO, |total, level| {
+                    let price = FixedPoint::from_decimal(level.price.get());
+                    total.checked_add(price.checked_mul(FixedPoint::from_decimal(level.quantity.get()))?)
+                })
+                .map(FixedPoint::to_decimal)
+        };
+        if let (Some(bid_volume), Some(ask_volume)) = (fixed_depth(&book.bids), fixed_depth(&book.asks)) {
+            return Ok(checked_add("cex liquidity", bid_volume, ask_volume)? / Decimal::TWO);
+        }
 
-        let ask_volume: Decimal = book.asks.iter()
-            .take(10)
-            .map(|level| level.price * level.quantity)
-            .sum();
+        let depth = |levels: &[BookLevel]| {
+            levels.iter()
+                .take(10) // Top 10 levels
+                .map(|level| checked_mul("book depth", level.price.get(), level.quantity.get()))
+                .collect::<Result<Vec<_>, _>>()
+                .and_then(|values| checked_sum("book depth", values))
+        };
+        let bid_volume = depth(&book.bids)?;
+        let ask_volume = depth(&book.asks)?;
 
-        (bid_volume + ask_volume) / Decimal::from(2)
+        Ok(checked_add("cex liquidity", bid_volume, ask_volume)? / Decimal::TWO)
     }
 
-    /// Calculate realized volatility (deterministic)
-    pub fn calculate_volatility(&mut self, current_price: Price) -> Decimal {
-        self.price_history.push_back(current_price);
-        
-        if self.price_history.len() > self.max_history {
-            self.price_history.pop_front();
+    /// Calculate realized volatility (deterministic): the annualized
+    /// standard deviation of log returns over the last `max_history`
+    /// prices, `current_price` included
+    ///
+    /// A zero price is a missing quote, not a move to zero: no return is
+    /// taken into or out of it.
+    pub fn calculate_volatility(&mut self, current_price: Price) -> Result<Decimal, ArithmeticError> {
+        let previous = self.last_price.replace(current_price);
+        if let Some(previous) = previous.filter(|previous| !previous.is_zero() && !current_price.is_zero()) {
+            let ratio = checked_div("log return", current_price.get(), previous.get())?;
+            let log_return = ratio.checked_ln().ok_or(ArithmeticError::Overflow("log return"))?;
+            self.return_stats.push(log_return)?;
+            self.returns.push_back(log_return);
+            // max_history prices hold one return fewer
+            while self.returns.len() >= self.max_history.max(1) {
+                let Some(oldest) = self.returns.pop_front() else { break };
+                self.return_stats.pop(oldest)?;
+            }
         }
+        self.annualized(&self.return_stats)
+    }
 
-        if self.price_history.len() < 2 {
-            return Decimal::ZERO;
+    /// Volatility over only the latest `window` returns (at most those
+    /// kept), for a shorter horizon than `max_history`; computed afresh,
+    /// O(window)
+    pub fn calculate_volatility_windowed(&self, window: usize) -> Result<Decimal, ArithmeticError> {
+        let recent = self.returns.iter().skip(self.returns.len().saturating_sub(window));
+        let mut stats = Welford::default();
+        for log_return in recent {
+            stats.push(*log_return)?;
         }
+        self.annualized(&stats)
+    }
 
-        // Calculate returns
-        let returns: Vec<Decimal> = self.price_history
-            .iter()
-            .zip(self.price_history.iter().skip(1))
-            .map(|(prev, curr)| (curr - prev) / prev)
-            .collect();
+    /// Volatility of `prices`, oldest first, computed afresh: the figure
+    /// `calculate_volatility` reaches after being fed them, when they fit
+    /// its window
+    pub fn calculate_volatility_over(&self, prices: &[Price]) -> Result<Decimal, ArithmeticError> {
+        self.realized_volatility(prices.iter().copied())
+    }
 
-        // Calculate variance
-        let mean: Decimal = returns.iter().sum::<Decimal>() / Decimal::from(returns.len());
-        let variance: Decimal = returns.iter()
-            .map(|r| (r - mean).powi(2))
-            .sum::<Decimal>() / Decimal::from(returns.len());
+    /// Annualized volatility of the log returns between `prices`, skipping
+    /// returns into or out of a zero price
+    fn realized_volatility(&self, prices: impl Iterator<Item = Price>) -> Result<Decimal, ArithmeticError> {
+        let mut stats = Welford::default();
+        let mut previous: Option<Price> = None;
+        for price in prices {
+            if let Some(previous) = previous.replace(price).filter(|previous| !previous.is_zero() && !price.is_zero()) {
+                let ratio = checked_div("log return", price.get(), previous.get())?;
+                let log_return = ratio.checked_ln().ok_or(ArithmeticError::Overflow("log return"))?;
+                stats.push(log_return)?;
+            }
+        }
+        self.annualized(&stats)
+    }
 
-        // Volatility = sqrt(variance) * sqrt(periods_per_year)
-        // Assuming 1-minute bars, 525600 periods per year
-        variance.sqrt() * Decimal::from(724) // sqrt(525600) ≈ 724
+    /// Population standard deviation of the returns in `stats`, scaled to a
+    /// year; zero for no returns or a variance rounded to zero or below
+    fn annualized(&self, stats: &Welford) -> Result<Decimal, ArithmeticError> {
+        let variance = stats.variance()?;
+        if variance <= Decimal::ZERO {
+            return Ok(Decimal::ZERO);
+        }
+        let annual_variance = checked_mul("volatility", variance, self.periods_per_year)?;
+        annual_variance.sqrt().ok_or(ArithmeticError::Overflow("volatility"))
     }
 
     /// Calculate RSI (Relative Strength Index) - deterministic
-    pub fn calculate_rsi(&self, prices: &[Price], period: usize) -> Option<Decimal> {
-        if prices.len() < period + 1 {
-            return None;
+    ///
+    /// Wilder's RSI over `period` changes as of the last of `prices`, by
+    /// feeding them through an `RsiState`; None before `period` changes.
+    pub fn calculate_rsi(&self, prices: &[Price], period: usize) -> Result<Option<Decimal>, ArithmeticError> {
+        let mut state = RsiState::new(period);
+        let mut rsi = None;
+        for price in prices {
+            rsi = state.update(*price)?;
         }
+        Ok(rsi)
+    }
+
+    /// Feed `price` to the running RSI; its value once `rsi_period`
+    /// changes have been seen
+    pub fn update_rsi(&mut self, price: Price) -> Result<Option<Decimal>, ArithmeticError> {
+        self.rsi.update(price)
+    }
 
-        let changes: Vec<Decimal> = prices.windows(2)
-            .map(|w| w[1] - w[0])
-            .collect();
+    /// The running RSI
+    pub fn rsi(&self) -> &RsiState {
+        &self.rsi
+    }
 
-        let gains: Decimal = changes.iter()
-            .filter(|&&c| c > Decimal::ZERO)
-            .sum();
+    /// Feed `price` to every running indicator, RSI included (so a caller
+    /// uses this or `update_rsi`, not both), alongside `calculate_volatility`
+    pub fn update_indicators(&mut self, price: Price) -> Result<Indicators, ArithmeticError> {
+        Ok(Indicators {
+            rsi: self.rsi.update(price)?,
+            ema: self.ema.update(price.get())?,
+            macd: self.macd.update(price)?,
+            bollinger: self.bollinger.update(price)?,
+        })
+    }
 
-        let losses: Decimal = changes.iter()
-            .filter(|&&c| c < Decimal::ZERO)
-            .map(|c| -c)
-            .sum();
+    /// The running indicators as of the latest price
+    pub fn indicators(&self) -> Result<Indicators, ArithmeticError> {
+        Ok(Indicators {
+            rsi: self.rsi.value()?,
+            ema: self.ema.value(),
+            macd: self.macd.value()?,
+            bollinger: self.bollinger.value()?,
+        })
+    }
 
-        if losses == Decimal::ZERO {
-            return Some(Decimal::from(100));
+    /// EMA over `period` prices as of the last of `prices`; None before
+    /// `period` prices
+    pub fn calculate_ema(&self, prices: &[Price], period: usize) -> Result<Option<Decimal>, ArithmeticError> {
+        let mut state = EmaState::new(period);
+        let mut ema = None;
+        for price in prices {
+            ema = state.update(price.get())?;
         }
+        Ok(ema)
+    }
+
+    /// MACD with `params` as of the last of `prices`; None until the signal
+    /// line has its first value
+    pub fn calculate_macd(&self, prices: &[Price], params: MacdParams) -> Result<Option<Macd>, ArithmeticError> {
+        let mut state = MacdState::new(params);
+        let mut macd = None;
+        for price in prices {
+            macd = state.update(*price)?;
+        }
+        Ok(macd)
+    }
+
+    /// Bollinger Bands over the last `period` of `prices`, `width` standard
+    /// deviations wide; None for fewer than `period` prices
+    pub fn calculate_bollinger(&self, prices: &[Price], period: usize, width: Decimal) -> Result<Option<BollingerBands>, ArithmeticError> {
+        let mut state = BollingerState::new(period, width);
+        let mut bands = None;
+        for price in prices {
+            bands = state.update(*price)?;
+        }
+        Ok(bands)
+    }
+}
+
+/// Welford's running mean and sum of squared deviations from it, with
+/// removal for a sliding window
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Welford {
+    count: usize,
+    mean: Decimal,
+    /// Sum of squared deviations from `mean`
+    m2: Decimal,
+}
+
+impl Welford {
+    fn push(&mut self, value: Decimal) -> Result<(), ArithmeticError> {
+        self.count += 1;
+        let delta = checked_sub("volatility", value, self.mean)?;
+        self.mean = checked_add("volatility", self.mean, checked_div("volatility", delta, Decimal::from(self.count))?)?;
+        let spread = checked_mul("volatility", delta, checked_sub("volatility", value, self.mean)?)?;
+        self.m2 = checked_add("volatility", self.m2, spread)?;
+        Ok(())
+    }
+
+    /// Take out `value`, one pushed earlier
+    fn pop(&mut self, value: Decimal) -> Result<(), ArithmeticError> {
+        if self.count <= 1 {
+            *self = Self::default();
+            return Ok(());
+        }
+        self.count -= 1;
+        let delta = checked_sub("volatility", value, self.mean)?;
+        self.mean = checked_sub("volatility", self.mean, checked_div("volatility", delta, Decimal::from(self.count))?)?;
+        let spread = checked_mul("volatility", delta, checked_sub("volatility", value, self.mean)?)?;
+        self.m2 = checked_sub("volatility", self.m2, spread)?;
+        Ok(())
+    }
+
+    /// Population variance; zero for no values
+    fn variance(&self) -> Result<Decimal, ArithmeticError> {
+        if self.count == 0 {
+            return Ok(Decimal::ZERO);
+        }
+        checked_div("volatility", self.m2, Decimal::from(self.count))
+    }
+}
+
+/// Wilder's RSI, updated one price at a time
+///
+/// The first `period` changes seed the average gain and loss as simple
+/// means; each later change is smoothed in with weight 1/`period`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RsiState {
+    period: usize,
+    last_price: Option<Price>,
+    /// Changes seen, counted up to `period`
+    changes: usize,
+    /// Sums while seeding, Wilder averages after
+    average_gain: Decimal,
+    average_loss: Decimal,
+}
+
+impl RsiState {
+    /// Over `period` changes (at least one)
+    pub fn new(period: usize) -> Self {
+        Self {
+            period: period.max(1),
+            last_price: None,
+            changes: 0,
+            average_gain: Decimal::ZERO,
+            average_loss: Decimal::ZERO,
+        }
+    }
+
+    pub fn period(&self) -> usize {
+        self.period
+    }
+
+    /// Take the next price; the RSI once `period` changes have been seen
+    pub fn update(&mut self, price: Price) -> Result<Option<Decimal>, ArithmeticError> {
+        let Some(previous) = self.last_price.replace(price) else {
+            return Ok(None);
+        };
+        let change = price.delta(previous);
+        let (gain, loss) = if change > Decimal::ZERO { (change, Decimal::ZERO) } else { (Decimal::ZERO, -change) };
+        let period = Decimal::from(self.period);
+
+        if self.changes < self.period {
+            self.average_gain = checked_add("rsi", self.average_gain, gain)?;
+            self.average_loss = checked_add("rsi", self.average_loss, loss)?;
+            self.changes += 1;
+            if self.changes < self.period {
+                return Ok(None);
+            }
+            self.average_gain = checked_div("rsi", self.average_gain, period)?;
+            self.average_loss = checked_div("rsi", self.average_loss, period)?;
+        } else {
+            let kept = period - Decimal::ONE;
+            self.average_gain = checked_div("rsi", checked_add("rsi", checked_mul("rsi", self.average_gain, kept)?, gain)?, period)?;
+            self.average_loss = checked_div("rsi", checked_add("rsi", checked_mul("rsi", self.average_loss, kept)?, loss)?, period)?;
+        }
+        self.value()
+    }
+
+    /// The RSI as of the last price; None before `period` changes
+    pub fn value(&self) -> Result<Option<Decimal>, ArithmeticError> {
+        if self.changes < self.period {
+            return Ok(None);
+        }
+        // No losses: maximally overbought
+        if self.average_loss == Decimal::ZERO {
+            return Ok(Some(Decimal::ONE_HUNDRED));
+        }
+        let rs = checked_div("rsi", self.average_gain, self.average_loss)?;
+        let denominator = checked_add("rsi", Decimal::ONE, rs)?;
+        Ok(Some(Decimal::ONE_HUNDRED - checked_div("rsi", Decimal::ONE_HUNDRED, denominator)?))
+    }
+}
+
+/// Exponential moving average, updated one value at a time
+///
+/// The first `period` values seed it as their simple mean; each later value
+/// is weighted 2/(`period` + 1).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmaState {
+    period: usize,
+    /// Values seen, counted up to `period`
+    seen: usize,
+    /// Sum while seeding, the average after
+    average: Decimal,
+}
+
+impl EmaState {
+    /// Over `period` values (at least one)
+    pub fn new(period: usize) -> Self {
+        Self { period: period.max(1), seen: 0, average: Decimal::ZERO }
+    }
+
+    pub fn period(&self) -> usize {
+        self.period
+    }
+
+    /// Take the next value; the average once `period` values have been seen
+    pub fn update(&mut self, value: Decimal) -> Result<Option<Decimal>, ArithmeticError> {
+        let period = Decimal::from(self.period);
+        if self.seen < self.period {
+            self.average = checked_add("ema", self.average, value)?;
+            self.seen += 1;
+            if self.seen < self.period {
+                return Ok(None);
+            }
+            self.average = checked_div("ema", self.average, period)?;
+        } else {
+            let weight = checked_div("ema", Decimal::TWO, checked_add("ema", period, Decimal::ONE)?)?;
+            let step = checked_mul("ema", weight, checked_sub("ema", value, self.average)?)?;
+            self.average = checked_add("ema", self.average, step)?;
+        }
+        Ok(Some(self.average))
+    }
+
+    /// The average as of the last value; None before `period` values
+    pub fn value(&self) -> Option<Decimal> {
+        (self.seen >= self.period).then_some(self.average)
+    }
+}
+
+/// EMA periods of the MACD's fast, slow and signal lines
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MacdParams {
+    pub fast: usize,
+    pub slow: usize,
+    pub signal: usize,
+}
 
-        let rs = gains / losses;
-        Some(Decimal::from(100) - (Decimal::from(100) / (Decimal::ONE + rs)))
+impl Default for MacdParams {
+    fn default() -> Self {
+        Self { fast: 12, slow: 26, signal: 9 }
     }
 }
 
+/// One MACD reading
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Macd {
+    /// Fast EMA less slow EMA
+    pub line: Decimal,
+    /// EMA of `line`
+    pub signal: Decimal,
+    /// `line` less `signal`
+    pub histogram: Decimal,
+}
+
+/// MACD, updated one price at a time
+///
+/// The line starts once the slow EMA is seeded; the signal EMA is seeded
+/// from the line's first `signal` values, so the first reading comes after
+/// `slow` + `signal` - 1 prices.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MacdState {
+    fast: EmaState,
+    slow: EmaState,
+    signal: EmaState,
+    line: Option<Decimal>,
+}
+
+impl MacdState {
+    pub fn new(params: MacdParams) -> Self {
+        Self {
+            fast: EmaState::new(params.fast),
+            slow: EmaState::new(params.slow),
+            signal: EmaState::new(params.signal),
+            line: None,
+        }
+    }
+
+    /// Take the next price; the reading once the signal line has a value
+    pub fn update(&mut self, price: Price) -> Result<Option<Macd>, ArithmeticError> {
+        let fast = self.fast.update(price.get())?;
+        let slow = self.slow.update(price.get())?;
+        let (Some(fast), Some(slow)) = (fast, slow) else {
+            return Ok(None);
+        };
+        let line = checked_sub("macd", fast, slow)?;
+        self.line = Some(line);
+        self.signal.update(line)?;
+        self.value()
+    }
+
+    /// The reading as of the last price; None before the signal line has a
+    /// value
+    pub fn value(&self) -> Result<Option<Macd>, ArithmeticError> {
+        let (Some(line), Some(signal)) = (self.line, self.signal.value()) else {
+            return Ok(None);
+        };
+        Ok(Some(Macd { line, signal, histogram: checked_sub("macd", line, signal)? }))
+    }
+}
+
+/// Bollinger Bands: the mean of recent prices and a band either side
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BollingerBands {
+    pub lower: Decimal,
+    /// Simple mean over the period
+    pub middle: Decimal,
+    pub upper: Decimal,
+}
+
+/// Bollinger Bands, updated one price at a time
+///
+/// The window keeps running sums of prices and squared prices, exact in
+/// fixed point as prices leave it, so each price costs O(1). The deviation
+/// is the population one, as Bollinger defines it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BollingerState {
+    period: usize,
+    /// Standard deviations from the middle to either band
+    width: Decimal,
+    /// Latest prices, oldest first
+    window: VecDeque<Decimal>,
+    sum: Decimal,
+    square_sum: Decimal,
+}
+
+impl BollingerState {
+    /// Over `period` prices (at least one), `width` standard deviations wide
+    pub fn new(period: usize, width: Decimal) -> Self {
+        let period = period.max(1);
+        Self {
+            period,
+            width,
+            window: VecDeque::with_capacity(period),
+            sum: Decimal::ZERO,
+            square_sum: Decimal::ZERO,
+        }
+    }
+
+    pub fn period(&self) -> usize {
+        self.period
+    }
+
+    /// Take the next price; the bands once `period` prices have been seen
+    pub fn update(&mut self, price: Price) -> Result<Option<BollingerBands>, ArithmeticError> {
+        let price = price.get();
+        self.sum = checked_add("bollinger", self.sum, price)?;
+        self.square_sum = checked_add("bollinger", self.square_sum, checked_mul("bollinger", price, price)?)?;
+        self.window.push_back(price);
+        while self.window.len() > self.period {
+            let Some(oldest) = self.window.pop_front() else { break };
+            self.sum = checked_sub("bollinger", self.sum, oldest)?;
+            self.square_sum = checked_sub("bollinger", self.square_sum, checked_mul("bollinger", oldest, oldest)?)?;
+        }
+        self.value()
+    }
+
+    /// The bands as of the last price; None before `period` prices
+    pub fn value(&self) -> Result<Option<BollingerBands>, ArithmeticError> {
+        if self.window.len() < self.period {
+            return Ok(None);
+        }
+        let count = Decimal::from(self.window.len());
+        let middle = checked_div("bollinger", self.sum, count)?;
+        let variance = checked_sub("bollinger", checked_div("bollinger", self.square_sum, count)?, checked_mul("bollinger", middle, middle)?)?;
+        // A flat window rounds to zero or just below
+        let deviation = if variance <= Decimal::ZERO {
+            Decimal::ZERO
+        } else {
+            variance.sqrt().ok_or(ArithmeticError::Overflow("bollinger"))?
+        };
+        let offset = checked_mul("bollinger", self.width, deviation)?;
+        Ok(Some(BollingerBands {
+            lower: checked_sub("bollinger", middle, offset)?,
+            middle,
+            upper: checked_add("bollinger", middle, offset)?,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn price(value: &str) -> Price {
+        Price::new(value.parse().unwrap()).unwrap()
+    }
+
+    fn prices(values: &[&str]) -> Vec<Price> {
+        values.iter().map(|value| price(value)).collect()
+    }
+
+    fn assert_close(actual: Decimal, expected: Decimal) {
+        assert!((actual - expected).abs() < Decimal::new(1, 20), "{} is not {}", actual, expected);
+    }
+
+    fn calculator(max_history: usize) -> FeatureCalculator {
+        FeatureCalculator::new(max_history, DepthWindow::default(), MINUTES_PER_YEAR)
+    }
+
+    #[test]
+    fn incremental_volatility_equals_from_scratch() {
+        let series = prices(&[
+            "100", "101.5", "99.8", "102.3", "102.1", "98.7", "100.4", "103.9", "101.2", "104.6",
+            "103.3", "105.8", "102.9", "106.1", "104.4",
+        ]);
+        // Five prices per window, so returns leave it from the sixth on
+        let mut calculator = calculator(5);
+        for (index, current) in series.iter().enumerate() {
+            let incremental = calculator.calculate_volatility(*current).unwrap();
+            let window = &series[index.saturating_sub(4)..=index];
+            let from_scratch = calculator.calculate_volatility_over(window).unwrap();
+            assert!(
+                (incremental - from_scratch).abs() < Decimal::new(1, 18),
+                "at {}: {} incrementally, {} from scratch", index, incremental, from_scratch,
+            );
+        }
+    }
+
+    #[test]
+    fn rsi_matches_wilder_by_hand() {
+        // Changes +1 +1 -1 seed gains 2/3 and losses 1/3; +2 then -1 follow
+        // Wilder's (average × 2 + change) / 3
+        let mut rsi = RsiState::new(3);
+        let readings: Vec<_> =
+            prices(&["10", "11", "12", "11", "13", "12"]).into_iter().map(|p| rsi.update(p).unwrap()).collect();
+        assert_eq!(&readings[..3], &[None, None, None]);
+        let hundred = Decimal::ONE_HUNDRED;
+        // RS 2, then (10/9) / (2/9) = 5, then (20/27) / (13/27) = 20/13
+        assert_close(readings[3].unwrap(), hundred - hundred / Decimal::from(3));
+        assert_close(readings[4].unwrap(), hundred - hundred / Decimal::from(6));
+        assert_close(readings[5].unwrap(), hundred - Decimal::from(1300) / Decimal::from(33));
+        assert_eq!(rsi.value().unwrap(), readings[5]);
+    }
+
+    #[test]
+    fn rsi_without_losses_is_one_hundred() {
+        let mut rsi = RsiState::new(2);
+        let readings: Vec<_> = prices(&["10", "11", "12"]).into_iter().map(|p| rsi.update(p).unwrap()).collect();
+        assert_eq!(readings, vec![None, None, Some(Decimal::ONE_HUNDRED)]);
+    }
+
+    #[test]
+    fn ema_seeds_on_the_simple_mean_then_weights_by_period() {
+        // Seed (1 + 2 + 3) / 3 = 2, then weight 2 / (3 + 1) = 0.5
+        let mut ema = EmaState::new(3);
+        let readings: Vec<_> = ["1", "2", "3", "4", "8"].iter().map(|v| ema.update(v.parse().unwrap()).unwrap()).collect();
+        let expected = [None, None, Some(2), Some(3)].map(|v| v.map(Decimal::from));
+        assert_eq!(&readings[..4], &expected);
+        assert_eq!(readings[4], Some("5.5".parse().unwrap()));
+    }
+
+    #[test]
+    fn macd_matches_hand_computed_readings() {
+        // Fast EMA 1.5, 2.5, 3.5, 6.5, 37/6; slow 2, 3, 5.5, 5.75; the line
+        // 0.5, 0.5, 1, 5/12 seeds the signal at 0.5 on the fourth price
+        let mut macd = MacdState::new(MacdParams { fast: 2, slow: 3, signal: 2 });
+        let readings: Vec<_> =
+            prices(&["1", "2", "3", "4", "8", "6"]).into_iter().map(|p| macd.update(p).unwrap()).collect();
+        assert_eq!(&readings[..3], &[None, None, None]);
+        let half = Decimal::new(5, 1);
+        assert_eq!(readings[3], Some(Macd { line: half, signal: half, histogram: Decimal::ZERO }));
+
+        let fifth = readings[4].unwrap();
+        assert_close(fifth.line, Decimal::ONE);
+        assert_close(fifth.signal, Decimal::from(5) / Decimal::from(6));
+        assert_close(fifth.histogram, Decimal::ONE / Decimal::from(6));
+
+        let sixth = readings[5].unwrap();
+        assert_close(sixth.line, Decimal::from(5) / Decimal::from(12));
+        assert_close(sixth.signal, Decimal::from(5) / Decimal::from(9));
+        assert_close(sixth.histogram, Decimal::from(-5) / Decimal::from(36));
+    }
+
+    #[test]
+    fn bollinger_bands_sit_width_deviations_from_the_mean() {
+        // 3 3 7 7 and 3 7 7 3: mean 5, population deviation 2
+        let mut bollinger = BollingerState::new(4, Decimal::TWO);
+        let readings: Vec<_> =
+            prices(&["3", "3", "7", "7", "3"]).into_iter().map(|p| bollinger.update(p).unwrap()).collect();
+        assert_eq!(&readings[..3], &[None, None, None]);
+        let bands = BollingerBands { lower: Decimal::ONE, middle: Decimal::from(5), upper: Decimal::from(9) };
+        assert_eq!(&readings[3..], &[Some(bands), Some(bands)]);
+    }
+
+    #[test]
+    fn bollinger_bands_collapse_on_a_flat_window() {
+        let mut bollinger = BollingerState::new(3, Decimal::TWO);
+        let five = Decimal::from(5);
+        let readings: Vec<_> = prices(&["9", "5", "5", "5"]).into_iter().map(|p| bollinger.update(p).unwrap()).collect();
+        assert_eq!(readings[3], Some(BollingerBands { lower: five, middle: five, upper: five }));
+    }
+}
@@ -0,0 +1,90 @@
+//! Fixed Point: Scaled-Integer Fast Path for Feature Sums
+//!
+//! Book depth sums are the inner loop of feature calculation, and general
+//! Decimal arithmetic is several times slower than i128 math. A
+//! `FixedPoint` is an i128 mantissa with the scale Decimal would have given
+//! the same result (max for sums, the sum for products). Mantissas stay
+//! below 2^96 and scales at or below 28, the range where Decimal is exact
+//! too, and every operation is exact or fails. Callers fall back to Decimal
+//! on failure, so results convert back to the identical Decimal (value and
+//! scale) the Decimal path would have produced.
+
+use rust_decimal::Decimal;
+
+/// Most fractional digits kept (Decimal's maximum scale)
+const SCALE: u32 = 28;
+
+/// Powers of ten up to 10^SCALE
+const POW10: [i128; SCALE as usize + 1] = {
+    let mut table = [1i128; SCALE as usize + 1];
+    let mut i = 1;
+    while i < table.len() {
+        table[i] = table[i - 1] * 10;
+        i += 1;
+    }
+    table
+};
+
+/// Largest mantissa kept: Decimal's 96 bits
+const MAX_MANTISSA: u128 = 1 << 96;
+
+/// Exact decimal value in the range Decimal represents without rounding
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct FixedPoint {
+    mantissa: i128,
+    scale: u32,
+}
+
+impl FixedPoint {
+    /// Same value and scale as `Decimal::ZERO`
+    pub(crate) const ZERO: Self = Self { mantissa: 0, scale: 0 };
+
+    pub(crate) fn from_decimal(value: Decimal) -> Self {
+        Self { mantissa: value.mantissa(), scale: value.scale() }
+    }
+
+    /// The Decimal with this value and scale
+    pub(crate) fn to_decimal(self) -> Decimal {
+        Decimal::from_i128_with_scale(self.mantissa, self.scale)
+    }
+
+    pub(crate) fn checked_add(self, other: Self) -> Option<Self> {
+        let (a, b, scale) = Self::aligned(self, other)?;
+        Self::bounded(a.checked_add(b)?, scale)
+    }
+
+    pub(crate) fn checked_sub(self, other: Self) -> Option<Self> {
+        let (a, b, scale) = Self::aligned(self, other)?;
+        Self::bounded(a.checked_sub(b)?, scale)
+    }
+
+    /// None when the product needs more than `SCALE` fractional digits
+    pub(crate) fn checked_mul(self, other: Self) -> Option<Self> {
+        let scale = self.scale + other.scale;
+        if scale > SCALE {
+            return None;
+        }
+        Self::bounded(product(self.mantissa, other.mantissa)?, scale)
+    }
+
+    /// Both mantissas at the larger scale
+    fn aligned(a: Self, b: Self) -> Option<(i128, i128, u32)> {
+        let scale = a.scale.max(b.scale);
+        let widen = |value: Self| product(value.mantissa, POW10[(scale - value.scale) as usize]);
+        Some((widen(a)?, widen(b)?, scale))
+    }
+
+    fn bounded(mantissa: i128, scale: u32) -> Option<Self> {
+        (mantissa.unsigned_abs() < MAX_MANTISSA).then_some(Self { mantissa, scale })
+    }
+}
+
+/// `a × b`; i128's overflow-checked multiply is slow, so it is skipped when
+/// both factors fit in 63 bits and the product cannot overflow
+fn product(a: i128, b: i128) -> Option<i128> {
+    const SMALL: u128 = 1 << 63;
+    if a.unsigned_abs() < SMALL && b.unsigned_abs() < SMALL {
+        return Some(a * b);
+    }
+    a.checked_mul(b)
+}
@@ -2,15 +2,39 @@
 //!
 //! Implements the Proposer-Verifier architecture:
 //! - Proposer: Neural network (Mamba-2) suggests trades
-//! - Verifier: SMT Solver proves trades satisfy L0 invariants
+//! - Verifier: SMT Solver proves trades satisfy L0 invariants (or, without
+//!   the `smt` feature, the same axioms are checked directly)
 
 pub mod proposer;
+pub mod strategy;
 pub mod verifier;
+pub mod verification_pool;
+pub mod proof_cache;
+pub mod rules;
+#[cfg(feature = "smt")]
+pub mod smt;
 pub mod signals;
 pub mod features;
+pub mod cross_venue;
+pub mod cooldown;
+pub mod regime;
+pub mod shadow;
+mod fixed;
+#[cfg(test)]
+mod testing;
 
 pub use proposer::*;
+pub use strategy::*;
 pub use verifier::*;
+pub use verification_pool::*;
+pub use proof_cache::*;
+pub use rules::*;
+#[cfg(feature = "smt")]
+pub use smt::*;
 pub use signals::*;
 pub use features::*;
+pub use cross_venue::*;
+pub use cooldown::*;
+pub use regime::*;
+pub use shadow::*;
 
@@ -0,0 +1,330 @@
+//! Proof Cache: Reusing SMT Proofs for Near-Identical Signals
+//!
+//! Quoting paths verify the same trade over and over with barely changed
+//! inputs. The SMT model only names the axioms checked and the limit
+//! constants, so a proof found for one signal holds for any other that
+//! lands in the same buckets of symbol, side, size, price, position,
+//! leverage and entropy. Those proofs are kept, least recently used
+//! evicted first, and handed out again marked `cached`.
+//!
+//! Only the solver call is skipped: the L0 contract still checks every
+//! signal's exact values first, so a breach since the proof was cached is
+//! still rejected. An entry is dropped once the portfolio's equity has
+//! moved more than `max_equity_change` from when it was cached, or its set
+//! of open positions has changed.
+
+use crate::verifier::ProofObligation;
+use axiom_core::{Portfolio, Proof, Side, Symbol};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Proof cache tuning
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProofCacheConfig {
+    /// Proofs kept; 0 disables the cache
+    pub capacity: usize,
+    /// Bucket widths; a signal reuses a proof when each of its values falls
+    /// in the same bucket. 0 only matches the exact value
+    pub quantity_bucket: Decimal,
+    pub price_bucket: Decimal,
+    pub leverage_bucket: Decimal,
+    pub entropy_bucket: Decimal,
+    /// Drop a proof once equity has moved this fraction from when it was
+    /// cached
+    pub max_equity_change: Decimal,
+}
+
+impl Default for ProofCacheConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 1_024,
+            quantity_bucket: Decimal::new(1, 3),
+            price_bucket: Decimal::new(1, 2),
+            leverage_bucket: Decimal::new(1, 2),
+            entropy_bucket: Decimal::new(1, 15),
+            max_equity_change: Decimal::new(1, 2),
+        }
+    }
+}
+
+/// The buckets a proof is reused across
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ProofKey {
+    symbol: Symbol,
+    side: Side,
+    quantity: Decimal,
+    /// The price the order is valued at, so orders without a limit (stops
+    /// at their trigger) are told apart too
+    price: Decimal,
+    /// The symbol's position once the signal fills
+    projected: Decimal,
+    leverage: Decimal,
+    entropy: Decimal,
+    /// Whether the slippage axiom was part of the proof
+    slippage: bool,
+}
+
+#[derive(Debug, Clone)]
+struct CachedProof {
+    proof: Proof,
+    equity: Decimal,
+    /// Open positions' symbols and sides when cached, sorted
+    positions: Vec<(Symbol, Side)>,
+    /// Position in the recency order
+    used: u64,
+}
+
+/// Proofs by bucket, least recently used evicted first
+#[derive(Debug, Clone, Default)]
+pub struct ProofCache {
+    config: ProofCacheConfig,
+    entries: HashMap<ProofKey, CachedProof>,
+    /// Keys by when they were last used, oldest first
+    recency: BTreeMap<u64, ProofKey>,
+    clock: u64,
+    hits: u64,
+    misses: u64,
+}
+
+impl ProofCache {
+    pub fn new(config: ProofCacheConfig) -> Self {
+        Self { config, ..Self::default() }
+    }
+
+    /// A copy of the proof cached for obligations like `obligation`, marked
+    /// cached and with no solve time; None when there is none, or it no
+    /// longer fits the obligation's portfolio
+    pub fn get(&mut self, obligation: &ProofObligation<'_>) -> Option<Proof> {
+        if self.config.capacity == 0 {
+            return None;
+        }
+        let key = self.key(obligation);
+        let Some(entry) = self.entries.get(&key) else {
+            self.misses += 1;
+            return None;
+        };
+        if !self.still_fits(entry, obligation.portfolio) {
+            let used = entry.used;
+            self.entries.remove(&key);
+            self.recency.remove(&used);
+            self.misses += 1;
+            return None;
+        }
+
+        self.clock += 1;
+        let entry = self.entries.get_mut(&key)?;
+        self.recency.remove(&entry.used);
+        entry.used = self.clock;
+        self.recency.insert(self.clock, key);
+        self.hits += 1;
+        Some(Proof { cached: true, solve_us: 0, ..entry.proof.clone() })
+    }
+
+    /// Keep `proof`, found for `obligation`, evicting the least recently
+    /// used proof when full
+    pub fn insert(&mut self, obligation: &ProofObligation<'_>, proof: &Proof) {
+        if self.config.capacity == 0 {
+            return;
+        }
+        let key = self.key(obligation);
+        if let Some(previous) = self.entries.remove(&key) {
+            self.recency.remove(&previous.used);
+        }
+        while self.entries.len() >= self.config.capacity {
+            let Some((_, oldest)) = self.recency.pop_first() else { break };
+            self.entries.remove(&oldest);
+        }
+
+        self.clock += 1;
+        self.recency.insert(self.clock, key.clone());
+        self.entries.insert(key, CachedProof {
+            proof: proof.clone(),
+            equity: obligation.portfolio.equity.get(),
+            positions: open_positions(obligation.portfolio),
+            used: self.clock,
+        });
+    }
+
+    /// Drop every proof (the limit axioms changed)
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.recency.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Lookups answered from the cache
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// Lookups that had to go to the solver
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    fn key(&self, obligation: &ProofObligation<'_>) -> ProofKey {
+        let config = &self.config;
+        let ProofObligation { signal, portfolio, projected, price, bound, .. } = *obligation;
+        ProofKey {
+            symbol: signal.symbol.clone(),
+            side: signal.side,
+            quantity: bucket(signal.quantity.get(), config.quantity_bucket),
+            price: bucket(price.get(), config.price_bucket),
+            projected: bucket(projected.get(), config.quantity_bucket),
+            leverage: bucket(portfolio.leverage, config.leverage_bucket),
+            entropy: bucket(signal.entropy_count, config.entropy_bucket),
+            slippage: bound.is_some(),
+        }
+    }
+
+    fn still_fits(&self, entry: &CachedProof, portfolio: &Portfolio) -> bool {
+        let equity = portfolio.equity.get();
+        let moved = if entry.equity.is_zero() {
+            !equity.is_zero()
+        } else {
+            ((equity - entry.equity) / entry.equity).abs() > self.config.max_equity_change
+        };
+        !moved && entry.positions == open_positions(portfolio)
+    }
+}
+
+/// The bucket `value` falls in, as its lower edge; `value` itself when
+/// `width` is not positive or the bucket overflows
+fn bucket(value: Decimal, width: Decimal) -> Decimal {
+    if width <= Decimal::ZERO {
+        return value.normalize();
+    }
+    value.checked_div(width)
+        .and_then(|buckets| buckets.floor().checked_mul(width))
+        .unwrap_or(value)
+        .normalize()
+}
+
+fn open_positions(portfolio: &Portfolio) -> Vec<(Symbol, Side)> {
+    let mut positions: Vec<(Symbol, Side)> = portfolio.positions.iter()
+        .map(|position| (position.symbol.clone(), position.side))
+        .collect();
+    positions.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| (a.1 == Side::Sell).cmp(&(b.1 == Side::Sell))));
+    positions.dedup();
+    positions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{self, market, price};
+    use crate::{ProofBackend, RuleVerifier};
+    use axiom_core::{AccountId, Amount, LimitsRegistry, OrderType, Position, Quantity, TradeSignal, Venue};
+
+    /// Look `signal` up against `portfolio`, caching its proof on a miss;
+    /// whether it was a hit
+    fn lookup(cache: &mut ProofCache, signal: &TradeSignal, portfolio: &Portfolio) -> bool {
+        let limits = LimitsRegistry::builtin();
+        let obligation = ProofObligation::new(signal, portfolio, &limits, market()).unwrap();
+        if let Some(proof) = cache.get(&obligation) {
+            assert!(proof.cached);
+            assert_eq!(proof.solve_us, 0);
+            return true;
+        }
+        let proof = RuleVerifier::new().prove(&obligation).unwrap();
+        cache.insert(&obligation, &proof);
+        false
+    }
+
+    fn holding(symbol: &Symbol, side: Side) -> Position {
+        Position {
+            account: AccountId::default(),
+            symbol: symbol.clone(),
+            venue: Venue::Binance,
+            side,
+            quantity: Quantity::new(Decimal::ONE).unwrap(),
+            entry_price: market(),
+            current_price: market(),
+            unrealized_pnl: Amount::ZERO,
+            realized_pnl: Amount::ZERO,
+        }
+    }
+
+    #[test]
+    fn a_repeated_signal_is_answered_from_the_cache() {
+        let mut cache = ProofCache::new(ProofCacheConfig::default());
+        let portfolio = testing::portfolio();
+        assert!(!lookup(&mut cache, &testing::signal(), &portfolio));
+        assert!(lookup(&mut cache, &testing::signal(), &portfolio));
+        assert_eq!((cache.hits(), cache.misses()), (1, 1));
+    }
+
+    #[test]
+    fn a_proof_is_dropped_once_equity_moves_past_the_threshold() {
+        let mut cache = ProofCache::new(ProofCacheConfig::default());
+        let mut portfolio = testing::portfolio();
+        lookup(&mut cache, &testing::signal(), &portfolio);
+
+        // 0.5% is within the default 1%, 1.5% from the cached equity is not
+        portfolio.equity = Amount::new(Decimal::from(2_010_000));
+        assert!(lookup(&mut cache, &testing::signal(), &portfolio));
+        portfolio.equity = Amount::new(Decimal::from(2_030_000));
+        assert!(!lookup(&mut cache, &testing::signal(), &portfolio));
+        assert!(lookup(&mut cache, &testing::signal(), &portfolio));
+    }
+
+    #[test]
+    fn a_proof_is_dropped_once_the_open_positions_change() {
+        let mut cache = ProofCache::new(ProofCacheConfig::default());
+        let mut portfolio = testing::portfolio();
+        let eth = Symbol("ETH/USD".to_string());
+        lookup(&mut cache, &testing::signal(), &portfolio);
+
+        // A position opened in another symbol leaves this one's projection alone
+        portfolio.positions.push(holding(&eth, Side::Buy));
+        assert!(!lookup(&mut cache, &testing::signal(), &portfolio));
+        assert!(lookup(&mut cache, &testing::signal(), &portfolio));
+
+        // Flipped from long to short
+        portfolio.positions[0].side = Side::Sell;
+        assert!(!lookup(&mut cache, &testing::signal(), &portfolio));
+        portfolio.positions.clear();
+        assert!(!lookup(&mut cache, &testing::signal(), &portfolio));
+    }
+
+    #[test]
+    fn stops_at_different_triggers_do_not_share_a_proof() {
+        let mut cache = ProofCache::new(ProofCacheConfig::default());
+        let portfolio = testing::portfolio();
+        let stop = |trigger| {
+            testing::signal_of(OrderType::StopMarket { trigger: price(trigger) })
+                .build(&LimitsRegistry::builtin())
+                .unwrap()
+        };
+        assert!(!lookup(&mut cache, &stop(65_050), &portfolio));
+        assert!(!lookup(&mut cache, &stop(64_000), &portfolio));
+        assert!(lookup(&mut cache, &stop(65_050), &portfolio));
+    }
+
+    #[test]
+    fn the_least_recently_used_proof_is_evicted() {
+        let mut cache = ProofCache::new(ProofCacheConfig { capacity: 2, ..ProofCacheConfig::default() });
+        let portfolio = testing::portfolio();
+        let sized = |quantity: i64| {
+            let mut signal = testing::signal();
+            signal.quantity = Quantity::new(Decimal::new(quantity, 2)).unwrap();
+            signal
+        };
+        lookup(&mut cache, &sized(20), &portfolio);
+        lookup(&mut cache, &sized(25), &portfolio);
+        assert!(lookup(&mut cache, &sized(20), &portfolio));
+        lookup(&mut cache, &sized(30), &portfolio);
+        assert_eq!(cache.len(), 2);
+        assert!(lookup(&mut cache, &sized(20), &portfolio));
+        assert!(!lookup(&mut cache, &sized(25), &portfolio));
+    }
+}
@@ -3,28 +3,332 @@
 //! The "creative" component that proposes trades based on pattern matching.
 //! This is allowed to be probabilistic, but its outputs are verified.
 
-use axiom_core::{TradeSignal, Symbol, Venue, Side, OrderType, OrderBook, Portfolio};
-use axiom_engine::features::FeatureCalculator;
+use axiom_core::{
+    TradeSignal, Symbol, Venue, Side, OrderType, OrderBook, Portfolio, Price, Quantity, LatencyRecorder, LatencyStage,
+    ViolationSet, LimitsRegistry, FeatureVector, StrategyId, BPS_PER_UNIT, InstrumentRegistry, FundingRate,
+    Amount, LiquidationIntensity, ArithmeticError, AxiomRng, OrderStatus, VerifiedOrder,
+};
+use crate::cooldown::{CooldownConfig, CooldownTracker};
+use crate::cross_venue::{CrossVenueConfig, CrossVenueTracker, DivergenceReport};
+use crate::strategy::{ContradictionArbStrategy, ProposalContext, ProposerStrategy};
+use crate::features::{ConfidenceConfig, DepthWindow, FeatureCalculator, LiquidityInput, MINUTES_PER_YEAR};
+use axiom_data::evm::EvmPoolState;
+use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
-use chrono::Utc;
-use tracing::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::{debug, info, warn};
+
+/// Label of the proposer's random stream; strategies draw from forks of it
+pub const PROPOSER_RNG_LABEL: &str = "proposer";
+
+/// Price a proposal's limit is set from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LimitPriceSource {
+    /// Halfway between the touches
+    #[default]
+    Mid,
+    /// The touches weighted by the opposite side's size, which leans toward
+    /// where an imbalanced book is heading
+    Microprice,
+}
+
+/// Proposer tuning
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProposerConfig {
+    /// Propose only above this contradiction score
+    pub contradiction_threshold: Decimal,
+    /// ... and above this relative spread
+    pub spread_threshold: Decimal,
+    /// Size of each proposal (the verifier enforces limits)
+    pub base_quantity: Quantity,
+    /// Fair value proposals are priced at
+    pub limit_price: LimitPriceSource,
+    /// Mids kept per symbol for RSI and volatility
+    pub feature_window: usize,
+    /// Book levels entropy and book shape are measured over
+    pub depth_window: DepthWindow,
+    /// How far the contradiction score's inputs are trusted as they age
+    pub confidence: ConfidenceConfig,
+    /// Strategy proposals are attributed to, for capital allocation
+    pub strategy: StrategyId,
+    /// Propose nothing for a symbol while more than this notional is being
+    /// liquidated in it (either side, over the liquidation window); None
+    /// keeps proposing through a cascade
+    pub max_liquidation_notional: Option<Amount>,
+    /// Books compared across venues and when their divergence is traded
+    pub cross_venue: CrossVenueConfig,
+    /// How long an order holds back more of the same
+    pub cooldown: CooldownConfig,
+}
+
+impl Default for ProposerConfig {
+    fn default() -> Self {
+        Self {
+            contradiction_threshold: Decimal::new(5, 2),
+            spread_threshold: Decimal::new(1, 3),
+            base_quantity: Quantity::new(Decimal::new(1, 1)).unwrap_or(Quantity::ZERO),
+            limit_price: LimitPriceSource::Mid,
+            feature_window: 1000,
+            depth_window: DepthWindow::default(),
+            confidence: ConfidenceConfig::default(),
+            strategy: StrategyId::default(),
+            max_liquidation_notional: None,
+            cross_venue: CrossVenueConfig::default(),
+            cooldown: CooldownConfig::default(),
+        }
+    }
+}
+
+/// A symbol's latest `feature_window` mids, oldest first
+#[derive(Debug, Default)]
+struct MidHistory {
+    mids: Vec<Price>,
+    /// Timestamp of the book the last mid came from
+    last_at: Option<DateTime<Utc>>,
+}
 
 /// Trade proposer (simplified - in production would use Mamba-2)
 pub struct Proposer {
+    config: ProposerConfig,
     feature_calc: FeatureCalculator,
     hallucination_count: u64,
     total_proposals: u64,
+    /// Violations seen per error code (a rejection may count several)
+    violation_counts: BTreeMap<&'static str, u64>,
+    /// Latest on-chain liquidity per symbol
+    onchain: HashMap<Symbol, LiquidityInput>,
+    /// Latest perpetual funding per symbol
+    funding: HashMap<Symbol, FundingRate>,
+    /// Latest liquidation intensity per symbol
+    liquidations: HashMap<Symbol, LiquidationIntensity>,
+    /// Recent mids per symbol, for RSI and volatility
+    history: HashMap<Symbol, MidHistory>,
+    /// The built-in rule `propose_trade` applies
+    strategy: ContradictionArbStrategy,
+    /// Latest book per symbol and venue
+    cross_venue: CrossVenueTracker,
+    /// Orders holding back more of the same
+    cooldowns: CooldownTracker,
+    latency: Option<Arc<dyn LatencyRecorder>>,
+    limits: Arc<LimitsRegistry>,
+    instruments: Arc<InstrumentRegistry>,
+    /// The only randomness proposals may draw on
+    rng: AxiomRng,
 }
 
 impl Proposer {
     pub fn new() -> Self {
+        Self::with_config(ProposerConfig::default())
+    }
+
+    pub fn with_config(config: ProposerConfig) -> Self {
         Self {
-            feature_calc: FeatureCalculator::new(1000),
+            feature_calc: FeatureCalculator::new(config.feature_window, config.depth_window, MINUTES_PER_YEAR),
+            strategy: ContradictionArbStrategy::new(config.clone()),
+            cross_venue: CrossVenueTracker::from_config(&config.cross_venue),
+            cooldowns: CooldownTracker::new(config.cooldown),
+            config,
             hallucination_count: 0,
             total_proposals: 0,
+            violation_counts: BTreeMap::new(),
+            onchain: HashMap::new(),
+            funding: HashMap::new(),
+            liquidations: HashMap::new(),
+            history: HashMap::new(),
+            latency: None,
+            limits: Arc::new(LimitsRegistry::builtin()),
+            instruments: Arc::new(InstrumentRegistry::new()),
+            rng: AxiomRng::new(PROPOSER_RNG_LABEL),
         }
     }
 
+    /// Draw from `rng` instead of the stream seeded by DETERMINISTIC_SEED
+    pub fn set_rng(&mut self, rng: AxiomRng) {
+        self.rng = rng;
+    }
+
+    pub fn rng(&self) -> &AxiomRng {
+        &self.rng
+    }
+
+    /// Report feature_calc latency
+    pub fn set_latency_recorder(&mut self, latency: Arc<dyn LatencyRecorder>) {
+        self.latency = Some(latency);
+    }
+
+    /// Retune in place, keeping counters and recorders; the price history
+    /// is only discarded when the feature or depth window changes
+    pub fn set_config(&mut self, config: ProposerConfig) {
+        if config.feature_window != self.config.feature_window || config.depth_window != self.config.depth_window {
+            self.feature_calc = FeatureCalculator::new(config.feature_window, config.depth_window, MINUTES_PER_YEAR);
+        }
+        if config.cross_venue.max_book_age_ms != self.config.cross_venue.max_book_age_ms {
+            self.cross_venue = CrossVenueTracker::from_config(&config.cross_venue);
+        }
+        self.strategy.set_config(&config);
+        self.cooldowns.set_config(config.cooldown);
+        self.config = config;
+    }
+
+    /// Whether `signal` repeats an order still cooling down, `mid` being the
+    /// mid of the book it was proposed on
+    pub fn cooldown_suppresses(&mut self, signal: &TradeSignal, mid: Price) -> bool {
+        self.cooldowns.suppresses(&signal.account, &signal.symbol, signal.side, mid, signal.timestamp)
+    }
+
+    /// Hold back more of `order` (hashed as `order_id`), made with the mid
+    /// at `mid`
+    pub fn start_cooldown(&mut self, order: &VerifiedOrder, order_id: &str, mid: Price) {
+        let signal = &order.signal;
+        self.cooldowns.start(&signal.account, &signal.symbol, signal.side, mid, signal.timestamp, order_id.to_string());
+    }
+
+    /// End the cooldown order `order_id` started once it is done; true when
+    /// one ended
+    pub fn on_order_update(&mut self, order_id: &str, status: OrderStatus) -> bool {
+        self.cooldowns.on_order_update(order_id, status)
+    }
+
+    /// The book's features as this proposer's history sees them; None for
+    /// a one-sided book or an arithmetic failure
+    pub fn feature_vector(&self, book: &OrderBook) -> Option<FeatureVector> {
+        self.compute_features(book).ok().flatten()
+    }
+
+    /// The book's features over the mids of earlier books (the same
+    /// whether or not this book's mid has been recorded yet)
+    fn compute_features(&self, book: &OrderBook) -> Result<Option<FeatureVector>, ArithmeticError> {
+        let history = match self.history.get(&book.symbol) {
+            Some(history) if history.last_at == Some(book.timestamp) => &history.mids[..history.mids.len() - 1],
+            Some(history) => &history.mids[..],
+            None => &[],
+        };
+        self.feature_calc.compute_all(
+            book,
+            history,
+            &self.onchain_liquidity(book),
+            &self.config.confidence,
+            self.funding.get(&book.symbol),
+            self.liquidation_intensity(&book.symbol),
+        )
+    }
+
+    /// Keep the book's mid in its symbol's history, once per book (books
+    /// no newer than the last one kept are ignored), and the book as its
+    /// venue's latest; `propose_trade` does this itself, so this is for
+    /// books it is not shown
+    pub fn record_book(&mut self, book: &OrderBook) {
+        self.cross_venue.record(book);
+        let Some(mid) = axiom_data::normalization::calculate_mid_price(book) else { return };
+        let window = self.config.feature_window.max(1);
+        let history = self.history.entry(book.symbol.clone()).or_default();
+        if history.last_at.is_some_and(|last_at| last_at >= book.timestamp) {
+            return;
+        }
+        history.mids.push(mid);
+        history.last_at = Some(book.timestamp);
+        if history.mids.len() > window {
+            let excess = history.mids.len() - window;
+            history.mids.drain(..excess);
+        }
+    }
+
+    /// Compare `symbol`'s books against `input` from now on
+    pub fn record_onchain_liquidity(&mut self, symbol: Symbol, input: LiquidityInput) {
+        self.onchain.insert(symbol, input);
+    }
+
+    /// Compare the pool's symbol's books against its holdings from now on
+    pub fn record_evm_pool(&mut self, state: &EvmPoolState) {
+        self.record_onchain_liquidity(state.symbol.clone(), LiquidityInput::from_evm_pool(state));
+    }
+
+    /// Measure `funding`'s symbol's basis against it from now on
+    pub fn record_funding(&mut self, funding: FundingRate) {
+        self.funding.insert(funding.symbol.clone(), funding);
+    }
+
+    /// `symbol`'s funding basis against `mid`, when its funding is known
+    pub fn funding_basis(&self, symbol: &Symbol, mid: Price) -> Option<Decimal> {
+        let funding = self.funding.get(symbol)?;
+        self.feature_calc.calculate_funding_basis(mid, funding.mark_price, funding.rate).ok()
+    }
+
+    /// Hold back `symbol`'s proposals while `intensity` is over the
+    /// configured limit, from now on
+    pub fn record_liquidation_intensity(&mut self, symbol: Symbol, intensity: LiquidationIntensity) {
+        self.liquidations.insert(symbol, intensity);
+    }
+
+    /// `symbol`'s latest liquidation intensity (zero when none was recorded)
+    pub fn liquidation_intensity(&self, symbol: &Symbol) -> LiquidationIntensity {
+        self.liquidations.get(symbol).copied().unwrap_or_default()
+    }
+
+    /// Whether `symbol` is liquidating past `max_liquidation_notional`
+    fn in_cascade(&self, symbol: &Symbol) -> bool {
+        let Some(max) = self.config.max_liquidation_notional else { return false };
+        // An unsummable intensity is past any limit
+        !self.liquidation_intensity(symbol).total().is_ok_and(|total| total <= max)
+    }
+
+    fn onchain_liquidity(&self, book: &OrderBook) -> LiquidityInput {
+        self.onchain.get(&book.symbol).cloned().unwrap_or_else(|| LiquidityInput::absent(book))
+    }
+
+    /// Build signals only for symbols in `limits`
+    pub fn set_limits(&mut self, limits: Arc<LimitsRegistry>) {
+        self.limits = limits;
+    }
+
+    /// Put prices and sizes on the venue grids in `instruments`
+    pub fn set_instruments(&mut self, instruments: Arc<InstrumentRegistry>) {
+        self.instruments = instruments;
+    }
+
+    /// Record `book` and compute its features for a proposal; None while
+    /// `symbol` is liquidating past the limit, for a one-sided book, or
+    /// when its features are not computable
+    pub fn proposal_features(&mut self, symbol: &Symbol, venue: &Venue, book: &OrderBook) -> Option<FeatureVector> {
+        self.record_book(book);
+
+        if self.in_cascade(symbol) {
+            debug!("{} is liquidating past the limit; no proposal", symbol.0);
+            return None;
+        }
+
+        let start = Instant::now();
+        let features = self.compute_features(book);
+        if let Some(latency) = &self.latency {
+            latency.record(LatencyStage::FeatureCalc, venue, start.elapsed());
+        }
+        match features {
+            Ok(features) => features,
+            Err(e) => {
+                warn!("Features for {} not computable, no proposal: {}", symbol.0, e);
+                None
+            }
+        }
+    }
+
+    /// What a strategy proposes on `book` from, with this proposer's limits
+    /// and instruments
+    pub fn context<'a>(
+        &'a self,
+        symbol: &'a Symbol,
+        venue: &'a Venue,
+        features: &'a FeatureVector,
+        book: &'a OrderBook,
+        portfolio: &'a Portfolio,
+    ) -> ProposalContext<'a> {
+        ProposalContext { symbol, venue, features, book, portfolio, limits: &self.limits, instruments: &self.instruments }
+    }
+
     /// Propose a trade signal based on market state
     ///
     /// This is the "thinking" component - it can be creative and probabilistic.
@@ -37,52 +341,102 @@ impl Proposer {
         portfolio: &Portfolio,
     ) -> Option<TradeSignal> {
         self.total_proposals += 1;
+        let features = self.proposal_features(symbol, venue, book)?;
+        let context = ProposalContext {
+            symbol,
+            venue,
+            features: &features,
+            book,
+            portfolio,
+            limits: &self.limits,
+            instruments: &self.instruments,
+        };
+        self.strategy.propose(&context)
+    }
 
-        // Calculate features
-        let contradiction_score = self.feature_calc.calculate_contradiction_score(book, Decimal::ZERO);
-        let entropy = self.feature_calc.calculate_entropy(book);
-        
-        // Simple rule-based proposer (in production, this would be a neural network)
-        // Look for arbitrage opportunities (crossed spreads, mispricing)
-        let mid_price = axiom_data::normalization::calculate_mid_price(book)?;
-        let spread = axiom_data::normalization::calculate_spread(book)?;
-        let spread_pct = axiom_data::normalization::calculate_spread_pct(book)?;
-
-        // Propose trade if contradiction is high (market inefficiency detected)
-        let threshold = Decimal::from_str_exact("0.05").unwrap_or(Decimal::from(5) / Decimal::from(100));
-        let spread_threshold = Decimal::from_str_exact("0.001").unwrap_or(Decimal::from(1) / Decimal::from(1000));
-        
-        if contradiction_score > threshold && spread_pct > spread_threshold {
-            
-            // Determine side based on depth imbalance
-            let imbalance = axiom_data::normalization::calculate_depth_imbalance(book);
-            let side = if imbalance > Decimal::ZERO {
-                Side::Buy  // More bid volume, expect upward pressure
-            } else {
-                Side::Sell // More ask volume, expect downward pressure
-            };
-
-            // Calculate position size (simplified - verifier will check)
-            let base_quantity = Decimal::from_str_exact("0.1").unwrap_or(Decimal::from(1) / Decimal::from(10));
-            
-            let signal = TradeSignal {
-                symbol: symbol.clone(),
-                venue: venue.clone(),
-                side,
-                order_type: OrderType::Limit,
-                quantity: base_quantity,
-                limit_price: Some(mid_price),
-                stop_price: None,
-                timestamp: Utc::now(),
-                contradiction_score,
-                entropy_count: entropy,
-            };
-
-            info!("Proposed trade: {:?} @ {}", side, mid_price);
-            Some(signal)
-        } else {
-            None
+    /// `symbol`'s widest divergence between the latest books of its venues
+    pub fn cross_venue_divergence(&self, symbol: &Symbol) -> Option<DivergenceReport> {
+        self.cross_venue.calculate_cross_venue_divergence(symbol)
+    }
+
+    /// `venue`'s latest book for `symbol`, as arbitrage compares them
+    pub fn venue_book(&self, symbol: &Symbol, venue: &Venue) -> Option<&OrderBook> {
+        self.cross_venue.book(symbol, venue)
+    }
+
+    /// A buy on one venue and a sell on another when their books for
+    /// `symbol` cross by at least `cross_venue.min_edge_bps`, each sized to
+    /// the smaller of `base_quantity` and what both books can take
+    pub fn propose_arbitrage(&mut self, symbol: &Symbol) -> Option<[TradeSignal; 2]> {
+        let min_edge_bps = self.config.cross_venue.min_edge_bps?;
+        let report = self.cross_venue.calculate_cross_venue_divergence(symbol)?;
+        if !report.crossed || report.edge_bps < min_edge_bps {
+            return None;
         }
+        self.total_proposals += 2;
+
+        let mut quantity = report.executable_quantity.min(self.config.base_quantity);
+        let mut legs = [
+            (report.buy_venue.clone(), Side::Buy, report.buy_price),
+            (report.sell_venue.clone(), Side::Sell, report.sell_price),
+        ];
+        // Both legs on their venue's grid at one size, or neither
+        for (venue, side, price) in &mut legs {
+            if let Some(instrument) = self.instruments.get(venue, symbol) {
+                match (instrument.round_price(*price, *side), instrument.round_quantity(quantity)) {
+                    (Ok(rounded), Ok(rounded_quantity)) if !rounded_quantity.is_zero() => {
+                        *price = rounded;
+                        quantity = rounded_quantity;
+                    }
+                    _ => {
+                        warn!("Arbitrage for {} does not fit {}'s tick or lot step; none made", symbol.0, venue);
+                        return None;
+                    }
+                }
+            }
+        }
+        let on_both_grids = legs.iter().all(|(venue, _, _)| {
+            self.instruments.get(venue, symbol)
+                .is_none_or(|instrument| instrument.round_quantity(quantity).is_ok_and(|rounded| rounded == quantity))
+        });
+        if !on_both_grids {
+            warn!("Arbitrage for {}: no size on both venues' lot steps; none made", symbol.0);
+            return None;
+        }
+
+        let mut signals = Vec::with_capacity(2);
+        for (venue, side, price) in legs {
+            let signal = TradeSignal::builder()
+                .with_strategy(self.config.strategy.clone())
+                .with_symbol(symbol.clone())
+                .with_venue(venue)
+                .with_side(side)
+                .with_order_type(OrderType::Limit)
+                .with_quantity(quantity)
+                .with_limit_price(price)
+                // The venues' disagreement is the contradiction
+                .with_scores(report.edge_bps / BPS_PER_UNIT, Decimal::ZERO)
+                .with_expected_edge_bps(report.edge_bps)
+                .with_timestamp(report.as_of)
+                .build(&self.limits);
+            match signal {
+                Ok(signal) => signals.push(signal),
+                Err(e) => {
+                    warn!("Arbitrage for {} discarded: {}", symbol.0, e);
+                    return None;
+                }
+            }
+        }
+        info!(
+            "Proposed arbitrage: buy {} on {} @ {}, sell on {} @ {} ({} bps)",
+            quantity, report.buy_venue, report.buy_price, report.sell_venue, report.sell_price, report.edge_bps,
+        );
+        signals.try_into().ok()
+    }
+
+    /// Verifier rejections and proposals so far
+    pub fn proposal_counts(&self) -> (u64, u64) {
+        (self.hallucination_count, self.total_proposals)
     }
 
     /// Get hallucination rate (rejection rate from verifier)
@@ -94,11 +448,19 @@ impl Proposer {
         Decimal::from(self.hallucination_count) / Decimal::from(self.total_proposals)
     }
 
-    /// Record a rejection (hallucination)
-    pub fn record_rejection(&mut self) {
+    /// Record a rejection (hallucination) and every invariant it violated
+    pub fn record_rejection(&mut self, violations: &ViolationSet) {
         self.hallucination_count += 1;
+        for code in violations.codes() {
+            *self.violation_counts.entry(code).or_insert(0) += 1;
+        }
         warn!("Proposer rejection recorded. Rate: {}", self.hallucination_rate());
     }
+
+    /// Violations seen so far, by error code
+    pub fn violation_counts(&self) -> &BTreeMap<&'static str, u64> {
+        &self.violation_counts
+    }
 }
 
 impl Default for Proposer {
@@ -0,0 +1,158 @@
+//! Regime Detection: Per-Symbol Market Regime Tracking
+//!
+//! Turns order books into regime metrics (volatility, trend, entropy) and
+//! feeds them to a core `RegimeStateMachine` per symbol. Every transition is
+//! emitted as a `regime_changed` event and, when a state file is configured,
+//! persisted so a restart resumes the regime instead of resetting to Normal.
+
+use crate::features::{DepthWindow, FeatureCalculator, MINUTES_PER_YEAR};
+use axiom_core::events::RegimeChanged;
+use axiom_core::{
+    ArithmeticError, Correlation, EventLog, MarketRegime, OrderBook, Price, RegimeMetrics, RegimeSnapshot,
+    RegimeStateMachine, RegimeThresholds, RegimeTransition, Symbol,
+};
+use axiom_core::arith::{checked_div, checked_sub};
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, VecDeque};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// Prices kept per symbol for the volatility and trend window
+const REGIME_WINDOW: usize = 300;
+
+struct SymbolRegime {
+    machine: RegimeStateMachine,
+    features: FeatureCalculator,
+    prices: VecDeque<Price>,
+}
+
+/// Regime state machines for every traded symbol
+pub struct RegimeDetector {
+    thresholds: RegimeThresholds,
+    symbols: BTreeMap<Symbol, SymbolRegime>,
+    state_path: Option<PathBuf>,
+    event_log: Option<Arc<EventLog>>,
+}
+
+impl RegimeDetector {
+    pub fn new(thresholds: RegimeThresholds) -> Self {
+        Self {
+            thresholds,
+            symbols: BTreeMap::new(),
+            state_path: None,
+            event_log: None,
+        }
+    }
+
+    /// Persist regimes to `path`, resuming from it if it exists
+    pub fn with_state_path(mut self, path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        match RegimeSnapshot::load(&path) {
+            Ok(snapshot) => {
+                for (symbol, state) in snapshot.symbols {
+                    info!("Resuming {} in {:?} regime (since {})", symbol.0, state.regime, state.entered_at);
+                    let machine = RegimeStateMachine::restore(self.thresholds.clone(), state);
+                    self.symbols.insert(symbol, SymbolRegime::new(machine));
+                }
+            }
+            Err(e) => warn!("Ignoring regime state {}: {}", path.display(), e),
+        }
+        self.state_path = Some(path);
+        self
+    }
+
+    /// Emit a `regime_changed` event on every transition
+    pub fn with_event_log(mut self, event_log: Arc<EventLog>) -> Self {
+        self.event_log = Some(event_log);
+        self
+    }
+
+    /// Current regime for `symbol` (Normal until it has been observed)
+    pub fn regime(&self, symbol: &Symbol) -> MarketRegime {
+        self.symbols.get(symbol).map_or(MarketRegime::Normal, |entry| entry.machine.regime())
+    }
+
+    /// Most restrictive regime across all symbols (for system health)
+    pub fn overall(&self) -> MarketRegime {
+        self.symbols.values()
+            .map(|entry| entry.machine.regime())
+            .max_by_key(|regime| regime.severity())
+            .unwrap_or(MarketRegime::Normal)
+    }
+
+    /// Update `symbol`'s regime from a book observed at `at`
+    ///
+    /// A book whose metrics cannot be computed counts as Disorderly input.
+    pub fn on_book(&mut self, symbol: &Symbol, book: &OrderBook, at: DateTime<Utc>) -> Option<RegimeTransition> {
+        let thresholds = &self.thresholds;
+        let entry = self.symbols.entry(symbol.clone())
+            .or_insert_with(|| SymbolRegime::new(RegimeStateMachine::new(thresholds.clone(), at)));
+
+        let metrics = match entry.metrics(book) {
+            Ok(metrics) => metrics,
+            Err(e) => {
+                warn!("Regime metrics for {} not computable: {}", symbol.0, e);
+                RegimeMetrics { volatility: thresholds.disorderly_volatility.enter, ..RegimeMetrics::default() }
+            }
+        };
+        let transition = entry.machine.observe(metrics, at)?;
+
+        info!("{} regime {:?} -> {:?} after {}s", symbol.0, transition.from, transition.to, transition.dwell_secs);
+        if let Some(log) = &self.event_log {
+            log.emit(&Correlation::default(), &RegimeChanged {
+                symbol: symbol.clone(),
+                from: transition.from,
+                to: transition.to,
+                dwell_secs: transition.dwell_secs,
+                volatility: metrics.volatility,
+                trend: metrics.trend,
+                entropy: metrics.entropy,
+            });
+        }
+        self.persist();
+        Some(transition)
+    }
+
+    fn persist(&self) {
+        let Some(path) = &self.state_path else { return };
+        let snapshot = RegimeSnapshot {
+            symbols: self.symbols.iter().map(|(symbol, entry)| (symbol.clone(), entry.machine.state())).collect(),
+        };
+        if let Err(e) = snapshot.save(path) {
+            warn!("Failed to persist regime state to {}: {}", path.display(), e);
+        }
+    }
+}
+
+impl SymbolRegime {
+    fn new(machine: RegimeStateMachine) -> Self {
+        Self {
+            machine,
+            features: FeatureCalculator::new(REGIME_WINDOW, DepthWindow::default(), MINUTES_PER_YEAR),
+            prices: VecDeque::with_capacity(REGIME_WINDOW),
+        }
+    }
+
+    fn metrics(&mut self, book: &OrderBook) -> Result<RegimeMetrics, ArithmeticError> {
+        let entropy = self.features.calculate_entropy(book)?;
+        let Some(mid) = axiom_data::normalization::calculate_mid_price(book) else {
+            // One-sided or empty book: no price to track
+            return Ok(RegimeMetrics { entropy, ..RegimeMetrics::default() });
+        };
+
+        self.prices.push_back(mid);
+        if self.prices.len() > REGIME_WINDOW {
+            self.prices.pop_front();
+        }
+        let volatility = self.features.calculate_volatility(mid)?;
+        let trend = match self.prices.front() {
+            Some(first) if !first.is_zero() => {
+                checked_div("regime trend", checked_sub("regime trend", mid.get(), first.get())?, first.get())?
+            }
+            _ => Default::default(),
+        };
+
+        Ok(RegimeMetrics { volatility, trend, entropy })
+    }
+}
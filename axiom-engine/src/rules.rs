@@ -0,0 +1,105 @@
+//! Rule Backend: Proofs by Direct Evaluation
+//!
+//! The axioms are linear inequalities over the signal's and portfolio's
+//! values, so they can be checked exactly in Decimal arithmetic without a
+//! solver. The proof records each axiom that held and the limit constants
+//! it was checked against, under the same names the SMT backend uses; a
+//! signal either backend accepts, the other accepts too. Needs no system
+//! libraries, so it is what a build without the `smt` feature verifies
+//! with.
+
+use axiom_core::{Proof, Side, ViolationSet, DELTA_U_MAX_SQ};
+use crate::verifier::{Axiom, BatchObligation, BatchViolation, JointAxiom, ProofBackend, ProofObligation};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+/// Checks the axioms one by one
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RuleVerifier;
+
+impl RuleVerifier {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Whether `axiom` holds for `obligation`; an overflow counts as a failure
+    fn holds(&self, axiom: Axiom, obligation: &ProofObligation<'_>) -> bool {
+        let ProofObligation { signal, portfolio, projected, symbol_limits, max_leverage, price, bound } = *obligation;
+        match axiom {
+            Axiom::PositionSizeLimit => projected.get() <= symbol_limits.max_position.get(),
+            Axiom::LeverageLimit => portfolio.leverage <= max_leverage,
+            Axiom::RiskBudget => {
+                let equity = portfolio.equity.get();
+                let within = |value: Decimal| {
+                    let min = symbol_limits.min_risk_budget().checked_mul(equity)?;
+                    let max = symbol_limits.max_risk_budget().checked_mul(equity)?;
+                    Some(value >= min && value <= max)
+                };
+                equity > Decimal::ZERO
+                    && price.get().checked_mul(signal.quantity.get()).and_then(within).unwrap_or(false)
+            }
+            Axiom::EntropyLimit => signal.entropy_count <= DELTA_U_MAX_SQ,
+            Axiom::EnergyConstraint => portfolio.energy <= DELTA_U_MAX_SQ,
+            Axiom::SlippageTolerance => bound.is_none_or(|bound| match signal.side {
+                Side::Buy => bound.price.get() <= bound.worst.get(),
+                Side::Sell => bound.price.get() >= bound.worst.get(),
+            }),
+        }
+    }
+
+    /// Whether `axiom` holds at leg `index` of `batch`
+    fn holds_jointly(&self, axiom: JointAxiom, batch: &BatchObligation, index: usize) -> bool {
+        let leg = &batch.legs[index];
+        match axiom {
+            JointAxiom::BatchLeverage => batch.equity > Decimal::ZERO
+                && batch.max_leverage.checked_mul(batch.equity).is_some_and(|max| leg.gross <= max),
+            JointAxiom::BatchPosition => leg.position.abs() <= leg.max_position,
+        }
+    }
+}
+
+impl ProofBackend for RuleVerifier {
+    fn prove(&self, obligation: &ProofObligation<'_>) -> Result<Proof, ViolationSet> {
+        let asserted = obligation.axioms();
+        let failed: Vec<Axiom> = asserted.iter().copied().filter(|axiom| !self.holds(*axiom, obligation)).collect();
+        if !failed.is_empty() {
+            return Err(obligation.violations(&failed));
+        }
+
+        let symbol = &obligation.signal.symbol.0;
+        let limits = obligation.symbol_limits;
+        let mut model: HashMap<String, String> = asserted.iter()
+            .map(|axiom| (axiom.label().to_string(), true.to_string()))
+            .collect();
+        for (name, value) in [
+            (format!("MaxPosition[{}]", symbol), limits.max_position.get()),
+            (format!("MinRiskBudget[{}]", symbol), limits.min_risk_budget()),
+            (format!("MaxRiskBudget[{}]", symbol), limits.max_risk_budget()),
+            ("MaxLeverage".to_string(), obligation.max_leverage),
+            ("MaxEntropy".to_string(), DELTA_U_MAX_SQ),
+            ("MaxEnergy".to_string(), DELTA_U_MAX_SQ),
+        ] {
+            model.insert(name, value.to_string());
+        }
+
+        Ok(Proof {
+            satisfiable: true,
+            model,
+            axioms_satisfied: asserted.iter().map(|axiom| axiom.label().to_string()).collect(),
+            solve_us: 0,
+            cached: false,
+        })
+    }
+
+    fn prove_batch(&self, batch: &BatchObligation) -> Result<(), BatchViolation> {
+        let mut rejected = BatchViolation::default();
+        for index in 0..batch.legs.len() {
+            for axiom in JointAxiom::ALL {
+                if !self.holds_jointly(axiom, batch, index) {
+                    rejected.push(index, batch.violation(index, axiom));
+                }
+            }
+        }
+        rejected.into_result()
+    }
+}
@@ -0,0 +1,136 @@
+//! Shadow Strategies: Candidates Running Beside the Live Proposer
+//!
+//! Each shadow slot is a proposer with its own tuning. It sees every book the
+//! live proposer sees (after the live decision is made), and its proposals
+//! are verified and signed like live ones but only logged. A shared time
+//! budget caps shadow work at a fraction of the live path's own time, so
+//! candidates cannot starve live trading; books over budget are skipped and
+//! counted. Promoting a candidate means copying its table to `[proposer]`.
+
+use crate::proposer::{Proposer, ProposerConfig};
+use axiom_core::{Price, Quantity, Side, Symbol};
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+/// Shadow strategy configuration (the `[shadow]` section)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShadowSettings {
+    /// Shadow work allowed, as a fraction of the time spent on the live path
+    pub max_share: Decimal,
+    /// Candidate proposer tunings by name
+    pub strategies: BTreeMap<String, ProposerConfig>,
+}
+
+impl Default for ShadowSettings {
+    fn default() -> Self {
+        Self { max_share: Decimal::new(25, 2), strategies: BTreeMap::new() }
+    }
+}
+
+/// Credit for shadow work, earned by live work
+#[derive(Debug, Clone)]
+pub struct ShadowBudget {
+    share: f64,
+    /// Seconds of shadow work available; negative after an overrun
+    credit: f64,
+}
+
+impl ShadowBudget {
+    /// Unused credit is capped so a quiet period cannot bank a burst
+    const MAX_CREDIT_SECS: f64 = 0.1;
+
+    pub fn new(share: Decimal) -> Self {
+        Self { share: share.to_f64().unwrap_or(0.0).max(0.0), credit: 0.0 }
+    }
+
+    /// The live path spent `elapsed`
+    pub fn earn(&mut self, elapsed: Duration) {
+        self.credit = (self.credit + elapsed.as_secs_f64() * self.share).min(Self::MAX_CREDIT_SECS);
+    }
+
+    /// Whether shadow work may run now
+    pub fn available(&self) -> bool {
+        self.credit > 0.0
+    }
+
+    /// Shadow work spent `elapsed`
+    pub fn spend(&mut self, elapsed: Duration) {
+        self.credit -= elapsed.as_secs_f64();
+    }
+}
+
+/// One candidate strategy and its hypothetical book
+pub struct ShadowSlot {
+    pub name: String,
+    pub proposer: Proposer,
+    pub book: PaperBook,
+}
+
+impl ShadowSlot {
+    pub fn new(name: String, config: ProposerConfig) -> Self {
+        Self { name, proposer: Proposer::with_config(config), book: PaperBook::default() }
+    }
+}
+
+/// Net position in one symbol under the paper-fill model
+#[derive(Debug, Clone, Default)]
+struct PaperPosition {
+    /// Signed: positive long, negative short
+    quantity: Decimal,
+    entry: Decimal,
+    mark: Decimal,
+}
+
+/// Hypothetical PnL under the paper-fill model: every verified order fills
+/// in full at its limit price (the reference mid for market orders), and
+/// positions are marked to each book's mid
+#[derive(Debug, Clone, Default)]
+pub struct PaperBook {
+    positions: HashMap<Symbol, PaperPosition>,
+    realized: Decimal,
+}
+
+impl PaperBook {
+    pub fn fill(&mut self, symbol: &Symbol, side: Side, quantity: Quantity, price: Price) {
+        let signed = match side {
+            Side::Buy => quantity.get(),
+            Side::Sell => -quantity.get(),
+        };
+        let price = price.get();
+        let position = self.positions.entry(symbol.clone()).or_default();
+        position.mark = price;
+        if position.quantity.is_zero() || position.quantity.is_sign_positive() == signed.is_sign_positive() {
+            let total = position.quantity + signed;
+            position.entry = (position.entry * position.quantity.abs() + price * signed.abs()) / total.abs();
+            position.quantity = total;
+            return;
+        }
+        // Reducing (and possibly flipping) the position
+        let closed = signed.abs().min(position.quantity.abs());
+        let direction = if position.quantity.is_sign_positive() { Decimal::ONE } else { -Decimal::ONE };
+        self.realized += (price - position.entry) * closed * direction;
+        position.quantity += signed;
+        if position.quantity.is_zero() {
+            position.entry = Decimal::ZERO;
+        } else if position.quantity.is_sign_positive() != direction.is_sign_positive() {
+            position.entry = price;
+        }
+    }
+
+    pub fn mark(&mut self, symbol: &Symbol, price: Price) {
+        if let Some(position) = self.positions.get_mut(symbol) {
+            position.mark = price.get();
+        }
+    }
+
+    /// Realized plus unrealized PnL since start
+    pub fn pnl(&self) -> Decimal {
+        self.realized + self.positions.values()
+            .map(|position| (position.mark - position.entry) * position.quantity)
+            .sum::<Decimal>()
+    }
+}
@@ -1,27 +1,462 @@
 //! Signal Generation: Proposer + Verifier Pipeline
 //!
-//! Orchestrates the hybrid signal generation system.
+//! Orchestrates the hybrid signal generation system. The live proposer
+//! computes each book's features; the live strategies are then asked in
+//! priority order and the first proposal goes to the verifier. Shadow
+//! strategies run after the live decision on each book, within their time
+//! budget. The async entry points await the live proof from a
+//! `VerificationPool` instead of proving on the calling thread.
 
-use axiom_core::{TradeSignal, VerifiedOrder, OrderBook, Portfolio, Symbol, Venue};
-use axiom_engine::proposer::Proposer;
-use axiom_engine::verifier::Verifier;
-use tracing::{info, warn};
+use axiom_core::{
+    AccountId, TradeSignal, VerifiedOrder, OrderBook, OrderType, Portfolio, Position, Side, Symbol, Venue, ViolationSet,
+    AuditLog, AuditRecord, SignalJournal, EventLog, Correlation, SystemEvent, signal_hash, order_hash,
+    LatencyRecorder, LatencyStage, FunnelRecorder, FunnelStage, SigningKeys,
+    DEFAULT_SIGNATURE_VALIDITY_MS, LimitsRegistry, InvariantViolation, RegimeThresholds,
+    CZeroSignature, FeatureVector, Price, ShadowObservation, MarketRegime, FundingRate, LiquidationIntensity,
+    InstrumentRegistry, AxiomRng, OrderStatus,
+};
+use axiom_core::events;
+use crate::features::LiquidityInput;
+use crate::proposer::{Proposer, ProposerConfig, PROPOSER_RNG_LABEL};
+use crate::regime::RegimeDetector;
+use crate::shadow::{PaperBook, ShadowBudget, ShadowSettings, ShadowSlot};
+use crate::strategy::{ContradictionArbStrategy, ProposerStrategy, StrategyReport, StrategyStats};
+use crate::verification_pool::{VerificationError, VerificationPool, VerificationRequest};
+use crate::verifier::{Verifier, VerifierConfig};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Instant;
+use tokio::sync::watch;
+use tracing::{debug, info, warn, error};
+
+/// The slice of configuration the signal generator reloads while running
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignalSettings {
+    pub proposer: ProposerConfig,
+    pub limits: Arc<LimitsRegistry>,
+    pub shadow: ShadowSettings,
+}
+
+/// Observations held for `take_shadow_observations`; older ones are dropped
+const MAX_PENDING_OBSERVATIONS: usize = 10_000;
+
+/// A live strategy and its record
+struct StrategySlot {
+    strategy: Box<dyn ProposerStrategy>,
+    enabled: bool,
+    stats: StrategyStats,
+}
+
+impl StrategySlot {
+    /// `strategy`, drawing from its own fork of `rng`
+    fn new(mut strategy: Box<dyn ProposerStrategy>, rng: &AxiomRng) -> Self {
+        strategy.set_rng(rng.fork(strategy.name()));
+        Self { strategy, enabled: true, stats: StrategyStats::default() }
+    }
+}
+
+/// A live strategy's proposal on its way to the verifier
+struct LiveProposal {
+    /// Index of the proposing strategy
+    strategy: usize,
+    signal: TradeSignal,
+    signal_id: String,
+    correlation: Correlation,
+    /// The proposer's mid, the cooldown's reference
+    mid: Price,
+}
+
+/// Signs verified orders with whichever C=0 key is active, recording each
+/// signature in the audit chain
+///
+/// The key's nonces only go up, and the executor refuses an order whose
+/// nonce is not above the last it accepted. Orders must therefore be
+/// signed in the order they are submitted: where several generators feed
+/// one queue, they leave their orders unsigned and a single signer signs
+/// them as they are released (see the CLI's signal driver).
+#[derive(Clone)]
+pub struct OrderSigner {
+    keys: Arc<SigningKeys>,
+    validity: chrono::Duration,
+    journal: Option<Arc<dyn SignalJournal>>,
+    latency: Option<Arc<dyn LatencyRecorder>>,
+}
+
+impl OrderSigner {
+    pub fn new(keys: Arc<SigningKeys>) -> Self {
+        Self {
+            keys,
+            validity: chrono::Duration::milliseconds(DEFAULT_SIGNATURE_VALIDITY_MS),
+            journal: None,
+            latency: None,
+        }
+    }
+
+    /// How long each signature stays valid for execution
+    pub fn with_validity(mut self, validity: chrono::Duration) -> Self {
+        self.validity = validity;
+        self
+    }
+
+    /// Record every signature in the audit chain
+    pub fn with_audit_log(self, audit_log: Arc<AuditLog>) -> Self {
+        self.with_journal(audit_log)
+    }
+
+    /// Record every signature in `journal`
+    pub fn with_journal(mut self, journal: Arc<dyn SignalJournal>) -> Self {
+        self.journal = Some(journal);
+        self
+    }
+
+    /// Report sign latency per venue
+    pub fn with_latency_recorder(mut self, latency: Arc<dyn LatencyRecorder>) -> Self {
+        self.latency = Some(latency);
+        self
+    }
+
+    /// Attach a signature to `order`, taking the key's next nonce; on
+    /// failure the order is left unsigned, and the executor refuses it
+    pub fn sign(&self, order: &mut VerifiedOrder) {
+        let signal_id = signal_hash(&order.signal);
+        let order_id = order_hash(order);
+        self.sign_as(&signal_id, &order_id, order);
+    }
+
+    fn sign_as(&self, signal_id: &str, order_id: &str, order: &mut VerifiedOrder) {
+        let start = Instant::now();
+        let Some(signature) = self.signature(order) else { return };
+        if let Some(latency) = &self.latency {
+            latency.record(LatencyStage::Sign, &order.signal.venue, start.elapsed());
+        }
+        if let Some(log) = &self.journal {
+            let record = AuditRecord::OrderSigned { order: order.clone(), signature: signature.clone() };
+            if let Err(e) = log.append(signal_id, Some(order_id), record) {
+                error!("Failed to write audit entry: {}", e);
+            }
+        }
+        order.signature = Some(signature);
+    }
+
+    /// Signature from the active key; None on failure
+    fn signature(&self, order: &VerifiedOrder) -> Option<CZeroSignature> {
+        match self.keys.signer(chrono::Utc::now()).sign(order, self.validity) {
+            Ok(signature) => Some(signature),
+            Err(e) => {
+                error!("Failed to sign verified order: {}", e);
+                None
+            }
+        }
+    }
+}
 
 /// Signal generator combining proposer and verifier
 pub struct SignalGenerator {
+    /// Features and market inputs for the live strategies
     proposer: Proposer,
+    /// Live strategies, highest priority first
+    strategies: Vec<StrategySlot>,
     verifier: Verifier,
+    /// Shared by every generator of a run (one state file, one set of machines)
+    regime: Arc<Mutex<RegimeDetector>>,
+    /// Books no strategy was asked about because the regime forbids new
+    /// positions; not rejections
+    stood_down: u64,
+    journal: Option<Arc<dyn SignalJournal>>,
+    event_log: Option<Arc<EventLog>>,
+    latency: Option<Arc<dyn LatencyRecorder>>,
+    funnel: Option<Arc<dyn FunnelRecorder>>,
+    signing_keys: Option<Arc<SigningKeys>>,
+    signature_validity: chrono::Duration,
+    /// Cleared by `stop_intake` at shutdown
+    accepting: bool,
+    /// Reloaded settings, applied between signals
+    updates: Option<watch::Receiver<SignalSettings>>,
+    /// Candidate strategies, never executed
+    shadows: Vec<ShadowSlot>,
+    shadow_budget: ShadowBudget,
+    /// The live strategy's verified orders under the shadows' paper-fill model
+    live_book: PaperBook,
+    observations: VecDeque<ShadowObservation>,
+    /// Venue tick and lot rules proposals are rounded to
+    instruments: Arc<InstrumentRegistry>,
+    /// Verifier threads for the async path; started on first use
+    pool: Option<VerificationPool>,
 }
 
 impl SignalGenerator {
     pub fn new() -> Self {
+        let proposer = Proposer::new();
+        let strategy = StrategySlot::new(Box::new(ContradictionArbStrategy::new(ProposerConfig::default())), proposer.rng());
         Self {
-            proposer: Proposer::new(),
+            proposer,
+            strategies: vec![strategy],
             verifier: Verifier::new(),
+            regime: Arc::new(Mutex::new(RegimeDetector::new(RegimeThresholds::default()))),
+            stood_down: 0,
+            journal: None,
+            event_log: None,
+            latency: None,
+            funnel: None,
+            signing_keys: None,
+            signature_validity: chrono::Duration::milliseconds(DEFAULT_SIGNATURE_VALIDITY_MS),
+            accepting: true,
+            updates: None,
+            shadows: Vec::new(),
+            shadow_budget: ShadowBudget::new(ShadowSettings::default().max_share),
+            live_book: PaperBook::default(),
+            observations: VecDeque::new(),
+            instruments: Arc::new(InstrumentRegistry::new()),
+            pool: None,
         }
     }
 
-    /// Generate a verified trade signal
+    /// Record every proposal and verification outcome in the audit chain
+    pub fn with_audit_log(self, audit_log: Arc<AuditLog>) -> Self {
+        self.with_journal(audit_log)
+    }
+
+    /// Record every proposal and verification outcome in `journal` (an
+    /// in-memory chain, say, where nothing should reach disk)
+    pub fn with_journal(mut self, journal: Arc<dyn SignalJournal>) -> Self {
+        self.journal = Some(journal);
+        self
+    }
+
+    /// Emit structured lifecycle events for every proposal
+    pub fn with_event_log(mut self, event_log: Arc<EventLog>) -> Self {
+        self.event_log = Some(event_log);
+        self
+    }
+
+    /// Report feature_calc, verify and solve latency per venue
+    pub fn with_latency_recorder(mut self, latency: Arc<dyn LatencyRecorder>) -> Self {
+        self.proposer.set_latency_recorder(latency.clone());
+        self.verifier.set_latency_recorder(latency.clone());
+        self.latency = Some(latency);
+        self.pool = None;
+        self
+    }
+
+    /// Count books processed, proposals, and verifications per symbol
+    pub fn with_funnel_recorder(mut self, funnel: Arc<dyn FunnelRecorder>) -> Self {
+        self.funnel = Some(funnel);
+        self
+    }
+
+    /// Attach a C=0 signature to every verified order, signed by whichever
+    /// key is active at the time
+    ///
+    /// Only for a generator whose orders are submitted as it verifies them;
+    /// see `OrderSigner` for generators that share a queue.
+    pub fn with_signing_keys(mut self, signing_keys: Arc<SigningKeys>) -> Self {
+        self.signing_keys = Some(signing_keys);
+        self
+    }
+
+    /// How long each C=0 signature stays valid for execution
+    pub fn with_signature_validity(mut self, validity: chrono::Duration) -> Self {
+        self.signature_validity = validity;
+        self
+    }
+
+    /// Propose and verify signals against `limits` instead of the built-in per-symbol limits
+    pub fn with_limits(mut self, limits: Arc<LimitsRegistry>) -> Self {
+        self.proposer.set_limits(limits.clone());
+        for slot in &mut self.shadows {
+            slot.proposer.set_limits(limits.clone());
+        }
+        self.verifier = self.verifier.with_limits(limits);
+        self
+    }
+
+    /// Bound the verifier's solver by `config` (keeps the limits already set)
+    pub fn with_verifier_config(mut self, config: VerifierConfig) -> Self {
+        self.verifier = self.verifier.with_config(config);
+        self.pool = None;
+        self
+    }
+
+    /// Round proposed prices and sizes to the venue rules in `instruments`
+    pub fn with_instruments(mut self, instruments: Arc<InstrumentRegistry>) -> Self {
+        self.proposer.set_instruments(instruments.clone());
+        for slot in &mut self.shadows {
+            slot.proposer.set_instruments(instruments.clone());
+        }
+        self.instruments = instruments;
+        self
+    }
+
+    /// Tune the proposer and the live strategies (keeps any latency
+    /// recorder, limits and instruments already set)
+    pub fn with_proposer_config(mut self, config: ProposerConfig) -> Self {
+        for slot in &mut self.strategies {
+            slot.strategy.set_config(&config);
+        }
+        let mut proposer = Proposer::with_config(config);
+        if let Some(latency) = &self.latency {
+            proposer.set_latency_recorder(latency.clone());
+        }
+        proposer.set_limits(self.verifier.limits());
+        proposer.set_instruments(self.instruments.clone());
+        proposer.set_rng(self.proposer.rng().clone());
+        self.proposer = proposer;
+        self
+    }
+
+    /// Draw every random stream from `seed` instead of DETERMINISTIC_SEED;
+    /// the live strategies are handed fresh forks
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        let rng = AxiomRng::with_seed(seed, PROPOSER_RNG_LABEL);
+        for slot in &mut self.strategies {
+            let fork = rng.fork(slot.strategy.name());
+            slot.strategy.set_rng(fork);
+        }
+        self.proposer.set_rng(rng);
+        self
+    }
+
+    /// Ask `strategy` after the live strategies already set
+    pub fn with_strategy(mut self, strategy: Box<dyn ProposerStrategy>) -> Self {
+        self.strategies.push(StrategySlot::new(strategy, self.proposer.rng()));
+        self
+    }
+
+    /// Replace the live strategies, highest priority first
+    pub fn with_strategies(mut self, strategies: Vec<Box<dyn ProposerStrategy>>) -> Self {
+        let rng = self.proposer.rng();
+        self.strategies = strategies.into_iter().map(|strategy| StrategySlot::new(strategy, rng)).collect();
+        self
+    }
+
+    /// Stop or resume asking the live strategies named `name`; false when
+    /// there are none
+    pub fn set_strategy_enabled(&mut self, name: &str, enabled: bool) -> bool {
+        let mut found = false;
+        for slot in self.strategies.iter_mut().filter(|slot| slot.strategy.name() == name) {
+            if slot.enabled != enabled {
+                info!("Strategy {} {}", name, if enabled { "enabled" } else { "disabled" });
+            }
+            slot.enabled = enabled;
+            found = true;
+        }
+        found
+    }
+
+    /// Every live strategy's record, in priority order
+    pub fn strategy_reports(&self) -> Vec<StrategyReport> {
+        self.strategies.iter().map(|slot| StrategyReport {
+            name: slot.strategy.name().to_string(),
+            enabled: slot.enabled,
+            stats: slot.stats.clone(),
+        }).collect()
+    }
+
+    /// Run `settings.strategies` in shadow beside the live proposer
+    pub fn with_shadow_settings(mut self, settings: ShadowSettings) -> Self {
+        self.set_shadow_settings(settings);
+        self
+    }
+
+    /// Replace the shadow slots; a strategy kept by name keeps its proposer
+    /// history and paper book
+    fn set_shadow_settings(&mut self, settings: ShadowSettings) {
+        self.shadow_budget = ShadowBudget::new(settings.max_share);
+        let limits = self.verifier.limits();
+        let mut previous = std::mem::take(&mut self.shadows);
+        self.shadows = settings.strategies.into_iter()
+            .map(|(name, config)| {
+                let mut slot = match previous.iter().position(|slot| slot.name == name) {
+                    Some(index) => {
+                        let mut slot = previous.swap_remove(index);
+                        slot.proposer.set_config(config);
+                        slot
+                    }
+                    None => ShadowSlot::new(name, config),
+                };
+                slot.proposer.set_limits(limits.clone());
+                slot.proposer.set_instruments(self.instruments.clone());
+                slot
+            })
+            .collect();
+    }
+
+    /// Follow `updates`: each new value replaces the proposer tuning, the
+    /// limits and the shadow strategies together, before the next signal
+    pub fn with_settings_updates(mut self, updates: watch::Receiver<SignalSettings>) -> Self {
+        self.updates = Some(updates);
+        self
+    }
+
+    /// Apply the latest reloaded settings, if they changed
+    fn apply_updates(&mut self) {
+        let Some(updates) = &mut self.updates else { return };
+        if !updates.has_changed().unwrap_or(false) {
+            return;
+        }
+        let settings = updates.borrow_and_update().clone();
+        for slot in &mut self.strategies {
+            slot.strategy.set_config(&settings.proposer);
+        }
+        self.proposer.set_config(settings.proposer);
+        self.proposer.set_limits(settings.limits.clone());
+        self.verifier.set_limits(settings.limits);
+        self.set_shadow_settings(settings.shadow);
+        info!("Signal settings reloaded");
+    }
+
+    /// Track market regimes with `regime` (thresholds, persistence, events)
+    pub fn with_regime_detector(mut self, regime: RegimeDetector) -> Self {
+        self.regime = Arc::new(Mutex::new(regime));
+        self
+    }
+
+    /// Track regimes with a detector other generators share (per-symbol workers)
+    pub fn with_shared_regime_detector(mut self, regime: Arc<Mutex<RegimeDetector>>) -> Self {
+        self.regime = regime;
+        self
+    }
+
+    /// Most restrictive regime across all symbols
+    pub fn overall_regime(&self) -> MarketRegime {
+        self.regime.lock().unwrap_or_else(PoisonError::into_inner).overall()
+    }
+
+    /// The book's features as the live proposer's history sees them
+    pub fn feature_vector(&self, book: &OrderBook) -> Option<FeatureVector> {
+        self.proposer.feature_vector(book)
+    }
+
+    /// Compare `symbol`'s books against this on-chain reading from now on
+    /// (live and shadow strategies alike)
+    pub fn record_onchain_liquidity(&mut self, symbol: &Symbol, input: LiquidityInput) {
+        for slot in &mut self.shadows {
+            slot.proposer.record_onchain_liquidity(symbol.clone(), input.clone());
+        }
+        self.proposer.record_onchain_liquidity(symbol.clone(), input);
+    }
+
+    /// Charge the symbol's signals for this funding payment when it falls
+    /// due within their holding period, and measure its funding basis
+    /// against it (live and shadow strategies alike)
+    pub fn record_funding(&mut self, funding: &FundingRate) {
+        self.verifier.record_funding(funding.symbol.clone(), funding.estimate());
+        for slot in &mut self.shadows {
+            slot.proposer.record_funding(funding.clone());
+        }
+        self.proposer.record_funding(funding.clone());
+    }
+
+    /// Hold back `symbol`'s proposals while it liquidates past the limit
+    /// (live and shadow strategies alike, each against its own limit)
+    pub fn record_liquidation_intensity(&mut self, symbol: &Symbol, intensity: LiquidationIntensity) {
+        for slot in &mut self.shadows {
+            slot.proposer.record_liquidation_intensity(symbol.clone(), intensity);
+        }
+        self.proposer.record_liquidation_intensity(symbol.clone(), intensity);
+    }
+
+    /// Generate a verified trade signal for the default account
     ///
     /// Returns Some(VerifiedOrder) if a valid signal is generated,
     /// None if no opportunity is found or verification fails.
@@ -32,26 +467,657 @@ impl SignalGenerator {
         book: &OrderBook,
         portfolio: &Portfolio,
     ) -> Option<VerifiedOrder> {
-        // Step 1: Proposer suggests a trade
-        let signal = self.proposer.propose_trade(symbol, venue, book, portfolio)?;
+        self.generate_account_signal(&AccountId::default(), symbol, venue, book, portfolio, None)
+    }
+
+    /// Generate a verified trade signal for `account`
+    ///
+    /// The signal is sized and verified against `portfolio` (the account's
+    /// own); with several accounts, `aggregate` (all of them together) must
+    /// also stay within the portfolio-wide invariants. Pass None when the
+    /// account is the only one.
+    pub fn generate_account_signal(
+        &mut self,
+        account: &AccountId,
+        symbol: &Symbol,
+        venue: &Venue,
+        book: &OrderBook,
+        portfolio: &Portfolio,
+        aggregate: Option<&Portfolio>,
+    ) -> Option<VerifiedOrder> {
+        if !self.accepting {
+            return None;
+        }
+        self.apply_updates();
+        let start = Instant::now();
+        let live = self.generate_live(account, symbol, venue, book, portfolio, aggregate);
+        if !self.shadows.is_empty() {
+            self.shadow_budget.earn(start.elapsed());
+            self.run_shadows(symbol, venue, book, portfolio, live.as_ref());
+        }
+        live
+    }
+
+    /// Generate verified arbitrage legs for `account`: a buy on one venue
+    /// and a sell on another when their books for `symbol` cross
+    ///
+    /// The legs are verified together (`Verifier::verify_batch`), each
+    /// against the mid of its own venue's book, so either both come back
+    /// or neither does. `aggregate` is as for `generate_account_signal`.
+    pub fn generate_arbitrage_signals(
+        &mut self,
+        account: &AccountId,
+        symbol: &Symbol,
+        portfolio: &Portfolio,
+        aggregate: Option<&Portfolio>,
+    ) -> Vec<VerifiedOrder> {
+        if !self.accepting {
+            return Vec::new();
+        }
+        self.apply_updates();
+        let regime = self.regime.lock().unwrap_or_else(PoisonError::into_inner).regime(symbol);
+        if !regime.allows_new_positions() {
+            debug!("{} in {:?} regime, not proposing arbitrage", symbol.0, regime);
+            return Vec::new();
+        }
+
+        let Some(signals) = self.proposer.propose_arbitrage(symbol) else { return Vec::new() };
+        let mut legs = Vec::with_capacity(signals.len());
+        for mut signal in signals {
+            signal.account = account.clone();
+            let Some(mid) = self.proposer.venue_book(symbol, &signal.venue)
+                .and_then(axiom_data::normalization::calculate_mid_price)
+            else {
+                debug!("No {} mid on {} for arbitrage", symbol.0, signal.venue);
+                return Vec::new();
+            };
+            if self.proposer.cooldown_suppresses(&signal, mid) {
+                debug!("{} {:?} on {} still cooling down; arbitrage suppressed", symbol.0, signal.side, signal.venue);
+                return Vec::new();
+            }
+            legs.push((signal, mid));
+        }
+
+        let mut correlations = Vec::with_capacity(legs.len());
+        for (signal, _) in &legs {
+            self.count(FunnelStage::Proposed, symbol);
+            let signal_id = signal_hash(signal);
+            let correlation = Correlation::for_signal(&signal_id, &signal.venue).with_account(account);
+            self.audit(&signal_id, None, AuditRecord::SignalProposed { signal: signal.clone(), features: None });
+            self.emit(&correlation, &events::SignalProposed {
+                symbol: signal.symbol.clone(),
+                side: signal.side,
+                quantity: signal.quantity,
+                limit_price: signal.limit_price,
+                contradiction_score: signal.contradiction_score,
+                entropy_count: signal.entropy_count,
+            });
+            correlations.push((signal_id, correlation));
+        }
+
+        let start = Instant::now();
+        let outcome = match aggregate {
+            Some(aggregate) => self.verifier.verify_account_batch(&legs, portfolio, aggregate),
+            None => self.verifier.verify_batch(&legs, portfolio),
+        };
+        if let (Some(latency), Some((signal, _))) = (&self.latency, legs.first()) {
+            latency.record(LatencyStage::Verify, &signal.venue, start.elapsed());
+        }
+
+        match outcome {
+            Ok(orders) => {
+                info!("Arbitrage for {} generated and verified ({} legs)", symbol.0, orders.len());
+                let mut verified = Vec::with_capacity(orders.len());
+                for ((mut order, (signal_id, correlation)), (_, mid)) in orders.into_iter().zip(correlations).zip(&legs) {
+                    self.count(FunnelStage::Verified, symbol);
+                    let order_id = order_hash(&order);
+                    self.audit(&signal_id, Some(&order_id), AuditRecord::OrderVerified { order: order.clone() });
+                    self.emit(&correlation.with_order_hash(&order_id), &events::OrderVerified {
+                        symbol: order.signal.symbol.clone(),
+                        side: order.signal.side,
+                        quantity: order.signal.quantity,
+                        limit_price: order.signal.limit_price,
+                        proof_signature: order.proof_signature.clone(),
+                        axioms_satisfied: order.proof.axioms_satisfied.clone(),
+                    });
+                    self.sign(&signal_id, &order_id, &mut order);
+                    self.proposer.start_cooldown(&order, &order_id, *mid);
+                    verified.push(order);
+                }
+                verified
+            }
+            Err(rejected) => {
+                warn!("Arbitrage for {} rejected by verifier: {}", symbol.0, rejected);
+                for (index, ((signal, _), (signal_id, correlation))) in legs.iter().zip(correlations).enumerate() {
+                    // A leg not at fault is rejected with its batch
+                    let Some(violations) = rejected.legs.get(&index) else {
+                        self.audit(&signal_id, None, AuditRecord::SignalRejected {
+                            codes: Vec::new(),
+                            reason: rejected.to_string(),
+                        });
+                        continue;
+                    };
+                    let codes: Vec<String> = violations.codes().into_iter().map(String::from).collect();
+                    self.audit(&signal_id, None, AuditRecord::SignalRejected {
+                        codes: codes.clone(),
+                        reason: violations.to_string(),
+                    });
+                    self.emit(&correlation, &events::SignalRejected {
+                        symbol: signal.symbol.clone(),
+                        codes,
+                        violations: serde_json::to_value(violations).unwrap_or_default(),
+                        message: violations.to_string(),
+                    });
+                    self.proposer.record_rejection(violations);
+                }
+                Vec::new()
+            }
+        }
+    }
+
+    /// `generate_account_signal` without blocking the async runtime: the
+    /// proof is found on the verification pool (`VerifierConfig::pool`)
+    /// while the caller awaits it
+    ///
+    /// A full verification queue drops the signal unverified and returns
+    /// `VerificationError::QueueFull`, also emitted as a
+    /// `verification_backlog` event; a rejection is Ok(None) as usual.
+    /// Shadow strategies then run in place on the calling worker thread,
+    /// which needs the multi-threaded runtime.
+    pub async fn generate_account_signal_async(
+        &mut self,
+        account: &AccountId,
+        symbol: &Symbol,
+        venue: &Venue,
+        book: &OrderBook,
+        portfolio: &Portfolio,
+        aggregate: Option<&Portfolio>,
+    ) -> Result<Option<VerifiedOrder>, VerificationError> {
+        if !self.accepting {
+            return Ok(None);
+        }
+        self.apply_updates();
+        let start = Instant::now();
+        let live = self.generate_live_pooled(account, symbol, venue, book, portfolio, aggregate).await?;
+        if !self.shadows.is_empty() {
+            self.shadow_budget.earn(start.elapsed());
+            tokio::task::block_in_place(|| self.run_shadows(symbol, venue, book, portfolio, live.as_ref()));
+        }
+        Ok(live)
+    }
+
+    /// `generate_signal` for the default account, verified on the pool
+    pub async fn generate_signal_async(
+        &mut self,
+        symbol: &Symbol,
+        venue: &Venue,
+        book: &OrderBook,
+        portfolio: &Portfolio,
+    ) -> Result<Option<VerifiedOrder>, VerificationError> {
+        self.generate_account_signal_async(&AccountId::default(), symbol, venue, book, portfolio, None).await
+    }
+
+    /// Whether the async path verifies on pool threads (any configured)
+    pub fn verifies_on_pool(&self) -> bool {
+        self.verifier.config().pool.workers > 0
+    }
+
+    /// Shadow observations since the last call, oldest first
+    pub fn take_shadow_observations(&mut self) -> Vec<ShadowObservation> {
+        self.observations.drain(..).collect()
+    }
+
+    fn generate_live(
+        &mut self,
+        account: &AccountId,
+        symbol: &Symbol,
+        venue: &Venue,
+        book: &OrderBook,
+        portfolio: &Portfolio,
+        aggregate: Option<&Portfolio>,
+    ) -> Option<VerifiedOrder> {
+        let proposal = self.propose_live(account, symbol, venue, book, portfolio)?;
 
-        // Step 2: Verifier checks and proves
-        match self.verifier.verify_signal(&signal, portfolio) {
-            Ok(verified) => {
+        // Step 2: Verifier checks and proves against the mid of the same book the
+        // proposer saw (stop trigger placement and slippage both use it)
+        let start = Instant::now();
+        let outcome = axiom_data::normalization::calculate_mid_price(book)
+            .ok_or(ViolationSet::from(InvariantViolation::NoMarketReference))
+            .and_then(|mid| match aggregate {
+                Some(aggregate) => self.verifier.verify_account_signal(&proposal.signal, portfolio, aggregate, mid),
+                None => self.verifier.verify_signal(&proposal.signal, portfolio, mid),
+            });
+        if let Some(latency) = &self.latency {
+            latency.record(LatencyStage::Verify, venue, start.elapsed());
+        }
+        self.conclude_live(proposal, outcome)
+    }
+
+    /// `generate_live` with the proof found on the verification pool
+    async fn generate_live_pooled(
+        &mut self,
+        account: &AccountId,
+        symbol: &Symbol,
+        venue: &Venue,
+        book: &OrderBook,
+        portfolio: &Portfolio,
+        aggregate: Option<&Portfolio>,
+    ) -> Result<Option<VerifiedOrder>, VerificationError> {
+        let Some(proposal) = self.propose_live(account, symbol, venue, book, portfolio) else { return Ok(None) };
+        let Some(mid) = axiom_data::normalization::calculate_mid_price(book) else {
+            return Ok(self.conclude_live(proposal, Err(InvariantViolation::NoMarketReference.into())));
+        };
+        let request = VerificationRequest {
+            signal: proposal.signal.clone(),
+            portfolio: portfolio.clone(),
+            aggregate: aggregate.cloned(),
+            market: mid,
+            limits: self.verifier.limits(),
+            funding: self.verifier.funding(symbol),
+        };
+
+        // Queue wait included: it is part of what the signal waits for
+        let start = Instant::now();
+        let outcome = self.verification_pool().verify(request).await;
+        if let Some(latency) = &self.latency {
+            latency.record(LatencyStage::Verify, venue, start.elapsed());
+        }
+        match outcome {
+            Ok(verified) => Ok(self.conclude_live(proposal, Ok(verified))),
+            Err(VerificationError::Rejected(violations)) => Ok(self.conclude_live(proposal, Err(violations))),
+            Err(e) => {
+                warn!("Signal for {} not verified: {}", symbol.0, e);
+                if let VerificationError::QueueFull { depth } = e {
+                    self.emit(&proposal.correlation, &events::VerificationBacklog {
+                        symbol: symbol.clone(),
+                        venue: venue.clone(),
+                        queue_depth: depth,
+                    });
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// The verification pool, started on first use
+    fn verification_pool(&mut self) -> &VerificationPool {
+        let config = self.verifier.config();
+        let (limits, latency) = (self.verifier.limits(), self.latency.clone());
+        self.pool.get_or_insert_with(|| VerificationPool::new(config.pool, config, limits, latency))
+    }
+
+    /// Step 0 and 1: the regime gate, then the first live strategy's
+    /// proposal, audited and announced
+    fn propose_live(
+        &mut self,
+        account: &AccountId,
+        symbol: &Symbol,
+        venue: &Venue,
+        book: &OrderBook,
+        portfolio: &Portfolio,
+    ) -> Option<LiveProposal> {
+        self.count(FunnelStage::BookProcessed, symbol);
+        // RSI and volatility see every book, proposed on or not
+        self.proposer.record_book(book);
+
+        // Step 0: No new positions while the market is disorderly or unprovable
+        let regime = {
+            let mut detector = self.regime.lock().unwrap_or_else(PoisonError::into_inner);
+            detector.on_book(symbol, book, book.timestamp);
+            detector.regime(symbol)
+        };
+        if !regime.allows_new_positions() {
+            self.stood_down += 1;
+            debug!("{} in {:?} regime, not proposing", symbol.0, regime);
+            return None;
+        }
+
+        // Step 1: The first enabled strategy, by priority, to propose on the book's features
+        let features = self.proposer.proposal_features(symbol, venue, book)?;
+        let context = self.proposer.context(symbol, venue, &features, book, portfolio);
+        let mut proposal = None;
+        for (index, slot) in self.strategies.iter_mut().enumerate().filter(|(_, slot)| slot.enabled) {
+            if proposal.is_some() {
+                slot.strategy.observe(&context);
+                continue;
+            }
+            slot.stats.evaluated += 1;
+            if let Some(signal) = slot.strategy.propose(&context) {
+                slot.stats.proposed += 1;
+                proposal = Some((index, signal));
+            }
+        }
+        let (strategy, mut signal) = proposal?;
+        signal.account = account.clone();
+        let mid = features.mid;
+        if self.proposer.cooldown_suppresses(&signal, mid) {
+            let slot = &mut self.strategies[strategy];
+            slot.stats.suppressed += 1;
+            debug!("{} {:?} still cooling down; {} proposal suppressed", symbol.0, signal.side, slot.strategy.name());
+            return None;
+        }
+        self.count(FunnelStage::Proposed, symbol);
+        let signal_id = signal_hash(&signal);
+        let correlation = Correlation::for_signal(&signal_id, venue).with_account(account);
+        self.audit(&signal_id, None, AuditRecord::SignalProposed {
+            signal: signal.clone(),
+            features: Some(features),
+        });
+        self.emit(&correlation, &events::SignalProposed {
+            symbol: signal.symbol.clone(),
+            side: signal.side,
+            quantity: signal.quantity,
+            limit_price: signal.limit_price,
+            contradiction_score: signal.contradiction_score,
+            entropy_count: signal.entropy_count,
+        });
+        Some(LiveProposal { strategy, signal, signal_id, correlation, mid })
+    }
+
+    /// Step 3: record the verifier's outcome for `proposal`, signing and
+    /// cooling down a verified order
+    fn conclude_live(&mut self, proposal: LiveProposal, outcome: Result<VerifiedOrder, ViolationSet>) -> Option<VerifiedOrder> {
+        let LiveProposal { strategy, signal, signal_id, correlation, mid } = proposal;
+        let symbol = &signal.symbol;
+        match outcome {
+            Ok(mut verified) => {
                 info!("Signal generated and verified");
+                self.count(FunnelStage::Verified, symbol);
+                let order_id = order_hash(&verified);
+                self.audit(
+                    &signal_id,
+                    Some(&order_id),
+                    AuditRecord::OrderVerified { order: verified.clone() },
+                );
+                self.emit(&correlation.with_order_hash(&order_id), &events::OrderVerified {
+                    symbol: signal.symbol.clone(),
+                    side: signal.side,
+                    quantity: signal.quantity,
+                    limit_price: signal.limit_price,
+                    proof_signature: verified.proof_signature.clone(),
+                    axioms_satisfied: verified.proof.axioms_satisfied.clone(),
+                });
+                self.sign(&signal_id, &order_id, &mut verified);
+                self.proposer.start_cooldown(&verified, &order_id, mid);
                 Some(verified)
             }
+            Err(violations) => {
+                let codes: Vec<String> = violations.codes().into_iter().map(String::from).collect();
+                warn!("Signal rejected by verifier {:?}: {}", codes, violations);
+                self.audit(&signal_id, None, AuditRecord::SignalRejected {
+                    codes: codes.clone(),
+                    reason: violations.to_string(),
+                });
+                self.emit(&correlation, &events::SignalRejected {
+                    symbol: signal.symbol.clone(),
+                    codes,
+                    violations: serde_json::to_value(&violations).unwrap_or_default(),
+                    message: violations.to_string(),
+                });
+                let slot = &mut self.strategies[strategy];
+                slot.stats.record_rejection(&violations);
+                warn!("Strategy {} rejection recorded. Rate: {}", slot.strategy.name(), slot.stats.hallucination_rate());
+                None
+            }
+        }
+    }
+
+    /// Let every shadow strategy decide on the book the live one just saw,
+    /// if the budget allows, and record one observation per strategy
+    fn run_shadows(
+        &mut self,
+        symbol: &Symbol,
+        venue: &Venue,
+        book: &OrderBook,
+        portfolio: &Portfolio,
+        live: Option<&VerifiedOrder>,
+    ) {
+        let mid = axiom_data::normalization::calculate_mid_price(book);
+        if let Some(order) = live {
+            if let Some(price) = order.signal.limit_price.or(mid) {
+                self.live_book.fill(symbol, order.signal.side, order.signal.quantity, price);
+            }
+        }
+        if let Some(mid) = mid {
+            self.live_book.mark(symbol, mid);
+            for slot in &mut self.shadows {
+                slot.book.mark(symbol, mid);
+            }
+        }
+        for slot in &mut self.shadows {
+            slot.proposer.record_book(book);
+        }
+
+        // Over budget: skip this book rather than delay the next live decision
+        let evaluated = self.shadow_budget.available();
+        if !evaluated {
+            debug!("Shadow budget spent, {} skipped", symbol.0);
+        }
+        // Shadows face the same regime gate as live
+        let proposing = evaluated
+            && self.regime.lock().unwrap_or_else(PoisonError::into_inner).regime(symbol).allows_new_positions();
+        let features = self.proposer.feature_vector(book);
+        let start = Instant::now();
+        for index in 0..self.shadows.len() {
+            let (shadow, shadow_rejected) = if proposing {
+                self.run_shadow(index, symbol, venue, book, portfolio, mid)
+            } else {
+                (None, false)
+            };
+            if self.observations.len() >= MAX_PENDING_OBSERVATIONS {
+                self.observations.pop_front();
+            }
+            let slot = &self.shadows[index];
+            self.observations.push_back(ShadowObservation {
+                strategy: slot.name.clone(),
+                symbol: symbol.clone(),
+                at: book.timestamp,
+                evaluated,
+                live: live.map(|order| order.signal.side),
+                shadow,
+                shadow_rejected,
+                features: features.clone(),
+                live_pnl: self.live_book.pnl(),
+                shadow_pnl: slot.book.pnl(),
+            });
+        }
+        if evaluated {
+            self.shadow_budget.spend(start.elapsed());
+        }
+    }
+
+    /// Propose, verify and sign for one shadow slot, logging the outcome
+    /// tagged as shadow; returns the verified side and whether it was rejected
+    fn run_shadow(
+        &mut self,
+        index: usize,
+        symbol: &Symbol,
+        venue: &Venue,
+        book: &OrderBook,
+        portfolio: &Portfolio,
+        mid: Option<Price>,
+    ) -> (Option<Side>, bool) {
+        let Some(signal) = self.shadows[index].proposer.propose_trade(symbol, venue, book, portfolio) else {
+            return (None, false);
+        };
+        let strategy = self.shadows[index].name.clone();
+        let signal_id = signal_hash(&signal);
+        let correlation = Correlation::for_signal(&signal_id, venue);
+        let outcome = mid
+            .ok_or(ViolationSet::from(InvariantViolation::NoMarketReference))
+            .and_then(|mid| self.verifier.verify_signal(&signal, portfolio, mid));
+
+        let event = |codes: Vec<String>| events::ShadowSignal {
+            strategy: strategy.clone(),
+            symbol: signal.symbol.clone(),
+            side: signal.side,
+            quantity: signal.quantity,
+            limit_price: signal.limit_price,
+            codes,
+            shadow: true,
+        };
+        match outcome {
+            Ok(mut verified) => {
+                debug!("Shadow {} verified {:?} {}", strategy, signal.side, symbol.0);
+                let order_id = order_hash(&verified);
+                verified.signature = self.signature(&verified);
+                if let Some(price) = signal.limit_price.or(mid) {
+                    self.shadows[index].book.fill(symbol, signal.side, signal.quantity, price);
+                }
+                self.emit(&correlation.with_order_hash(&order_id), &event(Vec::new()));
+                self.audit(&signal_id, Some(&order_id), AuditRecord::ShadowSignal {
+                    strategy: strategy.clone(),
+                    signal: signal.clone(),
+                    order: Some(verified),
+                    codes: Vec::new(),
+                });
+                (Some(signal.side), false)
+            }
+            Err(violations) => {
+                let codes: Vec<String> = violations.codes().into_iter().map(String::from).collect();
+                debug!("Shadow {} rejected {:?}: {}", strategy, codes, violations);
+                self.emit(&correlation, &event(codes.clone()));
+                self.audit(&signal_id, None, AuditRecord::ShadowSignal {
+                    strategy: strategy.clone(),
+                    signal: signal.clone(),
+                    order: None,
+                    codes,
+                });
+                self.shadows[index].proposer.record_rejection(&violations);
+                (None, true)
+            }
+        }
+    }
+
+    /// Stop proposing; `generate_signal` returns None from now on
+    pub fn stop_intake(&mut self) {
+        if self.accepting {
+            info!("Signal intake stopped");
+            self.accepting = false;
+        }
+    }
+
+    /// Verify and sign a market order closing `position` at its last price
+    /// (flattening at shutdown) against its account's `portfolio`; None if it
+    /// cannot be built or proved
+    pub fn propose_exit(&mut self, position: &Position, portfolio: &Portfolio) -> Option<VerifiedOrder> {
+        let side = match position.side {
+            Side::Buy => Side::Sell,
+            Side::Sell => Side::Buy,
+        };
+        let signal = match TradeSignal::builder()
+            .with_account(position.account.clone())
+            .with_symbol(position.symbol.clone())
+            .with_venue(position.venue.clone())
+            .with_side(side)
+            .with_order_type(OrderType::Market)
+            .with_quantity(position.quantity)
+            .with_expected_price(position.current_price)
+            .build(&self.verifier.limits())
+        {
+            Ok(signal) => signal,
             Err(e) => {
-                warn!("Signal rejected by verifier: {:?}", e);
-                self.proposer.record_rejection();
+                warn!("Exit for {} not built: {}", position.symbol.0, e);
+                return None;
+            }
+        };
+
+        let signal_id = signal_hash(&signal);
+        self.audit(&signal_id, None, AuditRecord::SignalProposed { signal: signal.clone(), features: None });
+        match self.verifier.verify_signal(&signal, portfolio, position.current_price) {
+            Ok(mut verified) => {
+                let order_id = order_hash(&verified);
+                self.audit(&signal_id, Some(&order_id), AuditRecord::OrderVerified { order: verified.clone() });
+                self.sign(&signal_id, &order_id, &mut verified);
+                Some(verified)
+            }
+            Err(violations) => {
+                warn!("Exit for {} rejected by verifier: {}", position.symbol.0, violations);
+                self.audit(&signal_id, None, AuditRecord::SignalRejected {
+                    codes: violations.codes().into_iter().map(String::from).collect(),
+                    reason: violations.to_string(),
+                });
                 None
             }
         }
     }
 
-    /// Get current hallucination rate
+    /// Left unsigned without keys, and refused by the executor's safety check
+    fn sign(&self, signal_id: &str, order_id: &str, order: &mut VerifiedOrder) {
+        if let Some(signer) = self.signer() {
+            signer.sign_as(signal_id, order_id, order);
+        }
+    }
+
+    /// C=0 signature from the active key; None without keys or on failure
+    fn signature(&self, order: &VerifiedOrder) -> Option<CZeroSignature> {
+        self.signer()?.signature(order)
+    }
+
+    fn signer(&self) -> Option<OrderSigner> {
+        let keys = self.signing_keys.clone()?;
+        Some(OrderSigner {
+            keys,
+            validity: self.signature_validity,
+            journal: self.journal.clone(),
+            latency: self.latency.clone(),
+        })
+    }
+
+    fn audit(&self, signal_id: &str, order_id: Option<&str>, record: AuditRecord) {
+        if let Some(log) = &self.journal {
+            if let Err(e) = log.append(signal_id, order_id, record) {
+                error!("Failed to write audit entry: {}", e);
+            }
+        }
+    }
+
+    fn count(&self, stage: FunnelStage, symbol: &Symbol) {
+        if let Some(funnel) = &self.funnel {
+            funnel.record(stage, symbol);
+        }
+    }
+
+    fn emit<E: SystemEvent>(&self, correlation: &Correlation, event: &E) {
+        if let Some(log) = &self.event_log {
+            log.emit(correlation, event);
+        }
+    }
+
+    /// Get current hallucination rate, over every live strategy
     pub fn hallucination_rate(&self) -> rust_decimal::Decimal {
-        self.proposer.hallucination_rate()
+        let (rejected, evaluated) = self.proposal_counts();
+        if evaluated == 0 {
+            return rust_decimal::Decimal::ZERO;
+        }
+        rust_decimal::Decimal::from(rejected) / rust_decimal::Decimal::from(evaluated)
+    }
+
+    /// End the cooldown order `order_id` (its `order_hash`) started once
+    /// the order is filled, cancelled or rejected
+    pub fn on_order_update(&mut self, order_id: &str, status: OrderStatus) {
+        if self.proposer.on_order_update(order_id, status) {
+            debug!("Order {} is {:?}; its cooldown ended", order_id, status);
+        }
+    }
+
+    /// Books stood down on because their symbol's regime (Disorderly or
+    /// Unprovable) forbids new positions
+    pub fn stood_down_count(&self) -> u64 {
+        self.stood_down
+    }
+
+    /// Proposals held back by a cooldown, summed over the live strategies
+    pub fn suppressed_count(&self) -> u64 {
+        self.strategies.iter().map(|slot| slot.stats.suppressed).sum()
+    }
+
+    /// Verifier rejections and books evaluated, summed over the live
+    /// strategies
+    pub fn proposal_counts(&self) -> (u64, u64) {
+        self.strategies.iter().fold((0, 0), |(rejected, evaluated), slot| {
+            (rejected + slot.stats.rejected, evaluated + slot.stats.evaluated)
+        })
     }
 }
 
@@ -0,0 +1,490 @@
+//! SMT Backend: Proofs from z3
+//!
+//! The limit axioms are named rational constants asserted once per limits
+//! registry; each signal's values are asserted against them in a push/pop
+//! scope, each one implied by its axiom's label, and checked under those
+//! labels as assumptions; when the check fails, each label is checked alone
+//! to name every axiom that fails. Checks are bounded by the configured
+//! timeout, and a timed out check is reported as L0_VERIFICATION_TIMEOUT
+//! rather than a failure of any axiom. Built only with the `smt` feature
+//! (on by default).
+
+use axiom_core::{InvariantViolation, LimitsRegistry, Proof, Side, Symbol, ViolationSet, DELTA_U_MAX_SQ};
+use crate::verifier::{Axiom, BatchObligation, BatchViolation, JointAxiom, ProofBackend, ProofObligation, VerifierConfig};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::ffi::CString;
+use std::time::Instant;
+use tracing::warn;
+use z3::{Config, Context, Params, Solver, ast::{Ast, Bool, Real}};
+
+/// One symbol's limit constants in the solver
+struct SymbolAxioms {
+    max_position: Real<'static>,
+    min_risk_budget: Real<'static>,
+    max_risk_budget: Real<'static>,
+}
+
+/// A solver holding the limit axioms for one limits registry
+///
+/// The per-symbol position and risk budget limits, the leverage cap and
+/// the entropy and energy thresholds are named rational constants fixed
+/// once when the session is built; verifications only add their own
+/// values, inside a push/pop scope.
+pub struct SmtVerifier {
+    // The fields below borrow `context`; they are declared first so they
+    // drop before it
+    solver: Solver<'static>,
+    symbols: HashMap<Symbol, SymbolAxioms>,
+    max_leverage: Real<'static>,
+    max_entropy: Real<'static>,
+    max_energy: Real<'static>,
+    /// By axiom, in `Axiom::ALL` order
+    labels: Vec<(Axiom, Bool<'static>)>,
+    timeout_ms: u32,
+    context: Box<Context>,
+}
+
+impl SmtVerifier {
+    pub fn new(limits: &LimitsRegistry, config: &VerifierConfig) -> Self {
+        if let Some(max_memory_mb) = config.max_memory_mb {
+            set_memory_limit(max_memory_mb);
+        }
+        let cfg = Config::new();
+        let context = Box::new(Context::new(&cfg));
+        // SAFETY: the context is boxed, so its address survives moves of the
+        // session, and every borrow of it is a field of the session that is
+        // dropped before it (field order) and never handed out as 'static
+        let ctx: &'static Context = unsafe { &*(context.as_ref() as *const Context) };
+
+        let solver = Solver::new(ctx);
+        let mut params = Params::new(ctx);
+        params.set_u32("timeout", config.timeout_ms);
+        solver.set_params(&params);
+        let constant = |name: String, value: Decimal| {
+            let constant = Real::new_const(ctx, name);
+            solver.assert(&constant._eq(&real(ctx, value)));
+            constant
+        };
+        let symbols = limits.symbols()
+            .filter_map(|symbol| {
+                let limit = limits.get(symbol).ok()?;
+                let axioms = SymbolAxioms {
+                    max_position: constant(format!("MaxPosition[{}]", symbol.0), limit.max_position.get()),
+                    min_risk_budget: constant(format!("MinRiskBudget[{}]", symbol.0), limit.min_risk_budget()),
+                    max_risk_budget: constant(format!("MaxRiskBudget[{}]", symbol.0), limit.max_risk_budget()),
+                };
+                Some((symbol.clone(), axioms))
+            })
+            .collect();
+        let max_leverage = constant("MaxLeverage".to_string(), limits.max_leverage());
+        let max_entropy = constant("MaxEntropy".to_string(), DELTA_U_MAX_SQ);
+        let max_energy = constant("MaxEnergy".to_string(), DELTA_U_MAX_SQ);
+
+        Self {
+            solver,
+            symbols,
+            max_leverage,
+            max_entropy,
+            max_energy,
+            labels: Axiom::ALL.iter().map(|axiom| (*axiom, Bool::new_const(ctx, axiom.label()))).collect(),
+            timeout_ms: config.timeout_ms,
+            context,
+        }
+    }
+
+    fn context(&self) -> &Context {
+        &self.context
+    }
+
+    fn label(&self, axiom: Axiom) -> &Bool<'static> {
+        &self.labels.iter().find(|(labelled, _)| *labelled == axiom).expect("every axiom has a label").1
+    }
+
+    /// Assert `constraint` under `axiom`'s label, to be checked by assuming it
+    fn track(&self, axiom: Axiom, constraint: &Bool<'_>) {
+        self.solver.assert(&self.label(axiom).implies(constraint));
+    }
+
+    /// Assert one signal's values against the limit axioms and check them
+    fn check_scope(&self, obligation: &ProofObligation<'_>) -> Result<Proof, ViolationSet> {
+        let ProofObligation { signal, portfolio, projected, price, bound, .. } = *obligation;
+        let context = self.context();
+        let solver = &self.solver;
+        let Some(axioms) = self.symbols.get(&signal.symbol) else {
+            // Only when the limits changed without rebuilding the session
+            return Err(InvariantViolation::Unproven { reason: format!("no limit axioms for {}", signal.symbol.0) }.into());
+        };
+
+        // Per-signal values, exact as rationals
+        let quantity = real(context, projected.get());
+        let leverage = real(context, portfolio.leverage);
+        let value = real(context, price.get()).mul(&[&real(context, signal.quantity.get())]);
+        let equity = real(context, portfolio.equity.get());
+        let zero = Real::from_real(context, 0, 1);
+
+        // Add constraints (axioms), each under its label so a failed check
+        // can be narrowed to every axiom that fails
+        // Axiom 1: projected position <= max_quantity
+        self.track(Axiom::PositionSizeLimit, &quantity.le(&axioms.max_position));
+
+        // Axiom 2: leverage <= max_leverage
+        self.track(Axiom::LeverageLimit, &leverage.le(&self.max_leverage));
+
+        // Axiom 3: min_risk_budget <= position value / equity <= max_risk_budget,
+        // multiplied out over a positive equity
+        let within_budget = equity.gt(&zero).and(&[
+            &value.ge(&axioms.min_risk_budget.mul(&[&equity])),
+            &value.le(&axioms.max_risk_budget.mul(&[&equity])),
+        ]);
+        self.track(Axiom::RiskBudget, &within_budget);
+
+        // Axiom 4: entropy <= max_entropy (not in the Unprovable regime)
+        self.track(Axiom::EntropyLimit, &real(context, signal.entropy_count).le(&self.max_entropy));
+
+        // Axiom 5: Hamiltonian energy <= max_energy
+        self.track(Axiom::EnergyConstraint, &real(context, portfolio.energy).le(&self.max_energy));
+
+        // Axiom 6: price no worse than the slippage bound (above for buys, below for sells)
+        if let Some(bound) = bound {
+            let price = real(context, bound.price.get());
+            let worst = real(context, bound.worst.get());
+            match signal.side {
+                Side::Buy => self.track(Axiom::SlippageTolerance, &price.le(&worst)),
+                Side::Sell => self.track(Axiom::SlippageTolerance, &price.ge(&worst)),
+            }
+        }
+        let asserted = obligation.axioms();
+        let assumptions: Vec<Bool<'static>> = asserted.iter().map(|axiom| self.label(*axiom).clone()).collect();
+
+        // Check satisfiability, within the configured timeout
+        let start = Instant::now();
+        match solver.check_assumptions(&assumptions) {
+            z3::SatResult::Sat => {
+                let model = solver.get_model();
+                let mut proof_model = HashMap::new();
+
+                // Extract model values for this signal's axioms and bounds (the
+                // solver also holds the bounds of every other symbol)
+                for axiom in &asserted {
+                    let value = model.eval(self.label(*axiom)).expect("a label evaluates in its model");
+                    proof_model.insert(axiom.label().to_string(), value.to_string());
+                }
+                for constant in [
+                    &axioms.max_position,
+                    &axioms.min_risk_budget,
+                    &axioms.max_risk_budget,
+                    &self.max_leverage,
+                    &self.max_entropy,
+                    &self.max_energy,
+                ] {
+                    let value = model.eval(constant).expect("a constant evaluates in its model");
+                    proof_model.insert(constant.to_string(), value.to_string());
+                }
+
+                Ok(Proof {
+                    satisfiable: true,
+                    model: proof_model,
+                    // Exactly the axioms asserted above
+                    axioms_satisfied: asserted.iter().map(|axiom| axiom.label().to_string()).collect(),
+                    solve_us: 0,
+                    cached: false,
+                })
+            }
+            z3::SatResult::Unsat => {
+                // Each constraint only involves constants, so checking its
+                // label alone settles it; failures come out in axiom order
+                let failed: Vec<Axiom> = asserted.iter().copied()
+                    .filter(|axiom| solver.check_assumptions(&[self.label(*axiom).clone()]) != z3::SatResult::Sat)
+                    .collect();
+                warn!("SMT solver found constraints unsatisfiable: {:?}", failed);
+                if failed.is_empty() {
+                    // No axiom failing alone would mean they only fail together
+                    return Err(InvariantViolation::Unproven {
+                        reason: format!("axioms {:?} fail only together", asserted),
+                    }.into());
+                }
+                Err(obligation.violations(&failed))
+            }
+            z3::SatResult::Unknown => {
+                let reason = solver.get_reason_unknown().unwrap_or_else(|| "solver returned unknown".to_string());
+                // z3 reports its timeout as "timeout" or, once it has
+                // interrupted itself, "canceled"
+                if reason.contains("timeout") || reason.contains("canceled") {
+                    warn!("SMT solver timed out after {:?} ({})", start.elapsed(), reason);
+                    return Err(InvariantViolation::VerificationTimeout { timeout_ms: self.timeout_ms }.into());
+                }
+                warn!("SMT solver returned unknown: {}", reason);
+                Err(InvariantViolation::Unproven { reason }.into())
+            }
+        }
+    }
+
+    /// `axiom` at leg `index` of `batch`, over the session's leverage cap
+    fn joint_constraint(&self, axiom: JointAxiom, batch: &BatchObligation, index: usize) -> Bool<'_> {
+        let context = self.context();
+        let leg = &batch.legs[index];
+        match axiom {
+            JointAxiom::BatchLeverage => {
+                let equity = real(context, batch.equity);
+                equity.gt(&Real::from_real(context, 0, 1)).and(&[
+                    &real(context, leg.gross).le(&self.max_leverage.mul(&[&equity])),
+                ])
+            }
+            JointAxiom::BatchPosition => {
+                let position = real(context, leg.position.abs());
+                match self.symbols.get(&leg.symbol) {
+                    Some(axioms) => position.le(&axioms.max_position),
+                    None => position.le(&real(context, leg.max_position)),
+                }
+            }
+        }
+    }
+
+    /// Assert every leg's joint axioms together and check them; when they
+    /// fail, check each alone to name every leg at fault
+    fn check_batch_scope(&self, batch: &BatchObligation) -> Result<(), BatchViolation> {
+        let context = self.context();
+        let solver = &self.solver;
+        let constraints: Vec<(usize, JointAxiom)> = (0..batch.legs.len())
+            .flat_map(|index| JointAxiom::ALL.into_iter().map(move |axiom| (index, axiom)))
+            .collect();
+        let labels: Vec<Bool<'_>> = constraints.iter()
+            .map(|(index, axiom)| {
+                let label = Bool::new_const(context, format!("{}[{}]", axiom.label(), index));
+                solver.assert(&label.implies(&self.joint_constraint(*axiom, batch, *index)));
+                label
+            })
+            .collect();
+
+        let start = Instant::now();
+        let every_leg = |violation: InvariantViolation| {
+            let mut rejected = BatchViolation::default();
+            for index in 0..batch.legs.len() {
+                rejected.push(index, violation.clone());
+            }
+            Err(rejected)
+        };
+        match solver.check_assumptions(&labels) {
+            z3::SatResult::Sat => Ok(()),
+            z3::SatResult::Unsat => {
+                // Each constraint only involves constants, so checking its
+                // label alone settles it
+                let mut rejected = BatchViolation::default();
+                for ((index, axiom), label) in constraints.into_iter().zip(labels) {
+                    if solver.check_assumptions(&[label]) != z3::SatResult::Sat {
+                        rejected.push(index, batch.violation(index, axiom));
+                    }
+                }
+                warn!("SMT solver found batch constraints unsatisfiable for legs {:?}", rejected.legs.keys().collect::<Vec<_>>());
+                if rejected.is_empty() {
+                    return every_leg(InvariantViolation::Unproven {
+                        reason: "batch constraints fail only together".to_string(),
+                    });
+                }
+                Err(rejected)
+            }
+            z3::SatResult::Unknown => {
+                let reason = solver.get_reason_unknown().unwrap_or_else(|| "solver returned unknown".to_string());
+                if reason.contains("timeout") || reason.contains("canceled") {
+                    warn!("SMT solver timed out on a batch after {:?} ({})", start.elapsed(), reason);
+                    return every_leg(InvariantViolation::VerificationTimeout { timeout_ms: self.timeout_ms });
+                }
+                warn!("SMT solver returned unknown on a batch: {}", reason);
+                every_leg(InvariantViolation::Unproven { reason })
+            }
+        }
+    }
+}
+
+// SAFETY: the session owns its context and every solver and term borrowing
+// it, none of which is handed out, so they all move between threads
+// together; z3 allows a context on any one thread at a time, and the session
+// is not `Sync`, so it is never used from two at once
+unsafe impl Send for SmtVerifier {}
+
+impl ProofBackend for SmtVerifier {
+    fn prove(&self, obligation: &ProofObligation<'_>) -> Result<Proof, ViolationSet> {
+        self.solver.push();
+        let result = self.check_scope(obligation);
+        self.solver.pop(1);
+        result
+    }
+
+    fn prove_batch(&self, batch: &BatchObligation) -> Result<(), BatchViolation> {
+        self.solver.push();
+        let result = self.check_batch_scope(batch);
+        self.solver.pop(1);
+        result
+    }
+}
+
+/// Cap z3's memory at `max_memory_mb` megabytes
+///
+/// The cap is a global parameter (a context config rejects it), which the
+/// z3 bindings don't expose, so it is set through z3-sys.
+fn set_memory_limit(max_memory_mb: u32) {
+    let name = CString::new("memory_max_size").expect("no interior NUL");
+    let value = CString::new(max_memory_mb.to_string()).expect("no interior NUL");
+    // SAFETY: both arguments are valid NUL-terminated strings that outlive
+    // the call, and z3 copies the value
+    unsafe { z3_sys::Z3_global_param_set(name.as_ptr(), value.as_ptr()) };
+}
+
+/// `value` as an exact z3 rational: its mantissa over ten to its scale
+fn real(context: &Context, value: Decimal) -> Real<'_> {
+    let denominator = 10u128.pow(value.scale());
+    let magnitude = Real::from_real_str(context, &value.mantissa().unsigned_abs().to_string(), &denominator.to_string())
+        .expect("decimal digits are a valid numeral");
+    if value.is_sign_negative() {
+        magnitude.unary_minus()
+    } else {
+        magnitude
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use axiom_core::{AccountId, Amount, OrderType, Portfolio, Position, Quantity, TradeSignal, Venue};
+
+    fn prove(signal: &TradeSignal) -> Result<Proof, ViolationSet> {
+        prove_against(signal, &testing::portfolio())
+    }
+
+    fn prove_against(signal: &TradeSignal, portfolio: &Portfolio) -> Result<Proof, ViolationSet> {
+        let limits = LimitsRegistry::builtin();
+        let obligation = ProofObligation::new(signal, portfolio, &limits, testing::market()).unwrap();
+        SmtVerifier::new(&limits, &VerifierConfig::default()).prove(&obligation)
+    }
+
+    #[test]
+    fn a_signal_within_every_axiom_is_proved() {
+        let proof = prove(&testing::signal()).unwrap();
+        assert!(proof.satisfiable);
+    }
+
+    #[test]
+    fn a_stop_market_is_valued_at_its_trigger() {
+        let signal = testing::signal_of(OrderType::StopMarket { trigger: testing::price(65_050) })
+            .build(&LimitsRegistry::builtin())
+            .unwrap();
+        assert!(prove(&signal).is_ok());
+    }
+
+    #[test]
+    fn a_signal_over_only_the_position_limit_is_unsat() {
+        // 9.9 BTC held plus 0.25 bought is over the 10 BTC limit
+        let mut portfolio = testing::portfolio();
+        portfolio.positions.push(Position {
+            account: AccountId::default(),
+            symbol: testing::signal().symbol,
+            venue: Venue::Binance,
+            side: Side::Buy,
+            quantity: Quantity::new(Decimal::new(99, 1)).unwrap(),
+            entry_price: testing::market(),
+            current_price: testing::market(),
+            unrealized_pnl: Amount::ZERO,
+            realized_pnl: Amount::ZERO,
+        });
+        assert_eq!(prove_against(&testing::signal(), &portfolio).unwrap_err().codes(), vec!["L0_POSITION_SIZE"]);
+    }
+
+    #[test]
+    fn a_signal_over_only_the_leverage_limit_is_unsat() {
+        let mut portfolio = testing::portfolio();
+        portfolio.leverage = LimitsRegistry::builtin().max_leverage() + Decimal::ONE;
+        assert_eq!(prove_against(&testing::signal(), &portfolio).unwrap_err().codes(), vec!["L0_LEVERAGE"]);
+    }
+
+    #[test]
+    fn a_signal_over_only_its_risk_budget_is_unsat() {
+        // 1 BTC at 65000 is 3.25% of equity, with the position well inside 10 BTC
+        let mut signal = testing::signal();
+        signal.quantity = Quantity::new(Decimal::ONE).unwrap();
+        assert_eq!(prove(&signal).unwrap_err().codes(), vec!["L0_RISK_BUDGET_EXCEEDED"]);
+    }
+
+    #[test]
+    fn a_signal_over_only_the_entropy_bound_is_unsat() {
+        let mut signal = testing::signal();
+        signal.entropy_count = Decimal::ONE;
+        assert_eq!(prove(&signal).unwrap_err().codes(), vec!["L0_EXCESSIVE_ENTROPY"]);
+    }
+
+    #[test]
+    fn a_signal_over_only_the_energy_bound_is_unsat() {
+        let mut portfolio = testing::portfolio();
+        portfolio.energy = Decimal::new(1, 6);
+        assert_eq!(prove_against(&testing::signal(), &portfolio).unwrap_err().codes(), vec!["L0_ENERGY_DIVERGENCE"]);
+    }
+
+    #[test]
+    fn a_signal_over_only_its_slippage_tolerance_is_unsat() {
+        // A limit buy 200 over a 65000 market
+        let signal = testing::signal_of(OrderType::Limit)
+            .with_limit_price(testing::price(65_200))
+            .build(&LimitsRegistry::builtin())
+            .unwrap();
+        assert_eq!(prove(&signal).unwrap_err().codes(), vec!["L0_SLIPPAGE_TOLERANCE"]);
+    }
+
+    /// Pigeonhole: `holes + 1` pigeons, each in some hole and no two sharing
+    /// one; unsatisfiable, and exponential for resolution, so no solver
+    /// settles it within a millisecond
+    fn pigeonhole(context: &Context, holes: usize) -> Vec<Bool<'_>> {
+        let placed: Vec<Vec<Bool<'_>>> = (0..=holes)
+            .map(|pigeon| (0..holes).map(|hole| Bool::new_const(context, format!("p{pigeon}h{hole}"))).collect())
+            .collect();
+        let mut constraints: Vec<Bool<'_>> = placed.iter()
+            .map(|holes| holes[0].or(&holes[1..].iter().collect::<Vec<_>>()))
+            .collect();
+        for (first, pigeon) in placed.iter().enumerate() {
+            for other in &placed[first + 1..] {
+                for (mine, theirs) in pigeon.iter().zip(other) {
+                    constraints.push(mine.and(&[theirs]).not());
+                }
+            }
+        }
+        constraints
+    }
+
+    #[test]
+    fn a_check_over_its_timeout_is_a_verification_timeout() {
+        let limits = LimitsRegistry::builtin();
+        let portfolio = testing::portfolio();
+        let config = VerifierConfig { timeout_ms: 1, ..VerifierConfig::default() };
+        let session = SmtVerifier::new(&limits, &config);
+        session.solver.push();
+        for constraint in pigeonhole(session.context(), 12) {
+            session.solver.assert(&constraint);
+        }
+        let signal = testing::signal();
+        let obligation = ProofObligation::new(&signal, &portfolio, &limits, testing::market()).unwrap();
+        let result = session.prove(&obligation);
+        session.solver.pop(1);
+        assert_eq!(result.unwrap_err().codes(), vec!["L0_VERIFICATION_TIMEOUT"]);
+    }
+
+    #[test]
+    fn pop_leaves_no_assertion_behind() {
+        let limits = LimitsRegistry::builtin();
+        let portfolio = testing::portfolio();
+        let session = SmtVerifier::new(&limits, &VerifierConfig::default());
+        let prove = |signal: &TradeSignal| {
+            session.prove(&ProofObligation::new(signal, &portfolio, &limits, testing::market()).unwrap())
+        };
+        let mut over_budget = testing::signal();
+        over_budget.quantity = Quantity::new(Decimal::ONE).unwrap();
+        let mut entropic = testing::signal();
+        entropic.entropy_count = Decimal::ONE;
+
+        // Each verification sees only its own values, whatever came before
+        assert_eq!(prove(&over_budget).unwrap_err().codes(), vec!["L0_RISK_BUDGET_EXCEEDED"]);
+        assert!(prove(&testing::signal()).is_ok());
+        assert_eq!(prove(&entropic).unwrap_err().codes(), vec!["L0_EXCESSIVE_ENTROPY"]);
+        assert!(prove(&testing::signal()).is_ok());
+        assert_eq!(prove(&over_budget).unwrap_err().codes(), vec!["L0_RISK_BUDGET_EXCEEDED"]);
+    }
+}
@@ -0,0 +1,358 @@
+//! Strategies: Pluggable Proposal Rules
+//!
+//! A strategy turns one book's features into at most one signal. The signal
+//! generator asks its enabled strategies in priority order and takes the
+//! first proposal; each strategy's proposals and verifier rejections are
+//! counted on their own, so one that keeps proposing what the verifier
+//! refuses can be singled out and disabled without touching the others.
+//! Strategies after the one that proposed still observe the book, so any
+//! history they keep has no gaps.
+
+use axiom_core::{
+    AxiomRng, FeatureVector, InstrumentRegistry, LimitsRegistry, OrderBook, OrderType, Portfolio, Quantity, Side,
+    StrategyId, Symbol, TradeSignal, Venue, ViolationSet, BPS_PER_UNIT, DELTA_U_MAX_SQ,
+};
+use axiom_risk::calculate_position_size;
+use crate::features::{BollingerState, RsiState, DEFAULT_BOLLINGER_PERIOD, DEFAULT_BOLLINGER_WIDTH, DEFAULT_RSI_PERIOD};
+use crate::proposer::{LimitPriceSource, ProposerConfig};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tracing::{debug, info, warn};
+
+/// Everything a strategy proposes from
+#[derive(Debug, Clone, Copy)]
+pub struct ProposalContext<'a> {
+    pub symbol: &'a Symbol,
+    pub venue: &'a Venue,
+    pub features: &'a FeatureVector,
+    pub book: &'a OrderBook,
+    pub portfolio: &'a Portfolio,
+    /// Signals are built against these
+    pub limits: &'a LimitsRegistry,
+    /// Venue tick and lot rules proposals are rounded to
+    pub instruments: &'a InstrumentRegistry,
+}
+
+/// A proposal rule
+pub trait ProposerStrategy: Send {
+    /// Name its proposals and rejections are counted under
+    fn name(&self) -> &str;
+
+    /// At most one signal for the book in `context`
+    fn propose(&mut self, context: &ProposalContext<'_>) -> Option<TradeSignal>;
+
+    /// See a book it was not asked about because a strategy before it
+    /// proposed; ignored unless overridden
+    fn observe(&mut self, _context: &ProposalContext<'_>) {}
+
+    /// Retune from reloaded proposer settings; ignored unless overridden
+    fn set_config(&mut self, _config: &ProposerConfig) {}
+
+    /// Take the stream any randomness must come from, forked for this
+    /// strategy; ignored unless overridden
+    fn set_rng(&mut self, _rng: AxiomRng) {}
+}
+
+/// One strategy's proposals and verifier rejections
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct StrategyStats {
+    /// Books it was asked to propose on
+    pub evaluated: u64,
+    /// Signals it proposed
+    pub proposed: u64,
+    /// Proposals held back as repeats of an order still cooling down; not
+    /// rejections
+    pub suppressed: u64,
+    /// Proposals the verifier rejected
+    pub rejected: u64,
+    /// Violations behind those rejections, by error code
+    pub violation_counts: BTreeMap<&'static str, u64>,
+}
+
+impl StrategyStats {
+    /// Rejections per book evaluated, as the proposer has always counted it
+    pub fn hallucination_rate(&self) -> Decimal {
+        if self.evaluated == 0 {
+            return Decimal::ZERO;
+        }
+        Decimal::from(self.rejected) / Decimal::from(self.evaluated)
+    }
+
+    /// Count a rejection and every invariant it violated
+    pub fn record_rejection(&mut self, violations: &ViolationSet) {
+        self.rejected += 1;
+        for code in violations.codes() {
+            *self.violation_counts.entry(code).or_insert(0) += 1;
+        }
+    }
+}
+
+/// A strategy as the signal generator reports it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StrategyReport {
+    pub name: String,
+    pub enabled: bool,
+    pub stats: StrategyStats,
+}
+
+/// Name of the built-in contradiction strategy
+pub const CONTRADICTION_ARB: &str = "contradiction_arb";
+
+/// The built-in rule: rest at fair value on the side the book leans to
+/// when the on-chain/CEX contradiction and the spread are both wide
+#[derive(Debug, Clone)]
+pub struct ContradictionArbStrategy {
+    config: ProposerConfig,
+}
+
+impl ContradictionArbStrategy {
+    pub fn new(config: ProposerConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl ProposerStrategy for ContradictionArbStrategy {
+    fn name(&self) -> &str {
+        CONTRADICTION_ARB
+    }
+
+    fn set_config(&mut self, config: &ProposerConfig) {
+        self.config = config.clone();
+    }
+
+    fn propose(&mut self, context: &ProposalContext<'_>) -> Option<TradeSignal> {
+        let ProposalContext { symbol, venue, features, book, .. } = *context;
+        let spread_pct = features.spread_pct;
+
+        // Simple rule-based proposer (in production, this would be a neural network)
+        // Look for arbitrage opportunities (crossed spreads, mispricing)
+
+        // Propose trade if contradiction is high (market inefficiency detected),
+        // as far as its inputs can be trusted
+        if features.adjusted_contradiction_score <= self.config.contradiction_threshold
+            || spread_pct <= self.config.spread_threshold
+        {
+            return None;
+        }
+
+        // Determine side based on depth imbalance
+        let side = if features.depth_imbalance > Decimal::ZERO {
+            Side::Buy  // More bid volume, expect upward pressure
+        } else {
+            Side::Sell // More ask volume, expect downward pressure
+        };
+
+        // Calculate position size (simplified - verifier will check)
+        let mut base_quantity = self.config.base_quantity;
+        let mut limit_price = match self.config.limit_price {
+            LimitPriceSource::Mid => features.mid,
+            LimitPriceSource::Microprice => features.microprice.unwrap_or(features.mid),
+        };
+        // A price or size off the venue's grid would be rejected there
+        if let Some(instrument) = context.instruments.get(venue, symbol) {
+            match (instrument.round_price(limit_price, side), instrument.round_quantity(base_quantity)) {
+                (Ok(price), Ok(quantity)) if !quantity.is_zero() => {
+                    limit_price = price;
+                    base_quantity = quantity;
+                }
+                _ => {
+                    warn!("Proposal for {} does not fit the venue's tick or lot step; none made", symbol.0);
+                    return None;
+                }
+            }
+        }
+        // Resting at fair value earns about half the spread (spread_pct is in percent)
+        let expected_edge_bps = spread_pct / Decimal::ONE_HUNDRED * BPS_PER_UNIT / Decimal::TWO;
+
+        let signal = match TradeSignal::builder()
+            .with_strategy(self.config.strategy.clone())
+            .with_symbol(symbol.clone())
+            .with_venue(venue.clone())
+            .with_side(side)
+            .with_order_type(OrderType::Limit)
+            .with_quantity(base_quantity)
+            .with_limit_price(limit_price)
+            .with_scores(features.contradiction_score, features.entropy)
+            .with_expected_edge_bps(expected_edge_bps)
+            // Stamped with the book it came from, so a replay reproduces it
+            .with_timestamp(book.timestamp)
+            .build(context.limits)
+        {
+            Ok(signal) => signal,
+            Err(e) => {
+                warn!("Proposal for {} discarded: {}", symbol.0, e);
+                return None;
+            }
+        };
+
+        info!("Proposed trade: {:?} @ {}", side, limit_price);
+        Some(signal)
+    }
+}
+
+/// Name of the mean-reversion strategy
+pub const MEAN_REVERSION: &str = "mean_reversion";
+
+/// Mean-reversion tuning
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MeanReversionConfig {
+    /// Mids the bands are taken over
+    pub band_period: usize,
+    /// Standard deviations from the middle band to either outer band
+    pub band_width: Decimal,
+    /// Changes the RSI is taken over
+    pub rsi_period: usize,
+    /// Buy below the lower band only while the RSI is under this
+    pub oversold: Decimal,
+    /// Sell above the upper band only while the RSI is over this
+    pub overbought: Decimal,
+    /// Certainty the position size is weighted by, in (0, 1]
+    pub certainty: Decimal,
+    /// Strategy proposals are attributed to, for capital allocation
+    pub strategy: StrategyId,
+}
+
+impl Default for MeanReversionConfig {
+    fn default() -> Self {
+        Self {
+            band_period: DEFAULT_BOLLINGER_PERIOD,
+            band_width: DEFAULT_BOLLINGER_WIDTH,
+            rsi_period: DEFAULT_RSI_PERIOD,
+            oversold: Decimal::from(30),
+            overbought: Decimal::from(70),
+            certainty: Decimal::ONE,
+            strategy: StrategyId::default(),
+        }
+    }
+}
+
+/// Fade a mid that closes outside a Bollinger Band while the RSI agrees
+/// it is stretched: buy below the lower band when oversold, sell above the
+/// upper band when overbought, at the microprice and sized by the risk
+/// budget
+///
+/// Stands aside while the book's entropy is past DELTA_U_MAX_SQ, and
+/// never adds to an open position on the same side.
+#[derive(Debug, Clone)]
+pub struct MeanReversionStrategy {
+    config: MeanReversionConfig,
+    /// Bands and RSI per symbol, fed every mid seen
+    indicators: BTreeMap<Symbol, (BollingerState, RsiState)>,
+}
+
+impl MeanReversionStrategy {
+    pub fn new(config: MeanReversionConfig) -> Self {
+        Self { config, indicators: BTreeMap::new() }
+    }
+
+    /// Feed the book's mid to its symbol's bands and RSI; the lower,
+    /// middle and upper bands and the RSI, once both have enough mids
+    fn update(&mut self, context: &ProposalContext<'_>) -> Option<(Decimal, Decimal, Decimal, Decimal)> {
+        let config = &self.config;
+        let (bands, rsi) = self.indicators.entry(context.symbol.clone()).or_insert_with(|| {
+            (BollingerState::new(config.band_period, config.band_width), RsiState::new(config.rsi_period))
+        });
+        let mid = context.features.mid;
+        let bands = bands.update(mid);
+        let rsi = rsi.update(mid);
+        match (bands, rsi) {
+            (Ok(Some(bands)), Ok(Some(rsi))) => Some((bands.lower, bands.middle, bands.upper, rsi)),
+            (Err(e), _) | (_, Err(e)) => {
+                warn!("Mean reversion indicators for {} not computable: {}", context.symbol.0, e);
+                None
+            }
+            _ => None,
+        }
+    }
+}
+
+impl ProposerStrategy for MeanReversionStrategy {
+    fn name(&self) -> &str {
+        MEAN_REVERSION
+    }
+
+    fn observe(&mut self, context: &ProposalContext<'_>) {
+        self.update(context);
+    }
+
+    fn propose(&mut self, context: &ProposalContext<'_>) -> Option<TradeSignal> {
+        let (lower, middle, upper, rsi) = self.update(context)?;
+        let ProposalContext { symbol, venue, features, book, portfolio, .. } = *context;
+
+        let close = features.mid.get();
+        let side = if close < lower && rsi < self.config.oversold {
+            Side::Buy
+        } else if close > upper && rsi > self.config.overbought {
+            Side::Sell
+        } else {
+            return None;
+        };
+        if features.entropy > DELTA_U_MAX_SQ {
+            debug!("{} entropy {} too high to fade a band touch", symbol.0, features.entropy);
+            return None;
+        }
+        if portfolio.positions.iter().any(|position| position.symbol == *symbol && position.side == side) {
+            debug!("{} already has a {:?} position; not adding", symbol.0, side);
+            return None;
+        }
+
+        let instrument = context.instruments.get(venue, symbol);
+        let mut limit_price = features.microprice.unwrap_or(features.mid);
+        if let Some(instrument) = instrument {
+            limit_price = instrument.round_price(limit_price, side).ok()?;
+        }
+        // Reverting to the middle band is the edge hoped for
+        let expected_edge_bps = if limit_price.is_zero() {
+            Decimal::ZERO
+        } else {
+            (middle - limit_price.get()).abs() / limit_price.get() * BPS_PER_UNIT
+        };
+        let build = |quantity: Quantity| {
+            TradeSignal::builder()
+                .with_strategy(self.config.strategy.clone())
+                .with_symbol(symbol.clone())
+                .with_venue(venue.clone())
+                .with_side(side)
+                .with_order_type(OrderType::Limit)
+                .with_quantity(quantity)
+                .with_limit_price(limit_price)
+                .with_scores(features.contradiction_score, features.entropy)
+                .with_expected_edge_bps(expected_edge_bps)
+                // Stamped with the book it came from, so a replay reproduces it
+                .with_timestamp(book.timestamp)
+                .build(context.limits)
+        };
+
+        // Sized from a one-unit draft: sizing reads only its symbol, side and price
+        let draft = match build(Quantity::new(Decimal::ONE).ok()?) {
+            Ok(draft) => draft,
+            Err(e) => {
+                warn!("Mean reversion proposal for {} discarded: {}", symbol.0, e);
+                return None;
+            }
+        };
+        let quantity = match calculate_position_size(&draft, portfolio, self.config.certainty, context.limits, instrument) {
+            Ok(quantity) if !quantity.is_zero() => quantity,
+            Ok(_) => {
+                debug!("Mean reversion for {}: risk budget allows no size", symbol.0);
+                return None;
+            }
+            Err(e) => {
+                warn!("Mean reversion for {} not sized: {}", symbol.0, e);
+                return None;
+            }
+        };
+        match build(quantity) {
+            Ok(signal) => {
+                info!("Proposed mean reversion: {:?} {} @ {} (RSI {})", side, quantity, limit_price, rsi.round_dp(2));
+                Some(signal)
+            }
+            Err(e) => {
+                warn!("Mean reversion proposal for {} discarded: {}", symbol.0, e);
+                None
+            }
+        }
+    }
+}
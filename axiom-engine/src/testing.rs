@@ -0,0 +1,40 @@
+//! Fixtures shared by the unit tests
+
+use axiom_core::{Amount, LimitsRegistry, OrderType, Portfolio, Price, Quantity, Side, Symbol, TradeSignal, TradeSignalBuilder, Venue};
+use rust_decimal::Decimal;
+
+pub fn price(value: i64) -> Price {
+    Price::new(Decimal::from(value)).unwrap()
+}
+
+/// The reference price the fixtures are verified against
+pub fn market() -> Price {
+    price(65_000)
+}
+
+/// A 0.25 BTC/USD buy of `order_type` with no entropy and 100 bps of
+/// expected edge, to be completed with its prices and built
+pub fn signal_of(order_type: OrderType) -> TradeSignalBuilder {
+    TradeSignal::builder()
+        .with_symbol(Symbol("BTC/USD".to_string()))
+        .with_venue(Venue::Binance)
+        .with_side(Side::Buy)
+        .with_order_type(order_type)
+        .with_quantity(Quantity::new(Decimal::new(25, 2)).unwrap())
+        .with_scores(Decimal::new(8, 1), Decimal::ZERO)
+        .with_expected_edge_bps(Decimal::ONE_HUNDRED)
+}
+
+/// A limit buy at the market that every axiom accepts against `portfolio()`
+pub fn signal() -> TradeSignal {
+    signal_of(OrderType::Limit)
+        .with_limit_price(market())
+        .build(&LimitsRegistry::builtin())
+        .unwrap()
+}
+
+/// 2,000,000 of equity and nothing held, so `signal()` is 0.8% of it:
+/// inside the default 0.25%-1% risk budget
+pub fn portfolio() -> Portfolio {
+    Portfolio::new(Amount::new(Decimal::from(2_000_000)))
+}
@@ -0,0 +1,286 @@
+//! Verification Pool: Proofs Off the Async Threads
+//!
+//! A proof can take as long as the solver timeout, and z3 blocks the
+//! thread it runs on. The pool runs verifications on a few dedicated
+//! threads, each with its own verifier (a z3 context is not shared between
+//! threads), fed through a bounded queue. Callers await the outcome; a full
+//! queue is refused at once with `VERIFY_QUEUE_FULL` rather than queueing
+//! without bound, so a verification backlog shows up as its own error.
+//!
+//! Each request carries the limits and the symbol's funding estimate the
+//! caller's own verifier holds, so a worker proves against the same inputs
+//! however long it has been since it last saw a reload.
+
+use axiom_core::{
+    ErrorCode, FundingEstimate, LatencyRecorder, LimitsRegistry, Portfolio, Price, TradeSignal, VerifiedOrder,
+    ViolationSet,
+};
+use crate::verifier::{Verifier, VerifierConfig};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex, PoisonError};
+use tokio::sync::oneshot;
+use tracing::{debug, warn};
+
+/// Verification worker tuning
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VerificationPoolConfig {
+    /// Worker threads; 0 keeps the signal workers proving on their own
+    /// blocking tasks (the async path then starts a single thread)
+    pub workers: usize,
+    /// Verifications waiting for a worker before new ones are refused
+    pub queue_depth: usize,
+}
+
+impl Default for VerificationPoolConfig {
+    fn default() -> Self {
+        Self { workers: 2, queue_depth: 64 }
+    }
+}
+
+/// Why the pool returned no verified order
+#[derive(Debug, Clone, Serialize, thiserror::Error)]
+#[serde(tag = "code", content = "details")]
+pub enum VerificationError {
+    #[error("Verification queue full ({depth} waiting)")]
+    #[serde(rename = "VERIFY_QUEUE_FULL")]
+    QueueFull { depth: usize },
+
+    #[error("Verification pool shut down")]
+    #[serde(rename = "VERIFY_POOL_CLOSED")]
+    Closed,
+
+    #[error("{0}")]
+    #[serde(rename = "VERIFY_REJECTED")]
+    Rejected(ViolationSet),
+}
+
+impl ErrorCode for VerificationError {
+    fn code(&self) -> &'static str {
+        match self {
+            VerificationError::QueueFull { .. } => "VERIFY_QUEUE_FULL",
+            VerificationError::Closed => "VERIFY_POOL_CLOSED",
+            VerificationError::Rejected(_) => "VERIFY_REJECTED",
+        }
+    }
+}
+
+/// One signal to verify, with everything its verifier needs
+#[derive(Debug, Clone)]
+pub struct VerificationRequest {
+    pub signal: TradeSignal,
+    pub portfolio: Portfolio,
+    /// Every account together, for a signal of one of several
+    pub aggregate: Option<Portfolio>,
+    /// Reference price from the book the signal was proposed on
+    pub market: Price,
+    pub limits: Arc<LimitsRegistry>,
+    /// The symbol's latest funding, for the edge check
+    pub funding: Option<FundingEstimate>,
+}
+
+struct Job {
+    request: VerificationRequest,
+    reply: oneshot::Sender<Result<VerifiedOrder, ViolationSet>>,
+}
+
+/// Verifier threads behind a bounded queue
+pub struct VerificationPool {
+    config: VerificationPoolConfig,
+    jobs: SyncSender<Job>,
+    /// Jobs handed to the queue and not yet picked up by a worker
+    waiting: Arc<AtomicUsize>,
+}
+
+impl VerificationPool {
+    /// Start `config.workers` threads (at least one), each verifying with
+    /// `verifier_config` and reporting to `latency`
+    pub fn new(
+        config: VerificationPoolConfig,
+        verifier_config: VerifierConfig,
+        limits: Arc<LimitsRegistry>,
+        latency: Option<Arc<dyn LatencyRecorder>>,
+    ) -> Self {
+        let (jobs, queue) = mpsc::sync_channel(config.queue_depth);
+        let queue = Arc::new(Mutex::new(queue));
+        let waiting = Arc::new(AtomicUsize::new(0));
+        for index in 0..config.workers.max(1) {
+            let (queue, waiting, limits, latency) = (queue.clone(), waiting.clone(), limits.clone(), latency.clone());
+            let spawned = std::thread::Builder::new()
+                .name(format!("verifier-{}", index))
+                .spawn(move || {
+                    // Built on the worker: the backend stays on the thread it serves
+                    let mut verifier = Verifier::new().with_config(verifier_config).with_limits(limits);
+                    if let Some(latency) = latency {
+                        verifier.set_latency_recorder(latency);
+                    }
+                    work(verifier, &queue, &waiting);
+                });
+            if let Err(e) = spawned {
+                warn!("Verification worker {} not started: {}", index, e);
+            }
+        }
+        Self { config, jobs, waiting }
+    }
+
+    pub fn config(&self) -> VerificationPoolConfig {
+        self.config
+    }
+
+    /// Verifications waiting for a worker
+    pub fn waiting(&self) -> usize {
+        self.waiting.load(Ordering::Relaxed)
+    }
+
+    /// Queue `request` and wait for its outcome; refused at once when the
+    /// queue is full
+    pub async fn verify(&self, request: VerificationRequest) -> Result<VerifiedOrder, VerificationError> {
+        let (reply, outcome) = oneshot::channel();
+        self.waiting.fetch_add(1, Ordering::Relaxed);
+        if let Err(e) = self.jobs.try_send(Job { request, reply }) {
+            self.waiting.fetch_sub(1, Ordering::Relaxed);
+            return Err(match e {
+                TrySendError::Full(_) => VerificationError::QueueFull { depth: self.config.queue_depth },
+                TrySendError::Disconnected(_) => VerificationError::Closed,
+            });
+        }
+        match outcome.await {
+            Ok(outcome) => outcome.map_err(VerificationError::Rejected),
+            // The worker died mid-verification
+            Err(_) => Err(VerificationError::Closed),
+        }
+    }
+}
+
+/// Verify queued jobs until the pool is dropped
+fn work(mut verifier: Verifier, queue: &Mutex<Receiver<Job>>, waiting: &AtomicUsize) {
+    loop {
+        let job = queue.lock().unwrap_or_else(PoisonError::into_inner).recv();
+        let Ok(Job { request, reply }) = job else {
+            debug!("Verification pool closed; worker exiting");
+            return;
+        };
+        waiting.fetch_sub(1, Ordering::Relaxed);
+
+        if !Arc::ptr_eq(&verifier.limits(), &request.limits) {
+            verifier.set_limits(request.limits.clone());
+        }
+        // A request without funding clears what an earlier one left behind
+        match request.funding {
+            Some(funding) => verifier.record_funding(request.signal.symbol.clone(), funding),
+            None => verifier.clear_funding(&request.signal.symbol),
+        }
+        let outcome = match &request.aggregate {
+            Some(aggregate) => verifier.verify_account_signal(&request.signal, &request.portfolio, aggregate, request.market),
+            None => verifier.verify_signal(&request.signal, &request.portfolio, request.market),
+        };
+        // The caller may have stopped waiting
+        let _ = reply.send(outcome);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use std::sync::atomic::AtomicU64;
+    use std::time::{Duration, Instant};
+
+    fn pool(workers: usize, queue_depth: usize) -> Arc<VerificationPool> {
+        let config = VerificationPoolConfig { workers, queue_depth };
+        Arc::new(VerificationPool::new(config, VerifierConfig::default(), Arc::new(LimitsRegistry::builtin()), None))
+    }
+
+    fn request() -> VerificationRequest {
+        VerificationRequest {
+            signal: testing::signal(),
+            portfolio: testing::portfolio(),
+            aggregate: None,
+            market: testing::market(),
+            limits: Arc::new(LimitsRegistry::builtin()),
+            funding: None,
+        }
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn verifications_leave_the_async_thread_free() {
+        let pool = pool(2, 128);
+        // A tick task sharing the runtime's only thread with the callers,
+        // recording the longest it waited between ticks
+        let longest_us = Arc::new(AtomicU64::new(0));
+        let ticker = tokio::spawn({
+            let longest_us = longest_us.clone();
+            async move {
+                let mut last = Instant::now();
+                loop {
+                    tokio::time::sleep(Duration::from_millis(1)).await;
+                    let gap = u64::try_from(last.elapsed().as_micros()).unwrap_or(u64::MAX);
+                    longest_us.fetch_max(gap, Ordering::Relaxed);
+                    last = Instant::now();
+                }
+            }
+        });
+
+        let verifications: Vec<_> = (0..100)
+            .map(|_| {
+                let pool = pool.clone();
+                tokio::spawn(async move { pool.verify(request()).await })
+            })
+            .collect();
+        for verification in verifications {
+            assert!(verification.await.unwrap().is_ok());
+        }
+        ticker.abort();
+
+        // Blocking the thread on the proofs would stall it for all of them
+        let longest = Duration::from_micros(longest_us.load(Ordering::Relaxed));
+        assert!(longest > Duration::ZERO, "the tick task never ran");
+        assert!(longest < Duration::from_millis(50), "tick task stalled for {:?}", longest);
+        assert_eq!(pool.waiting(), 0);
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn a_full_queue_is_refused_at_once() {
+        // One worker and one slot: submitted together, most of 100 requests
+        // find the slot taken while the worker proves
+        let pool = pool(1, 1);
+        let verifications: Vec<_> = (0..100)
+            .map(|_| {
+                let pool = pool.clone();
+                tokio::spawn(async move { pool.verify(request()).await })
+            })
+            .collect();
+        let mut refused = 0;
+        for verification in verifications {
+            match verification.await.unwrap() {
+                Ok(_) => {}
+                Err(e) => {
+                    assert_eq!(e.code(), "VERIFY_QUEUE_FULL");
+                    assert!(matches!(e, VerificationError::QueueFull { depth: 1 }));
+                    refused += 1;
+                }
+            }
+        }
+        assert!(refused > 0);
+        assert_eq!(pool.waiting(), 0);
+    }
+
+    #[tokio::test]
+    async fn a_request_without_funding_clears_the_workers_estimate() {
+        let pool = pool(1, 8);
+        // 95 bps due within the holding period, with 10 bps of fees, eats
+        // the 100 bps of edge
+        let mut funded = request();
+        funded.funding = Some(FundingEstimate {
+            rate: rust_decimal::Decimal::new(95, 4),
+            next_funding: funded.signal.timestamp + chrono::Duration::minutes(30),
+        });
+        let rejected = pool.verify(funded).await.unwrap_err();
+        assert!(matches!(&rejected, VerificationError::Rejected(violations) if violations.codes() == ["L0_INSUFFICIENT_EDGE"]), "{:?}", rejected);
+
+        // The same worker, no longer told of any funding
+        assert!(pool.verify(request()).await.is_ok());
+    }
+}
@@ -2,126 +2,719 @@
 //!
 //! The "proving" component that verifies all trade proposals satisfy
 //! the L0 Invariant Contract using formal methods.
+//!
+//! A signal that passes the L0 checks is handed to a proof backend: z3
+//! (`SmtVerifier`, behind the default `smt` feature), or `RuleVerifier`,
+//! which evaluates the same axioms directly and needs no system libraries.
+//! Both accept and reject the same signals with the same violations. The
+//! SMT check is bounded by the solver timeout in `VerifierConfig`, so a
+//! pathological query rejects its signal (L0_VERIFICATION_TIMEOUT) instead
+//! of stalling the signal path. The backend's duration goes into the proof
+//! and, with a latency recorder, into the `solve` stage. Proofs found are
+//! kept in a `ProofCache` and reused for near-identical signals.
+//!
+//! Legs that must trade together (cross-venue arbitrage) are verified as a
+//! batch: each leg on its own, then the joint axioms over the portfolio as
+//! it would stand after every leg fills. A failing batch is rejected whole,
+//! with the violations of each leg at fault.
 
 use axiom_core::{
     TradeSignal, VerifiedOrder, Proof, Portfolio, L0InvariantContract,
-    InvariantViolation, MarketRegime,
+    InvariantViolation, proof_signature, LimitsRegistry, Price, Quantity, Side, SlippageBound,
+    Symbol, SymbolLimits, UnitError, ViolationSet, EdgeCheck, FundingEstimate, LatencyRecorder, LatencyStage,
 };
+use axiom_core::arith;
 use axiom_core::constants::*;
 use rust_decimal::Decimal;
 use chrono::Utc;
-use std::collections::HashMap;
-use tracing::{info, warn};
-use z3::{Config, Context, Solver, ast::Int};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Instant;
+use tracing::info;
+use crate::proof_cache::{ProofCache, ProofCacheConfig};
+use crate::rules::RuleVerifier;
+use crate::verification_pool::VerificationPoolConfig;
+#[cfg(feature = "smt")]
+use crate::smt::SmtVerifier;
+
+/// Whether this build can prove with z3 (the `smt` feature)
+pub const SMT_AVAILABLE: bool = cfg!(feature = "smt");
+
+/// How a signal that passed the L0 checks is proven
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VerifierBackend {
+    /// z3, when built with the `smt` feature
+    Smt,
+    /// The same axioms evaluated directly
+    Rules,
+}
+
+impl Default for VerifierBackend {
+    fn default() -> Self {
+        if SMT_AVAILABLE { VerifierBackend::Smt } else { VerifierBackend::Rules }
+    }
+}
 
-/// SMT-based verifier gate
+/// Proof backend and solver resource limits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VerifierConfig {
+    pub backend: VerifierBackend,
+    /// Give up on a check after this long and reject the signal
+    pub timeout_ms: u32,
+    /// Cap on the solver's memory; z3 applies it process-wide. None leaves
+    /// it unbounded
+    pub max_memory_mb: Option<u32>,
+    /// Proofs reused for near-identical signals
+    pub cache: ProofCacheConfig,
+    /// Threads the async signal path verifies on
+    pub pool: VerificationPoolConfig,
+}
+
+impl Default for VerifierConfig {
+    fn default() -> Self {
+        Self {
+            backend: VerifierBackend::default(),
+            timeout_ms: 250,
+            max_memory_mb: None,
+            cache: ProofCacheConfig::default(),
+            pool: VerificationPoolConfig::default(),
+        }
+    }
+}
+
+/// Verifier gate: the L0 checks, then a proof from the configured backend
 pub struct Verifier {
-    context: Context,
+    /// Holds the limit axioms; rebuilt whenever they change
+    backend: Box<dyn ProofBackend>,
+    limits: Arc<LimitsRegistry>,
+    config: VerifierConfig,
+    /// Proofs found so far; only valid for the current limit axioms
+    cache: Mutex<ProofCache>,
+    /// Latest published funding per symbol, for the edge check
+    funding: HashMap<Symbol, FundingEstimate>,
+    latency: Option<Arc<dyn LatencyRecorder>>,
 }
 
 impl Verifier {
     pub fn new() -> Self {
-        let cfg = Config::new();
-        let context = Context::new(&cfg);
-        Self { context }
+        let limits = Arc::new(LimitsRegistry::builtin());
+        let config = VerifierConfig::default();
+        Self {
+            backend: backend(&limits, &config),
+            limits,
+            cache: Mutex::new(ProofCache::new(config.cache)),
+            config,
+            funding: HashMap::new(),
+            latency: None,
+        }
+    }
+
+    /// Bound every check by `config` instead of the defaults
+    pub fn with_config(mut self, config: VerifierConfig) -> Self {
+        self.backend = backend(&self.limits, &config);
+        self.cache = Mutex::new(ProofCache::new(config.cache));
+        self.config = config;
+        self
+    }
+
+    /// Backend and resource limits proofs are found with
+    pub fn config(&self) -> VerifierConfig {
+        self.config
+    }
+
+    /// Proof cache hits and misses so far
+    pub fn cache_stats(&self) -> (u64, u64) {
+        let cache = self.lock_cache();
+        (cache.hits(), cache.misses())
+    }
+
+    fn lock_cache(&self) -> MutexGuard<'_, ProofCache> {
+        self.cache.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Report each backend check as the `solve` stage, by the signal's venue
+    pub fn set_latency_recorder(&mut self, latency: Arc<dyn LatencyRecorder>) {
+        self.latency = Some(latency);
+    }
+
+    /// Verify against `limits` instead of the built-in per-symbol limits
+    pub fn with_limits(mut self, limits: Arc<LimitsRegistry>) -> Self {
+        self.set_limits(limits);
+        self
+    }
+
+    /// Replace the limits between verifications (config reload)
+    ///
+    /// The backend is rebuilt with the new limit axioms.
+    pub fn set_limits(&mut self, limits: Arc<LimitsRegistry>) {
+        self.backend = backend(&limits, &self.config);
+        self.lock_cache().clear();
+        self.limits = limits;
+    }
+
+    /// Limits signals are verified against
+    pub fn limits(&self) -> Arc<LimitsRegistry> {
+        self.limits.clone()
+    }
+
+    /// Charge `symbol`'s signals for `estimate` when it falls due within
+    /// their holding period
+    pub fn record_funding(&mut self, symbol: Symbol, estimate: FundingEstimate) {
+        self.funding.insert(symbol, estimate);
+    }
+
+    /// Stop charging `symbol`'s signals for funding, once none is published
+    pub fn clear_funding(&mut self, symbol: &Symbol) {
+        self.funding.remove(symbol);
+    }
+
+    /// `symbol`'s funding last recorded
+    pub fn funding(&self, symbol: &Symbol) -> Option<FundingEstimate> {
+        self.funding.get(symbol).copied()
     }
 
     /// Verify a trade signal and generate proof
     ///
+    /// `market` is the reference price from the book the signal was proposed on.
     /// Returns Ok(VerifiedOrder) if the trade satisfies all invariants,
-    /// Err(InvariantViolation) otherwise.
+    /// otherwise every violated invariant.
     pub fn verify_signal(
         &self,
         signal: &TradeSignal,
         portfolio: &Portfolio,
-    ) -> Result<VerifiedOrder, InvariantViolation> {
-        // Step 1: Check L0 Invariant Contract
-        L0InvariantContract::verify_signal(signal, portfolio)?;
+        market: Price,
+    ) -> Result<VerifiedOrder, ViolationSet> {
+        self.verify(signal, portfolio, None, market)
+    }
+
+    /// Verify a signal for one of several accounts
+    ///
+    /// `portfolio` is the signal's account; `aggregate` (every account
+    /// together) must also satisfy the portfolio-wide invariants.
+    pub fn verify_account_signal(
+        &self,
+        signal: &TradeSignal,
+        portfolio: &Portfolio,
+        aggregate: &Portfolio,
+        market: Price,
+    ) -> Result<VerifiedOrder, ViolationSet> {
+        self.verify(signal, portfolio, Some(aggregate), market)
+    }
+
+    /// Verify legs that must all trade or none, each with the reference
+    /// price from its own book
+    ///
+    /// Every leg is verified as `verify_signal` would, then the legs are
+    /// proven together: the gross exposure and leverage once they all fill,
+    /// and each symbol's combined position, must stay within the limits.
+    /// Either every leg is verified or the batch is rejected, naming the
+    /// legs at fault.
+    pub fn verify_batch(&self, legs: &[(TradeSignal, Price)], portfolio: &Portfolio) -> Result<Vec<VerifiedOrder>, BatchViolation> {
+        self.verify_legs(legs, portfolio, None)
+    }
+
+    /// `verify_batch` for one of several accounts (see `verify_account_signal`)
+    pub fn verify_account_batch(
+        &self,
+        legs: &[(TradeSignal, Price)],
+        portfolio: &Portfolio,
+        aggregate: &Portfolio,
+    ) -> Result<Vec<VerifiedOrder>, BatchViolation> {
+        self.verify_legs(legs, portfolio, Some(aggregate))
+    }
 
-        // Step 2: Check Hamiltonian energy
-        L0InvariantContract::verify_hamiltonian_energy(portfolio)?;
+    fn verify(
+        &self,
+        signal: &TradeSignal,
+        portfolio: &Portfolio,
+        aggregate: Option<&Portfolio>,
+        market: Price,
+    ) -> Result<VerifiedOrder, ViolationSet> {
+        let proof = self.check(signal, portfolio, aggregate, market)?;
+        Ok(self.seal(signal, proof))
+    }
+
+    fn verify_legs(
+        &self,
+        legs: &[(TradeSignal, Price)],
+        portfolio: &Portfolio,
+        aggregate: Option<&Portfolio>,
+    ) -> Result<Vec<VerifiedOrder>, BatchViolation> {
+        // Each leg on its own first
+        let mut rejected = BatchViolation::default();
+        let mut proofs = Vec::with_capacity(legs.len());
+        for (index, (signal, market)) in legs.iter().enumerate() {
+            match self.check(signal, portfolio, aggregate, *market) {
+                Ok(proof) => proofs.push(proof),
+                Err(violations) => rejected.extend(index, violations),
+            }
+        }
+        rejected.into_result()?;
+
+        // Then all of them together
+        let batch = BatchObligation::new(legs, portfolio, &self.limits)?;
+        let start = Instant::now();
+        let result = self.backend.prove_batch(&batch);
+        if let (Some(latency), Some((signal, _))) = (&self.latency, legs.first()) {
+            latency.record(LatencyStage::Solve, &signal.venue, start.elapsed());
+        }
+        result?;
+
+        let orders = legs.iter().zip(proofs).enumerate()
+            .map(|(index, ((signal, _), mut proof))| {
+                batch.record(index, &mut proof);
+                self.seal(signal, proof)
+            })
+            .collect();
+        Ok(orders)
+    }
+
+    /// Every check on one signal, and its proof
+    fn check(
+        &self,
+        signal: &TradeSignal,
+        portfolio: &Portfolio,
+        aggregate: Option<&Portfolio>,
+        market: Price,
+    ) -> Result<Proof, ViolationSet> {
+        // Step 1: Check L0 Invariant Contract (includes the entropy regime)
+        let mut violations = L0InvariantContract::verify_signal(signal, portfolio, &self.limits, market)
+            .err()
+            .unwrap_or_default();
 
-        // Step 3: Check market regime (entropy threshold)
-        if signal.entropy_count > DELTA_U_MAX_SQ {
-            return Err(InvariantViolation::ExcessiveEntropy);
+        // Step 2: Check the strategy's allocation (exposures are tracked on
+        // the aggregate), Hamiltonian energy, then every account together
+        violations.record(L0InvariantContract::verify_allocation(
+            signal, aggregate.unwrap_or(portfolio), &self.limits, market,
+        ));
+        violations.record(L0InvariantContract::verify_hamiltonian_energy(portfolio));
+        if let Some(aggregate) = aggregate {
+            violations.extend(L0InvariantContract::verify_aggregate(aggregate, &self.limits).err().unwrap_or_default());
         }
 
-        // Step 4: Generate SMT proof
-        let proof = self.generate_proof(signal, portfolio)?;
+        // Step 3: Check the expected edge covers fees and imminent funding
+        let edge = match L0InvariantContract::verify_edge(signal, portfolio, &self.limits, self.funding.get(&signal.symbol)) {
+            Ok(edge) => Some(edge),
+            Err(violation) => {
+                violations.push(violation);
+                None
+            }
+        };
+        violations.into_result()?;
+
+        // Step 4: Generate SMT proof, carrying the edge calculation
+        let mut proof = self.generate_proof(signal, portfolio, market)?;
+        if let Some(edge) = edge {
+            record_edge(&mut proof, &edge);
+        }
+        Ok(proof)
+    }
 
-        // Step 5: Create verified order
+    /// Step 5: the verified order `proof` proves `signal` to be
+    fn seal(&self, signal: &TradeSignal, proof: Proof) -> VerifiedOrder {
         let verified_order = VerifiedOrder {
             signal: signal.clone(),
-            proof_signature: format!("C=0:{}", hex::encode(&proof.model.get("hash").unwrap_or(&"".to_string()).as_bytes())),
+            proof_signature: proof_signature(signal, &proof),
             proof,
             verified_at: Utc::now(),
+            signature: None,
         };
 
-        info!("Trade verified: {} {} @ {:?}", 
+        info!("Trade verified: {:?} {} @ {:?}", 
             signal.side, signal.quantity, signal.limit_price);
 
-        Ok(verified_order)
+        verified_order
     }
 
     /// Generate SMT proof for the trade
+    ///
+    /// A proof cached for a signal like this one is reused instead of
+    /// asking the backend.
     fn generate_proof(
         &self,
         signal: &TradeSignal,
         portfolio: &Portfolio,
-    ) -> Result<Proof, InvariantViolation> {
-        let solver = Solver::new(&self.context);
-
-        // Create SMT variables
-        let quantity = Int::from_i64(&self.context, 
-            (signal.quantity * Decimal::from(1_000_000)).to_i64().unwrap_or(0));
-        let max_quantity = Int::from_i64(&self.context, 
-            (MAX_POSITION_SIZE_BTC * Decimal::from(1_000_000)).to_i64().unwrap_or(0));
-        let leverage = Int::from_i64(&self.context,
-            (portfolio.leverage * Decimal::from(1_000_000)).to_i64().unwrap_or(0));
-        let max_leverage = Int::from_i64(&self.context,
-            (MAX_LEVERAGE * Decimal::from(1_000_000)).to_i64().unwrap_or(0));
-
-        // Add constraints (axioms)
-        // Axiom 1: quantity <= max_quantity
-        solver.assert(&quantity.le(&max_quantity));
-        
-        // Axiom 2: leverage <= max_leverage
-        solver.assert(&leverage.le(&max_leverage));
-
-        // Check satisfiability
-        match solver.check() {
-            z3::SatResult::Sat => {
-                let model = solver.get_model().unwrap();
-                let mut proof_model = HashMap::new();
-                
-                // Extract model values
-                for decl in model.get_const_decls() {
-                    let value = model.eval(&decl.apply(&[]), true).unwrap();
-                    proof_model.insert(decl.name().to_string(), value.to_string());
-                }
+        market: Price,
+    ) -> Result<Proof, ViolationSet> {
+        let obligation = ProofObligation::new(signal, portfolio, &self.limits, market)?;
+        if let Some(proof) = self.lock_cache().get(&obligation) {
+            return Ok(proof);
+        }
 
-                Ok(Proof {
-                    satisfiable: true,
-                    model: proof_model,
-                    axioms_satisfied: vec![
-                        "PositionSizeLimit".to_string(),
-                        "LeverageLimit".to_string(),
-                        "RiskBudget".to_string(),
-                        "EnergyConstraint".to_string(),
-                    ],
-                })
-            }
-            z3::SatResult::Unsat => {
-                warn!("SMT solver found constraints unsatisfiable");
-                Err(InvariantViolation::LeverageExceeded {
+        let start = Instant::now();
+        let result = self.backend.prove(&obligation);
+        let elapsed = start.elapsed();
+        if let Some(latency) = &self.latency {
+            latency.record(LatencyStage::Solve, &signal.venue, elapsed);
+        }
+        let mut proof = result?;
+        proof.solve_us = u64::try_from(elapsed.as_micros()).unwrap_or(u64::MAX);
+        self.lock_cache().insert(&obligation, &proof);
+        Ok(proof)
+    }
+}
+
+/// The backend `config` asks for, holding `limits`' axioms
+#[cfg_attr(not(feature = "smt"), allow(unused_variables))]
+fn backend(limits: &LimitsRegistry, config: &VerifierConfig) -> Box<dyn ProofBackend> {
+    match config.backend {
+        #[cfg(feature = "smt")]
+        VerifierBackend::Smt => Box::new(SmtVerifier::new(limits, config)),
+        #[cfg(not(feature = "smt"))]
+        VerifierBackend::Smt => {
+            tracing::warn!("Built without the smt feature; proving with rules instead");
+            Box::new(RuleVerifier::new())
+        }
+        VerifierBackend::Rules => Box::new(RuleVerifier::new()),
+    }
+}
+
+/// Finds the proof for a signal that passed the L0 checks
+///
+/// `Send` so a verifier can move with its generator onto the thread that
+/// proves (a blocking task or a pool worker); a backend is never shared.
+pub trait ProofBackend: Send {
+    /// A proof that every axiom in `obligation.axioms()` holds, or the
+    /// violations of those that do not
+    fn prove(&self, obligation: &ProofObligation<'_>) -> Result<Proof, ViolationSet>;
+
+    /// Whether every leg of `batch` keeps both joint axioms, or the
+    /// violations of the legs that do not
+    fn prove_batch(&self, batch: &BatchObligation) -> Result<(), BatchViolation>;
+}
+
+/// One signal's values for the axioms to be checked against
+#[derive(Debug, Clone, Copy)]
+pub struct ProofObligation<'a> {
+    pub signal: &'a TradeSignal,
+    pub portfolio: &'a Portfolio,
+    /// The symbol's position once the signal fills
+    pub projected: Quantity,
+    pub symbol_limits: &'a SymbolLimits,
+    pub max_leverage: Decimal,
+    /// The price the order is valued at for its risk budget (see
+    /// `L0InvariantContract::order_price`)
+    pub price: Price,
+    /// Absent for orders without a price to bound
+    pub bound: Option<SlippageBound>,
+}
+
+impl<'a> ProofObligation<'a> {
+    /// What `signal` must satisfy against `limits`, with `market` as the
+    /// reference price
+    pub fn new(
+        signal: &'a TradeSignal,
+        portfolio: &'a Portfolio,
+        limits: &'a LimitsRegistry,
+        market: Price,
+    ) -> Result<Self, ViolationSet> {
+        Ok(Self {
+            signal,
+            portfolio,
+            projected: L0InvariantContract::projected_position(signal, portfolio)?,
+            symbol_limits: limits.get(&signal.symbol).map_err(InvariantViolation::from)?,
+            max_leverage: limits.max_leverage(),
+            price: L0InvariantContract::order_price(signal, market),
+            bound: L0InvariantContract::slippage_bound(signal, market)?,
+        })
+    }
+
+    /// Axioms the signal is checked against, in order
+    pub fn axioms(&self) -> Vec<Axiom> {
+        Axiom::ALL.into_iter()
+            .filter(|axiom| *axiom != Axiom::SlippageTolerance || self.bound.is_some())
+            .collect()
+    }
+
+    /// The violations `failed` axioms stand for, as the L0 contract words them
+    pub fn violations(&self, failed: &[Axiom]) -> ViolationSet {
+        let ProofObligation { signal, portfolio, projected, symbol_limits, max_leverage, price, bound } = *self;
+        let mut violations = ViolationSet::new();
+        for axiom in failed {
+            match axiom {
+                Axiom::PositionSizeLimit => violations.push(InvariantViolation::PositionSizeExceeded {
+                    quantity: projected.get(),
+                    max: symbol_limits.max_position.get(),
+                }),
+                Axiom::LeverageLimit => violations.push(InvariantViolation::LeverageExceeded {
                     current: portfolio.leverage,
-                    max: MAX_LEVERAGE,
+                    max: max_leverage,
+                }),
+                Axiom::RiskBudget => violations.push(risk_budget_violation(signal, portfolio, symbol_limits, price)),
+                Axiom::EntropyLimit => violations.push(InvariantViolation::ExcessiveEntropy),
+                Axiom::EnergyConstraint => violations.push(InvariantViolation::EnergyDivergence {
+                    energy: portfolio.energy,
+                    threshold: DELTA_U_MAX_SQ,
+                }),
+                Axiom::SlippageTolerance => if let Some(bound) = bound {
+                    violations.push(InvariantViolation::SlippageToleranceExceeded {
+                        side: signal.side,
+                        price: bound.price.get(),
+                        reference: bound.reference.get(),
+                        deviation: bound.price.delta(bound.reference).abs() / bound.reference.get(),
+                        max: MAX_SLIPPAGE_TOLERANCE,
+                    });
+                },
+            }
+        }
+        violations
+    }
+}
+
+/// An axiom a proof establishes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axiom {
+    /// Projected position within the symbol's maximum
+    PositionSizeLimit,
+    LeverageLimit,
+    /// Position value within the symbol's risk budget of a positive equity
+    RiskBudget,
+    EntropyLimit,
+    /// Hamiltonian energy within its threshold
+    EnergyConstraint,
+    /// Price no worse than the slippage bound
+    SlippageTolerance,
+}
+
+impl Axiom {
+    pub const ALL: [Axiom; 6] = [
+        Axiom::PositionSizeLimit,
+        Axiom::LeverageLimit,
+        Axiom::RiskBudget,
+        Axiom::EntropyLimit,
+        Axiom::EnergyConstraint,
+        Axiom::SlippageTolerance,
+    ];
+
+    /// Name the proof lists it under
+    pub fn label(&self) -> &'static str {
+        match self {
+            Axiom::PositionSizeLimit => "PositionSizeLimit",
+            Axiom::LeverageLimit => "LeverageLimit",
+            Axiom::RiskBudget => "RiskBudget",
+            Axiom::EntropyLimit => "EntropyLimit",
+            Axiom::EnergyConstraint => "EnergyConstraint",
+            Axiom::SlippageTolerance => "SlippageTolerance",
+        }
+    }
+}
+
+/// A joint axiom, checked at each leg of a batch over that leg and every
+/// leg before it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JointAxiom {
+    /// Gross exposure within the leverage cap of a positive equity
+    BatchLeverage,
+    /// The symbol's combined position within its maximum
+    BatchPosition,
+}
+
+impl JointAxiom {
+    pub const ALL: [JointAxiom; 2] = [JointAxiom::BatchLeverage, JointAxiom::BatchPosition];
+
+    /// Name the proof lists it under
+    pub fn label(&self) -> &'static str {
+        match self {
+            JointAxiom::BatchLeverage => "BatchLeverage",
+            JointAxiom::BatchPosition => "BatchPosition",
+        }
+    }
+}
+
+/// The portfolio once a batch's legs up to and including one have filled
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchLeg {
+    pub symbol: Symbol,
+    /// Gross exposure: the portfolio's plus every leg's notional so far
+    pub gross: Decimal,
+    /// The symbol's signed net position
+    pub position: Decimal,
+    pub max_position: Decimal,
+}
+
+/// Several legs' combined post-trade exposure and positions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchObligation {
+    /// One per leg, in order
+    pub legs: Vec<BatchLeg>,
+    pub equity: Decimal,
+    pub max_leverage: Decimal,
+}
+
+impl BatchObligation {
+    /// Accumulate `legs` onto `portfolio`; a leg whose figures overflow is
+    /// rejected
+    fn new(legs: &[(TradeSignal, Price)], portfolio: &Portfolio, limits: &LimitsRegistry) -> Result<Self, BatchViolation> {
+        let mut gross = portfolio.total_exposure.get();
+        let mut positions: HashMap<&Symbol, Decimal> = HashMap::new();
+        let mut batch = Vec::with_capacity(legs.len());
+        for (index, (signal, market)) in legs.iter().enumerate() {
+            let leg = || -> Result<BatchLeg, InvariantViolation> {
+                let held = match positions.get(&signal.symbol) {
+                    Some(position) => *position,
+                    None => held_position(&signal.symbol, portfolio)?,
+                };
+                let quantity = match signal.side {
+                    Side::Buy => signal.quantity.get(),
+                    Side::Sell => -signal.quantity.get(),
+                };
+                let notional = L0InvariantContract::order_price(signal, *market).notional(signal.quantity)?;
+                Ok(BatchLeg {
+                    symbol: signal.symbol.clone(),
+                    gross: arith::checked_add("batch exposure", gross, notional.get()).map_err(UnitError::from)?,
+                    position: arith::checked_add("batch position", held, quantity).map_err(UnitError::from)?,
+                    max_position: limits.get(&signal.symbol)?.max_position.get(),
                 })
+            };
+            match leg() {
+                Ok(leg) => {
+                    gross = leg.gross;
+                    positions.insert(&signal.symbol, leg.position);
+                    batch.push(leg);
+                }
+                Err(violation) => {
+                    let mut rejected = BatchViolation::default();
+                    rejected.push(index, violation);
+                    return Err(rejected);
+                }
             }
-            z3::SatResult::Unknown => {
-                warn!("SMT solver returned unknown");
-                Err(InvariantViolation::ExcessiveEntropy)
+        }
+        Ok(Self { legs: batch, equity: portfolio.equity.get(), max_leverage: limits.max_leverage() })
+    }
+
+    /// Leverage once legs up to `index` have filled; None without a
+    /// positive equity
+    pub fn leverage(&self, index: usize) -> Option<Decimal> {
+        let leg = self.legs.get(index)?;
+        (self.equity > Decimal::ZERO).then(|| leg.gross.checked_div(self.equity)).flatten()
+    }
+
+    /// The violation a failed joint axiom at leg `index` stands for
+    pub fn violation(&self, index: usize, axiom: JointAxiom) -> InvariantViolation {
+        let leg = &self.legs[index];
+        match axiom {
+            JointAxiom::BatchLeverage => match self.leverage(index) {
+                Some(leverage) => InvariantViolation::LeverageExceeded { current: leverage, max: self.max_leverage },
+                None => InvariantViolation::NonPositiveEquity,
+            },
+            JointAxiom::BatchPosition => InvariantViolation::PositionSizeExceeded {
+                quantity: leg.position.abs(),
+                max: leg.max_position,
+            },
+        }
+    }
+
+    /// Add the joint axioms to leg `index`'s proof
+    fn record(&self, index: usize, proof: &mut Proof) {
+        for axiom in JointAxiom::ALL {
+            proof.axioms_satisfied.push(axiom.label().to_string());
+        }
+        proof.model.insert("Batch.legs".to_string(), self.legs.len().to_string());
+        proof.model.insert("Batch.index".to_string(), index.to_string());
+        if let Some(leverage) = self.legs.len().checked_sub(1).and_then(|last| self.leverage(last)) {
+            proof.model.insert("Batch.leverage".to_string(), leverage.to_string());
+        }
+    }
+}
+
+/// A rejected batch: the violations of each leg at fault, by index
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BatchViolation {
+    pub legs: BTreeMap<usize, ViolationSet>,
+}
+
+impl BatchViolation {
+    pub fn push(&mut self, index: usize, violation: InvariantViolation) {
+        self.legs.entry(index).or_default().push(violation);
+    }
+
+    pub fn extend(&mut self, index: usize, violations: ViolationSet) {
+        self.legs.entry(index).or_default().extend(violations);
+    }
+
+    /// Indices of the legs at fault, ascending
+    pub fn indices(&self) -> Vec<usize> {
+        self.legs.keys().copied().collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.legs.is_empty()
+    }
+
+    /// Ok(()) when no leg is at fault
+    pub fn into_result(self) -> Result<(), BatchViolation> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl std::fmt::Display for BatchViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let legs: Vec<String> = self.legs.iter().map(|(index, violations)| format!("leg {}: {}", index, violations)).collect();
+        write!(f, "Batch rejected ({})", legs.join("; "))
+    }
+}
+
+impl std::error::Error for BatchViolation {}
+
+/// `symbol`'s signed net position in `portfolio`
+fn held_position(symbol: &Symbol, portfolio: &Portfolio) -> Result<Decimal, InvariantViolation> {
+    portfolio.positions.iter()
+        .filter(|position| position.symbol == *symbol)
+        .try_fold(Decimal::ZERO, |net, position| {
+            let quantity = match position.side {
+                Side::Buy => position.quantity.get(),
+                Side::Sell => -position.quantity.get(),
+            };
+            arith::checked_add("batch position", net, quantity)
+        })
+        .map_err(|e| UnitError::from(e).into())
+}
+
+/// The risk budget violation a failed RiskBudget axiom stands for, as the
+/// L0 contract words it
+fn risk_budget_violation(signal: &TradeSignal, portfolio: &Portfolio, limits: &SymbolLimits, price: Price) -> InvariantViolation {
+    if !portfolio.equity.is_positive() {
+        return InvariantViolation::NonPositiveEquity;
+    }
+    let fraction = price.notional(signal.quantity)
+        .and_then(|value| value.ratio(portfolio.equity));
+    match fraction {
+        Ok(fraction) if fraction < limits.min_risk_budget() => {
+            InvariantViolation::RiskBudgetTooSmall { fraction, min: limits.min_risk_budget() }
+        }
+        Ok(fraction) => InvariantViolation::RiskBudgetExceeded { fraction, max: limits.max_risk_budget() },
+        Err(e) => e.into(),
+    }
+}
+
+/// Add the edge check's outcome to the proof model (and, when the edge was
+/// checked, the axioms satisfied)
+fn record_edge(proof: &mut Proof, edge: &EdgeCheck) {
+    match edge {
+        EdgeCheck::Cleared(breakdown) => {
+            for (key, value) in [
+                ("Edge.expected_bps", breakdown.expected_bps),
+                ("Edge.fees_bps", breakdown.fees_bps),
+                ("Edge.funding_bps", breakdown.funding_bps),
+                ("Edge.net_bps", breakdown.net_bps),
+                ("Edge.min_net_bps", breakdown.min_net_bps),
+            ] {
+                proof.model.insert(key.to_string(), value.to_string());
             }
+            proof.axioms_satisfied.push("ExpectedEdge".to_string());
+        }
+        EdgeCheck::OptedOut => {
+            proof.model.insert("Edge".to_string(), "opted_out".to_string());
+        }
+        EdgeCheck::Exit => {
+            proof.model.insert("Edge".to_string(), "exit".to_string());
         }
     }
 }
@@ -132,3 +725,108 @@ impl Default for Verifier {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{self, market, price};
+    use axiom_core::{OrderType, TrailOffset};
+
+    /// Every backend this build has, by name
+    fn backends(limits: &LimitsRegistry) -> Vec<(&'static str, Box<dyn ProofBackend>)> {
+        let config = |backend| VerifierConfig { backend, ..VerifierConfig::default() };
+        vec![
+            ("rules", backend(limits, &config(VerifierBackend::Rules))),
+            #[cfg(feature = "smt")]
+            ("smt", backend(limits, &config(VerifierBackend::Smt))),
+        ]
+    }
+
+    fn quantity(value: &str) -> Quantity {
+        Quantity::new(value.parse().unwrap()).unwrap()
+    }
+
+    /// Signals every backend must accept (no codes) or reject with exactly
+    /// these violations
+    fn corpus() -> Vec<(&'static str, TradeSignal, Vec<&'static str>)> {
+        let limits = LimitsRegistry::builtin();
+        let stop_limit = OrderType::StopLimit { trigger: price(65_050), limit: price(65_100) };
+        let stop_market = OrderType::StopMarket { trigger: price(65_050) };
+        let trailing = OrderType::TrailingStop { offset: TrailOffset::Absolute(price(50)) };
+        let with_quantity = |mut signal: TradeSignal, value: &str| {
+            signal.quantity = quantity(value);
+            signal
+        };
+        let market_order = testing::signal_of(OrderType::Market).with_expected_price(market()).build(&limits).unwrap();
+        let stop_market_order = testing::signal_of(stop_market).build(&limits).unwrap();
+        let mut entropic = testing::signal();
+        entropic.entropy_count = Decimal::ONE;
+
+        vec![
+            ("limit", testing::signal(), vec![]),
+            ("market", market_order.clone(), vec![]),
+            ("stop-limit", testing::signal_of(stop_limit).build(&limits).unwrap(), vec![]),
+            ("stop-market", stop_market_order.clone(), vec![]),
+            ("trailing stop", testing::signal_of(trailing).build(&limits).unwrap(), vec![]),
+            ("limit over budget", with_quantity(testing::signal(), "1"), vec!["L0_RISK_BUDGET_EXCEEDED"]),
+            ("limit under budget", with_quantity(testing::signal(), "0.01"), vec!["L0_RISK_BUDGET_TOO_SMALL"]),
+            ("market over budget", with_quantity(market_order, "1"), vec!["L0_RISK_BUDGET_EXCEEDED"]),
+            ("stop-market over budget", with_quantity(stop_market_order, "1"), vec!["L0_RISK_BUDGET_EXCEEDED"]),
+            ("over position", with_quantity(testing::signal(), "11"), vec!["L0_POSITION_SIZE", "L0_RISK_BUDGET_EXCEEDED"]),
+            ("entropic", entropic, vec!["L0_EXCESSIVE_ENTROPY"]),
+            (
+                "limit through the slippage bound",
+                testing::signal_of(OrderType::Limit).with_limit_price(price(65_200)).build(&limits).unwrap(),
+                vec!["L0_SLIPPAGE_TOLERANCE"],
+            ),
+        ]
+    }
+
+    #[test]
+    fn backends_accept_and_reject_the_same_signals() {
+        let limits = LimitsRegistry::builtin();
+        let portfolio = testing::portfolio();
+        for (name, backend) in backends(&limits) {
+            for (case, signal, expected) in corpus() {
+                let obligation = ProofObligation::new(&signal, &portfolio, &limits, market()).unwrap();
+                let codes = match backend.prove(&obligation) {
+                    Ok(proof) => {
+                        let labels: Vec<&str> = obligation.axioms().iter().map(Axiom::label).collect();
+                        assert_eq!(proof.axioms_satisfied, labels, "{} on {}", name, case);
+                        vec![]
+                    }
+                    Err(violations) => violations.codes(),
+                };
+                assert_eq!(codes, expected, "{} on {}", name, case);
+            }
+        }
+    }
+
+    #[test]
+    fn backends_reject_a_portfolio_over_its_leverage_cap() {
+        let limits = LimitsRegistry::builtin();
+        let mut portfolio = testing::portfolio();
+        portfolio.leverage = limits.max_leverage() + Decimal::ONE;
+        let signal = testing::signal();
+        let obligation = ProofObligation::new(&signal, &portfolio, &limits, market()).unwrap();
+        for (name, backend) in backends(&limits) {
+            assert_eq!(backend.prove(&obligation).unwrap_err().codes(), vec!["L0_LEVERAGE"], "{}", name);
+        }
+    }
+
+    #[test]
+    fn a_cached_proof_does_not_pass_a_portfolio_since_over_its_leverage_cap() {
+        // Buckets wide enough that the breached portfolio still finds the
+        // proof cached for the healthy one
+        let cache = ProofCacheConfig { leverage_bucket: Decimal::ONE_THOUSAND, ..ProofCacheConfig::default() };
+        let verifier = Verifier::new().with_config(VerifierConfig { cache, ..VerifierConfig::default() });
+        let mut portfolio = testing::portfolio();
+        let signal = testing::signal();
+        assert!(!verifier.verify_signal(&signal, &portfolio, market()).unwrap().proof.cached);
+        assert!(verifier.verify_signal(&signal, &portfolio, market()).unwrap().proof.cached);
+
+        portfolio.leverage = verifier.limits().max_leverage() + Decimal::ONE;
+        let violations = verifier.verify_signal(&signal, &portfolio, market()).unwrap_err();
+        assert!(violations.codes().contains(&"L0_LEVERAGE"), "{:?}", violations.codes());
+    }
+}
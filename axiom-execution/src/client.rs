@@ -0,0 +1,155 @@
+//! Exchange Clients: The Last Step Before a Venue
+//!
+//! The executor hands every routed, safety-checked order to an
+//! `ExchangeClient`. `DryRunClient` stands in for the real clients in
+//! `run --dry-run`: it sends nothing, writes each request it would have sent
+//! to the audit log, and keeps them for the shutdown summary. The client is
+//! fixed when the executor is built, so a dry run cannot turn live without a
+//! restart.
+
+use crate::executor::ExecutionError;
+use crate::stops::NativeStopOrder;
+use axiom_core::{
+    order_hash, signal_hash, AccountId, AuditLog, AuditRecord, ExchangePositionReport, OrderStatus, OrderType, Price,
+    Symbol, Venue, VerifiedOrder,
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use tracing::{error, info};
+
+/// Future returned by `ExchangeClient` calls
+pub type ClientFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, ExecutionError>> + Send + 'a>>;
+
+/// One order leg as the venue would receive it
+#[derive(Debug, Clone)]
+pub struct SubmitRequest<'a> {
+    /// Venue the routing plan chose for this leg
+    pub venue: Venue,
+    pub order: &'a VerifiedOrder,
+    /// Order type sent (a triggered emulated stop goes out as its execution type)
+    pub order_type: OrderType,
+    pub limit_price: Option<Price>,
+    /// Native parameters for a conditional order the venue holds itself
+    pub native_stop: Option<NativeStopOrder>,
+}
+
+/// A venue's order API
+pub trait ExchangeClient: Send + Sync {
+    /// Submit one order leg
+    fn submit<'a>(&'a self, request: &'a SubmitRequest<'a>) -> ClientFuture<'a, OrderStatus>;
+
+    /// Cancel every resting order for `symbol` on `venue`
+    fn cancel_all<'a>(&'a self, symbol: &'a Symbol, venue: &'a Venue) -> ClientFuture<'a, ()>;
+
+    /// `account`'s open positions and balance on `venue`, for reconciliation;
+    /// unsupported unless overridden
+    fn positions<'a>(&'a self, account: &'a AccountId, venue: &'a Venue) -> ClientFuture<'a, ExchangePositionReport> {
+        Box::pin(async move {
+            Err(ExecutionError::Unsupported(format!("position reports for {} on {}", account, venue)))
+        })
+    }
+
+    /// Whether requests really reach the venue; a dry-run client's requests
+    /// are not recorded as submissions
+    fn is_dry_run(&self) -> bool {
+        false
+    }
+}
+
+/// An order a dry run would have placed, with its full decision context
+#[derive(Debug, Clone, Serialize)]
+pub struct DryRunOrder {
+    pub at: DateTime<Utc>,
+    pub signal_hash: String,
+    pub order_hash: String,
+    pub venue: Venue,
+    pub order_type: OrderType,
+    pub limit_price: Option<Price>,
+    /// Venue order type and parameters for a native conditional order
+    pub native_stop: Option<(String, Vec<(String, String)>)>,
+    /// Signal (features, sizing, stop), proof and C=0 signature
+    pub order: VerifiedOrder,
+}
+
+/// Records requests instead of sending them
+pub struct DryRunClient {
+    audit_log: Option<Arc<AuditLog>>,
+    orders: Mutex<Vec<DryRunOrder>>,
+}
+
+impl DryRunClient {
+    pub fn new() -> Self {
+        Self { audit_log: None, orders: Mutex::new(Vec::new()) }
+    }
+
+    /// Write every would-have-sent request to the audit log
+    pub fn with_audit_log(mut self, audit_log: Arc<AuditLog>) -> Self {
+        self.audit_log = Some(audit_log);
+        self
+    }
+
+    /// Every order that would have been placed, in order
+    pub fn orders(&self) -> Vec<DryRunOrder> {
+        self.orders.lock().map(|orders| orders.clone()).unwrap_or_default()
+    }
+}
+
+impl Default for DryRunClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ExchangeClient for DryRunClient {
+    fn submit<'a>(&'a self, request: &'a SubmitRequest<'a>) -> ClientFuture<'a, OrderStatus> {
+        Box::pin(async move {
+            let order = request.order;
+            let recorded = DryRunOrder {
+                at: Utc::now(),
+                signal_hash: signal_hash(&order.signal),
+                order_hash: order_hash(order),
+                venue: request.venue.clone(),
+                order_type: request.order_type,
+                limit_price: request.limit_price,
+                native_stop: request.native_stop.as_ref().map(|native| (
+                    native.order_type.to_string(),
+                    native.params.iter().map(|(name, value)| (name.to_string(), value.clone())).collect(),
+                )),
+                order: order.clone(),
+            };
+            info!("DRY RUN: would send {:?} {} {} {:?} @ {:?} to {} (order {})",
+                order.signal.side, order.signal.quantity, order.signal.symbol.0,
+                request.order_type, request.limit_price, request.venue, recorded.order_hash);
+
+            if let Some(log) = &self.audit_log {
+                let record = AuditRecord::DryRunSubmission {
+                    venue: request.venue.clone(),
+                    order_type: request.order_type,
+                    limit_price: request.limit_price,
+                };
+                if let Err(e) = log.append(&recorded.signal_hash, Some(&recorded.order_hash), record) {
+                    error!("Failed to write audit entry: {}", e);
+                }
+            }
+            if let Ok(mut orders) = self.orders.lock() {
+                orders.push(recorded);
+            }
+            // Never left the process
+            Ok(OrderStatus::Pending)
+        })
+    }
+
+    fn cancel_all<'a>(&'a self, symbol: &'a Symbol, venue: &'a Venue) -> ClientFuture<'a, ()> {
+        Box::pin(async move {
+            info!("DRY RUN: would cancel all orders for {} on {}", symbol.0, venue);
+            Ok(())
+        })
+    }
+
+    fn is_dry_run(&self) -> bool {
+        true
+    }
+}
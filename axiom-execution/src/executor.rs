@@ -3,55 +3,547 @@
 //! Handles actual order submission to exchanges with retry logic and
 //! error handling.
 
-use axiom_core::{VerifiedOrder, Symbol, Venue, OrderStatus};
+use axiom_core::{
+    AccountId, VerifiedOrder, Symbol, Venue, OrderBook, OrderStatus, OrderType, Price, Quantity, Side, Tick,
+    AuditLog, AuditRecord, EventLog, Correlation, signal_hash, order_hash,
+    LatencyRecorder, LatencyStage, FunnelRecorder, FunnelStage, ErrorCode,
+};
+use axiom_core::events;
+use axiom_risk::{RiskGate, GateState};
+use serde::Serialize;
+use crate::client::{ExchangeClient, SubmitRequest};
+use crate::liquidity::{OrderStore, RestingOrder};
+use crate::routing::OrderRouter;
 use crate::safety::SafetyChecker;
-use tracing::{info, error, warn};
-use std::collections::HashMap;
+use crate::stops::{native_stop, StopEmulator};
+use crate::venue_errors::{ExchangeError, Remediation, RemediationHooks};
+use tokio::sync::watch;
+use tracing::{debug, info, error, warn};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 /// Order executor
 pub struct OrderExecutor {
     // In production, would hold exchange API clients
     _venue_clients: HashMap<Venue, ()>,
+    /// Replaces the venue clients when set (dry run)
+    client: Option<Arc<dyn ExchangeClient>>,
+    router: OrderRouter,
+    audit_log: Option<Arc<AuditLog>>,
+    event_log: Option<Arc<EventLog>>,
+    risk_gate: Option<RiskGate>,
+    /// Per-account gates, closed when one account must stop alone
+    account_gates: HashMap<AccountId, RiskGate>,
+    latency: Option<Arc<dyn LatencyRecorder>>,
+    funnel: Option<Arc<dyn FunnelRecorder>>,
+    /// Conditional orders the venue cannot hold natively
+    stops: Mutex<StopEmulator>,
+    /// Our limit orders resting on venue books, taken out of the book by the
+    /// liquidity check. An emulated stop is not on the book while armed; it
+    /// is added once it fires and is submitted as a limit
+    own_orders: OrderStore,
+    /// Latest book per market (see `on_book`), for the liquidity check
+    books: Mutex<HashMap<(Symbol, Venue), Arc<OrderBook>>>,
+    safety: SafetyChecker,
+    /// Cleared by `stop_intake` at shutdown
+    accepting: AtomicBool,
+    /// Orders between intake and submission
+    in_flight: AtomicUsize,
+    /// Venues refusing orders until resumed, with the reason
+    paused_venues: Mutex<BTreeMap<Venue, String>>,
+    remediation: Option<Arc<dyn RemediationHooks>>,
+}
+
+/// Counts an order as in flight until dropped
+struct InFlight<'a>(&'a AtomicUsize);
+
+impl<'a> InFlight<'a> {
+    fn enter(counter: &'a AtomicUsize) -> Self {
+        counter.fetch_add(1, Ordering::SeqCst);
+        Self(counter)
+    }
+}
+
+impl Drop for InFlight<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
 }
 
 impl OrderExecutor {
     pub fn new() -> Self {
         Self {
             _venue_clients: HashMap::new(),
+            client: None,
+            router: OrderRouter,
+            audit_log: None,
+            event_log: None,
+            risk_gate: None,
+            account_gates: HashMap::new(),
+            latency: None,
+            funnel: None,
+            stops: Mutex::new(StopEmulator::new()),
+            own_orders: OrderStore::new(),
+            books: Mutex::new(HashMap::new()),
+            safety: SafetyChecker::new(),
+            accepting: AtomicBool::new(true),
+            in_flight: AtomicUsize::new(0),
+            paused_venues: Mutex::new(BTreeMap::new()),
+            remediation: None,
         }
     }
 
+    /// Record submissions and fills in the audit chain
+    pub fn with_audit_log(mut self, audit_log: Arc<AuditLog>) -> Self {
+        self.audit_log = Some(audit_log);
+        self
+    }
+
+    /// Emit structured submission and fill events
+    pub fn with_event_log(mut self, event_log: Arc<EventLog>) -> Self {
+        self.event_log = Some(event_log);
+        self
+    }
+
+    /// Refuse new orders while the risk gate is closed
+    pub fn with_risk_gate(mut self, risk_gate: RiskGate) -> Self {
+        self.risk_gate = Some(risk_gate);
+        self
+    }
+
+    /// Refuse new orders for `account` while `gate` is closed (the shared
+    /// risk gate still applies)
+    pub fn with_account_gate(mut self, account: AccountId, gate: RiskGate) -> Self {
+        self.account_gates.insert(account, gate);
+        self
+    }
+
+    /// Report submit_rtt latency per venue
+    pub fn with_latency_recorder(mut self, latency: Arc<dyn LatencyRecorder>) -> Self {
+        self.latency = Some(latency);
+        self
+    }
+
+    /// Count submissions and fills per symbol
+    pub fn with_funnel_recorder(mut self, funnel: Arc<dyn FunnelRecorder>) -> Self {
+        self.funnel = Some(funnel);
+        self
+    }
+
+    /// Send every order through `client` instead of the venue clients
+    ///
+    /// Fixed for the executor's lifetime; there is no way to swap it later.
+    pub fn with_exchange_client(mut self, client: Arc<dyn ExchangeClient>) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Pre-flight checks, including the trusted C=0 keys
+    pub fn with_safety_checker(mut self, safety: SafetyChecker) -> Self {
+        self.safety = safety;
+        self
+    }
+
+    /// Notify `hooks` of venue errors that need more than a failed order
+    pub fn with_remediation(mut self, hooks: Arc<dyn RemediationHooks>) -> Self {
+        self.remediation = Some(hooks);
+        self
+    }
+
     /// Execute a verified order
     ///
     /// Returns the order status after submission
     pub async fn execute_order(&self, order: &VerifiedOrder) -> Result<OrderStatus, ExecutionError> {
+        if !self.accepting.load(Ordering::SeqCst) {
+            return Err(ExecutionError::ShuttingDown);
+        }
+        self.execute(order).await
+    }
+
+    /// Execute an order that reduces exposure, even after `stop_intake`
+    /// (flattening at shutdown)
+    pub async fn execute_exit(&self, order: &VerifiedOrder) -> Result<OrderStatus, ExecutionError> {
+        self.execute(order).await
+    }
+
+    async fn execute(&self, order: &VerifiedOrder) -> Result<OrderStatus, ExecutionError> {
+        let _in_flight = InFlight::enter(&self.in_flight);
+
+        // Step 0: Risk gates
+        self.check_gates(order)?;
+
         // Step 1: Safety check
-        SafetyChecker::check_order(order)?;
+        self.safety.check_order(order)?;
+
+        // Step 2: Conditional orders the venue can't hold are emulated locally
+        let signal = &order.signal;
+        if signal.order_type.is_conditional() && native_stop(&signal.venue, signal.side, &signal.order_type).is_none() {
+            info!("Arming emulated {:?} for {} on {}", signal.order_type, signal.symbol.0, signal.venue);
+            if let Ok(mut stops) = self.stops.lock() {
+                stops.arm(order.clone());
+            }
+            return Ok(OrderStatus::Pending);
+        }
+
+        self.submit(order, signal.order_type, signal.limit_price).await
+    }
+
+    /// Feed a traded price to the stop emulator and submit any stops it fires
+    ///
+    /// Triggered stops go through the risk gate again before submission.
+    pub async fn on_trade(&self, tick: &Tick) -> Vec<Result<OrderStatus, ExecutionError>> {
+        let triggered = match self.stops.lock() {
+            Ok(mut stops) => stops.on_trade(&tick.symbol, &tick.venue, tick.price),
+            Err(_) => return Vec::new(),
+        };
+
+        let mut results = Vec::with_capacity(triggered.len());
+        for stop in triggered {
+            info!("Emulated stop triggered at {} for {}; submitting as {:?}",
+                stop.trigger_price, stop.order.signal.symbol.0, stop.execute_as);
+            if let Err(e) = self.check_gates(&stop.order) {
+                results.push(Err(e));
+                continue;
+            }
+            results.push(self.submit(&stop.order, stop.execute_as, stop.limit_price).await);
+        }
+        results
+    }
+
+    /// Refuse the order while the shared gate or its account's gate is closed
+    fn check_gates(&self, order: &VerifiedOrder) -> Result<(), ExecutionError> {
+        let account = &order.signal.account;
+        let gates = [self.risk_gate.as_ref(), self.account_gates.get(account)];
+        for gate in gates.into_iter().flatten() {
+            if let GateState::Closed { reason } = gate.state() {
+                return Err(ExecutionError::GateClosed(reason));
+            }
+        }
+        self.check_venue(&order.signal.venue)
+    }
+
+    /// Refuse orders for a paused venue
+    fn check_venue(&self, venue: &Venue) -> Result<(), ExecutionError> {
+        match self.paused_venues.lock().ok().and_then(|paused| paused.get(venue).cloned()) {
+            Some(reason) => Err(ExecutionError::GateClosed(format!("{} paused: {}", venue, reason))),
+            None => Ok(()),
+        }
+    }
+
+    /// Refuse orders for `venue` until `resume_venue`
+    pub fn pause_venue(&self, venue: &Venue, reason: impl Into<String>) {
+        let reason = reason.into();
+        error!("Pausing {}: {}", venue, reason);
+        if let Ok(mut paused) = self.paused_venues.lock() {
+            paused.insert(venue.clone(), reason);
+        }
+    }
+
+    /// Accept orders for `venue` again; false if it was not paused
+    pub fn resume_venue(&self, venue: &Venue) -> bool {
+        let resumed = self.paused_venues.lock().is_ok_and(|mut paused| paused.remove(venue).is_some());
+        if resumed {
+            info!("Resumed {}", venue);
+        }
+        resumed
+    }
 
-        // Step 2: Submit to exchange
-        // (In production, would call exchange API)
-        info!("Executing order: {} {} @ {:?}",
+    /// Paused venues and why
+    pub fn paused_venues(&self) -> BTreeMap<Venue, String> {
+        self.paused_venues.lock().map(|paused| paused.clone()).unwrap_or_default()
+    }
+
+    /// Act on a classified venue error beyond failing the order
+    fn remediate(&self, order: &VerifiedOrder, error: &ExchangeError) {
+        let signal = &order.signal;
+        match error.kind.remediation() {
+            Remediation::PauseVenue => {
+                self.pause_venue(&error.venue, error.to_string());
+                if let Some(hooks) = &self.remediation {
+                    hooks.venue_paused(&error.venue, error);
+                }
+            }
+            Remediation::HaltSymbol => {
+                warn!("{} halted on {}: {}", signal.symbol.0, error.venue, error);
+                if let Some(hooks) = &self.remediation {
+                    hooks.symbol_halted(&signal.symbol, &error.venue, error);
+                }
+            }
+            Remediation::Reconcile => {
+                warn!("Insufficient balance for {} on {}: {}", signal.account, error.venue, error);
+                if let Some(hooks) = &self.remediation {
+                    hooks.reconcile(&signal.account, &error.venue, error);
+                }
+            }
+            // No retry layer yet; the caller sees the error and the next signal retries
+            Remediation::Backoff(delay) => warn!("{} (retry after {:?})", error, delay),
+            Remediation::None => {}
+        }
+    }
+
+    /// Submit an order to the exchange as `order_type`
+    async fn submit(
+        &self,
+        order: &VerifiedOrder,
+        order_type: OrderType,
+        limit_price: Option<Price>,
+    ) -> Result<OrderStatus, ExecutionError> {
+        let start = Instant::now();
+        info!("Executing order: {:?} {} {:?} @ {:?}",
             order.signal.side,
             order.signal.quantity,
-            order.signal.limit_price
+            order_type,
+            limit_price
         );
+        self.check_liquidity(order, order_type, limit_price)?;
+
+        let plan = self.router.route_order(order);
+        // Placeholder without a client: simulate order submission (in
+        // production, the venue clients get the same requests)
+        let mut status = OrderStatus::Submitted;
+        if let Some(client) = &self.client {
+            for (venue, leg) in plan {
+                self.check_venue(&venue)?;
+                let native_stop = native_stop(&venue, leg.signal.side, &order_type);
+                let request = SubmitRequest { venue, order: leg, order_type, limit_price, native_stop };
+                status = match client.submit(&request).await {
+                    Ok(status) => status,
+                    Err(ExecutionError::ExchangeApi(e)) => {
+                        self.remediate(order, &e);
+                        return Err(ExecutionError::ExchangeApi(e));
+                    }
+                    Err(e) => return Err(e),
+                };
+            }
+        }
+        if let Some(latency) = &self.latency {
+            latency.record(LatencyStage::SubmitRtt, &order.signal.venue, start.elapsed());
+        }
+        // A dry-run client recorded the requests itself; nothing reached a venue
+        if self.client.as_ref().is_some_and(|client| client.is_dry_run()) {
+            return Ok(status);
+        }
+        if let (OrderType::Limit, Some(price)) = (order_type, limit_price) {
+            self.rest(order, price);
+        }
+        self.count(FunnelStage::Submitted, order);
+        self.audit(order, AuditRecord::OrderSubmitted { status });
+        if let Some(log) = &self.event_log {
+            log.emit(&Self::correlation(order), &events::OrderSubmitted {
+                symbol: order.signal.symbol.clone(),
+                side: order.signal.side,
+                quantity: order.signal.quantity,
+                limit_price,
+                status,
+            });
+        }
+
+        Ok(status)
+    }
+
+    /// Record the latest book for its market; orders for that market are
+    /// checked against it before submission
+    pub fn on_book(&self, book: Arc<OrderBook>) {
+        if let Ok(mut books) = self.books.lock() {
+            books.insert((book.symbol.clone(), book.venue.clone()), book);
+        }
+    }
+
+    /// Refuse an order that would take more than the latest book holds from
+    /// other traders, or trade against one of our own orders
+    ///
+    /// A limit order that does not cross the book rests rather than takes,
+    /// and is not checked; nor is anything without a book for its market.
+    fn check_liquidity(
+        &self,
+        order: &VerifiedOrder,
+        order_type: OrderType,
+        limit_price: Option<Price>,
+    ) -> Result<(), ExecutionError> {
+        let signal = &order.signal;
+        let Some(book) = self.books.lock().ok()
+            .and_then(|books| books.get(&(signal.symbol.clone(), signal.venue.clone())).cloned())
+        else {
+            debug!("No {} book on {}; depth not checked", signal.symbol.0, signal.venue);
+            return Ok(());
+        };
+        let best = match signal.side {
+            Side::Buy => book.asks.first(),
+            Side::Sell => book.bids.first(),
+        };
+        // A market order's price is only what it expects to pay; it is
+        // bounded by the slippage tolerance instead
+        let limit = match order_type {
+            OrderType::Market => None,
+            _ => limit_price,
+        };
+        let crosses = match (limit, best) {
+            (None, _) => true,
+            (Some(_), None) => false,
+            (Some(limit), Some(best)) => match signal.side {
+                Side::Buy => best.price <= limit,
+                Side::Sell => best.price >= limit,
+            },
+        };
+        if !crosses {
+            return Ok(());
+        }
+        // Checked at the price it is submitted at (a fired stop's own limit)
+        let mut submitted = order.clone();
+        submitted.signal.limit_price = limit;
+        self.safety.check_liquidity(&submitted, &book, &self.own_orders)?;
+        Ok(())
+    }
+
+    /// Record `order` as resting at `price`
+    fn rest(&self, order: &VerifiedOrder, price: Price) {
+        let signal = &order.signal;
+        self.own_orders.rest(RestingOrder {
+            id: order_hash(order),
+            account: signal.account.clone(),
+            venue: signal.venue.clone(),
+            symbol: signal.symbol.clone(),
+            side: signal.side,
+            price,
+            quantity: signal.quantity,
+        });
+    }
+
+    /// Our resting orders, as the liquidity check sees them
+    pub fn own_orders(&self) -> &OrderStore {
+        &self.own_orders
+    }
+
+    /// Record an execution report for a submitted order
+    pub fn record_fill(&self, order: &VerifiedOrder, quantity: Quantity, price: Price) {
+        info!("Fill: {} @ {}", quantity, price);
+        self.own_orders.fill(&order_hash(order), quantity);
+        self.count(FunnelStage::Filled, order);
+        self.audit(order, AuditRecord::OrderFilled { quantity, price });
+        if let Some(log) = &self.event_log {
+            log.emit(&Self::correlation(order), &events::Fill {
+                symbol: order.signal.symbol.clone(),
+                side: order.signal.side,
+                quantity,
+                price,
+            });
+        }
+    }
 
-        // Placeholder: simulate order submission
-        Ok(OrderStatus::Submitted)
+    fn correlation(order: &VerifiedOrder) -> Correlation {
+        Correlation::for_signal(&signal_hash(&order.signal), &order.signal.venue)
+            .with_order_hash(&order_hash(order))
+            .with_account(&order.signal.account)
+    }
+
+    fn count(&self, stage: FunnelStage, order: &VerifiedOrder) {
+        if let Some(funnel) = &self.funnel {
+            funnel.record(stage, &order.signal.symbol);
+        }
+    }
+
+    fn audit(&self, order: &VerifiedOrder, record: AuditRecord) {
+        if let Some(log) = &self.audit_log {
+            if let Err(e) = log.append(&signal_hash(&order.signal), Some(&order_hash(order)), record) {
+                error!("Failed to write audit entry: {}", e);
+            }
+        }
     }
 
     /// Cancel an order
     pub async fn cancel_order(&self, order_id: &str, venue: &Venue) -> Result<(), ExecutionError> {
-        info!("Cancelling order {} on {}", order_id, venue.0);
+        info!("Cancelling order {} on {}", order_id, venue);
+        self.own_orders.cancel(order_id);
         // Placeholder: would call exchange cancel API
         Ok(())
     }
 
     /// Cancel all orders for a symbol
     pub async fn cancel_all(&self, symbol: &Symbol, venue: &Venue) -> Result<(), ExecutionError> {
-        warn!("Cancelling all orders for {} on {}", symbol.0, venue.0);
-        // Placeholder: would call exchange cancel-all API
-        Ok(())
+        warn!("Cancelling all orders for {} on {}", symbol.0, venue);
+        if let Ok(mut stops) = self.stops.lock() {
+            let dropped = stops.cancel(symbol, venue);
+            if dropped > 0 {
+                info!("Dropped {} emulated stops for {} on {}", dropped, symbol.0, venue);
+            }
+        }
+        self.own_orders.cancel_market(venue, symbol);
+        match &self.client {
+            Some(client) => client.cancel_all(symbol, venue).await,
+            // Placeholder: would call exchange cancel-all API
+            None => Ok(()),
+        }
+    }
+
+    /// Refuse new orders from now on (exits still go through `execute_exit`)
+    pub fn stop_intake(&self) {
+        if self.accepting.swap(false, Ordering::SeqCst) {
+            info!("Order intake stopped");
+        }
+    }
+
+    /// Whether new orders are still accepted
+    pub fn is_accepting(&self) -> bool {
+        self.accepting.load(Ordering::SeqCst)
+    }
+
+    /// Orders resting locally (emulated stops); venue-held orders are not
+    /// tracked yet
+    pub fn open_orders(&self) -> Vec<VerifiedOrder> {
+        self.stops.lock().map(|stops| stops.orders()).unwrap_or_default()
+    }
+
+    /// `open_orders` placed for `account`
+    pub fn open_orders_for(&self, account: &AccountId) -> Vec<VerifiedOrder> {
+        let mut orders = self.open_orders();
+        orders.retain(|order| &order.signal.account == account);
+        orders
+    }
+
+    /// Orders accepted but not yet submitted
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::SeqCst)
+    }
+
+    /// Wait until no order is in flight; false if `deadline` passes first
+    pub async fn drain(&self, deadline: Duration) -> bool {
+        let started = Instant::now();
+        while self.in_flight() > 0 {
+            if started.elapsed() >= deadline {
+                warn!("{} orders still in flight after {:?}", self.in_flight(), deadline);
+                return false;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        true
+    }
+
+    /// Dead man's switch: cancel every resting order as soon as the risk gate closes
+    ///
+    /// Runs until the gate is dropped or `shutdown` turns true.
+    pub async fn run_dead_man_switch(
+        &self,
+        mut gate: watch::Receiver<GateState>,
+        markets: Vec<(Symbol, Venue)>,
+        mut shutdown: watch::Receiver<bool>,
+    ) {
+        loop {
+            tokio::select! {
+                changed = gate.changed() => if changed.is_err() { break },
+                _ = shutdown.wait_for(|stop| *stop) => break,
+            }
+            let state = gate.borrow_and_update().clone();
+            if let GateState::Closed { reason } = state {
+                error!("Dead man's switch fired ({}); cancelling all orders", reason);
+                for (symbol, venue) in &markets {
+                    if let Err(e) = self.cancel_all(symbol, venue).await {
+                        error!("Cancel-all failed for {} on {} [{}]: {}", symbol.0, venue, e.code(), e);
+                    }
+                }
+            }
+        }
     }
 }
 
@@ -61,18 +553,174 @@ impl Default for OrderExecutor {
     }
 }
 
-#[derive(Debug, thiserror::Error)]
+#[derive(Debug, Serialize, thiserror::Error)]
+#[serde(tag = "code", content = "details")]
 pub enum ExecutionError {
     #[error("Safety check failed: {0}")]
+    #[serde(rename = "EXEC_SAFETY_CHECK")]
     SafetyCheck(#[from] crate::safety::SafetyError),
+
+    #[error("Risk gate closed: {0}")]
+    #[serde(rename = "EXEC_GATE_CLOSED")]
+    GateClosed(String),
     
     #[error("Exchange API error: {0}")]
-    ExchangeApi(String),
+    #[serde(rename = "EXEC_EXCHANGE_API")]
+    
+    ExchangeApi(ExchangeError),
     
     #[error("Network error: {0}")]
+    #[serde(rename = "EXEC_NETWORK")]
+    
     Network(String),
     
     #[error("Timeout: {0}")]
+    #[serde(rename = "EXEC_TIMEOUT")]
+    
     Timeout(String),
+
+    #[error("Shutting down; no new orders accepted")]
+    #[serde(rename = "EXEC_SHUTTING_DOWN")]
+    ShuttingDown,
+
+    #[error("Not supported by this client: {0}")]
+    #[serde(rename = "EXEC_UNSUPPORTED")]
+    Unsupported(String),
+}
+
+impl ErrorCode for ExecutionError {
+    fn code(&self) -> &'static str {
+        match self {
+            ExecutionError::SafetyCheck(_) => "EXEC_SAFETY_CHECK",
+            ExecutionError::GateClosed(_) => "EXEC_GATE_CLOSED",
+            ExecutionError::ExchangeApi(_) => "EXEC_EXCHANGE_API",
+            ExecutionError::Network(_) => "EXEC_NETWORK",
+            ExecutionError::Timeout(_) => "EXEC_TIMEOUT",
+            ExecutionError::ShuttingDown => "EXEC_SHUTTING_DOWN",
+            ExecutionError::Unsupported(_) => "EXEC_UNSUPPORTED",
+        }
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::safety::SafetyError;
+    use axiom_core::{BookLevel, CZeroKey, LimitsRegistry, Proof, TradeSignal, TrustedKey};
+    use chrono::Utc;
+    use rust_decimal::Decimal;
+
+    fn price(value: i64) -> Price {
+        Price::new(Decimal::from(value)).unwrap()
+    }
+
+    /// Tenths of a unit
+    fn tenths(value: i64) -> Quantity {
+        Quantity::new(Decimal::new(value, 1)).unwrap()
+    }
+
+    /// BTC/USD on Binance: bids 0.2 at 64990 and 0.3 at 64980, asks 0.2 at
+    /// 65010 and 0.3 at 65020
+    fn book() -> Arc<OrderBook> {
+        let level = |at: i64, quantity: i64| BookLevel { price: price(at), quantity: tenths(quantity) };
+        Arc::new(OrderBook {
+            symbol: Symbol("BTC/USD".to_string()),
+            venue: Venue::Binance,
+            bids: Arc::new(vec![level(64_990, 2), level(64_980, 3)]),
+            asks: Arc::new(vec![level(65_010, 2), level(65_020, 3)]),
+            timestamp: Utc::now(),
+            sequence: 1,
+        })
+    }
+
+    /// A BTC/USD order on Binance, signed by `key`; a limit at `limit`,
+    /// otherwise a market order
+    fn order(key: &CZeroKey, side: Side, quantity: Quantity, limit: Option<Price>) -> VerifiedOrder {
+        let builder = TradeSignal::builder()
+            .with_symbol(Symbol("BTC/USD".to_string()))
+            .with_venue(Venue::Binance)
+            .with_side(side)
+            .with_quantity(quantity);
+        let builder = match limit {
+            Some(limit) => builder.with_order_type(OrderType::Limit).with_limit_price(limit),
+            None => builder.with_order_type(OrderType::Market).with_expected_price(price(65_000)),
+        };
+        let signal = builder.build(&LimitsRegistry::builtin()).unwrap();
+        let proof = Proof {
+            satisfiable: true,
+            model: HashMap::new(),
+            axioms_satisfied: Vec::new(),
+            solve_us: 0,
+            cached: false,
+        };
+        let mut order = VerifiedOrder {
+            proof_signature: axiom_core::proof_signature(&signal, &proof),
+            signal,
+            proof,
+            verified_at: Utc::now(),
+            signature: None,
+        };
+        order.signature = Some(key.sign(&order, chrono::Duration::minutes(1)).unwrap());
+        order
+    }
+
+    fn executor(key: &CZeroKey) -> OrderExecutor {
+        let executor = OrderExecutor::new()
+            .with_safety_checker(SafetyChecker::new().with_trusted_keys([TrustedKey::new(key.verifying_key())]));
+        executor.on_book(book());
+        executor
+    }
+
+    #[tokio::test]
+    async fn a_resting_order_is_tracked_until_filled_or_cancelled() {
+        let key = CZeroKey::generate();
+        let executor = executor(&key);
+        // Below the best ask: it rests rather than takes
+        let quote = order(&key, Side::Sell, tenths(2), Some(price(65_010)));
+
+        assert_eq!(executor.execute_order(&quote).await.unwrap(), OrderStatus::Submitted);
+        assert_eq!(executor.own_orders().resting_at(&Venue::Binance, &book().symbol, Side::Sell, price(65_010)), tenths(2));
+
+        executor.record_fill(&quote, tenths(1), price(65_010));
+        assert_eq!(executor.own_orders().resting_at(&Venue::Binance, &book().symbol, Side::Sell, price(65_010)), tenths(1));
+        executor.cancel_order(&order_hash(&quote), &Venue::Binance).await.unwrap();
+        assert!(executor.own_orders().levels(&Venue::Binance, &book().symbol, Side::Sell).is_empty());
+
+        // A market order never rests
+        executor.execute_order(&order(&key, Side::Buy, tenths(1), None)).await.unwrap();
+        assert!(executor.own_orders().levels(&Venue::Binance, &book().symbol, Side::Buy).is_empty());
+    }
+
+    #[tokio::test]
+    async fn an_order_reaching_our_own_quote_is_refused_until_it_fills() {
+        let key = CZeroKey::generate();
+        let executor = executor(&key);
+        let quote = order(&key, Side::Sell, tenths(2), Some(price(65_010)));
+        executor.execute_order(&quote).await.unwrap();
+
+        // The book shows 0.5 on the ask, but the best 0.2 of it is ours
+        match executor.execute_order(&order(&key, Side::Buy, tenths(4), None)).await {
+            Err(ExecutionError::SafetyCheck(SafetyError::SelfTrade { price, .. })) => assert_eq!(price, Decimal::from(65_010)),
+            other => panic!("expected a self-trade, got {:?}", other),
+        }
+
+        executor.record_fill(&quote, tenths(2), price(65_010));
+        assert_eq!(executor.execute_order(&order(&key, Side::Buy, tenths(4), None)).await.unwrap(), OrderStatus::Submitted);
+    }
+
+    #[tokio::test]
+    async fn only_orders_that_cross_the_book_are_checked_for_depth() {
+        let key = CZeroKey::generate();
+        let executor = executor(&key);
+
+        match executor.execute_order(&order(&key, Side::Buy, tenths(6), None)).await {
+            Err(ExecutionError::SafetyCheck(SafetyError::InsufficientLiquidity { available, .. })) => {
+                assert_eq!(available, Decimal::new(5, 1))
+            }
+            other => panic!("expected too little depth, got {:?}", other),
+        }
+        // The same size resting under the ask takes nothing
+        let resting = order(&key, Side::Buy, tenths(6), Some(price(65_000)));
+        assert_eq!(executor.execute_order(&resting).await.unwrap(), OrderStatus::Submitted);
+    }
+}
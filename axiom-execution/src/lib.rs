@@ -3,11 +3,19 @@
 //! Handles order execution with built-in safety checks and C=0 signature
 //! verification before any order is sent to an exchange.
 
+pub mod client;
 pub mod executor;
+pub mod liquidity;
 pub mod safety;
 pub mod routing;
+pub mod stops;
+pub mod venue_errors;
 
+pub use client::*;
 pub use executor::*;
+pub use liquidity::*;
 pub use safety::*;
 pub use routing::*;
+pub use stops::*;
+pub use venue_errors::*;
 
@@ -0,0 +1,358 @@
+//! Own Liquidity: Book Depth Net of Our Resting Orders
+//!
+//! A venue's public book includes our own resting orders. Walking it as-is
+//! overstates what an aggressive order can take from other traders, and
+//! whatever part of the fill lands on our own quotes is a self-trade. The
+//! `OrderStore` tracks what we have resting per venue, symbol and price;
+//! depth estimates report both the gross book and the book with our orders
+//! taken out.
+
+use axiom_core::{AccountId, Amount, OrderBook, Price, Quantity, Side, Symbol, UnitError, Venue};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+/// One of our orders resting on a venue book
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RestingOrder {
+    pub id: String,
+    pub account: AccountId,
+    pub venue: Venue,
+    pub symbol: Symbol,
+    pub side: Side,
+    pub price: Price,
+    /// Quantity still open
+    pub quantity: Quantity,
+}
+
+/// Our open orders across venues, by id
+#[derive(Debug, Default)]
+pub struct OrderStore {
+    orders: Mutex<HashMap<String, RestingOrder>>,
+}
+
+impl OrderStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record an order resting on a venue book (replaces one with the same id)
+    pub fn rest(&self, order: RestingOrder) {
+        self.lock().insert(order.id.clone(), order);
+    }
+
+    /// Reduce an order's open quantity by a fill; a fully filled order is removed
+    pub fn fill(&self, id: &str, quantity: Quantity) {
+        let mut orders = self.lock();
+        if let Some(order) = orders.get_mut(id) {
+            order.quantity = order.quantity.saturating_sub(quantity);
+            if order.quantity.is_zero() {
+                orders.remove(id);
+            }
+        }
+    }
+
+    /// Drop a cancelled or expired order
+    pub fn cancel(&self, id: &str) -> Option<RestingOrder> {
+        self.lock().remove(id)
+    }
+
+    /// Drop every order on one book (e.g. on cancel-all); returns how many
+    pub fn cancel_market(&self, venue: &Venue, symbol: &Symbol) -> usize {
+        let mut orders = self.lock();
+        let before = orders.len();
+        orders.retain(|_, order| order.venue != *venue || order.symbol != *symbol);
+        before - orders.len()
+    }
+
+    /// Our open quantity on `side` of one book at `price`
+    pub fn resting_at(&self, venue: &Venue, symbol: &Symbol, side: Side, price: Price) -> Quantity {
+        self.levels(venue, symbol, side).get(&price).copied().unwrap_or_default()
+    }
+
+    /// Our open quantity per price on `side` of one book
+    pub fn levels(&self, venue: &Venue, symbol: &Symbol, side: Side) -> BTreeMap<Price, Quantity> {
+        let mut levels: BTreeMap<Price, Quantity> = BTreeMap::new();
+        for order in self.lock().values() {
+            if order.venue == *venue && order.symbol == *symbol && order.side == side {
+                let level = levels.entry(order.price).or_default();
+                // Open quantities come from Quantity values, so the sum only
+                // fails past Decimal's range; keep the level as it was
+                *level = level.checked_add(order.quantity).unwrap_or(*level);
+            }
+        }
+        levels
+    }
+
+    /// Our best-priced order an order on `side` no worse than `limit` could
+    /// trade against (any limit for a market order)
+    pub fn crossing(&self, venue: &Venue, symbol: &Symbol, side: Side, limit: Option<Price>) -> Option<RestingOrder> {
+        self.lock().values()
+            .filter(|order| order.venue == *venue && order.symbol == *symbol && order.side != side)
+            .filter(|order| limit.is_none_or(|limit| within(side, order.price, limit)))
+            .min_by(|a, b| match side {
+                Side::Buy => a.price.cmp(&b.price),
+                Side::Sell => b.price.cmp(&a.price),
+            })
+            .cloned()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, RestingOrder>> {
+        self.orders.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// What an order could take from one book
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FillEstimate {
+    /// Quantity available within the price bound, capped at the order size
+    pub quantity: Quantity,
+    /// Volume-weighted average price of that quantity; None when nothing fills
+    pub vwap: Option<Price>,
+}
+
+/// Fillable depth as the book shows it and with our own orders taken out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DepthEstimate {
+    pub gross: FillEstimate,
+    pub self_excluded: FillEstimate,
+}
+
+/// Walk the side of `book` an order on `side` takes from, up to `quantity`
+/// and no worse than `limit`
+///
+/// Our own orders rest on the opposite side of the order; their quantity at
+/// each level is subtracted for the self-excluded figure.
+pub fn estimate_fill(
+    book: &OrderBook,
+    side: Side,
+    quantity: Quantity,
+    limit: Option<Price>,
+    own: &OrderStore,
+) -> Result<DepthEstimate, UnitError> {
+    walk(book, side, quantity, own, |price| limit.is_none_or(|limit| within(side, price, limit)))
+}
+
+/// Our resting order an order on `side` would reach
+///
+/// Liquidity from other traders at prices strictly better than our best
+/// crossing order fills first; only if it cannot absorb the whole order does
+/// the order reach ours. Other traders' size at our own price is not counted,
+/// since their queue position relative to ours is unknown.
+pub fn self_trade(
+    book: &OrderBook,
+    side: Side,
+    quantity: Quantity,
+    limit: Option<Price>,
+    own: &OrderStore,
+) -> Result<Option<RestingOrder>, UnitError> {
+    let Some(resting) = own.crossing(&book.venue, &book.symbol, side, limit) else {
+        return Ok(None);
+    };
+
+    let ahead = walk(book, side, quantity, own, |price| price != resting.price && within(side, price, resting.price))?;
+    Ok((ahead.self_excluded.quantity < quantity).then_some(resting))
+}
+
+fn walk(
+    book: &OrderBook,
+    side: Side,
+    quantity: Quantity,
+    own: &OrderStore,
+    include: impl Fn(Price) -> bool,
+) -> Result<DepthEstimate, UnitError> {
+    let levels = match side {
+        Side::Buy => &book.asks,
+        Side::Sell => &book.bids,
+    };
+    let ours = own.levels(&book.venue, &book.symbol, opposite(side));
+
+    let mut gross = Fill::new(quantity);
+    let mut self_excluded = Fill::new(quantity);
+    // Levels run best first, so the first one out of bounds ends the walk
+    for level in levels.iter().take_while(|level| include(level.price)) {
+        let mine = ours.get(&level.price).copied().unwrap_or_default();
+        gross.take(level.price, level.quantity)?;
+        self_excluded.take(level.price, level.quantity.saturating_sub(mine))?;
+    }
+
+    Ok(DepthEstimate {
+        gross: gross.finish()?,
+        self_excluded: self_excluded.finish()?,
+    })
+}
+
+/// Whether `price` is no worse than `limit` for an order on `side`
+fn within(side: Side, price: Price, limit: Price) -> bool {
+    match side {
+        Side::Buy => price <= limit,
+        Side::Sell => price >= limit,
+    }
+}
+
+fn opposite(side: Side) -> Side {
+    match side {
+        Side::Buy => Side::Sell,
+        Side::Sell => Side::Buy,
+    }
+}
+
+/// Running fill while walking book levels
+struct Fill {
+    remaining: Quantity,
+    filled: Quantity,
+    notional: Amount,
+}
+
+impl Fill {
+    fn new(quantity: Quantity) -> Self {
+        Self { remaining: quantity, filled: Quantity::default(), notional: Amount::default() }
+    }
+
+    fn take(&mut self, price: Price, available: Quantity) -> Result<(), UnitError> {
+        let take = available.min(self.remaining);
+        if take.is_zero() {
+            return Ok(());
+        }
+        self.notional = self.notional.checked_add(price.notional(take)?)?;
+        self.filled = self.filled.checked_add(take)?;
+        self.remaining = self.remaining.saturating_sub(take);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<FillEstimate, UnitError> {
+        let vwap = if self.filled.is_zero() {
+            None
+        } else {
+            Some(self.notional.price_per(self.filled)?)
+        };
+        Ok(FillEstimate { quantity: self.filled, vwap })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axiom_core::BookLevel;
+    use chrono::Utc;
+    use rust_decimal::Decimal;
+    use std::sync::Arc;
+
+    fn dec(value: &str) -> Decimal {
+        value.parse().unwrap()
+    }
+
+    fn price(value: &str) -> Price {
+        Price::new(dec(value)).unwrap()
+    }
+
+    fn quantity(value: &str) -> Quantity {
+        Quantity::new(dec(value)).unwrap()
+    }
+
+    fn level(p: &str, q: &str) -> BookLevel {
+        BookLevel { price: price(p), quantity: quantity(q) }
+    }
+
+    fn book() -> OrderBook {
+        OrderBook {
+            symbol: Symbol("BTC/USDT".to_string()),
+            venue: Venue::Binance,
+            bids: Arc::new(vec![level("99", "2"), level("98", "3")]),
+            asks: Arc::new(vec![level("101", "2"), level("102", "3"), level("103", "5")]),
+            timestamp: Utc::now(),
+            sequence: 1,
+        }
+    }
+
+    fn resting(id: &str, side: Side, p: &str, q: &str) -> RestingOrder {
+        RestingOrder {
+            id: id.to_string(),
+            account: AccountId::default(),
+            venue: Venue::Binance,
+            symbol: Symbol("BTC/USDT".to_string()),
+            side,
+            price: price(p),
+            quantity: quantity(q),
+        }
+    }
+
+    #[test]
+    fn without_own_orders_both_views_agree() {
+        let estimate = estimate_fill(&book(), Side::Buy, quantity("3"), None, &OrderStore::new()).unwrap();
+
+        assert_eq!(estimate.gross, estimate.self_excluded);
+        assert_eq!(estimate.gross.quantity, quantity("3"));
+        // 2 @ 101 + 1 @ 102
+        assert_eq!(estimate.gross.vwap.unwrap().get(), dec("304") / dec("3"));
+    }
+
+    #[test]
+    fn own_asks_are_subtracted_from_a_buy() {
+        let own = OrderStore::new();
+        own.rest(resting("a", Side::Sell, "101", "1.5"));
+        own.rest(resting("b", Side::Sell, "102", "1"));
+
+        let estimate = estimate_fill(&book(), Side::Buy, quantity("4"), None, &own).unwrap();
+
+        // Gross: 2 @ 101 + 2 @ 102
+        assert_eq!(estimate.gross.quantity, quantity("4"));
+        assert_eq!(estimate.gross.vwap, Some(price("101.5")));
+        // Self-excluded: 0.5 @ 101 + 2 @ 102 + 1.5 @ 103
+        assert_eq!(estimate.self_excluded.quantity, quantity("4"));
+        assert_eq!(estimate.self_excluded.vwap, Some(price("102.25")));
+    }
+
+    #[test]
+    fn limit_caps_fillable_depth() {
+        let own = OrderStore::new();
+        own.rest(resting("a", Side::Sell, "101", "2"));
+
+        let estimate = estimate_fill(&book(), Side::Buy, quantity("10"), Some(price("102")), &own).unwrap();
+
+        assert_eq!(estimate.gross.quantity, quantity("5"));
+        assert_eq!(estimate.self_excluded.quantity, quantity("3"));
+        assert_eq!(estimate.self_excluded.vwap, Some(price("102")));
+    }
+
+    #[test]
+    fn own_orders_elsewhere_do_not_count() {
+        let own = OrderStore::new();
+        // Same side as the order, another venue, and another symbol
+        own.rest(resting("a", Side::Buy, "101", "2"));
+        own.rest(RestingOrder { venue: Venue::Bybit, ..resting("b", Side::Sell, "101", "2") });
+        own.rest(RestingOrder { symbol: Symbol("ETH/USDT".to_string()), ..resting("c", Side::Sell, "101", "2") });
+
+        let estimate = estimate_fill(&book(), Side::Buy, quantity("2"), None, &own).unwrap();
+
+        assert_eq!(estimate.gross, estimate.self_excluded);
+    }
+
+    #[test]
+    fn fills_and_cancels_release_depth() {
+        let own = OrderStore::new();
+        own.rest(resting("a", Side::Buy, "99", "2"));
+        own.fill("a", quantity("0.5"));
+        assert_eq!(own.resting_at(&Venue::Binance, &Symbol("BTC/USDT".to_string()), Side::Buy, price("99")), quantity("1.5"));
+
+        let estimate = estimate_fill(&book(), Side::Sell, quantity("2"), None, &own).unwrap();
+        assert_eq!(estimate.self_excluded.quantity, quantity("2"));
+        // 0.5 @ 99 + 1.5 @ 98
+        assert_eq!(estimate.self_excluded.vwap, Some(price("98.25")));
+
+        own.fill("a", quantity("1.5"));
+        assert!(own.cancel("a").is_none());
+    }
+
+    #[test]
+    fn self_trade_only_when_order_reaches_our_level() {
+        let own = OrderStore::new();
+        own.rest(resting("a", Side::Sell, "102", "1"));
+
+        // Others' 2 @ 101 absorb a small buy before it reaches our ask
+        assert_eq!(self_trade(&book(), Side::Buy, quantity("2"), None, &own).unwrap(), None);
+        // A larger one walks into 102, where we rest
+        assert_eq!(self_trade(&book(), Side::Buy, quantity("3"), None, &own).unwrap().map(|o| o.id), Some("a".to_string()));
+        // A limit below our ask cannot reach it
+        assert_eq!(self_trade(&book(), Side::Buy, quantity("3"), Some(price("101")), &own).unwrap(), None);
+    }
+}
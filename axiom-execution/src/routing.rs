@@ -15,11 +15,15 @@ impl OrderRouter {
     /// - Liquidity depth
     /// - Fee structure
     /// - Latency
-    pub fn route_order(&self, order: &VerifiedOrder) -> Vec<(Venue, VerifiedOrder)> {
+    ///
+    /// Legs borrow the order: a signed order cannot be split without a new
+    /// signature, so every leg is the whole order on some venue.
+    pub fn route_order<'a>(&self, order: &'a VerifiedOrder) -> Vec<(Venue, &'a VerifiedOrder)> {
         // Simplified: route to primary venue
         // In production, would implement TWAP/VWAP algorithms
-        info!("Routing order to venue: {}", order.signal.venue.0);
-        vec![(order.signal.venue.clone(), order.clone())]
+        let venue = &order.signal.venue;
+        info!("Routing order to venue: {} (taker fee {} bps)", venue, venue.capabilities().taker_fee_bps);
+        vec![(order.signal.venue.clone(), order)]
     }
 }
 
@@ -1,47 +1,205 @@
 //! Execution Safety: Pre-Flight Checks
 //!
 //! Every order must pass these checks before being sent to an exchange.
+//! Orders are signed, so one off the venue's price or size grid is refused
+//! here rather than rounded; rounding happens where the order is built.
 
-use axiom_core::{VerifiedOrder, Symbol, Decimal};
-use axiom_core::constants::*;
+use axiom_core::{
+    VerifiedOrder, SignatureError, TrustedKey, KeyStatus, LimitsError, LimitsRegistry, ErrorCode,
+    InstrumentError, InstrumentRegistry, OrderType, OrderBook, Side, UnitError, MAX_SLIPPAGE_TOLERANCE,
+};
+use rust_decimal::Decimal;
+use crate::liquidity::{self, DepthEstimate, OrderStore};
+use chrono::{Duration, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::watch;
 use tracing::{info, warn};
 
+/// Default allowance for clock skew between signer and executor
+pub const DEFAULT_CLOCK_SKEW_MS: i64 = 500;
+
 /// Pre-flight safety checks
-pub struct SafetyChecker;
+#[derive(Debug)]
+pub struct SafetyChecker {
+    /// Keys whose C=0 signatures are accepted; empty refuses every order
+    trusted_keys: Vec<TrustedKey>,
+    /// Grace period past a signature's expiry
+    clock_skew: Duration,
+    /// Highest nonce accepted so far per signing key (replay protection)
+    last_nonce: Mutex<HashMap<[u8; 32], u64>>,
+    /// Per-symbol order size limits
+    limits: Arc<LimitsRegistry>,
+    /// Reloaded limits; when set, each check reads the latest value
+    limit_updates: Option<watch::Receiver<Arc<LimitsRegistry>>>,
+    /// Venue tick, lot and minimum size rules; empty skips the check
+    instruments: Arc<InstrumentRegistry>,
+}
 
 impl SafetyChecker {
+    pub fn new() -> Self {
+        Self {
+            trusted_keys: Vec::new(),
+            clock_skew: Duration::milliseconds(DEFAULT_CLOCK_SKEW_MS),
+            last_nonce: Mutex::new(HashMap::new()),
+            limits: Arc::new(LimitsRegistry::builtin()),
+            limit_updates: None,
+            instruments: Arc::new(InstrumentRegistry::new()),
+        }
+    }
+
+    /// Require every order to carry a valid C=0 signature from one of `keys`
+    /// while that key is active
+    pub fn with_trusted_keys(mut self, keys: impl IntoIterator<Item = TrustedKey>) -> Self {
+        self.trusted_keys.extend(keys);
+        self
+    }
+
+    /// Accept signatures up to `clock_skew` past their expiry
+    pub fn with_clock_skew(mut self, clock_skew: Duration) -> Self {
+        self.clock_skew = clock_skew;
+        self
+    }
+
+    /// Check order sizes against `limits` instead of the built-in limits
+    pub fn with_limits(mut self, limits: Arc<LimitsRegistry>) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Follow `updates` instead of fixed limits (config reload)
+    pub fn with_limit_updates(mut self, updates: watch::Receiver<Arc<LimitsRegistry>>) -> Self {
+        self.limit_updates = Some(updates);
+        self
+    }
+
+    /// Refuse orders that break their venue's rules in `instruments`
+    pub fn with_instruments(mut self, instruments: Arc<InstrumentRegistry>) -> Self {
+        self.instruments = instruments;
+        self
+    }
+
+    /// Limits orders are currently checked against
+    pub fn limits(&self) -> Arc<LimitsRegistry> {
+        match &self.limit_updates {
+            Some(updates) => updates.borrow().clone(),
+            None => self.limits.clone(),
+        }
+    }
+
     /// Verify order is safe to execute
-    pub fn check_order(order: &VerifiedOrder) -> Result<(), SafetyError> {
+    pub fn check_order(&self, order: &VerifiedOrder) -> Result<(), SafetyError> {
         // Check 1: Verify C=0 signature
-        // (In production, would verify cryptographic signature)
-        
+        self.check_signature(order)?;
+
         // Check 2: Verify order size
-        Self::check_order_size(&order.signal)?;
+        self.check_order_size(&order.signal)?;
         
         // Check 3: Verify price is reasonable
         Self::check_price(&order.signal)?;
-        
+
+        // Check 4: Verify the venue would accept the price and size
+        self.check_instrument(&order.signal)?;
+
         info!("Safety checks passed for order");
         Ok(())
     }
 
-    fn check_order_size(signal: &axiom_core::TradeSignal) -> Result<(), SafetyError> {
-        let max_size = match signal.symbol.0.as_str() {
-            "BTC/USD" => MAX_ORDER_SIZE_BTC,
-            "ETH/USD" => MAX_ORDER_SIZE_ETH,
-            "SOL/USD" => MAX_ORDER_SIZE_SOL,
-            _ => return Err(SafetyError::UnsupportedSymbol),
+    /// Check the book can fill the order from other traders' liquidity
+    ///
+    /// Uses the book with our own resting orders (from `own`) taken out:
+    /// the order must not reach one of our own orders on the other side, and
+    /// the rest of the book must hold its full quantity within its limit
+    /// price (or, for a market order, within the slippage tolerance of the
+    /// best price). Returns both the gross and self-excluded estimates.
+    pub fn check_liquidity(&self, order: &VerifiedOrder, book: &OrderBook, own: &OrderStore) -> Result<DepthEstimate, SafetyError> {
+        let signal = &order.signal;
+
+        if let Some(resting) = liquidity::self_trade(book, signal.side, signal.quantity, signal.limit_price, own)? {
+            warn!("Order would trade against our own order {} at {}", resting.id, resting.price);
+            return Err(SafetyError::SelfTrade { order_id: resting.id, price: resting.price.get() });
+        }
+
+        let best = match signal.side {
+            Side::Buy => book.asks.first(),
+            Side::Sell => book.bids.first(),
         };
+        let limit = match (signal.limit_price, best) {
+            (Some(limit), _) => Some(limit),
+            (None, Some(best)) => Some(best.price.adverse(signal.side, MAX_SLIPPAGE_TOLERANCE)?),
+            (None, None) => None,
+        };
+
+        let estimate = liquidity::estimate_fill(book, signal.side, signal.quantity, limit, own)?;
+        if estimate.self_excluded.quantity < signal.quantity {
+            warn!(
+                "Book holds {} of {} needed ({} gross, before our own orders)",
+                estimate.self_excluded.quantity, signal.quantity, estimate.gross.quantity,
+            );
+            return Err(SafetyError::InsufficientLiquidity {
+                needed: signal.quantity.get(),
+                available: estimate.self_excluded.quantity.get(),
+                gross: estimate.gross.quantity.get(),
+            });
+        }
+
+        Ok(estimate)
+    }
+
+    fn check_signature(&self, order: &VerifiedOrder) -> Result<(), SafetyError> {
+        // Fail closed: with nothing to check against, nothing is trusted
+        if self.trusted_keys.is_empty() {
+            warn!("No trusted C=0 keys configured; refusing order");
+            return Err(SafetyError::NoTrustedKeys);
+        }
+
+        let signature = order.signature.as_ref().ok_or(SafetyError::MissingSignature)?;
+        let Some(trusted) = self.trusted_keys.iter()
+            .find(|trusted| trusted.key.as_bytes().as_slice() == signature.verifying_key.as_slice())
+        else {
+            warn!("Order signed by untrusted key {}", hex::encode(&signature.verifying_key));
+            return Err(SafetyError::UntrustedKey);
+        };
+
+        let now = Utc::now();
+        match trusted.status(now) {
+            KeyStatus::Active => {}
+            KeyStatus::Retired => {
+                warn!("Order signed by retired key {}", trusted.key_id);
+                return Err(SafetyError::RetiredKey { key_id: trusted.key_id.clone() });
+            }
+            KeyStatus::Pending => {
+                warn!("Order signed by key {} before its activation", trusted.key_id);
+                return Err(SafetyError::KeyNotYetActive { key_id: trusted.key_id.clone() });
+            }
+        }
+
+        signature.verify(order, now, self.clock_skew)?;
+
+        // Only an authentic signature may advance the nonce
+        let mut last_nonce = self.last_nonce.lock().unwrap_or_else(|e| e.into_inner());
+        let last = last_nonce.entry(trusted.key.to_bytes()).or_insert(0);
+        if signature.nonce <= *last {
+            warn!("Replayed C=0 signature: nonce {} <= last seen {}", signature.nonce, last);
+            return Err(SafetyError::ReplayedNonce { nonce: signature.nonce, last_seen: *last });
+        }
+        *last = signature.nonce;
+        Ok(())
+    }
+
+    fn check_order_size(&self, signal: &axiom_core::TradeSignal) -> Result<(), SafetyError> {
+        let max_size = self.limits().get(&signal.symbol)?.max_order;
 
         if signal.quantity > max_size {
             warn!("Order size {} exceeds maximum {}", signal.quantity, max_size);
             return Err(SafetyError::OrderSizeExceeded {
-                size: signal.quantity,
-                max: max_size,
+                size: signal.quantity.get(),
+                max: max_size.get(),
             });
         }
 
-        if signal.quantity <= Decimal::ZERO {
+        if signal.quantity.is_zero() {
             return Err(SafetyError::InvalidQuantity);
         }
 
@@ -50,27 +208,220 @@ impl SafetyChecker {
 
     fn check_price(signal: &axiom_core::TradeSignal) -> Result<(), SafetyError> {
         if let Some(price) = signal.limit_price {
-            if price <= Decimal::ZERO {
+            if price.is_zero() {
                 return Err(SafetyError::InvalidPrice);
             }
         }
 
         Ok(())
     }
+
+    /// Tick, lot step, size range and minimum notional, when the venue's
+    /// rules for the symbol are known
+    fn check_instrument(&self, signal: &axiom_core::TradeSignal) -> Result<(), SafetyError> {
+        let Some(instrument) = self.instruments.get(&signal.venue, &signal.symbol) else {
+            return Ok(());
+        };
+        // A market order's limit_price is only its expected price
+        let resting = match signal.order_type {
+            OrderType::Limit => signal.limit_price,
+            OrderType::StopLimit { limit, .. } => Some(limit),
+            _ => None,
+        };
+        resting.map_or(Ok(()), |price| instrument.check_price(price, signal.side))
+            .and_then(|()| instrument.check_quantity(signal.quantity, signal.limit_price.or(resting)))
+            .map_err(|e| {
+                warn!("Order for {} on {} breaks the venue's rules: {}", signal.symbol.0, signal.venue, e);
+                SafetyError::Instrument(e)
+            })
+    }
+}
+
+impl Default for SafetyChecker {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
-#[derive(Debug, thiserror::Error)]
+#[derive(Debug, Serialize, thiserror::Error)]
+#[serde(tag = "code", content = "details")]
 pub enum SafetyError {
+    #[error("Order carries no C=0 signature")]
+    #[serde(rename = "SAFETY_MISSING_SIGNATURE")]
+    MissingSignature,
+
+    #[error("No trusted C=0 keys configured")]
+    #[serde(rename = "SAFETY_NO_TRUSTED_KEYS")]
+    NoTrustedKeys,
+
+    #[error("C=0 signature is from an untrusted key")]
+    #[serde(rename = "SAFETY_UNTRUSTED_KEY")]
+    UntrustedKey,
+
+    #[error("C=0 signature is from retired key {key_id}")]
+    #[serde(rename = "SAFETY_RETIRED_KEY")]
+    RetiredKey { key_id: String },
+
+    #[error("C=0 signature is from key {key_id}, which is not active yet")]
+    #[serde(rename = "SAFETY_KEY_NOT_YET_ACTIVE")]
+    KeyNotYetActive { key_id: String },
+
+    #[error("Invalid C=0 signature: {0}")]
+    #[serde(rename = "SAFETY_INVALID_SIGNATURE")]
+    InvalidSignature(#[from] SignatureError),
+
+    #[error("C=0 signature nonce {nonce} already used (last seen {last_seen})")]
+    #[serde(rename = "SAFETY_REPLAYED_NONCE")]
+    ReplayedNonce { nonce: u64, last_seen: u64 },
+
+    #[error("Order would trade against our own order {order_id} at {price}")]
+    #[serde(rename = "SAFETY_SELF_TRADE")]
+    SelfTrade { order_id: String, price: Decimal },
+
+    #[error("Insufficient liquidity: {available} of {needed} fillable ({gross} before our own orders)")]
+    #[serde(rename = "SAFETY_INSUFFICIENT_LIQUIDITY")]
+    InsufficientLiquidity { needed: Decimal, available: Decimal, gross: Decimal },
+
+    #[error("Depth arithmetic failed: {0}")]
+    #[serde(rename = "SAFETY_ARITHMETIC")]
+    Arithmetic(#[from] UnitError),
+
     #[error("Order size exceeded: {size} > {max}")]
+    #[serde(rename = "SAFETY_ORDER_SIZE")]
     OrderSizeExceeded { size: Decimal, max: Decimal },
     
     #[error("Invalid quantity (must be > 0)")]
+    #[serde(rename = "SAFETY_INVALID_QUANTITY")]
+    
     InvalidQuantity,
     
     #[error("Invalid price (must be > 0)")]
+    #[serde(rename = "SAFETY_INVALID_PRICE")]
+    
     InvalidPrice,
     
-    #[error("Unsupported symbol")]
-    UnsupportedSymbol,
+    #[error("Unsupported symbol: {0}")]
+    #[serde(rename = "SAFETY_UNSUPPORTED_SYMBOL")]
+    
+    UnsupportedSymbol(#[from] LimitsError),
+
+    #[error("Venue rules: {0}")]
+    #[serde(rename = "SAFETY_INSTRUMENT")]
+    Instrument(#[from] InstrumentError),
+}
+
+impl ErrorCode for SafetyError {
+    fn code(&self) -> &'static str {
+        match self {
+            SafetyError::MissingSignature => "SAFETY_MISSING_SIGNATURE",
+            SafetyError::NoTrustedKeys => "SAFETY_NO_TRUSTED_KEYS",
+            SafetyError::UntrustedKey => "SAFETY_UNTRUSTED_KEY",
+            SafetyError::RetiredKey { .. } => "SAFETY_RETIRED_KEY",
+            SafetyError::KeyNotYetActive { .. } => "SAFETY_KEY_NOT_YET_ACTIVE",
+            SafetyError::InvalidSignature(_) => "SAFETY_INVALID_SIGNATURE",
+            SafetyError::ReplayedNonce { .. } => "SAFETY_REPLAYED_NONCE",
+            SafetyError::SelfTrade { .. } => "SAFETY_SELF_TRADE",
+            SafetyError::InsufficientLiquidity { .. } => "SAFETY_INSUFFICIENT_LIQUIDITY",
+            SafetyError::Arithmetic(_) => "SAFETY_ARITHMETIC",
+            SafetyError::OrderSizeExceeded { .. } => "SAFETY_ORDER_SIZE",
+            SafetyError::InvalidQuantity => "SAFETY_INVALID_QUANTITY",
+            SafetyError::InvalidPrice => "SAFETY_INVALID_PRICE",
+            SafetyError::UnsupportedSymbol(_) => "SAFETY_UNSUPPORTED_SYMBOL",
+            SafetyError::Instrument(_) => "SAFETY_INSTRUMENT",
+        }
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axiom_core::{CZeroKey, Price, Proof, Quantity, Symbol, TradeSignal, Venue};
+    use rust_decimal::Decimal;
+
+    fn order() -> VerifiedOrder {
+        let signal = TradeSignal::builder()
+            .with_symbol(Symbol("BTC/USD".to_string()))
+            .with_venue(Venue::Binance)
+            .with_side(Side::Buy)
+            .with_order_type(OrderType::Limit)
+            .with_quantity(Quantity::new(Decimal::new(25, 2)).unwrap())
+            .with_limit_price(Price::new(Decimal::new(6500050, 2)).unwrap())
+            .with_scores(Decimal::new(8, 1), Decimal::new(3, 0))
+            .build(&LimitsRegistry::builtin())
+            .unwrap();
+        let proof = Proof {
+            satisfiable: true,
+            model: HashMap::new(),
+            axioms_satisfied: vec!["PositionLimit".to_string()],
+            solve_us: 0,
+            cached: false,
+        };
+        VerifiedOrder {
+            proof_signature: axiom_core::proof_signature(&signal, &proof),
+            signal,
+            proof,
+            verified_at: Utc::now(),
+            signature: None,
+        }
+    }
+
+    /// `order()` signed by `key`, valid for a minute
+    fn signed(key: &CZeroKey) -> VerifiedOrder {
+        let mut order = order();
+        order.signature = Some(key.sign(&order, Duration::minutes(1)).unwrap());
+        order
+    }
+
+    #[test]
+    fn no_trusted_keys_refuses_even_a_signed_order() {
+        let key = CZeroKey::generate();
+        let safety = SafetyChecker::new();
+
+        assert!(matches!(safety.check_signature(&signed(&key)), Err(SafetyError::NoTrustedKeys)));
+        assert!(matches!(safety.check_signature(&order()), Err(SafetyError::NoTrustedKeys)));
+    }
+
+    #[test]
+    fn trusted_key_signature_is_accepted() {
+        let key = CZeroKey::generate();
+        let safety = SafetyChecker::new().with_trusted_keys([TrustedKey::new(key.verifying_key())]);
+
+        safety.check_signature(&signed(&key)).unwrap();
+        assert!(matches!(safety.check_signature(&order()), Err(SafetyError::MissingSignature)));
+    }
+
+    #[test]
+    fn replayed_signature_is_refused() {
+        let key = CZeroKey::generate();
+        let safety = SafetyChecker::new().with_trusted_keys([TrustedKey::new(key.verifying_key())]);
+        let first = signed(&key);
+        let nonce = first.signature.as_ref().unwrap().nonce;
+
+        safety.check_signature(&first).unwrap();
+        match safety.check_signature(&first) {
+            Err(SafetyError::ReplayedNonce { nonce: replayed, last_seen }) => assert_eq!((replayed, last_seen), (nonce, nonce)),
+            other => panic!("expected a replay, got {:?}", other),
+        }
+        // A fresh signature over the same order moves the nonce on
+        safety.check_signature(&signed(&key)).unwrap();
+    }
+
+    #[test]
+    fn expired_signature_is_refused_past_the_clock_skew() {
+        let key = CZeroKey::generate();
+        let safety = SafetyChecker::new()
+            .with_trusted_keys([TrustedKey::new(key.verifying_key())])
+            .with_clock_skew(Duration::seconds(1));
+        let mut order = order();
+
+        // Expired, but within the skew allowance
+        order.signature = Some(key.sign(&order, Duration::milliseconds(-200)).unwrap());
+        safety.check_signature(&order).unwrap();
+
+        order.signature = Some(key.sign(&order, Duration::seconds(-10)).unwrap());
+        assert!(matches!(
+            safety.check_signature(&order),
+            Err(SafetyError::InvalidSignature(SignatureError::Expired { .. })),
+        ));
+    }
+}
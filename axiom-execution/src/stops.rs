@@ -0,0 +1,198 @@
+//! Stop Orders: Venue Translation and Trigger Emulation
+//!
+//! Conditional orders go to the venue natively when it supports them.
+//! Otherwise (and always in paper trading) they are held here and triggered
+//! against the traded-price stream. Triggering depends only on the sequence of
+//! trade prices, never on wall-clock polling, so a replayed tick stream
+//! reproduces every trigger exactly.
+
+use axiom_core::{OrderType, Price, Side, Symbol, TrailOffset, Venue, VerifiedOrder};
+use rust_decimal::Decimal;
+use tracing::error;
+
+/// A conditional order in a venue's native vocabulary
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NativeStopOrder {
+    /// Venue order type name
+    pub order_type: &'static str,
+    /// Venue-specific parameters (name, value)
+    pub params: Vec<(&'static str, String)>,
+}
+
+/// Translate a conditional order to the venue's native parameters
+///
+/// Returns None when the venue cannot express it, in which case the executor
+/// emulates the trigger.
+pub fn native_stop(venue: &Venue, side: Side, order_type: &OrderType) -> Option<NativeStopOrder> {
+    let capabilities = venue.capabilities();
+    let rising = side == Side::Buy;
+
+    match (venue, order_type) {
+        (_, OrderType::StopLimit { .. } | OrderType::StopMarket { .. }) if !capabilities.stop_orders => None,
+        (_, OrderType::TrailingStop { .. }) if !capabilities.trailing_stop => None,
+
+        (Venue::Binance, OrderType::StopLimit { trigger, limit }) => Some(NativeStopOrder {
+            order_type: "STOP",
+            params: vec![("stopPrice", trigger.to_string()), ("price", limit.to_string())],
+        }),
+        (Venue::Binance, OrderType::StopMarket { trigger }) => Some(NativeStopOrder {
+            order_type: "STOP_MARKET",
+            params: vec![("stopPrice", trigger.to_string())],
+        }),
+        // Binance only accepts a percentage callback between 0.1% and 10%
+        (Venue::Binance, OrderType::TrailingStop { offset: TrailOffset::Percent(percent) })
+            if *percent >= Decimal::new(1, 1) && *percent <= Decimal::TEN =>
+        {
+            Some(NativeStopOrder {
+                order_type: "TRAILING_STOP_MARKET",
+                params: vec![("callbackRate", percent.round_dp(1).to_string())],
+            })
+        }
+
+        (Venue::Bybit, OrderType::StopLimit { trigger, limit }) => Some(NativeStopOrder {
+            order_type: "Limit",
+            params: vec![
+                ("triggerPrice", trigger.to_string()),
+                ("price", limit.to_string()),
+                ("triggerDirection", if rising { "1" } else { "2" }.to_string()),
+            ],
+        }),
+        (Venue::Bybit, OrderType::StopMarket { trigger }) => Some(NativeStopOrder {
+            order_type: "Market",
+            params: vec![
+                ("triggerPrice", trigger.to_string()),
+                ("triggerDirection", if rising { "1" } else { "2" }.to_string()),
+            ],
+        }),
+
+        (Venue::Hyperliquid, OrderType::StopLimit { trigger, limit }) => Some(NativeStopOrder {
+            order_type: "trigger",
+            params: vec![
+                ("triggerPx", trigger.to_string()),
+                ("limitPx", limit.to_string()),
+                ("isMarket", "false".to_string()),
+                ("tpsl", "sl".to_string()),
+            ],
+        }),
+        (Venue::Hyperliquid, OrderType::StopMarket { trigger }) => Some(NativeStopOrder {
+            order_type: "trigger",
+            params: vec![
+                ("triggerPx", trigger.to_string()),
+                ("isMarket", "true".to_string()),
+                ("tpsl", "sl".to_string()),
+            ],
+        }),
+
+        _ => None,
+    }
+}
+
+/// An emulated stop whose condition was met
+#[derive(Debug, Clone)]
+pub struct TriggeredStop {
+    pub order: VerifiedOrder,
+    /// Order type to submit now (Limit or Market)
+    pub execute_as: OrderType,
+    /// Limit price for `execute_as == Limit`
+    pub limit_price: Option<Price>,
+    /// Trade price that fired the trigger
+    pub trigger_price: Price,
+}
+
+struct ArmedStop {
+    order: VerifiedOrder,
+    /// Most favorable trade since arming (trailing stops only)
+    extreme: Option<Price>,
+}
+
+/// Holds conditional orders and fires them from the trade stream
+#[derive(Default)]
+pub struct StopEmulator {
+    armed: Vec<ArmedStop>,
+}
+
+impl StopEmulator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Hold a conditional order until its trigger is met
+    pub fn arm(&mut self, order: VerifiedOrder) {
+        self.armed.push(ArmedStop { order, extreme: None });
+    }
+
+    /// Drop every armed stop for a market (e.g. on cancel-all)
+    pub fn cancel(&mut self, symbol: &Symbol, venue: &Venue) -> usize {
+        let before = self.armed.len();
+        self.armed.retain(|stop| stop.order.signal.symbol != *symbol || stop.order.signal.venue != *venue);
+        before - self.armed.len()
+    }
+
+    pub fn armed(&self) -> usize {
+        self.armed.len()
+    }
+
+    /// Every armed order, in arming order
+    pub fn orders(&self) -> Vec<VerifiedOrder> {
+        self.armed.iter().map(|stop| stop.order.clone()).collect()
+    }
+
+    /// Apply one traded price; returns the stops it fires, in arming order
+    pub fn on_trade(&mut self, symbol: &Symbol, venue: &Venue, price: Price) -> Vec<TriggeredStop> {
+        let mut triggered = Vec::new();
+        let mut remaining = Vec::with_capacity(self.armed.len());
+
+        for mut stop in std::mem::take(&mut self.armed) {
+            let signal = &stop.order.signal;
+            if signal.symbol != *symbol || signal.venue != *venue {
+                remaining.push(stop);
+                continue;
+            }
+
+            let side = signal.side;
+            let fired = match signal.order_type {
+                OrderType::StopLimit { trigger, limit } => crosses(side, price, trigger).then_some((OrderType::Limit, Some(limit))),
+                OrderType::StopMarket { trigger } => crosses(side, price, trigger).then_some((OrderType::Market, None)),
+                OrderType::TrailingStop { offset } => {
+                    // Ratchet the extreme first, so a new high (low) never triggers a sell (buy)
+                    let extreme = match (side, stop.extreme) {
+                        (_, None) => price,
+                        (Side::Sell, Some(high)) => high.max(price),
+                        (Side::Buy, Some(low)) => low.min(price),
+                    };
+                    stop.extreme = Some(extreme);
+                    match offset.stop_from(side, extreme) {
+                        Ok(stop_price) => crosses(side, price, stop_price).then_some((OrderType::Market, None)),
+                        Err(e) => {
+                            // Keep the stop armed; the next trade retries
+                            error!("Trailing stop for {} not evaluated: {}", symbol.0, e);
+                            None
+                        }
+                    }
+                }
+                _ => Some((signal.order_type, signal.limit_price)),
+            };
+
+            match fired {
+                Some((execute_as, limit_price)) => triggered.push(TriggeredStop {
+                    order: stop.order,
+                    execute_as,
+                    limit_price,
+                    trigger_price: price,
+                }),
+                None => remaining.push(stop),
+            }
+        }
+
+        self.armed = remaining;
+        triggered
+    }
+}
+
+/// Buy stops fire at or above the trigger; sell stops at or below
+fn crosses(side: Side, price: Price, trigger: Price) -> bool {
+    match side {
+        Side::Buy => price >= trigger,
+        Side::Sell => price <= trigger,
+    }
+}
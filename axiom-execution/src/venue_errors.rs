@@ -0,0 +1,292 @@
+//! Venue Errors: One Taxonomy for Every Exchange's Rejections
+//!
+//! Each venue reports failures in its own vocabulary (Binance's negative
+//! codes, Bybit's retCodes, Hyperliquid's bare messages). A client hands the
+//! raw error to `ExchangeError::classify`, which maps it through the venue's
+//! table into a shared `ExchangeErrorKind`, so retries, alerts and the
+//! executor's remediation hooks act on what went wrong rather than on text.
+//! Codes a table does not know fall back to message heuristics, then to
+//! `Unknown` with the native code kept.
+
+use axiom_core::{AccountId, ErrorCode, Symbol, Venue};
+use serde::Serialize;
+use std::time::Duration;
+
+/// An error as the venue returned it
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NativeError {
+    /// Venue error code ("-2010", "110007"); empty when the venue sends none
+    pub code: String,
+    pub message: String,
+    pub http_status: Option<u16>,
+    /// From a Retry-After header or the venue's own field
+    pub retry_after: Option<Duration>,
+}
+
+impl NativeError {
+    pub fn new(code: impl Into<String>, message: impl Into<String>) -> Self {
+        Self { code: code.into(), message: message.into(), ..Self::default() }
+    }
+
+    pub fn with_http_status(mut self, status: u16) -> Self {
+        self.http_status = Some(status);
+        self
+    }
+
+    pub fn with_retry_after(mut self, retry_after: Duration) -> Self {
+        self.retry_after = Some(retry_after);
+        self
+    }
+}
+
+/// What a venue error means, whatever venue sent it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ExchangeErrorKind {
+    /// Request or order rate exceeded; retry after the given delay if known
+    RateLimited { retry_after_ms: Option<u64> },
+    /// Not enough balance or margin for the order
+    InsufficientBalance,
+    /// The venue refused the order's parameters
+    InvalidOrder { reason: String },
+    /// The symbol is not trading (halted, suspended, delisting)
+    SymbolHalted,
+    /// Key, signature, permission or IP whitelist rejected
+    AuthFailure,
+    /// The venue is down for maintenance or shedding load
+    Maintenance,
+    /// Nothing in the venue's table or the heuristics matched
+    Unknown { code: String },
+}
+
+/// What the executor does about an error, beyond failing the order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Remediation {
+    /// Transient: the order may be retried (after the delay if known)
+    Backoff(Option<Duration>),
+    /// Stop sending to the venue until an operator resumes it
+    PauseVenue,
+    /// Stop trading the symbol on the venue
+    HaltSymbol,
+    /// Reconcile the account's balances and positions
+    Reconcile,
+    None,
+}
+
+impl ExchangeErrorKind {
+    /// Whether the same request may succeed if sent again later
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, ExchangeErrorKind::RateLimited { .. } | ExchangeErrorKind::Maintenance)
+    }
+
+    pub fn remediation(&self) -> Remediation {
+        match self {
+            ExchangeErrorKind::RateLimited { retry_after_ms } => Remediation::Backoff(retry_after_ms.map(Duration::from_millis)),
+            ExchangeErrorKind::Maintenance => Remediation::Backoff(None),
+            ExchangeErrorKind::AuthFailure => Remediation::PauseVenue,
+            ExchangeErrorKind::SymbolHalted => Remediation::HaltSymbol,
+            ExchangeErrorKind::InsufficientBalance => Remediation::Reconcile,
+            ExchangeErrorKind::InvalidOrder { .. } | ExchangeErrorKind::Unknown { .. } => Remediation::None,
+        }
+    }
+
+    /// Stable name for metrics and alert context
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExchangeErrorKind::RateLimited { .. } => "rate_limited",
+            ExchangeErrorKind::InsufficientBalance => "insufficient_balance",
+            ExchangeErrorKind::InvalidOrder { .. } => "invalid_order",
+            ExchangeErrorKind::SymbolHalted => "symbol_halted",
+            ExchangeErrorKind::AuthFailure => "auth_failure",
+            ExchangeErrorKind::Maintenance => "maintenance",
+            ExchangeErrorKind::Unknown { .. } => "unknown",
+        }
+    }
+}
+
+/// A classified venue error
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, thiserror::Error)]
+#[error("{venue} rejected ({code}): {message} [{}]", .kind.name())]
+pub struct ExchangeError {
+    pub venue: Venue,
+    pub code: String,
+    pub message: String,
+    pub kind: ExchangeErrorKind,
+}
+
+impl ExchangeError {
+    /// Map `native` through `venue`'s table
+    pub fn classify(venue: &Venue, native: NativeError) -> Self {
+        let kind = classify(venue, &native);
+        Self { venue: venue.clone(), code: native.code, message: native.message, kind }
+    }
+}
+
+impl ErrorCode for ExchangeError {
+    fn code(&self) -> &'static str {
+        match self.kind {
+            ExchangeErrorKind::RateLimited { .. } => "VENUE_RATE_LIMITED",
+            ExchangeErrorKind::InsufficientBalance => "VENUE_INSUFFICIENT_BALANCE",
+            ExchangeErrorKind::InvalidOrder { .. } => "VENUE_INVALID_ORDER",
+            ExchangeErrorKind::SymbolHalted => "VENUE_SYMBOL_HALTED",
+            ExchangeErrorKind::AuthFailure => "VENUE_AUTH_FAILURE",
+            ExchangeErrorKind::Maintenance => "VENUE_MAINTENANCE",
+            ExchangeErrorKind::Unknown { .. } => "VENUE_UNKNOWN",
+        }
+    }
+}
+
+/// Called by the executor when a venue error needs more than a failed order
+///
+/// The executor pauses the venue itself on `AuthFailure`; these hooks let
+/// the rest of the process follow (alerts, feeds, signal generation,
+/// reconciliation). They run on the submitting task, so they must not block.
+pub trait RemediationHooks: Send + Sync {
+    /// The venue rejected our credentials and is paused
+    fn venue_paused(&self, venue: &Venue, error: &ExchangeError);
+
+    /// `symbol` is not trading on `venue`
+    fn symbol_halted(&self, symbol: &Symbol, venue: &Venue, error: &ExchangeError);
+
+    /// `account` lacked balance on `venue` for an order
+    fn reconcile(&self, account: &AccountId, venue: &Venue, error: &ExchangeError);
+}
+
+/// Class of a table entry; `InvalidOrder` entries carry their reason
+#[derive(Debug, Clone, Copy)]
+enum Class {
+    RateLimited,
+    InsufficientBalance,
+    InvalidOrder(&'static str),
+    SymbolHalted,
+    AuthFailure,
+    Maintenance,
+}
+
+/// Binance spot and USDⓈ-M futures error codes
+const BINANCE: &[(&str, Class)] = &[
+    ("-1001", Class::Maintenance),
+    ("-1002", Class::AuthFailure),
+    ("-1003", Class::RateLimited),
+    ("-1008", Class::RateLimited),
+    ("-1013", Class::InvalidOrder("filter failure")),
+    ("-1015", Class::RateLimited),
+    ("-1016", Class::Maintenance),
+    ("-1021", Class::InvalidOrder("timestamp outside recvWindow")),
+    ("-1022", Class::AuthFailure),
+    ("-1100", Class::InvalidOrder("illegal characters in a parameter")),
+    ("-1102", Class::InvalidOrder("mandatory parameter missing or malformed")),
+    ("-1111", Class::InvalidOrder("precision over the maximum for the asset")),
+    ("-1121", Class::InvalidOrder("invalid symbol")),
+    ("-2011", Class::InvalidOrder("cancel rejected")),
+    ("-2013", Class::InvalidOrder("order does not exist")),
+    ("-2014", Class::AuthFailure),
+    ("-2015", Class::AuthFailure),
+    ("-2018", Class::InsufficientBalance),
+    ("-2019", Class::InsufficientBalance),
+    ("-4164", Class::InvalidOrder("notional below the minimum")),
+];
+
+/// Binance -2010 (new order rejected) says why only in its message
+const BINANCE_REJECTIONS: &[(&str, Class)] = &[
+    ("insufficient balance", Class::InsufficientBalance),
+    ("market is closed", Class::SymbolHalted),
+    ("trading is disabled", Class::SymbolHalted),
+    ("this action is disabled on this account", Class::AuthFailure),
+];
+
+/// Bybit v5 retCodes
+const BYBIT: &[(&str, Class)] = &[
+    ("10001", Class::InvalidOrder("parameter error")),
+    ("10002", Class::InvalidOrder("timestamp outside recv_window")),
+    ("10003", Class::AuthFailure),
+    ("10004", Class::AuthFailure),
+    ("10005", Class::AuthFailure),
+    ("10006", Class::RateLimited),
+    ("10007", Class::AuthFailure),
+    ("10010", Class::AuthFailure),
+    ("10016", Class::Maintenance),
+    ("10018", Class::RateLimited),
+    ("110001", Class::InvalidOrder("order does not exist")),
+    ("110003", Class::InvalidOrder("price outside the permitted range")),
+    ("110004", Class::InsufficientBalance),
+    ("110007", Class::InsufficientBalance),
+    ("110012", Class::InsufficientBalance),
+    ("110017", Class::InvalidOrder("reduce-only order would increase the position")),
+    ("110023", Class::SymbolHalted),
+    ("110094", Class::InvalidOrder("order value below the minimum")),
+    ("170121", Class::InvalidOrder("invalid symbol")),
+    ("170131", Class::InsufficientBalance),
+    ("170213", Class::InvalidOrder("order does not exist")),
+];
+
+/// Hyperliquid sends messages without codes; matched by (lowercase) fragment
+const HYPERLIQUID: &[(&str, Class)] = &[
+    ("insufficient margin", Class::InsufficientBalance),
+    ("insufficient spot balance", Class::InsufficientBalance),
+    ("minimum value of", Class::InvalidOrder("order value below the minimum")),
+    ("divisible by tick size", Class::InvalidOrder("price not a multiple of the tick size")),
+    ("invalid price", Class::InvalidOrder("invalid price")),
+    ("invalid size", Class::InvalidOrder("invalid size")),
+    ("post only order would have immediately matched", Class::InvalidOrder("post-only order would cross")),
+    ("reduce only order would increase position", Class::InvalidOrder("reduce-only order would increase the position")),
+    ("could not immediately match", Class::InvalidOrder("IOC order found no liquidity")),
+    ("away from the reference price", Class::InvalidOrder("price too far from the reference")),
+    ("too many cumulative requests", Class::RateLimited),
+    ("api wallet", Class::AuthFailure),
+    ("trading is halted", Class::SymbolHalted),
+];
+
+/// Any venue: matched by (lowercase) fragment when its own table has no entry
+const GENERIC: &[(&str, Class)] = &[
+    ("too many requests", Class::RateLimited),
+    ("rate limit", Class::RateLimited),
+    ("insufficient", Class::InsufficientBalance),
+    ("signature", Class::AuthFailure),
+    ("api key", Class::AuthFailure),
+    ("api-key", Class::AuthFailure),
+    ("unauthorized", Class::AuthFailure),
+    ("maintenance", Class::Maintenance),
+    ("halted", Class::SymbolHalted),
+    ("suspended", Class::SymbolHalted),
+    ("delisted", Class::SymbolHalted),
+    ("market is closed", Class::SymbolHalted),
+];
+
+fn classify(venue: &Venue, native: &NativeError) -> ExchangeErrorKind {
+    let message = native.message.to_lowercase();
+    let by_code = |table: &[(&str, Class)]| table.iter().find(|(code, _)| *code == native.code).map(|(_, class)| *class);
+    let by_message = |table: &[(&str, Class)]| table.iter().find(|(fragment, _)| message.contains(fragment)).map(|(_, class)| *class);
+
+    let class = match venue {
+        Venue::Binance if native.code == "-2010" => {
+            Some(by_message(BINANCE_REJECTIONS).unwrap_or(Class::InvalidOrder("new order rejected")))
+        }
+        Venue::Binance => by_code(BINANCE),
+        Venue::Bybit => by_code(BYBIT),
+        Venue::Hyperliquid => by_message(HYPERLIQUID),
+        Venue::Paper | Venue::Custom(_) => None,
+    };
+    let class = class
+        .or(match native.http_status {
+            Some(418 | 429) => Some(Class::RateLimited),
+            Some(401 | 403) => Some(Class::AuthFailure),
+            Some(503) => Some(Class::Maintenance),
+            _ => None,
+        })
+        .or_else(|| by_message(GENERIC));
+
+    match class {
+        Some(Class::RateLimited) => ExchangeErrorKind::RateLimited {
+            retry_after_ms: native.retry_after.map(|delay| delay.as_millis() as u64),
+        },
+        Some(Class::InsufficientBalance) => ExchangeErrorKind::InsufficientBalance,
+        Some(Class::InvalidOrder(reason)) => ExchangeErrorKind::InvalidOrder { reason: reason.to_string() },
+        Some(Class::SymbolHalted) => ExchangeErrorKind::SymbolHalted,
+        Some(Class::AuthFailure) => ExchangeErrorKind::AuthFailure,
+        Some(Class::Maintenance) => ExchangeErrorKind::Maintenance,
+        None => ExchangeErrorKind::Unknown {
+            code: if native.code.is_empty() { native.message.clone() } else { native.code.clone() },
+        },
+    }
+}
@@ -0,0 +1,39 @@
+//! Minimal live stream client
+//!
+//! Usage: AXIOM_LIVE_TOKEN=... cargo run -p axiom-oracle --example live_client -- [ws://127.0.0.1:9101] [event_type ...]
+//!
+//! Subscribes to health and portfolio frames plus the given event types
+//! (all events if none are given) and prints every frame received.
+
+use futures::{SinkExt, StreamExt};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::Message;
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let mut args = std::env::args().skip(1);
+    let url = args.next().unwrap_or_else(|| "ws://127.0.0.1:9101".to_string());
+    let event_types: Vec<String> = args.collect();
+    let token = std::env::var("AXIOM_LIVE_TOKEN")?;
+
+    let mut request = url.into_client_request()?;
+    request.headers_mut().insert("Authorization", format!("Bearer {}", token).parse()?);
+    let (mut ws, _) = tokio_tungstenite::connect_async(request).await?;
+
+    let subscribe = serde_json::json!({
+        "op": "subscribe",
+        "channels": ["health", "events", "portfolio"],
+        "event_types": event_types,
+    });
+    ws.send(Message::Text(subscribe.to_string())).await?;
+
+    while let Some(message) = ws.next().await {
+        match message? {
+            Message::Text(text) => println!("{}", text),
+            Message::Close(_) => break,
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
@@ -2,41 +2,431 @@
 //!
 //! Detects anomalies and triggers alerts when system deviates from expected behavior.
 
-use axiom_core::{SystemHealth, CircuitBreakerState};
+use crate::monitoring::FunnelShift;
+use axiom_core::{SystemHealth, CircuitBreakerState, LatencyStage, ErrorCode, EventListener, SystemEvent};
 use axiom_core::constants::*;
-use tracing::{warn, error};
+use axiom_core::events::{RegimeChanged, VerificationBacklog};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+use tracing::{info, warn, error};
+
+/// Alert severity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum AlertSeverity {
+    #[serde(alias = "info")]
+    Info,
+    #[serde(alias = "warning")]
+    Warning,
+    #[serde(alias = "critical")]
+    Critical,
+}
+
+/// A routed alert
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Alert {
+    pub severity: AlertSeverity,
+    /// Component or check that raised the alert
+    pub source: String,
+    pub message: String,
+    pub timestamp: DateTime<Utc>,
+    /// Structured context (symbol, venue, values, ...)
+    #[serde(default)]
+    pub context: BTreeMap<String, String>,
+    /// Incident tracking this alert, if any
+    #[serde(default)]
+    pub incident_id: Option<u64>,
+}
+
+impl Alert {
+    pub fn new(severity: AlertSeverity, source: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity,
+            source: source.into(),
+            message: message.into(),
+            timestamp: Utc::now(),
+            context: BTreeMap::new(),
+            incident_id: None,
+        }
+    }
+
+    pub fn with_context(mut self, key: &str, value: impl ToString) -> Self {
+        self.context.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    /// Alert raised for an error, tagged with its stable code
+    pub fn for_error(severity: AlertSeverity, source: impl Into<String>, error: &(impl ErrorCode + std::fmt::Display)) -> Self {
+        Self::new(severity, source, error.to_string()).with_context("code", error.code())
+    }
+
+    /// Dedup key for alerts raised from errors: one live incident per source and error code
+    pub fn error_key(&self) -> Option<String> {
+        self.context.get("code").map(|code| format!("{}:{}", self.source, code))
+    }
+}
+
+/// Destination for alerts (log, chat, email, pager)
+pub trait AlertSink: Send + Sync {
+    fn send(&self, alert: &Alert);
+}
+
+/// Alert sink that writes to the tracing log
+pub struct LogAlertSink;
+
+impl AlertSink for LogAlertSink {
+    fn send(&self, alert: &Alert) {
+        match alert.severity {
+            AlertSeverity::Info => info!("[{}] {}", alert.source, alert.message),
+            AlertSeverity::Warning => warn!("[{}] {}", alert.source, alert.message),
+            AlertSeverity::Critical => error!("[{}] {}", alert.source, alert.message),
+        }
+    }
+}
+
+/// Warning and critical levels for one metric (alert when the value exceeds a level)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Threshold<T> {
+    pub warning: T,
+    pub critical: T,
+}
+
+impl<T: PartialOrd + Copy> Threshold<T> {
+    pub fn new(warning: T, critical: T) -> Self {
+        Self { warning, critical }
+    }
+
+    /// Severity for an observed value, if it breaches either level
+    pub fn classify(&self, value: T) -> Option<AlertSeverity> {
+        if value > self.critical {
+            Some(AlertSeverity::Critical)
+        } else if value > self.warning {
+            Some(AlertSeverity::Warning)
+        } else {
+            None
+        }
+    }
+
+    fn is_ordered(&self) -> bool {
+        self.warning <= self.critical
+    }
+}
+
+/// Per-deployment alert thresholds
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlertThresholds {
+    pub consistency_error: Threshold<Decimal>,
+    pub entropy: Threshold<Decimal>,
+    pub hallucination_rate: Threshold<Decimal>,
+    /// Overall p99 latency (milliseconds)
+    pub latency_p99_ms: Threshold<u64>,
+    /// Per-stage p99 latency (microseconds); stages not listed use their default budget
+    #[serde(default)]
+    pub stage_latency_p99_us: BTreeMap<LatencyStage, Threshold<u64>>,
+    /// Seconds without data on a stream
+    pub data_staleness_secs: Threshold<u64>,
+    /// Execution slippage versus intended price (basis points)
+    pub slippage_bps: Threshold<Decimal>,
+}
+
+impl AlertThresholds {
+    /// Reject thresholds that are negative or whose warning exceeds critical
+    pub fn validate(&self) -> Result<(), ThresholdError> {
+        let decimals = [
+            ("consistency_error", &self.consistency_error),
+            ("entropy", &self.entropy),
+            ("hallucination_rate", &self.hallucination_rate),
+            ("slippage_bps", &self.slippage_bps),
+        ];
+        for (name, threshold) in decimals {
+            if threshold.warning < Decimal::ZERO {
+                return Err(ThresholdError::Negative(name.to_string()));
+            }
+            if !threshold.is_ordered() {
+                return Err(ThresholdError::WarningAboveCritical(name.to_string()));
+            }
+        }
+
+        let integers = [
+            ("latency_p99_ms".to_string(), &self.latency_p99_ms),
+            ("data_staleness_secs".to_string(), &self.data_staleness_secs),
+        ];
+        let stages = self.stage_latency_p99_us.iter()
+            .map(|(stage, threshold)| (format!("stage_latency_p99_us.{}", stage), threshold));
+        for (name, threshold) in integers.into_iter().chain(stages) {
+            if !threshold.is_ordered() {
+                return Err(ThresholdError::WarningAboveCritical(name));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Threshold for a pipeline stage (configured, or derived from its default budget)
+    pub fn stage_latency(&self, stage: LatencyStage) -> Threshold<u64> {
+        self.stage_latency_p99_us.get(&stage).copied().unwrap_or_else(|| {
+            let budget = stage.default_p99_budget_us();
+            Threshold::new(budget, budget * 5)
+        })
+    }
+}
+
+impl Default for AlertThresholds {
+    fn default() -> Self {
+        Self {
+            consistency_error: Threshold::new(MAX_CONSISTENCY_ERROR, MAX_CONSISTENCY_ERROR),
+            entropy: Threshold::new(DELTA_U_MAX_SQ, DELTA_U_MAX_SQ * dec!(100)),
+            hallucination_rate: Threshold::new(MAX_HALLUCINATION_RATE, MAX_HALLUCINATION_RATE * dec!(10)),
+            latency_p99_ms: Threshold::new(100, 500),
+            stage_latency_p99_us: BTreeMap::new(),
+            data_staleness_secs: Threshold::new(30, 120),
+            slippage_bps: Threshold::new(MAX_SLIPPAGE_TOLERANCE * dec!(10000), MAX_SLIPPAGE_TOLERANCE * dec!(50000)),
+        }
+    }
+}
+
+/// Invalid alert threshold configuration
+#[derive(Debug, thiserror::Error)]
+pub enum ThresholdError {
+    #[error("Threshold {0} must not be negative")]
+    Negative(String),
+
+    #[error("Threshold {0} has a warning level above its critical level")]
+    WarningAboveCritical(String),
+}
 
 /// Alert manager
-pub struct AlertManager;
+///
+/// Thresholds live behind a shared lock so a config reload takes effect on
+/// the next check cycle.
+pub struct AlertManager {
+    thresholds: Arc<RwLock<AlertThresholds>>,
+    sink: Arc<dyn AlertSink>,
+}
 
 impl AlertManager {
+    pub fn new(thresholds: AlertThresholds, sink: Arc<dyn AlertSink>) -> Result<Self, ThresholdError> {
+        thresholds.validate()?;
+        Ok(Self {
+            thresholds: Arc::new(RwLock::new(thresholds)),
+            sink,
+        })
+    }
+
+    /// Replace the thresholds (validated first; the old set stays on error)
+    pub fn update_thresholds(&self, thresholds: AlertThresholds) -> Result<(), ThresholdError> {
+        thresholds.validate()?;
+        *self.thresholds.write().unwrap_or_else(|e| e.into_inner()) = thresholds;
+        info!("Alert thresholds reloaded");
+        Ok(())
+    }
+
+    /// Current thresholds
+    pub fn thresholds(&self) -> AlertThresholds {
+        self.thresholds.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
     /// Check for anomalies and trigger alerts
-    pub fn check_anomalies(&self, health: &SystemHealth) {
+    pub fn check_anomalies(&self, health: &SystemHealth) -> Vec<Alert> {
+        let thresholds = self.thresholds();
+        let mut alerts = Vec::new();
+
         // Check consistency error
-        if health.consistency_error.value > MAX_CONSISTENCY_ERROR {
-            error!("CONTRADICTION DETECTED: Consistency error = {}", health.consistency_error.value);
+        if let Some(severity) = thresholds.consistency_error.classify(health.consistency_error.value) {
+            alerts.push(Alert::new(severity, "consistency",
+                format!("CONTRADICTION DETECTED: Consistency error = {}", health.consistency_error.value)));
         }
 
         // Check entropy
-        if health.entropy_count.value > DELTA_U_MAX_SQ {
-            warn!("HIGH ENTROPY: Market disorder detected. Value: {}", health.entropy_count.value);
+        if let Some(severity) = thresholds.entropy.classify(health.entropy_count.value) {
+            alerts.push(Alert::new(severity, "entropy",
+                format!("HIGH ENTROPY: Market disorder detected. Value: {}", health.entropy_count.value)));
         }
 
         // Check circuit breaker
         if matches!(health.circuit_breaker, CircuitBreakerState::Tripped) {
-            error!("CIRCUIT BREAKER TRIPPED: System halted");
+            alerts.push(Alert::new(AlertSeverity::Critical, "circuit_breaker",
+                "CIRCUIT BREAKER TRIPPED: System halted"));
         }
 
         // Check hallucination rate
-        if health.hallucination_rate > MAX_HALLUCINATION_RATE {
-            warn!("HIGH HALLUCINATION RATE: {} (threshold: {})",
-                health.hallucination_rate, MAX_HALLUCINATION_RATE);
+        if let Some(severity) = thresholds.hallucination_rate.classify(health.hallucination_rate) {
+            alerts.push(Alert::new(severity, "hallucination",
+                format!("HIGH HALLUCINATION RATE: {} (warning: {}, critical: {})",
+                    health.hallucination_rate,
+                    thresholds.hallucination_rate.warning,
+                    thresholds.hallucination_rate.critical)));
         }
 
         // Check latency
-        if health.latency_p99 > 100 {
-            warn!("HIGH LATENCY: P99 = {}ms", health.latency_p99);
+        if let Some(severity) = thresholds.latency_p99_ms.classify(health.latency_p99) {
+            alerts.push(Alert::new(severity, "latency",
+                format!("HIGH LATENCY: P99 = {}ms", health.latency_p99)));
+        }
+
+        // Check per-stage latency
+        for entry in &health.latency_breakdown.stages {
+            let threshold = thresholds.stage_latency(entry.stage);
+            if let Some(severity) = threshold.classify(entry.p99_us) {
+                alerts.push(Alert::new(severity, "latency",
+                    format!("HIGH {} LATENCY on {}: P99 = {}us (warning: {}us, critical: {}us)",
+                        entry.stage, entry.venue, entry.p99_us, threshold.warning, threshold.critical)));
+            }
+        }
+
+        for alert in &alerts {
+            self.sink.send(alert);
+        }
+        alerts
+    }
+
+    /// Alert on a stream that has been silent for `silent_secs`
+    pub fn check_staleness(&self, stream: &str, silent_secs: u64) -> Option<Alert> {
+        let severity = self.thresholds().data_staleness_secs.classify(silent_secs)?;
+        let alert = Alert::new(severity, "data_staleness",
+            format!("STALE DATA: {} silent for {}s", stream, silent_secs));
+        self.sink.send(&alert);
+        Some(alert)
+    }
+
+    /// Alert on an execution whose slippage exceeded the thresholds
+    pub fn check_slippage(&self, symbol: &str, slippage_bps: Decimal) -> Option<Alert> {
+        let severity = self.thresholds().slippage_bps.classify(slippage_bps)?;
+        let alert = Alert::new(severity, "slippage",
+            format!("HIGH SLIPPAGE on {}: {} bps", symbol, slippage_bps));
+        self.sink.send(&alert);
+        Some(alert)
+    }
+
+    /// Alert on a portfolio aggregate whose running value disagreed with a
+    /// full recomputation (already corrected; the drift points at a bug)
+    pub fn check_portfolio_drift(&self, metric: &str, tracked: Decimal, recomputed: Decimal) -> Alert {
+        let alert = Alert::new(AlertSeverity::Critical, "portfolio_drift",
+            format!("PORTFOLIO DRIFT in {}: tracked {}, recomputed {}", metric, tracked, recomputed))
+            .with_context("metric", metric)
+            .with_context("tracked", tracked)
+            .with_context("recomputed", recomputed);
+        self.sink.send(&alert);
+        alert
+    }
+
+    /// Alert on `dropped` messages a full feed channel discarded since the
+    /// last check
+    pub fn check_dropped_messages(&self, stream: &str, dropped: u64) -> Option<Alert> {
+        if dropped == 0 {
+            return None;
+        }
+        let alert = Alert::new(AlertSeverity::Warning, "backpressure",
+            format!("BACKPRESSURE: {} {} messages dropped (consumer falling behind)", dropped, stream))
+            .with_context("stream", stream)
+            .with_context("dropped", dropped);
+        self.sink.send(&alert);
+        Some(alert)
+    }
+
+    /// Log a symbol's regime change: a warning when the new regime forbids
+    /// new positions, info otherwise
+    pub fn check_regime_change(&self, change: &RegimeChanged) -> Alert {
+        let severity = if change.to.allows_new_positions() { AlertSeverity::Info } else { AlertSeverity::Warning };
+        let alert = Alert::new(severity, "regime",
+            format!("REGIME CHANGE on {}: {:?} -> {:?} after {}s (entropy {})",
+                change.symbol.0, change.from, change.to, change.dwell_secs, change.entropy))
+            .with_context("symbol", &change.symbol.0)
+            .with_context("from", format!("{:?}", change.from))
+            .with_context("to", format!("{:?}", change.to));
+        self.sink.send(&alert);
+        alert
+    }
+
+    /// Warn that a signal was dropped because verification is backlogged;
+    /// one incident per venue while it lasts
+    pub fn check_verification_backlog(&self, backlog: &VerificationBacklog) -> Alert {
+        let alert = Alert::new(AlertSeverity::Warning, "verifier",
+            format!("VERIFICATION BACKLOG: {} signal on {} dropped, {} proofs already queued",
+                backlog.symbol.0, backlog.venue, backlog.queue_depth))
+            .with_context("code", "VERIFY_QUEUE_FULL")
+            .with_context("symbol", &backlog.symbol.0)
+            .with_context("venue", &backlog.venue);
+        self.sink.send(&alert);
+        alert
+    }
+
+    /// Flag sudden changes in funnel conversion ratios
+    pub fn check_funnel(&self, shifts: &[FunnelShift]) {
+        for shift in shifts {
+            self.sink.send(&Alert::new(AlertSeverity::Warning, "funnel",
+                format!("FUNNEL SHIFT on {}: {} -> {} conversion {:.1}% (baseline {:.1}%)",
+                    shift.symbol, shift.from, shift.to, shift.current * 100.0, shift.baseline * 100.0)));
         }
     }
 }
 
+/// Event-log listener that alerts on every `regime_changed` event
+pub struct RegimeAlertListener {
+    alerts: Arc<AlertManager>,
+}
+
+impl RegimeAlertListener {
+    pub fn new(alerts: Arc<AlertManager>) -> Self {
+        Self { alerts }
+    }
+}
+
+impl EventListener for RegimeAlertListener {
+    fn on_event(&self, event_type: &str, line: &str) {
+        #[derive(Deserialize)]
+        struct Envelope {
+            payload: RegimeChanged,
+        }
+        if event_type != RegimeChanged::EVENT_TYPE {
+            return;
+        }
+        match serde_json::from_str::<Envelope>(line) {
+            Ok(envelope) => {
+                self.alerts.check_regime_change(&envelope.payload);
+            }
+            Err(e) => warn!("Unparseable {} event not alerted: {}", event_type, e),
+        }
+    }
+}
+
+/// Event-log listener that alerts on every `verification_backlog` event
+pub struct BacklogAlertListener {
+    alerts: Arc<AlertManager>,
+}
+
+impl BacklogAlertListener {
+    pub fn new(alerts: Arc<AlertManager>) -> Self {
+        Self { alerts }
+    }
+}
+
+impl EventListener for BacklogAlertListener {
+    fn on_event(&self, event_type: &str, line: &str) {
+        #[derive(Deserialize)]
+        struct Envelope {
+            payload: VerificationBacklog,
+        }
+        if event_type != VerificationBacklog::EVENT_TYPE {
+            return;
+        }
+        match serde_json::from_str::<Envelope>(line) {
+            Ok(envelope) => {
+                self.alerts.check_verification_backlog(&envelope.payload);
+            }
+            Err(e) => warn!("Unparseable {} event not alerted: {}", event_type, e),
+        }
+    }
+}
+
+impl Default for AlertManager {
+    fn default() -> Self {
+        Self {
+            thresholds: Arc::new(RwLock::new(AlertThresholds::default())),
+            sink: Arc::new(LogAlertSink),
+        }
+    }
+}
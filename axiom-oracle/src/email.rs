@@ -0,0 +1,301 @@
+//! Email Alerts: SMTP Alert Sink
+//!
+//! Critical alerts are mailed immediately; everything else is batched into a
+//! periodic digest. Delivery runs on its own task behind a bounded queue, so
+//! a slow or unreachable mail server never blocks the alert pipeline: failed
+//! sends retry with backoff and are eventually dropped and counted.
+
+use crate::alerts::{Alert, AlertSeverity, AlertSink};
+use lettre::message::{Mailbox, MultiPart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::{error, warn};
+
+/// Transport security
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpTls {
+    /// TLS from the first byte (usually port 465)
+    Implicit,
+    /// Plaintext upgraded with STARTTLS (usually port 587)
+    StartTls,
+    /// No encryption (local relays only)
+    None,
+}
+
+/// SMTP sink configuration
+#[derive(Debug, Clone)]
+pub struct SmtpConfig {
+    pub host: String,
+    pub port: u16,
+    pub tls: SmtpTls,
+    pub from: String,
+    pub username: Option<String>,
+    /// Supplied from the environment at startup; never stored in the config file
+    pub password: Option<String>,
+    /// Recipients per severity; severities without recipients are not mailed
+    pub recipients: BTreeMap<AlertSeverity, Vec<String>>,
+    /// Interval between digests of non-critical alerts
+    pub digest_interval: Duration,
+    /// Delivery attempts before a message is dropped
+    pub max_attempts: u32,
+    /// Delay before the first retry (doubles on each attempt)
+    pub retry_backoff: Duration,
+    /// Prefix for incident links (e.g. "https://ops.example.com/incidents/")
+    pub incident_url_base: Option<String>,
+}
+
+/// Alerts waiting in the sink's queue before new ones are dropped
+const QUEUE_CAPACITY: usize = 1024;
+
+/// SMTP sink setup error
+#[derive(Debug, thiserror::Error)]
+pub enum EmailError {
+    #[error("Invalid email address: {0}")]
+    Address(String),
+
+    #[error("SMTP transport error: {0}")]
+    Transport(String),
+}
+
+/// Alert sink that delivers email over SMTP
+pub struct SmtpAlertSink {
+    queue: mpsc::Sender<Alert>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl SmtpAlertSink {
+    /// Start the delivery task and return the sink feeding it
+    pub fn spawn(config: SmtpConfig) -> Result<(Self, JoinHandle<()>), EmailError> {
+        let from: Mailbox = config.from.parse()
+            .map_err(|_| EmailError::Address(config.from.clone()))?;
+        for address in config.recipients.values().flatten() {
+            address.parse::<Mailbox>().map_err(|_| EmailError::Address(address.clone()))?;
+        }
+
+        let builder = match config.tls {
+            SmtpTls::Implicit => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.host),
+            SmtpTls::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host),
+            SmtpTls::None => Ok(AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.host)),
+        }
+        .map_err(|e| EmailError::Transport(e.to_string()))?;
+
+        let mut builder = builder.port(config.port);
+        if let (Some(username), Some(password)) = (&config.username, &config.password) {
+            builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+
+        let (queue, rx) = mpsc::channel(QUEUE_CAPACITY);
+        let dropped = Arc::new(AtomicU64::new(0));
+        let worker = EmailWorker {
+            transport: builder.build(),
+            from,
+            config,
+            dropped: dropped.clone(),
+        };
+
+        Ok((Self { queue, dropped }, tokio::spawn(worker.run(rx))))
+    }
+
+    /// Alerts dropped because the queue was full or delivery kept failing
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+impl AlertSink for SmtpAlertSink {
+    fn send(&self, alert: &Alert) {
+        if self.queue.try_send(alert.clone()).is_err() {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+            metrics::counter!("axiom_alert_email_dropped_total").increment(1);
+            warn!("Email alert queue full; dropped alert from {}", alert.source);
+        }
+    }
+}
+
+struct EmailWorker {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+    config: SmtpConfig,
+    dropped: Arc<AtomicU64>,
+}
+
+impl EmailWorker {
+    async fn run(self, mut rx: mpsc::Receiver<Alert>) {
+        let mut digest: Vec<Alert> = Vec::new();
+        let mut ticker = tokio::time::interval(self.config.digest_interval);
+        ticker.tick().await;
+
+        loop {
+            tokio::select! {
+                alert = rx.recv() => match alert {
+                    Some(alert) if alert.severity == AlertSeverity::Critical => {
+                        self.deliver(AlertSeverity::Critical, &[alert]).await;
+                    }
+                    Some(alert) => digest.push(alert),
+                    None => break,
+                },
+                _ = ticker.tick() => self.flush_digest(std::mem::take(&mut digest)).await,
+            }
+        }
+
+        self.flush_digest(digest).await;
+    }
+
+    /// One digest per severity, so each goes only to that severity's recipients
+    async fn flush_digest(&self, alerts: Vec<Alert>) {
+        let mut by_severity: BTreeMap<AlertSeverity, Vec<Alert>> = BTreeMap::new();
+        for alert in alerts {
+            by_severity.entry(alert.severity).or_default().push(alert);
+        }
+        for (severity, alerts) in by_severity {
+            self.deliver(severity, &alerts).await;
+        }
+    }
+
+    async fn deliver(&self, severity: AlertSeverity, alerts: &[Alert]) {
+        let Some(recipients) = self.config.recipients.get(&severity).filter(|r| !r.is_empty()) else {
+            return;
+        };
+
+        let subject = match alerts {
+            [alert] => format!("[{:?}] {}: {}", alert.severity, alert.source, truncate(&alert.message, 120)),
+            _ => format!("[{:?}] Axiom alert digest ({} alerts)", severity, alerts.len()),
+        };
+
+        let mut builder = Message::builder().from(self.from.clone()).subject(subject);
+        for recipient in recipients {
+            // Addresses were validated when the sink was built
+            if let Ok(mailbox) = recipient.parse::<Mailbox>() {
+                builder = builder.to(mailbox);
+            }
+        }
+
+        let base = self.config.incident_url_base.as_deref();
+        let message = match builder.multipart(MultiPart::alternative_plain_html(
+            render_text(alerts, base),
+            render_html(alerts, base),
+        )) {
+            Ok(message) => message,
+            Err(e) => {
+                error!("Failed to build alert email: {}", e);
+                self.drop_alerts(alerts.len());
+                return;
+            }
+        };
+
+        let mut backoff = self.config.retry_backoff;
+        for attempt in 1..=self.config.max_attempts.max(1) {
+            match self.transport.send(message.clone()).await {
+                Ok(_) => return,
+                Err(e) if attempt < self.config.max_attempts => {
+                    warn!("Alert email attempt {} failed: {}; retrying in {:?}", attempt, e, backoff);
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(Duration::from_secs(300));
+                }
+                Err(e) => error!("Alert email failed after {} attempts: {}", attempt, e),
+            }
+        }
+        self.drop_alerts(alerts.len());
+    }
+
+    fn drop_alerts(&self, count: usize) {
+        self.dropped.fetch_add(count as u64, Ordering::Relaxed);
+        metrics::counter!("axiom_alert_email_dropped_total").increment(count as u64);
+    }
+}
+
+fn truncate(s: &str, max_chars: usize) -> String {
+    match s.char_indices().nth(max_chars) {
+        Some((idx, _)) => format!("{}…", &s[..idx]),
+        None => s.to_string(),
+    }
+}
+
+fn incident_ref(id: u64, base: Option<&str>) -> (String, Option<String>) {
+    (format!("Incident #{}", id), base.map(|base| format!("{}{}", base, id)))
+}
+
+/// Plain-text rendering of one or more alerts
+pub fn render_text(alerts: &[Alert], incident_url_base: Option<&str>) -> String {
+    let mut out = String::new();
+    for alert in alerts {
+        let _ = writeln!(out, "[{:?}] {} — {}", alert.severity, alert.source, alert.timestamp.to_rfc3339());
+        let _ = writeln!(out, "{}", alert.message);
+        for (key, value) in &alert.context {
+            let _ = writeln!(out, "  {}: {}", key, value);
+        }
+        if let Some(id) = alert.incident_id {
+            match incident_ref(id, incident_url_base) {
+                (label, Some(url)) => { let _ = writeln!(out, "{}: {}", label, url); }
+                (label, None) => { let _ = writeln!(out, "{}", label); }
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// HTML rendering of one or more alerts
+pub fn render_html(alerts: &[Alert], incident_url_base: Option<&str>) -> String {
+    let mut out = String::from("<html><body style=\"font-family: monospace\">");
+    for alert in alerts {
+        let color = match alert.severity {
+            AlertSeverity::Critical => "#b00020",
+            AlertSeverity::Warning => "#b26a00",
+            AlertSeverity::Info => "#1f5fa8",
+        };
+        let _ = write!(
+            out,
+            "<div style=\"margin-bottom:1em\"><b style=\"color:{}\">[{:?}]</b> <b>{}</b> <small>{}</small><p>{}</p>",
+            color,
+            alert.severity,
+            escape_html(&alert.source),
+            alert.timestamp.to_rfc3339(),
+            escape_html(&alert.message),
+        );
+        if !alert.context.is_empty() {
+            out.push_str("<table>");
+            for (key, value) in &alert.context {
+                let _ = write!(out, "<tr><td>{}</td><td>{}</td></tr>", escape_html(key), escape_html(value));
+            }
+            out.push_str("</table>");
+        }
+        if let Some(id) = alert.incident_id {
+            match incident_ref(id, incident_url_base) {
+                (label, Some(url)) => {
+                    let _ = write!(out, "<p><a href=\"{}\">{}</a></p>", escape_html(&url), label);
+                }
+                (label, None) => {
+                    let _ = write!(out, "<p>{}</p>", label);
+                }
+            }
+        }
+        out.push_str("</div>");
+    }
+    out.push_str("</body></html>");
+    out
+}
+
+fn escape_html(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,257 @@
+//! Feed Monitor: Per-Stream Staleness and Message-Rate Alerts
+//!
+//! Tracks message arrival per (venue, symbol, channel). Each stream learns an
+//! expected rate for every UTC hour of the day from its own history; a stream
+//! is flagged when its rate falls below a fraction of that baseline, or when
+//! nothing arrives within a hard deadline. This is independent of transport
+//! stall detection: a connection can stay busy while one symbol goes silent.
+
+use crate::alerts::{Alert, AlertSeverity, AlertSink};
+use crate::incidents::IncidentTracker;
+use axiom_core::{Symbol, Venue};
+use chrono::{DateTime, Duration, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::task::JoinHandle;
+
+/// Identity of a single data stream
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct FeedKey {
+    pub venue: Venue,
+    pub symbol: Symbol,
+    /// Stream channel (e.g. "trades", "book")
+    pub channel: String,
+}
+
+impl FeedKey {
+    pub fn new(venue: &Venue, symbol: &Symbol, channel: &str) -> Self {
+        Self {
+            venue: venue.clone(),
+            symbol: symbol.clone(),
+            channel: channel.to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for FeedKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}/{}", self.venue, self.symbol.0, self.channel)
+    }
+}
+
+/// Feed monitor thresholds
+#[derive(Debug, Clone)]
+pub struct FeedMonitorConfig {
+    /// Length of the rate measurement window
+    pub window: Duration,
+    /// Alert if no message arrives within this deadline, regardless of baseline
+    pub stale_after: Duration,
+    /// Alert if the windowed rate drops below this fraction of baseline
+    pub min_rate_fraction: f64,
+    /// EWMA weight given to each new window when learning the baseline
+    pub baseline_alpha: f64,
+    /// Windows observed in an hour slot before its baseline is trusted
+    pub min_baseline_windows: u32,
+}
+
+impl Default for FeedMonitorConfig {
+    fn default() -> Self {
+        Self {
+            window: Duration::seconds(60),
+            stale_after: Duration::seconds(30),
+            min_rate_fraction: 0.2,
+            baseline_alpha: 0.1,
+            min_baseline_windows: 10,
+        }
+    }
+}
+
+/// Why a stream is considered unhealthy
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum FeedIssue {
+    /// No message within the hard deadline
+    Stale { silent_for_secs: i64 },
+    /// Rate fell below the learned baseline
+    LowRate { rate_per_min: f64, baseline_per_min: f64 },
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct HourBaseline {
+    rate_per_min: f64,
+    windows: u32,
+}
+
+struct FeedState {
+    last_message: DateTime<Utc>,
+    window_start: DateTime<Utc>,
+    window_count: u64,
+    /// Expected messages per minute, by UTC hour of day
+    baselines: [HourBaseline; 24],
+    issue: Option<FeedIssue>,
+}
+
+impl FeedState {
+    fn new(now: DateTime<Utc>) -> Self {
+        Self {
+            last_message: now,
+            window_start: now,
+            window_count: 0,
+            baselines: [HourBaseline::default(); 24],
+            issue: None,
+        }
+    }
+}
+
+/// Per-stream feed health monitor
+pub struct FeedMonitor {
+    config: FeedMonitorConfig,
+    feeds: Mutex<HashMap<FeedKey, FeedState>>,
+    alert_sink: Arc<dyn AlertSink>,
+    incidents: Option<Arc<IncidentTracker>>,
+}
+
+impl FeedMonitor {
+    pub fn new(config: FeedMonitorConfig, alert_sink: Arc<dyn AlertSink>) -> Self {
+        Self {
+            config,
+            feeds: Mutex::new(HashMap::new()),
+            alert_sink,
+            incidents: None,
+        }
+    }
+
+    /// Track stream issues as incidents (auto-resolved on recovery)
+    pub fn with_incidents(mut self, incidents: Arc<IncidentTracker>) -> Self {
+        self.incidents = Some(incidents);
+        self
+    }
+
+    /// Record one message on a stream
+    pub fn record_message(&self, key: &FeedKey, now: DateTime<Utc>) {
+        if let Ok(mut feeds) = self.feeds.lock() {
+            let state = feeds.entry(key.clone()).or_insert_with(|| FeedState::new(now));
+            // After a silence, start a fresh window so the gap isn't scored as a low rate
+            if now - state.last_message > self.config.stale_after {
+                state.window_start = now;
+                state.window_count = 0;
+            }
+            state.last_message = now;
+            state.window_count += 1;
+        }
+    }
+
+    /// Evaluate every stream, alerting on new issues and on recoveries
+    ///
+    /// Returns the streams currently flagged.
+    pub fn check(&self, now: DateTime<Utc>) -> Vec<(FeedKey, FeedIssue)> {
+        let mut raised = Vec::new();
+        let mut resolved = Vec::new();
+        let mut flagged = Vec::new();
+
+        if let Ok(mut feeds) = self.feeds.lock() {
+            for (key, state) in feeds.iter_mut() {
+                let issue = self.evaluate(state, now);
+
+                match (&state.issue, &issue) {
+                    (None, Some(new)) => raised.push((key.clone(), new.clone())),
+                    (Some(_), None) => resolved.push(key.clone()),
+                    _ => {}
+                }
+
+                state.issue = issue.clone();
+                if let Some(issue) = issue {
+                    flagged.push((key.clone(), issue));
+                }
+            }
+        }
+
+        raised.sort_by(|a, b| a.0.cmp(&b.0));
+        resolved.sort();
+        flagged.sort_by(|a, b| a.0.cmp(&b.0));
+
+        for (key, issue) in raised {
+            let message = match issue {
+                FeedIssue::Stale { silent_for_secs } => {
+                    format!("{} stale: no message for {}s", key, silent_for_secs)
+                }
+                FeedIssue::LowRate { rate_per_min, baseline_per_min } => {
+                    format!("{} rate {:.1}/min below baseline {:.1}/min", key, rate_per_min, baseline_per_min)
+                }
+            };
+            let alert = Alert::new(AlertSeverity::Warning, "feed_monitor", message);
+            match &self.incidents {
+                Some(incidents) => {
+                    incidents.fire(&format!("feed:{}", key), alert);
+                }
+                None => self.alert_sink.send(&alert),
+            }
+        }
+
+        for key in resolved {
+            match &self.incidents {
+                Some(incidents) => incidents.clear(&format!("feed:{}", key), now),
+                None => self.alert_sink.send(&Alert::new(
+                    AlertSeverity::Info,
+                    "feed_monitor",
+                    format!("{} recovered", key),
+                )),
+            }
+        }
+
+        flagged
+    }
+
+    /// Close any finished window into the baseline, then classify the stream
+    fn evaluate(&self, state: &mut FeedState, now: DateTime<Utc>) -> Option<FeedIssue> {
+        let silent_for = now - state.last_message;
+        if silent_for > self.config.stale_after {
+            return Some(FeedIssue::Stale { silent_for_secs: silent_for.num_seconds() });
+        }
+
+        if now - state.window_start < self.config.window {
+            // Mid-window: a low rate stands until the next window closes
+            return match state.issue {
+                Some(FeedIssue::LowRate { .. }) => state.issue.clone(),
+                _ => None,
+            };
+        }
+
+        let minutes = (now - state.window_start).num_milliseconds() as f64 / 60_000.0;
+        let rate = state.window_count as f64 / minutes;
+        let slot = &mut state.baselines[state.window_start.hour() as usize];
+        let baseline = (slot.windows >= self.config.min_baseline_windows).then_some(slot.rate_per_min);
+
+        let issue = match baseline {
+            Some(expected) if rate < expected * self.config.min_rate_fraction => {
+                Some(FeedIssue::LowRate { rate_per_min: rate, baseline_per_min: expected })
+            }
+            _ => None,
+        };
+
+        // Degraded windows are not learned, so an outage can't lower its own baseline
+        if issue.is_none() {
+            slot.rate_per_min = if slot.windows == 0 {
+                rate
+            } else {
+                self.config.baseline_alpha * rate + (1.0 - self.config.baseline_alpha) * slot.rate_per_min
+            };
+            slot.windows = slot.windows.saturating_add(1);
+        }
+
+        state.window_start = now;
+        state.window_count = 0;
+        issue
+    }
+
+    /// Run checks on their own task
+    pub fn spawn(self: Arc<Self>, interval: std::time::Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                self.check(Utc::now());
+            }
+        })
+    }
+}
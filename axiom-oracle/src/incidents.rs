@@ -0,0 +1,254 @@
+//! Incident Tracking: Alert Ownership and Lifecycle
+//!
+//! A firing condition opens an incident (Open → Acknowledged → Resolved).
+//! Unacknowledged Critical incidents are re-notified on an escalating
+//! schedule until someone acknowledges them. Incidents resolve automatically
+//! when their condition clears, or manually by an operator.
+
+use crate::alerts::{Alert, AlertSeverity, AlertSink};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Incident lifecycle state
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IncidentState {
+    Open,
+    Acknowledged { by: String, at: DateTime<Utc> },
+    Resolved { by: String, at: DateTime<Utc> },
+}
+
+/// A tracked incident
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Incident {
+    pub id: u64,
+    /// Condition key (e.g. "feed:binance/BTC/USD/trades"); one live incident per key
+    pub key: String,
+    pub alert: Alert,
+    pub state: IncidentState,
+    pub opened_at: DateTime<Utc>,
+    /// Notifications sent so far (initial + re-notifications)
+    pub notifications: u32,
+    pub last_notified: DateTime<Utc>,
+}
+
+impl Incident {
+    pub fn is_resolved(&self) -> bool {
+        matches!(self.state, IncidentState::Resolved { .. })
+    }
+}
+
+/// Incident tracking error
+#[derive(Debug, thiserror::Error)]
+pub enum IncidentError {
+    #[error("No incident with id {0}")]
+    NotFound(u64),
+
+    #[error("Incident {0} is already resolved")]
+    AlreadyResolved(u64),
+}
+
+/// Re-notification schedule for unacknowledged Critical incidents
+#[derive(Debug, Clone)]
+pub struct EscalationPolicy {
+    /// Delay before the first re-notification
+    pub initial: Duration,
+    /// Each subsequent delay is multiplied by this factor
+    pub factor: i32,
+    /// Upper bound on the delay between re-notifications
+    pub max: Duration,
+}
+
+impl EscalationPolicy {
+    /// Delay before the next notification, given how many have been sent
+    pub fn delay_after(&self, notifications: u32) -> Duration {
+        let mut delay = self.initial;
+        for _ in 1..notifications {
+            delay = delay * self.factor;
+            if delay >= self.max {
+                return self.max;
+            }
+        }
+        delay.min(self.max)
+    }
+}
+
+impl Default for EscalationPolicy {
+    fn default() -> Self {
+        Self {
+            initial: Duration::minutes(5),
+            factor: 2,
+            max: Duration::hours(1),
+        }
+    }
+}
+
+struct Incidents {
+    next_id: u64,
+    /// Live (unresolved) incident id per condition key
+    live: HashMap<String, u64>,
+    all: HashMap<u64, Incident>,
+}
+
+/// Incident tracker
+pub struct IncidentTracker {
+    sink: Arc<dyn AlertSink>,
+    policy: EscalationPolicy,
+    incidents: Mutex<Incidents>,
+}
+
+impl IncidentTracker {
+    pub fn new(sink: Arc<dyn AlertSink>, policy: EscalationPolicy) -> Self {
+        Self {
+            sink,
+            policy,
+            incidents: Mutex::new(Incidents {
+                next_id: 1,
+                live: HashMap::new(),
+                all: HashMap::new(),
+            }),
+        }
+    }
+
+    /// Report a firing condition; opens an incident unless one is already live for `key`
+    pub fn fire(&self, key: &str, mut alert: Alert) -> u64 {
+        let Ok(mut incidents) = self.incidents.lock() else {
+            self.sink.send(&alert);
+            return 0;
+        };
+
+        if let Some(id) = incidents.live.get(key) {
+            return *id;
+        }
+
+        let id = incidents.next_id;
+        incidents.next_id += 1;
+        alert.incident_id = Some(id);
+        let now = alert.timestamp;
+        incidents.live.insert(key.to_string(), id);
+        incidents.all.insert(id, Incident {
+            id,
+            key: key.to_string(),
+            alert: alert.clone(),
+            state: IncidentState::Open,
+            opened_at: now,
+            notifications: 1,
+            last_notified: now,
+        });
+        drop(incidents);
+
+        self.sink.send(&alert);
+        id
+    }
+
+    /// The condition behind `key` has cleared; resolve its live incident
+    pub fn clear(&self, key: &str, now: DateTime<Utc>) {
+        let resolved = {
+            let Ok(mut incidents) = self.incidents.lock() else { return };
+            let Some(id) = incidents.live.remove(key) else { return };
+            incidents.all.get_mut(&id).map(|incident| {
+                incident.state = IncidentState::Resolved { by: "auto".to_string(), at: now };
+                incident.clone()
+            })
+        };
+
+        if let Some(incident) = resolved {
+            let mut alert = Alert::new(
+                AlertSeverity::Info,
+                incident.alert.source.clone(),
+                format!("Incident #{} resolved: {}", incident.id, incident.alert.message),
+            );
+            alert.incident_id = Some(incident.id);
+            self.sink.send(&alert);
+        }
+    }
+
+    /// Record that an operator is handling an incident
+    pub fn acknowledge(&self, id: u64, by: &str, now: DateTime<Utc>) -> Result<Incident, IncidentError> {
+        let mut incidents = self.incidents.lock().unwrap_or_else(|e| e.into_inner());
+        let incident = incidents.all.get_mut(&id).ok_or(IncidentError::NotFound(id))?;
+        if incident.is_resolved() {
+            return Err(IncidentError::AlreadyResolved(id));
+        }
+        incident.state = IncidentState::Acknowledged { by: by.to_string(), at: now };
+        Ok(incident.clone())
+    }
+
+    /// Manually resolve an incident
+    pub fn resolve(&self, id: u64, by: &str, now: DateTime<Utc>) -> Result<Incident, IncidentError> {
+        let mut incidents = self.incidents.lock().unwrap_or_else(|e| e.into_inner());
+        let incident = incidents.all.get_mut(&id).ok_or(IncidentError::NotFound(id))?;
+        if incident.is_resolved() {
+            return Err(IncidentError::AlreadyResolved(id));
+        }
+        incident.state = IncidentState::Resolved { by: by.to_string(), at: now };
+        let incident = incident.clone();
+        incidents.live.remove(&incident.key);
+        Ok(incident)
+    }
+
+    /// Re-notify unacknowledged Critical incidents whose escalation delay has elapsed
+    ///
+    /// Returns the ids that were re-notified.
+    pub fn escalate(&self, now: DateTime<Utc>) -> Vec<u64> {
+        let mut due = Vec::new();
+        if let Ok(mut incidents) = self.incidents.lock() {
+            for incident in incidents.all.values_mut() {
+                if incident.state != IncidentState::Open || incident.alert.severity != AlertSeverity::Critical {
+                    continue;
+                }
+                if now - incident.last_notified >= self.policy.delay_after(incident.notifications) {
+                    incident.notifications += 1;
+                    incident.last_notified = now;
+                    due.push(incident.clone());
+                }
+            }
+        }
+        due.sort_by_key(|incident| incident.id);
+
+        for incident in &due {
+            let mut alert = Alert::new(
+                incident.alert.severity,
+                incident.alert.source.clone(),
+                format!(
+                    "UNACKNOWLEDGED incident #{} (notice {}): {}",
+                    incident.id, incident.notifications, incident.alert.message
+                ),
+            );
+            alert.context = incident.alert.context.clone();
+            alert.incident_id = Some(incident.id);
+            self.sink.send(&alert);
+        }
+
+        due.iter().map(|incident| incident.id).collect()
+    }
+
+    /// Unresolved incidents, oldest first
+    pub fn open_incidents(&self) -> Vec<Incident> {
+        let incidents = self.incidents.lock().unwrap_or_else(|e| e.into_inner());
+        let mut open: Vec<Incident> = incidents.all.values()
+            .filter(|incident| !incident.is_resolved())
+            .cloned()
+            .collect();
+        open.sort_by_key(|incident| incident.id);
+        open
+    }
+
+    /// Take resolved incidents out of the tracker for persistence, alongside a
+    /// snapshot of the ones still open
+    pub fn drain_for_history(&self) -> Vec<Incident> {
+        let mut incidents = self.incidents.lock().unwrap_or_else(|e| e.into_inner());
+        let resolved: Vec<u64> = incidents.all.values()
+            .filter(|incident| incident.is_resolved())
+            .map(|incident| incident.id)
+            .collect();
+
+        let mut history: Vec<Incident> = resolved.iter()
+            .filter_map(|id| incidents.all.remove(id))
+            .collect();
+        history.extend(incidents.all.values().cloned());
+        history.sort_by_key(|incident| incident.id);
+        history
+    }
+}
@@ -5,8 +5,24 @@
 pub mod monitoring;
 pub mod telemetry;
 pub mod alerts;
+pub mod watchdog;
+pub mod reports;
+pub mod feed_monitor;
+pub mod incidents;
+pub mod snapshot;
+pub mod resources;
+pub mod email;
+pub mod live_stream;
 
 pub use monitoring::*;
 pub use telemetry::*;
 pub use alerts::*;
+pub use watchdog::*;
+pub use reports::*;
+pub use feed_monitor::*;
+pub use incidents::*;
+pub use snapshot::*;
+pub use resources::*;
+pub use email::*;
+pub use live_stream::*;
 
@@ -0,0 +1,341 @@
+//! Live Stream: WebSocket Feed for the Dashboard
+//!
+//! Streams JSON frames to dashboard clients: periodic SystemHealth snapshots,
+//! the structured event log, and portfolio summaries. Each client chooses its
+//! channels (and event types) with subscription messages. Frames fan out over
+//! a broadcast channel, so a slow client only ever loses its own frames and
+//! never holds up the producer.
+
+use crate::telemetry::TelemetryCollector;
+use axiom_core::{EventListener, Portfolio, RedactedPortfolio, SystemHealth};
+use chrono::{DateTime, Utc};
+use futures::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{broadcast, watch};
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::http::StatusCode;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{debug, info, warn};
+
+/// Stream channel a client can subscribe to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LiveChannel {
+    Health,
+    Events,
+    Portfolio,
+}
+
+/// Frame sent to clients
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LiveFrame {
+    Health { health: SystemHealth },
+    Event { event_type: String, event: Value },
+    Portfolio { portfolio: RedactedPortfolio, at: DateTime<Utc> },
+    /// Frames this client missed because it fell behind
+    Dropped { frames: u64 },
+    /// Reply to a subscription message
+    Subscribed { channels: BTreeSet<LiveChannel>, event_types: Option<BTreeSet<String>> },
+    Error { message: String },
+}
+
+impl LiveFrame {
+    fn channel(&self) -> Option<LiveChannel> {
+        match self {
+            LiveFrame::Health { .. } => Some(LiveChannel::Health),
+            LiveFrame::Event { .. } => Some(LiveChannel::Events),
+            LiveFrame::Portfolio { .. } => Some(LiveChannel::Portfolio),
+            _ => None,
+        }
+    }
+}
+
+/// Message sent by clients
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum ClientMessage {
+    /// Add channels; `event_types` (when given) replaces the event filter
+    Subscribe {
+        channels: Vec<LiveChannel>,
+        #[serde(default)]
+        event_types: Option<Vec<String>>,
+    },
+    Unsubscribe { channels: Vec<LiveChannel> },
+}
+
+/// Per-client subscription state
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Subscription {
+    pub channels: BTreeSet<LiveChannel>,
+    /// Event types to forward; `None` forwards all
+    pub event_types: Option<BTreeSet<String>>,
+}
+
+impl Subscription {
+    /// Apply a client message and return the acknowledgement frame
+    pub fn apply(&mut self, message: ClientMessage) -> LiveFrame {
+        match message {
+            ClientMessage::Subscribe { channels, event_types } => {
+                self.channels.extend(channels);
+                if let Some(event_types) = event_types {
+                    self.event_types = (!event_types.is_empty()).then(|| event_types.into_iter().collect());
+                }
+            }
+            ClientMessage::Unsubscribe { channels } => {
+                for channel in channels {
+                    self.channels.remove(&channel);
+                    if channel == LiveChannel::Events {
+                        self.event_types = None;
+                    }
+                }
+            }
+        }
+        LiveFrame::Subscribed {
+            channels: self.channels.clone(),
+            event_types: self.event_types.clone(),
+        }
+    }
+
+    /// Whether this client should receive `frame`
+    pub fn wants(&self, frame: &LiveFrame) -> bool {
+        let Some(channel) = frame.channel() else { return true };
+        if !self.channels.contains(&channel) {
+            return false;
+        }
+        match (frame, &self.event_types) {
+            (LiveFrame::Event { event_type, .. }, Some(types)) => types.contains(event_type),
+            _ => true,
+        }
+    }
+}
+
+/// Live stream server settings
+#[derive(Debug, Clone)]
+pub struct LiveStreamConfig {
+    pub bind: SocketAddr,
+    /// Static bearer token clients must present
+    pub token: String,
+    pub health_interval: Duration,
+    pub portfolio_interval: Duration,
+    /// Frames buffered per client before the oldest are dropped
+    pub client_buffer: usize,
+}
+
+/// Producer side of the stream; cheap to clone
+#[derive(Clone)]
+pub struct LiveStreamHub {
+    frames: broadcast::Sender<Arc<LiveFrame>>,
+}
+
+impl LiveStreamHub {
+    pub fn new(client_buffer: usize) -> Self {
+        let (frames, _) = broadcast::channel(client_buffer.max(1));
+        Self { frames }
+    }
+
+    /// Publish a frame to every connected client (never blocks)
+    pub fn publish(&self, frame: LiveFrame) {
+        // No receivers just means no clients are connected
+        let _ = self.frames.send(Arc::new(frame));
+    }
+
+    /// Event-log listener that forwards every event to the stream
+    pub fn event_listener(&self) -> LiveEventListener {
+        LiveEventListener { hub: self.clone() }
+    }
+
+    pub fn clients(&self) -> usize {
+        self.frames.receiver_count()
+    }
+}
+
+/// Forwards structured events from `EventLog` to the live stream
+pub struct LiveEventListener {
+    hub: LiveStreamHub,
+}
+
+impl EventListener for LiveEventListener {
+    fn on_event(&self, event_type: &str, line: &str) {
+        if self.hub.clients() == 0 {
+            return;
+        }
+        match serde_json::from_str(line) {
+            Ok(event) => self.hub.publish(LiveFrame::Event { event_type: event_type.to_string(), event }),
+            Err(e) => warn!("Dropping unparseable {} event from live stream: {}", event_type, e),
+        }
+    }
+}
+
+type PortfolioFn = Box<dyn Fn() -> Portfolio + Send + Sync>;
+
+/// WebSocket server for the live stream
+pub struct LiveStreamServer {
+    config: LiveStreamConfig,
+    hub: LiveStreamHub,
+    telemetry: Arc<TelemetryCollector>,
+    portfolio: Option<PortfolioFn>,
+}
+
+impl LiveStreamServer {
+    pub fn new(config: LiveStreamConfig, hub: LiveStreamHub, telemetry: Arc<TelemetryCollector>) -> Self {
+        Self {
+            config,
+            hub,
+            telemetry,
+            portfolio: None,
+        }
+    }
+
+    pub fn with_portfolio(mut self, portfolio: impl Fn() -> Portfolio + Send + Sync + 'static) -> Self {
+        self.portfolio = Some(Box::new(portfolio));
+        self
+    }
+
+    /// Bind the listener, then accept clients and publish periodic frames on
+    /// their own tasks until `shutdown` turns true
+    pub async fn spawn(self, shutdown: watch::Receiver<bool>) -> std::io::Result<JoinHandle<()>> {
+        if self.config.token.is_empty() {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "live stream token must be set"));
+        }
+        let listener = TcpListener::bind(self.config.bind).await?;
+        info!("Live stream listening on ws://{}", listener.local_addr()?);
+        let server = Arc::new(self);
+
+        tokio::spawn(server.clone().publish_periodic(shutdown.clone()));
+        let mut shutdown = shutdown;
+        Ok(tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    accepted = listener.accept() => match accepted {
+                        Ok((stream, peer)) => {
+                            tokio::spawn(server.clone().handle_client(stream, peer));
+                        }
+                        Err(e) => warn!("Live stream accept failed: {}", e),
+                    },
+                    _ = shutdown.wait_for(|stop| *stop) => {
+                        info!("Live stream no longer accepting clients");
+                        break;
+                    }
+                }
+            }
+        }))
+    }
+
+    async fn publish_periodic(self: Arc<Self>, mut shutdown: watch::Receiver<bool>) {
+        let mut health_ticker = tokio::time::interval(self.config.health_interval);
+        let mut portfolio_ticker = tokio::time::interval(self.config.portfolio_interval);
+        loop {
+            tokio::select! {
+                _ = health_ticker.tick() => {
+                    if self.hub.clients() == 0 {
+                        continue;
+                    }
+                    if let Some(health) = self.telemetry.get_health().await {
+                        self.hub.publish(LiveFrame::Health { health });
+                    }
+                }
+                // The watch guard is not Send, so drop it before the other arms await
+                _ = async { drop(shutdown.wait_for(|stop| *stop).await) } => break,
+                _ = portfolio_ticker.tick() => {
+                    if self.hub.clients() == 0 {
+                        continue;
+                    }
+                    if let Some(portfolio) = &self.portfolio {
+                        self.hub.publish(LiveFrame::Portfolio {
+                            portfolio: portfolio().redacted(),
+                            at: Utc::now(),
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    // The handshake callback's error type is fixed by tungstenite
+    #[allow(clippy::result_large_err)]
+    async fn handle_client(self: Arc<Self>, stream: TcpStream, peer: SocketAddr) {
+        let token = self.config.token.clone();
+        let authorize = move |request: &Request, response: Response| -> Result<Response, ErrorResponse> {
+            if request_token(request).is_some_and(|presented| constant_time_eq(presented, &token)) {
+                Ok(response)
+            } else {
+                let mut error = ErrorResponse::new(Some("unauthorized".to_string()));
+                *error.status_mut() = StatusCode::UNAUTHORIZED;
+                Err(error)
+            }
+        };
+
+        let ws = match tokio_tungstenite::accept_hdr_async(stream, authorize).await {
+            Ok(ws) => ws,
+            Err(e) => {
+                debug!("Live stream handshake with {} failed: {}", peer, e);
+                return;
+            }
+        };
+        info!("Live stream client connected: {}", peer);
+
+        let (mut outgoing, mut incoming) = ws.split();
+        let mut frames = self.hub.frames.subscribe();
+        let mut subscription = Subscription::default();
+
+        loop {
+            let reply = tokio::select! {
+                frame = frames.recv() => match frame {
+                    Ok(frame) if subscription.wants(&frame) => frame,
+                    Ok(_) => continue,
+                    Err(broadcast::error::RecvError::Lagged(missed)) => {
+                        metrics::counter!("axiom_live_stream_dropped_frames_total").increment(missed);
+                        Arc::new(LiveFrame::Dropped { frames: missed })
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+                message = incoming.next() => match message {
+                    Some(Ok(Message::Text(text))) => Arc::new(match serde_json::from_str(&text) {
+                        Ok(message) => subscription.apply(message),
+                        Err(e) => LiveFrame::Error { message: format!("invalid message: {}", e) },
+                    }),
+                    Some(Ok(Message::Close(_))) | None => break,
+                    Some(Ok(_)) => continue,
+                    Some(Err(e)) => {
+                        debug!("Live stream client {} errored: {}", peer, e);
+                        break;
+                    }
+                },
+            };
+
+            let Ok(text) = serde_json::to_string(reply.as_ref()) else { continue };
+            if outgoing.send(Message::Text(text)).await.is_err() {
+                break;
+            }
+        }
+
+        info!("Live stream client disconnected: {}", peer);
+    }
+}
+
+/// Token from `Authorization: Bearer <token>` or a `token` query parameter
+fn request_token(request: &Request) -> Option<&str> {
+    let header = request.headers()
+        .get("authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    header.or_else(|| {
+        request.uri().query()?
+            .split('&')
+            .find_map(|pair| pair.strip_prefix("token="))
+    })
+}
+
+/// Compare secrets without leaking the position of the first difference
+pub fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len() && a.bytes().zip(b.bytes()).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
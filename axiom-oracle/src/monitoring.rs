@@ -2,16 +2,86 @@
 //!
 //! Tracks system health, latency, and performance metrics.
 
-use axiom_core::{SystemHealth, ConsistencyError, EntropyCount, CircuitBreakerState};
-use axiom_risk::circuit_breaker::CircuitBreaker;
+use axiom_core::{
+    SystemHealth, ConsistencyError, EntropyCount, CircuitBreakerState, MarketRegime, Symbol, Venue,
+    LatencyBreakdown, LatencyRecorder, LatencyStage, StageLatency, FunnelRecorder, FunnelStage,
+};
 use rust_decimal::Decimal;
-use chrono::Utc;
-use std::collections::VecDeque;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Length of one funnel counting window
+pub const FUNNEL_WINDOW_SECS: i64 = 300;
+
+/// Funnel windows retained for rolling ratios (one hour)
+pub const FUNNEL_WINDOWS_KEPT: usize = 12;
+
+/// Stage counts per symbol
+pub type FunnelCounts = BTreeMap<String, BTreeMap<FunnelStage, u64>>;
+
+/// Funnel counts over one window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunnelWindow {
+    pub started_at: DateTime<Utc>,
+    pub counts: FunnelCounts,
+}
+
+/// Conversion between two consecutive funnel stages
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Conversion {
+    pub from: FunnelStage,
+    pub to: FunnelStage,
+    /// None when nothing reached `from`
+    pub ratio: Option<f64>,
+}
+
+/// A conversion ratio that moved sharply against its trailing baseline
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FunnelShift {
+    pub symbol: String,
+    pub from: FunnelStage,
+    pub to: FunnelStage,
+    pub baseline: f64,
+    pub current: f64,
+}
+
+/// Conversion ratios between consecutive stages
+pub fn conversions(counts: &BTreeMap<FunnelStage, u64>) -> Vec<Conversion> {
+    FunnelStage::ALL.windows(2)
+        .map(|pair| {
+            let reached = counts.get(&pair[0]).copied().unwrap_or(0);
+            let converted = counts.get(&pair[1]).copied().unwrap_or(0);
+            Conversion {
+                from: pair[0],
+                to: pair[1],
+                ratio: (reached > 0).then(|| converted as f64 / reached as f64),
+            }
+        })
+        .collect()
+}
+
+fn merge_counts(into: &mut FunnelCounts, from: &FunnelCounts) {
+    for (symbol, stages) in from {
+        let entry = into.entry(symbol.clone()).or_default();
+        for (stage, count) in stages {
+            *entry.entry(*stage).or_insert(0) += count;
+        }
+    }
+}
 
 /// System monitor
 pub struct SystemMonitor {
     health_history: VecDeque<SystemHealth>,
     latency_samples: VecDeque<u64>,
+    /// Per (stage, venue) samples in microseconds
+    stage_samples: BTreeMap<(LatencyStage, Venue), VecDeque<u64>>,
+    /// Rolling funnel windows, newest last
+    funnel_windows: VecDeque<FunnelWindow>,
+    /// Funnel counts since the last daily close
+    daily_funnel: FunnelCounts,
     max_history: usize,
 }
 
@@ -20,10 +90,117 @@ impl SystemMonitor {
         Self {
             health_history: VecDeque::with_capacity(max_history),
             latency_samples: VecDeque::with_capacity(max_history),
+            stage_samples: BTreeMap::new(),
+            funnel_windows: VecDeque::with_capacity(FUNNEL_WINDOWS_KEPT),
+            daily_funnel: BTreeMap::new(),
             max_history,
         }
     }
 
+    /// Count one opportunity reaching a funnel stage
+    pub fn record_funnel(&mut self, stage: FunnelStage, symbol: &Symbol, now: DateTime<Utc>) {
+        let window_expired = self.funnel_windows.back()
+            .is_none_or(|w| (now - w.started_at).num_seconds() >= FUNNEL_WINDOW_SECS);
+        if window_expired {
+            self.funnel_windows.push_back(FunnelWindow { started_at: now, counts: BTreeMap::new() });
+            if self.funnel_windows.len() > FUNNEL_WINDOWS_KEPT {
+                self.funnel_windows.pop_front();
+            }
+        }
+
+        if let Some(window) = self.funnel_windows.back_mut() {
+            *window.counts.entry(symbol.0.clone()).or_default().entry(stage).or_insert(0) += 1;
+        }
+        *self.daily_funnel.entry(symbol.0.clone()).or_default().entry(stage).or_insert(0) += 1;
+    }
+
+    /// Rolling conversion ratios per symbol over the retained windows
+    pub fn conversion_ratios(&self) -> BTreeMap<String, Vec<Conversion>> {
+        let mut totals = BTreeMap::new();
+        for window in &self.funnel_windows {
+            merge_counts(&mut totals, &window.counts);
+        }
+        totals.iter().map(|(symbol, counts)| (symbol.clone(), conversions(counts))).collect()
+    }
+
+    /// Conversions in the newest window that moved more than `max_change`
+    /// (absolute) from the average of the preceding windows
+    ///
+    /// Only stages reached at least `min_samples` times in the newest window are judged.
+    pub fn funnel_shifts(&self, min_samples: u64, max_change: f64) -> Vec<FunnelShift> {
+        let Some(current) = self.funnel_windows.back() else {
+            return Vec::new();
+        };
+        let history = self.funnel_windows.len() - 1;
+        if history == 0 {
+            return Vec::new();
+        }
+
+        let mut baseline_counts = BTreeMap::new();
+        for window in self.funnel_windows.iter().take(history) {
+            merge_counts(&mut baseline_counts, &window.counts);
+        }
+
+        let mut shifts = Vec::new();
+        for (symbol, counts) in &current.counts {
+            let Some(baseline) = baseline_counts.get(symbol) else { continue };
+            let baseline = conversions(baseline);
+            for (now, before) in conversions(counts).iter().zip(baseline.iter()) {
+                let reached = counts.get(&now.from).copied().unwrap_or(0);
+                if reached < min_samples {
+                    continue;
+                }
+                if let (Some(current), Some(baseline)) = (now.ratio, before.ratio) {
+                    if (current - baseline).abs() > max_change {
+                        shifts.push(FunnelShift {
+                            symbol: symbol.clone(),
+                            from: now.from,
+                            to: now.to,
+                            baseline,
+                            current,
+                        });
+                    }
+                }
+            }
+        }
+        shifts
+    }
+
+    /// Take the funnel counts accumulated since the last call (daily close)
+    pub fn take_daily_funnel(&mut self) -> FunnelCounts {
+        std::mem::take(&mut self.daily_funnel)
+    }
+
+    /// Record a latency sample for one pipeline stage on one venue
+    pub fn record_stage_latency(&mut self, stage: LatencyStage, venue: &Venue, latency_us: u64) {
+        let samples = self.stage_samples.entry((stage, venue.clone())).or_default();
+        samples.push_back(latency_us);
+        if samples.len() > self.max_history {
+            samples.pop_front();
+        }
+    }
+
+    /// Per-stage, per-venue percentiles
+    pub fn latency_breakdown(&self) -> LatencyBreakdown {
+        let stages = self.stage_samples.iter()
+            .filter(|(_, samples)| !samples.is_empty())
+            .map(|((stage, venue), samples)| {
+                let mut sorted: Vec<u64> = samples.iter().copied().collect();
+                sorted.sort_unstable();
+                StageLatency {
+                    stage: *stage,
+                    venue: venue.clone(),
+                    count: sorted.len(),
+                    p50_us: nearest_rank(&sorted, 0.5).unwrap_or(0),
+                    p99_us: nearest_rank(&sorted, 0.99).unwrap_or(0),
+                    p999_us: nearest_rank(&sorted, 0.999).unwrap_or(0),
+                }
+            })
+            .collect();
+
+        LatencyBreakdown { stages }
+    }
+
     /// Record a latency sample
     pub fn record_latency(&mut self, latency_ms: u64) {
         self.latency_samples.push_back(latency_ms);
@@ -32,35 +209,35 @@ impl SystemMonitor {
         }
     }
 
-    /// Calculate latency percentiles
-    pub fn latency_percentiles(&self) -> (u64, u64, u64) {
-        if self.latency_samples.is_empty() {
-            return (0, 0, 0);
-        }
-
-        let mut sorted: Vec<u64> = self.latency_samples.iter().cloned().collect();
-        sorted.sort();
-
-        let p50_idx = (sorted.len() as f64 * 0.5) as usize;
-        let p99_idx = (sorted.len() as f64 * 0.99) as usize;
-        let p999_idx = (sorted.len() as f64 * 0.999) as usize.min(sorted.len() - 1);
+    /// Nearest-rank percentile of the recorded latencies (milliseconds)
+    ///
+    /// `q` is a fraction in [0, 1]; 0 yields the minimum and 1 the maximum.
+    /// Returns None when no samples have been recorded or `q` is out of range.
+    pub fn percentile(&self, q: f64) -> Option<u64> {
+        let mut sorted: Vec<u64> = self.latency_samples.iter().copied().collect();
+        sorted.sort_unstable();
+        nearest_rank(&sorted, q)
+    }
 
-        (
-            sorted.get(p50_idx).copied().unwrap_or(0),
-            sorted.get(p99_idx).copied().unwrap_or(0),
-            sorted.get(p999_idx).copied().unwrap_or(0),
-        )
+    /// Latency distribution summary (all zeros, count 0, when no samples exist)
+    pub fn latency_summary(&self) -> LatencySummary {
+        let mut sorted: Vec<u64> = self.latency_samples.iter().copied().collect();
+        sorted.sort_unstable();
+        LatencySummary::from_sorted(&sorted)
     }
 
-    /// Generate system health snapshot
+    /// Generate system health snapshot (`regime`: the regime detector's
+    /// overall regime; `drawdown`: equity below its peak, as a fraction)
     pub fn generate_health_snapshot(
         &mut self,
         consistency_error: Decimal,
         entropy_count: Decimal,
+        regime: MarketRegime,
         circuit_breaker: CircuitBreakerState,
+        drawdown: Decimal,
         hallucination_rate: Decimal,
     ) -> SystemHealth {
-        let (p50, p99, p999) = self.latency_percentiles();
+        let latency = self.latency_summary();
 
         let health = SystemHealth {
             consistency_error: ConsistencyError {
@@ -71,18 +248,16 @@ impl SystemMonitor {
             entropy_count: EntropyCount {
                 value: entropy_count,
                 threshold: axiom_core::constants::DELTA_U_MAX_SQ,
-                regime: if entropy_count > axiom_core::constants::DELTA_U_MAX_SQ {
-                    axiom_core::MarketRegime::Unprovable
-                } else {
-                    axiom_core::MarketRegime::Normal
-                },
+                regime,
                 timestamp: Utc::now(),
             },
             circuit_breaker,
+            drawdown,
             hallucination_rate,
-            latency_p50: p50,
-            latency_p99: p99,
-            latency_p999: p999,
+            latency_p50: latency.p50,
+            latency_p99: latency.p99,
+            latency_p999: latency.p999,
+            latency_breakdown: self.latency_breakdown(),
             timestamp: Utc::now(),
         };
 
@@ -100,3 +275,138 @@ impl SystemMonitor {
     }
 }
 
+/// Latency distribution summary
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LatencySummary {
+    pub count: usize,
+    pub p50: u64,
+    pub p90: u64,
+    pub p95: u64,
+    pub p99: u64,
+    pub p999: u64,
+    pub max: u64,
+}
+
+impl LatencySummary {
+    /// Summarize an ascending-sorted sample set (empty input yields the zero summary)
+    pub fn from_sorted(sorted: &[u64]) -> Self {
+        let at = |q| nearest_rank(sorted, q).unwrap_or(0);
+        Self {
+            count: sorted.len(),
+            p50: at(0.5),
+            p90: at(0.9),
+            p95: at(0.95),
+            p99: at(0.99),
+            p999: at(0.999),
+            max: sorted.last().copied().unwrap_or(0),
+        }
+    }
+}
+
+/// Nearest-rank percentile: the smallest sample with at least `q` of the
+/// samples at or below it
+///
+/// The rank is clamped to [1, len], so tiny sample sets never index out of bounds.
+fn nearest_rank(sorted: &[u64], q: f64) -> Option<u64> {
+    if sorted.is_empty() || !(0.0..=1.0).contains(&q) {
+        return None;
+    }
+    // Nudge down so q * len landing a hair above an integer doesn't skip a
+    // rank (0.07 * 100 is 7.000000000000001 in f64)
+    let rank = (q * sorted.len() as f64 - 1e-9).ceil() as usize;
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
+/// `LatencyRecorder` that feeds a shared SystemMonitor
+pub struct MonitorLatencyRecorder {
+    monitor: Arc<Mutex<SystemMonitor>>,
+}
+
+impl MonitorLatencyRecorder {
+    pub fn new(monitor: Arc<Mutex<SystemMonitor>>) -> Self {
+        Self { monitor }
+    }
+}
+
+impl LatencyRecorder for MonitorLatencyRecorder {
+    fn record(&self, stage: LatencyStage, venue: &Venue, elapsed: Duration) {
+        if let Ok(mut monitor) = self.monitor.lock() {
+            monitor.record_stage_latency(stage, venue, elapsed.as_micros() as u64);
+        }
+    }
+}
+
+/// `FunnelRecorder` that feeds a shared SystemMonitor and the metrics recorder
+pub struct MonitorFunnelRecorder {
+    monitor: Arc<Mutex<SystemMonitor>>,
+}
+
+impl MonitorFunnelRecorder {
+    pub fn new(monitor: Arc<Mutex<SystemMonitor>>) -> Self {
+        Self { monitor }
+    }
+}
+
+impl FunnelRecorder for MonitorFunnelRecorder {
+    fn record(&self, stage: FunnelStage, symbol: &Symbol) {
+        metrics::counter!("axiom_funnel_total", "stage" => stage.as_str(), "symbol" => symbol.0.clone()).increment(1);
+        if let Ok(mut monitor) = self.monitor.lock() {
+            monitor.record_funnel(stage, symbol, Utc::now());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(sorted: &[u64]) -> LatencySummary {
+        LatencySummary::from_sorted(sorted)
+    }
+
+    #[test]
+    fn a_single_sample_is_every_percentile() {
+        let expected = LatencySummary { count: 1, p50: 42, p90: 42, p95: 42, p99: 42, p999: 42, max: 42 };
+        assert_eq!(summary(&[42]), expected);
+    }
+
+    #[test]
+    fn two_samples_split_at_the_median() {
+        let expected = LatencySummary { count: 2, p50: 10, p90: 20, p95: 20, p99: 20, p999: 20, max: 20 };
+        assert_eq!(summary(&[10, 20]), expected);
+    }
+
+    #[test]
+    fn ten_samples_with_ties_take_the_nearest_rank() {
+        // Ranks 5, 9, then 10 for every higher percentile
+        let expected = LatencySummary { count: 10, p50: 2, p90: 8, p95: 13, p99: 13, p999: 13, max: 13 };
+        assert_eq!(summary(&[1, 1, 2, 2, 2, 3, 5, 8, 8, 13]), expected);
+    }
+
+    #[test]
+    fn ten_thousand_samples_each_tied_once() {
+        // 1, 1, 2, 2, ..., 5000, 5000: rank r holds ceil(r / 2)
+        let sorted: Vec<u64> = (1..=5_000).flat_map(|value| [value, value]).collect();
+        let expected = LatencySummary { count: 10_000, p50: 2_500, p90: 4_500, p95: 4_750, p99: 4_950, p999: 4_995, max: 5_000 };
+        assert_eq!(summary(&sorted), expected);
+    }
+
+    #[test]
+    fn a_rank_a_hair_above_an_integer_is_not_skipped() {
+        let mut monitor = SystemMonitor::new(100);
+        for latency in 1..=100 {
+            monitor.record_latency(latency);
+        }
+        // q * len is 7.000000000000001 and 55.00000000000001 in f64
+        assert_eq!(monitor.percentile(0.07), Some(7));
+        assert_eq!(monitor.percentile(0.55), Some(55));
+        assert_eq!((monitor.percentile(0.0), monitor.percentile(1.0)), (Some(1), Some(100)));
+        assert_eq!(monitor.percentile(1.5), None);
+    }
+
+    #[test]
+    fn no_samples_summarize_to_zero() {
+        assert_eq!(summary(&[]), LatencySummary::default());
+        assert_eq!(SystemMonitor::new(10).percentile(0.5), None);
+    }
+}
@@ -0,0 +1,511 @@
+//! PnL Reports: End-of-Day and Weekly Summaries
+//!
+//! Reports are derived purely from stored `DailyTelemetry` records, so
+//! regenerating a past day's report yields byte-identical output. The
+//! publisher renders each report as JSON and text, writes both to disk, and
+//! pushes the text summary through the alert sink.
+
+use crate::alerts::{Alert, AlertSeverity, AlertSink};
+use crate::incidents::{Incident, IncidentState, IncidentTracker};
+use crate::monitoring::{conversions, FunnelCounts, SystemMonitor};
+use crate::telemetry::{
+    BreakerEvent, DailyTelemetry, SlippageSample, StrategyAllocation, TelemetryCollector, TelemetryStore,
+};
+use axiom_core::ShadowComparison;
+use rust_decimal::Decimal;
+use chrono::{Datelike, Duration as ChronoDuration, NaiveDate, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tokio::task::JoinHandle;
+use tracing::{error, info};
+
+/// PnL attributed to one symbol
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SymbolPnl {
+    pub realized: Decimal,
+    pub unrealized: Decimal,
+}
+
+/// End-of-day report
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DailyReport {
+    pub date: NaiveDate,
+    pub realized_pnl: Decimal,
+    pub unrealized_pnl: Decimal,
+    pub fees: Decimal,
+    pub funding: Decimal,
+    /// Realized + unrealized - fees + funding
+    pub net_pnl: Decimal,
+    pub by_symbol: BTreeMap<String, SymbolPnl>,
+    /// Equity per account at the last mark of the day
+    #[serde(default)]
+    pub by_account: BTreeMap<String, Decimal>,
+    /// Exposure against allocation per strategy at the last mark of the day
+    #[serde(default)]
+    pub by_strategy: BTreeMap<String, StrategyAllocation>,
+    pub signals_proposed: u64,
+    pub signals_verified: u64,
+    pub orders_executed: u64,
+    /// Verifier rejections (hallucinations) by error code
+    pub hallucinations: BTreeMap<String, u64>,
+    /// Peak-to-trough drawdown of the intraday equity curve, as a fraction
+    pub max_drawdown: Decimal,
+    pub breaker_events: Vec<BreakerEvent>,
+    pub worst_slippage: Option<SlippageSample>,
+    pub incidents: Vec<Incident>,
+    /// Opportunity funnel stage counts per symbol
+    pub funnel: FunnelCounts,
+    /// Live-vs-shadow comparison per shadow strategy
+    #[serde(default)]
+    pub shadow: BTreeMap<String, ShadowComparison>,
+}
+
+impl DailyReport {
+    /// Build a report from one day's telemetry (pure: no clock, no I/O)
+    pub fn generate(day: &DailyTelemetry) -> Self {
+        let mut by_symbol: BTreeMap<String, SymbolPnl> = BTreeMap::new();
+        for (symbol, pnl) in &day.realized_pnl {
+            by_symbol.entry(symbol.clone())
+                .or_insert(SymbolPnl { realized: Decimal::ZERO, unrealized: Decimal::ZERO })
+                .realized += *pnl;
+        }
+        for (symbol, pnl) in &day.unrealized_pnl {
+            by_symbol.entry(symbol.clone())
+                .or_insert(SymbolPnl { realized: Decimal::ZERO, unrealized: Decimal::ZERO })
+                .unrealized += *pnl;
+        }
+
+        let realized_pnl: Decimal = day.realized_pnl.values().sum();
+        let unrealized_pnl: Decimal = day.unrealized_pnl.values().sum();
+
+        Self {
+            date: day.date,
+            realized_pnl,
+            unrealized_pnl,
+            fees: day.fees,
+            funding: day.funding,
+            net_pnl: realized_pnl + unrealized_pnl - day.fees + day.funding,
+            by_symbol,
+            by_account: day.account_equity.clone(),
+            by_strategy: day.strategies.clone(),
+            signals_proposed: day.signals_proposed,
+            signals_verified: day.signals_verified,
+            orders_executed: day.orders_executed,
+            hallucinations: day.rejections.clone(),
+            max_drawdown: max_drawdown(day.equity_curve.iter().map(|(_, equity)| *equity)),
+            breaker_events: day.breaker_events.clone(),
+            worst_slippage: day.worst_slippage.clone(),
+            incidents: day.incidents.clone(),
+            funnel: day.funnel.clone(),
+            shadow: day.shadow.clone(),
+        }
+    }
+
+    pub fn hallucination_count(&self) -> u64 {
+        self.hallucinations.values().sum()
+    }
+
+    /// Human-readable summary
+    pub fn render_text(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "Daily report {}", self.date);
+        let _ = writeln!(out, "  Net PnL:        {}", self.net_pnl);
+        let _ = writeln!(out, "  Realized:       {}", self.realized_pnl);
+        let _ = writeln!(out, "  Unrealized:     {}", self.unrealized_pnl);
+        let _ = writeln!(out, "  Fees:           {}", self.fees);
+        let _ = writeln!(out, "  Funding:        {}", self.funding);
+        let _ = writeln!(out, "  Max drawdown:   {}%", (self.max_drawdown * Decimal::ONE_HUNDRED).round_dp(2));
+        let _ = writeln!(out, "  Signals:        {} proposed / {} verified / {} executed",
+            self.signals_proposed, self.signals_verified, self.orders_executed);
+        render_breakdown(&mut out, &self.by_symbol, &self.hallucinations);
+        render_accounts(&mut out, &self.by_account);
+        render_strategies(&mut out, &self.by_strategy);
+        let _ = writeln!(out, "  Breaker events: {}", self.breaker_events.len());
+        for event in &self.breaker_events {
+            let scope = event.account.as_deref().unwrap_or("all accounts");
+            let _ = writeln!(out, "    {} {:?} ({}): {}", event.timestamp.format("%H:%M:%S"), event.state, scope, event.reason);
+        }
+        render_slippage(&mut out, self.worst_slippage.as_ref());
+        render_funnel(&mut out, &self.funnel);
+        render_shadow(&mut out, &self.shadow);
+        let _ = writeln!(out, "  Incidents:      {}", self.incidents.len());
+        for incident in &self.incidents {
+            let state = match &incident.state {
+                IncidentState::Open => "OPEN".to_string(),
+                IncidentState::Acknowledged { by, .. } => format!("acked by {}", by),
+                IncidentState::Resolved { by, .. } => format!("resolved by {}", by),
+            };
+            let _ = writeln!(out, "    #{} [{:?}] {} ({})", incident.id, incident.alert.severity, incident.alert.message, state);
+        }
+        out
+    }
+}
+
+/// Weekly rollup of daily reports
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WeeklyReport {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub days: usize,
+    pub realized_pnl: Decimal,
+    /// Unrealized PnL as of the last day in the week
+    pub unrealized_pnl: Decimal,
+    pub fees: Decimal,
+    pub funding: Decimal,
+    pub net_pnl: Decimal,
+    pub by_symbol: BTreeMap<String, SymbolPnl>,
+    /// Equity per account as of the last day in the week
+    #[serde(default)]
+    pub by_account: BTreeMap<String, Decimal>,
+    /// Exposure against allocation per strategy as of the last day in the week
+    #[serde(default)]
+    pub by_strategy: BTreeMap<String, StrategyAllocation>,
+    pub signals_proposed: u64,
+    pub signals_verified: u64,
+    pub orders_executed: u64,
+    pub hallucinations: BTreeMap<String, u64>,
+    /// Worst single-day drawdown in the week
+    pub max_drawdown: Decimal,
+    pub breaker_events: usize,
+    pub worst_slippage: Option<SlippageSample>,
+    pub incidents: usize,
+    pub funnel: FunnelCounts,
+}
+
+impl WeeklyReport {
+    /// Aggregate daily reports (order-independent; returns None if empty)
+    pub fn rollup(dailies: &[DailyReport]) -> Option<Self> {
+        let mut sorted: Vec<&DailyReport> = dailies.iter().collect();
+        sorted.sort_by_key(|d| d.date);
+        let first = sorted.first()?;
+        let last = sorted.last()?;
+
+        let mut by_symbol: BTreeMap<String, SymbolPnl> = BTreeMap::new();
+        let mut hallucinations: BTreeMap<String, u64> = BTreeMap::new();
+        let mut worst_slippage: Option<SlippageSample> = None;
+        let mut funnel = FunnelCounts::new();
+
+        for day in &sorted {
+            for (symbol, stages) in &day.funnel {
+                let entry = funnel.entry(symbol.clone()).or_default();
+                for (stage, count) in stages {
+                    *entry.entry(*stage).or_insert(0) += count;
+                }
+            }
+            for (symbol, pnl) in &day.by_symbol {
+                by_symbol.entry(symbol.clone())
+                    .or_insert(SymbolPnl { realized: Decimal::ZERO, unrealized: Decimal::ZERO })
+                    .realized += pnl.realized;
+            }
+            for (reason, count) in &day.hallucinations {
+                *hallucinations.entry(reason.clone()).or_insert(0) += count;
+            }
+            if let Some(sample) = &day.worst_slippage {
+                let worse = worst_slippage.as_ref()
+                    .is_none_or(|worst| sample.slippage_bps > worst.slippage_bps);
+                if worse {
+                    worst_slippage = Some(sample.clone());
+                }
+            }
+        }
+        for (symbol, pnl) in &last.by_symbol {
+            by_symbol.entry(symbol.clone())
+                .or_insert(SymbolPnl { realized: Decimal::ZERO, unrealized: Decimal::ZERO })
+                .unrealized = pnl.unrealized;
+        }
+
+        let realized_pnl: Decimal = sorted.iter().map(|d| d.realized_pnl).sum();
+        let fees: Decimal = sorted.iter().map(|d| d.fees).sum();
+        let funding: Decimal = sorted.iter().map(|d| d.funding).sum();
+
+        Some(Self {
+            start: first.date,
+            end: last.date,
+            days: sorted.len(),
+            realized_pnl,
+            unrealized_pnl: last.unrealized_pnl,
+            fees,
+            funding,
+            net_pnl: realized_pnl + last.unrealized_pnl - fees + funding,
+            by_symbol,
+            by_account: last.by_account.clone(),
+            by_strategy: last.by_strategy.clone(),
+            signals_proposed: sorted.iter().map(|d| d.signals_proposed).sum(),
+            signals_verified: sorted.iter().map(|d| d.signals_verified).sum(),
+            orders_executed: sorted.iter().map(|d| d.orders_executed).sum(),
+            hallucinations,
+            max_drawdown: sorted.iter().map(|d| d.max_drawdown).max().unwrap_or(Decimal::ZERO),
+            breaker_events: sorted.iter().map(|d| d.breaker_events.len()).sum(),
+            worst_slippage,
+            incidents: sorted.iter().map(|d| d.incidents.len()).sum(),
+            funnel,
+        })
+    }
+
+    /// Human-readable summary
+    pub fn render_text(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "Weekly report {} .. {} ({} days)", self.start, self.end, self.days);
+        let _ = writeln!(out, "  Net PnL:        {}", self.net_pnl);
+        let _ = writeln!(out, "  Realized:       {}", self.realized_pnl);
+        let _ = writeln!(out, "  Unrealized:     {}", self.unrealized_pnl);
+        let _ = writeln!(out, "  Fees:           {}", self.fees);
+        let _ = writeln!(out, "  Funding:        {}", self.funding);
+        let _ = writeln!(out, "  Worst drawdown: {}%", (self.max_drawdown * Decimal::ONE_HUNDRED).round_dp(2));
+        let _ = writeln!(out, "  Signals:        {} proposed / {} verified / {} executed",
+            self.signals_proposed, self.signals_verified, self.orders_executed);
+        render_breakdown(&mut out, &self.by_symbol, &self.hallucinations);
+        render_accounts(&mut out, &self.by_account);
+        render_strategies(&mut out, &self.by_strategy);
+        let _ = writeln!(out, "  Breaker events: {}", self.breaker_events);
+        render_slippage(&mut out, self.worst_slippage.as_ref());
+        render_funnel(&mut out, &self.funnel);
+        let _ = writeln!(out, "  Incidents:      {}", self.incidents);
+        out
+    }
+}
+
+/// Equity per account; omitted with a single account (it is the total)
+fn render_accounts(out: &mut String, by_account: &BTreeMap<String, Decimal>) {
+    if by_account.len() < 2 {
+        return;
+    }
+    let _ = writeln!(out, "  By account:");
+    for (account, equity) in by_account {
+        let _ = writeln!(out, "    {:<12} equity {}", account, equity);
+    }
+}
+
+/// Strategies with an allocation or an edge-check opt-out, or more than one
+/// strategy trading
+fn render_strategies(out: &mut String, by_strategy: &BTreeMap<String, StrategyAllocation>) {
+    let notable = |strategy: &StrategyAllocation| strategy.allocation.is_some() || strategy.edge_opt_out;
+    if !by_strategy.values().any(notable) && by_strategy.len() < 2 {
+        return;
+    }
+    let _ = writeln!(out, "  By strategy:");
+    for (strategy, held) in by_strategy {
+        let edge = if held.edge_opt_out { "; no edge check" } else { "" };
+        match (held.allocation, held.budget) {
+            (Some(allocation), Some(budget)) => {
+                let _ = writeln!(out, "    {:<12} exposure {} of budget {} ({}% of equity{})",
+                    strategy, held.exposure, budget, (allocation * Decimal::ONE_HUNDRED).normalize(), edge);
+            }
+            _ => {
+                let _ = writeln!(out, "    {:<12} exposure {} (unbudgeted{})", strategy, held.exposure, edge);
+            }
+        }
+    }
+}
+
+fn render_breakdown(out: &mut String, by_symbol: &BTreeMap<String, SymbolPnl>, hallucinations: &BTreeMap<String, u64>) {
+    let _ = writeln!(out, "  By symbol:");
+    for (symbol, pnl) in by_symbol {
+        let _ = writeln!(out, "    {:<12} realized {} unrealized {}", symbol, pnl.realized, pnl.unrealized);
+    }
+    let _ = writeln!(out, "  Hallucinations: {}", hallucinations.values().sum::<u64>());
+    for (code, count) in hallucinations {
+        let _ = writeln!(out, "    {:>6}  {}", count, code);
+    }
+}
+
+fn render_slippage(out: &mut String, worst: Option<&SlippageSample>) {
+    match worst {
+        Some(sample) => {
+            let _ = writeln!(out, "  Worst slippage: {} bps on {}", sample.slippage_bps, sample.symbol);
+        }
+        None => {
+            let _ = writeln!(out, "  Worst slippage: n/a");
+        }
+    }
+}
+
+fn render_funnel(out: &mut String, funnel: &FunnelCounts) {
+    let _ = writeln!(out, "  Funnel:");
+    for (symbol, counts) in funnel {
+        let stages: Vec<String> = counts.iter().map(|(stage, count)| format!("{} {}", stage, count)).collect();
+        let _ = writeln!(out, "    {:<12} {}", symbol, stages.join(" > "));
+        let ratios: Vec<String> = conversions(counts).iter()
+            .filter_map(|c| c.ratio.map(|ratio| format!("{}->{} {:.1}%", c.from, c.to, ratio * 100.0)))
+            .collect();
+        if !ratios.is_empty() {
+            let _ = writeln!(out, "    {:<12} {}", "", ratios.join(", "));
+        }
+    }
+}
+
+fn render_shadow(out: &mut String, shadow: &BTreeMap<String, ShadowComparison>) {
+    if shadow.is_empty() {
+        return;
+    }
+    let _ = writeln!(out, "  Shadow vs live:");
+    for (strategy, comparison) in shadow {
+        let _ = writeln!(out, "    {:<12} agree {}% of {} books ({}% when signalled), {} skipped",
+            strategy,
+            (comparison.agreement_rate() * Decimal::ONE_HUNDRED).round_dp(1),
+            comparison.books,
+            (comparison.signal_agreement_rate() * Decimal::ONE_HUNDRED).round_dp(1),
+            comparison.skipped);
+        let _ = writeln!(out, "    {:<12} signals live {} / shadow {} ({} rejected); PnL live {} / shadow {}",
+            "", comparison.live_signals, comparison.shadow_signals, comparison.shadow_rejections,
+            comparison.live_pnl.round_dp(2), comparison.shadow_pnl.round_dp(2));
+        for divergence in comparison.divergences.iter().take(5) {
+            let side = |side: Option<axiom_core::Side>| side.map_or_else(|| "none".to_string(), |side| format!("{:?}", side));
+            let features = divergence.features.as_ref().map_or_else(String::new, |f| format!(
+                " (mid {}, spread {}, imbalance {}, contradiction {})",
+                f.mid, f.spread_pct.round_dp(6), f.depth_imbalance.round_dp(4), f.contradiction_score.round_dp(4)));
+            let _ = writeln!(out, "    {:<12} {} {}: live {}, shadow {}{}", "", divergence.at.format("%H:%M:%S"),
+                divergence.symbol.0, side(divergence.live), side(divergence.shadow), features);
+        }
+    }
+}
+
+/// Largest peak-to-trough decline, as a fraction of the peak
+fn max_drawdown(equity: impl Iterator<Item = Decimal>) -> Decimal {
+    let mut peak = Decimal::ZERO;
+    let mut worst = Decimal::ZERO;
+    for value in equity {
+        if value > peak {
+            peak = value;
+        } else if peak > Decimal::ZERO {
+            worst = worst.max((peak - value) / peak);
+        }
+    }
+    worst
+}
+
+/// Writes reports to disk and pushes them through the alert sink
+pub struct ReportPublisher {
+    dir: PathBuf,
+    alert_sink: Arc<dyn AlertSink>,
+}
+
+impl ReportPublisher {
+    pub fn new(dir: impl AsRef<Path>, alert_sink: Arc<dyn AlertSink>) -> std::io::Result<Self> {
+        std::fs::create_dir_all(dir.as_ref())?;
+        Ok(Self {
+            dir: dir.as_ref().to_path_buf(),
+            alert_sink,
+        })
+    }
+
+    pub fn publish_daily(&self, report: &DailyReport) -> std::io::Result<()> {
+        let stem = format!("daily-{}", report.date.format("%Y-%m-%d"));
+        self.write(&stem, report, &report.render_text())
+    }
+
+    pub fn publish_weekly(&self, report: &WeeklyReport) -> std::io::Result<()> {
+        let stem = format!("weekly-{}", report.start.format("%Y-%m-%d"));
+        self.write(&stem, report, &report.render_text())
+    }
+
+    fn write<T: Serialize>(&self, stem: &str, report: &T, text: &str) -> std::io::Result<()> {
+        let json = serde_json::to_string_pretty(report)?;
+        std::fs::write(self.dir.join(format!("{}.json", stem)), json)?;
+        std::fs::write(self.dir.join(format!("{}.txt", stem)), text)?;
+        self.alert_sink.send(&Alert::new(AlertSeverity::Info, "reports", text));
+        Ok(())
+    }
+}
+
+/// Daily report schedule
+pub struct ReportScheduler {
+    collector: Arc<TelemetryCollector>,
+    store: TelemetryStore,
+    publisher: ReportPublisher,
+    /// UTC time of day at which the previous day is closed out
+    daily_time_utc: NaiveTime,
+    incidents: Option<Arc<IncidentTracker>>,
+    monitor: Option<Arc<Mutex<SystemMonitor>>>,
+}
+
+impl ReportScheduler {
+    pub fn new(
+        collector: Arc<TelemetryCollector>,
+        store: TelemetryStore,
+        publisher: ReportPublisher,
+        daily_time_utc: NaiveTime,
+    ) -> Self {
+        Self {
+            collector,
+            store,
+            publisher,
+            daily_time_utc,
+            incidents: None,
+            monitor: None,
+        }
+    }
+
+    /// Include the monitor's daily funnel counts in each day's record
+    pub fn with_monitor(mut self, monitor: Arc<Mutex<SystemMonitor>>) -> Self {
+        self.monitor = Some(monitor);
+        self
+    }
+
+    /// Include incident history in each day's record
+    pub fn with_incidents(mut self, incidents: Arc<IncidentTracker>) -> Self {
+        self.incidents = Some(incidents);
+        self
+    }
+
+    /// Close out the collector's day, persist it, and publish its report
+    pub async fn close_day(&self, next: NaiveDate) -> std::io::Result<DailyReport> {
+        let mut day = self.collector.roll_day(next).await;
+        if let Some(incidents) = &self.incidents {
+            day.incidents = incidents.drain_for_history();
+        }
+        if let Some(monitor) = &self.monitor {
+            if let Ok(mut monitor) = monitor.lock() {
+                day.funnel = monitor.take_daily_funnel();
+            }
+        }
+        self.store.save_day(&day)?;
+        self.publish_for(day.date)
+    }
+
+    /// Regenerate and publish the report for a stored day
+    ///
+    /// On Sundays the week ending that day is rolled up and published too.
+    pub fn publish_for(&self, date: NaiveDate) -> std::io::Result<DailyReport> {
+        let day = self.store.load_day(date)?.unwrap_or_else(|| DailyTelemetry::new(date));
+        let report = DailyReport::generate(&day);
+        self.publisher.publish_daily(&report)?;
+
+        if date.weekday() == chrono::Weekday::Sun {
+            let start = date - ChronoDuration::days(6);
+            let dailies: Vec<DailyReport> = self.store.load_range(start, date)?
+                .iter()
+                .map(DailyReport::generate)
+                .collect();
+            if let Some(weekly) = WeeklyReport::rollup(&dailies) {
+                self.publisher.publish_weekly(&weekly)?;
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Run the schedule on its own task
+    pub fn spawn(self: Arc<Self>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                let now = Utc::now();
+                let mut next_run = now.date_naive().and_time(self.daily_time_utc).and_utc();
+                if next_run <= now {
+                    next_run += ChronoDuration::days(1);
+                }
+                let wait = (next_run - now).to_std().unwrap_or_default();
+                tokio::time::sleep(wait).await;
+
+                let today = Utc::now().date_naive();
+                match self.close_day(today).await {
+                    Ok(report) => info!("Published daily report for {}", report.date),
+                    Err(e) => error!("Failed to publish daily report: {}", e),
+                }
+            }
+        })
+    }
+}
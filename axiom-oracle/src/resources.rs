@@ -0,0 +1,270 @@
+//! Resource Monitoring: Process Memory, CPU, Tasks, and Queue Depths
+//!
+//! Samples process RSS and CPU from procfs, the tokio alive-task count, and
+//! the depth of every registered queue. Samples are published to telemetry
+//! and the metrics recorder, and a sustained, monotonic RSS climb raises an
+//! alert before a leak becomes an outage.
+
+use crate::alerts::{Alert, AlertSeverity, AlertSink};
+use crate::telemetry::TelemetryCollector;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// Depth of one registered queue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueueDepth {
+    pub depth: usize,
+    pub capacity: usize,
+}
+
+/// One resource sample
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResourceSnapshot {
+    pub rss_bytes: Option<u64>,
+    /// CPU usage since the previous sample (100 = one core fully busy)
+    pub cpu_percent: Option<f64>,
+    pub alive_tasks: usize,
+    pub queues: BTreeMap<String, QueueDepth>,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Handle a producer/consumer pair uses to report its queue depth
+#[derive(Clone)]
+pub struct QueueGauge {
+    depth: Arc<AtomicUsize>,
+}
+
+impl QueueGauge {
+    pub fn push(&self) {
+        self.depth.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn pop(&self) {
+        let _ = self.depth.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |d| d.checked_sub(1));
+    }
+
+    pub fn set(&self, depth: usize) {
+        self.depth.store(depth, Ordering::Relaxed);
+    }
+}
+
+type DepthFn = Box<dyn Fn() -> Option<QueueDepth> + Send + Sync>;
+
+/// RSS growth alert settings
+#[derive(Debug, Clone)]
+pub struct ResourceMonitorConfig {
+    /// Window over which RSS growth is assessed
+    pub rss_window: Duration,
+    /// Alert when RSS rises monotonically faster than this over the window
+    pub rss_slope_alert_bytes_per_hour: u64,
+}
+
+impl Default for ResourceMonitorConfig {
+    fn default() -> Self {
+        Self {
+            rss_window: Duration::hours(1),
+            rss_slope_alert_bytes_per_hour: 256 * 1024 * 1024,
+        }
+    }
+}
+
+struct CpuSample {
+    cpu_ticks: u64,
+    at: std::time::Instant,
+}
+
+/// Process resource sampler
+pub struct ResourceMonitor {
+    config: ResourceMonitorConfig,
+    telemetry: Arc<TelemetryCollector>,
+    alert_sink: Arc<dyn AlertSink>,
+    queues: Mutex<BTreeMap<String, DepthFn>>,
+    rss_history: Mutex<VecDeque<(DateTime<Utc>, u64)>>,
+    last_cpu: Mutex<Option<CpuSample>>,
+    rss_alerting: Mutex<bool>,
+}
+
+impl ResourceMonitor {
+    pub fn new(
+        config: ResourceMonitorConfig,
+        telemetry: Arc<TelemetryCollector>,
+        alert_sink: Arc<dyn AlertSink>,
+    ) -> Self {
+        Self {
+            config,
+            telemetry,
+            alert_sink,
+            queues: Mutex::new(BTreeMap::new()),
+            rss_history: Mutex::new(VecDeque::new()),
+            last_cpu: Mutex::new(None),
+            rss_alerting: Mutex::new(false),
+        }
+    }
+
+    /// Track a bounded tokio channel by its sender (does not keep the channel open)
+    pub fn register_channel<T: Send + 'static>(&self, name: &str, sender: &mpsc::Sender<T>) {
+        let weak = sender.downgrade();
+        self.register(name, Box::new(move || {
+            weak.upgrade().map(|sender| QueueDepth {
+                depth: sender.max_capacity() - sender.capacity(),
+                capacity: sender.max_capacity(),
+            })
+        }));
+    }
+
+    /// Register any other queue; the returned gauge is updated by its owner
+    pub fn register_queue(&self, name: &str, capacity: usize) -> QueueGauge {
+        let gauge = QueueGauge { depth: Arc::new(AtomicUsize::new(0)) };
+        let depth = gauge.depth.clone();
+        self.register(name, Box::new(move || {
+            Some(QueueDepth { depth: depth.load(Ordering::Relaxed), capacity })
+        }));
+        gauge
+    }
+
+    fn register(&self, name: &str, depth: DepthFn) {
+        if let Ok(mut queues) = self.queues.lock() {
+            queues.insert(name.to_string(), depth);
+        }
+    }
+
+    /// Take one sample, publish it, and run the RSS growth check
+    pub async fn sample(&self) -> ResourceSnapshot {
+        let now = Utc::now();
+        let rss_bytes = read_rss_bytes();
+        let cpu_percent = self.cpu_percent();
+        let alive_tasks = tokio::runtime::Handle::try_current()
+            .map(|handle| handle.metrics().num_alive_tasks())
+            .unwrap_or(0);
+
+        let mut queues = BTreeMap::new();
+        if let Ok(mut registered) = self.queues.lock() {
+            // Channels whose senders are all gone have closed; stop reporting them
+            registered.retain(|name, depth| match depth() {
+                Some(depth) => {
+                    queues.insert(name.clone(), depth);
+                    true
+                }
+                None => false,
+            });
+        }
+
+        let snapshot = ResourceSnapshot {
+            rss_bytes,
+            cpu_percent,
+            alive_tasks,
+            queues,
+            timestamp: now,
+        };
+
+        publish_metrics(&snapshot);
+        if let Some(rss) = rss_bytes {
+            self.check_rss_growth(now, rss);
+        }
+        self.telemetry.update_resources(snapshot.clone()).await;
+
+        snapshot
+    }
+
+    fn cpu_percent(&self) -> Option<f64> {
+        let cpu_ticks = read_cpu_ticks()?;
+        let at = std::time::Instant::now();
+        let mut last = self.last_cpu.lock().ok()?;
+        let previous = last.replace(CpuSample { cpu_ticks, at })?;
+
+        let wall = at.duration_since(previous.at).as_secs_f64();
+        if wall <= 0.0 {
+            return None;
+        }
+        let cpu_secs = cpu_ticks.saturating_sub(previous.cpu_ticks) as f64 / CLOCK_TICKS_PER_SEC;
+        Some(cpu_secs / wall * 100.0)
+    }
+
+    /// Alert when RSS has only grown across a full window, faster than the configured slope
+    fn check_rss_growth(&self, now: DateTime<Utc>, rss: u64) {
+        let Ok(mut history) = self.rss_history.lock() else { return };
+        history.push_back((now, rss));
+        while history.front().is_some_and(|(at, _)| now - *at > self.config.rss_window) {
+            history.pop_front();
+        }
+
+        let (Some(&(first_at, first_rss)), Some(&(_, last_rss))) = (history.front(), history.back()) else {
+            return;
+        };
+        let span = now - first_at;
+        // Require (nearly) a full window of history before judging a trend
+        let full_window = span * 10 >= self.config.rss_window * 9;
+        let monotonic = history.iter().zip(history.iter().skip(1)).all(|(a, b)| b.1 >= a.1);
+        let hours = span.num_seconds() as f64 / 3600.0;
+        let slope = if hours > 0.0 { (last_rss - first_rss.min(last_rss)) as f64 / hours } else { 0.0 };
+        let growing = full_window && monotonic && slope > self.config.rss_slope_alert_bytes_per_hour as f64;
+        drop(history);
+
+        let Ok(mut alerting) = self.rss_alerting.lock() else { return };
+        if growing && !*alerting {
+            self.alert_sink.send(&Alert::new(
+                AlertSeverity::Warning,
+                "resources",
+                format!(
+                    "RSS grew monotonically from {} MB to {} MB over {} min ({:.0} MB/h); possible leak",
+                    first_rss / (1024 * 1024),
+                    last_rss / (1024 * 1024),
+                    span.num_minutes(),
+                    slope / (1024.0 * 1024.0),
+                ),
+            ));
+        }
+        *alerting = growing;
+    }
+
+    /// Sample on its own task
+    pub fn spawn(self: Arc<Self>, interval: std::time::Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                self.sample().await;
+            }
+        })
+    }
+}
+
+fn publish_metrics(snapshot: &ResourceSnapshot) {
+    if let Some(rss) = snapshot.rss_bytes {
+        metrics::gauge!("axiom_process_rss_bytes").set(rss as f64);
+    }
+    if let Some(cpu) = snapshot.cpu_percent {
+        metrics::gauge!("axiom_process_cpu_percent").set(cpu);
+    }
+    metrics::gauge!("axiom_tokio_alive_tasks").set(snapshot.alive_tasks as f64);
+    for (name, depth) in &snapshot.queues {
+        metrics::gauge!("axiom_queue_depth", "queue" => name.clone()).set(depth.depth as f64);
+        metrics::gauge!("axiom_queue_capacity", "queue" => name.clone()).set(depth.capacity as f64);
+    }
+}
+
+/// USER_HZ; 100 on every mainstream Linux configuration
+const CLOCK_TICKS_PER_SEC: f64 = 100.0;
+
+/// Resident set size from /proc/self/status (Linux only)
+fn read_rss_bytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb * 1024)
+}
+
+/// User + system CPU ticks from /proc/self/stat (Linux only)
+fn read_cpu_ticks() -> Option<u64> {
+    let stat = std::fs::read_to_string("/proc/self/stat").ok()?;
+    // The command name may contain spaces; fields resume after the closing paren
+    let fields: Vec<&str> = stat.rsplit_once(')')?.1.split_whitespace().collect();
+    let utime: u64 = fields.get(11)?.parse().ok()?;
+    let stime: u64 = fields.get(12)?.parse().ok()?;
+    Some(utime + stime)
+}
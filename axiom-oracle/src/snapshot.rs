@@ -0,0 +1,234 @@
+//! State Snapshots: One-File System Dump for Debugging
+//!
+//! Assembles health, portfolio, and every registered component's state into a
+//! single versioned JSON document, small enough to attach to an incident
+//! ticket. Order books are summarized rather than embedded, secrets are
+//! redacted, and the file is written atomically.
+
+use crate::incidents::{Incident, IncidentTracker};
+use crate::resources::ResourceSnapshot;
+use crate::telemetry::TelemetryCollector;
+use axiom_core::{OrderBook, Portfolio, Price, SystemHealth};
+use rust_decimal::Decimal;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tracing::{error, warn};
+
+/// Snapshot document schema version (bump on any structural change)
+pub const SNAPSHOT_SCHEMA_VERSION: u32 = 3;
+
+/// Upper bound on the serialized snapshot size
+pub const MAX_SNAPSHOT_BYTES: usize = 1024 * 1024;
+
+/// Key fragments whose values are never written to a snapshot
+const SECRET_KEY_FRAGMENTS: &[&str] = &["secret", "password", "passphrase", "token", "private", "api_key", "apikey"];
+
+/// Build identification
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuildInfo {
+    pub version: String,
+    pub git_commit: Option<String>,
+}
+
+impl BuildInfo {
+    pub fn current() -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            git_commit: option_env!("AXIOM_GIT_COMMIT").map(str::to_string),
+        }
+    }
+}
+
+/// Top-of-book summary standing in for a full order book
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookSummary {
+    pub symbol: String,
+    pub venue: String,
+    pub best_bid: Option<Price>,
+    pub best_ask: Option<Price>,
+    pub bid_levels: usize,
+    pub ask_levels: usize,
+    pub bid_depth: Decimal,
+    pub ask_depth: Decimal,
+    pub sequence: u64,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl BookSummary {
+    pub fn from_book(book: &OrderBook) -> Self {
+        Self {
+            symbol: book.symbol.0.clone(),
+            venue: book.venue.to_string(),
+            best_bid: book.bids.first().map(|level| level.price),
+            best_ask: book.asks.first().map(|level| level.price),
+            bid_levels: book.bids.len(),
+            ask_levels: book.asks.len(),
+            bid_depth: book.bids.iter().map(|level| level.quantity.get()).sum(),
+            ask_depth: book.asks.iter().map(|level| level.quantity.get()).sum(),
+            sequence: book.sequence,
+            timestamp: book.timestamp,
+        }
+    }
+}
+
+/// The exported document
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SystemSnapshot {
+    pub schema_version: u32,
+    pub taken_at: DateTime<Utc>,
+    /// Why the snapshot was taken ("periodic", "on_demand", "fatal: ...")
+    pub trigger: String,
+    pub build: BuildInfo,
+    pub health: Option<SystemHealth>,
+    pub resources: Option<ResourceSnapshot>,
+    pub portfolio: Option<Portfolio>,
+    pub open_incidents: Vec<Incident>,
+    /// Registered component sections (open orders, venue health, data quality, config, warm-up, ...)
+    pub sections: BTreeMap<String, Value>,
+}
+
+type SectionFn = Box<dyn Fn() -> Value + Send + Sync>;
+type PortfolioFn = Box<dyn Fn() -> Portfolio + Send + Sync>;
+
+/// Snapshot export error
+#[derive(Debug, thiserror::Error)]
+pub enum SnapshotError {
+    #[error("Snapshot I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Snapshot serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+}
+
+/// Assembles and writes system snapshots
+pub struct SnapshotExporter {
+    path: PathBuf,
+    telemetry: Arc<TelemetryCollector>,
+    portfolio: Option<PortfolioFn>,
+    incidents: Option<Arc<IncidentTracker>>,
+    sections: BTreeMap<String, SectionFn>,
+}
+
+impl SnapshotExporter {
+    pub fn new(path: impl AsRef<Path>, telemetry: Arc<TelemetryCollector>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            telemetry,
+            portfolio: None,
+            incidents: None,
+            sections: BTreeMap::new(),
+        }
+    }
+
+    pub fn with_portfolio(mut self, portfolio: impl Fn() -> Portfolio + Send + Sync + 'static) -> Self {
+        self.portfolio = Some(Box::new(portfolio));
+        self
+    }
+
+    pub fn with_incidents(mut self, incidents: Arc<IncidentTracker>) -> Self {
+        self.incidents = Some(incidents);
+        self
+    }
+
+    /// Add a named section produced on demand by a component
+    pub fn with_section(mut self, name: &str, section: impl Fn() -> Value + Send + Sync + 'static) -> Self {
+        self.sections.insert(name.to_string(), Box::new(section));
+        self
+    }
+
+    /// Assemble a snapshot from current component state
+    pub async fn capture(&self, trigger: &str) -> SystemSnapshot {
+        let mut sections = BTreeMap::new();
+        for (name, section) in &self.sections {
+            let mut value = section();
+            redact(&mut value);
+            sections.insert(name.clone(), value);
+        }
+
+        SystemSnapshot {
+            schema_version: SNAPSHOT_SCHEMA_VERSION,
+            taken_at: Utc::now(),
+            trigger: trigger.to_string(),
+            build: BuildInfo::current(),
+            health: self.telemetry.get_health().await,
+            resources: self.telemetry.get_resources().await,
+            portfolio: self.portfolio.as_ref().map(|portfolio| portfolio()),
+            open_incidents: self.incidents.as_ref()
+                .map(|incidents| incidents.open_incidents())
+                .unwrap_or_default(),
+            sections,
+        }
+    }
+
+    /// Capture and write a snapshot (temp file + rename)
+    pub async fn export(&self, trigger: &str) -> Result<PathBuf, SnapshotError> {
+        let mut snapshot = self.capture(trigger).await;
+        let mut json = serde_json::to_vec_pretty(&snapshot)?;
+
+        // Over budget: drop the largest sections until it fits
+        while json.len() > MAX_SNAPSHOT_BYTES {
+            let largest = snapshot.sections.iter()
+                .filter(|(_, value)| !is_truncated(value))
+                .map(|(name, value)| (name.clone(), serde_json::to_vec(value).map(|v| v.len()).unwrap_or(0)))
+                .max_by_key(|(_, size)| *size);
+
+            let Some((name, size)) = largest else { break };
+            warn!("Snapshot section '{}' ({} bytes) truncated to fit size limit", name, size);
+            snapshot.sections.insert(name, serde_json::json!({ "truncated": true, "bytes": size }));
+            json = serde_json::to_vec_pretty(&snapshot)?;
+        }
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = self.path.with_extension("json.tmp");
+        std::fs::write(&tmp, &json)?;
+        std::fs::rename(&tmp, &self.path)?;
+
+        Ok(self.path.clone())
+    }
+
+    /// Export periodically on its own task until `shutdown` turns true
+    pub fn spawn(self: Arc<Self>, interval: std::time::Duration, mut shutdown: watch::Receiver<bool>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => {}
+                    _ = shutdown.wait_for(|stop| *stop) => break,
+                }
+                if let Err(e) = self.export("periodic").await {
+                    error!("Failed to export system snapshot: {}", e);
+                }
+            }
+        })
+    }
+}
+
+fn is_truncated(value: &Value) -> bool {
+    value.get("truncated").and_then(Value::as_bool).unwrap_or(false)
+}
+
+/// Replace the value of any secret-looking key, recursively
+pub fn redact(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                let key = key.to_ascii_lowercase();
+                if SECRET_KEY_FRAGMENTS.iter().any(|fragment| key.contains(fragment)) {
+                    *value = Value::String("[REDACTED]".to_string());
+                } else {
+                    redact(value);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact),
+        _ => {}
+    }
+}
@@ -2,40 +2,295 @@
 //!
 //! Collects and aggregates metrics from all system components.
 
-use axiom_core::SystemHealth;
+use crate::incidents::Incident;
+use crate::monitoring::FunnelCounts;
+use crate::resources::ResourceSnapshot;
+use axiom_core::{AccountId, SystemHealth, CircuitBreakerState, ShadowComparison, ShadowObservation, Symbol, ViolationSet};
+use rust_decimal::Decimal;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
 use tracing::info;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+/// Circuit breaker transition recorded during the day
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BreakerEvent {
+    pub timestamp: DateTime<Utc>,
+    pub state: CircuitBreakerState,
+    pub reason: String,
+    /// The account whose breaker moved (None: the portfolio-wide breaker)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account: Option<String>,
+}
+
+/// Worst observed execution slippage
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SlippageSample {
+    pub symbol: String,
+    pub slippage_bps: Decimal,
+}
+
+/// One strategy's exposure against its capital allocation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StrategyAllocation {
+    /// Gross exposure attributed to the strategy
+    pub exposure: Decimal,
+    /// Configured share of equity (None: unbudgeted)
+    pub allocation: Option<Decimal>,
+    /// Allocation × equity at the time of the mark
+    pub budget: Option<Decimal>,
+    /// Opted out of the expected-edge check ([edge] opt_out)
+    #[serde(default)]
+    pub edge_opt_out: bool,
+}
+
+/// Everything recorded about one UTC trading day
+///
+/// Maps are ordered so serialized records (and reports derived from them)
+/// are byte-identical across runs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DailyTelemetry {
+    pub date: NaiveDate,
+    /// Realized PnL per symbol
+    pub realized_pnl: BTreeMap<String, Decimal>,
+    /// Unrealized PnL per symbol at the last mark of the day
+    pub unrealized_pnl: BTreeMap<String, Decimal>,
+    pub fees: Decimal,
+    /// Net funding received (negative when paid)
+    pub funding: Decimal,
+    pub signals_proposed: u64,
+    pub signals_verified: u64,
+    pub orders_executed: u64,
+    /// Verifier rejections keyed by error code
+    pub rejections: BTreeMap<String, u64>,
+    pub equity_curve: Vec<(DateTime<Utc>, Decimal)>,
+    /// Equity per account at the last mark of the day
+    #[serde(default)]
+    pub account_equity: BTreeMap<String, Decimal>,
+    /// Exposure and allocation per strategy at the last mark of the day
+    #[serde(default)]
+    pub strategies: BTreeMap<String, StrategyAllocation>,
+    pub breaker_events: Vec<BreakerEvent>,
+    pub worst_slippage: Option<SlippageSample>,
+    /// Incidents resolved during the day, plus any still open at close
+    #[serde(default)]
+    pub incidents: Vec<Incident>,
+    /// Opportunity funnel stage counts per symbol
+    #[serde(default)]
+    pub funnel: FunnelCounts,
+    /// Each shadow strategy against the live one
+    #[serde(default)]
+    pub shadow: BTreeMap<String, ShadowComparison>,
+}
+
+impl DailyTelemetry {
+    pub fn new(date: NaiveDate) -> Self {
+        Self {
+            date,
+            realized_pnl: BTreeMap::new(),
+            unrealized_pnl: BTreeMap::new(),
+            fees: Decimal::ZERO,
+            funding: Decimal::ZERO,
+            signals_proposed: 0,
+            signals_verified: 0,
+            orders_executed: 0,
+            rejections: BTreeMap::new(),
+            equity_curve: Vec::new(),
+            account_equity: BTreeMap::new(),
+            strategies: BTreeMap::new(),
+            breaker_events: Vec::new(),
+            worst_slippage: None,
+            incidents: Vec::new(),
+            funnel: FunnelCounts::new(),
+            shadow: BTreeMap::new(),
+        }
+    }
+}
+
 /// Telemetry collector
 pub struct TelemetryCollector {
     health: Arc<RwLock<Option<SystemHealth>>>,
+    today: Arc<RwLock<DailyTelemetry>>,
+    resources: Arc<RwLock<Option<ResourceSnapshot>>>,
 }
 
 impl TelemetryCollector {
     pub fn new() -> Self {
         Self {
             health: Arc::new(RwLock::new(None)),
+            today: Arc::new(RwLock::new(DailyTelemetry::new(Utc::now().date_naive()))),
+            resources: Arc::new(RwLock::new(None)),
         }
     }
 
     /// Update system health
     pub async fn update_health(&self, health: SystemHealth) {
-        let mut h = self.health.write().await;
-        *h = Some(health);
-        
         // Log critical metrics
-        info!("System Health - Consistency Error: {}, Entropy: {}, Circuit Breaker: {:?}",
+        info!("System Health - Consistency Error: {}, Entropy: {}, Circuit Breaker: {:?}, Drawdown: {}",
             health.consistency_error.value,
             health.entropy_count.value,
-            health.circuit_breaker
+            health.circuit_breaker,
+            health.drawdown
         );
+
+        *self.health.write().await = Some(health);
     }
 
     /// Get current health
     pub async fn get_health(&self) -> Option<SystemHealth> {
         self.health.read().await.clone()
     }
+
+    /// Update process resource usage
+    pub async fn update_resources(&self, resources: ResourceSnapshot) {
+        *self.resources.write().await = Some(resources);
+    }
+
+    /// Get latest process resource usage
+    pub async fn get_resources(&self) -> Option<ResourceSnapshot> {
+        self.resources.read().await.clone()
+    }
+
+    pub async fn record_proposal(&self) {
+        self.today.write().await.signals_proposed += 1;
+    }
+
+    pub async fn record_verification(&self) {
+        self.today.write().await.signals_verified += 1;
+    }
+
+    /// Count a verifier rejection under the error code of every violation
+    pub async fn record_rejection(&self, violations: &ViolationSet) {
+        let mut today = self.today.write().await;
+        for code in violations.codes() {
+            *today.rejections.entry(code.to_string()).or_insert(0) += 1;
+        }
+    }
+
+    pub async fn record_execution(&self) {
+        self.today.write().await.orders_executed += 1;
+    }
+
+    /// Record a fill's realized PnL, fee, and slippage versus the intended price
+    pub async fn record_fill(&self, symbol: &Symbol, realized_pnl: Decimal, fee: Decimal, slippage_bps: Decimal) {
+        let mut today = self.today.write().await;
+        *today.realized_pnl.entry(symbol.0.clone()).or_insert(Decimal::ZERO) += realized_pnl;
+        today.fees += fee;
+
+        let worse = today.worst_slippage.as_ref()
+            .is_none_or(|worst| slippage_bps > worst.slippage_bps);
+        if worse {
+            today.worst_slippage = Some(SlippageSample {
+                symbol: symbol.0.clone(),
+                slippage_bps,
+            });
+        }
+    }
+
+    /// Fold shadow-strategy observations into the day's comparisons
+    pub async fn record_shadow(&self, observations: &[ShadowObservation]) {
+        if observations.is_empty() {
+            return;
+        }
+        let mut today = self.today.write().await;
+        for observation in observations {
+            today.shadow.entry(observation.strategy.clone()).or_default().record(observation);
+        }
+    }
+
+    pub async fn record_funding(&self, amount: Decimal) {
+        self.today.write().await.funding += amount;
+    }
+
+    /// Record the latest mark-to-market state
+    pub async fn record_mark(
+        &self,
+        equity: Decimal,
+        unrealized_pnl: BTreeMap<String, Decimal>,
+        account_equity: BTreeMap<String, Decimal>,
+    ) {
+        let mut today = self.today.write().await;
+        today.equity_curve.push((Utc::now(), equity));
+        today.unrealized_pnl = unrealized_pnl;
+        today.account_equity = account_equity;
+    }
+
+    /// Record each strategy's exposure against its allocation
+    pub async fn record_allocations(&self, strategies: BTreeMap<String, StrategyAllocation>) {
+        self.today.write().await.strategies = strategies;
+    }
+
+    /// Record a breaker transition, for `account`'s breaker or (None) the
+    /// portfolio-wide one
+    pub async fn record_breaker(&self, account: Option<&AccountId>, state: CircuitBreakerState, reason: &str) {
+        self.today.write().await.breaker_events.push(BreakerEvent {
+            timestamp: Utc::now(),
+            state,
+            reason: reason.to_string(),
+            account: account.map(|account| account.0.clone()),
+        });
+    }
+
+    /// Snapshot of the current day's record
+    pub async fn today(&self) -> DailyTelemetry {
+        self.today.read().await.clone()
+    }
+
+    /// Close out the current day, starting a fresh record for `next`
+    pub async fn roll_day(&self, next: NaiveDate) -> DailyTelemetry {
+        let mut today = self.today.write().await;
+        std::mem::replace(&mut *today, DailyTelemetry::new(next))
+    }
+}
+
+/// On-disk store of completed daily telemetry records (one JSON file per day)
+pub struct TelemetryStore {
+    dir: PathBuf,
+}
+
+impl TelemetryStore {
+    pub fn new(dir: impl AsRef<Path>) -> std::io::Result<Self> {
+        std::fs::create_dir_all(dir.as_ref())?;
+        Ok(Self {
+            dir: dir.as_ref().to_path_buf(),
+        })
+    }
+
+    fn day_path(&self, date: NaiveDate) -> PathBuf {
+        self.dir.join(format!("{}.json", date.format("%Y-%m-%d")))
+    }
+
+    /// Persist a day's record (atomically replaces any previous version)
+    pub fn save_day(&self, day: &DailyTelemetry) -> std::io::Result<()> {
+        let json = serde_json::to_string_pretty(day)?;
+        let path = self.day_path(day.date);
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, json)?;
+        std::fs::rename(tmp, path)
+    }
+
+    /// Load a day's record, if one was stored
+    pub fn load_day(&self, date: NaiveDate) -> std::io::Result<Option<DailyTelemetry>> {
+        match std::fs::read_to_string(self.day_path(date)) {
+            Ok(json) => Ok(Some(serde_json::from_str(&json)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Load every stored day in [start, end]
+    pub fn load_range(&self, start: NaiveDate, end: NaiveDate) -> std::io::Result<Vec<DailyTelemetry>> {
+        let mut days = Vec::new();
+        for date in start.iter_days().take_while(|d| *d <= end) {
+            if let Some(day) = self.load_day(date)? {
+                days.push(day);
+            }
+        }
+        Ok(days)
+    }
 }
 
 impl Default for TelemetryCollector {
@@ -0,0 +1,272 @@
+//! Watchdog: Component Heartbeat Monitoring
+//!
+//! Every long-running task registers with the watchdog and sends periodic
+//! heartbeats. A component that misses its deadline raises a Critical alert;
+//! if it is trading-critical, the risk gate is closed, which in turn fires
+//! the executor's dead man's switch.
+
+use crate::alerts::{Alert, AlertSeverity, AlertSink};
+use crate::incidents::IncidentTracker;
+use axiom_risk::RiskGate;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::task::JoinHandle;
+use tokio::time::{Duration, Instant};
+use tracing::info;
+
+struct Registration {
+    deadline: Duration,
+    trading_critical: bool,
+    last_beat: Instant,
+    missed: bool,
+    /// Miss has been reported and not yet seen to recover
+    reported: bool,
+}
+
+type Registry = Arc<Mutex<HashMap<String, Registration>>>;
+
+/// Handle held by a monitored task to report liveness
+#[derive(Clone)]
+pub struct Heartbeat {
+    name: String,
+    registry: Registry,
+}
+
+impl Heartbeat {
+    /// Report that the component is alive
+    pub fn beat(&self) {
+        if let Ok(mut registry) = self.registry.lock() {
+            if let Some(reg) = registry.get_mut(&self.name) {
+                if reg.missed {
+                    info!("Watchdog: {} recovered", self.name);
+                }
+                reg.last_beat = Instant::now();
+                reg.missed = false;
+            }
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Heartbeat watchdog
+pub struct Watchdog {
+    registry: Registry,
+    alert_sink: Arc<dyn AlertSink>,
+    risk_gate: RiskGate,
+    incidents: Option<Arc<IncidentTracker>>,
+}
+
+impl Watchdog {
+    pub fn new(alert_sink: Arc<dyn AlertSink>, risk_gate: RiskGate) -> Self {
+        Self {
+            registry: Arc::new(Mutex::new(HashMap::new())),
+            alert_sink,
+            risk_gate,
+            incidents: None,
+        }
+    }
+
+    /// Track missed heartbeats as incidents (auto-resolved when beats resume)
+    pub fn with_incidents(mut self, incidents: Arc<IncidentTracker>) -> Self {
+        self.incidents = Some(incidents);
+        self
+    }
+
+    /// Register a component; it must beat at least once per `deadline`
+    pub fn register(&self, name: &str, deadline: Duration, trading_critical: bool) -> Heartbeat {
+        if let Ok(mut registry) = self.registry.lock() {
+            registry.insert(name.to_string(), Registration {
+                deadline,
+                trading_critical,
+                last_beat: Instant::now(),
+                missed: false,
+                reported: false,
+            });
+        }
+
+        Heartbeat {
+            name: name.to_string(),
+            registry: self.registry.clone(),
+        }
+    }
+
+    /// Check all deadlines once, returning components that newly missed theirs
+    pub fn check(&self) -> Vec<String> {
+        let now = Instant::now();
+        let mut newly_missed = Vec::new();
+        let mut critical_missed = Vec::new();
+        let mut recovered = Vec::new();
+
+        if let Ok(mut registry) = self.registry.lock() {
+            for (name, reg) in registry.iter_mut() {
+                if reg.reported && !reg.missed {
+                    reg.reported = false;
+                    recovered.push(name.clone());
+                }
+                if !reg.missed && now.duration_since(reg.last_beat) > reg.deadline {
+                    reg.missed = true;
+                    reg.reported = true;
+                    if reg.trading_critical {
+                        critical_missed.push(name.clone());
+                    }
+                    newly_missed.push(name.clone());
+                }
+            }
+        }
+        newly_missed.sort();
+        critical_missed.sort();
+
+        for name in &newly_missed {
+            let alert = Alert::new(
+                AlertSeverity::Critical,
+                "watchdog",
+                format!("{} missed its heartbeat deadline", name),
+            );
+            match &self.incidents {
+                Some(incidents) => {
+                    incidents.fire(&format!("watchdog:{}", name), alert);
+                }
+                None => self.alert_sink.send(&alert),
+            }
+        }
+
+        if let Some(incidents) = &self.incidents {
+            for name in &recovered {
+                incidents.clear(&format!("watchdog:{}", name), chrono::Utc::now());
+            }
+        }
+
+        if !critical_missed.is_empty() {
+            self.risk_gate.close(format!("Watchdog: heartbeat lost from {}", critical_missed.join(", ")));
+        }
+
+        newly_missed
+    }
+
+    /// Run the watchdog on its own task
+    pub fn spawn(self: Arc<Self>, interval: Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                self.check();
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axiom_core::{OrderStatus, Symbol, Venue};
+    use axiom_execution::{ClientFuture, ExchangeClient, OrderExecutor, SubmitRequest};
+    use axiom_risk::GateState;
+    use tokio::sync::{mpsc, watch};
+    use tokio::time::sleep;
+
+    /// Keeps every alert sent
+    #[derive(Default)]
+    struct Alerts(Mutex<Vec<Alert>>);
+
+    impl AlertSink for Alerts {
+        fn send(&self, alert: &Alert) {
+            self.0.lock().unwrap().push(alert.clone());
+        }
+    }
+
+    impl Alerts {
+        fn sent(&self) -> Vec<Alert> {
+            self.0.lock().unwrap().clone()
+        }
+    }
+
+    /// Reports each cancel-all on a channel
+    struct Cancels(mpsc::UnboundedSender<(Symbol, Venue)>);
+
+    impl ExchangeClient for Cancels {
+        fn submit<'a>(&'a self, _request: &'a SubmitRequest<'a>) -> ClientFuture<'a, OrderStatus> {
+            Box::pin(async { Ok(OrderStatus::Pending) })
+        }
+
+        fn cancel_all<'a>(&'a self, symbol: &'a Symbol, venue: &'a Venue) -> ClientFuture<'a, ()> {
+            let _ = self.0.send((symbol.clone(), venue.clone()));
+            Box::pin(async { Ok(()) })
+        }
+    }
+
+    /// A task beating four times per `deadline` until aborted
+    fn beating(heartbeat: Heartbeat, deadline: Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                heartbeat.beat();
+                sleep(deadline / 4).await;
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn a_trading_critical_task_killed_raises_an_alert_closes_the_gate_and_cancels_all() {
+        let deadline = Duration::from_millis(200);
+        let alerts = Arc::new(Alerts::default());
+        let gate = RiskGate::new();
+        let watchdog = Arc::new(Watchdog::new(alerts.clone(), gate.clone()));
+        let generator = beating(watchdog.register("signal-generator", deadline, true), deadline);
+
+        let (cancels, mut cancelled) = mpsc::unbounded_channel();
+        let executor = Arc::new(OrderExecutor::new().with_exchange_client(Arc::new(Cancels(cancels))));
+        let market = (Symbol("BTC/USD".to_string()), Venue::Binance);
+        let (stop, shutdown) = watch::channel(false);
+        let switch = tokio::spawn({
+            let (executor, gate, markets) = (executor.clone(), gate.subscribe(), vec![market.clone()]);
+            async move { executor.run_dead_man_switch(gate, markets, shutdown).await }
+        });
+        let watching = watchdog.clone().spawn(deadline / 10);
+
+        // Nothing happens while it beats
+        sleep(deadline * 2).await;
+        assert!(alerts.sent().is_empty());
+        assert!(gate.is_open());
+
+        // Its last beat was at most a quarter deadline ago, and the watchdog
+        // checks every tenth of one
+        generator.abort();
+        let cancel = tokio::time::timeout(deadline * 2, cancelled.recv()).await
+            .expect("cancel-all within the deadline");
+        assert_eq!(cancel, Some(market));
+
+        let sent = alerts.sent();
+        assert_eq!(sent.len(), 1);
+        assert_eq!((sent[0].severity, sent[0].source.as_str()), (AlertSeverity::Critical, "watchdog"));
+        assert!(sent[0].message.contains("signal-generator"));
+        assert!(matches!(gate.state(), GateState::Closed { reason } if reason.contains("signal-generator")));
+
+        watching.abort();
+        stop.send(true).unwrap();
+        switch.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn a_missed_beat_from_a_non_critical_task_only_alerts_once() {
+        let deadline = Duration::from_millis(50);
+        let alerts = Arc::new(Alerts::default());
+        let gate = RiskGate::new();
+        let watchdog = Watchdog::new(alerts.clone(), gate.clone());
+        let heartbeat = watchdog.register("telemetry-writer", deadline, false);
+
+        sleep(deadline * 2).await;
+        assert_eq!(watchdog.check(), vec!["telemetry-writer".to_string()]);
+        assert!(watchdog.check().is_empty());
+        assert_eq!(alerts.sent().len(), 1);
+        assert!(gate.is_open());
+
+        // Missed again after recovering: a fresh alert
+        heartbeat.beat();
+        assert!(watchdog.check().is_empty());
+        sleep(deadline * 2).await;
+        assert_eq!(watchdog.check(), vec!["telemetry-writer".to_string()]);
+        assert_eq!(alerts.sent().len(), 2);
+    }
+}
@@ -0,0 +1,88 @@
+//! Per-update cost of `PortfolioManager` over a session holding 50
+//! positions: aggregates adjusted by each position's change, against a full
+//! recomputation over every position on each update.
+//!
+//! cargo bench -p axiom-risk --bench portfolio_updates
+
+use axiom_core::{AccountId, Amount, Price, Quantity, Side, Symbol, Venue};
+use axiom_risk::{CorrelationConfig, PortfolioManager, DEFAULT_CONSISTENCY_INTERVAL, DEFAULT_DRIFT_TOLERANCE};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+const POSITIONS: usize = 50;
+const UPDATES: usize = 20_000;
+
+/// One step of the session
+enum Update {
+    Fill(Symbol, Side, Quantity, Price),
+    Tick(HashMap<Symbol, Price>),
+}
+
+/// A position opened in each symbol, then a tick on one symbol per update
+/// with every tenth update a fill instead
+fn session() -> Vec<Update> {
+    let symbols: Vec<Symbol> = (0..POSITIONS).map(|index| Symbol(format!("SYM{index}/USDT"))).collect();
+    let price = |step: usize| Price::new(Decimal::new(10_000 + (step % 97) as i64, 2)).unwrap();
+    let opening = symbols.iter()
+        .map(|symbol| Update::Fill(symbol.clone(), Side::Buy, Quantity::new(Decimal::ONE).unwrap(), price(0)));
+    let steps = (0..UPDATES).map(|step| {
+        let symbol = symbols[step * 7 % POSITIONS].clone();
+        if step % 10 == 0 {
+            let side = if step % 20 == 0 { Side::Buy } else { Side::Sell };
+            Update::Fill(symbol, side, Quantity::new(Decimal::new(1, 1)).unwrap(), price(step))
+        } else {
+            Update::Tick(HashMap::from([(symbol, price(step))]))
+        }
+    });
+    opening.chain(steps).collect()
+}
+
+/// Time spent in fills and in ticks replaying `session` with a full
+/// recomputation every `interval` updates (the correlation matrix, costly
+/// alike either way, is left out)
+fn measure(session: &[Update], interval: u64) -> (Duration, Duration) {
+    let account = AccountId::default();
+    let mut manager = PortfolioManager::new(Amount::new(Decimal::from(1_000_000)))
+        .with_consistency_check(interval, DEFAULT_DRIFT_TOLERANCE)
+        .with_correlation(CorrelationConfig { refresh_interval: 0, ..CorrelationConfig::default() });
+    let (mut fills, mut ticks) = (Duration::ZERO, Duration::ZERO);
+    for update in session {
+        let start = Instant::now();
+        match update {
+            Update::Fill(symbol, side, quantity, price) => {
+                manager.update_position(&account, symbol.clone(), Venue::Binance, *side, *quantity, *price, Amount::ZERO).unwrap();
+                fills += start.elapsed();
+            }
+            Update::Tick(prices) => {
+                manager.update_prices(prices).unwrap();
+                ticks += start.elapsed();
+            }
+        }
+    }
+    assert!(manager.take_drift().is_empty());
+    (fills, ticks)
+}
+
+fn main() {
+    let session = session();
+    let fill_count = session.iter().filter(|update| matches!(update, Update::Fill(..))).count() as u32;
+    let tick_count = session.len() as u32 - fill_count;
+    let (incremental_fills, incremental_ticks) = measure(&session, DEFAULT_CONSISTENCY_INTERVAL);
+    let (recomputed_fills, recomputed_ticks) = measure(&session, 1);
+
+    println!("{} fills and {} ticks over {} positions", fill_count, tick_count, POSITIONS);
+    println!(
+        "incremental (full check every {}): fill {:?}, tick {:?}",
+        DEFAULT_CONSISTENCY_INTERVAL, incremental_fills / fill_count, incremental_ticks / tick_count,
+    );
+    println!(
+        "full recomputation every update: fill {:?}, tick {:?}",
+        recomputed_fills / fill_count, recomputed_ticks / tick_count,
+    );
+    println!(
+        "speedup: fills {:.1}x, ticks {:.1}x (ticks also take each mark's log return)",
+        recomputed_fills.as_secs_f64() / incremental_fills.as_secs_f64(),
+        recomputed_ticks.as_secs_f64() / incremental_ticks.as_secs_f64(),
+    );
+}
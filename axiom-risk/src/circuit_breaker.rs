@@ -1,56 +1,148 @@
 //! Circuit Breakers: The Dead Man Switch
 //!
 //! Hard limits that trigger automatic shutdown or risk reduction.
+//!
+//! Daily drawdown is peak-to-trough within the UTC day, so a loss after an
+//! intraday high counts from the high, not from the open.
+//!
+//! A liquidation cascade in any symbol raises a warning rather than a trip:
+//! it is the market's leverage unwinding, not ours.
 
-use axiom_core::{Portfolio, CircuitBreakerState, Decimal};
+use axiom_core::{
+    AccountId, Portfolio, CircuitBreakerState, Amount, UnitError, EventLog, Correlation, Symbol,
+    LiquidationIntensity,
+};
 use axiom_core::constants::*;
+use axiom_core::events::BreakerTripped;
 use rust_decimal::Decimal;
-use chrono::{DateTime, Utc, Duration};
-use std::collections::VecDeque;
+use chrono::{DateTime, Utc};
+use crate::equity::EquityCurve;
+use crate::portfolio::PortfolioManager;
+use std::collections::BTreeMap;
+use std::sync::Arc;
 
 /// Circuit breaker manager
 pub struct CircuitBreaker {
     state: CircuitBreakerState,
-    daily_pnl_history: VecDeque<(DateTime<Utc>, Decimal)>,
+    equity: EquityCurve,
     max_daily_drawdown: Decimal,
+    max_leverage: Decimal,
     last_reset: DateTime<Utc>,
+    event_log: Option<Arc<EventLog>>,
+    /// Account this breaker watches (None: the whole portfolio)
+    account: Option<AccountId>,
+    /// Warn while any symbol liquidates more than this (None: never)
+    max_liquidation_notional: Option<Amount>,
+    /// Latest liquidation intensity per symbol
+    liquidations: BTreeMap<Symbol, LiquidationIntensity>,
+    /// Why the breaker was tripped by hand; it stays tripped until reset
+    manual: Option<String>,
 }
 
 impl CircuitBreaker {
     pub fn new(max_daily_drawdown: Decimal) -> Self {
         Self {
             state: CircuitBreakerState::Normal,
-            daily_pnl_history: VecDeque::new(),
+            equity: EquityCurve::default(),
             max_daily_drawdown,
+            max_leverage: MAX_LEVERAGE,
             last_reset: Utc::now(),
+            event_log: None,
+            account: None,
+            max_liquidation_notional: None,
+            liquidations: BTreeMap::new(),
+            manual: None,
         }
     }
 
+    /// Trip above `max_leverage` (clamped to MAX_LEVERAGE)
+    pub fn with_max_leverage(mut self, max_leverage: Decimal) -> Self {
+        self.max_leverage = max_leverage.min(MAX_LEVERAGE);
+        self
+    }
+
+    /// Warn while more than `max` notional is liquidated in any one symbol
+    /// over the liquidation window
+    pub fn with_max_liquidation_notional(mut self, max: Amount) -> Self {
+        self.max_liquidation_notional = Some(max);
+        self
+    }
+
+    /// Check `symbol` against the liquidation limit from now on
+    pub fn record_liquidation_intensity(&mut self, symbol: Symbol, intensity: LiquidationIntensity) {
+        self.liquidations.insert(symbol, intensity);
+    }
+
+    /// Symbol liquidating past the limit, with its total, if any (an
+    /// unsummable intensity counts as past it)
+    fn cascade(&self) -> Option<(&Symbol, Option<Amount>)> {
+        let max = self.max_liquidation_notional?;
+        self.liquidations.iter()
+            .map(|(symbol, intensity)| (symbol, intensity.total().ok()))
+            .find(|(_, total)| total.is_none_or(|total| total > max))
+    }
+
+    /// Watch one account; its trip events carry the account
+    pub fn for_account(mut self, account: AccountId) -> Self {
+        self.account = Some(account);
+        self
+    }
+
+    /// Emit a structured event whenever the breaker trips
+    pub fn with_event_log(mut self, event_log: Arc<EventLog>) -> Self {
+        self.event_log = Some(event_log);
+        self
+    }
+
     /// Check circuit breaker conditions
     pub fn check(&mut self, portfolio: &Portfolio) -> CircuitBreakerState {
-        // Check daily drawdown
-        let daily_drawdown = self.calculate_daily_drawdown(portfolio);
+        if self.manual.is_some() {
+            return self.state;
+        }
+        // Check daily drawdown (an uncomputable drawdown trips, never passes)
+        let daily_drawdown = match self.calculate_daily_drawdown(portfolio) {
+            Ok(drawdown) => drawdown,
+            Err(e) => {
+                tracing::error!("Circuit breaker TRIPPED: drawdown calculation failed: {}", e);
+                self.trip(format!("Drawdown calculation failed: {}", e));
+                return self.state;
+            }
+        };
         
-        if daily_drawdown.abs() > self.max_daily_drawdown {
-            self.state = CircuitBreakerState::Tripped;
+        if daily_drawdown > self.max_daily_drawdown {
             tracing::error!("Circuit breaker TRIPPED: Daily drawdown {} exceeds limit {}", 
                 daily_drawdown, self.max_daily_drawdown);
+            self.trip(format!("Daily drawdown {} exceeds limit {}",
+                daily_drawdown, self.max_daily_drawdown));
             return self.state;
         }
 
         // Check leverage
-        if portfolio.leverage > MAX_LEVERAGE {
-            self.state = CircuitBreakerState::Tripped;
+        if portfolio.leverage > self.max_leverage {
             tracing::error!("Circuit breaker TRIPPED: Leverage {} exceeds limit {}", 
-                portfolio.leverage, MAX_LEVERAGE);
+                portfolio.leverage, self.max_leverage);
+            self.trip(format!("Leverage {} exceeds limit {}", portfolio.leverage, self.max_leverage));
             return self.state;
         }
 
-        // Check Hamiltonian energy
-        let energy = axiom_risk::hamiltonian::calculate_hamiltonian_energy(portfolio);
+        // Check Hamiltonian energy (an uncomputable energy trips, never passes)
+        let energy = match crate::hamiltonian::calculate_hamiltonian_energy(portfolio) {
+            Ok(energy) => energy,
+            Err(e) => {
+                tracing::error!("Circuit breaker TRIPPED: energy calculation failed: {}", e);
+                self.trip(format!("Energy calculation failed: {}", e));
+                return self.state;
+            }
+        };
         if energy > DELTA_U_MAX_SQ {
             self.state = CircuitBreakerState::Warning;
             tracing::warn!("Circuit breaker WARNING: Energy {} exceeds threshold", energy);
+        } else if let Some((symbol, total)) = self.cascade() {
+            match total {
+                Some(total) => tracing::warn!("Circuit breaker WARNING: {} liquidated in {} exceeds threshold", total, symbol.0),
+                None => tracing::warn!("Circuit breaker WARNING: {} liquidations not summable", symbol.0),
+            }
+            self.state = CircuitBreakerState::Warning;
         } else {
             self.state = CircuitBreakerState::Normal;
         }
@@ -58,40 +150,52 @@ impl CircuitBreaker {
         self.state
     }
 
-    /// Calculate daily drawdown
-    fn calculate_daily_drawdown(&self, portfolio: &Portfolio) -> Decimal {
-        // Simplified: compare current equity to equity at start of day
-        // In production, would track peak equity throughout the day
-        if self.daily_pnl_history.is_empty() {
-            return Decimal::ZERO;
-        }
+    /// Trip for a reason found outside the breaker's own checks (e.g. a
+    /// failed reconciliation); held until `reset`
+    pub fn trip_manual(&mut self, reason: String) {
+        tracing::error!("Circuit breaker TRIPPED: {}", reason);
+        self.manual = Some(reason.clone());
+        self.trip(reason);
+    }
 
-        let start_equity = self.daily_pnl_history.front()
-            .map(|(_, equity)| *equity)
-            .unwrap_or(portfolio.equity);
+    /// Move to Tripped, emitting an event on the transition
+    fn trip(&mut self, reason: String) {
+        let was_tripped = self.state == CircuitBreakerState::Tripped;
+        self.state = CircuitBreakerState::Tripped;
 
-        (portfolio.equity - start_equity) / start_equity
+        if !was_tripped {
+            if let Some(log) = &self.event_log {
+                let correlation = match &self.account {
+                    Some(account) => Correlation::default().with_account(account),
+                    None => Correlation::default(),
+                };
+                log.emit(&correlation, &BreakerTripped {
+                    state: self.state,
+                    reason,
+                });
+            }
+        }
+    }
+
+    /// Peak-to-trough drawdown within the UTC day, counting the equity
+    /// being checked even if it was never recorded
+    fn calculate_daily_drawdown(&self, portfolio: &Portfolio) -> Result<Decimal, UnitError> {
+        self.equity.intraday_drawdown(Utc::now().date_naive(), portfolio.equity)
     }
 
-    /// Record daily PnL snapshot
+    /// Record an equity snapshot
     pub fn record_snapshot(&mut self, portfolio: &Portfolio) {
-        self.daily_pnl_history.push_back((Utc::now(), portfolio.equity));
-        
-        // Keep only last 24 hours
-        let cutoff = Utc::now() - Duration::hours(24);
-        while let Some(&(time, _)) = self.daily_pnl_history.front() {
-            if time < cutoff {
-                self.daily_pnl_history.pop_front();
-            } else {
-                break;
-            }
+        if let Err(e) = self.equity.record(Utc::now(), portfolio.equity) {
+            tracing::error!("Equity snapshot not recorded: {}", e);
         }
     }
 
-    /// Reset circuit breaker (start of new day)
+    /// Reset circuit breaker; the day's drawdown is measured afresh from
+    /// the latest equity
     pub fn reset(&mut self) {
         self.state = CircuitBreakerState::Normal;
-        self.daily_pnl_history.clear();
+        self.manual = None;
+        self.equity.restart_day();
         self.last_reset = Utc::now();
     }
 
@@ -99,5 +203,90 @@ impl CircuitBreaker {
     pub fn state(&self) -> CircuitBreakerState {
         self.state
     }
+
+    /// Equity recorded by `record_snapshot`
+    pub fn equity_curve(&self) -> &EquityCurve {
+        &self.equity
+    }
 }
 
+
+/// A circuit breaker per account plus one over every account together
+///
+/// An account's breaker halts that account alone; the aggregate breaker
+/// halts them all.
+pub struct AccountBreakers {
+    aggregate: CircuitBreaker,
+    accounts: BTreeMap<AccountId, CircuitBreaker>,
+}
+
+impl AccountBreakers {
+    pub fn new(aggregate: CircuitBreaker) -> Self {
+        Self { aggregate, accounts: BTreeMap::new() }
+    }
+
+    /// Watch `account` with its own breaker
+    pub fn with_account(mut self, account: AccountId, breaker: CircuitBreaker) -> Self {
+        self.accounts.insert(account.clone(), breaker.for_account(account));
+        self
+    }
+
+    /// Snapshot and check every breaker; returns the aggregate state
+    pub fn check(&mut self, manager: &PortfolioManager) -> CircuitBreakerState {
+        for (account, breaker) in &mut self.accounts {
+            if let Some(portfolio) = manager.account_portfolio(account) {
+                breaker.record_snapshot(&portfolio);
+                breaker.check(&portfolio);
+            }
+        }
+        let aggregate = manager.portfolio();
+        self.aggregate.record_snapshot(aggregate);
+        self.aggregate.check(aggregate)
+    }
+
+    /// Trip `account`'s own breaker, or the aggregate for an account
+    /// without one, until reset
+    pub fn trip_manual(&mut self, account: &AccountId, reason: String) {
+        match self.accounts.get_mut(account) {
+            Some(breaker) => breaker.trip_manual(reason),
+            None => self.aggregate.trip_manual(reason),
+        }
+    }
+
+    /// Check `symbol` against the aggregate breaker's liquidation limit
+    pub fn record_liquidation_intensity(&mut self, symbol: Symbol, intensity: LiquidationIntensity) {
+        self.aggregate.record_liquidation_intensity(symbol, intensity);
+    }
+
+    /// State of the aggregate breaker
+    pub fn state(&self) -> CircuitBreakerState {
+        self.aggregate.state()
+    }
+
+    /// Equity of every account together
+    pub fn equity_curve(&self) -> &EquityCurve {
+        self.aggregate.equity_curve()
+    }
+
+    /// State orders for `account` are subject to: the aggregate's when it
+    /// halts trading, otherwise the account's own
+    pub fn account_state(&self, account: &AccountId) -> CircuitBreakerState {
+        match self.aggregate.state() {
+            state @ (CircuitBreakerState::Tripped | CircuitBreakerState::Halted) => state,
+            aggregate => self.accounts.get(account).map_or(aggregate, CircuitBreaker::state),
+        }
+    }
+
+    /// State of each account's own breaker, ignoring the aggregate
+    pub fn account_states(&self) -> BTreeMap<AccountId, CircuitBreakerState> {
+        self.accounts.iter().map(|(account, breaker)| (account.clone(), breaker.state())).collect()
+    }
+
+    /// Reset every breaker
+    pub fn reset(&mut self) {
+        self.aggregate.reset();
+        for breaker in self.accounts.values_mut() {
+            breaker.reset();
+        }
+    }
+}
@@ -0,0 +1,186 @@
+//! Correlation: How the Symbols Held Move Together
+//!
+//! Marks are turned into log returns per symbol, the latest `window` of
+//! them kept, and each pair's Pearson correlation is taken over the returns
+//! both have, aligned on the most recent. A pair with fewer than
+//! `min_samples` returns in common, or with a flat series, counts as
+//! uncorrelated; a symbol is always fully correlated with itself. A zero
+//! price is a missing quote: no return is taken into or out of it.
+
+use axiom_core::arith::{checked_add, checked_div, checked_mul, checked_sub};
+use axiom_core::{ArithmeticError, Price, Symbol};
+use rust_decimal::{Decimal, MathematicalOps};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+
+/// Correlation tuning
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CorrelationConfig {
+    /// Returns kept per symbol
+    pub window: usize,
+    /// Returns a pair needs in common before it counts as correlated
+    pub min_samples: usize,
+    /// Mark updates between recomputations of the matrix; 0 never
+    pub refresh_interval: u64,
+}
+
+impl Default for CorrelationConfig {
+    fn default() -> Self {
+        Self { window: 100, min_samples: 20, refresh_interval: 10 }
+    }
+}
+
+/// A correlation matrix and the symbols its rows and columns stand for
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CorrelationMatrix {
+    pub symbols: Vec<Symbol>,
+    pub matrix: Vec<Vec<Decimal>>,
+}
+
+/// Rolling log returns per symbol
+#[derive(Debug, Clone, Default)]
+pub struct ReturnsTracker {
+    config: CorrelationConfig,
+    last: BTreeMap<Symbol, Price>,
+    returns: BTreeMap<Symbol, VecDeque<Decimal>>,
+}
+
+impl ReturnsTracker {
+    pub fn new(config: CorrelationConfig) -> Self {
+        Self { config, ..Self::default() }
+    }
+
+    pub fn config(&self) -> CorrelationConfig {
+        self.config
+    }
+
+    /// Take the return from `symbol`'s previous mark to `price`
+    pub fn record(&mut self, symbol: &Symbol, price: Price) -> Result<(), ArithmeticError> {
+        let previous = self.last.insert(symbol.clone(), price);
+        let Some(previous) = previous.filter(|previous| !previous.is_zero() && !price.is_zero()) else {
+            return Ok(());
+        };
+        let ratio = checked_div("log return", price.get(), previous.get())?;
+        let log_return = ratio.checked_ln().ok_or(ArithmeticError::Overflow("log return"))?;
+        let returns = self.returns.entry(symbol.clone()).or_default();
+        returns.push_back(log_return);
+        while returns.len() > self.config.window.max(1) {
+            returns.pop_front();
+        }
+        Ok(())
+    }
+
+    /// Returns kept for `symbol`, oldest first
+    pub fn returns(&self, symbol: &Symbol) -> impl Iterator<Item = &Decimal> {
+        self.returns.get(symbol).into_iter().flatten()
+    }
+
+    /// Pearson correlation of `a`'s and `b`'s latest common returns; 0
+    /// below `min_samples` or for a flat series
+    pub fn correlation(&self, a: &Symbol, b: &Symbol) -> Result<Decimal, ArithmeticError> {
+        if a == b {
+            return Ok(Decimal::ONE);
+        }
+        let (Some(x), Some(y)) = (self.returns.get(a), self.returns.get(b)) else {
+            return Ok(Decimal::ZERO);
+        };
+        let count = x.len().min(y.len());
+        if count < self.config.min_samples.max(2) {
+            return Ok(Decimal::ZERO);
+        }
+        let pairs = x.iter().skip(x.len() - count).zip(y.iter().skip(y.len() - count));
+
+        let (mut sum_x, mut sum_y, mut sum_xx, mut sum_yy, mut sum_xy) =
+            (Decimal::ZERO, Decimal::ZERO, Decimal::ZERO, Decimal::ZERO, Decimal::ZERO);
+        for (x, y) in pairs {
+            sum_x = checked_add("correlation", sum_x, *x)?;
+            sum_y = checked_add("correlation", sum_y, *y)?;
+            sum_xx = checked_add("correlation", sum_xx, checked_mul("correlation", *x, *x)?)?;
+            sum_yy = checked_add("correlation", sum_yy, checked_mul("correlation", *y, *y)?)?;
+            sum_xy = checked_add("correlation", sum_xy, checked_mul("correlation", *x, *y)?)?;
+        }
+        let n = Decimal::from(count);
+        let moment = |sum_ab: Decimal, sum_a: Decimal, sum_b: Decimal| {
+            checked_sub("correlation", checked_mul("correlation", n, sum_ab)?, checked_mul("correlation", sum_a, sum_b)?)
+        };
+        let covariance = moment(sum_xy, sum_x, sum_y)?;
+        let variance_x = moment(sum_xx, sum_x, sum_x)?;
+        let variance_y = moment(sum_yy, sum_y, sum_y)?;
+        if variance_x <= Decimal::ZERO || variance_y <= Decimal::ZERO {
+            return Ok(Decimal::ZERO);
+        }
+        let spread = checked_mul("correlation", variance_x, variance_y)?
+            .sqrt()
+            .ok_or(ArithmeticError::Overflow("correlation"))?;
+        if spread.is_zero() {
+            return Ok(Decimal::ZERO);
+        }
+        // Rounding can leave a perfect correlation a hair outside [-1, 1]
+        Ok(checked_div("correlation", covariance, spread)?.clamp(Decimal::NEGATIVE_ONE, Decimal::ONE))
+    }
+
+    /// Pairwise correlations of every symbol marked so far, in name order
+    pub fn matrix(&self) -> Result<CorrelationMatrix, ArithmeticError> {
+        let symbols: Vec<Symbol> = self.last.keys().cloned().collect();
+        let mut matrix = vec![vec![Decimal::ZERO; symbols.len()]; symbols.len()];
+        for (i, a) in symbols.iter().enumerate() {
+            matrix[i][i] = Decimal::ONE;
+            for (j, b) in symbols.iter().enumerate().skip(i + 1) {
+                let correlation = self.correlation(a, b)?;
+                matrix[i][j] = correlation;
+                matrix[j][i] = correlation;
+            }
+        }
+        Ok(CorrelationMatrix { symbols, matrix })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MARKS: [i64; 8] = [100, 103, 101, 106, 104, 99, 102, 108];
+
+    fn tracker() -> ReturnsTracker {
+        ReturnsTracker::new(CorrelationConfig { window: 100, min_samples: 5, refresh_interval: 1 })
+    }
+
+    fn symbol(name: &str) -> Symbol {
+        Symbol(name.to_string())
+    }
+
+    fn close_to(value: Decimal, expected: Decimal) -> bool {
+        (value - expected).abs() < Decimal::new(1, 9)
+    }
+
+    #[test]
+    fn co_moving_marks_correlate_at_one() {
+        let mut tracker = tracker();
+        for mark in MARKS {
+            tracker.record(&symbol("BTC"), Price::new(Decimal::from(mark)).unwrap()).unwrap();
+            // Twice the price, the same returns
+            tracker.record(&symbol("ETH"), Price::new(Decimal::from(2 * mark)).unwrap()).unwrap();
+        }
+        let correlation = tracker.correlation(&symbol("BTC"), &symbol("ETH")).unwrap();
+        assert!(close_to(correlation, Decimal::ONE), "{}", correlation);
+    }
+
+    #[test]
+    fn anti_moving_marks_correlate_at_minus_one() {
+        let mut tracker = tracker();
+        for mark in MARKS {
+            let mark = Decimal::from(mark);
+            tracker.record(&symbol("BTC"), Price::new(mark).unwrap()).unwrap();
+            // The reciprocal: every log return negated
+            tracker.record(&symbol("ETH"), Price::new(Decimal::from(10_000) / mark).unwrap()).unwrap();
+        }
+        let correlation = tracker.correlation(&symbol("BTC"), &symbol("ETH")).unwrap();
+        assert!(close_to(correlation, Decimal::NEGATIVE_ONE), "{}", correlation);
+
+        let matrix = tracker.matrix().unwrap();
+        assert_eq!(matrix.symbols, vec![symbol("BTC"), symbol("ETH")]);
+        assert_eq!(matrix.matrix[0][1], matrix.matrix[1][0]);
+        assert_eq!(matrix.matrix[0][0], Decimal::ONE);
+    }
+}
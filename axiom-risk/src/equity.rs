@@ -0,0 +1,182 @@
+//! Equity Curve: Peaks, Drawdowns and the Day's Return
+//!
+//! Timestamped equity samples with the running peak and the worst
+//! peak-to-trough decline seen, plus the same for the current UTC day. The
+//! peaks and drawdowns are kept as samples arrive, so thinning the stored
+//! curve loses resolution but never a trough: past `capacity` samples,
+//! every other one is dropped (the latest always stays). Drawdowns are
+//! fractions of the peak they are measured from, 0 from a peak at or
+//! below zero.
+
+use axiom_core::{Amount, UnitError};
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use std::collections::VecDeque;
+
+/// Samples kept before the curve is thinned: a day at one every 30s
+pub const DEFAULT_EQUITY_CAPACITY: usize = 2_880;
+
+/// Equity over time and the drawdowns along it
+#[derive(Debug, Clone)]
+pub struct EquityCurve {
+    capacity: usize,
+    samples: VecDeque<(DateTime<Utc>, Amount)>,
+    peak: Option<Amount>,
+    drawdown: Decimal,
+    max_drawdown: Decimal,
+    /// UTC day of the latest sample
+    day: Option<NaiveDate>,
+    /// Equity the day opened at: the previous day's close, or its first sample
+    day_open: Amount,
+    day_peak: Amount,
+    day_max_drawdown: Decimal,
+}
+
+impl Default for EquityCurve {
+    fn default() -> Self {
+        Self::new(DEFAULT_EQUITY_CAPACITY)
+    }
+}
+
+impl EquityCurve {
+    /// Keep at most `capacity` samples (at least 2)
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(2),
+            samples: VecDeque::new(),
+            peak: None,
+            drawdown: Decimal::ZERO,
+            max_drawdown: Decimal::ZERO,
+            day: None,
+            day_open: Amount::ZERO,
+            day_peak: Amount::ZERO,
+            day_max_drawdown: Decimal::ZERO,
+        }
+    }
+
+    /// Add `equity` at `at`; a sample on a later UTC day opens that day
+    pub fn record(&mut self, at: DateTime<Utc>, equity: Amount) -> Result<(), UnitError> {
+        let day = at.date_naive();
+        if self.day.is_none_or(|current| day > current) {
+            self.day_open = self.latest().map_or(equity, |(_, close)| close);
+            self.day = Some(day);
+            self.day_peak = equity;
+            self.day_max_drawdown = Decimal::ZERO;
+        }
+
+        let peak = self.peak.map_or(equity, |peak| peak.max(equity));
+        self.peak = Some(peak);
+        self.drawdown = drawdown(peak, equity)?;
+        self.max_drawdown = self.max_drawdown.max(self.drawdown);
+        self.day_peak = self.day_peak.max(equity);
+        self.day_max_drawdown = self.day_max_drawdown.max(drawdown(self.day_peak, equity)?);
+
+        self.samples.push_back((at, equity));
+        if self.samples.len() > self.capacity {
+            self.thin();
+        }
+        Ok(())
+    }
+
+    /// Samples kept, oldest first
+    pub fn samples(&self) -> impl Iterator<Item = &(DateTime<Utc>, Amount)> {
+        self.samples.iter()
+    }
+
+    pub fn latest(&self) -> Option<(DateTime<Utc>, Amount)> {
+        self.samples.back().copied()
+    }
+
+    /// Highest equity recorded
+    pub fn peak_equity(&self) -> Option<Amount> {
+        self.peak
+    }
+
+    /// Latest equity's decline from the peak
+    pub fn current_drawdown_from_peak(&self) -> Decimal {
+        self.drawdown
+    }
+
+    /// Largest peak-to-trough decline recorded
+    pub fn max_drawdown(&self) -> Decimal {
+        self.max_drawdown
+    }
+
+    /// Latest equity against the day's open; None before any sample or
+    /// from a zero open
+    pub fn daily_return(&self) -> Result<Option<Decimal>, UnitError> {
+        let Some((_, equity)) = self.latest() else {
+            return Ok(None);
+        };
+        if self.day_open.is_zero() {
+            return Ok(None);
+        }
+        equity.checked_sub(self.day_open)?.ratio(self.day_open).map(Some)
+    }
+
+    /// Peak-to-trough decline within `day`, counting `equity` as one more
+    /// sample; 0 when nothing was recorded on `day`
+    pub fn intraday_drawdown(&self, day: NaiveDate, equity: Amount) -> Result<Decimal, UnitError> {
+        if self.day != Some(day) {
+            return Ok(Decimal::ZERO);
+        }
+        let current = drawdown(self.day_peak.max(equity), equity)?;
+        Ok(self.day_max_drawdown.max(current))
+    }
+
+    /// Measure the day's drawdown from the latest equity on (e.g. after a
+    /// breaker reset); the running peak and maximum stand
+    pub fn restart_day(&mut self) {
+        if let Some((_, equity)) = self.latest() {
+            self.day_peak = equity;
+        }
+        self.day_max_drawdown = Decimal::ZERO;
+    }
+
+    /// Drop every other sample, keeping the latest
+    fn thin(&mut self) {
+        let last = self.samples.len() - 1;
+        let mut index = 0;
+        self.samples.retain(|_| {
+            let keep = (last - index).is_multiple_of(2);
+            index += 1;
+            keep
+        });
+    }
+}
+
+/// `equity`'s decline below `peak`, as a fraction of it
+fn drawdown(peak: Amount, equity: Amount) -> Result<Decimal, UnitError> {
+    if !peak.is_positive() || equity >= peak {
+        return Ok(Decimal::ZERO);
+    }
+    peak.checked_sub(equity)?.ratio(peak)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn amount(value: &str) -> Amount {
+        Amount::new(value.parse().unwrap())
+    }
+
+    fn hour(hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 1, 2, hour, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn daily_drawdown_is_measured_from_the_intraday_peak() {
+        let mut curve = EquityCurve::default();
+        curve.record(hour(0), amount("100")).unwrap();
+        curve.record(hour(12), amount("120")).unwrap();
+        curve.record(hour(18), amount("90")).unwrap();
+
+        // 30 below the midday peak of 120, not 10 below the open
+        let day = hour(0).date_naive();
+        assert_eq!(curve.intraday_drawdown(day, amount("90")).unwrap(), "0.25".parse::<Decimal>().unwrap());
+        assert_eq!(curve.max_drawdown(), "0.25".parse::<Decimal>().unwrap());
+        assert_eq!(curve.daily_return().unwrap(), Some("-0.1".parse().unwrap()));
+    }
+}
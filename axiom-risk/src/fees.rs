@@ -0,0 +1,74 @@
+//! Fee Model: What Each Fill Costs
+//!
+//! Maker and taker rates per venue, in basis points of notional. A venue
+//! without configured rates is charged its built-in defaults
+//! (`Venue::capabilities`); a negative rate is a rebate. The fee is passed
+//! to `PortfolioManager::update_position` with the fill it was paid on.
+
+use axiom_core::constants::BPS_PER_UNIT;
+use axiom_core::{Amount, OrderType, Price, Quantity, UnitError, Venue};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// One venue's rates, in basis points of notional
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VenueFees {
+    /// Fill that rested on the book (negative = rebate)
+    pub maker_bps: Decimal,
+    /// Fill that took liquidity
+    pub taker_bps: Decimal,
+}
+
+impl VenueFees {
+    /// The venue's built-in default rates
+    pub fn defaults(venue: &Venue) -> Self {
+        let capabilities = venue.capabilities();
+        Self { maker_bps: capabilities.maker_fee_bps, taker_bps: capabilities.taker_fee_bps }
+    }
+
+    pub fn bps(&self, maker: bool) -> Decimal {
+        if maker { self.maker_bps } else { self.taker_bps }
+    }
+}
+
+/// Fee rates by venue
+#[derive(Debug, Clone, Default)]
+pub struct FeeModel {
+    venues: HashMap<Venue, VenueFees>,
+}
+
+impl FeeModel {
+    /// Every venue at its built-in defaults
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Charge `venue` at `fees` instead of its defaults
+    pub fn with_venue(mut self, venue: Venue, fees: VenueFees) -> Self {
+        self.venues.insert(venue, fees);
+        self
+    }
+
+    /// Rates `venue` is charged at
+    pub fn rates(&self, venue: &Venue) -> VenueFees {
+        self.venues.get(venue).copied().unwrap_or_else(|| VenueFees::defaults(venue))
+    }
+
+    /// Fee on `notional` filled on `venue`
+    pub fn fee(&self, venue: &Venue, notional: Amount, maker: bool) -> Result<Amount, UnitError> {
+        notional.scale(self.rates(venue).bps(maker) / BPS_PER_UNIT)
+    }
+
+    /// Fee on a fill of `quantity` at `price`; limit orders are taken to
+    /// have rested (maker), every other order type to have crossed (taker)
+    pub fn fill_fee(
+        &self,
+        venue: &Venue,
+        order_type: &OrderType,
+        quantity: Quantity,
+        price: Price,
+    ) -> Result<Amount, UnitError> {
+        self.fee(venue, price.notional(quantity)?, matches!(order_type, OrderType::Limit))
+    }
+}
@@ -2,47 +2,121 @@
 //!
 //! Models portfolio risk as physical energy, enforcing Lyapunov stability.
 
-use axiom_core::{Portfolio, Decimal};
+use axiom_core::{Portfolio, ArithmeticError, Side, Symbol};
+use axiom_core::arith::{checked_add, checked_div, checked_mul};
 use axiom_core::constants::DELTA_U_MAX_SQ;
 use rust_decimal::Decimal;
+use std::collections::BTreeMap;
 
 /// Calculate Hamiltonian energy (risk measure)
-pub fn calculate_hamiltonian_energy(portfolio: &Portfolio) -> Decimal {
+///
+/// Errors rather than panics if extreme leverage overflows the square.
+pub fn calculate_hamiltonian_energy(portfolio: &Portfolio) -> Result<Decimal, ArithmeticError> {
     // Energy = 0.5 * (leverage^2 + correlation_penalty)
-    let leverage_term = portfolio.leverage * portfolio.leverage;
+    let leverage_term = checked_mul("leverage squared", portfolio.leverage, portfolio.leverage)?;
     
-    // Correlation penalty (simplified - in production would use full covariance matrix)
-    let correlation_penalty = calculate_correlation_penalty(portfolio);
+    let correlation_penalty = calculate_correlation_penalty(portfolio)?;
     
-    (leverage_term + correlation_penalty) / Decimal::from(2)
+    Ok(checked_add("hamiltonian energy", leverage_term, correlation_penalty)? / Decimal::TWO)
 }
 
 /// Calculate correlation penalty
 ///
-/// Penalizes concentrated positions (high correlation)
-fn calculate_correlation_penalty(portfolio: &Portfolio) -> Decimal {
-    if portfolio.positions.is_empty() {
-        return Decimal::ZERO;
+/// The off-diagonal part of w' C w, with w each symbol's net notional as a
+/// fraction of equity (venues and accounts netted) and C the portfolio's
+/// correlation matrix: exposures that move together add up, offsetting ones
+/// cancel. The diagonal (each w_i squared) is left out, as the leverage term
+/// already counts each symbol's own size, so a single position costs no
+/// more than its leverage. A symbol missing from the matrix counts as
+/// uncorrelated with the rest. Zero without positive equity.
+fn calculate_correlation_penalty(portfolio: &Portfolio) -> Result<Decimal, ArithmeticError> {
+    let equity = portfolio.equity.get();
+    if portfolio.positions.is_empty() || equity <= Decimal::ZERO {
+        return Ok(Decimal::ZERO);
     }
 
-    // Simplified: penalty increases with number of positions in same direction
-    let long_count = portfolio.positions.iter()
-        .filter(|p| p.side == axiom_core::Side::Buy)
-        .count();
-
-    let short_count = portfolio.positions.iter()
-        .filter(|p| p.side == axiom_core::Side::Sell)
-        .count();
+    let mut weights: BTreeMap<&Symbol, Decimal> = BTreeMap::new();
+    for position in &portfolio.positions {
+        let notional = checked_mul("correlation penalty", position.current_price.get(), position.quantity.get())?;
+        let signed = match position.side {
+            Side::Buy => notional,
+            Side::Sell => -notional,
+        };
+        let weight = weights.entry(&position.symbol).or_default();
+        *weight = checked_add("correlation penalty", *weight, checked_div("correlation penalty", signed, equity)?)?;
+    }
 
-    let concentration = Decimal::from(long_count.max(short_count));
-    concentration * (Decimal::from(1) / Decimal::from(10))
+    let row = |symbol: &Symbol| portfolio.correlation_symbols.iter().position(|listed| listed == symbol);
+    let mut penalty = Decimal::ZERO;
+    for (a, wa) in &weights {
+        for (b, wb) in weights.iter().filter(|(b, _)| *b != a) {
+            let correlation = match (row(a), row(b)) {
+                (Some(i), Some(j)) => portfolio.correlation_matrix.get(i)
+                    .and_then(|row| row.get(j))
+                    .copied()
+                    .unwrap_or_default(),
+                _ => Decimal::ZERO,
+            };
+            let term = checked_mul("correlation penalty", checked_mul("correlation penalty", *wa, *wb)?, correlation)?;
+            penalty = checked_add("correlation penalty", penalty, term)?;
+        }
+    }
+    Ok(penalty)
 }
 
 /// Check Lyapunov stability condition
 ///
-/// Returns true if dE/dt <= 0 (energy not diverging)
+/// Returns true if dE/dt <= 0 (energy not diverging). Energy too large to
+/// compute counts as diverging.
 pub fn check_lyapunov_stability(portfolio: &Portfolio) -> bool {
-    let energy = calculate_hamiltonian_energy(portfolio);
-    energy <= DELTA_U_MAX_SQ
+    calculate_hamiltonian_energy(portfolio).is_ok_and(|energy| energy <= DELTA_U_MAX_SQ)
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axiom_core::{AccountId, Amount, Position, Price, Quantity, Venue};
+
+    fn position(symbol: &str, side: Side, quantity: &str) -> Position {
+        let price = Price::new(Decimal::ONE_HUNDRED).unwrap();
+        Position {
+            account: AccountId::default(),
+            symbol: Symbol(symbol.to_string()),
+            venue: Venue::Binance,
+            side,
+            quantity: Quantity::new(quantity.parse().unwrap()).unwrap(),
+            entry_price: price,
+            current_price: price,
+            unrealized_pnl: Amount::ZERO,
+            realized_pnl: Amount::ZERO,
+        }
+    }
+
+    /// 10000 of equity holding `positions`, with BTC and ETH correlated at `correlation`
+    fn portfolio(positions: Vec<Position>, correlation: Decimal) -> Portfolio {
+        let mut portfolio = Portfolio::new(Amount::new(Decimal::from(10_000)));
+        portfolio.positions = positions;
+        portfolio.correlation_symbols = vec![Symbol("BTC".to_string()), Symbol("ETH".to_string())];
+        portfolio.correlation_matrix = vec![vec![Decimal::ONE, correlation], vec![correlation, Decimal::ONE]];
+        portfolio
+    }
+
+    #[test]
+    fn single_position_has_no_correlation_penalty() {
+        // A weight of 0.5
+        let portfolio = portfolio(vec![position("BTC", Side::Buy, "50")], Decimal::ONE);
+        assert_eq!(calculate_correlation_penalty(&portfolio).unwrap(), Decimal::ZERO);
+    }
+
+    #[test]
+    fn penalty_counts_each_pair_by_its_correlation() {
+        // Weights 0.5 and 0.2: 2 * 0.5 * 0.2 = 0.2 together, -0.2 hedged
+        let together = portfolio(vec![position("BTC", Side::Buy, "50"), position("ETH", Side::Buy, "20")], Decimal::ONE);
+        assert_eq!(calculate_correlation_penalty(&together).unwrap(), "0.2".parse::<Decimal>().unwrap());
+        let hedged = portfolio(vec![position("BTC", Side::Buy, "50"), position("ETH", Side::Sell, "20")], Decimal::ONE);
+        assert_eq!(calculate_correlation_penalty(&hedged).unwrap(), "-0.2".parse::<Decimal>().unwrap());
+        let unrelated = portfolio(vec![position("BTC", Side::Buy, "50"), position("ETH", Side::Buy, "20")], Decimal::ZERO);
+        assert_eq!(calculate_correlation_penalty(&unrelated).unwrap(), Decimal::ZERO);
+    }
+}
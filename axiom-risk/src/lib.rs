@@ -3,12 +3,22 @@
 //! Risk management is not a module—it's the physics engine of the system.
 
 pub mod portfolio;
+pub mod fees;
+pub mod correlation;
+pub mod reconciliation;
+pub mod equity;
 pub mod circuit_breaker;
 pub mod hamiltonian;
 pub mod position_sizing;
+pub mod risk_gate;
 
 pub use portfolio::*;
+pub use fees::*;
+pub use correlation::*;
+pub use reconciliation::*;
+pub use equity::*;
 pub use circuit_breaker::*;
 pub use hamiltonian::*;
 pub use position_sizing::*;
+pub use risk_gate::*;
 
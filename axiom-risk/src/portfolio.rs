@@ -1,147 +1,905 @@
 //! Portfolio Management: Real-Time State Tracking
 //!
-//! Maintains the portfolio state with Hamiltonian energy calculations.
+//! Maintains the portfolio state with Hamiltonian energy calculations, per
+//! account and across all accounts.
 
-use axiom_core::{Portfolio, Position, Symbol, Side, Price, Amount, Decimal};
-use axiom_core::constants::*;
+use axiom_core::{AccountId, Portfolio, Position, StrategyId, Symbol, Venue, Side, Price, Quantity, Amount, UnitError};
+use axiom_core::arith;
+use crate::correlation::{CorrelationConfig, CorrelationMatrix, ReturnsTracker};
 use rust_decimal::Decimal;
-use chrono::Utc;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
+use tracing::{debug, error, warn};
+
+/// Updates between full recomputations of the aggregates
+pub const DEFAULT_CONSISTENCY_INTERVAL: u64 = 1000;
+
+/// Largest difference (quote currency or leverage) treated as rounding
+pub const DEFAULT_DRIFT_TOLERANCE: Decimal = Decimal::from_parts(1, 0, 0, false, 6);
+
+/// An aggregate whose running value disagreed with a full recomputation
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricDrift {
+    pub metric: &'static str,
+    pub tracked: Decimal,
+    pub recomputed: Decimal,
+}
+
+/// Portfolio-wide sums a position contributes to
+#[derive(Debug, Clone, Copy, Default)]
+struct Contribution {
+    gross: Amount,
+    net: Amount,
+    unrealized: Amount,
+}
+
+impl Contribution {
+    fn of(position: &Position) -> Result<Self, UnitError> {
+        let notional = position.current_price.notional(position.quantity)?;
+        Ok(Self { gross: notional, net: notional.signed(position.side), unrealized: position.unrealized_pnl })
+    }
+
+    fn plus(self, other: Self) -> Result<Self, UnitError> {
+        Ok(Self {
+            gross: self.gross.checked_add(other.gross)?,
+            net: self.net.checked_add(other.net)?,
+            unrealized: self.unrealized.checked_add(other.unrealized)?,
+        })
+    }
+
+    fn minus(self, other: Self) -> Result<Self, UnitError> {
+        Ok(Self {
+            gross: self.gross.checked_sub(other.gross)?,
+            net: self.net.checked_sub(other.net)?,
+            unrealized: self.unrealized.checked_sub(other.unrealized)?,
+        })
+    }
+}
+
+/// One account's balance and running sums
+#[derive(Debug, Clone, Copy)]
+struct Ledger {
+    /// Equity excluding unrealized PnL
+    base_equity: Amount,
+    totals: Contribution,
+    equity: Amount,
+    leverage: Decimal,
+}
+
+impl Ledger {
+    fn new(base_equity: Amount) -> Self {
+        Self { base_equity, totals: Contribution::default(), equity: base_equity, leverage: Decimal::ZERO }
+    }
+
+    /// Derive equity and leverage from the sums
+    fn refresh(&mut self) -> Result<(), UnitError> {
+        self.equity = self.base_equity.checked_add(self.totals.unrealized)?;
+        self.leverage = leverage(self.totals.gross, self.equity)?;
+        Ok(())
+    }
+}
 
 /// Portfolio manager
+///
+/// Positions and balances are kept per account, with one position per
+/// symbol and venue: legs held on two venues stay apart, each at its own
+/// entry price, and `net_position` nets them. `portfolio` is every
+/// account together and `account_portfolio` one account alone. Exposures,
+/// unrealized PnL and leverage are adjusted by each position's change
+/// rather than re-summed over every position, so a fill or a price tick
+/// costs the same however many positions are open. Every
+/// `consistency_interval` updates the aggregates are recomputed in full;
+/// a difference beyond the tolerance is recorded as drift (see
+/// `take_drift`) and the recomputed values replace the running ones.
+///
+/// Fills attributed to a strategy (`attribute_fill`) are tracked as that
+/// strategy's signed quantity per symbol, valued at the latest mark, in
+/// `portfolio().strategies`.
+///
+/// Every mark update also feeds each symbol's log returns, from which
+/// `portfolio().correlation_matrix` is recomputed on the configured
+/// cadence (see `with_correlation`).
+///
+/// Fees paid on fills and funding settled on perpetuals (`apply_funding`)
+/// move cash directly; their running totals are in `portfolio()`, and fees
+/// also by symbol and by venue.
 pub struct PortfolioManager {
+    /// Every account together
     portfolio: Portfolio,
-    position_map: HashMap<Symbol, Position>,
+    /// Where each open position sits in `portfolio.positions`, by symbol
+    /// then account and venue
+    index: HashMap<Symbol, BTreeMap<(AccountId, Venue), usize>>,
+    accounts: BTreeMap<AccountId, Ledger>,
+    /// Equity excluding unrealized PnL
+    base_equity: Amount,
+    /// Running sum of unrealized PnL over open positions
+    unrealized: Amount,
+    /// PnL realized by fills since construction, already in `base_equity`
+    realized: Amount,
+    /// Fees paid since construction, by symbol and by venue
+    fees_by_symbol: BTreeMap<Symbol, Amount>,
+    fees_by_venue: BTreeMap<Venue, Amount>,
+    consistency_interval: u64,
+    drift_tolerance: Decimal,
+    updates_since_check: u64,
+    drift: Vec<MetricDrift>,
+    /// Latest fill or mark price per symbol, for strategy exposures
+    marks: HashMap<Symbol, Price>,
+    /// Mark returns behind `portfolio.correlation_matrix`
+    returns: ReturnsTracker,
+    updates_since_correlation: u64,
 }
 
 impl PortfolioManager {
+    /// A single (default) account holding `initial_equity`
     pub fn new(initial_equity: Amount) -> Self {
+        Self::for_accounts([(AccountId::default(), initial_equity)])
+    }
+
+    /// One ledger per account, each starting flat with its initial equity
+    pub fn for_accounts(accounts: impl IntoIterator<Item = (AccountId, Amount)>) -> Self {
+        let accounts: BTreeMap<AccountId, Ledger> = accounts.into_iter()
+            .map(|(account, equity)| (account, Ledger::new(equity)))
+            .collect();
+        // Configured equities are far below the overflow bound
+        let base_equity = accounts.values()
+            .try_fold(Amount::ZERO, |total, ledger| total.checked_add(ledger.base_equity))
+            .unwrap_or(Amount::ZERO);
         Self {
-            portfolio: Portfolio {
-                equity: initial_equity,
-                positions: Vec::new(),
-                total_exposure: Decimal::ZERO,
-                net_exposure: Decimal::ZERO,
-                leverage: Decimal::ZERO,
-                energy: Decimal::ZERO,
-                correlation_matrix: Vec::new(),
-            },
-            position_map: HashMap::new(),
+            portfolio: Portfolio::new(base_equity),
+            index: HashMap::new(),
+            accounts,
+            base_equity,
+            unrealized: Amount::ZERO,
+            realized: Amount::ZERO,
+            fees_by_symbol: BTreeMap::new(),
+            fees_by_venue: BTreeMap::new(),
+            consistency_interval: DEFAULT_CONSISTENCY_INTERVAL,
+            drift_tolerance: DEFAULT_DRIFT_TOLERANCE,
+            updates_since_check: 0,
+            drift: Vec::new(),
+            marks: HashMap::new(),
+            returns: ReturnsTracker::new(CorrelationConfig::default()),
+            updates_since_correlation: 0,
         }
     }
 
-    /// Update portfolio with new position
+    /// Recompute the aggregates in full every `interval` updates (0 never),
+    /// recording differences above `tolerance`
+    pub fn with_consistency_check(mut self, interval: u64, tolerance: Decimal) -> Self {
+        self.consistency_interval = interval;
+        self.drift_tolerance = tolerance;
+        self
+    }
+
+    /// Correlate mark returns over `config.window` marks, recomputing the
+    /// matrix every `config.refresh_interval` mark updates
+    pub fn with_correlation(mut self, config: CorrelationConfig) -> Self {
+        self.returns = ReturnsTracker::new(config);
+        self
+    }
+
+    /// Update `account`'s position on `venue` with a fill that cost `fee`;
+    /// returns the PnL it realized, before the fee
+    ///
+    /// A fill against the position closes up to its size, crediting the PnL
+    /// on the closed part to the position and the account's cash; any size
+    /// past flat opens a position on the fill's side at the fill price. A
+    /// position brought to zero is removed. The fee is debited from the
+    /// account's cash. An account not set up at construction is opened with
+    /// no equity.
+    #[allow(clippy::too_many_arguments)]
     pub fn update_position(
         &mut self,
+        account: &AccountId,
         symbol: Symbol,
+        venue: Venue,
         side: Side,
-        quantity: Decimal,
+        quantity: Quantity,
         price: Price,
-    ) {
-        let position_value = quantity * price;
-        
-        // Update or create position
-        let position = self.position_map.entry(symbol.clone())
-            .and_modify(|p| {
-                match (p.side, side) {
-                    (Side::Buy, Side::Buy) | (Side::Sell, Side::Sell) => {
-                        // Add to position
-                        let total_value = p.quantity * p.entry_price + position_value;
-                        let total_quantity = p.quantity + quantity;
-                        p.entry_price = total_value / total_quantity;
-                        p.quantity = total_quantity;
-                    }
-                    (Side::Buy, Side::Sell) | (Side::Sell, Side::Buy) => {
-                        // Reduce or close position
-                        if quantity >= p.quantity {
-                            // Position closed
-                            p.quantity = Decimal::ZERO;
-                        } else {
-                            p.quantity -= quantity;
-                        }
-                    }
-                }
-                p.current_price = price;
-            })
-            .or_insert_with(|| Position {
-                symbol: symbol.clone(),
-                venue: axiom_core::Venue("unknown".to_string()),
-                side,
-                quantity,
-                entry_price: price,
-                current_price: price,
-                unrealized_pnl: Decimal::ZERO,
-                realized_pnl: Decimal::ZERO,
-            });
-
-        // Recalculate portfolio metrics
-        self.recalculate_metrics();
-    }
-
-    /// Update position prices (mark-to-market)
-    pub fn update_prices(&mut self, prices: &HashMap<Symbol, Price>) {
+        fee: Amount,
+    ) -> Result<Amount, UnitError> {
+        if !self.accounts.contains_key(account) {
+            warn!("Fill for unknown account {}; opening it with no equity", account);
+            self.accounts.insert(account.clone(), Ledger::new(Amount::ZERO));
+        }
+        self.marks.insert(symbol.clone(), price);
+        self.charge_fee(account, &symbol, &venue, fee)?;
+        let Some(slot) = self.slot(account, &symbol, &venue) else {
+            self.open(account, symbol, venue, side, quantity, price)?;
+            self.refresh(account)?;
+            return Ok(Amount::ZERO);
+        };
+
+        let p = &mut self.portfolio.positions[slot];
+        let before = Contribution::of(p)?;
+        let mut realized = Amount::ZERO;
+        let mut remainder = Quantity::ZERO;
+        if p.side == side {
+            // Add to position
+            let total_value = p.entry_price.notional(p.quantity)?
+                .checked_add(price.notional(quantity)?)?;
+            let total_quantity = p.quantity.checked_add(quantity)?;
+            p.entry_price = total_value.price_per(total_quantity)?;
+            p.quantity = total_quantity;
+        } else {
+            // Reduce, close or flip position
+            let closed = p.quantity.min(quantity);
+            realized = Amount::pnl(p.side, p.entry_price, price, closed)?;
+            p.realized_pnl = p.realized_pnl.checked_add(realized)?;
+            remainder = quantity.saturating_sub(p.quantity);
+            p.quantity = p.quantity.saturating_sub(closed);
+        }
+        p.current_price = price;
+        p.unrealized_pnl = Amount::pnl(p.side, p.entry_price, price, p.quantity)?;
+        let after = Contribution::of(p)?;
+
+        self.adjust(account, before, after)?;
+        self.book(account, realized)?;
+        self.realized = self.realized.checked_add(realized)?;
+        if self.portfolio.positions[slot].quantity.is_zero() {
+            let closed = &self.portfolio.positions[slot];
+            if !remainder.is_zero() {
+                debug!("{} position in {} flipped to {:?} {}", account, symbol.0, side, remainder);
+            } else {
+                debug!("{} position in {} closed, realizing {}", account, symbol.0, closed.realized_pnl);
+            }
+            let venue = closed.venue.clone();
+            self.remove(slot);
+            self.open(account, symbol, venue, side, remainder, price)?;
+        }
+        self.refresh(account)?;
+        Ok(realized)
+    }
+
+    /// Open `account`'s position in `symbol` (none is open); nothing for a
+    /// zero quantity
+    fn open(
+        &mut self,
+        account: &AccountId,
+        symbol: Symbol,
+        venue: Venue,
+        side: Side,
+        quantity: Quantity,
+        price: Price,
+    ) -> Result<(), UnitError> {
+        if quantity.is_zero() {
+            return Ok(());
+        }
+        let key = (account.clone(), venue.clone());
+        let position = Position {
+            account: account.clone(),
+            symbol: symbol.clone(),
+            venue,
+            side,
+            quantity,
+            entry_price: price,
+            current_price: price,
+            unrealized_pnl: Amount::ZERO,
+            realized_pnl: Amount::ZERO,
+        };
+        let after = Contribution::of(&position)?;
+        self.index.entry(symbol).or_default().insert(key, self.portfolio.positions.len());
+        self.portfolio.positions.push(position);
+        self.adjust(account, Contribution::default(), after)
+    }
+
+    /// Replace `account`'s position in `symbol` on `venue` with one the
+    /// venue reported (None: flat), marked at the latest price known
+    ///
+    /// For reconciliation: no PnL is realized and no fee charged; the
+    /// difference shows up as unrealized PnL against the reported entry.
+    pub fn adopt_position(
+        &mut self,
+        account: &AccountId,
+        symbol: &Symbol,
+        venue: &Venue,
+        reported: Option<(Side, Quantity, Price)>,
+    ) -> Result<(), UnitError> {
+        if !self.accounts.contains_key(account) {
+            self.accounts.insert(account.clone(), Ledger::new(Amount::ZERO));
+        }
+        if let Some(slot) = self.slot(account, symbol, venue) {
+            let before = Contribution::of(&self.portfolio.positions[slot])?;
+            self.adjust(account, before, Contribution::default())?;
+            self.remove(slot);
+        }
+        if let Some((side, quantity, entry_price)) = reported.filter(|(_, quantity, _)| !quantity.is_zero()) {
+            let mark = self.marks.get(symbol).copied().unwrap_or(entry_price);
+            self.open(account, symbol.clone(), venue.clone(), side, quantity, entry_price)?;
+            if let Some(slot) = self.slot(account, symbol, venue) {
+                let position = &mut self.portfolio.positions[slot];
+                let before = Contribution::of(position)?;
+                position.current_price = mark;
+                position.unrealized_pnl = Amount::pnl(side, entry_price, mark, quantity)?;
+                let after = Contribution::of(position)?;
+                self.adjust(account, before, after)?;
+            }
+        }
+        self.refresh(account)
+    }
+
+    /// Set `account`'s cash (equity excluding unrealized PnL) to a balance
+    /// the venue reported
+    pub fn adopt_balance(&mut self, account: &AccountId, balance: Amount) -> Result<(), UnitError> {
+        let current = self.account_balance(account).unwrap_or(Amount::ZERO);
+        if !self.accounts.contains_key(account) {
+            self.accounts.insert(account.clone(), Ledger::new(Amount::ZERO));
+        }
+        self.book(account, balance.checked_sub(current)?)?;
+        self.refresh(account)
+    }
+
+    /// Debit `fee`, paid on a fill in `symbol` on `venue`, from `account`'s cash
+    fn charge_fee(&mut self, account: &AccountId, symbol: &Symbol, venue: &Venue, fee: Amount) -> Result<(), UnitError> {
+        if fee.is_zero() {
+            return Ok(());
+        }
+        self.book(account, Amount::ZERO.checked_sub(fee)?)?;
+        self.portfolio.total_fees = self.portfolio.total_fees.checked_add(fee)?;
+        let by_symbol = self.fees_by_symbol.entry(symbol.clone()).or_default();
+        *by_symbol = by_symbol.checked_add(fee)?;
+        let by_venue = self.fees_by_venue.entry(venue.clone()).or_default();
+        *by_venue = by_venue.checked_add(fee)?;
+        Ok(())
+    }
+
+    /// Settle one funding interval of `symbol` on `venue` at `rate` (longs
+    /// pay shorts when positive), on every account's position there valued
+    /// at `mark_price`; returns the net funding received
+    pub fn apply_funding(&mut self, symbol: &Symbol, venue: &Venue, rate: Decimal, mark_price: Price) -> Result<Amount, UnitError> {
+        let Some(slots) = self.index.get(symbol) else { return Ok(Amount::ZERO) };
+        let slots: Vec<usize> = slots.iter()
+            .filter(|((_, held_on), _)| held_on == venue)
+            .map(|(_, slot)| *slot)
+            .collect();
+        let mut received = Amount::ZERO;
+        let mut settled = BTreeSet::new();
+        for slot in slots {
+            let position = &self.portfolio.positions[slot];
+            let paid = mark_price.notional(position.quantity)?.scale(rate)?.signed(position.side);
+            let account = position.account.clone();
+            let payment = Amount::ZERO.checked_sub(paid)?;
+            self.book(&account, payment)?;
+            received = received.checked_add(payment)?;
+            settled.insert(account);
+        }
+        if settled.is_empty() {
+            return Ok(Amount::ZERO);
+        }
+        self.portfolio.total_funding = self.portfolio.total_funding.checked_add(received)?;
+        for account in &settled {
+            self.refresh_account(account)?;
+        }
+        self.refresh_aggregate()?;
+        Ok(received)
+    }
+
+    /// Move `cash` into (or, negative, out of) `account`'s and the overall
+    /// balance
+    fn book(&mut self, account: &AccountId, cash: Amount) -> Result<(), UnitError> {
+        if cash.is_zero() {
+            return Ok(());
+        }
+        self.base_equity = self.base_equity.checked_add(cash)?;
+        if let Some(ledger) = self.accounts.get_mut(account) {
+            ledger.base_equity = ledger.base_equity.checked_add(cash)?;
+        }
+        Ok(())
+    }
+
+    /// Attribute a fill (already applied with `update_position`) to `strategy`
+    pub fn attribute_fill(
+        &mut self,
+        strategy: &StrategyId,
+        symbol: &Symbol,
+        side: Side,
+        quantity: Quantity,
+    ) -> Result<(), UnitError> {
+        let held = self.portfolio.strategies.entry(strategy.clone()).or_default();
+        let net = held.net.entry(symbol.clone()).or_default();
+        match side {
+            Side::Buy => *net += quantity.get(),
+            Side::Sell => *net -= quantity.get(),
+        }
+        if net.is_zero() {
+            held.net.remove(symbol);
+        }
+        self.refresh_strategies()
+    }
+
+    /// Value every strategy's holdings at the latest marks
+    fn refresh_strategies(&mut self) -> Result<(), UnitError> {
+        for held in self.portfolio.strategies.values_mut() {
+            let mut exposure = Amount::ZERO;
+            for (symbol, net) in &held.net {
+                let Some(mark) = self.marks.get(symbol) else { continue };
+                exposure = exposure.checked_add(mark.notional(Quantity::new(net.abs())?)?)?;
+            }
+            held.exposure = exposure;
+        }
+        Ok(())
+    }
+
+    /// Update position prices (mark-to-market) in every account
+    pub fn update_prices(&mut self, prices: &HashMap<Symbol, Price>) -> Result<(), UnitError> {
+        let mut marked = BTreeSet::new();
+        self.marks.extend(prices.iter().map(|(symbol, price)| (symbol.clone(), *price)));
         for (symbol, price) in prices {
-            if let Some(position) = self.position_map.get_mut(symbol) {
+            self.returns.record(symbol, *price)?;
+        }
+        self.refresh_correlation()?;
+        if !self.portfolio.strategies.is_empty() {
+            self.refresh_strategies()?;
+        }
+        for (symbol, price) in prices {
+            let Some(slots) = self.index.get(symbol) else { continue };
+            let slots: Vec<usize> = slots.values().copied().collect();
+            for slot in slots {
+                let position = &mut self.portfolio.positions[slot];
+                let before = Contribution::of(position)?;
                 position.current_price = *price;
-                position.unrealized_pnl = match position.side {
-                    Side::Buy => (price - position.entry_price) * position.quantity,
-                    Side::Sell => (position.entry_price - price) * position.quantity,
-                };
+                position.unrealized_pnl = Amount::pnl(position.side, position.entry_price, *price, position.quantity)?;
+                let after = Contribution::of(position)?;
+                let account = position.account.clone();
+                self.adjust(&account, before, after)?;
+                marked.insert(account);
             }
         }
 
-        self.recalculate_metrics();
+        for account in &marked {
+            self.refresh_account(account)?;
+        }
+        self.refresh_aggregate()
     }
 
-    /// Recalculate all portfolio metrics
-    fn recalculate_metrics(&mut self) {
-        // Update positions vector
-        self.portfolio.positions = self.position_map.values()
-            .filter(|p| p.quantity > Decimal::ZERO)
-            .cloned()
+    /// Feed a mark for a symbol with no open position to the correlation
+    /// returns only (`update_prices` feeds them as it marks)
+    pub fn observe_mark(&mut self, symbol: &Symbol, price: Price) -> Result<(), UnitError> {
+        self.returns.record(symbol, price)?;
+        self.refresh_correlation()
+    }
+
+    /// Recompute the correlation matrix when due
+    fn refresh_correlation(&mut self) -> Result<(), UnitError> {
+        let interval = self.returns.config().refresh_interval;
+        self.updates_since_correlation += 1;
+        if interval == 0 || self.updates_since_correlation < interval {
+            return Ok(());
+        }
+        self.updates_since_correlation = 0;
+        let CorrelationMatrix { symbols, matrix } = self.returns.matrix()?;
+        self.portfolio.correlation_symbols = symbols;
+        self.portfolio.correlation_matrix = matrix;
+        Ok(())
+    }
+
+    /// Recompute the aggregates from every open position, record any
+    /// difference beyond the tolerance, and adopt the recomputed values
+    pub fn check_consistency(&mut self) -> Result<Vec<MetricDrift>, UnitError> {
+        let mut full = Contribution::default();
+        let mut by_account: BTreeMap<AccountId, Contribution> = self.accounts.keys()
+            .map(|account| (account.clone(), Contribution::default()))
             .collect();
+        for position in &self.portfolio.positions {
+            let part = Contribution::of(position)?;
+            full = full.plus(part)?;
+            let totals = by_account.entry(position.account.clone()).or_default();
+            *totals = totals.plus(part)?;
+        }
+        for (account, totals) in by_account {
+            let ledger = self.accounts.entry(account).or_insert_with(|| Ledger::new(Amount::ZERO));
+            ledger.totals = totals;
+            ledger.refresh()?;
+        }
+        let equity = self.base_equity.checked_add(full.unrealized)?;
+        let leverage = leverage(full.gross, equity)?;
+
+        let compared = [
+            ("total_exposure", self.portfolio.total_exposure.get(), full.gross.get()),
+            ("net_exposure", self.portfolio.net_exposure.get(), full.net.get()),
+            ("unrealized_pnl", self.unrealized.get(), full.unrealized.get()),
+            ("leverage", self.portfolio.leverage, leverage),
+        ];
+        let drift: Vec<MetricDrift> = compared.into_iter()
+            .filter(|(_, tracked, recomputed)| (tracked - recomputed).abs() > self.drift_tolerance)
+            .map(|(metric, tracked, recomputed)| MetricDrift { metric, tracked, recomputed })
+            .collect();
+        for d in &drift {
+            error!("Portfolio {} drifted: tracked {}, recomputed {}", d.metric, d.tracked, d.recomputed);
+        }
+
+        self.portfolio.total_exposure = full.gross;
+        self.portfolio.net_exposure = full.net;
+        self.unrealized = full.unrealized;
+        self.portfolio.equity = equity;
+        self.portfolio.leverage = leverage;
+        self.updates_since_check = 0;
+        self.drift.extend(drift.iter().cloned());
+        Ok(drift)
+    }
 
-        // Calculate total exposure
-        self.portfolio.total_exposure = self.portfolio.positions.iter()
-            .map(|p| p.quantity * p.current_price)
-            .sum();
+    /// Drift found by the periodic checks since the last call
+    pub fn take_drift(&mut self) -> Vec<MetricDrift> {
+        std::mem::take(&mut self.drift)
+    }
+
+    /// Move the aggregates (and `account`'s sums) by one position's change
+    fn adjust(&mut self, account: &AccountId, before: Contribution, after: Contribution) -> Result<(), UnitError> {
+        let portfolio = &mut self.portfolio;
+        portfolio.total_exposure = portfolio.total_exposure.checked_sub(before.gross)?.checked_add(after.gross)?;
+        portfolio.net_exposure = portfolio.net_exposure.checked_sub(before.net)?.checked_add(after.net)?;
+        self.unrealized = self.unrealized.checked_sub(before.unrealized)?.checked_add(after.unrealized)?;
+        if let Some(ledger) = self.accounts.get_mut(account) {
+            ledger.totals = ledger.totals.minus(before)?.plus(after)?;
+        }
+        Ok(())
+    }
 
-        // Calculate net exposure
-        let long_exposure: Decimal = self.portfolio.positions.iter()
-            .filter(|p| p.side == Side::Buy)
-            .map(|p| p.quantity * p.current_price)
-            .sum();
+    /// Where `account`'s position in `symbol` on `venue` sits
+    fn slot(&self, account: &AccountId, symbol: &Symbol, venue: &Venue) -> Option<usize> {
+        self.index.get(symbol)?.get(&(account.clone(), venue.clone())).copied()
+    }
 
-        let short_exposure: Decimal = self.portfolio.positions.iter()
-            .filter(|p| p.side == Side::Sell)
-            .map(|p| p.quantity * p.current_price)
-            .sum();
+    /// Drop a closed position, keeping the index in step
+    fn remove(&mut self, slot: usize) {
+        let removed = self.portfolio.positions.swap_remove(slot);
+        if let Some(slots) = self.index.get_mut(&removed.symbol) {
+            slots.remove(&(removed.account, removed.venue));
+            if slots.is_empty() {
+                self.index.remove(&removed.symbol);
+            }
+        }
+        if let Some(moved) = self.portfolio.positions.get(slot) {
+            if let Some(slots) = self.index.get_mut(&moved.symbol) {
+                slots.insert((moved.account.clone(), moved.venue.clone()), slot);
+            }
+        }
+    }
 
-        self.portfolio.net_exposure = long_exposure - short_exposure;
+    /// Derive `account`'s and the overall equity and leverage from the sums
+    fn refresh(&mut self, account: &AccountId) -> Result<(), UnitError> {
+        self.refresh_account(account)?;
+        self.refresh_aggregate()
+    }
 
-        // Calculate leverage
-        if self.portfolio.equity > Decimal::ZERO {
-            self.portfolio.leverage = self.portfolio.total_exposure / self.portfolio.equity;
-        } else {
-            self.portfolio.leverage = Decimal::ZERO;
+    fn refresh_account(&mut self, account: &AccountId) -> Result<(), UnitError> {
+        match self.accounts.get_mut(account) {
+            Some(ledger) => ledger.refresh(),
+            None => Ok(()),
         }
+    }
 
-        // Update equity (including unrealized PnL)
-        let total_unrealized: Decimal = self.portfolio.positions.iter()
-            .map(|p| p.unrealized_pnl)
-            .sum();
+    /// Derive equity and leverage from the aggregates; run the full check when due
+    fn refresh_aggregate(&mut self) -> Result<(), UnitError> {
+        self.portfolio.equity = self.base_equity.checked_add(self.unrealized)?;
+        self.portfolio.leverage = leverage(self.portfolio.total_exposure, self.portfolio.equity)?;
 
-        self.portfolio.equity = self.portfolio.equity + total_unrealized;
+        self.updates_since_check += 1;
+        if self.consistency_interval > 0 && self.updates_since_check >= self.consistency_interval {
+            self.check_consistency()?;
+        }
+        Ok(())
     }
 
-    /// Get current portfolio
+    /// Get current portfolio (every account together)
     pub fn portfolio(&self) -> &Portfolio {
         &self.portfolio
     }
 
-    /// Get position for symbol
-    pub fn get_position(&self, symbol: &Symbol) -> Option<&Position> {
-        self.position_map.get(symbol)
+    /// One account's positions and balances as a portfolio of its own
+    pub fn account_portfolio(&self, account: &AccountId) -> Option<Portfolio> {
+        let ledger = self.accounts.get(account)?;
+        let mut portfolio = Portfolio::new(ledger.equity);
+        portfolio.positions = self.portfolio.positions.iter()
+            .filter(|position| &position.account == account)
+            .cloned()
+            .collect();
+        portfolio.total_exposure = ledger.totals.gross;
+        portfolio.net_exposure = ledger.totals.net;
+        portfolio.leverage = ledger.leverage;
+        Some(portfolio)
+    }
+
+    /// PnL realized by fills across every account, closed positions included
+    pub fn realized_pnl(&self) -> Amount {
+        self.realized
+    }
+
+    /// Fees paid per symbol, across accounts
+    pub fn fees_by_symbol(&self) -> &BTreeMap<Symbol, Amount> {
+        &self.fees_by_symbol
+    }
+
+    /// Fees paid per venue, across accounts
+    pub fn fees_by_venue(&self) -> &BTreeMap<Venue, Amount> {
+        &self.fees_by_venue
+    }
+
+    /// Latest fill or mark price seen for `symbol`
+    pub fn mark(&self, symbol: &Symbol) -> Option<Price> {
+        self.marks.get(symbol).copied()
+    }
+
+    /// One account's cash: equity excluding unrealized PnL
+    pub fn account_balance(&self, account: &AccountId) -> Option<Amount> {
+        self.accounts.get(account).map(|ledger| ledger.base_equity)
+    }
+
+    /// `account`'s open positions on `venue`
+    pub fn positions_at<'a>(&'a self, account: &'a AccountId, venue: &'a Venue) -> impl Iterator<Item = &'a Position> + 'a {
+        self.portfolio.positions.iter()
+            .filter(move |position| &position.account == account && &position.venue == venue)
+    }
+
+    /// One account's equity, including its unrealized PnL
+    pub fn account_equity(&self, account: &AccountId) -> Option<Amount> {
+        self.accounts.get(account).map(|ledger| ledger.equity)
+    }
+
+    /// Accounts held, in name order
+    pub fn accounts(&self) -> impl Iterator<Item = &AccountId> {
+        self.accounts.keys()
+    }
+
+    /// Whether more than one account is held (otherwise the aggregate is the
+    /// only account)
+    pub fn is_multi_account(&self) -> bool {
+        self.accounts.len() > 1
+    }
+
+    /// Get `account`'s open position for symbol on `venue`
+    pub fn get_position_at(&self, account: &AccountId, symbol: &Symbol, venue: &Venue) -> Option<&Position> {
+        self.slot(account, symbol, venue).map(|slot| &self.portfolio.positions[slot])
+    }
+
+    /// Signed quantity held in `symbol` (positive long), netted across
+    /// venues and accounts
+    pub fn net_position(&self, symbol: &Symbol) -> Result<Decimal, UnitError> {
+        let Some(slots) = self.index.get(symbol) else { return Ok(Decimal::ZERO) };
+        slots.values().try_fold(Decimal::ZERO, |net, slot| {
+            let position = &self.portfolio.positions[*slot];
+            let quantity = match position.side {
+                Side::Buy => position.quantity.get(),
+                Side::Sell => -position.quantity.get(),
+            };
+            Ok(arith::checked_add("net position", net, quantity)?)
+        })
+    }
+
+    /// Gross notional of the positions held on each venue, at their marks
+    pub fn exposure_by_venue(&self) -> Result<BTreeMap<Venue, Amount>, UnitError> {
+        let mut exposure: BTreeMap<Venue, Amount> = BTreeMap::new();
+        for position in &self.portfolio.positions {
+            let held = exposure.entry(position.venue.clone()).or_default();
+            *held = held.checked_add(Contribution::of(position)?.gross)?;
+        }
+        Ok(exposure)
+    }
+
+    /// Whether any account holds a position in symbol
+    pub fn has_position(&self, symbol: &Symbol) -> bool {
+        self.index.contains_key(symbol)
+    }
+
+    /// Write the portfolio to `path` as JSON (temp file + rename)
+    pub fn save(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let path = path.as_ref();
+        let contents = serde_json::to_string_pretty(&self.portfolio).map_err(std::io::Error::other)?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, contents)?;
+        std::fs::rename(&tmp, path)
     }
 }
 
+fn leverage(exposure: Amount, equity: Amount) -> Result<Decimal, UnitError> {
+    if equity.is_positive() {
+        exposure.ratio(equity)
+    } else {
+        Ok(Decimal::ZERO)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axiom_core::OrderType;
+    use crate::fees::{FeeModel, VenueFees};
+
+    fn price(value: &str) -> Price {
+        Price::new(value.parse().unwrap()).unwrap()
+    }
+
+    fn quantity(value: &str) -> Quantity {
+        Quantity::new(value.parse().unwrap()).unwrap()
+    }
+
+    fn amount(value: &str) -> Amount {
+        Amount::new(value.parse().unwrap())
+    }
+
+    fn btc() -> Symbol {
+        Symbol("BTC/USDT".to_string())
+    }
+
+    #[test]
+    fn offsetting_legs_on_two_venues_net_to_zero() {
+        let account = AccountId::default();
+        let mut manager = PortfolioManager::new(amount("100000"));
+        manager.update_position(&account, btc(), Venue::Binance, Side::Buy, quantity("1"), price("50000"), Amount::ZERO).unwrap();
+        manager.update_position(&account, btc(), Venue::Bybit, Side::Sell, quantity("1"), price("50100"), Amount::ZERO).unwrap();
+        manager.update_prices(&HashMap::from([(btc(), price("50050"))])).unwrap();
+
+        // Each leg keeps its own entry price
+        assert_eq!(manager.get_position_at(&account, &btc(), &Venue::Binance).unwrap().entry_price, price("50000"));
+        assert_eq!(manager.get_position_at(&account, &btc(), &Venue::Bybit).unwrap().entry_price, price("50100"));
+
+        assert_eq!(manager.net_position(&btc()).unwrap(), Decimal::ZERO);
+        let portfolio = manager.portfolio();
+        assert_eq!(portfolio.net_exposure, Amount::ZERO);
+        assert_eq!(portfolio.total_exposure, amount("100100"));
+        // Both legs are 50 in profit: 100100 of exposure on 100100 of equity
+        assert_eq!(portfolio.leverage, Decimal::ONE);
+
+        let by_venue = manager.exposure_by_venue().unwrap();
+        assert_eq!(by_venue[&Venue::Binance], amount("50050"));
+        assert_eq!(by_venue[&Venue::Bybit], amount("50050"));
+    }
+
+    #[test]
+    fn partial_close_realizes_the_closed_part_only() {
+        let account = AccountId::default();
+        let mut manager = PortfolioManager::new(amount("10000"));
+        manager.update_position(&account, btc(), Venue::Binance, Side::Buy, quantity("2"), price("100"), Amount::ZERO).unwrap();
+        let realized = manager.update_position(&account, btc(), Venue::Binance, Side::Sell, quantity("0.5"), price("110"), Amount::ZERO).unwrap();
+
+        assert_eq!(realized, amount("5"));
+        let position = manager.get_position_at(&account, &btc(), &Venue::Binance).unwrap();
+        assert_eq!((position.side, position.quantity, position.entry_price), (Side::Buy, quantity("1.5"), price("100")));
+        assert_eq!(position.realized_pnl, amount("5"));
+        assert_eq!(position.unrealized_pnl, amount("15"));
+        assert_eq!(manager.portfolio().equity, amount("10020"));
+    }
+
+    #[test]
+    fn exact_close_realizes_everything_and_removes_the_position() {
+        let account = AccountId::default();
+        let mut manager = PortfolioManager::new(amount("10000"));
+        manager.update_position(&account, btc(), Venue::Binance, Side::Buy, quantity("1"), price("100"), Amount::ZERO).unwrap();
+        let realized = manager.update_position(&account, btc(), Venue::Binance, Side::Sell, quantity("1"), price("90"), Amount::ZERO).unwrap();
+
+        assert_eq!(realized, amount("-10"));
+        assert!(manager.get_position_at(&account, &btc(), &Venue::Binance).is_none());
+        assert_eq!(manager.realized_pnl(), amount("-10"));
+        assert_eq!(manager.portfolio().equity, amount("9990"));
+        assert_eq!(manager.portfolio().total_exposure, Amount::ZERO);
+    }
+
+    #[test]
+    fn flip_through_zero_opens_the_remainder_at_the_fill_price() {
+        let account = AccountId::default();
+        let mut manager = PortfolioManager::new(amount("10000"));
+        manager.update_position(&account, btc(), Venue::Binance, Side::Buy, quantity("1"), price("100"), Amount::ZERO).unwrap();
+        let realized = manager.update_position(&account, btc(), Venue::Binance, Side::Sell, quantity("3"), price("120"), Amount::ZERO).unwrap();
+
+        assert_eq!(realized, amount("20"));
+        let position = manager.get_position_at(&account, &btc(), &Venue::Binance).unwrap();
+        assert_eq!((position.side, position.quantity, position.entry_price), (Side::Sell, quantity("2"), price("120")));
+        assert_eq!(position.realized_pnl, Amount::ZERO);
+        assert_eq!(position.unrealized_pnl, Amount::ZERO);
+        assert_eq!(manager.net_position(&btc()).unwrap(), "-2".parse::<Decimal>().unwrap());
+        assert_eq!(manager.portfolio().equity, amount("10020"));
+    }
+
+    #[test]
+    fn round_trip_nets_both_fees_and_funding_out_of_the_gross() {
+        let account = AccountId::default();
+        let fees = FeeModel::new().with_venue(Venue::Binance, VenueFees { maker_bps: "2".parse().unwrap(), taker_bps: "5".parse().unwrap() });
+        let mut manager = PortfolioManager::new(amount("100000"));
+
+        // Rested in at 50000 (maker, 10), held over one funding interval, crossed out at 51000 (taker, 25.5)
+        let entry_fee = fees.fill_fee(&Venue::Binance, &OrderType::Limit, quantity("1"), price("50000")).unwrap();
+        manager.update_position(&account, btc(), Venue::Binance, Side::Buy, quantity("1"), price("50000"), entry_fee).unwrap();
+        let funding = manager.apply_funding(&btc(), &Venue::Binance, "0.0001".parse().unwrap(), price("50500")).unwrap();
+        let exit_fee = fees.fill_fee(&Venue::Binance, &OrderType::Market, quantity("1"), price("51000")).unwrap();
+        let gross = manager.update_position(&account, btc(), Venue::Binance, Side::Sell, quantity("1"), price("51000"), exit_fee).unwrap();
+
+        assert_eq!((entry_fee, exit_fee), (amount("10"), amount("25.5")));
+        assert_eq!(funding, amount("-5.05"));
+        assert_eq!(gross, amount("1000"));
+        let portfolio = manager.portfolio();
+        assert_eq!(portfolio.total_fees, amount("35.5"));
+        assert_eq!(portfolio.total_funding, amount("-5.05"));
+        // net = 1000 - 10 - 25.5 - 5.05
+        assert_eq!(portfolio.equity, amount("100959.45"));
+        assert_eq!(manager.fees_by_venue()[&Venue::Binance], amount("35.5"));
+    }
+
+    /// One BTC bought at 100 and marked at 110 against 10000, checked only
+    /// when asked: 110 of exposure, 10 unrealized
+    fn marked(interval: u64) -> PortfolioManager {
+        let account = AccountId::default();
+        let mut manager = PortfolioManager::new(amount("10000")).with_consistency_check(interval, DEFAULT_DRIFT_TOLERANCE);
+        manager.update_position(&account, btc(), Venue::Binance, Side::Buy, quantity("1"), price("100"), Amount::ZERO).unwrap();
+        manager.update_prices(&HashMap::from([(btc(), price("110"))])).unwrap();
+        manager
+    }
+
+    /// Apply a delta no position accounts for, as a lost or doubled update would
+    fn inject(manager: &mut PortfolioManager, gross: &str, unrealized: &str) {
+        let account = AccountId::default();
+        let stray = Contribution { gross: amount(gross), net: amount(gross), unrealized: amount(unrealized) };
+        manager.adjust(&account, Contribution::default(), stray).unwrap();
+        manager.refresh(&account).unwrap();
+    }
+
+    #[test]
+    fn injected_drift_is_reported_and_replaced_by_the_recomputed_values() {
+        let mut manager = marked(0);
+        inject(&mut manager, "5", "2");
+        assert_eq!(manager.portfolio().total_exposure, amount("115"));
+
+        let drift = manager.check_consistency().unwrap();
+        let metrics: Vec<&str> = drift.iter().map(|d| d.metric).collect();
+        assert_eq!(metrics, vec!["total_exposure", "net_exposure", "unrealized_pnl", "leverage"]);
+        assert_eq!((drift[0].tracked, drift[0].recomputed), (Decimal::from(115), Decimal::from(110)));
+        assert_eq!((drift[2].tracked, drift[2].recomputed), (Decimal::from(12), Decimal::from(10)));
+        assert_eq!(drift[3].recomputed, Decimal::from(110) / Decimal::from(10_010));
+
+        let portfolio = manager.portfolio();
+        assert_eq!((portfolio.total_exposure, portfolio.net_exposure), (amount("110"), amount("110")));
+        assert_eq!(portfolio.equity, amount("10010"));
+        assert_eq!(manager.take_drift(), drift);
+        assert!(manager.take_drift().is_empty());
+    }
+
+    #[test]
+    fn drift_within_the_tolerance_is_not_reported() {
+        let mut manager = marked(0);
+        inject(&mut manager, "0.0000005", "0");
+        assert!(manager.check_consistency().unwrap().is_empty());
+        assert!(manager.take_drift().is_empty());
+        assert_eq!(manager.portfolio().total_exposure, amount("110"));
+    }
+
+    #[test]
+    fn the_periodic_check_reports_drift_injected_between_updates() {
+        // The fill, the mark and the injected delta's refresh are updates 1
+        // to 3; the check runs on the 4th
+        let mut manager = marked(4);
+        inject(&mut manager, "5", "0");
+        assert!(manager.take_drift().is_empty());
+
+        manager.update_prices(&HashMap::from([(btc(), price("111"))])).unwrap();
+        let drift = manager.take_drift();
+        assert_eq!(drift[0], MetricDrift { metric: "total_exposure", tracked: Decimal::from(116), recomputed: Decimal::from(111) });
+        assert_eq!(manager.portfolio().total_exposure, amount("111"));
+    }
+
+    #[test]
+    fn fills_and_ticks_over_fifty_positions_stay_within_rounding() {
+        // Averaged entry prices round in the last places, so only the
+        // tolerance keeps the running sums and the recomputation equal
+        let account = AccountId::default();
+        let mut manager = PortfolioManager::new(amount("1000000"))
+            .with_consistency_check(7, DEFAULT_DRIFT_TOLERANCE)
+            .with_correlation(CorrelationConfig { refresh_interval: 0, ..CorrelationConfig::default() });
+        let symbols: Vec<Symbol> = (0..50).map(|index| Symbol(format!("SYM{index}/USDT"))).collect();
+        for step in 0..2_000u32 {
+            let symbol = symbols[(step * 7 % 50) as usize].clone();
+            let mark = Price::new(Decimal::from(100 + step % 13)).unwrap();
+            if step % 3 == 0 {
+                let side = if step % 2 == 0 { Side::Buy } else { Side::Sell };
+                let size = Quantity::new(Decimal::new(i64::from(1 + step % 5), 1)).unwrap();
+                manager.update_position(&account, symbol, Venue::Binance, side, size, mark, Amount::ZERO).unwrap();
+            } else {
+                manager.update_prices(&HashMap::from([(symbol, mark)])).unwrap();
+            }
+        }
+        assert!(manager.take_drift().is_empty());
+        assert!(manager.check_consistency().unwrap().is_empty());
+    }
+}
@@ -2,51 +2,94 @@
 //!
 //! Calculates optimal position size based on risk budget and certainty score.
 
-use axiom_core::{TradeSignal, Portfolio, Decimal};
-use axiom_core::constants::*;
+use axiom_core::{
+    round_price_to_tick, round_qty_to_step, Instrument, TradeSignal, Portfolio, Price, Quantity, UnitError, LimitsError,
+    LimitsRegistry,
+};
 use rust_decimal::Decimal;
 
 /// Calculate position size using Kelly Criterion
 ///
-/// Position size is adjusted by the certainty score (1 - P(Hallucination))
+/// Position size is adjusted by the certainty score (1 - P(Hallucination)),
+/// rounded down to the lot step. A size below the minimum notional is
+/// rounded up to the minimum when that still fits the full (unweighted)
+/// risk budget and position limit; otherwise the result is zero (no trade),
+/// never dust. With the venue's `instrument` rules, its step, size range and
+/// minimum notional apply too, and the size is valued at the limit price
+/// as the venue's tick grid will have it.
 pub fn calculate_position_size(
     signal: &TradeSignal,
     portfolio: &Portfolio,
     certainty_score: Decimal,
-) -> Decimal {
+    limits: &LimitsRegistry,
+    instrument: Option<&Instrument>,
+) -> Result<Quantity, SizingError> {
+    let symbol_limits = limits.get(&signal.symbol)?;
+
     // Base risk budget (0.25% - 1% of equity)
-    let base_risk = portfolio.equity * MAX_RISK_BUDGET;
+    let base_risk = portfolio.equity.scale(symbol_limits.max_risk_budget())?;
     
     // Adjust by certainty score
-    let adjusted_risk = base_risk * certainty_score;
+    let adjusted_risk = base_risk.scale(certainty_score)?;
     
     // Calculate position size from risk and stop distance
     // Simplified: assume 2% stop loss
     let stop_distance = Decimal::from(2) / Decimal::from(100);
     
-    if stop_distance == Decimal::ZERO {
-        return Decimal::ZERO;
+    if stop_distance == Decimal::ZERO || !adjusted_risk.is_positive() {
+        return Ok(Quantity::ZERO);
     }
     
-    let position_value = adjusted_risk / stop_distance;
+    let position_value = adjusted_risk.scale(Decimal::ONE / stop_distance)?;
     
-    // Get price
-    let price = signal.limit_price.unwrap_or(Decimal::ZERO);
-    if price == Decimal::ZERO {
-        return Decimal::ZERO;
+    // Get price, on the venue's tick grid
+    let mut price = signal.limit_price.unwrap_or(Price::ZERO);
+    if let Some(instrument) = instrument {
+        price = round_price_to_tick(price, instrument.price_tick, signal.side)?;
+    }
+    if price.is_zero() {
+        return Ok(Quantity::ZERO);
     }
     
     // Position size in base currency
-    let size = position_value / price;
+    let size = position_value.quantity_at(price)?;
     
-    // Enforce maximum position size
-    let max_size = match signal.symbol.0.as_str() {
-        "BTC/USD" => MAX_POSITION_SIZE_BTC,
-        "ETH/USD" => MAX_POSITION_SIZE_ETH,
-        "SOL/USD" => MAX_POSITION_SIZE_SOL,
-        _ => Decimal::from(1),
+    // Enforce maximum position and order size and the lot step
+    let max_size = match instrument.and_then(|instrument| instrument.max_quantity) {
+        Some(max) => symbol_limits.max_position.min(max),
+        None => symbol_limits.max_position,
     };
-    
-    size.min(max_size)
+    let step = instrument.map(|instrument| instrument.quantity_step).or(symbol_limits.step_size);
+    let mut size = size.min(max_size);
+    if let Some(step) = step {
+        size = round_qty_to_step(size, step)?;
+    }
+    let min_notional = instrument.map_or(symbol_limits.min_notional, |instrument| {
+        instrument.min_notional.max(symbol_limits.min_notional)
+    });
+    let min_size = instrument.map_or(Quantity::ZERO, |instrument| instrument.min_quantity);
+    if size >= min_size && price.notional(size)? >= min_notional {
+        return Ok(size);
+    }
+
+    // Below the minimum: round up if the budget allows, else no trade
+    let mut minimum = min_notional.quantity_at(price)?.max(min_size);
+    if let Some(step) = step.filter(|step| !step.is_zero()) {
+        minimum = minimum.ceil_to(step)?;
+    }
+    let max_value = base_risk.scale(Decimal::ONE / stop_distance)?;
+    if minimum <= max_size && price.notional(minimum)? <= max_value {
+        Ok(minimum)
+    } else {
+        Ok(Quantity::ZERO)
+    }
 }
 
+#[derive(Debug, thiserror::Error)]
+pub enum SizingError {
+    #[error(transparent)]
+    Unit(#[from] UnitError),
+
+    #[error(transparent)]
+    Limits(#[from] LimitsError),
+}
@@ -0,0 +1,388 @@
+//! Reconciliation: Local Books Against the Venue's
+//!
+//! Fills can be missed and positions changed by hand on the venue, so the
+//! tracked portfolio drifts from what the venue holds. A `Reconciler` diffs
+//! a venue's `ExchangePositionReport` against the account's positions and
+//! cash on that venue and lists every difference beyond tolerance: a
+//! position the venue holds and we do not (an unreported fill), one we
+//! hold and it does not (a phantom), a size or entry price apart, a
+//! balance apart. What happens next is the policy's: log only, adopt the
+//! venue's figures, or trip the account's circuit breaker.
+
+use axiom_core::{
+    AccountId, Amount, ExchangePositionReport, Position, Price, Quantity, Side, Symbol, UnitError, Venue,
+};
+use axiom_core::arith::{checked_add, checked_div, checked_mul, checked_sub};
+use axiom_core::constants::BPS_PER_UNIT;
+use crate::circuit_breaker::AccountBreakers;
+use crate::portfolio::PortfolioManager;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tracing::{info, warn};
+
+/// What to do about differences beyond tolerance
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReconciliationPolicy {
+    /// Report them and change nothing
+    #[default]
+    LogOnly,
+    /// Take the venue's positions and balance as the truth
+    AdoptExchange,
+    /// Trip the account's circuit breaker until someone looks
+    Halt,
+}
+
+/// Reconciliation tuning
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReconciliationConfig {
+    pub policy: ReconciliationPolicy,
+    /// Size difference, in base units, treated as rounding
+    pub quantity_tolerance: Decimal,
+    /// Entry price difference, in bps of the reported price, treated as
+    /// rounding
+    pub price_tolerance_bps: Decimal,
+    /// Balance difference, in quote currency, treated as rounding
+    pub balance_tolerance: Decimal,
+}
+
+impl Default for ReconciliationConfig {
+    fn default() -> Self {
+        Self {
+            policy: ReconciliationPolicy::LogOnly,
+            quantity_tolerance: Decimal::new(1, 8),
+            price_tolerance_bps: Decimal::ONE,
+            balance_tolerance: Decimal::new(1, 2),
+        }
+    }
+}
+
+/// Which figure disagrees
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MismatchField {
+    /// Signed size (positive long); 0 on one side is a position the other
+    /// does not hold
+    Quantity,
+    EntryPrice,
+    Balance,
+}
+
+/// One difference beyond tolerance
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Mismatch {
+    /// None for the balance
+    pub symbol: Option<Symbol>,
+    pub field: MismatchField,
+    pub local: Decimal,
+    pub reported: Decimal,
+}
+
+impl Mismatch {
+    /// A position the venue holds that is not tracked locally
+    pub fn is_unreported_fill(&self) -> bool {
+        self.field == MismatchField::Quantity && self.local.is_zero()
+    }
+
+    /// A position tracked locally that the venue does not hold
+    pub fn is_phantom(&self) -> bool {
+        self.field == MismatchField::Quantity && self.reported.is_zero()
+    }
+}
+
+impl std::fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let field = match self.field {
+            MismatchField::Quantity => "quantity",
+            MismatchField::EntryPrice => "entry price",
+            MismatchField::Balance => "balance",
+        };
+        match &self.symbol {
+            Some(symbol) => write!(f, "{} {}: local {}, venue {}", symbol.0, field, self.local, self.reported),
+            None => write!(f, "{}: local {}, venue {}", field, self.local, self.reported),
+        }
+    }
+}
+
+/// What was done about the mismatches
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReconciliationAction {
+    /// Nothing beyond tolerance
+    None,
+    Logged,
+    Adopted,
+    Halted,
+}
+
+/// One account's books on one venue against the venue's report
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReconciliationReport {
+    pub account: AccountId,
+    pub venue: Venue,
+    /// When the venue produced the report
+    pub reported_at: DateTime<Utc>,
+    pub mismatches: Vec<Mismatch>,
+    pub action: ReconciliationAction,
+}
+
+impl ReconciliationReport {
+    pub fn is_clean(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+
+    /// The mismatches on one line, for logs and alerts
+    pub fn summary(&self) -> String {
+        if self.is_clean() {
+            return "consistent with venue".to_string();
+        }
+        self.mismatches.iter().map(Mismatch::to_string).collect::<Vec<_>>().join("; ")
+    }
+}
+
+/// Diffs venue reports against the tracked portfolio
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Reconciler {
+    config: ReconciliationConfig,
+}
+
+impl Reconciler {
+    pub fn new(config: ReconciliationConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn config(&self) -> ReconciliationConfig {
+        self.config
+    }
+
+    /// Differences beyond tolerance between `report` and `manager`'s books
+    /// for the report's account and venue; changes nothing
+    pub fn diff(&self, manager: &PortfolioManager, report: &ExchangePositionReport) -> Result<Vec<Mismatch>, UnitError> {
+        let local: BTreeMap<&Symbol, &Position> = manager.positions_at(&report.account, &report.venue)
+            .map(|position| (&position.symbol, position))
+            .collect();
+        let reported = reported_positions(report)?;
+
+        let mut symbols: Vec<&Symbol> = local.keys().chain(reported.keys()).copied().collect();
+        symbols.sort();
+        symbols.dedup();
+
+        let mut mismatches = Vec::new();
+        for symbol in symbols {
+            let position = local.get(symbol);
+            let local_quantity = position.map_or(Decimal::ZERO, |position| signed(position.side, position.quantity));
+            let (reported_quantity, reported_entry) = reported.get(symbol).copied().unwrap_or((Decimal::ZERO, None));
+            if checked_sub("reconciliation", local_quantity, reported_quantity)?.abs() > self.config.quantity_tolerance {
+                mismatches.push(Mismatch {
+                    symbol: Some(symbol.clone()),
+                    field: MismatchField::Quantity,
+                    local: local_quantity,
+                    reported: reported_quantity,
+                });
+                continue;
+            }
+            // Same size: compare where it was entered, when the venue says
+            if let (Some(position), Some(entry)) = (position, reported_entry) {
+                if !entry.is_zero() {
+                    let apart = checked_mul(
+                        "reconciliation",
+                        checked_div("reconciliation", position.entry_price.delta(entry).abs(), entry.get())?,
+                        BPS_PER_UNIT,
+                    )?;
+                    if apart > self.config.price_tolerance_bps {
+                        mismatches.push(Mismatch {
+                            symbol: Some(symbol.clone()),
+                            field: MismatchField::EntryPrice,
+                            local: position.entry_price.get(),
+                            reported: entry.get(),
+                        });
+                    }
+                }
+            }
+        }
+
+        if let Some(balance) = report.balance {
+            let local = manager.account_balance(&report.account).unwrap_or(Amount::ZERO);
+            if balance.checked_sub(local)?.get().abs() > self.config.balance_tolerance {
+                mismatches.push(Mismatch {
+                    symbol: None,
+                    field: MismatchField::Balance,
+                    local: local.get(),
+                    reported: balance.get(),
+                });
+            }
+        }
+        Ok(mismatches)
+    }
+
+    /// Diff `report` against `manager` and act on the mismatches as the
+    /// policy says
+    pub fn reconcile(
+        &self,
+        manager: &mut PortfolioManager,
+        breakers: &mut AccountBreakers,
+        report: &ExchangePositionReport,
+    ) -> Result<ReconciliationReport, UnitError> {
+        let mismatches = self.diff(manager, report)?;
+        let action = match (mismatches.is_empty(), self.config.policy) {
+            (true, _) => ReconciliationAction::None,
+            (false, ReconciliationPolicy::LogOnly) => ReconciliationAction::Logged,
+            (false, ReconciliationPolicy::AdoptExchange) => {
+                self.adopt(manager, report, &mismatches)?;
+                ReconciliationAction::Adopted
+            }
+            (false, ReconciliationPolicy::Halt) => ReconciliationAction::Halted,
+        };
+        let result = ReconciliationReport {
+            account: report.account.clone(),
+            venue: report.venue.clone(),
+            reported_at: report.timestamp,
+            mismatches,
+            action,
+        };
+
+        match action {
+            ReconciliationAction::None => info!("{} on {}: {}", result.account, result.venue, result.summary()),
+            ReconciliationAction::Halted => breakers.trip_manual(&result.account, format!(
+                "Reconciliation with {} failed: {}", result.venue, result.summary()
+            )),
+            _ => warn!("{} on {} differs from the venue ({:?}): {}", result.account, result.venue, action, result.summary()),
+        }
+        Ok(result)
+    }
+
+    /// Take the venue's figures for every mismatched position and balance
+    fn adopt(&self, manager: &mut PortfolioManager, report: &ExchangePositionReport, mismatches: &[Mismatch]) -> Result<(), UnitError> {
+        let reported = reported_positions(report)?;
+        let mut symbols: Vec<&Symbol> = mismatches.iter().filter_map(|mismatch| mismatch.symbol.as_ref()).collect();
+        symbols.dedup();
+        for symbol in symbols {
+            let adopted = match reported.get(symbol) {
+                Some((net, entry)) if !net.is_zero() => {
+                    let side = if *net < Decimal::ZERO { Side::Sell } else { Side::Buy };
+                    // Without a reported entry the local one (or the mark) stands
+                    let local_entry = manager.get_position_at(&report.account, symbol, &report.venue)
+                        .map(|position| position.entry_price);
+                    let Some(entry) = entry.or(local_entry).or(manager.mark(symbol)) else {
+                        warn!("{} on {}: no entry price or mark to adopt it at; left as is", symbol.0, report.venue);
+                        continue;
+                    };
+                    Some((side, Quantity::new(net.abs())?, entry))
+                }
+                _ => None,
+            };
+            manager.adopt_position(&report.account, symbol, &report.venue, adopted)?;
+        }
+        if mismatches.iter().any(|mismatch| mismatch.field == MismatchField::Balance) {
+            if let Some(balance) = report.balance {
+                manager.adopt_balance(&report.account, balance)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The report's signed size (positive long) and entry price per symbol
+fn reported_positions(report: &ExchangePositionReport) -> Result<BTreeMap<&Symbol, (Decimal, Option<Price>)>, UnitError> {
+    let mut reported: BTreeMap<&Symbol, (Decimal, Option<Price>)> = BTreeMap::new();
+    for position in &report.positions {
+        let held = reported.entry(&position.symbol).or_insert((Decimal::ZERO, None));
+        held.0 = checked_add("reconciliation", held.0, signed(position.side, position.quantity))?;
+        held.1 = held.1.or(position.entry_price);
+    }
+    Ok(reported)
+}
+
+fn signed(side: Side, quantity: Quantity) -> Decimal {
+    match side {
+        Side::Buy => quantity.get(),
+        Side::Sell => -quantity.get(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuit_breaker::CircuitBreaker;
+    use axiom_core::{CircuitBreakerState, ReportedPosition};
+    use chrono::TimeZone;
+
+    fn decimal(value: &str) -> Decimal {
+        value.parse().unwrap()
+    }
+
+    fn btc() -> Symbol {
+        Symbol("BTC/USDT".to_string())
+    }
+
+    /// Long 1 BTC at 50000 on Binance, 100000 in cash
+    fn manager() -> PortfolioManager {
+        let mut manager = PortfolioManager::new(Amount::new(decimal("100000")));
+        let price = Price::new(decimal("50000")).unwrap();
+        manager.update_position(&AccountId::default(), btc(), Venue::Binance, Side::Buy, Quantity::new(Decimal::ONE).unwrap(), price, Amount::ZERO).unwrap();
+        manager
+    }
+
+    fn report(quantity: &str, entry: &str, balance: &str) -> ExchangePositionReport {
+        ExchangePositionReport {
+            account: AccountId::default(),
+            venue: Venue::Binance,
+            positions: vec![ReportedPosition {
+                symbol: btc(),
+                side: Side::Buy,
+                quantity: Quantity::new(decimal(quantity)).unwrap(),
+                entry_price: Some(Price::new(decimal(entry)).unwrap()),
+            }],
+            balance: Some(Amount::new(decimal(balance))),
+            timestamp: Utc.with_ymd_and_hms(2026, 1, 2, 3, 4, 5).unwrap(),
+        }
+    }
+
+    fn halting() -> Reconciler {
+        Reconciler::new(ReconciliationConfig { policy: ReconciliationPolicy::Halt, ..ReconciliationConfig::default() })
+    }
+
+    #[test]
+    fn matching_report_is_clean() {
+        let mut manager = manager();
+        let mut breakers = AccountBreakers::new(CircuitBreaker::new(decimal("0.1")));
+        let result = halting().reconcile(&mut manager, &mut breakers, &report("1", "50000", "100000")).unwrap();
+
+        assert!(result.is_clean());
+        assert_eq!(result.action, ReconciliationAction::None);
+        assert_ne!(breakers.state(), CircuitBreakerState::Tripped);
+    }
+
+    #[test]
+    fn drift_within_tolerance_is_clean() {
+        let mut manager = manager();
+        let mut breakers = AccountBreakers::new(CircuitBreaker::new(decimal("0.1")));
+        // 0.5e-8 in size, 0.4 bps in entry, half a cent in cash
+        let result = halting().reconcile(&mut manager, &mut breakers, &report("1.000000005", "50002", "100000.005")).unwrap();
+
+        assert!(result.is_clean(), "{}", result.summary());
+        assert_eq!(result.action, ReconciliationAction::None);
+        assert_ne!(breakers.state(), CircuitBreakerState::Tripped);
+    }
+
+    #[test]
+    fn mismatch_trips_the_breaker_under_halt() {
+        let mut manager = manager();
+        let mut breakers = AccountBreakers::new(CircuitBreaker::new(decimal("0.1")));
+        let result = halting().reconcile(&mut manager, &mut breakers, &report("1.5", "50000", "100000")).unwrap();
+
+        assert_eq!(result.mismatches, vec![Mismatch {
+            symbol: Some(btc()),
+            field: MismatchField::Quantity,
+            local: Decimal::ONE,
+            reported: decimal("1.5"),
+        }]);
+        assert_eq!(result.action, ReconciliationAction::Halted);
+        assert_eq!(breakers.state(), CircuitBreakerState::Tripped);
+        // Halting changes nothing in the books
+        assert_eq!(manager.net_position(&btc()).unwrap(), Decimal::ONE);
+    }
+}
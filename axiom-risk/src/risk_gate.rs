@@ -0,0 +1,63 @@
+//! Risk Gate: The Master Trading Switch
+//!
+//! A single shared switch that every order must pass before submission.
+//! Any component (circuit breaker, watchdog, operator) can close it; closing
+//! the gate notifies subscribers so resting orders can be cancelled.
+
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::watch;
+use tracing::{error, info};
+
+/// Gate state
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GateState {
+    Open,
+    Closed { reason: String },
+}
+
+/// Shared risk gate (cheap to clone)
+#[derive(Debug, Clone)]
+pub struct RiskGate {
+    state: Arc<watch::Sender<GateState>>,
+}
+
+impl RiskGate {
+    pub fn new() -> Self {
+        let (tx, _rx) = watch::channel(GateState::Open);
+        Self { state: Arc::new(tx) }
+    }
+
+    /// Block all new orders
+    pub fn close(&self, reason: impl Into<String>) {
+        let reason = reason.into();
+        error!("Risk gate CLOSED: {}", reason);
+        self.state.send_replace(GateState::Closed { reason });
+    }
+
+    /// Permit new orders again
+    pub fn open(&self) {
+        info!("Risk gate opened");
+        self.state.send_replace(GateState::Open);
+    }
+
+    pub fn is_open(&self) -> bool {
+        matches!(*self.state.borrow(), GateState::Open)
+    }
+
+    /// Current state
+    pub fn state(&self) -> GateState {
+        self.state.borrow().clone()
+    }
+
+    /// Subscribe to state changes
+    pub fn subscribe(&self) -> watch::Receiver<GateState> {
+        self.state.subscribe()
+    }
+}
+
+impl Default for RiskGate {
+    fn default() -> Self {
+        Self::new()
+    }
+}